@@ -95,6 +95,7 @@ impl<S> TableFieldSchemaBuilder<S, FieldType> {
             mode,
             rounding_mode: self.rounding_mode,
             range_element_type: self.range_element_type,
+            fields: None,
             max_length: self.max_length,
             description: self.description,
             default_value_expression: self.default_value_expression,
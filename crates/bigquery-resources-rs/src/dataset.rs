@@ -1 +1,94 @@
+use std::collections::HashMap;
 
+use timestamp::{Duration, Timestamp};
+
+use crate::{DatasetReference, util};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dataset<S = Box<str>> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub self_link: Option<S>,
+    pub dataset_reference: DatasetReference<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<Box<str>, S>>,
+    /// Where the dataset's data is stored, i.e 'US' or 'us-central1'. Can't be changed
+    /// once the dataset is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<S>,
+    /// The default lifetime of new tables in the dataset.
+    #[serde(
+        default,
+        rename = "defaultTableExpirationMs",
+        skip_serializing_if = "Option::is_none",
+        with = "util::duration_ms::optional"
+    )]
+    pub default_table_expiration: Option<Duration>,
+    /// The default lifetime of the partitions in new partitioned tables.
+    #[serde(
+        default,
+        rename = "defaultPartitionExpirationMs",
+        skip_serializing_if = "Option::is_none",
+        with = "util::duration_ms::optional"
+    )]
+    pub default_partition_expiration: Option<Duration>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::timestamp_ms::optional"
+    )]
+    pub creation_time: Option<Timestamp>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::timestamp_ms::optional"
+    )]
+    pub last_modified_time: Option<Timestamp>,
+}
+
+impl<S> Dataset<S> {
+    pub const fn new(dataset_reference: DatasetReference<S>) -> Self {
+        Self {
+            etag: None,
+            id: None,
+            self_link: None,
+            dataset_reference,
+            friendly_name: None,
+            description: None,
+            labels: None,
+            location: None,
+            default_table_expiration: None,
+            default_partition_expiration: None,
+            creation_time: None,
+            last_modified_time: None,
+        }
+    }
+
+    pub fn location(mut self, location: S) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn description(mut self, description: S) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn labels(mut self, labels: HashMap<Box<str>, S>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    pub fn default_table_expiration(mut self, expiration: Duration) -> Self {
+        self.default_table_expiration = Some(expiration);
+        self
+    }
+}
@@ -7,6 +7,7 @@ pub mod copy;
 pub mod extract;
 pub mod load;
 pub mod query;
+pub mod statistics;
 
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -25,7 +26,7 @@ pub struct Job<S = Box<str>> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub job_reference: Option<JobReference<S>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub statistics: Option<serde_json::Value>,
+    pub statistics: Option<statistics::JobStatistics<S>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<JobStatus<S>>,
     #[serde(skip_serializing_if = "Option::is_none")]
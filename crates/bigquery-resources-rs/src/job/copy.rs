@@ -1,7 +1,118 @@
-// 'empty' enum to indicate this is a TODO item
+use timestamp::Timestamp;
+
+use super::load::{CreateDisposition, WriteDisposition};
+use crate::{TableReference, util};
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
-pub enum JobConfigurationTableCopy<S> {
-    #[doc(hidden)]
-    __ToDo(std::marker::PhantomData<S>),
+pub struct JobConfigurationTableCopy<S = Box<str>> {
+    pub source_tables: Vec<TableReference<S>>,
+    pub destination_table: TableReference<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_disposition: Option<CreateDisposition>,
+    #[serde(default)]
+    pub write_disposition: WriteDisposition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_type: Option<OperationType>,
+    /// When the destination table expires. Only used for snapshots and clones.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::timestamp_ms::optional"
+    )]
+    pub destination_expiration_time: Option<Timestamp>,
+}
+
+impl<S> JobConfigurationTableCopy<S> {
+    pub fn new(
+        source_tables: impl Into<Vec<TableReference<S>>>,
+        destination_table: TableReference<S>,
+    ) -> Self {
+        Self {
+            source_tables: source_tables.into(),
+            destination_table,
+            create_disposition: None,
+            write_disposition: WriteDisposition::default(),
+            operation_type: None,
+            destination_expiration_time: None,
+        }
+    }
+
+    pub fn write_disposition(mut self, write_disposition: WriteDisposition) -> Self {
+        self.write_disposition = write_disposition;
+        self
+    }
+
+    pub fn create_disposition(mut self, create_disposition: CreateDisposition) -> Self {
+        self.create_disposition = Some(create_disposition);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OperationType {
+    /// A regular copy, the default.
+    Copy,
+    /// Creates a read-only snapshot of the source table.
+    Snapshot,
+    /// Restores a snapshot into a regular table.
+    Restore,
+    /// Creates a writable, copy-on-write clone of the source table.
+    Clone,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{Job, JobConfigurationKind};
+
+    #[test]
+    fn test_copy_job_json() {
+        let source = TableReference {
+            project_id: "project",
+            dataset_id: "main",
+            table_id: "effort",
+        };
+
+        let dest = TableReference {
+            table_id: "effort_backup",
+            ..source
+        };
+
+        let job = Job::from(
+            JobConfigurationTableCopy::new(vec![source], dest)
+                .write_disposition(WriteDisposition::WriteTruncate),
+        );
+
+        let json = serde_json::to_value(&job).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "configuration": {
+                    "jobType": "COPY",
+                    "copy": {
+                        "sourceTables": [{
+                            "projectId": "project",
+                            "datasetId": "main",
+                            "tableId": "effort",
+                        }],
+                        "destinationTable": {
+                            "projectId": "project",
+                            "datasetId": "main",
+                            "tableId": "effort_backup",
+                        },
+                        "writeDisposition": "WRITE_TRUNCATE",
+                    },
+                },
+            })
+        );
+
+        let round_trip: Job = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            round_trip.configuration.kind,
+            JobConfigurationKind::Copy(ref copy) if copy.write_disposition == WriteDisposition::WriteTruncate
+        ));
+    }
 }
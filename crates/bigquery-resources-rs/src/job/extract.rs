@@ -45,6 +45,21 @@ pub struct TableExtract<S = Box<str>> {
     pub format: TableExtractFormat<S>,
 }
 
+impl<S> TableExtract<S> {
+    pub fn new(source_table: TableReference<S>, format: TableExtractFormat<S>) -> Self {
+        Self {
+            source_table,
+            compression: None,
+            format,
+        }
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Compression {
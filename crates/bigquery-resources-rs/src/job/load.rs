@@ -17,6 +17,7 @@ pub struct JobConfigurationLoad<S> {
         skip_serializing_if = "DestinationTableProperties::is_empty"
     )]
     pub destination_table_properties: DestinationTableProperties<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub create_disposition: Option<CreateDisposition>,
     #[serde(default)]
     pub write_disposition: WriteDisposition,
@@ -50,6 +51,28 @@ impl<S> JobConfigurationLoad<S> {
         self.write_disposition = WriteDisposition::WriteTruncate;
         self
     }
+
+    pub fn write_disposition(mut self, write_disposition: WriteDisposition) -> Self {
+        self.write_disposition = write_disposition;
+        self
+    }
+
+    pub fn create_disposition(mut self, create_disposition: CreateDisposition) -> Self {
+        self.create_disposition = Some(create_disposition);
+        self
+    }
+
+    /// Sets an explicit schema, turning off schema autodetection.
+    pub fn schema(mut self, schema: TableSchema<S>) -> Self {
+        self.schema = Some(schema);
+        self.source_format.set_autodetect(false);
+        self
+    }
+
+    pub fn ignore_unknown_values(mut self, ignore_unknown_values: bool) -> Self {
+        self.ignore_unknown_values = ignore_unknown_values;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -190,6 +213,29 @@ pub enum JsonExtension {
     GeoJson,
 }
 
+impl<S> SourceFormat<S> {
+    /// Newline delimited JSON, with the schema autodetected.
+    pub const fn json() -> Self {
+        Self::NewlineDelimitedJson {
+            json_extension: None,
+            autodetect: true,
+        }
+    }
+
+    /// Turns schema autodetection on/off. Only CSV and JSON support autodetection, the
+    /// other formats carry their own schema so this does nothing for them.
+    pub fn set_autodetect(&mut self, autodetect: bool) {
+        match self {
+            Self::Csv(opts) => opts.autodetect = autodetect,
+            Self::NewlineDelimitedJson {
+                autodetect: json_autodetect,
+                ..
+            } => *json_autodetect = autodetect,
+            _ => (),
+        }
+    }
+}
+
 impl<S> Default for SourceFormat<S> {
     fn default() -> Self {
         Self::Csv(CsvOptions::default())
@@ -1,7 +1,63 @@
-// empty enum to indicate this is a TODO item
+use std::num::NonZeroU64;
+
+use super::load::{CreateDisposition, WriteDisposition};
+use crate::query::{ParameterMode, QueryParameter, QueryRequest};
+use crate::{DatasetReference, TableReference};
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
-pub enum JobConfigurationQuery<S> {
-    #[doc(hidden)]
-    __ToDo(std::marker::PhantomData<S>),
+pub struct JobConfigurationQuery<S> {
+    pub query: S,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_dataset: Option<DatasetReference<S>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_table: Option<TableReference<S>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_disposition: Option<CreateDisposition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_disposition: Option<WriteDisposition>,
+    // BigQuery defaults to legacy sql if this is omitted, so always serialize it.
+    #[serde(default)]
+    pub use_legacy_sql: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_query_cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter_mode: Option<ParameterMode>,
+    // parameters are write-only, since [`QueryParameterValue`] can't be deserialized.
+    //
+    // [`QueryParameterValue`]: crate::query::QueryParameterValue
+    #[serde(
+        default = "Vec::new",
+        skip_deserializing,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub query_parameters: Vec<QueryParameter<S>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::util::uint64::optional"
+    )]
+    pub maximum_bytes_billed: Option<NonZeroU64>,
+}
+
+impl<S> JobConfigurationQuery<S> {
+    /// Builds the job configuration equivalent to a [`QueryRequest`], for running it via
+    /// 'jobs.insert' rather than 'jobs.query'.
+    pub fn from_request(request: QueryRequest<S>) -> Self
+    where
+        S: From<&'static str>,
+    {
+        Self {
+            query: S::from(request.query.as_str()),
+            default_dataset: request.default_dataset,
+            destination_table: None,
+            create_disposition: None,
+            write_disposition: None,
+            use_legacy_sql: request.use_legacy_sql,
+            use_query_cache: request.use_query_cache,
+            parameter_mode: request.parameter_mode,
+            query_parameters: request.query_parameters,
+            maximum_bytes_billed: request.maximum_bytes_billed,
+        }
+    }
 }
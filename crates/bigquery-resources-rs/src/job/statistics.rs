@@ -0,0 +1,193 @@
+//! Typed job statistics, returned once a job has started (or from a dry run).
+use timestamp::{Duration, Timestamp};
+
+use crate::table::TableSchema;
+use crate::{TableReference, util};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatistics<S = Box<str>> {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::timestamp_ms::optional"
+    )]
+    pub creation_time: Option<Timestamp>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::timestamp_ms::optional"
+    )]
+    pub start_time: Option<Timestamp>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::timestamp_ms::optional"
+    )]
+    pub end_time: Option<Timestamp>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub total_bytes_processed: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub total_slot_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<QueryStatistics<S>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<LoadStatistics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract: Option<ExtractStatistics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy: Option<CopyStatistics>,
+}
+
+impl<S> JobStatistics<S> {
+    /// How long the job took to run, if it's finished.
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.end_time? - self.start_time?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStatistics<S = Box<str>> {
+    /// For dry runs, this is the estimate of how much data the query will scan.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub total_bytes_processed: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub total_bytes_billed: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub total_slot_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "util::is_false")]
+    pub cache_hit: bool,
+    /// i.e 'SELECT', 'INSERT', 'CREATE_TABLE', etc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement_type: Option<S>,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub referenced_tables: Vec<TableReference<S>>,
+    /// The schema of the results. Only set for dry runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<TableSchema<S>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::int64::optional"
+    )]
+    pub num_dml_affected_rows: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadStatistics {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub input_files: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub input_file_bytes: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub output_rows: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub output_bytes: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub bad_records: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractStatistics {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub input_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyStatistics {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub copied_rows: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "util::uint64::optional"
+    )]
+    pub copied_logical_bytes: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_query_statistics() {
+        const JSON: &str = r#"{
+            "creationTime": "1700000000000",
+            "startTime": "1700000000500",
+            "endTime": "1700000002500",
+            "totalBytesProcessed": "1048576",
+            "totalSlotMs": "350",
+            "query": {
+                "totalBytesProcessed": "1048576",
+                "totalBytesBilled": "10485760",
+                "cacheHit": false,
+                "statementType": "SELECT",
+                "referencedTables": [
+                    { "projectId": "mysticetus", "datasetId": "main", "tableId": "effort" }
+                ]
+            }
+        }"#;
+
+        let stats: JobStatistics = serde_json::from_str(JSON).unwrap();
+
+        assert_eq!(stats.duration(), Some(Duration::from_seconds(2)));
+        assert_eq!(stats.total_slot_ms, Some(350));
+
+        let query = stats.query.unwrap();
+        assert_eq!(query.total_bytes_billed, Some(10 * 1024 * 1024));
+        assert_eq!(query.statement_type.as_deref(), Some("SELECT"));
+        assert_eq!(&*query.referenced_tables[0].table_id, "effort");
+    }
+}
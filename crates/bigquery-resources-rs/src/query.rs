@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroU64;
 
 use parameter_serializer::SerializeError;
@@ -7,11 +7,12 @@ use uuid::Uuid;
 
 mod results;
 mod row;
-mod rows;
+pub(crate) mod rows;
 mod value;
 
 pub use results::QueryResults;
 
+pub mod param;
 pub mod parameter_serializer;
 
 use crate::job::{JobCreationMode, JobReference};
@@ -83,6 +84,11 @@ mod query_string {
         pub const unsafe fn __new(s: &'static str) -> Self {
             Self(s)
         }
+
+        #[inline]
+        pub const fn as_str(&self) -> &'static str {
+            self.0
+        }
     }
 
     #[macro_export]
@@ -236,6 +242,7 @@ pub enum ParameterMode {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct QueryParameter<S = Box<str>> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<S>,
@@ -388,11 +395,19 @@ pub struct StructField {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QueryParameterValue {
     Scalar(serde_json::Value),
-    Array(Vec<serde_json::Value>),
-    Struct(serde_json::Map<String, serde_json::Value>),
+    Array(Vec<QueryParameterValue>),
+    Struct(BTreeMap<String, QueryParameterValue>),
     Range(Box<Range<QueryParameterValue>>),
 }
 
+impl QueryParameterValue {
+    pub const NULL: Self = Self::Scalar(serde_json::Value::Null);
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Scalar(serde_json::Value::Null))
+    }
+}
+
 impl serde::Serialize for QueryParameterValue {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
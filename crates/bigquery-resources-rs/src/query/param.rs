@@ -0,0 +1,167 @@
+//! Wrappers that mark the BigQuery type of a query parameter.
+//!
+//! Serde has no way to tell a timestamp/date/numeric/etc apart from a plain string or number,
+//! so without these the [`QueryParameterSerializer`] falls back on 'STRING', 'INT64' and
+//! 'FLOAT64'. With any other serializer the wrappers are transparent.
+//!
+//! ```
+//! use bigquery_resources_rs::query::QueryParameter;
+//! use bigquery_resources_rs::query::param::{Range, Timestamp};
+//!
+//! let start = timestamp::Timestamp::from_seconds(1_700_000_000);
+//! let end = timestamp::Timestamp::from_seconds(1_700_086_400);
+//!
+//! let window = Range::new(Timestamp(start), Timestamp(end));
+//! let param: QueryParameter = QueryParameter::serialize(window).unwrap().named("window");
+//! ```
+//!
+//! [`QueryParameterSerializer`]: super::parameter_serializer::QueryParameterSerializer
+use serde::ser::{Serialize, Serializer};
+
+use crate::table::FieldType;
+
+/// Newtype struct names the [`QueryParameterSerializer`] looks for to override the inferred
+/// type.
+///
+/// [`QueryParameterSerializer`]: super::parameter_serializer::QueryParameterSerializer
+pub(super) mod markers {
+    pub const TIMESTAMP: &str = "$bigquery::param::Timestamp";
+    pub const DATE: &str = "$bigquery::param::Date";
+    pub const DATETIME: &str = "$bigquery::param::DateTime";
+    pub const TIME: &str = "$bigquery::param::Time";
+    pub const NUMERIC: &str = "$bigquery::param::Numeric";
+    pub const BIGNUMERIC: &str = "$bigquery::param::BigNumeric";
+    pub const GEOGRAPHY: &str = "$bigquery::param::Geography";
+    pub const JSON: &str = "$bigquery::param::Json";
+    pub const RANGE: &str = "$bigquery::param::Range";
+}
+
+/// Maps a marker name back to the type it represents.
+pub(super) fn marker_type(name: &str) -> Option<FieldType> {
+    match name {
+        markers::TIMESTAMP => Some(FieldType::Timestamp),
+        markers::DATE => Some(FieldType::Date),
+        markers::DATETIME => Some(FieldType::DateTime),
+        markers::TIME => Some(FieldType::Time),
+        markers::NUMERIC => Some(FieldType::Numeric),
+        markers::BIGNUMERIC => Some(FieldType::BigNumeric),
+        markers::GEOGRAPHY => Some(FieldType::Geography),
+        markers::JSON => Some(FieldType::Json),
+        _ => None,
+    }
+}
+
+macro_rules! define_typed_wrappers {
+    ($($(#[$attr:meta])* $name:ident => $marker:ident),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub struct $name<T>(pub T);
+
+            impl<T: Serialize> Serialize for $name<T> {
+                #[inline]
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    serializer.serialize_newtype_struct(markers::$marker, &self.0)
+                }
+            }
+        )*
+    };
+}
+
+define_typed_wrappers! {
+    /// A 'TIMESTAMP' parameter. Wraps either a formatted timestamp string, or a
+    /// [`timestamp::Timestamp`] (or anything else that serializes as seconds since the epoch).
+    Timestamp => TIMESTAMP,
+    /// A 'DATE' parameter, i.e a [`timestamp::Date`] or a 'YYYY-MM-DD' string.
+    Date => DATE,
+    /// A 'DATETIME' parameter, i.e a civil 'YYYY-MM-DD HH:MM:SS' string.
+    DateTime => DATETIME,
+    /// A 'TIME' parameter, i.e a 'HH:MM:SS' string.
+    Time => TIME,
+    /// A 'NUMERIC' parameter. Wrapping a string avoids losing precision to floats.
+    Numeric => NUMERIC,
+    /// A 'BIGNUMERIC' parameter. Wrapping a string avoids losing precision to floats.
+    BigNumeric => BIGNUMERIC,
+    /// A 'GEOGRAPHY' parameter, as WKT or GeoJSON.
+    Geography => GEOGRAPHY,
+}
+
+/// A 'JSON' parameter. The inner value is serialized to a JSON string up front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> Serialize for Json<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let json = serde_json::to_string(&self.0).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_newtype_struct(markers::JSON, &json)
+    }
+}
+
+/// A 'BYTES' parameter. Needed for types like [`Vec<u8>`], which serialize as a sequence
+/// rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bytes<T>(pub T);
+
+impl<T: AsRef<[u8]>> Serialize for Bytes<T> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0.as_ref())
+    }
+}
+
+/// A 'RANGE' parameter. The bounds need to serialize as a 'DATE', 'DATETIME' or 'TIMESTAMP',
+/// so they'll usually be one of the wrappers above. A missing bound is unbounded, but at
+/// least one bound needs to be set so the element type can be inferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range<T> {
+    pub start: Option<T>,
+    pub end: Option<T>,
+}
+
+impl<T> Range<T> {
+    pub const fn new(start: T, end: T) -> Self {
+        Self {
+            start: Some(start),
+            end: Some(end),
+        }
+    }
+
+    pub const fn starting_at(start: T) -> Self {
+        Self {
+            start: Some(start),
+            end: None,
+        }
+    }
+
+    pub const fn ending_at(end: T) -> Self {
+        Self {
+            start: None,
+            end: Some(end),
+        }
+    }
+}
+
+impl<T> From<std::ops::Range<T>> for Range<T> {
+    fn from(range: std::ops::Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<T: Serialize> Serialize for Range<T> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(markers::RANGE, &(&self.start, &self.end))
+    }
+}
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
-use super::{QueryParameter, QueryParameterType, QueryParameterValue};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use super::param::{self, markers};
+use super::{QueryParameter, QueryParameterType, QueryParameterValue, Range, StructField};
 use crate::table::FieldType;
 
 #[derive(Debug)]
@@ -42,8 +47,14 @@ pub enum SerializeError {
     UnknownArrayElementType(QueryParameterValue),
     #[error("could not derive map value type")]
     UnknownMapValueType(QueryParameterValue),
+    #[error("could not derive range element type, at least one bound must be set")]
+    UnknownRangeElementType,
     #[error("found multiple incompatible nested types")]
     MixedNestedTypes,
+    #[error("struct field names must be strings")]
+    InvalidStructKey,
+    #[error("{0:?} parameters must be a single scalar value")]
+    NonScalarTypedValue(FieldType),
 }
 
 impl serde::ser::Error for SerializeError {
@@ -56,31 +67,106 @@ impl serde::ser::Error for SerializeError {
 }
 
 pub struct QueryParameterArraySerializer<S> {
-    array: Vec<serde_json::Value>,
+    array: Vec<QueryParameterValue>,
     element_ty: Option<QueryParameterType>,
     _marker: PhantomData<S>,
 }
 
+/// Serializes both structs and maps as a 'STRUCT' parameter.
 pub struct QueryParameterMapSerializer<S> {
-    map: serde_json::Map<String, serde_json::Value>,
+    fields: Vec<StructField>,
+    values: BTreeMap<String, QueryParameterValue>,
     next_key: Option<String>,
-    value_ty: Option<QueryParameterType>,
+    // set if any field was null, since we can't know the type of that field.
+    unknown_field_type: bool,
     _marker: PhantomData<S>,
 }
 
+/// Overrides the inferred type with the one from a [`param`] wrapper.
+fn retype_scalar<S>(
+    ty: FieldType,
+    result: Result<QueryParameter<S>, SerializeError>,
+) -> Result<QueryParameter<S>, SerializeError> {
+    let (inferred_ty, value) = match result {
+        Ok(QueryParameter {
+            parameter_type: QueryParameterType::Scalar(inferred_ty),
+            parameter_value: QueryParameterValue::Scalar(value),
+            ..
+        }) => (inferred_ty, value),
+        // a typed null is fine, since we know the type now.
+        Err(SerializeError::UnknownScalarType(QueryParameterValue::Scalar(value))) => {
+            (FieldType::String, value)
+        }
+        Ok(_) => return Err(SerializeError::NonScalarTypedValue(ty)),
+        Err(err) => return Err(err),
+    };
+
+    let value = match (ty, inferred_ty, value) {
+        // timestamp::Timestamp serializes as float seconds, but BigQuery wants a timestamp
+        // string.
+        (FieldType::Timestamp, FieldType::Integer | FieldType::Float, value) => {
+            let seconds = value
+                .as_f64()
+                .ok_or(SerializeError::NonScalarTypedValue(ty))?;
+
+            let ts = timestamp::Timestamp::from_seconds_f64_checked(seconds)
+                .map_err(<SerializeError as serde::ser::Error>::custom)?;
+
+            serde_json::Value::String(ts.as_iso8601())
+        }
+        (_, _, value) => value,
+    };
+
+    Ok(QueryParameter {
+        name: None,
+        parameter_type: QueryParameterType::Scalar(ty),
+        parameter_value: QueryParameterValue::Scalar(value),
+    })
+}
+
+/// Converts the 2 element array that [`param::Range`] serializes as into a 'RANGE' parameter.
+fn into_range<S>(
+    result: Result<QueryParameter<S>, SerializeError>,
+) -> Result<QueryParameter<S>, SerializeError> {
+    let (element_type, bounds) = match result {
+        Ok(QueryParameter {
+            parameter_type: QueryParameterType::Array { element_type },
+            parameter_value: QueryParameterValue::Array(bounds),
+            ..
+        }) => (element_type, bounds),
+        Err(SerializeError::UnknownArrayElementType(_)) => {
+            return Err(SerializeError::UnknownRangeElementType);
+        }
+        Ok(_) => return Err(SerializeError::MixedNestedTypes),
+        Err(err) => return Err(err),
+    };
+
+    let mut bounds = bounds.into_iter().map(|bound| match bound.is_null() {
+        true => None,
+        false => Some(bound),
+    });
+
+    let range = match (bounds.next().flatten(), bounds.next().flatten()) {
+        (Some(start), Some(end)) => Range::Bounded { start, end },
+        (Some(start), None) => Range::StartAt(start),
+        (None, Some(end)) => Range::EndAt(end),
+        (None, None) => return Err(SerializeError::UnknownRangeElementType),
+    };
+
+    Ok(QueryParameter {
+        name: None,
+        parameter_type: QueryParameterType::Range { element_type },
+        parameter_value: QueryParameterValue::Range(Box::new(range)),
+    })
+}
+
 impl<S> serde::Serializer for QueryParameterSerializer<S> {
     type Ok = QueryParameter<S>;
     type Error = SerializeError;
 
-    /*
     type SerializeMap = QueryParameterMapSerializer<S>;
     type SerializeStruct = QueryParameterMapSerializer<S>;
     type SerializeStructVariant = QueryParameterMapSerializer<S>;
-    */
-
-    type SerializeMap = serde::ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeStruct = serde::ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeStructVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
 
     type SerializeSeq = QueryParameterArraySerializer<S>;
     type SerializeTuple = QueryParameterArraySerializer<S>;
@@ -100,7 +186,11 @@ impl<S> serde::Serializer for QueryParameterSerializer<S> {
         serialize_f64(f64, Float),
         serialize_bool(bool, Bool),
         serialize_str(&str, String),
-        serialize_bytes(&[u8], Bytes),
+    }
+
+    #[inline]
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(new_scalar(FieldType::Bytes, STANDARD.encode(v)))
     }
 
     #[inline]
@@ -138,13 +228,20 @@ impl<S> serde::Serializer for QueryParameterSerializer<S> {
     #[inline]
     fn serialize_newtype_struct<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: ?Sized + serde::Serialize,
     {
-        value.serialize(self)
+        if name == markers::RANGE {
+            return into_range(value.serialize(self));
+        }
+
+        match param::marker_type(name) {
+            Some(ty) => retype_scalar(ty, value.serialize(self)),
+            None => value.serialize(self),
+        }
     }
 
     #[inline]
@@ -206,15 +303,14 @@ impl<S> serde::Serializer for QueryParameterSerializer<S> {
     }
 
     #[inline]
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        /*
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         Ok(QueryParameterMapSerializer {
-            map: len.map(serde_json::Map::with_capacity).unwrap_or_default(),
-            value_ty: None,
+            fields: len.map(Vec::with_capacity).unwrap_or_default(),
+            values: BTreeMap::new(),
+            next_key: None,
+            unknown_field_type: false,
             _marker: PhantomData,
         })
-        */
-        todo!("get SerializeMap/Struct serializers working")
     }
 
     #[inline]
@@ -249,15 +345,6 @@ impl<S> serde::Serializer for QueryParameterSerializer<S> {
     }
 }
 
-fn param_value_into_value(param: QueryParameterValue) -> serde_json::Value {
-    match param {
-        QueryParameterValue::Scalar(value) => value,
-        QueryParameterValue::Array(arr) => serde_json::Value::Array(arr),
-        QueryParameterValue::Struct(map) => serde_json::Value::Object(map),
-        QueryParameterValue::Range(_) => todo!("ranges arent supported for serialization yet"),
-    }
-}
-
 impl<S> serde::ser::SerializeSeq for QueryParameterArraySerializer<S> {
     type Ok = QueryParameter<S>;
     type Error = SerializeError;
@@ -272,7 +359,7 @@ impl<S> serde::ser::SerializeSeq for QueryParameterArraySerializer<S> {
                 parameter_value,
                 ..
             }) => {
-                self.array.push(param_value_into_value(parameter_value));
+                self.array.push(parameter_value);
                 match self.element_ty {
                     Some(ref existing) if existing == &parameter_type => (),
                     Some(_) => return Err(SerializeError::MixedNestedTypes),
@@ -286,7 +373,7 @@ impl<S> serde::ser::SerializeSeq for QueryParameterArraySerializer<S> {
             Err(SerializeError::UnknownArrayElementType(param))
             | Err(SerializeError::UnknownScalarType(param))
             | Err(SerializeError::UnknownMapValueType(param)) => {
-                self.array.push(param_value_into_value(param));
+                self.array.push(param);
                 Ok(())
             }
             Err(other_err) => Err(other_err),
@@ -367,11 +454,24 @@ impl<S> serde::ser::SerializeMap for QueryParameterMapSerializer<S> {
     type Ok = QueryParameter<S>;
     type Error = SerializeError;
 
-    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
     where
         T: ?Sized + serde::Serialize,
     {
-        todo!()
+        let key = match key.serialize(QueryParameterSerializer::<S>(PhantomData))? {
+            QueryParameter {
+                parameter_value: QueryParameterValue::Scalar(serde_json::Value::String(key)),
+                ..
+            } => key,
+            QueryParameter {
+                parameter_value: QueryParameterValue::Scalar(serde_json::Value::Number(num)),
+                ..
+            } => num.to_string(),
+            _ => return Err(SerializeError::InvalidStructKey),
+        };
+
+        self.next_key = Some(key);
+        Ok(())
     }
 
     fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
@@ -389,23 +489,21 @@ impl<S> serde::ser::SerializeMap for QueryParameterMapSerializer<S> {
                 parameter_value,
                 ..
             }) => {
-                self.map
-                    .insert(key, param_value_into_value(parameter_value));
-
-                match self.value_ty {
-                    Some(ref existing) if existing == &parameter_type => (),
-                    Some(_) => return Err(SerializeError::MixedNestedTypes),
-                    None => self.value_ty = Some(parameter_type),
-                }
+                self.fields.push(StructField {
+                    name: Some(Box::from(key.as_str())),
+                    ty: parameter_type,
+                    description: None,
+                });
+                self.values.insert(key, parameter_value);
                 Ok(())
             }
-            // if we get one of these, we could reasonably get the information from an element we
-            // havent been given yet (i.e an array of nullable integers, starting with
-            // null)
+            // same as with arrays, if this struct is an array element then another element
+            // might be able to fill in the type.
             Err(SerializeError::UnknownArrayElementType(param))
             | Err(SerializeError::UnknownScalarType(param))
             | Err(SerializeError::UnknownMapValueType(param)) => {
-                self.map.insert(key, param_value_into_value(param));
+                self.unknown_field_type = true;
+                self.values.insert(key, param);
                 Ok(())
             }
             Err(other_err) => Err(other_err),
@@ -413,7 +511,161 @@ impl<S> serde::ser::SerializeMap for QueryParameterMapSerializer<S> {
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        let _value = QueryParameterValue::Struct(self.map);
-        todo!()
+        let value = QueryParameterValue::Struct(self.values);
+
+        if self.unknown_field_type {
+            return Err(SerializeError::UnknownMapValueType(value));
+        }
+
+        Ok(QueryParameter {
+            name: None,
+            parameter_type: QueryParameterType::Struct {
+                fields: self.fields,
+            },
+            parameter_value: value,
+        })
+    }
+}
+
+impl<S> serde::ser::SerializeStruct for QueryParameterMapSerializer<S> {
+    type Ok = QueryParameter<S>;
+    type Error = SerializeError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.next_key = Some(key.to_owned());
+        serde::ser::SerializeMap::serialize_value(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        serde::ser::SerializeMap::end(self)
+    }
+}
+
+impl<S> serde::ser::SerializeStructVariant for QueryParameterMapSerializer<S> {
+    type Ok = QueryParameter<S>;
+    type Error = SerializeError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        serde::ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        serde::ser::SerializeMap::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::query::param;
+
+    fn to_json<T: serde::Serialize>(value: T) -> serde_json::Value {
+        let param = QueryParameter::<Box<str>>::serialize(value).unwrap();
+        serde_json::to_value(param).unwrap()
+    }
+
+    #[derive(serde::Serialize)]
+    struct Sighting<'a> {
+        species: &'a str,
+        count: Option<u32>,
+        seen_at: param::Timestamp<timestamp::Timestamp>,
+    }
+
+    #[test]
+    fn test_serialize_params() {
+        let seen_at = param::Timestamp(timestamp::Timestamp::from_seconds(1_700_000_000));
+
+        assert_eq!(
+            to_json(seen_at),
+            json!({
+                "parameterType": { "type": "TIMESTAMP" },
+                "parameterValue": { "value": "2023-11-14T22:13:20Z" },
+            })
+        );
+
+        // the null count in the first element gets its type from the second one.
+        let sightings = [
+            Sighting {
+                species: "orca",
+                count: None,
+                seen_at,
+            },
+            Sighting {
+                species: "humpback",
+                count: Some(2),
+                seen_at,
+            },
+        ];
+
+        assert_eq!(
+            to_json(&sightings),
+            json!({
+                "parameterType": {
+                    "type": "ARRAY",
+                    "arrayType": {
+                        "type": "STRUCT",
+                        "structTypes": [
+                            { "name": "species", "type": { "type": "STRING" } },
+                            { "name": "count", "type": { "type": "INTEGER" } },
+                            { "name": "seen_at", "type": { "type": "TIMESTAMP" } },
+                        ],
+                    },
+                },
+                "parameterValue": {
+                    "arrayValues": [
+                        {
+                            "structValues": {
+                                "species": { "value": "orca" },
+                                "count": { "value": null },
+                                "seen_at": { "value": "2023-11-14T22:13:20Z" },
+                            },
+                        },
+                        {
+                            "structValues": {
+                                "species": { "value": "humpback" },
+                                "count": { "value": 2 },
+                                "seen_at": { "value": "2023-11-14T22:13:20Z" },
+                            },
+                        },
+                    ],
+                },
+            })
+        );
+
+        assert_eq!(
+            to_json(param::Range::starting_at(param::Date("2024-01-01"))),
+            json!({
+                "parameterType": { "type": "RANGE", "rangeElementType": { "type": "DATE" } },
+                "parameterValue": { "rangeValue": { "start": { "value": "2024-01-01" } } },
+            })
+        );
+
+        assert_eq!(
+            to_json(param::Bytes(vec![0xde, 0xad])),
+            json!({
+                "parameterType": { "type": "BYTES" },
+                "parameterValue": { "value": "3q0=" },
+            })
+        );
+
+        assert!(matches!(
+            QueryParameter::<Box<str>>::serialize(param::Range::<param::Date<&str>> {
+                start: None,
+                end: None,
+            }),
+            Err(SerializeError::UnknownRangeElementType)
+        ));
     }
 }
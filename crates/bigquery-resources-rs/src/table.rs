@@ -8,6 +8,10 @@ use crate::builders::Unset;
 use crate::builders::table_field_schema::TableFieldSchemaBuilder;
 use crate::util;
 
+mod schema_diff;
+
+pub use schema_diff::{IncompatibleSchema, SchemaChange};
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Table<S = Box<str>> {
@@ -72,6 +76,59 @@ pub struct Table<S = Box<str>> {
     pub ty: Option<TableType>,
 }
 
+impl<S> Table<S> {
+    /// An empty table definition, with only the reference and schema set.
+    pub fn new(table_reference: TableReference<S>, schema: TableSchema<S>) -> Self {
+        Self {
+            etag: None,
+            id: None,
+            self_link: None,
+            table_reference: Some(table_reference),
+            friendly_name: None,
+            description: None,
+            labels: None,
+            schema: Some(schema),
+            time_partitioning: None,
+            range_partitioning: None,
+            clustering: Clustering::default(),
+            require_partition_filter: false,
+            num_bytes: None,
+            num_long_term_bytes: None,
+            num_rows: None,
+            expiration_time: None,
+            last_modified_time: None,
+            ty: None,
+        }
+    }
+
+    pub fn description(mut self, description: S) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn labels(mut self, labels: HashMap<Box<str>, S>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    pub fn expiration_time(mut self, expiration_time: Timestamp) -> Self {
+        self.expiration_time = Some(expiration_time);
+        self
+    }
+
+    pub fn time_partitioning(mut self, time_partitioning: TimePartitioning<S>) -> Self {
+        self.time_partitioning = Some(time_partitioning);
+        self
+    }
+
+    pub fn clustering(mut self, fields: impl Into<Vec<S>>) -> Self {
+        self.clustering = Clustering {
+            fields: fields.into(),
+        };
+        self
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TableType {
@@ -88,6 +145,54 @@ pub struct TableSchema<S> {
     pub fields: Vec<TableFieldSchema<S>>,
 }
 
+impl<S: AsRef<str> + Clone> TableSchema<S> {
+    /// Narrows the schema down to the selected fields, keeping them in schema order (which
+    /// is the order BigQuery returns them in). Nested fields can be selected with a dotted
+    /// path, i.e 'position.lat'. Names are compared case-insensitively.
+    pub fn select<F: AsRef<str>>(&self, selected: &[F]) -> Self {
+        let selected = selected.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+
+        Self {
+            fields: select_fields(&self.fields, &selected),
+        }
+    }
+}
+
+fn select_fields<S: AsRef<str> + Clone>(
+    fields: &[TableFieldSchema<S>],
+    selected: &[&str],
+) -> Vec<TableFieldSchema<S>> {
+    let mut dst = Vec::new();
+
+    for field in fields {
+        let name = field.name.as_ref();
+
+        let mut whole_field = false;
+        let mut nested = Vec::new();
+
+        for selector in selected {
+            match selector.split_once('.') {
+                None if selector.eq_ignore_ascii_case(name) => whole_field = true,
+                Some((parent, rest)) if parent.eq_ignore_ascii_case(name) => nested.push(rest),
+                _ => (),
+            }
+        }
+
+        if whole_field {
+            dst.push(field.clone());
+        } else if !nested.is_empty() {
+            let mut field = field.clone();
+            field.fields = field
+                .fields
+                .as_deref()
+                .map(|sub_fields| select_fields(sub_fields, &nested));
+            dst.push(field);
+        }
+    }
+
+    dst
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TableFieldSchema<S> {
@@ -121,6 +226,9 @@ pub struct TableFieldSchema<S> {
     pub default_value_expression: Option<S>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub range_element_type: Option<RangeElementType>,
+    /// The sub-fields of a 'RECORD' field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<TableFieldSchema<S>>>,
 }
 
 impl<S> TableFieldSchema<S> {
@@ -135,6 +243,7 @@ impl<S> TableFieldSchema<S> {
             mode,
             rounding_mode: None,
             range_element_type: None,
+            fields: None,
             max_length: None,
             description: None,
             default_value_expression: None,
@@ -151,17 +260,29 @@ impl<S> TableFieldSchema<S> {
     }
 
     pub fn map<'a, T: 'a>(&'a self, mut map_fn: impl FnMut(&'a S) -> T) -> TableFieldSchema<T> {
+        self.map_inner(&mut map_fn)
+    }
+
+    // takes a trait object, since recursing into sub-fields with a generic 'map_fn' would
+    // never stop instantiating new '&mut &mut ...' closure types.
+    fn map_inner<'a, T: 'a>(&'a self, map_fn: &mut dyn FnMut(&'a S) -> T) -> TableFieldSchema<T> {
         TableFieldSchema {
             name: map_fn(&self.name),
             ty: self.ty,
             mode: self.mode,
-            description: self.description.as_ref().map(&mut map_fn),
+            description: self.description.as_ref().map(&mut *map_fn),
             max_length: self.max_length,
             precision: self.precision,
             scale: self.scale,
             rounding_mode: self.rounding_mode,
-            default_value_expression: self.default_value_expression.as_ref().map(map_fn),
+            default_value_expression: self.default_value_expression.as_ref().map(&mut *map_fn),
             range_element_type: self.range_element_type,
+            fields: self.fields.as_ref().map(|fields| {
+                fields
+                    .iter()
+                    .map(|field| field.map_inner(&mut *map_fn))
+                    .collect()
+            }),
         }
     }
 }
@@ -310,3 +431,88 @@ impl<S> Default for Clustering<S> {
         Self { fields: vec![] }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        name: &'static str,
+        fields: Vec<TableFieldSchema<&'static str>>,
+    ) -> TableFieldSchema<&'static str> {
+        let mut record = TableFieldSchema::builder(name).record().nullable();
+        record.fields = Some(fields);
+        record
+    }
+
+    fn position() -> TableFieldSchema<&'static str> {
+        record(
+            "position",
+            vec![
+                TableFieldSchema::builder("lat").float().required(),
+                TableFieldSchema::builder("lon").float().required(),
+            ],
+        )
+    }
+
+    fn schema() -> TableSchema<&'static str> {
+        TableSchema {
+            fields: vec![
+                TableFieldSchema::builder("id").int().required(),
+                TableFieldSchema::builder("vessel").string().nullable(),
+                position(),
+            ],
+        }
+    }
+
+    fn names(schema: &TableSchema<&'static str>) -> Vec<&'static str> {
+        schema.fields.iter().map(|field| field.name).collect()
+    }
+
+    #[test]
+    fn test_select_top_level() {
+        let selected = schema().select(&["vessel", "id"]);
+
+        // schema order, not selection order
+        assert_eq!(names(&selected), ["id", "vessel"]);
+        assert_eq!(selected.fields[1], schema().fields[1]);
+
+        assert!(schema().select(&["missing"]).fields.is_empty());
+    }
+
+    #[test]
+    fn test_select_nested() {
+        let selected = schema().select(&["position.lon"]);
+
+        assert_eq!(
+            selected.fields,
+            [record(
+                "position",
+                vec![TableFieldSchema::builder("lon").float().required()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_select_mixed_case() {
+        let selected = schema().select(&["ID", "Position.LAT"]);
+
+        assert_eq!(names(&selected), ["id", "position"]);
+        assert_eq!(
+            selected.fields[1],
+            record(
+                "position",
+                vec![TableFieldSchema::builder("lat").float().required()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_select_whole_record_and_sub_field() {
+        // selecting the whole record takes precedence over any of its sub-fields
+        for selectors in [["position", "position.lat"], ["position.lat", "position"]] {
+            let selected = schema().select(&selectors);
+            assert_eq!(selected.fields, [position()]);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Checks whether one schema can be evolved into another by patching the table in place.
+//!
+//! BigQuery only allows 2 kinds of changes to an existing schema: adding new NULLABLE or
+//! REPEATED columns (at any nesting level), and relaxing REQUIRED columns to NULLABLE.
+//! Anything else needs the table to be recreated.
+use std::fmt;
+
+use super::{FieldMode, FieldType, TableFieldSchema, TableSchema};
+
+/// A compatible change between 2 schemas, returned by [`TableSchema::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A new NULLABLE/REPEATED column, at the given path.
+    AddedField { path: Box<str>, mode: FieldMode },
+    /// A REQUIRED column that's now NULLABLE.
+    RelaxedField { path: Box<str> },
+}
+
+/// A change between 2 schemas that can't be applied to an existing table.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IncompatibleSchema {
+    #[error("field '{path}' was removed")]
+    RemovedField { path: Box<str> },
+    #[error("field '{path}' changed type from {from:?} to {to:?}")]
+    TypeChanged {
+        path: Box<str>,
+        from: FieldType,
+        to: FieldType,
+    },
+    #[error("field '{path}' changed mode from {from:?} to {to:?}")]
+    ModeChanged {
+        path: Box<str>,
+        from: FieldMode,
+        to: FieldMode,
+    },
+    #[error("new field '{path}' must be NULLABLE or REPEATED, not REQUIRED")]
+    RequiredFieldAdded { path: Box<str> },
+}
+
+impl<S: AsRef<str>> TableSchema<S> {
+    /// Finds the changes needed to go from 'self' to 'new', erroring if any of them
+    /// can't be made to an existing table. An empty list means the schemas are equivalent.
+    ///
+    /// Column names are compared case-insensitively, same as BigQuery.
+    pub fn diff<S2: AsRef<str>>(
+        &self,
+        new: &TableSchema<S2>,
+    ) -> Result<Vec<SchemaChange>, IncompatibleSchema> {
+        let mut changes = Vec::new();
+        diff_fields(&Path::Root, &self.fields, &new.fields, &mut changes)?;
+        Ok(changes)
+    }
+}
+
+/// Lazily formatted dotted path to a field, so nothing is allocated unless there's a change.
+enum Path<'a> {
+    Root,
+    Field(&'a Path<'a>, &'a str),
+}
+
+impl Path<'_> {
+    fn to_boxed(&self) -> Box<str> {
+        self.to_string().into_boxed_str()
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Root => Ok(()),
+            Self::Field(Self::Root, name) => f.write_str(name),
+            Self::Field(parent, name) => write!(f, "{parent}.{name}"),
+        }
+    }
+}
+
+fn diff_fields<S: AsRef<str>, S2: AsRef<str>>(
+    parent: &Path<'_>,
+    old: &[TableFieldSchema<S>],
+    new: &[TableFieldSchema<S2>],
+    changes: &mut Vec<SchemaChange>,
+) -> Result<(), IncompatibleSchema> {
+    fn find<'a, S: AsRef<str>>(
+        fields: &'a [TableFieldSchema<S>],
+        name: &str,
+    ) -> Option<&'a TableFieldSchema<S>> {
+        fields
+            .iter()
+            .find(|field| field.name.as_ref().eq_ignore_ascii_case(name))
+    }
+
+    for old_field in old {
+        let path = Path::Field(parent, old_field.name.as_ref());
+
+        let Some(new_field) = find(new, old_field.name.as_ref()) else {
+            return Err(IncompatibleSchema::RemovedField {
+                path: path.to_boxed(),
+            });
+        };
+
+        if old_field.ty != new_field.ty {
+            return Err(IncompatibleSchema::TypeChanged {
+                path: path.to_boxed(),
+                from: old_field.ty,
+                to: new_field.ty,
+            });
+        }
+
+        match (old_field.mode, new_field.mode) {
+            (old_mode, new_mode) if old_mode == new_mode => (),
+            (FieldMode::Required, FieldMode::Nullable) => {
+                changes.push(SchemaChange::RelaxedField {
+                    path: path.to_boxed(),
+                })
+            }
+            (from, to) => {
+                return Err(IncompatibleSchema::ModeChanged {
+                    path: path.to_boxed(),
+                    from,
+                    to,
+                });
+            }
+        }
+
+        if old_field.ty == FieldType::Record {
+            diff_fields(
+                &path,
+                old_field.fields.as_deref().unwrap_or_default(),
+                new_field.fields.as_deref().unwrap_or_default(),
+                changes,
+            )?;
+        }
+    }
+
+    for new_field in new {
+        if find(old, new_field.name.as_ref()).is_some() {
+            continue;
+        }
+
+        let path = Path::Field(parent, new_field.name.as_ref()).to_boxed();
+
+        if new_field.mode == FieldMode::Required {
+            return Err(IncompatibleSchema::RequiredFieldAdded { path });
+        }
+
+        changes.push(SchemaChange::AddedField {
+            path,
+            mode: new_field.mode,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(fields: Vec<TableFieldSchema<&'static str>>) -> TableSchema<&'static str> {
+        TableSchema { fields }
+    }
+
+    fn record(
+        name: &'static str,
+        fields: Vec<TableFieldSchema<&'static str>>,
+    ) -> TableFieldSchema<&'static str> {
+        let mut record = TableFieldSchema::builder(name).record().nullable();
+        record.fields = Some(fields);
+        record
+    }
+
+    #[test]
+    fn test_schema_diff() {
+        let old = schema(vec![
+            TableFieldSchema::builder("id").int().required(),
+            TableFieldSchema::builder("vessel").string().required(),
+            record(
+                "position",
+                vec![
+                    TableFieldSchema::builder("lat").float().required(),
+                    TableFieldSchema::builder("lon").float().required(),
+                ],
+            ),
+        ]);
+
+        assert_eq!(old.diff(&old), Ok(vec![]));
+
+        let new = schema(vec![
+            TableFieldSchema::builder("id").int().required(),
+            TableFieldSchema::builder("VESSEL").string().nullable(),
+            record(
+                "position",
+                vec![
+                    TableFieldSchema::builder("lat").float().required(),
+                    TableFieldSchema::builder("lon").float().required(),
+                    TableFieldSchema::builder("alt").float().nullable(),
+                ],
+            ),
+            TableFieldSchema::builder("tags").string().repeated(),
+        ]);
+
+        assert_eq!(
+            old.diff(&new),
+            Ok(vec![
+                SchemaChange::RelaxedField {
+                    path: "vessel".into()
+                },
+                SchemaChange::AddedField {
+                    path: "position.alt".into(),
+                    mode: FieldMode::Nullable,
+                },
+                SchemaChange::AddedField {
+                    path: "tags".into(),
+                    mode: FieldMode::Repeated,
+                },
+            ])
+        );
+
+        // none of the reverse changes are allowed
+        assert_eq!(
+            new.diff(&old),
+            Err(IncompatibleSchema::ModeChanged {
+                path: "VESSEL".into(),
+                from: FieldMode::Nullable,
+                to: FieldMode::Required,
+            })
+        );
+
+        let removed = schema(vec![
+            TableFieldSchema::builder("id").int().required(),
+            TableFieldSchema::builder("vessel").string().required(),
+            record(
+                "position",
+                vec![TableFieldSchema::builder("lat").float().required()],
+            ),
+        ]);
+
+        assert_eq!(
+            old.diff(&removed),
+            Err(IncompatibleSchema::RemovedField {
+                path: "position.lon".into()
+            })
+        );
+
+        let retyped = schema(vec![
+            TableFieldSchema::builder("id").string().required(),
+            TableFieldSchema::builder("vessel").string().required(),
+        ]);
+
+        assert!(matches!(
+            old.diff(&retyped),
+            Err(IncompatibleSchema::TypeChanged { .. })
+        ));
+
+        let mut required_added = old.clone();
+        required_added
+            .fields
+            .push(TableFieldSchema::builder("fix").bool().required());
+
+        assert_eq!(
+            old.diff(&required_added),
+            Err(IncompatibleSchema::RequiredFieldAdded { path: "fix".into() })
+        );
+    }
+}
@@ -1,4 +1,11 @@
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+
+use serde::de;
+
 use super::ErrorProto;
+use crate::query::rows::RowsVisitor;
+use crate::table::TableSchema;
 
 #[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,3 +24,227 @@ pub struct InsertErrors<S> {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<ErrorProto<S>>,
 }
+
+/// A single page of rows from 'tabledata.list'.
+///
+/// Unlike query results, the response doesn't include the schema, so this is deserialized
+/// with a [`TableDataListSeed`] built from the table's schema (narrowed down with
+/// [`TableSchema::select`] if only some fields were requested).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDataList<Row, S = Box<str>> {
+    /// The total number of rows in the table.
+    pub total_rows: Option<u64>,
+    /// Token for the next page, missing on the last page.
+    pub page_token: Option<S>,
+    pub rows: Vec<Row>,
+}
+
+pub struct TableDataListSeed<'a, Row, S, S2 = Box<str>> {
+    schema: &'a TableSchema<S>,
+    max_results: Option<NonZeroU64>,
+    _marker: PhantomData<fn(Row, S2)>,
+}
+
+impl<'a, Row, S, S2> TableDataListSeed<'a, Row, S, S2> {
+    pub const fn new(schema: &'a TableSchema<S>) -> Self {
+        Self {
+            schema,
+            max_results: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The 'maxResults' the page was requested with, used to size the row buffer.
+    pub const fn max_results(mut self, max_results: Option<NonZeroU64>) -> Self {
+        self.max_results = max_results;
+        self
+    }
+}
+
+impl<'de, Row, S, S2> de::DeserializeSeed<'de> for TableDataListSeed<'_, Row, S, S2>
+where
+    Row: serde::Deserialize<'de>,
+    S: AsRef<str>,
+    S2: serde::Deserialize<'de>,
+{
+    type Value = TableDataList<Row, S2>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, Row, S, S2> de::Visitor<'de> for TableDataListSeed<'_, Row, S, S2>
+where
+    Row: serde::Deserialize<'de>,
+    S: AsRef<str>,
+    S2: serde::Deserialize<'de>,
+{
+    type Value = TableDataList<Row, S2>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a page of table data")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        enum Fields {
+            TotalRows,
+            PageToken,
+            Rows,
+            #[serde(other)]
+            Other,
+        }
+
+        let mut total_rows: Option<u64> = None;
+        let mut page_token: Option<S2> = None;
+        let mut rows: Option<Vec<Row>> = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                Fields::TotalRows if total_rows.is_some() => {
+                    return Err(de::Error::duplicate_field("totalRows"));
+                }
+                Fields::TotalRows => {
+                    total_rows = Some(map.next_value_seed(crate::util::Uint64ValueVisitor)?);
+                }
+                Fields::PageToken if page_token.is_some() => {
+                    return Err(de::Error::duplicate_field("pageToken"));
+                }
+                Fields::PageToken => page_token = map.next_value()?,
+                Fields::Rows if rows.is_some() => return Err(de::Error::duplicate_field("rows")),
+                Fields::Rows => {
+                    // 'totalRows' is the size of the whole table, not the page, so it can't
+                    // be used to size the buffer.
+                    rows = Some(map.next_value_seed(RowsVisitor {
+                        schema: self.schema,
+                        total_rows: None,
+                        request_limit: self.max_results,
+                        _marker: PhantomData,
+                    })?);
+                }
+                Fields::Other => _ = map.next_value::<de::IgnoredAny>()?,
+            }
+        }
+
+        Ok(TableDataList {
+            total_rows,
+            page_token,
+            // 'rows' is left out entirely when the page is empty
+            rows: rows.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use path_aware_serde::DeserializeSeedExt;
+
+    use super::*;
+    use crate::table::TableFieldSchema;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Row {
+        vessel: Box<str>,
+        alt: Option<f64>,
+    }
+
+    #[test]
+    fn test_deserialize_table_data_list() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = TableSchema {
+            fields: vec![
+                TableFieldSchema::builder("id").int().required(),
+                TableFieldSchema::builder("vessel").string().required(),
+                TableFieldSchema::builder("lat").float().required(),
+                TableFieldSchema::builder("alt").float().nullable(),
+            ],
+        };
+
+        // selectedFields=alt,vessel, which BigQuery returns in schema order
+        let selected = schema.select(&["alt", "VESSEL"]);
+
+        const PAGE: &str = r#"{
+            "kind": "bigquery#tableDataList",
+            "etag": "abc",
+            "totalRows": "3",
+            "pageToken": "next",
+            "rows": [
+                {"f": [{"v": "Test A"}, {"v": "1.5"}]},
+                {"f": [{"v": "Test B"}, {"v": null}]}
+            ]
+        }"#;
+
+        let mut de = serde_json::Deserializer::from_str(PAGE);
+        let page: TableDataList<Row> =
+            TableDataListSeed::new(&selected).deserialize_seed_path_aware(&mut de)?;
+
+        assert_eq!(page.total_rows, Some(3));
+        assert_eq!(page.page_token.as_deref(), Some("next"));
+        assert_eq!(
+            page.rows,
+            vec![
+                Row {
+                    vessel: "Test A".into(),
+                    alt: Some(1.5),
+                },
+                Row {
+                    vessel: "Test B".into(),
+                    alt: None,
+                },
+            ]
+        );
+
+        let mut de = serde_json::Deserializer::from_str(r#"{"totalRows": "0"}"#);
+        let empty: TableDataList<Row> =
+            TableDataListSeed::new(&selected).deserialize_seed_path_aware(&mut de)?;
+
+        assert!(empty.rows.is_empty() && empty.page_token.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_total_rows_larger_than_page() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = TableSchema {
+            fields: vec![
+                TableFieldSchema::builder("vessel").string().required(),
+                TableFieldSchema::builder("alt").float().nullable(),
+            ],
+        };
+
+        // 'totalRows' counts the whole table, and shouldn't be used to size a single page.
+        const PAGE: &str = r#"{
+            "totalRows": "9000000000000000000",
+            "pageToken": "next",
+            "rows": [
+                {"f": [{"v": "Test A"}, {"v": "1.5"}]},
+                {"f": [{"v": "Test B"}, {"v": null}]}
+            ]
+        }"#;
+
+        let mut de = serde_json::Deserializer::from_str(PAGE);
+        let page: TableDataList<Row> =
+            TableDataListSeed::new(&schema).deserialize_seed_path_aware(&mut de)?;
+
+        assert_eq!(page.total_rows, Some(9_000_000_000_000_000_000));
+        assert_eq!(page.rows.len(), 2);
+
+        let mut de = serde_json::Deserializer::from_str(PAGE);
+        let page: TableDataList<Row> = TableDataListSeed::new(&schema)
+            .max_results(NonZeroU64::new(2))
+            .deserialize_seed_path_aware(&mut de)?;
+
+        assert_eq!(page.rows.len(), 2);
+        assert_eq!(page.rows.capacity(), 2);
+
+        Ok(())
+    }
+}
@@ -9,6 +9,7 @@ use reqwest::{IntoUrl, Response, Url};
 use super::dataset::DatasetClient;
 use crate::Error;
 use crate::query::QueryBuilder;
+use crate::resources::dataset::Dataset;
 use crate::resources::job::Job;
 
 /// The Base URL for this service, missing the project id (which is the next path component)
@@ -80,6 +81,40 @@ impl BigQueryClient {
     pub fn into_dataset<D>(self, dataset_name: D) -> DatasetClient<'static, D> {
         DatasetClient::from_parts(dataset_name, self.inner)
     }
+
+    /// Lists every dataset in the project. The listed datasets only have the reference,
+    /// friendly name, labels and location filled in, the rest need a
+    /// [`DatasetClient::get`] call.
+    pub async fn list_datasets(&self) -> crate::Result<Vec<Dataset>> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct DatasetPage {
+            next_page_token: Option<Box<str>>,
+            #[serde(default)]
+            datasets: Vec<Dataset>,
+        }
+
+        let mut datasets = Vec::new();
+        let mut page_token: Option<Box<str>> = None;
+
+        loop {
+            let mut url = self.inner.make_url(["datasets"]);
+
+            if let Some(token) = page_token.take() {
+                url.query_pairs_mut().append_pair("pageToken", &token);
+            }
+
+            let resp = self.inner.get(url).await?;
+            let page: DatasetPage = deserialize_json(resp).await?;
+
+            datasets.extend(page.datasets);
+
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => return Ok(datasets),
+            }
+        }
+    }
 }
 
 impl InnerClient {
@@ -159,6 +194,25 @@ impl InnerClient {
         }
     }
 
+    #[inline]
+    pub(crate) async fn patch<S>(&self, url: impl IntoUrl, payload: S) -> Result<Response, Error>
+    where
+        S: serde::Serialize,
+    {
+        let resp = self
+            .request(reqwest::Method::PATCH, url)
+            .await?
+            .json(&payload)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            Err(handle_error(resp).await)
+        } else {
+            Ok(resp)
+        }
+    }
+
     #[inline]
     pub(crate) async fn post<S>(&self, url: impl IntoUrl, payload: S) -> Result<Response, Error>
     where
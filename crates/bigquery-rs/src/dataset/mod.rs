@@ -5,6 +5,7 @@ use shared::arc_or_ref::ArcOrRef;
 use super::client::InnerClient;
 use crate::BigQueryClient;
 use crate::query::QueryBuilder;
+use crate::resources::dataset::Dataset;
 use crate::resources::table::Table;
 use crate::table::TableClient;
 
@@ -88,6 +89,57 @@ impl<'a, D> DatasetClient<'a, D> {
 }
 
 impl<'a, D: AsRef<str>> DatasetClient<'a, D> {
+    pub fn dataset_reference(&self) -> DatasetReference {
+        DatasetReference {
+            project_id: Box::from(self.client.project_id().as_str()),
+            dataset_id: Box::from(self.dataset_name.as_ref()),
+        }
+    }
+
+    pub async fn get(&self) -> crate::Result<Dataset> {
+        let url = self
+            .client
+            .make_url(["datasets", self.dataset_name.as_ref()]);
+
+        let resp = self.client.get(url).await?;
+        super::client::deserialize_json(resp).await
+    }
+
+    /// Creates the dataset. The reference in 'dataset' is replaced with this dataset's, so
+    /// it can be built with a placeholder.
+    pub async fn create(&self, mut dataset: Dataset) -> crate::Result<Dataset> {
+        dataset.dataset_reference = self.dataset_reference();
+
+        let url = self.client.make_url(["datasets"]);
+
+        let resp = self.client.post(url, dataset).await?;
+        super::client::deserialize_json(resp).await
+    }
+
+    /// Gets the dataset, creating it from 'dataset' if it doesn't exist yet.
+    pub async fn ensure_exists(&self, dataset: Dataset) -> crate::Result<Dataset> {
+        match self.get().await {
+            Ok(existing) => Ok(existing),
+            Err(err) if err.is_not_found() => self.create(dataset).await,
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Deletes the dataset. Unless 'delete_contents' is set, this fails if the dataset
+    /// still has tables in it.
+    pub async fn delete(&self, delete_contents: bool) -> crate::Result<()> {
+        let mut url = self
+            .client
+            .make_url(["datasets", self.dataset_name.as_ref()]);
+
+        if delete_contents {
+            url.query_pairs_mut().append_pair("deleteContents", "true");
+        }
+
+        self.client.delete(url).await?;
+        Ok(())
+    }
+
     pub fn list_tables(&self, max_page_size: usize) -> TableStream<'_, 'a, D> {
         TableStream::new(self, max_page_size)
     }
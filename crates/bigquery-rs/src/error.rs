@@ -10,6 +10,17 @@ pub enum Error {
     MissingField(#[from] MissingField),
     #[error(transparent)]
     Json(#[from] path_aware_serde::Error<serde_json::Error>),
+    #[error(transparent)]
+    QueryParameter(#[from] crate::resources::query::parameter_serializer::SerializeError),
+    #[error("a query can't mix named and positional parameters")]
+    MixedParameterModes,
+    #[error("query would process {estimated} bytes, over the limit of {limit}")]
+    BytesLimitExceeded {
+        estimated: u64,
+        limit: std::num::NonZeroU64,
+    },
+    #[error(transparent)]
+    IncompatibleSchema(#[from] crate::resources::table::IncompatibleSchema),
     #[error("{main}")]
     JobError {
         main: ErrorProto,
@@ -6,6 +6,7 @@ use std::task::{Context, Poll};
 use tokio::time::Interval;
 use tokio_util::sync::ReusableBoxFuture;
 
+use crate::resources::job::statistics::JobStatistics;
 use crate::resources::job::{Job, JobReference, JobState, JobStatus};
 
 const DEFAULT_POLL_FREQUENCY: timestamp::Duration = timestamp::Duration::from_seconds(2);
@@ -60,17 +61,58 @@ impl<'a> ActiveJob<'a> {
         &self.status
     }
 
-    pub fn poll_with_callback<Cb>(
-        self,
-        frequency: timestamp::Duration,
-        callback: Cb,
-    ) -> ActiveJobFuture<'a, Cb> {
+    /// The statistics from the last time the job was fetched. For finished jobs, this has
+    /// the totals for the bytes processed/billed, slot time, rows loaded, etc.
+    pub fn statistics(&self) -> Option<&JobStatistics> {
+        self.job.statistics.as_ref()
+    }
+
+    /// Consumes the job, returning the statistics from the last time it was fetched.
+    pub fn into_statistics(self) -> Option<JobStatistics> {
+        self.job.statistics
+    }
+
+    fn job_url(&self) -> reqwest::Url {
         let mut url = self.client.inner.make_url(&["jobs", &self.job_ref.job_id]);
 
         url.query_pairs_mut()
             .append_pair("location", &self.job_ref.location)
             .finish();
 
+        url
+    }
+
+    /// Re-fetches the job, updating the status and statistics.
+    pub async fn refresh(&mut self) -> crate::Result<&JobStatus> {
+        let client = (*self.client).clone();
+        self.job = get_job(self.job_url(), client).await?;
+
+        self.status = self
+            .job
+            .status
+            .take()
+            .ok_or_else(|| crate::Error::missing_field::<Job>("status", None::<JobStatus>))?;
+
+        Ok(&self.status)
+    }
+
+    /// Polls the job until it's done. Unlike awaiting the [`ActiveJob`] directly, this
+    /// keeps the job around so the final [`ActiveJob::statistics`] can be read afterwards.
+    pub async fn wait(&mut self, frequency: timestamp::Duration) -> crate::Result<&JobStatus> {
+        while self.refresh().await?.state != JobState::Done {
+            tokio::time::sleep(frequency.into()).await;
+        }
+
+        Ok(&self.status)
+    }
+
+    pub fn poll_with_callback<Cb>(
+        self,
+        frequency: timestamp::Duration,
+        callback: Cb,
+    ) -> ActiveJobFuture<'a, Cb> {
+        let url = self.job_url();
+
         let client = (&*self.client).clone();
 
         ActiveJobFuture {
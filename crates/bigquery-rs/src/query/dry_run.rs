@@ -0,0 +1,59 @@
+use std::num::NonZeroU64;
+
+use bigquery_resources_rs::TableReference;
+use bigquery_resources_rs::job::Job;
+use bigquery_resources_rs::job::statistics::QueryStatistics;
+use bigquery_resources_rs::table::TableSchema;
+
+/// The result of dry-running a query, returned by [`QueryBuilder::dry_run`].
+///
+/// [`QueryBuilder::dry_run`]: super::QueryBuilder::dry_run
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    /// The estimated number of bytes the query will scan, and be billed for (ignoring the
+    /// per-query minimum).
+    pub total_bytes_processed: u64,
+    pub referenced_tables: Vec<TableReference>,
+    /// The schema of the query results.
+    pub schema: Option<TableSchema<Box<str>>>,
+    /// i.e 'SELECT', 'INSERT', 'CREATE_TABLE', etc.
+    pub statement_type: Option<Box<str>>,
+}
+
+impl DryRun {
+    pub(super) fn from_job(job: Job) -> crate::Result<Self> {
+        let QueryStatistics {
+            total_bytes_processed,
+            referenced_tables,
+            schema,
+            statement_type,
+            ..
+        } = job
+            .statistics
+            .and_then(|stats| stats.query)
+            .ok_or_else(|| crate::Error::missing_field::<Job>("statistics.query", None::<()>))?;
+
+        let total_bytes_processed = total_bytes_processed.ok_or_else(|| {
+            crate::Error::missing_field::<QueryStatistics>("total_bytes_processed", None::<u64>)
+        })?;
+
+        Ok(Self {
+            total_bytes_processed,
+            referenced_tables,
+            schema,
+            statement_type,
+        })
+    }
+
+    /// Errors if the query would scan more than 'limit' bytes.
+    pub fn check_bytes_limit(&self, limit: NonZeroU64) -> crate::Result<()> {
+        if self.total_bytes_processed > limit.get() {
+            return Err(crate::Error::BytesLimitExceeded {
+                estimated: self.total_bytes_processed,
+                limit,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,18 @@
 use std::num::NonZeroU64;
 
+use bigquery_resources_rs::job::query::JobConfigurationQuery;
+use bigquery_resources_rs::job::{Job, JobConfiguration};
 use bigquery_resources_rs::query::{
-    QueryParameter, QueryParameterType, QueryParameterValue, QueryRequest, QueryResponse,
+    ParameterMode, QueryParameter, QueryParameterType, QueryParameterValue, QueryRequest,
+    QueryResponse,
 };
 use bigquery_resources_rs::table::FieldType;
 
 use crate::BigQueryClient;
 
+mod dry_run;
 mod stream;
+pub use dry_run::DryRun;
 pub use stream::{Options, QueryStream};
 
 pub struct QueryBuilder<S = Box<str>> {
@@ -38,12 +43,20 @@ impl<S> QueryBuilder<S> {
         self
     }
 
+    /// Fails the query (without being billed) if it would process more than 'bytes'. Also
+    /// checked up front by [`QueryBuilder::dry_run`].
+    pub fn maximum_bytes_billed(mut self, bytes: NonZeroU64) -> Self {
+        self.request.maximum_bytes_billed = Some(bytes);
+        self
+    }
+
+    /// Adds a named 'INT64' or 'FLOAT64' parameter, referenced in the query as '@name'.
     pub fn number_param(
         mut self,
         name: impl Into<S>,
         param: impl Into<serde_json::Number>,
-    ) -> Self {
-        self.request.parameter_mode = Some(bigquery_resources_rs::query::ParameterMode::Named);
+    ) -> crate::Result<Self> {
+        self.set_parameter_mode(ParameterMode::Named)?;
 
         let number: serde_json::Number = param.into();
 
@@ -59,17 +72,95 @@ impl<S> QueryBuilder<S> {
             parameter_value: QueryParameterValue::Scalar(number.into()),
         });
 
-        self
+        Ok(self)
     }
 
-    pub fn string_param(mut self, name: impl Into<S>, param: impl Into<String>) -> Self {
-        self.request.parameter_mode = Some(bigquery_resources_rs::query::ParameterMode::Named);
+    /// Adds a named 'STRING' parameter, referenced in the query as '@name'.
+    pub fn string_param(
+        mut self,
+        name: impl Into<S>,
+        param: impl Into<String>,
+    ) -> crate::Result<Self> {
+        self.set_parameter_mode(ParameterMode::Named)?;
         self.request.query_parameters.push(QueryParameter {
             name: Some(name.into()),
             parameter_type: QueryParameterType::Scalar(FieldType::String),
             parameter_value: QueryParameterValue::Scalar(serde_json::Value::String(param.into())),
         });
-        self
+        Ok(self)
+    }
+
+    /// Adds a named parameter, referenced in the query as '@name'.
+    ///
+    /// The parameter type is inferred from how 'value' serializes, so structs become
+    /// 'STRUCT's, sequences become 'ARRAY's, etc. Types that serde can't distinguish from
+    /// strings or numbers (i.e 'TIMESTAMP', 'DATE', 'NUMERIC', 'RANGE') need to be wrapped in
+    /// one of the [`param`] types. A bare 'None' can't be inferred, so either wrap it in one
+    /// of those types, or use 'NULL' in the query instead.
+    ///
+    /// [`param`]: bigquery_resources_rs::query::param
+    pub fn param<T>(mut self, name: impl Into<S>, value: &T) -> crate::Result<Self>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.set_parameter_mode(ParameterMode::Named)?;
+
+        let param = QueryParameter::serialize(value)?.named(name);
+        self.request.query_parameters.push(param);
+        Ok(self)
+    }
+
+    /// Adds a positional parameter, referenced in the query as '?'. Positional parameters are
+    /// bound in the order they're added. See [`QueryBuilder::param`] for how the parameter
+    /// type is determined.
+    pub fn positional_param<T>(mut self, value: &T) -> crate::Result<Self>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.set_parameter_mode(ParameterMode::Positional)?;
+
+        let param = QueryParameter::serialize(value)?;
+        self.request.query_parameters.push(param);
+        Ok(self)
+    }
+
+    fn set_parameter_mode(&mut self, mode: ParameterMode) -> crate::Result<()> {
+        match self.request.parameter_mode {
+            Some(existing) if existing != mode => Err(crate::Error::MixedParameterModes),
+            _ => {
+                self.request.parameter_mode = Some(mode);
+                Ok(())
+            }
+        }
+    }
+
+    /// Validates the query without running it, returning the estimated bytes processed, the
+    /// tables it references and the result schema.
+    ///
+    /// If [`QueryBuilder::maximum_bytes_billed`] was set, this errors with
+    /// [`Error::BytesLimitExceeded`] if the estimate is over that limit.
+    ///
+    /// [`Error::BytesLimitExceeded`]: crate::Error::BytesLimitExceeded
+    pub async fn dry_run(self) -> crate::Result<DryRun>
+    where
+        S: From<&'static str> + serde::Serialize,
+    {
+        let limit = self.request.maximum_bytes_billed;
+
+        let mut config = JobConfiguration::from(JobConfigurationQuery::from_request(self.request));
+        config.dry_run = true;
+
+        let url = self.client.inner.make_url(["jobs"]);
+        let resp = self.client.inner.post(url, &config.into_job()).await?;
+
+        let job: Job = crate::client::deserialize_json(resp).await?;
+        let dry_run = DryRun::from_job(job)?;
+
+        if let Some(limit) = limit {
+            dry_run.check_bytes_limit(limit)?;
+        }
+
+        Ok(dry_run)
     }
 
     pub async fn execute<Row, S2>(self) -> crate::Result<QueryResponse<Row, S2>>
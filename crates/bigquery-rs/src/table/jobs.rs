@@ -0,0 +1,239 @@
+//! Builders for the load/extract/copy jobs started from a [`TableClient`].
+//!
+//! Each one starts the job, polls it until it's done, then returns the statistics for that
+//! kind of job. If the job fails, the error result and the rest of the per-row errors are
+//! returned as [`Error::JobError`].
+//!
+//! [`Error::JobError`]: crate::Error::JobError
+use crate::BigQueryClient;
+use crate::resources::TableReference;
+use crate::resources::job::copy::{JobConfigurationTableCopy, OperationType};
+use crate::resources::job::extract::{Compression, JobConfigurationExtract, TableExtract};
+use crate::resources::job::load::{CreateDisposition, JobConfigurationLoad, WriteDisposition};
+use crate::resources::job::statistics::{
+    CopyStatistics, ExtractStatistics, JobStatistics, LoadStatistics,
+};
+use crate::resources::job::{Job, JobConfiguration};
+use crate::resources::table::TableSchema;
+
+const DEFAULT_POLL_FREQUENCY: timestamp::Duration = timestamp::Duration::from_seconds(2);
+
+/// Shared options for starting + polling a job.
+#[derive(Debug, Clone, Copy)]
+struct JobOptions {
+    timeout: Option<timestamp::Duration>,
+    poll_frequency: timestamp::Duration,
+}
+
+impl Default for JobOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            poll_frequency: DEFAULT_POLL_FREQUENCY,
+        }
+    }
+}
+
+impl JobOptions {
+    async fn run(
+        self,
+        client: &BigQueryClient,
+        mut config: JobConfiguration<Box<str>>,
+    ) -> crate::Result<JobStatistics> {
+        config.job_timeout = self.timeout;
+
+        let mut job = client.start_job(config.into_job()).await?;
+
+        job.wait(self.poll_frequency)
+            .await?
+            .clone()
+            .into_result(|main, misc| crate::Error::JobError { main, misc })?;
+
+        job.into_statistics()
+            .ok_or_else(|| crate::Error::missing_field::<Job>("statistics", None::<()>))
+    }
+}
+
+macro_rules! impl_job_options {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl $name {
+                /// Has BigQuery cancel the job if it runs longer than 'timeout'.
+                pub fn timeout(mut self, timeout: timestamp::Duration) -> Self {
+                    self.options.timeout = Some(timeout);
+                    self
+                }
+
+                /// How often to check on the job, defaults to every 2 seconds.
+                pub fn poll_frequency(mut self, frequency: timestamp::Duration) -> Self {
+                    self.options.poll_frequency = frequency;
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_job_options!(LoadJob, ExtractJob, CopyJob);
+
+/// Loads files from GCS into a table. Built by [`TableClient::load_from_gcs`].
+///
+/// [`TableClient::load_from_gcs`]: super::TableClient::load_from_gcs
+#[must_use = "the job isn't started until `LoadJob::execute` is called"]
+pub struct LoadJob {
+    client: BigQueryClient,
+    config: JobConfigurationLoad<Box<str>>,
+    options: JobOptions,
+}
+
+impl LoadJob {
+    pub(super) fn new(client: BigQueryClient, config: JobConfigurationLoad<Box<str>>) -> Self {
+        Self {
+            client,
+            config,
+            options: JobOptions::default(),
+        }
+    }
+
+    /// Uses an explicit schema, rather than autodetecting one from the source files.
+    pub fn schema(mut self, schema: TableSchema<Box<str>>) -> Self {
+        self.config = self.config.schema(schema);
+        self
+    }
+
+    /// Turns schema autodetection on/off for CSV and JSON files.
+    pub fn autodetect(mut self, autodetect: bool) -> Self {
+        self.config.source_format.set_autodetect(autodetect);
+        self
+    }
+
+    pub fn write_disposition(mut self, write_disposition: WriteDisposition) -> Self {
+        self.config = self.config.write_disposition(write_disposition);
+        self
+    }
+
+    pub fn create_disposition(mut self, create_disposition: CreateDisposition) -> Self {
+        self.config = self.config.create_disposition(create_disposition);
+        self
+    }
+
+    pub fn ignore_unknown_values(mut self, ignore_unknown_values: bool) -> Self {
+        self.config = self.config.ignore_unknown_values(ignore_unknown_values);
+        self
+    }
+
+    pub async fn execute(self) -> crate::Result<LoadStatistics> {
+        let stats = self
+            .options
+            .run(&self.client, JobConfiguration::from(self.config))
+            .await?;
+
+        stats
+            .load
+            .ok_or_else(|| crate::Error::missing_field::<JobStatistics>("load", None::<()>))
+    }
+}
+
+/// Exports a table to GCS. Built by [`TableClient::extract_to_gcs`].
+///
+/// [`TableClient::extract_to_gcs`]: super::TableClient::extract_to_gcs
+#[must_use = "the job isn't started until `ExtractJob::execute` is called"]
+pub struct ExtractJob {
+    client: BigQueryClient,
+    destination_uris: Vec<Box<str>>,
+    extract: TableExtract<Box<str>>,
+    options: JobOptions,
+}
+
+impl ExtractJob {
+    pub(super) fn new(
+        client: BigQueryClient,
+        destination_uris: Vec<Box<str>>,
+        extract: TableExtract<Box<str>>,
+    ) -> Self {
+        Self {
+            client,
+            destination_uris,
+            extract,
+            options: JobOptions::default(),
+        }
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.extract = self.extract.compression(compression);
+        self
+    }
+
+    /// Adds another destination URI. Tables over 1GB need to be split across files, which
+    /// is done with a wildcard URI, i.e 'gs://bucket/export-*.csv'.
+    pub fn destination_uri(mut self, uri: impl Into<Box<str>>) -> Self {
+        self.destination_uris.push(uri.into());
+        self
+    }
+
+    pub async fn execute(self) -> crate::Result<ExtractStatistics> {
+        let config = JobConfigurationExtract::new(self.destination_uris, self.extract);
+
+        let stats = self
+            .options
+            .run(&self.client, JobConfiguration::from(config))
+            .await?;
+
+        stats
+            .extract
+            .ok_or_else(|| crate::Error::missing_field::<JobStatistics>("extract", None::<()>))
+    }
+}
+
+/// Copies one or more tables into another. Built by [`TableClient::copy_to`].
+///
+/// [`TableClient::copy_to`]: super::TableClient::copy_to
+#[must_use = "the job isn't started until `CopyJob::execute` is called"]
+pub struct CopyJob {
+    client: BigQueryClient,
+    config: JobConfigurationTableCopy<Box<str>>,
+    options: JobOptions,
+}
+
+impl CopyJob {
+    pub(super) fn new(client: BigQueryClient, config: JobConfigurationTableCopy<Box<str>>) -> Self {
+        Self {
+            client,
+            config,
+            options: JobOptions::default(),
+        }
+    }
+
+    /// Copies another table into the same destination, along with the original source.
+    pub fn also_copy(mut self, source: TableReference) -> Self {
+        self.config.source_tables.push(source);
+        self
+    }
+
+    pub fn write_disposition(mut self, write_disposition: WriteDisposition) -> Self {
+        self.config = self.config.write_disposition(write_disposition);
+        self
+    }
+
+    pub fn create_disposition(mut self, create_disposition: CreateDisposition) -> Self {
+        self.config = self.config.create_disposition(create_disposition);
+        self
+    }
+
+    /// Creates a snapshot/clone instead of a regular copy.
+    pub fn operation_type(mut self, operation_type: OperationType) -> Self {
+        self.config.operation_type = Some(operation_type);
+        self
+    }
+
+    pub async fn execute(self) -> crate::Result<CopyStatistics> {
+        let stats = self
+            .options
+            .run(&self.client, JobConfiguration::from(self.config))
+            .await?;
+
+        stats
+            .copy
+            .ok_or_else(|| crate::Error::missing_field::<JobStatistics>("copy", None::<()>))
+    }
+}
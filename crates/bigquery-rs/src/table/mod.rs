@@ -2,11 +2,19 @@ use shared::arc_or_ref::ArcOrRef;
 
 use super::client::InnerClient;
 use crate::BigQueryClient;
-use crate::resources::table::Table;
+use crate::resources::TableReference;
+use crate::resources::job::copy::JobConfigurationTableCopy;
+use crate::resources::job::extract::{TableExtract, TableExtractFormat};
+use crate::resources::job::load::{JobConfigurationLoad, SourceFormat};
+use crate::resources::table::{Table, TableSchema};
 use crate::resources::table_data::TableDataInsertAllResponse;
 
 mod insert_rows;
+mod jobs;
+mod read_rows;
 pub use insert_rows::InsertRowOptions;
+pub use jobs::{CopyJob, ExtractJob, LoadJob};
+pub use read_rows::{ReadRowsOptions, RowStream};
 
 #[derive(Debug, Clone)]
 pub struct TableClient<'a, D, T> {
@@ -129,4 +137,184 @@ impl<'a, D, T> TableClient<'a, D, T> {
         self.client.delete(url).await?;
         Ok(())
     }
+
+    /// The fully qualified reference to this table.
+    pub fn table_reference(&self) -> TableReference
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        TableReference {
+            project_id: Box::from(self.client.project_id().as_str()),
+            dataset_id: Box::from(self.dataset_name.as_ref()),
+            table_id: Box::from(self.table_name.as_ref()),
+        }
+    }
+
+    /// Loads files from GCS into this table, appending to it by default. 'uris' can contain
+    /// wildcards, i.e 'gs://bucket/exports/*.json'.
+    pub fn load_from_gcs<U>(&self, uris: U, format: SourceFormat<Box<str>>) -> LoadJob
+    where
+        U: IntoIterator,
+        U::Item: Into<Box<str>>,
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let uris = uris.into_iter().map(Into::into).collect::<Vec<_>>();
+        let config = JobConfigurationLoad::new(uris, format, self.table_reference());
+
+        LoadJob::new(self.client(), config)
+    }
+
+    /// Exports this table to GCS.
+    pub fn extract_to_gcs(
+        &self,
+        uri: impl Into<Box<str>>,
+        format: TableExtractFormat<Box<str>>,
+    ) -> ExtractJob
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let extract = TableExtract::new(self.table_reference(), format);
+
+        ExtractJob::new(self.client(), vec![uri.into()], extract)
+    }
+
+    /// Copies this table to 'destination', which can be in another dataset or project.
+    pub fn copy_to(&self, destination: TableReference) -> CopyJob
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let config = JobConfigurationTableCopy::new(vec![self.table_reference()], destination);
+
+        CopyJob::new(self.client(), config)
+    }
+
+    fn table_url(&self) -> reqwest::Url
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        self.client.make_url([
+            "datasets",
+            self.dataset_name.as_ref(),
+            "tables",
+            self.table_name.as_ref(),
+        ])
+    }
+
+    /// Creates the table. The reference in 'table' is replaced with this table's.
+    pub async fn create(&self, mut table: Table) -> crate::Result<Table>
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        table.table_reference = Some(self.table_reference());
+
+        let url = self
+            .client
+            .make_url(["datasets", self.dataset_name.as_ref(), "tables"]);
+
+        let resp = self.client.post(url, table).await?;
+        super::client::deserialize_json(resp).await
+    }
+
+    /// Patches the table, only changing the fields set in 'table'.
+    ///
+    /// If a schema is given, it replaces the existing schema entirely, and needs to be a
+    /// compatible superset of it (see [`TableSchema::diff`]).
+    pub async fn patch(&self, table: Table) -> crate::Result<Table>
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let resp = self.client.patch(self.table_url(), table).await?;
+        super::client::deserialize_json(resp).await
+    }
+
+    /// Evolves the table's schema into 'schema', checking that the changes can be made in
+    /// place first. Doesn't patch anything if the schemas already match.
+    pub async fn update_schema(&self, schema: TableSchema<Box<str>>) -> crate::Result<Table>
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let existing = self.get().await?;
+
+        if let Some(ref existing_schema) = existing.schema
+            && existing_schema.diff(&schema)?.is_empty()
+        {
+            return Ok(existing);
+        }
+
+        self.patch(Table::new(self.table_reference(), schema)).await
+    }
+
+    /// Makes sure the table exists and matches 'table', creating it if it doesn't exist.
+    ///
+    /// If it does, the schema is evolved (erroring if the changes aren't compatible), and
+    /// the description, labels, expiration and clustering are updated if they're set and
+    /// differ. Partitioning can't be changed after a table is created, so it's only used
+    /// when creating the table.
+    pub async fn ensure(&self, mut table: Table) -> crate::Result<Table>
+    where
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let existing = match self.get().await {
+            Ok(existing) => existing,
+            Err(err) if err.is_not_found() => return self.create(table).await,
+            Err(err) => return Err(err),
+        };
+
+        let schema_changed = match (&existing.schema, &table.schema) {
+            (Some(existing_schema), Some(schema)) => !existing_schema.diff(schema)?.is_empty(),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+
+        fn differs<V: PartialEq>(new: &Option<V>, existing: &Option<V>) -> bool {
+            new.is_some() && new != existing
+        }
+
+        let needs_patch = schema_changed
+            || differs(&table.description, &existing.description)
+            || differs(&table.labels, &existing.labels)
+            || differs(&table.expiration_time, &existing.expiration_time)
+            || (!table.clustering.is_empty() && table.clustering != existing.clustering);
+
+        if !needs_patch {
+            return Ok(existing);
+        }
+
+        table.table_reference = Some(self.table_reference());
+        table.time_partitioning = None;
+        table.range_partitioning = None;
+
+        self.patch(table).await
+    }
+
+    /// Reads every row in the table via 'tabledata.list', without running a query.
+    ///
+    /// Each row is deserialized into 'Row' using the table schema, the same way as query
+    /// results. Best suited to small tables, larger ones should use the Storage Read API.
+    pub fn read_rows<Row>(&self) -> RowStream<Row>
+    where
+        Row: serde::de::DeserializeOwned + Send + 'static,
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        self.read_rows_opt(ReadRowsOptions::default())
+    }
+
+    pub fn read_rows_opt<Row>(&self, options: ReadRowsOptions) -> RowStream<Row>
+    where
+        Row: serde::de::DeserializeOwned + Send + 'static,
+        D: AsRef<str>,
+        T: AsRef<str>,
+    {
+        RowStream::new(self.client(), self.table_url(), options)
+    }
 }
@@ -0,0 +1,250 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use reqwest::Url;
+use tokio_util::sync::ReusableBoxFuture;
+
+use crate::BigQueryClient;
+use crate::resources::table::{Table, TableSchema};
+use crate::resources::table_data::{TableDataList, TableDataListSeed};
+
+/// Options for [`TableClient::read_rows_opt`].
+///
+/// [`TableClient::read_rows_opt`]: super::TableClient::read_rows_opt
+#[derive(Debug, Clone, Default)]
+pub struct ReadRowsOptions {
+    /// Only read these fields. Nested fields can be selected with a dotted path,
+    /// i.e 'position.lat'.
+    pub selected_fields: Option<Vec<Box<str>>>,
+    /// The index of the first row to read.
+    pub start_index: Option<u64>,
+    /// The max number of rows per page. BigQuery also caps pages at 10MB.
+    pub page_size: Option<NonZeroU32>,
+}
+
+type PageResult<Row> = crate::Result<(Arc<TableSchema<Box<str>>>, TableDataList<Row>)>;
+
+pin_project_lite::pin_project! {
+    /// A [`Stream`] of rows from 'tabledata.list', returned by [`TableClient::read_rows`].
+    ///
+    /// The table schema is fetched up front, then used to deserialize each page of rows as
+    /// it comes in.
+    ///
+    /// [`TableClient::read_rows`]: super::TableClient::read_rows
+    pub struct RowStream<Row> {
+        client: BigQueryClient,
+        table_url: Url,
+        options: Arc<ReadRowsOptions>,
+        request_fut: ReusableBoxFuture<'static, PageResult<Row>>,
+        buf: std::vec::IntoIter<Row>,
+        total_rows: Option<u64>,
+        done: bool,
+    }
+}
+
+impl<Row> RowStream<Row>
+where
+    Row: serde::de::DeserializeOwned + Send + 'static,
+{
+    pub(super) fn new(client: BigQueryClient, table_url: Url, options: ReadRowsOptions) -> Self {
+        let options = Arc::new(options);
+
+        let request_fut = ReusableBoxFuture::new(get_page(
+            client.clone(),
+            table_url.clone(),
+            Arc::clone(&options),
+            None,
+            None,
+        ));
+
+        Self {
+            client,
+            table_url,
+            options,
+            request_fut,
+            buf: Vec::new().into_iter(),
+            total_rows: None,
+            done: false,
+        }
+    }
+
+    /// The total number of rows in the table, once the first page has been read.
+    pub fn total_rows(&self) -> Option<u64> {
+        self.total_rows
+    }
+
+    /// Reads every remaining row.
+    pub async fn collect(self) -> crate::Result<Vec<Row>> {
+        futures::TryStreamExt::try_collect(self).await
+    }
+}
+
+async fn get_page<Row>(
+    client: BigQueryClient,
+    table_url: Url,
+    options: Arc<ReadRowsOptions>,
+    schema: Option<Arc<TableSchema<Box<str>>>>,
+    page_token: Option<Box<str>>,
+) -> PageResult<Row>
+where
+    Row: serde::de::DeserializeOwned,
+{
+    let schema = match schema {
+        Some(schema) => schema,
+        None => {
+            let resp = client.inner.get(table_url.clone()).await?;
+            let table: Table = crate::client::deserialize_json(resp).await?;
+
+            let schema = table
+                .schema
+                .ok_or_else(|| crate::Error::missing_field::<Table>("schema", None::<()>))?;
+
+            match options.selected_fields {
+                Some(ref selected) => Arc::new(schema.select(selected)),
+                None => Arc::new(schema),
+            }
+        }
+    };
+
+    let url = page_url(table_url, &options, page_token.as_deref());
+
+    let resp = client.inner.get(url).await?;
+    let bytes = resp.bytes().await?;
+
+    let page = path_aware_serde::json::deserialize_slice_seed(
+        TableDataListSeed::<Row, _>::new(&*schema)
+            .max_results(options.page_size.map(NonZeroU64::from)),
+        &bytes,
+    )?;
+
+    Ok((schema, page))
+}
+
+/// Builds the 'tabledata.list' url for a page, starting at 'options.start_index' for the first
+/// page, and at 'page_token' for every one after.
+fn page_url(table_url: Url, options: &ReadRowsOptions, page_token: Option<&str>) -> Url {
+    let mut url = table_url;
+    url.path_segments_mut().expect("can be a base").push("data");
+
+    {
+        let mut query = url.query_pairs_mut();
+
+        match page_token {
+            Some(token) => _ = query.append_pair("pageToken", token),
+            // the token already encodes where the next page starts
+            None => {
+                if let Some(start_index) = options.start_index {
+                    query.append_pair("startIndex", itoa::Buffer::new().format(start_index));
+                }
+            }
+        }
+
+        if let Some(ref selected) = options.selected_fields {
+            query.append_pair("selectedFields", &selected.join(","));
+        }
+
+        if let Some(page_size) = options.page_size {
+            query.append_pair("maxResults", itoa::Buffer::new().format(page_size.get()));
+        }
+    }
+
+    url
+}
+
+impl<Row> Stream for RowStream<Row>
+where
+    Row: serde::de::DeserializeOwned + Send + 'static,
+{
+    type Item = crate::Result<Row>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        loop {
+            if let Some(row) = this.buf.next() {
+                return Poll::Ready(Some(Ok(row)));
+            }
+
+            if *this.done {
+                return Poll::Ready(None);
+            }
+
+            let (schema, page) = match std::task::ready!(this.request_fut.poll(cx)) {
+                Ok(result) => result,
+                Err(error) => {
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(error)));
+                }
+            };
+
+            *this.total_rows = page.total_rows.or(*this.total_rows);
+            *this.buf = page.rows.into_iter();
+
+            match page.page_token {
+                Some(token) if !token.is_empty() => {
+                    this.request_fut.set(get_page(
+                        this.client.clone(),
+                        this.table_url.clone(),
+                        Arc::clone(this.options),
+                        Some(schema),
+                        Some(token),
+                    ));
+                }
+                _ => *this.done = true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE_URL: &str =
+        "https://bigquery.googleapis.com/bigquery/v2/projects/p/datasets/d/tables/t";
+
+    fn query_pairs(url: &Url) -> Vec<(String, String)> {
+        url.query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_page_url() {
+        let options = ReadRowsOptions {
+            selected_fields: Some(vec!["id".into(), "position.lat".into()]),
+            start_index: Some(100),
+            page_size: NonZeroU32::new(50),
+        };
+
+        let table_url = Url::parse(TABLE_URL).unwrap();
+
+        let first = page_url(table_url.clone(), &options, None);
+        assert_eq!(
+            first.path(),
+            "/bigquery/v2/projects/p/datasets/d/tables/t/data"
+        );
+        assert_eq!(
+            query_pairs(&first),
+            [
+                ("startIndex".to_owned(), "100".to_owned()),
+                ("selectedFields".to_owned(), "id,position.lat".to_owned()),
+                ("maxResults".to_owned(), "50".to_owned()),
+            ]
+        );
+
+        // later pages pick up from the token, so 'startIndex' would point back at the first page
+        let second = page_url(table_url, &options, Some("next-page"));
+        assert_eq!(
+            query_pairs(&second),
+            [
+                ("pageToken".to_owned(), "next-page".to_owned()),
+                ("selectedFields".to_owned(), "id,position.lat".to_owned()),
+                ("maxResults".to_owned(), "50".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,443 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, Lit, Meta, NestedMeta, parse_macro_input};
+
+/// Derives `RowSchema` and `ColumnType` for a struct, building the BigQuery schema from the
+/// same field names that serde serializes.
+///
+/// Column types come from the rust field types (see `ColumnType` for the supported types),
+/// with `Option<T>` mapping to 'NULLABLE' and sequences to 'REPEATED' columns. Nested structs
+/// that also derive `TableSchema` become 'RECORD' columns.
+///
+/// Enums with only unit variants derive `ColumnType` as 'STRING' columns, since that's how serde
+/// serializes them.
+///
+/// Serde's `rename`, `rename_all`, `skip`/`skip_serializing` and `flatten` attributes are
+/// respected. Types without a direct rust equivalent can be set per field:
+///
+/// ```ignore
+/// #[derive(serde::Serialize, TableSchema)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Sighting {
+///     sighting_id: i64,
+///     #[bigquery(ty = "GEOGRAPHY", description = "WKT point")]
+///     location: String,
+///     #[bigquery(ty = "NUMERIC")]
+///     distance: Option<String>,
+///     #[bigquery(range = "TIMESTAMP")]
+///     window: Window,
+/// }
+/// ```
+#[proc_macro_derive(TableSchema, attributes(bigquery))]
+pub fn table_schema_derive(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    let result = match input.data {
+        Data::Struct(ref data) => derive_struct(&input, &data.fields),
+        Data::Enum(ref data) => derive_unit_enum(&input, data),
+        Data::Union(_) => Err(syn::Error::new(
+            input.ident.span(),
+            "TableSchema can't be derived for unions",
+        )),
+    };
+
+    match result {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+fn derive_struct(input: &DeriveInput, fields: &Fields) -> syn::Result<TokenStream2> {
+    let fields = match fields {
+        Fields::Named(named) => &named.named,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "TableSchema can only be derived for structs with named fields",
+            ));
+        }
+    };
+
+    let rename_all = serde_rename_all(&input.attrs)?;
+
+    let mut field_stmts = Vec::with_capacity(fields.len());
+    let mut field_types = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let serde = SerdeFieldAttrs::parse(&field.attrs)?;
+        if serde.skip {
+            continue;
+        }
+
+        let ty = &field.ty;
+        field_types.push(ty);
+
+        if serde.flatten {
+            field_stmts.push(quote! {
+                fields.extend(<#ty as ::bigquery_storage_rs::write::RowSchema>::fields());
+            });
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("named fields have idents");
+        let name = match serde.rename {
+            Some(rename) => rename,
+            None => {
+                let ident = ident.to_string();
+                let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+                match rename_all {
+                    Some(rule) => rule.apply(ident),
+                    None => ident.to_owned(),
+                }
+            }
+        };
+
+        let overrides = BigQueryFieldAttrs::parse(&field.attrs)?.into_tokens();
+
+        field_stmts.push(quote! {
+            {
+                #[allow(unused_mut)]
+                let mut field = ::bigquery_storage_rs::write::column::<#ty>(#name);
+                #overrides
+                fields.push(field);
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // only bother bounding field types for generic structs, otherwise missing impls would
+    // get reported against the where clause rather than the field itself.
+    let where_clause = if input.generics.params.is_empty() {
+        quote!(#where_clause)
+    } else {
+        let existing = where_clause.map(|clause| &clause.predicates);
+        quote! {
+            where
+                #(#field_types: ::bigquery_storage_rs::write::ColumnType,)*
+                #existing
+        }
+    };
+
+    let field_count = field_stmts.len();
+
+    Ok(quote! {
+        impl #impl_generics ::bigquery_storage_rs::write::RowSchema for #ident #ty_generics
+        #where_clause
+        {
+            fn fields() -> ::std::vec::Vec<
+                ::bigquery_storage_rs::__private::TableFieldSchema<::std::boxed::Box<str>>
+            > {
+                let mut fields = ::std::vec::Vec::with_capacity(#field_count);
+                #(#field_stmts)*
+                fields
+            }
+        }
+
+        impl #impl_generics ::bigquery_storage_rs::write::ColumnType for #ident #ty_generics
+        #where_clause
+        {
+            const TYPE: ::bigquery_storage_rs::__private::FieldType =
+                ::bigquery_storage_rs::__private::FieldType::Record;
+
+            fn fields() -> ::std::option::Option<::std::vec::Vec<
+                ::bigquery_storage_rs::__private::TableFieldSchema<::std::boxed::Box<str>>
+            >> {
+                ::std::option::Option::Some(
+                    <Self as ::bigquery_storage_rs::write::RowSchema>::fields()
+                )
+            }
+        }
+    })
+}
+
+fn derive_unit_enum(input: &DeriveInput, data: &syn::DataEnum) -> syn::Result<TokenStream2> {
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return Err(syn::Error::new(
+            variant.span(),
+            "TableSchema can only be derived for enums with unit variants",
+        ));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::bigquery_storage_rs::write::ColumnType for #ident #ty_generics
+        #where_clause
+        {
+            const TYPE: ::bigquery_storage_rs::__private::FieldType =
+                ::bigquery_storage_rs::__private::FieldType::String;
+        }
+    })
+}
+
+/// The subset of serde's field attributes that change the serialized shape.
+#[derive(Default)]
+struct SerdeFieldAttrs {
+    rename: Option<String>,
+    skip: bool,
+    flatten: bool,
+}
+
+impl SerdeFieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+
+        for nested in attr_args(attrs, "serde")? {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("skip") || path.is_ident("skip_serializing") =>
+                {
+                    parsed.skip = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("flatten") => {
+                    parsed.flatten = true
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    parsed.rename = Some(lit_str(&nv.lit)?);
+                }
+                // #[serde(rename(serialize = "..", deserialize = ".."))]
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("rename") => {
+                    for nested in list.nested {
+                        match nested {
+                            NestedMeta::Meta(Meta::NameValue(nv))
+                                if nv.path.is_ident("serialize") =>
+                            {
+                                parsed.rename = Some(lit_str(&nv.lit)?);
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[derive(Default)]
+struct BigQueryFieldAttrs {
+    ty: Option<syn::Ident>,
+    range: Option<syn::Ident>,
+    description: Option<String>,
+}
+
+impl BigQueryFieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+
+        for nested in attr_args(attrs, "bigquery")? {
+            let nv = match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) => nv,
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "expected `ty = \"..\"`, `range = \"..\"` or `description = \"..\"`",
+                    ));
+                }
+            };
+
+            let value = lit_str(&nv.lit)?;
+
+            if nv.path.is_ident("ty") {
+                let variant = field_type_variant(&value).ok_or_else(|| {
+                    syn::Error::new(nv.lit.span(), format!("unknown BigQuery type '{value}'"))
+                })?;
+                parsed.ty = Some(syn::Ident::new(variant, nv.lit.span()));
+            } else if nv.path.is_ident("range") {
+                let variant = range_element_variant(&value).ok_or_else(|| {
+                    syn::Error::new(
+                        nv.lit.span(),
+                        "RANGE element types must be one of DATE, DATETIME or TIMESTAMP",
+                    )
+                })?;
+                parsed.range = Some(syn::Ident::new(variant, nv.lit.span()));
+            } else if nv.path.is_ident("description") {
+                parsed.description = Some(value);
+            } else {
+                return Err(syn::Error::new(
+                    nv.path.span(),
+                    "unknown bigquery attribute",
+                ));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn into_tokens(self) -> TokenStream2 {
+        let ty = self.ty.map(|variant| {
+            quote! {
+                field.ty = ::bigquery_storage_rs::__private::FieldType::#variant;
+            }
+        });
+
+        let range = self.range.map(|variant| {
+            quote! {
+                field.ty = ::bigquery_storage_rs::__private::FieldType::Range;
+                field.range_element_type = ::std::option::Option::Some(
+                    ::bigquery_storage_rs::__private::RangeElementType::#variant
+                );
+            }
+        });
+
+        let description = self.description.map(|description| {
+            quote! {
+                field.description = ::std::option::Option::Some(#description.into());
+            }
+        });
+
+        quote!(#ty #range #description)
+    }
+}
+
+fn attr_args(attrs: &[syn::Attribute], name: &str) -> syn::Result<Vec<NestedMeta>> {
+    let mut args = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => args.extend(list.nested),
+            other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    format!("expected #[{name}(..)]"),
+                ));
+            }
+        }
+    }
+
+    Ok(args)
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        other => Err(syn::Error::new(other.span(), "expected a string literal")),
+    }
+}
+
+fn field_type_variant(ty: &str) -> Option<&'static str> {
+    Some(match ty.to_ascii_uppercase().as_str() {
+        "STRING" => "String",
+        "BYTES" => "Bytes",
+        "INTEGER" | "INT64" => "Integer",
+        "FLOAT" | "FLOAT64" => "Float",
+        "BOOL" | "BOOLEAN" => "Bool",
+        "TIMESTAMP" => "Timestamp",
+        "DATE" => "Date",
+        "TIME" => "Time",
+        "DATETIME" => "DateTime",
+        "GEOGRAPHY" => "Geography",
+        "NUMERIC" => "Numeric",
+        "BIGNUMERIC" => "BigNumeric",
+        "JSON" => "Json",
+        "RECORD" | "STRUCT" => "Record",
+        "INTERVAL" => "Interval",
+        // RANGE needs an element type, so it has its own attribute
+        _ => return None,
+    })
+}
+
+fn range_element_variant(ty: &str) -> Option<&'static str> {
+    Some(match ty.to_ascii_uppercase().as_str() {
+        "DATE" => "Date",
+        "DATETIME" => "DateTime",
+        "TIMESTAMP" => "Timestamp",
+        _ => return None,
+    })
+}
+
+/// Mirrors serde's `rename_all` rules, which assume snake_case field names.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    fn apply(self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_owned(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+            Self::Pascal | Self::Camel => {
+                let mut renamed = String::with_capacity(field.len());
+                let mut capitalize = matches!(self, Self::Pascal);
+
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        renamed.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        renamed.push(ch);
+                    }
+                }
+
+                renamed
+            }
+        }
+    }
+}
+
+fn serde_rename_all(attrs: &[syn::Attribute]) -> syn::Result<Option<RenameRule>> {
+    for nested in attr_args(attrs, "serde")? {
+        let lit = match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => nv.lit,
+            // #[serde(rename_all(serialize = "..", deserialize = ".."))]
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("rename_all") => {
+                let serialize = list.nested.into_iter().find_map(|nested| match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("serialize") => {
+                        Some(nv.lit)
+                    }
+                    _ => None,
+                });
+
+                match serialize {
+                    Some(lit) => lit,
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        let rule = lit_str(&lit)?;
+        return RenameRule::from_str(&rule)
+            .map(Some)
+            .ok_or_else(|| syn::Error::new(lit.span(), format!("unknown rename rule '{rule}'")));
+    }
+
+    Ok(None)
+}
@@ -28,6 +28,12 @@ pub enum Error {
     Encode(#[from] EncodeError),
     #[error(transparent)]
     Commit(#[from] CommitError),
+    #[error(transparent)]
+    Finalize(#[from] FinalizeError),
+    #[error(
+        "batch commits must be for a single table, but got streams for '{first}' and '{other}'"
+    )]
+    MixedTables { first: String, other: String },
 }
 
 impl From<ChannelError> for Error {
@@ -122,6 +128,13 @@ impl From<apache_avro::Error> for Error {
     }
 }
 
+#[cfg(feature = "arrow")]
+impl From<arrow_schema::ArrowError> for Error {
+    fn from(value: arrow_schema::ArrowError) -> Self {
+        Self::Deserialize(DeserializeError::from(value))
+    }
+}
+
 impl<E> From<E> for Error
 where
     InternalError: From<E>,
@@ -137,8 +150,8 @@ pub enum InternalError {
     Task(#[from] JoinError),
     #[error(transparent)]
     InvalidHeader(#[from] http::header::InvalidHeaderValue),
-    #[error("reading via arrow is not yet supported")]
-    ArrowNotSupported,
+    #[error("the read stream returned rows in a different format than the session requested")]
+    UnexpectedDataFormat,
     #[error("no schema was returned to deserialize from")]
     NoSchemaReturned,
     #[error("more append row responses were recieved than expected")]
@@ -248,4 +261,62 @@ impl CommitError {
             Self::One(_) => 1,
         }
     }
+
+    pub fn errors(&self) -> &[StorageError] {
+        match self {
+            Self::Many(many) => many,
+            Self::One(one) => std::slice::from_ref(one),
+        }
+    }
+
+    /// Finds the error for a specific write stream, by its fully qualified name.
+    pub fn stream_error(&self, write_stream: &str) -> Option<&StorageError> {
+        self.errors().iter().find(|err| err.entity == write_stream)
+    }
+}
+
+/// The streams that failed to finalize during a [`PendingBatch::commit`], along with their
+/// errors. Nothing is committed if any stream fails to finalize.
+///
+/// [`PendingBatch::commit`]: crate::write::PendingBatch::commit
+#[derive(Debug)]
+pub struct FinalizeError {
+    errors: Vec<(String, Error)>,
+}
+
+impl fmt::Display for FinalizeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} stream(s) failed to finalize",
+            self.errors.len()
+        )?;
+
+        for (stream, error) in self.errors.iter() {
+            write!(formatter, "\n- {stream}: {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for FinalizeError {}
+
+impl FinalizeError {
+    pub(crate) fn new(errors: Vec<(String, Error)>) -> Self {
+        Self { errors }
+    }
+
+    /// The fully qualified stream names and errors of every stream that failed to finalize.
+    pub fn errors(&self) -> &[(String, Error)] {
+        &self.errors
+    }
+
+    /// Finds the error for a specific write stream, by its fully qualified name.
+    pub fn stream_error(&self, write_stream: &str) -> Option<&Error> {
+        self.errors
+            .iter()
+            .find(|(stream, _)| stream == write_stream)
+            .map(|(_, error)| error)
+    }
 }
@@ -9,9 +9,19 @@ pub mod read;
 #[cfg(feature = "write")]
 pub mod write;
 
+// lets the code generated by `#[derive(TableSchema)]` resolve in this crate's own tests.
+#[cfg(all(test, feature = "write"))]
+extern crate self as bigquery_storage_rs;
+
+#[doc(hidden)] // for `bigquery-storage-rs-macros`
+#[cfg(feature = "write")]
+pub mod __private {
+    pub use bigquery_resources_rs::table::{FieldType, RangeElementType, TableFieldSchema};
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
 
-pub use error::Error;
+pub use error::{CommitError, Error, FinalizeError};
 use gcp_auth_provider::service::AuthSvc;
 use gcp_auth_provider::{Auth, Scopes};
 use tonic::transport::{Channel, ClientTlsConfig};
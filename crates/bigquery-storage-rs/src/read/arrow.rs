@@ -0,0 +1,220 @@
+//! Read sessions that yield Arrow [`RecordBatch`]es, created via
+//! [`ReadSessionBuilder::create_arrow`].
+//!
+//! [`ReadSessionBuilder::create_arrow`]: super::ReadSessionBuilder::create_arrow
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Instant;
+
+use arrow_array::RecordBatch;
+use arrow_buffer::Buffer;
+use arrow_ipc::reader::StreamDecoder;
+use arrow_schema::SchemaRef;
+use bytes::Bytes;
+use futures::stream::{FuturesUnordered, SelectAll, Stream, StreamExt};
+use protos::bigquery_storage::big_query_read_client::BigQueryReadClient;
+use protos::bigquery_storage::read_rows_response::Rows;
+use protos::bigquery_storage::{self, ReadRowsRequest, ReadRowsResponse};
+use serde::de::Deserialize;
+
+use super::arrow_de::RecordBatchDeserializer;
+use super::stream::HumanReadableBytes;
+use crate::{BigQueryStorageClient, Error};
+
+#[derive(Debug)]
+pub struct ArrowReadSession {
+    pub(super) read_session: bigquery_storage::ReadSession,
+    pub(super) schema: SchemaRef,
+    /// The IPC encoded schema, which each stream needs to decode its record batches.
+    pub(super) serialized_schema: Bytes,
+    pub(super) client: BigQueryStorageClient,
+}
+
+impl ArrowReadSession {
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    pub async fn take_stream(&mut self) -> Result<Option<ArrowReadStream>, Error> {
+        let read_stream = match self.read_session.streams.pop() {
+            Some(rs) => rs,
+            None => return Ok(None),
+        };
+
+        let request = ReadRowsRequest {
+            read_stream: read_stream.name,
+            offset: 0,
+        };
+
+        let mut channel = self.client.channel.clone();
+
+        let row_stream = BigQueryReadClient::new(&mut channel)
+            .read_rows(request)
+            .await?
+            .into_inner();
+
+        ArrowReadStream::new(
+            self.read_session.streams.len(),
+            row_stream,
+            self.serialized_schema.clone(),
+        )
+        .map(Some)
+    }
+
+    pub fn into_read_streams(
+        self,
+    ) -> FuturesUnordered<impl Future<Output = Result<ArrowReadStream, Error>>> {
+        let streams = FuturesUnordered::new();
+
+        for (id, stream) in self.read_session.streams.into_iter().enumerate() {
+            let mut channel = self.client.channel.clone();
+            let serialized_schema = self.serialized_schema.clone();
+
+            streams.push(async move {
+                let request = ReadRowsRequest {
+                    read_stream: stream.name,
+                    offset: 0,
+                };
+
+                let row_stream = BigQueryReadClient::new(&mut channel)
+                    .read_rows(request)
+                    .await?
+                    .into_inner();
+
+                ArrowReadStream::new(id, row_stream, serialized_schema)
+            });
+        }
+
+        streams
+    }
+
+    /// Opens every stream in the session, yielding batches from whichever is ready first.
+    pub async fn stream_all(self) -> Result<SelectAll<ArrowReadStream>, Error> {
+        let mut streams = Vec::with_capacity(self.read_session.streams.len());
+
+        let mut read_streams = self.into_read_streams();
+
+        while let Some(read_stream_result) = read_streams.next().await {
+            streams.push(read_stream_result?);
+        }
+
+        Ok(futures::stream::select_all(streams))
+    }
+}
+
+pin_project_lite::pin_project! {
+    pub struct ArrowReadStream {
+        stream_id: usize,
+        #[pin]
+        stream: tonic::Streaming<ReadRowsResponse>,
+        decoder: StreamDecoder,
+        rows_read: usize,
+        bytes_read: usize,
+        last_yielded: Option<std::time::Instant>,
+    }
+}
+
+impl std::fmt::Debug for ArrowReadStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArrowReadStream")
+            .field("stream_id", &self.stream_id)
+            .field("rows_read", &self.rows_read)
+            .field("bytes_read", &self.bytes_read)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ArrowReadStream {
+    pub(crate) fn new(
+        stream_id: usize,
+        stream: tonic::Streaming<ReadRowsResponse>,
+        serialized_schema: Bytes,
+    ) -> Result<Self, Error> {
+        let mut decoder = StreamDecoder::new();
+
+        // the schema message only primes the decoder, it never yields a batch.
+        let mut schema_buf = Buffer::from(serialized_schema);
+        while !schema_buf.is_empty() {
+            decoder.decode(&mut schema_buf)?;
+        }
+
+        Ok(Self {
+            stream_id,
+            stream,
+            decoder,
+            rows_read: 0,
+            bytes_read: 0,
+            last_yielded: None,
+        })
+    }
+
+    pub async fn next_batch(&mut self) -> Result<Option<RecordBatch>, Error> {
+        self.next().await.transpose()
+    }
+
+    /// Deserializes the rows in the next batch.
+    pub async fn next_rows<O>(&mut self) -> Result<Option<Vec<O>>, Error>
+    where
+        for<'de> O: Deserialize<'de>,
+    {
+        match self.next_batch().await? {
+            Some(batch) => RecordBatchDeserializer::new(batch)
+                .consume()
+                .map(Some)
+                .map_err(Error::Deserialize),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Stream for ArrowReadStream {
+    type Item = Result<RecordBatch, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let resp = match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(resp)) => resp,
+                Some(Err(err)) => return Poll::Ready(Some(Err(Error::from(err)))),
+                None => return Poll::Ready(None),
+            };
+
+            let rows = match resp.rows {
+                Some(Rows::ArrowRecordBatch(batch)) if resp.row_count > 0 => batch,
+                Some(Rows::AvroRows(_)) => {
+                    return Poll::Ready(Some(Err(Error::Internal(
+                        crate::error::InternalError::UnexpectedDataFormat,
+                    ))));
+                }
+                // empty responses only carry stats/throttling info, so wait for the next one
+                _ => continue,
+            };
+
+            *this.rows_read += resp.row_count as usize;
+            *this.bytes_read += rows.serialized_record_batch.len();
+
+            if let Some(last_yielded) = this.last_yielded.replace(Instant::now()) {
+                let dur = last_yielded.elapsed();
+
+                tracing::debug!(
+                    stream_id = this.stream_id,
+                    last_yielded_nanos = dur.as_nanos() as usize,
+                    bytes_read = tracing::field::display(HumanReadableBytes(*this.bytes_read)),
+                    rows_read = this.rows_read,
+                );
+            }
+
+            // converting from 'Bytes' doesn't copy, so the batch columns point directly into
+            // the response buffer.
+            let mut buf = Buffer::from(rows.serialized_record_batch);
+
+            while !buf.is_empty() {
+                if let Some(batch) = this.decoder.decode(&mut buf)? {
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+            }
+        }
+    }
+}
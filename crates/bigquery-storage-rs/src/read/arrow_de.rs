@@ -0,0 +1,704 @@
+//! Deserializes rows out of Arrow [`RecordBatch`]es, mirroring what [`avro_de`] does for
+//! Avro sessions.
+//!
+//! Each row is deserialized as a map of column name to value, so structs, maps and tuples
+//! (in column order) all work as row types. Null values follow [`Option`] semantics, and
+//! timestamps deserialize the same way as they do from Avro.
+//!
+//! [`avro_de`]: super::avro_de
+use std::marker::PhantomData;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Date32Type, Decimal128Type, Decimal256Type, Float32Type, Float64Type, Int8Type, Int16Type,
+    Int32Type, Int64Type, Time64MicrosecondType, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt8Type, UInt16Type,
+    UInt32Type, UInt64Type,
+};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, FieldRef, TimeUnit};
+use serde::de::{self, Error, IntoDeserializer};
+use serde::forward_to_deserialize_any;
+
+use super::DeserializeError;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone)]
+pub struct RecordBatchDeserializer {
+    batch: RecordBatch,
+    row: usize,
+}
+
+pub struct RowIter<S>
+where
+    for<'de> S: de::DeserializeSeed<'de> + Clone,
+{
+    de: RecordBatchDeserializer,
+    seed: S,
+}
+
+impl<S, O> Iterator for RowIter<S>
+where
+    for<'de> S: de::DeserializeSeed<'de, Value = O> + Clone,
+{
+    type Item = Result<O, DeserializeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.de.deserialize_value(self.seed.clone()).transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<S, O> ExactSizeIterator for RowIter<S>
+where
+    for<'de> S: de::DeserializeSeed<'de, Value = O> + Clone,
+{
+    fn len(&self) -> usize {
+        self.de.remaining()
+    }
+}
+
+impl RecordBatchDeserializer {
+    pub fn new(batch: RecordBatch) -> Self {
+        Self { batch, row: 0 }
+    }
+
+    pub fn consume<O>(self) -> Result<Vec<O>, DeserializeError>
+    where
+        for<'de> O: de::Deserialize<'de>,
+    {
+        self.consume_with_seed(&PhantomData)
+    }
+
+    pub fn consume_with_seed<'de, S>(
+        mut self,
+        seed: &S,
+    ) -> Result<Vec<<S as de::DeserializeSeed<'de>>::Value>, DeserializeError>
+    where
+        S: de::DeserializeSeed<'de> + Clone,
+    {
+        let mut batch = Vec::with_capacity(self.remaining());
+
+        while let Some(row) = self.deserialize_value(seed.clone())? {
+            batch.push(row);
+        }
+
+        Ok(batch)
+    }
+
+    fn remaining(&self) -> usize {
+        self.batch.num_rows() - self.row
+    }
+
+    pub(super) fn deserialize_value<'de, S>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, DeserializeError>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        if self.remaining() == 0 {
+            return Ok(None);
+        }
+
+        let de = StructDeserializer::new(
+            self.batch.schema_ref().fields(),
+            self.batch.columns(),
+            self.row,
+        );
+        self.row += 1;
+
+        let wrapped_de = path_aware_serde::Deserializer::new(de);
+
+        let item = seed.deserialize(wrapped_de)?;
+
+        Ok(Some(item))
+    }
+
+    pub fn row_iter_with_seed<S>(self, seed: S) -> RowIter<S>
+    where
+        for<'de> S: de::DeserializeSeed<'de> + Clone,
+    {
+        RowIter { de: self, seed }
+    }
+
+    pub fn row_iter<O>(self) -> RowIter<PhantomData<O>>
+    where
+        for<'de> O: de::Deserialize<'de>,
+    {
+        RowIter {
+            de: self,
+            seed: PhantomData,
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.batch.num_rows()
+    }
+
+    /// Returns the underlying batch, i.e to hand off to another arrow based library.
+    pub fn into_record_batch(self) -> RecordBatch {
+        self.batch
+    }
+}
+
+impl<'de> de::Deserializer<'de> for RecordBatchDeserializer {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for RecordBatchDeserializer {
+    type Error = DeserializeError;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining())
+    }
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.deserialize_value(seed)
+    }
+}
+
+/// Deserializes a single row out of a set of columns, either from the top level batch or a
+/// struct column.
+struct StructDeserializer<'a> {
+    fields: &'a [FieldRef],
+    columns: &'a [ArrayRef],
+    row: usize,
+}
+
+impl<'a> StructDeserializer<'a> {
+    fn new(fields: &'a [FieldRef], columns: &'a [ArrayRef], row: usize) -> Self {
+        Self {
+            fields,
+            columns,
+            row,
+        }
+    }
+}
+
+impl<'a, 'de> de::Deserializer<'de> for StructDeserializer<'a> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_map(MapAccess {
+            fields: self.fields.iter(),
+            columns: self.columns.iter(),
+            row: self.row,
+            next_value: None,
+        })
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(ColumnAccess {
+            columns: self.columns.iter(),
+            row: self.row,
+        })
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct enum identifier
+    }
+}
+
+struct MapAccess<'a> {
+    fields: std::slice::Iter<'a, FieldRef>,
+    columns: std::slice::Iter<'a, ArrayRef>,
+    row: usize,
+    next_value: Option<&'a dyn Array>,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for MapAccess<'a> {
+    type Error = DeserializeError;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let (field, column) = match (self.fields.next(), self.columns.next()) {
+            (Some(field), Some(column)) => (field, column),
+            _ => return Ok(None),
+        };
+
+        if self.next_value.replace(column.as_ref()).is_some() {
+            tracing::error!("value skipped in MapAccess");
+        }
+
+        seed.deserialize(field.name().as_str().into_deserializer())
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let array = self
+            .next_value
+            .take()
+            .expect("next_value_seed has no value to yield");
+
+        seed.deserialize(ValueDeserializer::new(array, self.row))
+    }
+}
+
+/// Yields the values in a single row, in column order.
+struct ColumnAccess<'a> {
+    columns: std::slice::Iter<'a, ArrayRef>,
+    row: usize,
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for ColumnAccess<'a> {
+    type Error = DeserializeError;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len())
+    }
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.columns.next() {
+            Some(column) => seed
+                .deserialize(ValueDeserializer::new(column.as_ref(), self.row))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Yields the values of a single list (a BigQuery 'REPEATED' field).
+struct ListAccess {
+    values: ArrayRef,
+    index: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for ListAccess {
+    type Error = DeserializeError;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len() - self.index)
+    }
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.index >= self.values.len() {
+            return Ok(None);
+        }
+
+        let de = ValueDeserializer::new(self.values.as_ref(), self.index);
+        self.index += 1;
+
+        seed.deserialize(de).map(Some)
+    }
+}
+
+/// Deserializes the value at 'row' in a single column.
+struct ValueDeserializer<'a> {
+    array: &'a dyn Array,
+    row: usize,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn new(array: &'a dyn Array, row: usize) -> Self {
+        Self { array, row }
+    }
+
+    fn is_null(&self) -> bool {
+        self.array.is_null(self.row)
+    }
+
+    fn timestamp(&self, unit: TimeUnit) -> Result<timestamp::Timestamp, DeserializeError> {
+        let row = self.row;
+
+        let ts = match unit {
+            TimeUnit::Second => {
+                let secs = self.array.as_primitive::<TimestampSecondType>().value(row);
+                let micros = secs
+                    .checked_mul(1_000_000)
+                    .ok_or_else(|| DeserializeError::custom("timestamp out of range"))?;
+                timestamp::Timestamp::from_micros_checked(micros)?
+            }
+            TimeUnit::Millisecond => timestamp::Timestamp::from_millis_checked(
+                self.array
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value(row),
+            )?,
+            TimeUnit::Microsecond => timestamp::Timestamp::from_micros_checked(
+                self.array
+                    .as_primitive::<TimestampMicrosecondType>()
+                    .value(row),
+            )?,
+            TimeUnit::Nanosecond => timestamp::Timestamp::from_nanos(
+                self.array
+                    .as_primitive::<TimestampNanosecondType>()
+                    .value(row),
+            ),
+        };
+
+        Ok(ts)
+    }
+}
+
+impl<'a, 'de> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.is_null() {
+            return visitor.visit_unit();
+        }
+
+        let array = self.array;
+        let row = self.row;
+
+        match array.data_type() {
+            DataType::Null => visitor.visit_unit(),
+            DataType::Boolean => visitor.visit_bool(array.as_boolean().value(row)),
+            DataType::Int8 => visitor.visit_i8(array.as_primitive::<Int8Type>().value(row)),
+            DataType::Int16 => visitor.visit_i16(array.as_primitive::<Int16Type>().value(row)),
+            DataType::Int32 => visitor.visit_i32(array.as_primitive::<Int32Type>().value(row)),
+            DataType::Int64 => visitor.visit_i64(array.as_primitive::<Int64Type>().value(row)),
+            DataType::UInt8 => visitor.visit_u8(array.as_primitive::<UInt8Type>().value(row)),
+            DataType::UInt16 => visitor.visit_u16(array.as_primitive::<UInt16Type>().value(row)),
+            DataType::UInt32 => visitor.visit_u32(array.as_primitive::<UInt32Type>().value(row)),
+            DataType::UInt64 => visitor.visit_u64(array.as_primitive::<UInt64Type>().value(row)),
+            DataType::Float32 => visitor.visit_f32(array.as_primitive::<Float32Type>().value(row)),
+            DataType::Float64 => visitor.visit_f64(array.as_primitive::<Float64Type>().value(row)),
+            DataType::Utf8 => visitor.visit_str(array.as_string::<i32>().value(row)),
+            DataType::LargeUtf8 => visitor.visit_str(array.as_string::<i64>().value(row)),
+            DataType::Binary => visitor.visit_bytes(array.as_binary::<i32>().value(row)),
+            DataType::LargeBinary => visitor.visit_bytes(array.as_binary::<i64>().value(row)),
+            // BigQuery 'DATE' columns, deserialized from the same 'YYYY-MM-DD' string
+            // that 'timestamp::Date' expects.
+            DataType::Date32 => {
+                let days = array.as_primitive::<Date32Type>().value(row) as i64;
+                let date = timestamp::Timestamp::from_seconds(days * SECONDS_PER_DAY).date();
+                visitor.visit_string(date.to_string())
+            }
+            // BigQuery 'TIME' columns, as microseconds since midnight.
+            DataType::Time64(TimeUnit::Microsecond) => {
+                let micros = array.as_primitive::<Time64MicrosecondType>().value(row);
+                let time = timestamp::Timestamp::from_micros(micros).time();
+                visitor.visit_string(time.to_string())
+            }
+            DataType::Timestamp(unit, _) => self
+                .timestamp(*unit)?
+                .into_deserializer()
+                .deserialize_any(visitor)
+                .map_err(DeserializeError::from),
+            // 'NUMERIC' and 'BIGNUMERIC' columns, which don't fit in any native numeric type
+            DataType::Decimal128(_, _) => {
+                visitor.visit_string(array.as_primitive::<Decimal128Type>().value_as_string(row))
+            }
+            DataType::Decimal256(_, _) => {
+                visitor.visit_string(array.as_primitive::<Decimal256Type>().value_as_string(row))
+            }
+            DataType::List(_) => visitor.visit_seq(ListAccess {
+                values: array.as_list::<i32>().value(row),
+                index: 0,
+            }),
+            DataType::LargeList(_) => visitor.visit_seq(ListAccess {
+                values: array.as_list::<i64>().value(row),
+                index: 0,
+            }),
+            DataType::Struct(_) => {
+                let array = array.as_struct();
+                StructDeserializer::new(array.fields(), array.columns(), row)
+                    .deserialize_any(visitor)
+            }
+            other => Err(DeserializeError::custom(format!(
+                "unsupported arrow data type: {other}"
+            ))),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.is_null() {
+            visitor.visit_unit()
+        } else {
+            Err(de::Error::invalid_type(
+                de::Unexpected::Other("non-null value"),
+                &"null",
+            ))
+        }
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.array.as_struct_opt() {
+            Some(array) if !self.is_null() => {
+                StructDeserializer::new(array.fields(), array.columns(), self.row)
+                    .deserialize_any(visitor)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // unit variants stored as strings are the only kind of enum BigQuery can represent
+        match self.array.data_type() {
+            DataType::Utf8 if !self.is_null() => {
+                let variant = self.array.as_string::<i32>().value(self.row);
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            DataType::LargeUtf8 if !self.is_null() => {
+                let variant = self.array.as_string::<i64>().value(self.row);
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf seq tuple tuple_struct map identifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::builder::{ListBuilder, StringBuilder};
+    use arrow_array::{Int64Array, StringArray, StructArray, TimestampMicrosecondArray};
+    use arrow_schema::{Field, Schema};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Location {
+        lat: f64,
+        lon: f64,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row {
+        id: i64,
+        name: Option<String>,
+        tags: Vec<String>,
+        created: timestamp::Timestamp,
+        location: Location,
+    }
+
+    #[test]
+    fn test_deserialize_record_batch() {
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        tags.values().append_value("a");
+        tags.values().append_value("b");
+        tags.append(true);
+        tags.append(true);
+        let tags = tags.finish();
+
+        let location = StructArray::from(vec![
+            (
+                Arc::new(Field::new("lat", DataType::Float64, false)),
+                Arc::new(arrow_array::Float64Array::from(vec![1.5, -2.0])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("lon", DataType::Float64, false)),
+                Arc::new(arrow_array::Float64Array::from(vec![3.0, 4.25])) as ArrayRef,
+            ),
+        ]);
+
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("tags", tags.data_type().clone(), false),
+            Field::new(
+                "created",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                false,
+            ),
+            Field::new("location", location.data_type().clone(), false),
+        ]);
+
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("first"), None])),
+                Arc::new(tags),
+                Arc::new(
+                    TimestampMicrosecondArray::from(vec![1_000_000, 2_500_000])
+                        .with_timezone("UTC"),
+                ),
+                Arc::new(location),
+            ],
+        )
+        .unwrap();
+
+        let de = RecordBatchDeserializer::new(batch);
+        assert_eq!(de.row_count(), 2);
+
+        let rows = de.consume::<Row>().unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                Row {
+                    id: 1,
+                    name: Some("first".to_owned()),
+                    tags: vec!["a".to_owned(), "b".to_owned()],
+                    created: timestamp::Timestamp::from_micros(1_000_000),
+                    location: Location { lat: 1.5, lon: 3.0 },
+                },
+                Row {
+                    id: 2,
+                    name: None,
+                    tags: vec![],
+                    created: timestamp::Timestamp::from_micros(2_500_000),
+                    location: Location {
+                        lat: -2.0,
+                        lon: 4.25
+                    },
+                },
+            ]
+        );
+    }
+}
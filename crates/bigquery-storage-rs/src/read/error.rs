@@ -12,6 +12,9 @@ enum ErrorInner {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Avro(#[from] apache_avro::Error),
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
     #[error(transparent)]
     InvalidTimestamp(#[from] timestamp::Error),
     #[error("{0}")]
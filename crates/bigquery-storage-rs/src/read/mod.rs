@@ -5,6 +5,8 @@ use std::sync::{Arc, LazyLock};
 use gcp_auth_provider::Scope;
 use http::HeaderValue;
 
+#[cfg(feature = "arrow")]
+pub mod arrow_de;
 pub mod avro_de;
 mod error;
 
@@ -18,9 +20,13 @@ use serde::de::{Deserialize, DeserializeSeed};
 
 use super::{BigQueryStorageClient, Error};
 
+#[cfg(feature = "arrow")]
+mod arrow;
 mod session;
 mod stream;
 
+#[cfg(feature = "arrow")]
+pub use arrow::{ArrowReadSession, ArrowReadStream};
 pub use session::ReadSession;
 pub use stream::ReadStream;
 
@@ -155,6 +161,69 @@ where
     where
         for<'de> S: DeserializeSeed<'de> + Clone,
     {
+        let (client, mut read_session) = self.create_read_session(DataFormat::Avro).await?;
+
+        let schema = match read_session.schema.take() {
+            Some(read_session::Schema::AvroSchema(avro_schema)) => avro_schema,
+            Some(read_session::Schema::ArrowSchema(_)) => {
+                return Err(Error::Internal(
+                    crate::error::InternalError::UnexpectedDataFormat,
+                ));
+            }
+            None => {
+                return Err(Error::Internal(
+                    crate::error::InternalError::NoSchemaReturned,
+                ));
+            }
+        };
+
+        let schema = Schema::parse_str(&schema.schema)?;
+
+        Ok(ReadSession {
+            read_session,
+            schema: Arc::new(schema),
+            client,
+            seed,
+        })
+    }
+
+    /// Creates a session that reads rows as Arrow [`RecordBatch`]es, rather than
+    /// deserializing them out of Avro. Batches can be handed off as-is to other arrow based
+    /// libraries, or deserialized with [`arrow_de::RecordBatchDeserializer`].
+    ///
+    /// [`RecordBatch`]: arrow_array::RecordBatch
+    #[cfg(feature = "arrow")]
+    pub async fn create_arrow(self) -> Result<ArrowReadSession, Error> {
+        let (client, mut read_session) = self.create_read_session(DataFormat::Arrow).await?;
+
+        let serialized_schema = match read_session.schema.take() {
+            Some(read_session::Schema::ArrowSchema(arrow_schema)) => arrow_schema.serialized_schema,
+            Some(read_session::Schema::AvroSchema(_)) => {
+                return Err(Error::Internal(
+                    crate::error::InternalError::UnexpectedDataFormat,
+                ));
+            }
+            None => {
+                return Err(Error::Internal(
+                    crate::error::InternalError::NoSchemaReturned,
+                ));
+            }
+        };
+
+        let schema = arrow_ipc::convert::try_schema_from_ipc_buffer(&serialized_schema)?;
+
+        Ok(ArrowReadSession {
+            read_session,
+            schema: Arc::new(schema),
+            serialized_schema,
+            client,
+        })
+    }
+
+    async fn create_read_session(
+        self,
+        data_format: DataFormat,
+    ) -> Result<(BigQueryStorageClient, bigquery_storage::ReadSession), Error> {
         let table_info = self
             .client
             .build_table_info(&self.dataset_id, &self.table_id);
@@ -178,7 +247,7 @@ where
             preferred_min_stream_count: *NUM_CPUS as i32,
             max_stream_count: self.max_stream_count.into(),
             read_session: Some(bigquery_storage::ReadSession {
-                data_format: DataFormat::Avro as i32,
+                data_format: data_format as i32,
                 table: table_info.table,
                 read_options,
                 trace_id: self.trace_id.to_string(),
@@ -196,7 +265,7 @@ where
 
         let mut client = BigQueryReadClient::new(&mut channel);
 
-        let mut read_session = client
+        let read_session = client
             .create_read_session(create_request)
             .await?
             .into_inner();
@@ -207,29 +276,9 @@ where
             table = read_session.table.as_str(),
             trace = read_session.trace_id.as_str(),
             stream_count = read_session.streams.len(),
+            data_format = data_format.as_str_name(),
         );
 
-        let schema = match read_session.schema.take() {
-            Some(read_session::Schema::AvroSchema(avro_schema)) => avro_schema,
-            Some(read_session::Schema::ArrowSchema(_)) => {
-                return Err(Error::Internal(
-                    crate::error::InternalError::ArrowNotSupported,
-                ));
-            }
-            None => {
-                return Err(Error::Internal(
-                    crate::error::InternalError::NoSchemaReturned,
-                ));
-            }
-        };
-
-        let schema = Schema::parse_str(&schema.schema)?;
-
-        Ok(ReadSession {
-            read_session,
-            schema: Arc::new(schema),
-            client: self.client,
-            seed,
-        })
+        Ok((self.client, read_session))
     }
 }
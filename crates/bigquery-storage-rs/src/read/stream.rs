@@ -60,7 +60,7 @@ where
             Some(Rows::AvroRows(avro_rows)) if row_count > 0 => avro_rows,
             Some(_) => {
                 return Err(Error::Internal(
-                    crate::error::InternalError::ArrowNotSupported,
+                    crate::error::InternalError::UnexpectedDataFormat,
                 ));
             }
             _ => return Ok(None),
@@ -105,7 +105,7 @@ where
             Some(Rows::AvroRows(avro_rows)) if row_count > 0 => avro_rows,
             Some(_) => {
                 return Poll::Ready(Some(Err(Error::Internal(
-                    crate::error::InternalError::ArrowNotSupported,
+                    crate::error::InternalError::UnexpectedDataFormat,
                 ))));
             }
             _ => return Poll::Pending,
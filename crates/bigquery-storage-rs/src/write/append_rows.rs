@@ -259,8 +259,8 @@ impl DriverFutureProjection<'_> {
                 protos::bigquery_storage::append_rows_response::Response::AppendResult(result) => {
                     if let Some(offset) = result.offset {
                         self.write_stream_inner
-                            .offset
-                            .fetch_max(offset.value as usize, std::sync::atomic::Ordering::SeqCst);
+                            .offsets
+                            .set_append_offset(offset.value);
                     }
                     _ = sender.send(Ok(()));
                 }
@@ -0,0 +1,225 @@
+//! Atomic, all-or-nothing writes across multiple [`Pending`] streams.
+//!
+//! Rows appended to a pending stream aren't visible until the stream is finalized and
+//! committed. [`PendingBatch`] groups up sessions writing to the same table, then finalizes
+//! and commits them with a single BatchCommitWriteStreams call, so either every row shows up
+//! or none do.
+use futures::future::join_all;
+
+use super::stream_types::{Pending, stream_table};
+use super::{Error, WriteSession};
+use crate::error::FinalizeError;
+
+/// A set of [`Pending`] write sessions that are committed together. BigQuery only allows
+/// batch commits within a single table, so every session needs to write to the same one.
+#[derive(Debug)]
+pub struct PendingBatch<R> {
+    sessions: Vec<WriteSession<Pending, R>>,
+}
+
+/// The result of a successful [`PendingBatch::commit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchCommit {
+    /// The total number of rows committed, across all streams.
+    pub rows: usize,
+    /// The time the rows became visible. Only [`None`] if there was nothing to commit.
+    pub commit_time: Option<timestamp::Timestamp>,
+}
+
+impl<R> Default for PendingBatch<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> PendingBatch<R> {
+    pub const fn new() -> Self {
+        Self {
+            sessions: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, session: WriteSession<Pending, R>) {
+        self.sessions.push(session);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn sessions(&self) -> &[WriteSession<Pending, R>] {
+        &self.sessions
+    }
+
+    /// Finalizes every stream, then commits them all at once.
+    ///
+    /// If any stream fails to finalize, nothing is committed, and every stream that failed is
+    /// returned as an [`Error::Finalize`]. If the commit itself fails, the errors for each
+    /// offending stream are returned as an [`Error::Commit`]. Both can be matched back up to
+    /// the sessions with `stream_error` and [`WriteSession::stream_name`].
+    ///
+    /// Sessions writing to more than one table are rejected with [`Error::MixedTables`] before
+    /// anything is finalized.
+    pub async fn commit(self) -> Result<BatchCommit, Error> {
+        if self.sessions.is_empty() {
+            return Ok(BatchCommit {
+                rows: 0,
+                commit_time: None,
+            });
+        }
+
+        check_single_table(self.sessions.iter().map(WriteSession::stream_name))?;
+
+        let results = join_all(self.sessions.into_iter().map(|session| async move {
+            let stream_name = session.stream_name().to_owned();
+            (stream_name, session.finalize().await)
+        }))
+        .await;
+
+        let streams = collect_finalized(results)?;
+
+        super::PendingStream::batch_commit(streams).await
+    }
+}
+
+/// Errors if the streams don't all belong to the same table.
+fn check_single_table<'a>(mut stream_names: impl Iterator<Item = &'a str>) -> Result<(), Error> {
+    let Some(first) = stream_names.next().map(stream_table) else {
+        return Ok(());
+    };
+
+    match stream_names.map(stream_table).find(|table| *table != first) {
+        Some(other) => Err(Error::MixedTables {
+            first: first.to_owned(),
+            other: other.to_owned(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Unzips the results of finalizing each stream, erroring with every stream that failed (not
+/// just the first).
+fn collect_finalized<T>(results: Vec<(String, Result<T, Error>)>) -> Result<Vec<T>, FinalizeError> {
+    let mut finalized = Vec::with_capacity(results.len());
+    let mut errors = Vec::new();
+
+    for (stream_name, result) in results {
+        match result {
+            Ok(stream) => finalized.push(stream),
+            Err(error) => errors.push((stream_name, error)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(finalized)
+    } else {
+        Err(FinalizeError::new(errors))
+    }
+}
+
+impl<R> Extend<WriteSession<Pending, R>> for PendingBatch<R> {
+    fn extend<T: IntoIterator<Item = WriteSession<Pending, R>>>(&mut self, iter: T) {
+        self.sessions.extend(iter);
+    }
+}
+
+impl<R> FromIterator<WriteSession<Pending, R>> for PendingBatch<R> {
+    fn from_iter<T: IntoIterator<Item = WriteSession<Pending, R>>>(iter: T) -> Self {
+        Self {
+            sessions: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protos::bigquery_storage::StorageError;
+    use protos::bigquery_storage::storage_error::StorageErrorCode;
+
+    use super::*;
+    use crate::error::CommitError;
+
+    const STREAM_A: &str = "projects/p/datasets/d/tables/t/streams/a";
+    const STREAM_B: &str = "projects/p/datasets/d/tables/t/streams/b";
+    const STREAM_C: &str = "projects/p/datasets/d/tables/t/streams/c";
+    const OTHER_TABLE_STREAM: &str = "projects/p/datasets/d/tables/other/streams/a";
+
+    #[test]
+    fn test_check_single_table() {
+        check_single_table(std::iter::empty()).unwrap();
+        check_single_table([STREAM_A, STREAM_B, STREAM_C].into_iter()).unwrap();
+
+        let error =
+            check_single_table([STREAM_A, STREAM_B, OTHER_TABLE_STREAM].into_iter()).unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::MixedTables { first, other }
+                if first == "projects/p/datasets/d/tables/t"
+                    && other == "projects/p/datasets/d/tables/other"
+        ));
+    }
+
+    #[test]
+    fn test_collect_finalized_reports_every_failure() {
+        let finalized = collect_finalized(vec![
+            (STREAM_A.to_owned(), Ok(1)),
+            (STREAM_B.to_owned(), Ok(2)),
+        ])
+        .unwrap();
+        assert_eq!(finalized, [1, 2]);
+
+        let error = collect_finalized(vec![
+            (
+                STREAM_A.to_owned(),
+                Err(tonic::Status::not_found("a").into()),
+            ),
+            (STREAM_B.to_owned(), Ok(2)),
+            (STREAM_C.to_owned(), Err(tonic::Status::aborted("c").into())),
+        ])
+        .unwrap_err();
+
+        let failed = error
+            .errors()
+            .iter()
+            .map(|(stream, _)| stream.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(failed, [STREAM_A, STREAM_C]);
+
+        assert!(matches!(
+            error.stream_error(STREAM_C),
+            Some(Error::Status(status)) if status.code() == tonic::Code::Aborted
+        ));
+        assert!(error.stream_error(STREAM_B).is_none());
+    }
+
+    #[test]
+    fn test_commit_stream_errors() {
+        let storage_error = |stream: &str| StorageError {
+            code: StorageErrorCode::StreamNotFound as i32,
+            entity: stream.to_owned(),
+            error_message: "stream not found".to_owned(),
+        };
+
+        assert!(CommitError::from_raw_errors(Vec::new()).is_ok());
+
+        let one = CommitError::from_raw_errors(vec![storage_error(STREAM_A)]).unwrap_err();
+        assert_eq!(one.num_errors(), 1);
+        assert!(one.stream_error(STREAM_A).is_some());
+        assert!(one.stream_error(STREAM_B).is_none());
+
+        let many =
+            CommitError::from_raw_errors(vec![storage_error(STREAM_A), storage_error(STREAM_C)])
+                .unwrap_err();
+        assert_eq!(many.num_errors(), 2);
+        assert_eq!(
+            many.stream_error(STREAM_C).map(|err| err.entity.as_str()),
+            Some(STREAM_C)
+        );
+        assert!(many.stream_error(STREAM_B).is_none());
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt;
 
 use gcp_auth_provider::Scope;
 use protos::bigquery_storage::big_query_write_client::BigQueryWriteClient;
-use protos::bigquery_storage::write_stream::WriteMode;
+use protos::bigquery_storage::write_stream::{Type, WriteMode};
 use protos::bigquery_storage::{CreateWriteStreamRequest, WriteStream};
 
 use super::default::load_default_schema;
@@ -101,7 +101,7 @@ where
     D: fmt::Display,
     T: fmt::Display,
 {
-    async fn create_inner(&mut self, name: String) -> Result<WriteStream, Error> {
+    async fn create_inner(&mut self, name: String, ty: Type) -> Result<WriteStream, Error> {
         let table_info = self
             .client
             .build_table_info(&self.dataset_id, &self.table_id);
@@ -114,7 +114,7 @@ where
                 create_time: None,
                 table_schema: None,
                 commit_time: None,
-                r#type: stream_types::Default::to_type() as i32,
+                r#type: ty as i32,
                 write_mode: WriteMode::Insert as i32,
             }),
         };
@@ -167,7 +167,7 @@ where
 {
     /// Creates the [`WriteSession`] for this stream.
     pub async fn create<R>(mut self) -> Result<WriteSession<W, R>, Error> {
-        let write_stream = self.create_inner(String::new()).await?;
+        let write_stream = self.create_inner(String::new(), W::to_type()).await?;
         WriteSession::new_inner(write_stream, self.client.channel, self.stream_type, None)
     }
 }
@@ -1,20 +1,27 @@
 use std::fmt;
-use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, PoisonError, RwLock};
 
+use bytes::{Bytes, BytesMut};
 use gcp_auth_provider::service::AuthSvc;
 use gcp_auth_provider::{Scope, Scopes};
 use http::HeaderValue;
 use net_utils::bidirec::{self, Bidirec};
 use net_utils::header::GoogRequestParam;
-use protos::bigquery_storage::append_rows_response::{AppendResult, Response};
+use protos::bigquery_storage::append_rows_request::{ProtoData, Rows};
 use protos::bigquery_storage::big_query_write_client::BigQueryWriteClient;
-use protos::bigquery_storage::{AppendRowsRequest, AppendRowsResponse, WriteStream};
+use protos::bigquery_storage::{
+    AppendRowsRequest, AppendRowsResponse, ProtoRows, ProtoSchema, WriteStream,
+};
+use protos::protobuf::Int64Value;
 use tonic::transport::Channel;
+use typenum::marker_traits::Bit;
 
 use super::{BigQueryStorageClient, Error};
+use crate::error::RowInsertErrors;
+use crate::proto::ProtoSerializer;
 
 mod append_rows;
+mod batch;
 mod builder;
 mod default;
 mod schema;
@@ -22,10 +29,16 @@ mod stream_types;
 // mod value;
 mod write2;
 
+pub use batch::{BatchCommit, PendingBatch};
+pub use bigquery_storage_rs_macros::TableSchema;
+pub use schema::{ColumnType, FieldSchema, RowSchema, TableSchema, column};
 pub(crate) use schema::{FieldInfo, Schema};
+use stream_types::WriteStreamType;
 pub use stream_types::{Buffered, Committed, Default, Pending, PendingStream};
 
-// use super::proto::ProtoSerializer;
+/// Google rejects append requests over 10MB, so leave some headroom for the
+/// non-row fields + per-row tag/length overhead.
+const MAX_APPEND_REQUEST_SIZE: usize = 9 * 1024 * 1024;
 
 #[derive(Debug, Clone)]
 pub struct WriteClient(BigQueryStorageClient);
@@ -93,7 +106,7 @@ impl<W, R> WriteSession<W, R> {
                 write_stream,
                 // generate a unique trace id for this write session
                 trace: uuid::Uuid::new_v4().to_string(),
-                offset: AtomicUsize::new(0),
+                offsets: offset::OffsetTracker::new(),
                 schema: RwLock::new(schema),
             }),
             channel,
@@ -162,11 +175,27 @@ struct WriteSessionInner {
     write_stream: WriteStream,
     trace: String,
     schema: RwLock<Schema>,
-    offset: AtomicUsize,
+    offsets: offset::OffsetTracker,
+}
+
+impl<W, R> WriteSession<W, R> {
+    /// The fully qualified name of the underlying write stream.
+    pub fn stream_name(&self) -> &str {
+        &self.inner.write_stream.name
+    }
+
+    /// The offset the next call to [`WriteSession::append_rows`] will write at, i.e the
+    /// number of rows that have been successfully appended so far.
+    ///
+    /// Always 0 for the [`Default`] stream, which doesn't support offsets.
+    pub fn append_offset(&self) -> i64 {
+        self.inner.offsets.append_offset()
+    }
 }
 
 impl<W, R> WriteSession<W, R>
 where
+    W: WriteStreamType,
     R: serde::Serialize,
 {
     async fn get_row_append_context(
@@ -188,8 +217,8 @@ where
             .unwrap_or_else(PoisonError::into_inner)
     }
 
-    fn handle_resp_inner(&self, resp: AppendRowsResponse) -> Result<(), Error> {
-        if let Some(new_schema) = resp.updated_schema {
+    fn handle_resp_inner(&self, mut resp: AppendRowsResponse) -> Result<(), Error> {
+        if let Some(new_schema) = resp.updated_schema.take() {
             let new = Schema::from_table_schema(new_schema)?;
 
             let mut guard = match self.inner.schema.write() {
@@ -200,64 +229,276 @@ where
             *guard = new;
         }
 
-        match resp.response {
-            Some(Response::AppendResult(AppendResult {
-                offset: Some(offset),
-            })) => {
-                self.inner
-                    .offset
-                    .store(offset.value as usize, std::sync::atomic::Ordering::SeqCst);
-            }
-            Some(Response::Error(status)) => {
-                Error::try_from_raw_status(status)?;
-            }
-            _ => (),
-        }
+        check_append_response::<W>(resp)
+    }
 
-        Ok(())
+    /// Serializes the rows, splitting them up into requests that stay under the 10MB limit.
+    fn encode_requests<I>(
+        &self,
+        rows: I,
+        offset: i64,
+    ) -> Result<(Vec<AppendRowsRequest>, usize), Error>
+    where
+        I: IntoIterator<Item = R>,
+    {
+        let schema = self.schema();
+
+        let serialized_rows = rows.into_iter().map(|row| -> Result<Bytes, Error> {
+            let mut buf = BytesMut::new();
+            ProtoSerializer::new(&mut buf, &schema).serialize_row(&row)?;
+            Ok(buf.freeze())
+        });
+
+        build_requests::<W, _>(
+            &self.inner.write_stream.name,
+            &self.inner.trace,
+            &schema,
+            serialized_rows,
+            offset,
+            MAX_APPEND_REQUEST_SIZE,
+        )
     }
 
-    /// Takes an [`IntoIterator`] of rows, serializing them in order and appends them to the table.
+    /// Takes an [`IntoIterator`] of rows, serializing them in order and appends them to the
+    /// table, returning the number of rows appended.
     ///
-    /// This is a placeholder for the real implementation, so there's no checks that
-    /// the request payload is under the 10MB limit. Google returns a very undescriptive error
-    /// when the payload is >10MB, so there's a manual check that'll error out if all of the
-    /// serialized rows go over a slightly reduced limit (8MB). In a future version this'll be
-    /// replaced by a unified [`append::RowAppendContext`]-based implementation that'll handle
-    /// splitting the payload up internally.
-    pub async fn append_rows<I>(&self, rows: I) -> Result<(), Error>
+    /// For all streams but the [`Default`] stream, each request is sent with an explicit
+    /// offset, starting at [`WriteSession::append_offset`]. The offset only advances once
+    /// every row has been appended, so if this errors it's safe to retry with the same rows;
+    /// any that made it in the first time around are skipped by BigQuery rather than being
+    /// written twice. Since the offset is shared, appends to the same session shouldn't be
+    /// run concurrently.
+    pub async fn append_rows<I>(&self, rows: I) -> Result<usize, Error>
     where
         I: IntoIterator<Item = R>,
-        I::IntoIter: Send + 'static,
     {
-        /*
+        let base_offset = self.inner.offsets.append_offset();
+
+        let (requests, total_rows) = self.encode_requests(rows, base_offset)?;
+
+        if requests.is_empty() {
+            return Ok(0);
+        }
+
         let mut client = BigQueryWriteClient::new(self.channel.clone());
 
-        let base_msg = AppendRowsRequest {
-            write_stream: self.inner.write_stream.name.clone(),
-            ..AppendRowsRequest::default()
-        };
+        let mut responses = client
+            .append_rows(futures::stream::iter(requests))
+            .await?
+            .into_inner();
 
-        let encoder = append_row_encoder::AppendRowsEncoder::new_iter(
-            Arc::clone(&self.inner),
-            base_msg,
-            rows,
-        );
+        while let Some(resp) = responses.message().await? {
+            self.handle_resp_inner(resp)?;
+        }
+
+        if W::AllowsOffsets::BOOL {
+            self.inner
+                .offsets
+                .set_append_offset(base_offset + total_rows as i64);
+        }
+
+        Ok(total_rows)
+    }
+}
+
+/// Checks the response to an append. If the stream supports offsets, 'AlreadyExists' means
+/// the rows were already written by an earlier attempt, so a retried append is a no-op rather
+/// than a duplicate.
+fn check_append_response<W: WriteStreamType>(resp: AppendRowsResponse) -> Result<(), Error> {
+    match RowInsertErrors::from_raw_response(resp) {
+        Ok(_) => Ok(()),
+        Err(Error::RowInsert(err))
+            if W::AllowsOffsets::BOOL && err.code() == tonic::Code::AlreadyExists =>
+        {
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Splits serialized rows up into requests that stay under 'max_request_size', returning them
+/// along with the total number of rows. Each request carries the offset of its first row (if
+/// the stream supports offsets), so the requests for the same set of rows are always identical.
+fn build_requests<W, I>(
+    write_stream: &str,
+    trace: &str,
+    schema: &Schema,
+    serialized_rows: I,
+    mut offset: i64,
+    max_request_size: usize,
+) -> Result<(Vec<AppendRowsRequest>, usize), Error>
+where
+    W: WriteStreamType,
+    I: IntoIterator<Item = Result<Bytes, Error>>,
+{
+    let mut requests = Vec::new();
+    let mut request_rows = Vec::new();
+    let mut request_size = 0;
+    let mut total_rows = 0;
+
+    let mut push_request = |serialized_rows: Vec<Bytes>| {
+        let row_count = serialized_rows.len();
+        let first = requests.is_empty();
+
+        requests.push(AppendRowsRequest {
+            // only the first request on a connection needs the stream name/schema
+            write_stream: if first {
+                write_stream.to_owned()
+            } else {
+                String::new()
+            },
+            offset: W::AllowsOffsets::BOOL.then_some(Int64Value { value: offset }),
+            trace_id: trace.to_owned(),
+            rows: Some(Rows::ProtoRows(ProtoData {
+                writer_schema: first.then(|| ProtoSchema {
+                    proto_descriptor: Some(schema.to_descriptor_proto()),
+                }),
+                rows: Some(ProtoRows { serialized_rows }),
+            })),
+            ..AppendRowsRequest::default()
+        });
 
-        let (handle, sink) = net_utils::infallible::into_infallible(futures::stream::iter(encoder));
+        offset += row_count as i64;
+    };
 
-        let mut stream = client.append_rows(sink).await?.into_inner();
+    for row in serialized_rows {
+        let row = row?;
 
-        let mut last_message = None;
-        while let Some(message) = stream.message().await? {
-            last_message = Some(message);
+        if !request_rows.is_empty() && request_size + row.len() > max_request_size {
+            push_request(std::mem::take(&mut request_rows));
+            request_size = 0;
         }
 
-        let last_message = last_message
-            .ok_or_else(|| Error::Status(tonic::Status::internal("append_rows never responded")))?;
+        // each row is length delimited, so account for the tag + length prefix too.
+        request_size += row.len() + 8;
+        total_rows += 1;
+        request_rows.push(row);
+    }
+
+    if !request_rows.is_empty() {
+        push_request(request_rows);
+    }
+
+    Ok((requests, total_rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use bigquery_resources_rs::table::{FieldMode, FieldType, TableFieldSchema};
+    use protos::bigquery_storage::append_rows_response::{AppendResult, Response};
+
+    use super::*;
+
+    const STREAM: &str = "projects/p/datasets/d/tables/t/streams/s";
+
+    fn schema() -> Schema {
+        Schema::from_table_schema([TableFieldSchema::new(
+            "data",
+            FieldType::Bytes,
+            FieldMode::Required,
+        )])
+        .unwrap()
+    }
+
+    fn rows(count: usize, size: usize) -> impl Iterator<Item = Result<Bytes, Error>> {
+        (0..count).map(move |idx| Ok(Bytes::from(vec![idx as u8; size])))
+    }
+
+    fn row_counts(requests: &[AppendRowsRequest]) -> Vec<usize> {
+        requests
+            .iter()
+            .map(|request| match request.rows {
+                Some(Rows::ProtoRows(ref data)) => {
+                    data.rows.as_ref().unwrap().serialized_rows.len()
+                }
+                _ => panic!("expected proto rows"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_requests_splits_at_max_size() {
+        let schema = schema();
+
+        // each row takes up 20 + 8 bytes, so only 2 fit in each request.
+        let (requests, total_rows) =
+            build_requests::<Pending, _>(STREAM, "trace", &schema, rows(5, 20), 10, 64).unwrap();
 
-        self.handle_resp_inner(last_message)
-        */
-        todo!()
+        assert_eq!(total_rows, 5);
+        assert_eq!(row_counts(&requests), [2, 2, 1]);
+
+        let offsets = requests
+            .iter()
+            .map(|request| request.offset.as_ref().map(|offset| offset.value))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [Some(10), Some(12), Some(14)]);
+
+        // only the first request carries the stream name and schema.
+        assert_eq!(requests[0].write_stream, STREAM);
+        assert!(requests[1..].iter().all(|req| req.write_stream.is_empty()));
+
+        // a single row over the limit still gets sent on its own.
+        let (requests, _) =
+            build_requests::<Pending, _>(STREAM, "trace", &schema, rows(2, 100), 0, 64).unwrap();
+        assert_eq!(row_counts(&requests), [1, 1]);
+
+        // the default stream doesn't support offsets.
+        let (requests, _) =
+            build_requests::<Default, _>(STREAM, "trace", &schema, rows(5, 20), 0, 64).unwrap();
+        assert!(requests.iter().all(|request| request.offset.is_none()));
+    }
+
+    #[test]
+    fn test_build_requests_retry_is_identical() {
+        let schema = schema();
+
+        let build = || {
+            build_requests::<Pending, _>(STREAM, "trace", &schema, rows(5, 20), 3, 64)
+                .unwrap()
+                .0
+        };
+
+        // retrying an append starts from the same offset, so BigQuery can skip the rows that
+        // already made it in.
+        assert_eq!(build(), build());
+
+        let offset = offset::OffsetTracker::new();
+        assert_eq!(offset.append_offset(), 0);
+        offset.set_append_offset(5);
+        // a stale update from an earlier attempt can't move the offset backwards.
+        offset.set_append_offset(3);
+        assert_eq!(offset.append_offset(), 5);
+    }
+
+    #[test]
+    fn test_already_exists_only_ok_with_offsets() {
+        let already_exists = || AppendRowsResponse {
+            response: Some(Response::Error(protos::rpc::Status {
+                code: tonic::Code::AlreadyExists as i32,
+                message: "offset already exists".to_owned(),
+                details: Vec::new(),
+            })),
+            ..AppendRowsResponse::default()
+        };
+
+        assert!(check_append_response::<Pending>(already_exists()).is_ok());
+        assert!(check_append_response::<Committed>(already_exists()).is_ok());
+        assert!(check_append_response::<Default>(already_exists()).is_err());
+
+        let out_of_range = AppendRowsResponse {
+            response: Some(Response::Error(protos::rpc::Status {
+                code: tonic::Code::OutOfRange as i32,
+                message: "offset out of range".to_owned(),
+                details: Vec::new(),
+            })),
+            ..AppendRowsResponse::default()
+        };
+        assert!(check_append_response::<Pending>(out_of_range).is_err());
+
+        let ok = AppendRowsResponse {
+            response: Some(Response::AppendResult(AppendResult { offset: None })),
+            ..AppendRowsResponse::default()
+        };
+        assert!(check_append_response::<Default>(ok).is_ok());
     }
 }
@@ -4,11 +4,13 @@ use protos::protobuf::DescriptorProto;
 use crate::proto::EncodeError;
 
 mod field_info;
+mod row_schema;
 mod table_schema;
 pub use field_info::FieldInfo;
+pub use row_schema::{ColumnType, RowSchema, column};
 pub use table_schema::{FieldSchema, TableSchema};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Schema {
     fields: Box<[FieldInfo]>,
     index: fxhash::FxHashMap<Box<str>, usize>,
@@ -16,8 +18,13 @@ pub struct Schema {
 
 impl Schema {
     pub fn from_table_schema(schema: impl TableSchema) -> Result<Self, EncodeError> {
-        let schema = schema.into_fields().into_iter();
+        Self::from_fields(schema.into_fields().into_iter())
+    }
 
+    /// Also used for the sub-fields of 'RECORD' fields, which get their own nested message.
+    fn from_fields<F: FieldSchema>(
+        schema: impl ExactSizeIterator<Item = F>,
+    ) -> Result<Self, EncodeError> {
         let schema_len = schema.len();
 
         assert!(
@@ -47,10 +54,18 @@ impl Schema {
     }
 
     pub fn to_descriptor_proto(&self) -> DescriptorProto {
-        DescriptorProto {
-            field: self.fields.iter().map(|field| field.to_proto()).collect(),
+        let mut descriptor = DescriptorProto {
+            field: Vec::with_capacity(self.fields.len()),
             ..Default::default()
+        };
+
+        for field in self.fields.iter() {
+            let (field, nested) = field.to_proto();
+            descriptor.field.push(field);
+            descriptor.nested_type.extend(nested);
         }
+
+        descriptor
     }
 
     pub fn get_field_by_index(&self, index: usize) -> Option<&FieldInfo> {
@@ -1,18 +1,28 @@
 use std::fmt;
 
+use bigquery_resources_rs::table::FieldType;
 use bytes::BufMut;
 use protos::bigquery_storage::table_field_schema::{Mode, Type as TableFieldType};
-use protos::protobuf::FieldDescriptorProto;
 use protos::protobuf::field_descriptor_proto::{Label, Type as FieldProtoType};
+use protos::protobuf::{DescriptorProto, FieldDescriptorProto};
 
-use super::FieldSchema;
-use super::table_schema::proto_type_to_field_type;
+use super::table_schema::{field_type_to_proto_type, proto_type_to_field_type};
+use super::{FieldSchema, Schema};
 use crate::proto::{EncodeError, Field, WireType};
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct FieldInfo {
     name: Box<str>,
     packed: u16,
+    nested: Option<Nested>,
+}
+
+/// The nested message 'RECORD' and 'RANGE' fields are encoded as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Nested {
+    Record(Box<Schema>),
+    /// A message with optional 'start' and 'end' fields, of the given element type.
+    Range(TableFieldType),
 }
 
 impl FieldInfo {
@@ -22,21 +32,43 @@ impl FieldInfo {
         field_type: TableFieldType,
         mode: Mode,
         name: Box<str>,
+        nested: Option<Nested>,
     ) -> Self {
         let proto_field = Field::new(index as u8, wire_type);
 
         let packed = pack(proto_field, mode, field_type);
 
-        Self { name, packed }
+        Self {
+            name,
+            packed,
+            nested,
+        }
     }
 
-    pub fn from_field(index: usize, field: impl FieldSchema) -> Result<Self, EncodeError> {
+    pub fn from_field<F: FieldSchema>(index: usize, mut field: F) -> Result<Self, EncodeError> {
         let Some(field_type) = field.ty() else {
             return Err(EncodeError::UnspecifiedFieldType(
                 field.into_field_name().into(),
             ));
         };
 
+        let nested = match field_type {
+            FieldType::Record => {
+                let schema = Schema::from_fields(field.take_fields().into_iter())?;
+                Some(Nested::Record(Box::new(schema)))
+            }
+            FieldType::Range => match field.range_element_type() {
+                Some(element_type) => Some(Nested::Range(field_type_to_proto_type(element_type))),
+                None => {
+                    let name: Box<str> = field.into_field_name().into();
+                    return Err(EncodeError::Misc(
+                        format!("RANGE field `{name}` has no element type").into(),
+                    ));
+                }
+            },
+            _ => None,
+        };
+
         let wire_type = crate::proto::field_type_to_wire_type(field_type);
 
         Ok(Self::from_parts(
@@ -45,6 +77,7 @@ impl FieldInfo {
             field.proto_ty(),
             field.proto_mode(),
             field.into_field_name().into(),
+            nested,
         ))
     }
 
@@ -82,10 +115,27 @@ impl FieldInfo {
         TableFieldType::try_from(raw_ty as i32).unwrap_or(TableFieldType::Unspecified)
     }
 
-    pub(super) fn to_proto(&self) -> FieldDescriptorProto {
+    /// Builds the descriptor for this field, along with the nested message type it refers to
+    /// for 'RECORD' and 'RANGE' fields.
+    pub(super) fn to_proto(&self) -> (FieldDescriptorProto, Option<DescriptorProto>) {
         let (field, mode, ty) = unpack(self.packed);
 
-        FieldDescriptorProto {
+        let nested = self.nested.as_ref().map(|nested| match nested {
+            Nested::Record(schema) => DescriptorProto {
+                name: Some(self.nested_type_name()),
+                ..schema.to_descriptor_proto()
+            },
+            Nested::Range(element_type) => DescriptorProto {
+                name: Some(self.nested_type_name()),
+                field: vec![
+                    range_bound_proto("start", 1, *element_type),
+                    range_bound_proto("end", 2, *element_type),
+                ],
+                ..Default::default()
+            },
+        });
+
+        let field = FieldDescriptorProto {
             name: Some(self.name.as_ref().to_owned()),
             number: Some(field.field_number() as i32),
             label: match mode {
@@ -94,36 +144,66 @@ impl FieldInfo {
                 Mode::Required => Some(Label::Required as i32),
                 Mode::Unspecified => None,
             },
-            r#type: match ty {
-                TableFieldType::Bool => Some(FieldProtoType::Bool as i32),
-                TableFieldType::String => Some(FieldProtoType::String as i32),
-                TableFieldType::Int64 => Some(FieldProtoType::Sint64 as i32),
-                TableFieldType::Double => Some(FieldProtoType::Float as i32),
-                TableFieldType::Struct => Some(FieldProtoType::Message as i32),
-                TableFieldType::Bytes => Some(FieldProtoType::Bytes as i32),
-                TableFieldType::Timestamp => Some(FieldProtoType::Int64 as i32),
-                TableFieldType::Date => Some(FieldProtoType::Int32 as i32),
-                TableFieldType::Time => Some(FieldProtoType::String as i32),
-                TableFieldType::Datetime => Some(FieldProtoType::String as i32),
-                TableFieldType::Geography => Some(FieldProtoType::String as i32),
-                TableFieldType::Numeric => Some(FieldProtoType::Bytes as i32),
-                TableFieldType::Bignumeric => Some(FieldProtoType::String as i32),
-                TableFieldType::Interval => Some(FieldProtoType::Sint64 as i32),
-                TableFieldType::Json => Some(FieldProtoType::String as i32),
-                TableFieldType::Range => todo!(),
-                TableFieldType::Unspecified => None,
-            },
-            type_name: None,
+            r#type: proto_field_type(ty).map(|ty| ty as i32),
+            type_name: nested.as_ref().and_then(|nested| nested.name.clone()),
             extendee: None,
             default_value: None,
             oneof_index: None,
             json_name: None,
             options: None,
             proto3_optional: None,
+        };
+
+        (field, nested)
+    }
+
+    /// Named after the field, so it's unique within the parent message.
+    fn nested_type_name(&self) -> String {
+        match self.nested {
+            Some(Nested::Record(_)) => format!("{}_Record", self.name),
+            Some(Nested::Range(_)) => format!("{}_Range", self.name),
+            None => unreachable!("only 'RECORD' and 'RANGE' fields have nested types"),
         }
     }
 }
 
+fn proto_field_type(ty: TableFieldType) -> Option<FieldProtoType> {
+    match ty {
+        TableFieldType::Bool => Some(FieldProtoType::Bool),
+        TableFieldType::String => Some(FieldProtoType::String),
+        TableFieldType::Int64 => Some(FieldProtoType::Sint64),
+        TableFieldType::Double => Some(FieldProtoType::Float),
+        TableFieldType::Struct => Some(FieldProtoType::Message),
+        TableFieldType::Bytes => Some(FieldProtoType::Bytes),
+        TableFieldType::Timestamp => Some(FieldProtoType::Int64),
+        TableFieldType::Date => Some(FieldProtoType::Int32),
+        TableFieldType::Time => Some(FieldProtoType::String),
+        TableFieldType::Datetime => Some(FieldProtoType::String),
+        TableFieldType::Geography => Some(FieldProtoType::String),
+        TableFieldType::Numeric => Some(FieldProtoType::Bytes),
+        TableFieldType::Bignumeric => Some(FieldProtoType::String),
+        TableFieldType::Interval => Some(FieldProtoType::Sint64),
+        TableFieldType::Json => Some(FieldProtoType::String),
+        TableFieldType::Range => Some(FieldProtoType::Message),
+        TableFieldType::Unspecified => None,
+    }
+}
+
+/// The 'start' or 'end' of a 'RANGE' message. Both are optional, a missing bound is unbounded.
+fn range_bound_proto(
+    name: &str,
+    number: i32,
+    element_type: TableFieldType,
+) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_owned()),
+        number: Some(number),
+        label: Some(Label::Optional as i32),
+        r#type: proto_field_type(element_type).map(|ty| ty as i32),
+        ..Default::default()
+    }
+}
+
 impl fmt::Debug for FieldInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let (field, mode, ty) = unpack(self.packed);
@@ -133,6 +213,7 @@ impl fmt::Debug for FieldInfo {
             .field("mode", &mode)
             .field("ty", &ty)
             .field("field", &field)
+            .field("nested", &self.nested)
             .finish()
     }
 }
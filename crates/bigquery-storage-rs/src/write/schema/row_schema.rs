@@ -0,0 +1,267 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use bigquery_resources_rs::table::{self as rest_table, FieldMode, FieldType, RangeElementType};
+
+use super::TableSchema;
+
+/// A row type with a fixed BigQuery schema, usually implemented with `#[derive(TableSchema)]`.
+///
+/// [`RowSchema::table_schema`] can be passed directly to [`Schema::from_table_schema`], or set
+/// on a [`rest_table::Table`] to create the table in the first place.
+///
+/// [`Schema::from_table_schema`]: super::Schema::from_table_schema
+pub trait RowSchema {
+    fn fields() -> Vec<rest_table::TableFieldSchema<Box<str>>>;
+
+    fn table_schema() -> rest_table::TableSchema<Box<str>> {
+        rest_table::TableSchema {
+            fields: Self::fields(),
+        }
+    }
+}
+
+/// Maps a rust type to the type and mode of a BigQuery column.
+pub trait ColumnType {
+    const TYPE: FieldType;
+
+    const MODE: FieldMode = FieldMode::Required;
+
+    /// Only used for 'RANGE' columns.
+    const RANGE_ELEMENT_TYPE: Option<RangeElementType> = None;
+
+    /// The sub-fields of 'RECORD' columns.
+    fn fields() -> Option<Vec<rest_table::TableFieldSchema<Box<str>>>> {
+        None
+    }
+}
+
+/// Builds the schema for a column named 'name', holding values of type 'T'.
+pub fn column<T: ColumnType + ?Sized>(name: &str) -> rest_table::TableFieldSchema<Box<str>> {
+    let mut field = rest_table::TableFieldSchema::new(name.into(), T::TYPE, T::MODE);
+    field.range_element_type = T::RANGE_ELEMENT_TYPE;
+    field.fields = T::fields();
+    field
+}
+
+impl<S> TableSchema for rest_table::TableSchema<S>
+where
+    S: Into<Box<str>>,
+{
+    type Field = rest_table::TableFieldSchema<S>;
+    type Fields = Vec<Self::Field>;
+
+    #[inline]
+    fn into_fields(self) -> Self::Fields {
+        self.fields
+    }
+}
+
+macro_rules! impl_column_type {
+    ($($variant:ident: $($t:ty),* $(,)?);* $(;)?) => {
+        $($(
+            impl ColumnType for $t {
+                const TYPE: FieldType = FieldType::$variant;
+            }
+        )*)*
+    };
+}
+
+impl_column_type! {
+    Bool: bool;
+    Integer: i8, i16, i32, i64, u8, u16, u32;
+    Float: f32, f64;
+    String: str, String, Cow<'_, str>, char, uuid::Uuid;
+    Bytes: bytes::Bytes;
+    Timestamp: timestamp::Timestamp;
+    Date: timestamp::Date;
+    Time: timestamp::Time;
+    Json: serde_json::Value;
+}
+
+impl ColumnType for std::ops::Range<timestamp::Timestamp> {
+    const TYPE: FieldType = FieldType::Range;
+    const RANGE_ELEMENT_TYPE: Option<RangeElementType> = Some(RangeElementType::Timestamp);
+}
+
+impl ColumnType for std::ops::Range<timestamp::Date> {
+    const TYPE: FieldType = FieldType::Range;
+    const RANGE_ELEMENT_TYPE: Option<RangeElementType> = Some(RangeElementType::Date);
+}
+
+macro_rules! impl_forwarded_column_type {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl<T: ColumnType + ?Sized> ColumnType for $t {
+                const TYPE: FieldType = T::TYPE;
+                const MODE: FieldMode = T::MODE;
+                const RANGE_ELEMENT_TYPE: Option<RangeElementType> = T::RANGE_ELEMENT_TYPE;
+
+                #[inline]
+                fn fields() -> Option<Vec<rest_table::TableFieldSchema<Box<str>>>> {
+                    T::fields()
+                }
+            }
+        )*
+    };
+}
+
+impl_forwarded_column_type!(&T, Box<T>, Arc<T>);
+
+impl<T: ColumnType> ColumnType for Option<T> {
+    const TYPE: FieldType = T::TYPE;
+    // BigQuery has no nullable arrays, a null array is written as an empty one.
+    const MODE: FieldMode = match T::MODE {
+        FieldMode::Repeated => FieldMode::Repeated,
+        _ => FieldMode::Nullable,
+    };
+    const RANGE_ELEMENT_TYPE: Option<RangeElementType> = T::RANGE_ELEMENT_TYPE;
+
+    #[inline]
+    fn fields() -> Option<Vec<rest_table::TableFieldSchema<Box<str>>>> {
+        T::fields()
+    }
+}
+
+macro_rules! impl_repeated_column_type {
+    ($($t:ty $(; const $n:ident: usize)?),* $(,)?) => {
+        $(
+            impl<T: ColumnType $(, const $n: usize)?> ColumnType for $t {
+                const TYPE: FieldType = T::TYPE;
+                const MODE: FieldMode = FieldMode::Repeated;
+                const RANGE_ELEMENT_TYPE: Option<RangeElementType> = T::RANGE_ELEMENT_TYPE;
+
+                #[inline]
+                fn fields() -> Option<Vec<rest_table::TableFieldSchema<Box<str>>>> {
+                    T::fields()
+                }
+            }
+        )*
+    };
+}
+
+impl_repeated_column_type!([T], Vec<T>, [T; N]; const N: usize);
+
+#[cfg(test)]
+mod tests {
+    use protos::protobuf::field_descriptor_proto::Type as FieldProtoType;
+    use serde::Serialize;
+
+    use super::*;
+    use crate::write::TableSchema;
+
+    #[allow(dead_code)]
+    #[derive(Serialize, TableSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct Position {
+        lat: f64,
+        lon: f64,
+        #[bigquery(ty = "GEOGRAPHY")]
+        point_wkt: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Serialize, TableSchema)]
+    #[serde(rename_all = "lowercase")]
+    enum Species {
+        Humpback,
+        Orca,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Serialize, TableSchema)]
+    struct Sighting {
+        #[serde(rename = "id")]
+        sighting_id: i64,
+        species: Species,
+        observed_at: timestamp::Timestamp,
+        positions: Vec<Position>,
+        notes: Option<String>,
+        #[bigquery(ty = "NUMERIC", description = "in meters")]
+        distance: Option<String>,
+        window: std::ops::Range<timestamp::Timestamp>,
+        #[serde(skip)]
+        cached: bool,
+    }
+
+    #[test]
+    fn test_derived_schema() {
+        use FieldMode::*;
+        use FieldType::*;
+
+        let fields = <Sighting as RowSchema>::fields();
+
+        let summary = fields
+            .iter()
+            .map(|field| (&*field.name, field.ty, field.mode))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            [
+                ("id", Integer, Required),
+                ("species", String, Required),
+                ("observed_at", Timestamp, Required),
+                ("positions", Record, Repeated),
+                ("notes", String, Nullable),
+                ("distance", Numeric, Nullable),
+                ("window", Range, Required),
+            ]
+        );
+
+        assert_eq!(fields[5].description.as_deref(), Some("in meters"));
+        assert_eq!(
+            fields[6].range_element_type,
+            Some(RangeElementType::Timestamp)
+        );
+
+        let position_fields = fields[3].fields.as_ref().unwrap();
+        let position_names = position_fields
+            .iter()
+            .map(|field| (&*field.name, field.ty))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            position_names,
+            [("lat", Float), ("lon", Float), ("pointWkt", Geography)]
+        );
+
+        // and the derived schema goes through the same path as fetched/hand built ones
+        let schema = crate::write::Schema::from_table_schema(Sighting::table_schema()).unwrap();
+        assert_eq!(schema.get_field("window").unwrap().index(), 6);
+
+        // RECORD and RANGE fields refer to nested message types.
+        let descriptor = schema.to_descriptor_proto();
+        assert_eq!(descriptor.field.len(), 7);
+
+        let nested = descriptor
+            .nested_type
+            .iter()
+            .map(|nested| nested.name())
+            .collect::<Vec<_>>();
+        assert_eq!(nested, ["positions_Record", "window_Range"]);
+
+        assert_eq!(descriptor.field[3].type_name(), "positions_Record");
+        assert_eq!(descriptor.field[6].type_name(), "window_Range");
+
+        let position_names = descriptor.nested_type[0]
+            .field
+            .iter()
+            .map(|field| field.name())
+            .collect::<Vec<_>>();
+        assert_eq!(position_names, ["lat", "lon", "pointWkt"]);
+
+        let window_names = descriptor.nested_type[1]
+            .field
+            .iter()
+            .map(|field| (field.name(), field.number(), field.r#type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            window_names,
+            [
+                ("start", 1, FieldProtoType::Int64),
+                ("end", 2, FieldProtoType::Int64),
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use bigquery_resources_rs::table::{self as rest_table, FieldMode, FieldType};
+use bigquery_resources_rs::table::{self as rest_table, FieldMode, FieldType, RangeElementType};
 use protos::bigquery_storage as proto_table;
 use protos::bigquery_storage::table_field_schema::{Mode, Type};
 
@@ -70,6 +70,14 @@ pub trait FieldSchema {
     fn proto_mode(&self) -> Mode;
 
     fn into_field_name(self) -> Self::Name;
+
+    /// The type of the start and end of 'RANGE' fields.
+    fn range_element_type(&self) -> Option<FieldType>;
+
+    /// Takes the sub-fields of 'RECORD' fields.
+    fn take_fields(&mut self) -> Vec<Self>
+    where
+        Self: Sized;
 }
 
 impl<S: Into<Box<str>>> FieldSchema for rest_table::TableFieldSchema<S> {
@@ -99,6 +107,20 @@ impl<S: Into<Box<str>>> FieldSchema for rest_table::TableFieldSchema<S> {
     fn into_field_name(self) -> Self::Name {
         self.name
     }
+
+    #[inline]
+    fn range_element_type(&self) -> Option<FieldType> {
+        self.range_element_type.map(|ty| match ty {
+            RangeElementType::Date => FieldType::Date,
+            RangeElementType::DateTime => FieldType::DateTime,
+            RangeElementType::Timestamp => FieldType::Timestamp,
+        })
+    }
+
+    #[inline]
+    fn take_fields(&mut self) -> Vec<Self> {
+        self.fields.take().unwrap_or_default()
+    }
 }
 
 impl FieldSchema for proto_table::TableFieldSchema {
@@ -128,6 +150,17 @@ impl FieldSchema for proto_table::TableFieldSchema {
     fn into_field_name(self) -> Self::Name {
         self.name
     }
+
+    #[inline]
+    fn range_element_type(&self) -> Option<FieldType> {
+        let element_type = self.range_element_type.as_ref()?;
+        proto_type_to_field_type(element_type.r#type())
+    }
+
+    #[inline]
+    fn take_fields(&mut self) -> Vec<Self> {
+        std::mem::take(&mut self.fields)
+    }
 }
 
 pub const fn proto_mode_to_field_mode(mode: Mode) -> Option<FieldMode> {
@@ -4,12 +4,14 @@ use gcp_auth_provider::service::AuthSvc;
 use net_utils::header::GoogRequestParam;
 use protos::bigquery_storage::big_query_write_client::BigQueryWriteClient;
 use protos::bigquery_storage::write_stream::Type;
-use protos::bigquery_storage::{BatchCommitWriteStreamsRequest, FinalizeWriteStreamResponse};
+use protos::bigquery_storage::{
+    BatchCommitWriteStreamsRequest, FinalizeWriteStreamRequest, FinalizeWriteStreamResponse,
+};
 use tonic::transport::Channel;
 use typenum::marker_traits::Bit;
 use typenum::{B0, B1};
 
-use super::{Error, WriteSession, WriteSessionInner};
+use super::{BatchCommit, Error, WriteSession, WriteSessionInner};
 
 mod private {
     pub trait Sealed {}
@@ -23,6 +25,8 @@ pub trait WriteStreamType: private::Sealed {
     type CanBatchCommit: Bit;
     /// Marker type of whether a stream can call the FlushRows endpoint.
     type CanFlush: Bit;
+    /// Marker type of whether appends can specify an explicit offset.
+    type AllowsOffsets: Bit;
 
     fn to_type() -> Type;
 }
@@ -41,7 +45,7 @@ pub trait FinalizeStream: WriteStreamType<CanFinalize = B1> + Sized {
 }
 
 macro_rules! impl_stream_types {
-    ($stream_type:ident { $type_variant:ident } ($finalize:ty, $commit:ty, $flush:ty)) => {
+    ($stream_type:ident { $type_variant:ident } ($finalize:ty, $commit:ty, $flush:ty, $offsets:ty)) => {
         #[doc = "Marker Type specifying a "]
         #[doc = stringify!($stream_type)]
         #[doc = " write stream."]
@@ -58,25 +62,26 @@ macro_rules! impl_stream_types {
             type CanFinalize = $finalize;
             type CanBatchCommit = $commit;
             type CanFlush = $flush;
+            type AllowsOffsets = $offsets;
 
             fn to_type() -> Type {
                 Type::$type_variant
             }
         }
     };
-    ($stream_type:ident( $finalize:ty, $commit:ty, $flush:ty )) => {
-        impl_stream_types!($stream_type { $stream_type } ($finalize, $commit, $flush));
+    ($stream_type:ident( $finalize:ty, $commit:ty, $flush:ty, $offsets:ty )) => {
+        impl_stream_types!($stream_type { $stream_type } ($finalize, $commit, $flush, $offsets));
     };
     (
         $(
             $stream_type:ident
             $({ $type_variant:ident })?
-            ( $finalize:ty, $commit:ty, $flush:ty )
+            ( $finalize:ty, $commit:ty, $flush:ty, $offsets:ty )
         ),*
         $(,)?
     ) => {
         $(
-            impl_stream_types!($stream_type $({ $type_variant })? ($finalize, $commit, $flush));
+            impl_stream_types!($stream_type $({ $type_variant })? ($finalize, $commit, $flush, $offsets));
         )*
     };
 }
@@ -99,10 +104,26 @@ macro_rules! impl_noop_finalize {
 }
 
 impl_stream_types! {
-    Buffered(B1, B0, B1),
-    Committed(B1, B0, B0),
-    Default { Committed } (B0, B0, B0),
-    Pending(B1, B1, B0),
+    Buffered(B1, B0, B1, B1),
+    Committed(B1, B0, B0, B1),
+    Default { Committed } (B0, B0, B0, B0),
+    Pending(B1, B1, B0, B1),
+}
+
+impl<W: FinalizeStream, R> WriteSession<W, R> {
+    /// Finalizes the stream, after which no more rows can be appended to it.
+    pub async fn finalize(self) -> Result<W::Ok, Error> {
+        let request = FinalizeWriteStreamRequest {
+            name: self.inner.write_stream.name.clone(),
+        };
+
+        let mut client = BigQueryWriteClient::new(self.channel.clone());
+        let resp = client.finalize_write_stream(request).await?.into_inner();
+
+        self.inner.offsets.set_commit_offset(resp.row_count);
+
+        W::on_finalized(self, resp)
+    }
 }
 
 impl_noop_finalize!(Buffered, Committed);
@@ -134,6 +155,16 @@ impl PendingStream {
         self.commit_many(std::iter::empty()).await
     }
 
+    /// The fully qualified name of the finalized write stream.
+    pub fn stream_name(&self) -> &str {
+        &self.inner.write_stream.name
+    }
+
+    /// The number of rows in the stream, as reported when it was finalized.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
     pub async fn commit_many<I>(self, others: I) -> Result<usize, Error>
     where
         I: IntoIterator<Item = Self>,
@@ -141,27 +172,37 @@ impl PendingStream {
         let iter = others.into_iter();
         let (low, high) = iter.size_hint();
 
-        let mut write_streams = Vec::with_capacity(1 + high.unwrap_or(low));
+        let mut streams = Vec::with_capacity(1 + high.unwrap_or(low));
+        streams.push(self);
+        streams.extend(iter);
 
-        let parent_end_idx = self.inner.write_stream.name.find("/streams/").unwrap();
-
-        let parent = self.inner.write_stream.name.as_str()[..parent_end_idx].to_owned();
-
-        macro_rules! unwrap_name {
-            ($parent:expr) => {{
-                match Arc::try_unwrap($parent.inner) {
-                    Ok(ws) => ws.write_stream.name,
-                    Err(arc) => arc.write_stream.name.clone(),
-                }
-            }};
-        }
-
-        write_streams.push(unwrap_name!(self));
-        let mut rows = self.rows;
+        Self::batch_commit(streams).await.map(|commit| commit.rows)
+    }
 
-        for pending in iter {
+    /// Commits all streams in a single BatchCommitWriteStreams call. Either every stream is
+    /// committed, or none are, in which case the per-stream errors are returned as a
+    /// [`CommitError`].
+    ///
+    /// Panics if 'streams' is empty.
+    ///
+    /// [`CommitError`]: crate::CommitError
+    pub(super) async fn batch_commit(streams: Vec<Self>) -> Result<BatchCommit, Error> {
+        let first = streams
+            .first()
+            .expect("batch_commit called with no streams");
+
+        let parent = stream_table(&first.inner.write_stream.name).to_owned();
+        let channel = first.channel.clone();
+
+        let mut rows = 0;
+        let mut write_streams = Vec::with_capacity(streams.len());
+
+        for pending in streams {
             rows += pending.rows;
-            write_streams.push(unwrap_name!(pending));
+            write_streams.push(match Arc::try_unwrap(pending.inner) {
+                Ok(ws) => ws.write_stream.name,
+                Err(arc) => arc.write_stream.name.clone(),
+            });
         }
 
         let req = BatchCommitWriteStreamsRequest {
@@ -169,12 +210,24 @@ impl PendingStream {
             write_streams,
         };
 
-        let mut client = BigQueryWriteClient::new(self.channel);
+        let mut client = BigQueryWriteClient::new(channel);
 
         let resp = client.batch_commit_write_streams(req).await?.into_inner();
 
         crate::error::CommitError::from_raw_errors(resp.stream_errors)?;
 
-        Ok(rows)
+        Ok(BatchCommit {
+            rows,
+            commit_time: resp.commit_time.map(timestamp::Timestamp::from),
+        })
+    }
+}
+
+/// Trims the '/streams/{stream}' suffix off of a fully qualified write stream name, leaving the
+/// table it writes to.
+pub(super) fn stream_table(stream_name: &str) -> &str {
+    match stream_name.find("/streams/") {
+        Some(idx) => &stream_name[..idx],
+        None => stream_name,
     }
 }
@@ -1 +1,521 @@
 //! Firestore admin interface
+//!
+//! Manages composite indexes, TTL policies and managed export/import of documents. Anything
+//! that runs server-side for a while is returned as an [`OperationHandle`], which can be polled
+//! or waited on until completion.
+use gcp_auth_provider::service::AuthSvc;
+use longrunning::OperationHandle;
+use net_utils::header::GoogRequestParam;
+use protos::firestore_admin::field::TtlConfig;
+use protos::firestore_admin::firestore_admin_client::FirestoreAdminClient;
+use protos::firestore_admin::index::index_field::{ArrayConfig, Order, ValueMode};
+use protos::firestore_admin::index::{IndexField, QueryScope};
+use protos::firestore_admin::{
+    CreateIndexRequest, DeleteIndexRequest, ExportDocumentsMetadata, ExportDocumentsRequest,
+    ExportDocumentsResponse, Field, FieldOperationMetadata, GetFieldRequest,
+    ImportDocumentsMetadata, ImportDocumentsRequest, Index, IndexOperationMetadata,
+    ListFieldsRequest, ListIndexesRequest, UpdateFieldRequest,
+};
+use protos::protobuf::{Empty, FieldMask};
+use tonic::transport::Channel;
+
+use crate::client::{FirestoreClient, ResponseExt};
+use crate::ser;
+
+const DEFAULT_PAGINATION_SIZE: i32 = 1000;
+
+/// Collection group wildcard, used to list fields across all collection groups.
+const ALL_COLLECTION_GROUPS: &str = "-";
+
+/// Admin client for a single database. Created via [`Firestore::admin`].
+///
+/// [`Firestore::admin`]: crate::Firestore::admin
+#[derive(Debug, Clone)]
+pub struct FirestoreAdmin {
+    client: FirestoreClient,
+}
+
+impl FirestoreAdmin {
+    pub(crate) fn new(client: FirestoreClient) -> Self {
+        Self { client }
+    }
+
+    fn admin_client(&self) -> FirestoreAdminClient<AuthSvc<GoogRequestParam<Channel>>> {
+        FirestoreAdminClient::new(self.client.channel.clone())
+    }
+
+    /// The channel handed off to [`OperationHandle`]s. The operations API is routed by
+    /// operation name, so it doesn't need the database routing header.
+    fn operation_channel(&self) -> AuthSvc<Channel> {
+        self.client
+            .channel
+            .clone()
+            .map(GoogRequestParam::into_inner)
+    }
+
+    fn collection_group_path(&self, collection_group: &str) -> String {
+        collection_group_path(&self.client.qualified_db_path, collection_group)
+    }
+
+    fn field_path(&self, collection_group: &str, field: &str) -> String {
+        field_path(&self.client.qualified_db_path, collection_group, field)
+    }
+
+    /// Lists all composite indexes on a collection group.
+    pub async fn list_indexes<C>(&self, collection_group: C) -> crate::Result<Vec<Index>>
+    where
+        C: AsRef<str>,
+    {
+        let parent = self.collection_group_path(collection_group.as_ref());
+        let mut client = self.admin_client();
+
+        let mut indexes = Vec::new();
+        let mut page_token = String::new();
+
+        loop {
+            let request = ListIndexesRequest {
+                parent: parent.clone(),
+                filter: String::new(),
+                page_size: DEFAULT_PAGINATION_SIZE,
+                page_token,
+            };
+
+            let resp = client.list_indexes(request).await?.into_inner();
+            indexes.extend(resp.indexes);
+
+            if resp.next_page_token.is_empty() {
+                return Ok(indexes);
+            }
+
+            page_token = resp.next_page_token;
+        }
+    }
+
+    /// Starts building a new composite index on a collection group.
+    pub fn index<C>(&self, collection_group: C) -> IndexBuilder<'_>
+    where
+        C: AsRef<str>,
+    {
+        IndexBuilder {
+            admin: self,
+            parent: self.collection_group_path(collection_group.as_ref()),
+            query_scope: QueryScope::Collection,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Deletes an index, given its fully qualified name (i.e [`Index::name`]).
+    pub async fn delete_index<N>(&self, name: N) -> crate::Result<()>
+    where
+        N: Into<String>,
+    {
+        let request = DeleteIndexRequest { name: name.into() };
+        self.admin_client().delete_index(request).await?;
+        Ok(())
+    }
+
+    /// Gets the TTL policy on a field, if there is one.
+    pub async fn get_ttl_policy<C, F>(
+        &self,
+        collection_group: C,
+        field: F,
+    ) -> crate::Result<Option<TtlConfig>>
+    where
+        C: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let request = GetFieldRequest {
+            name: self.field_path(collection_group.as_ref(), field.as_ref()),
+        };
+
+        let field = self
+            .admin_client()
+            .get_field(request)
+            .await
+            .handle_not_found()?;
+
+        Ok(field.and_then(|field| field.ttl_config))
+    }
+
+    /// Lists all fields with a TTL policy, across all collection groups.
+    pub async fn list_ttl_policies(&self) -> crate::Result<Vec<Field>> {
+        let parent = self.collection_group_path(ALL_COLLECTION_GROUPS);
+        let mut client = self.admin_client();
+
+        let mut fields = Vec::new();
+        let mut page_token = String::new();
+
+        loop {
+            let request = ListFieldsRequest {
+                parent: parent.clone(),
+                filter: "ttlConfig:*".to_owned(),
+                page_size: DEFAULT_PAGINATION_SIZE,
+                page_token,
+            };
+
+            let resp = client.list_fields(request).await?.into_inner();
+            fields.extend(resp.fields);
+
+            if resp.next_page_token.is_empty() {
+                return Ok(fields);
+            }
+
+            page_token = resp.next_page_token;
+        }
+    }
+
+    /// Sets a TTL policy on a field. Documents in the collection group are deleted once the
+    /// timestamp in `field` has passed.
+    pub async fn enable_ttl<C, F>(
+        &self,
+        collection_group: C,
+        field: F,
+    ) -> crate::Result<OperationHandle<FieldOperationMetadata, Field>>
+    where
+        C: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let name = self.field_path(collection_group.as_ref(), field.as_ref());
+        self.update_ttl(name, Some(TtlConfig { state: 0 })).await
+    }
+
+    /// Removes the TTL policy from a field.
+    pub async fn disable_ttl<C, F>(
+        &self,
+        collection_group: C,
+        field: F,
+    ) -> crate::Result<OperationHandle<FieldOperationMetadata, Field>>
+    where
+        C: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let name = self.field_path(collection_group.as_ref(), field.as_ref());
+        self.update_ttl(name, None).await
+    }
+
+    async fn update_ttl(
+        &self,
+        name: String,
+        ttl_config: Option<TtlConfig>,
+    ) -> crate::Result<OperationHandle<FieldOperationMetadata, Field>> {
+        let request = update_ttl_request(name, ttl_config);
+
+        let operation = self
+            .admin_client()
+            .update_field(request)
+            .await?
+            .into_inner();
+
+        Ok(OperationHandle::from_channel(
+            self.operation_channel(),
+            operation,
+        ))
+    }
+
+    /// Exports documents to GCS under `output_uri_prefix` (i.e `gs://bucket/path`). If
+    /// `collection_ids` is empty, all collections are exported.
+    pub async fn export_documents<U, I>(
+        &self,
+        output_uri_prefix: U,
+        collection_ids: I,
+    ) -> crate::Result<OperationHandle<ExportDocumentsMetadata, ExportDocumentsResponse>>
+    where
+        U: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let request = export_request(
+            &self.client.qualified_db_path,
+            output_uri_prefix,
+            collection_ids,
+        );
+
+        let operation = self
+            .admin_client()
+            .export_documents(request)
+            .await?
+            .into_inner();
+
+        Ok(OperationHandle::from_channel(
+            self.operation_channel(),
+            operation,
+        ))
+    }
+
+    /// Imports documents from a previous export. `input_uri_prefix` should be the
+    /// [`ExportDocumentsResponse::output_uri_prefix`] of that export. If `collection_ids` is
+    /// empty, all exported collections are imported.
+    pub async fn import_documents<U, I>(
+        &self,
+        input_uri_prefix: U,
+        collection_ids: I,
+    ) -> crate::Result<OperationHandle<ImportDocumentsMetadata, Empty>>
+    where
+        U: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let request = import_request(
+            &self.client.qualified_db_path,
+            input_uri_prefix,
+            collection_ids,
+        );
+
+        let operation = self
+            .admin_client()
+            .import_documents(request)
+            .await?
+            .into_inner();
+
+        Ok(OperationHandle::from_channel(
+            self.operation_channel(),
+            operation,
+        ))
+    }
+}
+
+/// Builds a composite index. Created via [`FirestoreAdmin::index`].
+///
+/// Fields are indexed in the order they're added.
+#[must_use = "IndexBuilder does nothing until 'create' is called"]
+pub struct IndexBuilder<'a> {
+    admin: &'a FirestoreAdmin,
+    parent: String,
+    query_scope: QueryScope,
+    fields: Vec<IndexField>,
+}
+
+impl IndexBuilder<'_> {
+    /// Makes the index usable by collection group queries, instead of only queries on a single
+    /// collection.
+    pub fn collection_group_scope(mut self) -> Self {
+        self.query_scope = QueryScope::CollectionGroup;
+        self
+    }
+
+    pub fn ascending<F: AsRef<str>>(self, field: F) -> Self {
+        self.add_field(field, ValueMode::Order(Order::Ascending as i32))
+    }
+
+    pub fn descending<F: AsRef<str>>(self, field: F) -> Self {
+        self.add_field(field, ValueMode::Order(Order::Descending as i32))
+    }
+
+    pub fn array_contains<F: AsRef<str>>(self, field: F) -> Self {
+        self.add_field(field, ValueMode::ArrayConfig(ArrayConfig::Contains as i32))
+    }
+
+    fn add_field<F: AsRef<str>>(mut self, field: F, value_mode: ValueMode) -> Self {
+        self.fields.push(index_field(field.as_ref(), value_mode));
+        self
+    }
+
+    /// Starts creating the index. Returns an error without sending a request if no fields were
+    /// added.
+    pub async fn create(self) -> crate::Result<OperationHandle<IndexOperationMetadata, Index>> {
+        let request = create_index_request(self.parent, self.query_scope, self.fields)?;
+
+        let operation = self
+            .admin
+            .admin_client()
+            .create_index(request)
+            .await?
+            .into_inner();
+
+        Ok(OperationHandle::from_channel(
+            self.admin.operation_channel(),
+            operation,
+        ))
+    }
+}
+
+fn collection_group_path(db_path: &str, collection_group: &str) -> String {
+    format!("{db_path}/collectionGroups/{collection_group}")
+}
+
+fn field_path(db_path: &str, collection_group: &str, field: &str) -> String {
+    format!(
+        "{}/fields/{}",
+        collection_group_path(db_path, collection_group),
+        ser::escape_field_path(field)
+    )
+}
+
+fn index_field(field: &str, value_mode: ValueMode) -> IndexField {
+    IndexField {
+        field_path: ser::escape_field_path(field),
+        value_mode: Some(value_mode),
+    }
+}
+
+fn create_index_request(
+    parent: String,
+    query_scope: QueryScope,
+    fields: Vec<IndexField>,
+) -> crate::Result<CreateIndexRequest> {
+    if fields.is_empty() {
+        return Err(crate::Error::InvalidArgument(
+            "cannot create an index without any fields",
+        ));
+    }
+
+    Ok(CreateIndexRequest {
+        parent,
+        index: Some(Index {
+            name: String::new(),
+            query_scope: query_scope as i32,
+            api_scope: 0,
+            fields,
+            state: 0,
+            density: 0,
+            multikey: false,
+            shard_count: 0,
+        }),
+    })
+}
+
+fn update_ttl_request(name: String, ttl_config: Option<TtlConfig>) -> UpdateFieldRequest {
+    UpdateFieldRequest {
+        field: Some(Field {
+            name,
+            index_config: None,
+            ttl_config,
+        }),
+        // only touch the ttl config, otherwise the single field index config gets reset too
+        update_mask: Some(FieldMask {
+            paths: vec!["ttl_config".to_owned()],
+        }),
+    }
+}
+
+fn export_request<U, I>(
+    db_path: &str,
+    output_uri_prefix: U,
+    collection_ids: I,
+) -> ExportDocumentsRequest
+where
+    U: Into<String>,
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    ExportDocumentsRequest {
+        name: db_path.to_owned(),
+        collection_ids: collection_ids.into_iter().map(Into::into).collect(),
+        output_uri_prefix: output_uri_prefix.into(),
+        namespace_ids: Vec::new(),
+        snapshot_time: None,
+    }
+}
+
+fn import_request<U, I>(
+    db_path: &str,
+    input_uri_prefix: U,
+    collection_ids: I,
+) -> ImportDocumentsRequest
+where
+    U: Into<String>,
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    ImportDocumentsRequest {
+        name: db_path.to_owned(),
+        collection_ids: collection_ids.into_iter().map(Into::into).collect(),
+        input_uri_prefix: input_uri_prefix.into(),
+        namespace_ids: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DB_PATH: &str = "projects/p/databases/(default)";
+
+    #[test]
+    fn test_field_paths() {
+        assert_eq!(
+            collection_group_path(DB_PATH, "whales"),
+            "projects/p/databases/(default)/collectionGroups/whales"
+        );
+
+        // field paths are escaped, since they're part of the resource name
+        assert_eq!(
+            field_path(DB_PATH, "whales", "stats.last seen"),
+            "projects/p/databases/(default)/collectionGroups/whales/fields/stats.`last seen`"
+        );
+
+        assert_eq!(
+            field_path(DB_PATH, ALL_COLLECTION_GROUPS, "expires"),
+            "projects/p/databases/(default)/collectionGroups/-/fields/expires"
+        );
+    }
+
+    #[test]
+    fn test_create_index_request() -> crate::Result<()> {
+        let fields = vec![
+            index_field("pod", ValueMode::Order(Order::Ascending as i32)),
+            index_field("tags", ValueMode::ArrayConfig(ArrayConfig::Contains as i32)),
+            index_field("1st sighting", ValueMode::Order(Order::Descending as i32)),
+        ];
+
+        let request = create_index_request(
+            collection_group_path(DB_PATH, "whales"),
+            QueryScope::CollectionGroup,
+            fields.clone(),
+        )?;
+
+        assert_eq!(request.parent, collection_group_path(DB_PATH, "whales"));
+
+        let index = request.index.expect("index should be set");
+        assert_eq!(index.query_scope, QueryScope::CollectionGroup as i32);
+        assert_eq!(index.fields, fields);
+        assert_eq!(index.fields[2].field_path, "`1st sighting`");
+
+        assert!(matches!(
+            create_index_request(String::new(), QueryScope::Collection, Vec::new()),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_ttl_request() {
+        let name = field_path(DB_PATH, "sessions", "expires");
+
+        let enable = update_ttl_request(name.clone(), Some(TtlConfig { state: 0 }));
+        let field = enable.field.expect("field should be set");
+        assert_eq!(field.name, name);
+        assert!(field.ttl_config.is_some());
+        assert!(field.index_config.is_none());
+
+        // disabling clears the ttl config, but only the ttl config
+        let disable = update_ttl_request(name, None);
+        assert!(
+            disable
+                .field
+                .is_some_and(|field| field.ttl_config.is_none())
+        );
+
+        for mask in [enable.update_mask, disable.update_mask] {
+            assert_eq!(
+                mask.map(|mask| mask.paths),
+                Some(vec!["ttl_config".to_owned()])
+            );
+        }
+    }
+
+    #[test]
+    fn test_export_import_requests() {
+        let export = export_request(DB_PATH, "gs://bucket/export", ["whales", "sightings"]);
+        assert_eq!(export.name, DB_PATH);
+        assert_eq!(export.output_uri_prefix, "gs://bucket/export");
+        assert_eq!(export.collection_ids, ["whales", "sightings"]);
+
+        // no collection ids means everything
+        let import = import_request(DB_PATH, "gs://bucket/export", Vec::<String>::new());
+        assert_eq!(import.name, DB_PATH);
+        assert_eq!(import.input_uri_prefix, "gs://bucket/export");
+        assert!(import.collection_ids.is_empty());
+    }
+}
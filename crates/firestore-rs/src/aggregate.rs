@@ -0,0 +1,340 @@
+//! Aggregation queries (count, sum, avg), backed by `Firestore.RunAggregationQuery`.
+use std::collections::HashMap;
+
+use protos::firestore::run_aggregation_query_request::QueryType;
+use protos::firestore::structured_aggregation_query::aggregation::{Avg, Count, Operator, Sum};
+use protos::firestore::structured_aggregation_query::{self, Aggregation};
+use protos::firestore::structured_query::FieldReference;
+use protos::firestore::{RunAggregationQueryRequest, StructuredAggregationQuery, StructuredQuery};
+use protos::protobuf::Int64Value;
+
+use crate::client::FirestoreClient;
+use crate::de::{deserialize_doc_fields, deserialize_value};
+
+/// Firestore limits the number of aggregations in a single query.
+const MAX_AGGREGATIONS: usize = 5;
+
+/// The alias used for single aggregation queries (i.e [`QueryBuilder::count`]).
+///
+/// [`QueryBuilder::count`]: crate::query::QueryBuilder::count
+const SINGLE_ALIAS: &str = "aggregate";
+
+/// Builds a query with multiple aggregations over the documents matching a query. Created via
+/// [`QueryBuilder::aggregate`].
+///
+/// Each aggregation is given an alias, which is used as the field name when deserializing the
+/// results.
+///
+/// [`QueryBuilder::aggregate`]: crate::query::QueryBuilder::aggregate
+pub struct AggregationBuilder<'a> {
+    client: &'a mut FirestoreClient,
+    parent: String,
+    query: StructuredQuery,
+    aggregations: Vec<Aggregation>,
+}
+
+impl<'a> AggregationBuilder<'a> {
+    pub(crate) fn new(
+        client: &'a mut FirestoreClient,
+        parent: String,
+        query: StructuredQuery,
+    ) -> Self {
+        Self {
+            client,
+            parent,
+            query,
+            aggregations: Vec::with_capacity(MAX_AGGREGATIONS),
+        }
+    }
+
+    /// Adds an aggregation. The number of aggregations is checked when the query is run, so
+    /// going over [`MAX_AGGREGATIONS`] returns an error then.
+    fn add_aggregation<S>(mut self, alias: S, operator: Operator) -> Self
+    where
+        S: Into<String>,
+    {
+        self.aggregations.push(Aggregation {
+            alias: alias.into(),
+            operator: Some(operator),
+        });
+
+        self
+    }
+
+    /// Counts the number of matching documents.
+    pub fn count<S>(self, alias: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.add_aggregation(alias, Operator::Count(Count { up_to: None }))
+    }
+
+    /// Counts the number of matching documents, stopping once `up_to` is hit. Since
+    /// aggregations are billed per index entry read, this puts an upper bound on the cost.
+    pub fn count_up_to<S>(self, alias: S, up_to: u64) -> Self
+    where
+        S: Into<String>,
+    {
+        self.add_aggregation(alias, count_up_to_operator(up_to))
+    }
+
+    /// Sums the values of a numeric field. Non-numeric values are ignored. The result is an
+    /// integer if all summed values are integers (and the sum doesn't overflow), otherwise a
+    /// double.
+    pub fn sum<S, F>(self, alias: S, field: F) -> Self
+    where
+        S: Into<String>,
+        F: AsRef<str>,
+    {
+        let field = Some(field_reference(field.as_ref()));
+        self.add_aggregation(alias, Operator::Sum(Sum { field }))
+    }
+
+    /// Averages the values of a numeric field. Non-numeric values are ignored. The result is
+    /// always a double, or null if there are no numeric values to average.
+    pub fn avg<S, F>(self, alias: S, field: F) -> Self
+    where
+        S: Into<String>,
+        F: AsRef<str>,
+    {
+        let field = Some(field_reference(field.as_ref()));
+        self.add_aggregation(alias, Operator::Avg(Avg { field }))
+    }
+
+    /// Runs the aggregation query, returning the raw results keyed by alias.
+    ///
+    /// Returns an error without sending a request if there are no aggregations, or more than
+    /// Firestore allows in a single query (5).
+    pub async fn run_raw(self) -> crate::Result<HashMap<String, protos::firestore::Value>> {
+        let request = build_request(self.parent, self.query, self.aggregations)?;
+
+        let mut stream = self
+            .client
+            .get()
+            .run_aggregation_query(request)
+            .await?
+            .into_inner();
+
+        while let Some(response) = stream.message().await? {
+            if let Some(result) = response.result {
+                return Ok(result.aggregate_fields);
+            }
+        }
+
+        Err(crate::Error::Internal(
+            "RunAggregationQuery completed without returning a result",
+        ))
+    }
+
+    /// Runs the aggregation query, deserializing the results as if they were the fields of a
+    /// document, keyed by alias.
+    pub async fn run<T>(self) -> crate::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let fields = self.run_raw().await?;
+        deserialize_doc_fields(fields).map_err(crate::Error::from)
+    }
+
+    /// Runs a query with a single aggregation under [`SINGLE_ALIAS`], deserializing the value.
+    pub(crate) async fn run_single<T>(self) -> crate::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let fields = self.run_raw().await?;
+        take_single(fields)
+    }
+
+    pub(crate) fn count_single(self) -> Self {
+        self.count(SINGLE_ALIAS)
+    }
+
+    pub(crate) fn sum_single(self, field: &str) -> Self {
+        self.sum(SINGLE_ALIAS, field)
+    }
+
+    pub(crate) fn avg_single(self, field: &str) -> Self {
+        self.avg(SINGLE_ALIAS, field)
+    }
+}
+
+fn build_request(
+    parent: String,
+    query: StructuredQuery,
+    aggregations: Vec<Aggregation>,
+) -> crate::Result<RunAggregationQueryRequest> {
+    if aggregations.is_empty() {
+        return Err(crate::Error::InvalidArgument(
+            "an aggregation query needs at least 1 aggregation",
+        ));
+    }
+
+    if aggregations.len() > MAX_AGGREGATIONS {
+        return Err(crate::Error::InvalidArgument(
+            "cannot use more than 5 aggregations in a single query",
+        ));
+    }
+
+    Ok(RunAggregationQueryRequest {
+        parent,
+        explain_options: None,
+        consistency_selector: None,
+        query_type: Some(QueryType::StructuredAggregationQuery(
+            StructuredAggregationQuery {
+                aggregations,
+                query_type: Some(structured_aggregation_query::QueryType::StructuredQuery(
+                    query,
+                )),
+            },
+        )),
+    })
+}
+
+fn take_single<T>(mut fields: HashMap<String, protos::firestore::Value>) -> crate::Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let value = fields.remove(SINGLE_ALIAS).ok_or(crate::Error::Internal(
+        "aggregation result is missing the requested aggregation",
+    ))?;
+
+    deserialize_value(value).map_err(crate::Error::from)
+}
+
+fn count_up_to_operator(up_to: u64) -> Operator {
+    let up_to = Some(Int64Value {
+        value: up_to.min(i64::MAX as u64) as i64,
+    });
+
+    Operator::Count(Count { up_to })
+}
+
+fn field_reference(field: &str) -> FieldReference {
+    FieldReference {
+        field_path: crate::ser::escape_field_path(field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protos::firestore::Value;
+    use protos::firestore::value::ValueType;
+
+    use super::*;
+
+    fn aggregation(alias: &str, operator: Operator) -> Aggregation {
+        Aggregation {
+            alias: alias.to_owned(),
+            operator: Some(operator),
+        }
+    }
+
+    fn value(value_type: ValueType) -> Value {
+        Value {
+            value_type: Some(value_type),
+        }
+    }
+
+    #[test]
+    fn test_build_request() -> crate::Result<()> {
+        let aggregations = vec![
+            aggregation("total", count_up_to_operator(u64::MAX)),
+            aggregation(
+                "sum",
+                Operator::Sum(Sum {
+                    field: Some(field_reference("stats.with space")),
+                }),
+            ),
+        ];
+
+        let request = build_request(
+            "projects/p/databases/(default)/documents".to_owned(),
+            StructuredQuery::default(),
+            aggregations.clone(),
+        )?;
+
+        let Some(QueryType::StructuredAggregationQuery(query)) = request.query_type else {
+            panic!("expected a structured aggregation query");
+        };
+
+        assert_eq!(query.aggregations, aggregations);
+        assert_eq!(
+            query.query_type,
+            Some(structured_aggregation_query::QueryType::StructuredQuery(
+                StructuredQuery::default()
+            ))
+        );
+
+        // up_to is clamped to what fits in an i64, and field paths are escaped
+        let Some(Operator::Count(Count { up_to })) = &aggregations[0].operator else {
+            unreachable!()
+        };
+        assert_eq!(up_to.as_ref().map(|v| v.value), Some(i64::MAX));
+
+        let Some(Operator::Sum(Sum { field })) = &aggregations[1].operator else {
+            unreachable!()
+        };
+        assert_eq!(
+            field.as_ref().map(|f| f.field_path.as_str()),
+            Some("stats.`with space`")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_request_aggregation_count() {
+        let count = |n: usize| {
+            let aggregations = (0..n)
+                .map(|i| aggregation(&format!("count_{i}"), count_up_to_operator(10)))
+                .collect();
+
+            build_request(String::new(), StructuredQuery::default(), aggregations)
+        };
+
+        assert!(matches!(count(0), Err(crate::Error::InvalidArgument(_))));
+        assert!(count(1).is_ok());
+        assert!(count(MAX_AGGREGATIONS).is_ok());
+        assert!(matches!(
+            count(MAX_AGGREGATIONS + 1),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_results() -> crate::Result<()> {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Stats {
+            total: i64,
+            sum: f64,
+            avg: Option<f64>,
+        }
+
+        let fields = HashMap::from([
+            ("total".to_owned(), value(ValueType::IntegerValue(3))),
+            ("sum".to_owned(), value(ValueType::DoubleValue(4.5))),
+            ("avg".to_owned(), value(ValueType::NullValue(0))),
+        ]);
+
+        let stats: Stats = deserialize_doc_fields(fields)?;
+        assert_eq!(
+            stats,
+            Stats {
+                total: 3,
+                sum: 4.5,
+                avg: None
+            }
+        );
+
+        let single = HashMap::from([(SINGLE_ALIAS.to_owned(), value(ValueType::IntegerValue(7)))]);
+        assert_eq!(take_single::<u64>(single)?, 7);
+
+        // a missing alias is an error, rather than a default value
+        assert!(matches!(
+            take_single::<u64>(HashMap::new()),
+            Err(crate::Error::Internal(_))
+        ));
+
+        Ok(())
+    }
+}
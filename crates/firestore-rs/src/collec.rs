@@ -3,6 +3,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll, ready};
 
+use futures::Stream;
 use protos::firestore::list_documents_request::ConsistencySelector;
 use protos::firestore::{
     CreateDocumentRequest, Document, DocumentMask, ListDocumentsRequest, ListDocumentsResponse,
@@ -10,6 +11,7 @@ use protos::firestore::{
 
 use crate::batch::read::{BatchRead, BatchReadStream, Collection, RawBatchReadStream};
 use crate::doc::{Doc, DocumentRef};
+use crate::listen::Snapshot;
 use crate::query::QueryBuilder;
 use crate::{Firestore, PathComponent, Reference};
 
@@ -190,28 +192,14 @@ impl<C: PathComponent> CollectionRef<C> {
         self.qualified_path.as_str()
     }
 
-    // listeners dont work yet, they need another pass of dev time
-    /*
-    #[allow(dead_code)]
-    async fn listen(&mut self) -> crate::Result<Listener>
+    /// Listens for realtime changes to all documents in this collection. Shorthand for
+    /// `self.query().listen()`.
+    pub fn listen<D>(&mut self) -> impl Stream<Item = crate::Result<Snapshot<D>>> + Send + use<C, D>
     where
-        C: PathComponent
+        D: serde::de::DeserializeOwned + Send,
     {
-        let database = crate::common::database_path_from_resource_path(&self.qualified_path)?;
-
-        let selector = CollectionSelector {
-            collection_id: self.collection_name.to_string(),
-            all_descendants: false,
-        };
-
-        let query = StructuredQuery {
-            from: vec![selector],
-            ..Default::default()
-        };
-
-        Listener::init_query(&mut self.client, database, self.parent_path.clone(), query).await
+        self.query().listen()
     }
-    */
 
     pub fn batch_read(&self) -> BatchRead<Collection> {
         BatchRead::new(
@@ -28,6 +28,17 @@ where
     .map_err(ConvertError::from_path_aware)
 }
 
+/// Deserializes a single firestore value.
+pub(crate) fn deserialize_value<'de, O>(value: firestore::Value) -> Result<O, ConvertError>
+where
+    O: de::Deserialize<'de>,
+{
+    O::deserialize(path_aware_serde::Deserializer::new(
+        ValueDeserializer::from(value),
+    ))
+    .map_err(ConvertError::from_path_aware)
+}
+
 pub(super) struct DocDeserializer {
     fields: HashMap<String, firestore::Value>,
 }
@@ -23,6 +23,7 @@ use crate::batch::read;
 use crate::client::ResponseExt;
 use crate::collec::CollectionRef;
 use crate::de::deserialize_doc_fields;
+use crate::listen::{DocumentListener, Listener, Snapshot};
 use crate::ser::{DocFields, WriteKind};
 use crate::{Error, PathComponent, Reference, ser};
 
@@ -229,6 +230,19 @@ impl<C: PathComponent, D: PathComponent> DocumentRef<C, D> {
         &self.collec_ref
     }
 
+    /// Listens for realtime changes to this document. The first snapshot contains the current
+    /// document (if it exists), and every snapshot after that contains any changes.
+    ///
+    /// The underlying stream is resumed if the connection drops, so this only ends if the
+    /// listener encounters an error it can't recover from.
+    pub fn listen<T>(&self) -> impl Stream<Item = crate::Result<Snapshot<T>>> + Send + use<C, D, T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        let listener_type = DocumentListener::new(self.reference.to_string());
+        Listener::new(self.collec_ref.parent.client.clone(), listener_type).into_stream()
+    }
+
     pub fn list_collection_ids(&mut self) -> impl Stream<Item = crate::Result<Vec<String>>> + '_ {
         crate::common::list_collection_ids(
             &mut self.collec_ref.parent.client,
@@ -469,8 +483,8 @@ impl<C: PathComponent, D: PathComponent> DocumentRef<C, D> {
     }
     */
 
-    async fn get_doc_inner(
-        &mut self,
+    pub(crate) async fn get_doc_inner(
+        &self,
         consistency_selector: Option<ConsistencySelector>,
         mask: Option<DocumentMask>,
     ) -> crate::Result<Option<Document>> {
@@ -690,7 +704,7 @@ fn encode_mask(fields: impl IntoIterator<Item: AsRef<str>>) -> Option<DocumentMa
 pub mod write_type {
     use super::firestore::write::Operation;
 
-    pub(super) mod private {
+    pub(crate) mod private {
         pub trait WriteType {
             fn to_operation(self) -> super::Operation;
         }
@@ -24,6 +24,8 @@ pub enum Error {
     ListenerClosed,
     #[error("internal error: {0}")]
     Internal(&'static str),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(&'static str),
     #[error("{code}: {message}")]
     RpcError {
         code: tonic::Code,
@@ -61,6 +63,18 @@ impl From<protos::rpc::Status> for Error {
     }
 }
 
+#[cfg(feature = "firestore-admin")]
+impl From<longrunning::Error> for Error {
+    fn from(value: longrunning::Error) -> Self {
+        match value {
+            longrunning::Error::Status(status) => Self::Status(status),
+            longrunning::Error::Decode(decode) => {
+                Self::Status(tonic::Status::new(tonic::Code::Unknown, decode.to_string()))
+            }
+        }
+    }
+}
+
 impl From<std::convert::Infallible> for Error {
     fn from(value: std::convert::Infallible) -> Self {
         match value {}
@@ -151,6 +165,11 @@ impl Error {
         matches!(self.rpc_code(), Some(tonic::Code::NotFound))
     }
 
+    /// If this is an ABORTED error, i.e a transaction that lost out due to contention.
+    pub fn is_aborted(&self) -> bool {
+        matches!(self.rpc_code(), Some(tonic::Code::Aborted))
+    }
+
     pub fn is_transient_error(&self) -> bool {
         self.rpc_code().map(is_transient_error).unwrap_or(false)
     }
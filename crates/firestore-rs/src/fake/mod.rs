@@ -0,0 +1,156 @@
+//! An in-memory fake of the Firestore gRPC service, for testing code that uses [`Firestore`]
+//! without needing a real project (or the emulator).
+//!
+//! ```no_run
+//! # async fn example() -> firestore_rs::Result<()> {
+//! use firestore_rs::fake::FakeFirestore;
+//!
+//! let fake = FakeFirestore::start().await?;
+//! let firestore = fake.firestore().await?;
+//!
+//! firestore
+//!     .collection("pods")
+//!     .doc("J")
+//!     .set(&serde_json::json!({ "members": 25 }))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Supports document reads/writes, batch gets, commits, batch writes, streaming writes, queries
+//! (filters, ordering, cursors, offsets and limits), listing collection ids and transactions.
+//! Transactions use optimistic concurrency: a commit is aborted if any document read in the
+//! transaction was modified since, which is enough to exercise retry logic. Read times are
+//! ignored, reads always see the latest data.
+//!
+//! Anything else (listeners, aggregations, etc) returns `UNIMPLEMENTED`.
+//!
+//! [`Firestore`]: crate::Firestore
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use gcp_auth_provider::providers::LoadProviderResult;
+use gcp_auth_provider::providers::emulator::EmulatorProvider;
+use gcp_auth_provider::{Auth, ProjectId};
+use parking_lot::Mutex;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tonic::transport::{Channel, Server};
+
+use crate::Firestore;
+
+mod query;
+mod service;
+mod store;
+
+/// The project id that the fake database lives under.
+pub const FAKE_PROJECT_ID: &str = "fake-project";
+
+/// An in-process Firestore server backed by an in-memory database. The server shuts down once
+/// this is dropped.
+#[derive(Debug)]
+pub struct FakeFirestore {
+    addr: SocketAddr,
+    store: Arc<Mutex<store::Store>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeFirestore {
+    /// Starts a new server with an empty database, listening on a random local port.
+    pub async fn start() -> crate::Result<Self> {
+        let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+            .await
+            .map_err(|_| crate::Error::Internal("failed to bind the fake Firestore server"))?;
+
+        let addr = listener
+            .local_addr()
+            .map_err(|_| crate::Error::Internal("fake Firestore server has no local address"))?;
+
+        let store = Arc::new(Mutex::new(store::Store::default()));
+        let (shutdown, shutdown_rx) = oneshot::channel();
+
+        let incoming = futures::stream::unfold(listener, |listener| async move {
+            let result = listener.accept().await.map(|(stream, _)| stream);
+            Some((result, listener))
+        });
+
+        let service = service::FirestoreService {
+            store: Arc::clone(&store),
+        };
+
+        tokio::spawn(async move {
+            let result = Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(incoming, async move {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+
+            if let Err(error) = result {
+                error!(message = "fake Firestore server failed", ?error);
+            }
+        });
+
+        Ok(Self {
+            addr,
+            store,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// The local address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Connects a new (plaintext) channel to the server.
+    pub async fn channel(&self) -> crate::Result<Channel> {
+        let channel = Channel::from_shared(format!("http://{}", self.addr))
+            .map_err(|_| crate::Error::Internal("invalid fake Firestore server address"))?
+            .connect()
+            .await?;
+
+        Ok(channel)
+    }
+
+    /// Builds a [`Firestore`] client connected to this server, under [`FAKE_PROJECT_ID`].
+    pub async fn firestore(&self) -> crate::Result<Firestore> {
+        let auth = Auth::new_from_provider(LoadProviderResult {
+            provider: EmulatorProvider,
+            project_id: ProjectId::new(FAKE_PROJECT_ID),
+            token_future: futures::future::TryMaybeDone::Gone,
+        });
+
+        let channel = self.channel().await?;
+        Ok(Firestore::from_auth_channel(auth.into_service(channel)))
+    }
+
+    /// Makes the next `count` messages sent on `Write` streams (including the first message
+    /// that opens a stream) fail with `UNAVAILABLE`, ending the stream without applying any
+    /// writes. Useful for testing [`WriteStream`] reconnects.
+    ///
+    /// [`WriteStream`]: crate::write_stream::WriteStream
+    pub fn fail_write_streams(&self, count: usize) {
+        self.store.lock().fail_write_streams(count);
+    }
+
+    /// Makes the next `count` messages that carry writes on `Write` streams apply their writes,
+    /// then fail with `UNAVAILABLE` instead of acknowledging them, as if the connection dropped
+    /// before the response made it back.
+    pub fn fail_write_acks(&self, count: usize) {
+        self.store.lock().fail_write_acks(count);
+    }
+
+    /// Deletes all documents, and aborts any open transactions.
+    pub fn clear(&self) {
+        self.store.lock().clear();
+    }
+}
+
+impl Drop for FakeFirestore {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
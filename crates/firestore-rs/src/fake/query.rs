@@ -0,0 +1,369 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use protos::firestore::structured_query::composite_filter::Operator as CompositeOperator;
+use protos::firestore::structured_query::field_filter::Operator as FieldOperator;
+use protos::firestore::structured_query::filter::FilterType;
+use protos::firestore::structured_query::unary_filter::{OperandType, Operator as UnaryOperator};
+use protos::firestore::structured_query::{
+    CollectionSelector, Direction, FieldFilter, FieldReference, Filter, Order, UnaryFilter,
+};
+use protos::firestore::value::ValueType;
+use protos::firestore::{Cursor, Document, DocumentMask, StructuredQuery, Value};
+use tonic::Status;
+
+use super::store::{apply_mask, values_equal};
+use crate::value::ValueRef;
+
+const NAME_FIELD: &str = "__name__";
+
+/// Evaluates a query against all documents, returning the matching documents in order.
+pub(super) fn run_query(
+    documents: &BTreeMap<String, Document>,
+    parent: &str,
+    query: &StructuredQuery,
+) -> Result<Vec<Document>, Status> {
+    let [selector] = query.from.as_slice() else {
+        return Err(Status::invalid_argument(
+            "queries must select from exactly one collection",
+        ));
+    };
+
+    let mut matches = Vec::new();
+
+    for document in documents.values() {
+        if !in_collection(parent, selector, &document.name) {
+            continue;
+        }
+
+        if let Some(ref filter) = query.r#where
+            && !eval_filter(document, filter)?
+        {
+            continue;
+        }
+
+        matches.push(document);
+    }
+
+    let orders = effective_order_by(query);
+
+    // documents missing a field used for ordering never match the query
+    matches.retain(|doc| orders.iter().all(|order| order_value(doc, order).is_some()));
+
+    matches.sort_by(|a, b| cmp_documents(a, b, &orders));
+
+    if let Some(ref start_at) = query.start_at {
+        matches.retain(|doc| match cmp_to_cursor(doc, start_at, &orders) {
+            Ordering::Greater => true,
+            Ordering::Equal => start_at.before,
+            Ordering::Less => false,
+        });
+    }
+
+    if let Some(ref end_at) = query.end_at {
+        matches.retain(|doc| match cmp_to_cursor(doc, end_at, &orders) {
+            Ordering::Less => true,
+            Ordering::Equal => !end_at.before,
+            Ordering::Greater => false,
+        });
+    }
+
+    let limit = query
+        .limit
+        .map(|limit| limit.value.max(0) as usize)
+        .unwrap_or(usize::MAX);
+
+    let mask = query.select.as_ref().map(|select| DocumentMask {
+        field_paths: select
+            .fields
+            .iter()
+            .filter(|field| field.field_path != NAME_FIELD)
+            .map(|field| field.field_path.clone())
+            .collect(),
+    });
+
+    Ok(matches
+        .into_iter()
+        .skip(query.offset.max(0) as usize)
+        .take(limit)
+        .map(|doc| apply_mask(doc.clone(), mask.as_ref()))
+        .collect())
+}
+
+fn in_collection(parent: &str, selector: &CollectionSelector, name: &str) -> bool {
+    let Some(relative) = name
+        .strip_prefix(parent)
+        .and_then(|rem| rem.strip_prefix('/'))
+    else {
+        return false;
+    };
+
+    let components = relative.split('/').collect::<Vec<_>>();
+
+    if selector.all_descendants {
+        components.len() >= 2 && components[components.len() - 2] == selector.collection_id
+    } else {
+        components.len() == 2 && components[0] == selector.collection_id
+    }
+}
+
+/// Builds the full ordering used by the backend: explicit orderings, then any fields used in
+/// inequality filters, then the document name.
+fn effective_order_by(query: &StructuredQuery) -> Vec<Order> {
+    let mut orders = query.order_by.clone();
+
+    if let Some(ref filter) = query.r#where {
+        let mut inequality_fields = Vec::new();
+        collect_inequality_fields(filter, &mut inequality_fields);
+        inequality_fields.sort();
+        inequality_fields.dedup();
+
+        let direction = orders
+            .last()
+            .map(|order| order.direction)
+            .unwrap_or(Direction::Ascending as i32);
+
+        for field_path in inequality_fields {
+            if !orders.iter().any(|order| order_field(order) == field_path) {
+                orders.push(Order {
+                    field: Some(FieldReference { field_path }),
+                    direction,
+                });
+            }
+        }
+    }
+
+    if !orders.iter().any(|order| order_field(order) == NAME_FIELD) {
+        let direction = orders
+            .last()
+            .map(|order| order.direction)
+            .unwrap_or(Direction::Ascending as i32);
+
+        orders.push(Order {
+            field: Some(FieldReference {
+                field_path: NAME_FIELD.to_owned(),
+            }),
+            direction,
+        });
+    }
+
+    orders
+}
+
+fn collect_inequality_fields(filter: &Filter, dst: &mut Vec<String>) {
+    match filter.filter_type {
+        Some(FilterType::CompositeFilter(ref composite)) => {
+            for filter in composite.filters.iter() {
+                collect_inequality_fields(filter, dst);
+            }
+        }
+        Some(FilterType::FieldFilter(ref field_filter)) => {
+            let is_inequality = matches!(
+                field_filter.op(),
+                FieldOperator::LessThan
+                    | FieldOperator::LessThanOrEqual
+                    | FieldOperator::GreaterThan
+                    | FieldOperator::GreaterThanOrEqual
+                    | FieldOperator::NotEqual
+                    | FieldOperator::NotIn
+            );
+
+            if is_inequality && let Some(ref field) = field_filter.field {
+                dst.push(field.field_path.clone());
+            }
+        }
+        Some(FilterType::UnaryFilter(ref unary)) => {
+            let is_inequality = matches!(
+                unary.op(),
+                UnaryOperator::IsNotNan | UnaryOperator::IsNotNull
+            );
+
+            if is_inequality && let Some(OperandType::Field(ref field)) = unary.operand_type {
+                dst.push(field.field_path.clone());
+            }
+        }
+        None => (),
+    }
+}
+
+fn order_field(order: &Order) -> &str {
+    order
+        .field
+        .as_ref()
+        .map(|field| field.field_path.as_str())
+        .unwrap_or_default()
+}
+
+fn order_value(document: &Document, order: &Order) -> Option<Value> {
+    field_value(document, order_field(order))
+}
+
+/// Extracts a field value, treating `__name__` as the document reference.
+fn field_value(document: &Document, field_path: &str) -> Option<Value> {
+    if field_path == NAME_FIELD {
+        return Some(Value {
+            value_type: Some(ValueType::ReferenceValue(document.name.clone())),
+        });
+    }
+
+    crate::util::extract_value(&document.fields, field_path).cloned()
+}
+
+fn apply_direction(order: &Order, cmp: Ordering) -> Ordering {
+    match order.direction() {
+        Direction::Descending => cmp.reverse(),
+        _ => cmp,
+    }
+}
+
+fn cmp_values(a: &Value, b: &Value) -> Ordering {
+    ValueRef::from_proto_ref(a).ord_cmp(ValueRef::from_proto_ref(b))
+}
+
+fn cmp_documents(a: &Document, b: &Document, orders: &[Order]) -> Ordering {
+    for order in orders {
+        let cmp = match (order_value(a, order), order_value(b, order)) {
+            (Some(a), Some(b)) => cmp_values(&a, &b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+
+        let cmp = apply_direction(order, cmp);
+
+        if cmp.is_ne() {
+            return cmp;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Compares a document against a cursor, in terms of the query ordering. Cursors can specify
+/// fewer values than there are orderings, in which case only that prefix is compared.
+fn cmp_to_cursor(document: &Document, cursor: &Cursor, orders: &[Order]) -> Ordering {
+    for (cursor_value, order) in cursor.values.iter().zip(orders) {
+        let Some(value) = order_value(document, order) else {
+            return Ordering::Less;
+        };
+
+        let cmp = apply_direction(order, cmp_values(&value, cursor_value));
+
+        if cmp.is_ne() {
+            return cmp;
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn eval_filter(document: &Document, filter: &Filter) -> Result<bool, Status> {
+    match filter.filter_type {
+        Some(FilterType::CompositeFilter(ref composite)) => {
+            let mut results = composite
+                .filters
+                .iter()
+                .map(|filter| eval_filter(document, filter));
+
+            match composite.op() {
+                CompositeOperator::And => results.try_fold(true, |acc, res| Ok(acc && res?)),
+                CompositeOperator::Or => results.try_fold(false, |acc, res| Ok(acc || res?)),
+                CompositeOperator::Unspecified => Err(Status::invalid_argument(
+                    "composite filter is missing an operator",
+                )),
+            }
+        }
+        Some(FilterType::FieldFilter(ref field_filter)) => {
+            eval_field_filter(document, field_filter)
+        }
+        Some(FilterType::UnaryFilter(ref unary)) => eval_unary_filter(document, unary),
+        None => Ok(true),
+    }
+}
+
+fn eval_field_filter(document: &Document, filter: &FieldFilter) -> Result<bool, Status> {
+    let field_path = filter
+        .field
+        .as_ref()
+        .map(|field| field.field_path.as_str())
+        .ok_or_else(|| Status::invalid_argument("field filter is missing a field"))?;
+
+    let operand = filter
+        .value
+        .as_ref()
+        .ok_or_else(|| Status::invalid_argument("field filter is missing a value"))?;
+
+    // no filter matches documents that are missing the field entirely
+    let Some(value) = field_value(document, field_path) else {
+        return Ok(false);
+    };
+
+    let range = |accept: fn(Ordering) -> bool| {
+        let a = ValueRef::from_proto_ref(&value);
+        let b = ValueRef::from_proto_ref(operand);
+        a.is_same_type_order(b) && !is_nan(&value) && !is_nan(operand) && accept(a.ord_cmp(b))
+    };
+
+    let operand_values = || match operand.value_type {
+        Some(ValueType::ArrayValue(ref array)) => Ok(array.values.as_slice()),
+        _ => Err(Status::invalid_argument(
+            "'in', 'not-in' and 'array-contains-any' filters require an array value",
+        )),
+    };
+
+    let array_values = match value.value_type {
+        Some(ValueType::ArrayValue(ref array)) => array.values.as_slice(),
+        _ => &[],
+    };
+
+    Ok(match filter.op() {
+        FieldOperator::LessThan => range(Ordering::is_lt),
+        FieldOperator::LessThanOrEqual => range(Ordering::is_le),
+        FieldOperator::GreaterThan => range(Ordering::is_gt),
+        FieldOperator::GreaterThanOrEqual => range(Ordering::is_ge),
+        FieldOperator::Equal => values_equal(&value, operand),
+        FieldOperator::NotEqual => !is_null(&value) && !values_equal(&value, operand),
+        FieldOperator::ArrayContains => array_values.iter().any(|v| values_equal(v, operand)),
+        FieldOperator::In => operand_values()?.iter().any(|v| values_equal(&value, v)),
+        FieldOperator::ArrayContainsAny => {
+            let operands = operand_values()?;
+            array_values
+                .iter()
+                .any(|v| operands.iter().any(|op| values_equal(v, op)))
+        }
+        FieldOperator::NotIn => {
+            !is_null(&value) && !operand_values()?.iter().any(|v| values_equal(&value, v))
+        }
+        FieldOperator::Unspecified => {
+            return Err(Status::invalid_argument(
+                "field filter is missing an operator",
+            ));
+        }
+    })
+}
+
+fn eval_unary_filter(document: &Document, filter: &UnaryFilter) -> Result<bool, Status> {
+    let Some(OperandType::Field(ref field)) = filter.operand_type else {
+        return Err(Status::invalid_argument("unary filter is missing a field"));
+    };
+
+    let value = field_value(document, &field.field_path);
+
+    Ok(match filter.op() {
+        UnaryOperator::IsNan => value.as_ref().is_some_and(is_nan),
+        UnaryOperator::IsNull => value.as_ref().is_some_and(is_null),
+        UnaryOperator::IsNotNan => value.as_ref().is_some_and(|v| !is_nan(v)),
+        UnaryOperator::IsNotNull => value.as_ref().is_some_and(|v| !is_null(v)),
+        UnaryOperator::Unspecified => {
+            return Err(Status::invalid_argument(
+                "unary filter is missing an operator",
+            ));
+        }
+    })
+}
+
+fn is_nan(value: &Value) -> bool {
+    matches!(value.value_type, Some(ValueType::DoubleValue(d)) if d.is_nan())
+}
+
+fn is_null(value: &Value) -> bool {
+    matches!(value.value_type, None | Some(ValueType::NullValue(_)))
+}
@@ -0,0 +1,455 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use protos::firestore::{
+    BatchGetDocumentsRequest, BatchGetDocumentsResponse, BatchWriteRequest, BatchWriteResponse,
+    BeginTransactionRequest, BeginTransactionResponse, CommitRequest, CommitResponse,
+    DeleteDocumentRequest, Document, GetDocumentRequest, ListCollectionIdsRequest,
+    ListCollectionIdsResponse, RollbackRequest, RunQueryRequest, RunQueryResponse,
+    UpdateDocumentRequest, Write, WriteRequest, WriteResponse, batch_get_documents_response,
+    get_document_request, run_query_request, run_query_response, write,
+};
+use protos::protobuf::Empty;
+use tonic::body::Body;
+use tonic::server::{Grpc, NamedService};
+use tonic::{Status, Streaming};
+use tonic_prost::ProstCodec;
+
+use super::query;
+use super::store::{Store, apply_mask};
+
+/// Hand-rolled equivalent of the tonic generated `FirestoreServer`, since the protos are only
+/// generated with client code.
+#[derive(Debug, Clone)]
+pub(super) struct FirestoreService {
+    pub(super) store: Arc<Mutex<Store>>,
+}
+
+impl NamedService for FirestoreService {
+    const NAME: &'static str = "google.firestore.v1.Firestore";
+}
+
+impl tower::Service<http::Request<Body>> for FirestoreService {
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+        let store = Arc::clone(&self.store);
+
+        Box::pin(async move {
+            let method = req
+                .uri()
+                .path()
+                .strip_prefix("/google.firestore.v1.Firestore/")
+                .unwrap_or_default()
+                .to_owned();
+
+            let response = match method.as_str() {
+                "GetDocument" => unary(store, req, get_document).await,
+                "UpdateDocument" => unary(store, req, update_document).await,
+                "DeleteDocument" => unary(store, req, delete_document).await,
+                "BatchGetDocuments" => server_streaming(store, req, batch_get_documents).await,
+                "BeginTransaction" => unary(store, req, begin_transaction).await,
+                "Commit" => unary(store, req, commit).await,
+                "Rollback" => unary(store, req, rollback).await,
+                "RunQuery" => server_streaming(store, req, run_query).await,
+                "ListCollectionIds" => unary(store, req, list_collection_ids).await,
+                "BatchWrite" => unary(store, req, batch_write).await,
+                "Write" => write_stream(store, req).await,
+                _ => Status::unimplemented(format!(
+                    "'{method}' is not supported by the fake Firestore server"
+                ))
+                .into_http(),
+            };
+
+            Ok(response)
+        })
+    }
+}
+
+async fn unary<Req, Resp>(
+    store: Arc<Mutex<Store>>,
+    req: http::Request<Body>,
+    handler: fn(&mut Store, Req) -> Result<Resp, Status>,
+) -> http::Response<Body>
+where
+    Req: prost::Message + Default + Send + 'static,
+    Resp: prost::Message + Send + 'static,
+{
+    let service = tower::service_fn(move |request: tonic::Request<Req>| {
+        let result = handler(&mut store.lock(), request.into_inner()).map(tonic::Response::new);
+        std::future::ready(result)
+    });
+
+    Grpc::new(ProstCodec::default()).unary(service, req).await
+}
+
+async fn server_streaming<Req, Resp>(
+    store: Arc<Mutex<Store>>,
+    req: http::Request<Body>,
+    handler: fn(&mut Store, Req) -> Result<Vec<Resp>, Status>,
+) -> http::Response<Body>
+where
+    Req: prost::Message + Default + Send + 'static,
+    Resp: prost::Message + Send + 'static,
+{
+    let service = tower::service_fn(move |request: tonic::Request<Req>| {
+        // the store can't be locked across await points, so responses are built up front
+        let result = handler(&mut store.lock(), request.into_inner()).map(|responses| {
+            tonic::Response::new(futures::stream::iter(responses.into_iter().map(Ok)))
+        });
+        std::future::ready(result)
+    });
+
+    Grpc::new(ProstCodec::default())
+        .server_streaming(service, req)
+        .await
+}
+
+async fn write_stream(store: Arc<Mutex<Store>>, req: http::Request<Body>) -> http::Response<Body> {
+    let service = tower::service_fn(move |request: tonic::Request<Streaming<WriteRequest>>| {
+        let state = WriteStreamState {
+            store: Arc::clone(&store),
+            requests: request.into_inner(),
+            stream_id: None,
+            next_token: 0,
+        };
+
+        // each request gets exactly one response. Errors end the stream, same as the real
+        // backend.
+        let responses = futures::stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+
+            let result = match state.requests.message().await {
+                Ok(Some(request)) => state.handle_request(request),
+                Ok(None) => return None,
+                Err(status) => Err(status),
+            };
+
+            match result {
+                Ok(response) => Some((Ok(response), Some(state))),
+                Err(status) => Some((Err(status), None)),
+            }
+        });
+
+        std::future::ready(Ok::<_, Status>(tonic::Response::new(responses)))
+    });
+
+    Grpc::new(ProstCodec::default())
+        .streaming(service, req)
+        .await
+}
+
+struct WriteStreamState {
+    store: Arc<Mutex<Store>>,
+    requests: Streaming<WriteRequest>,
+    /// Set after the handshake (the first request on the stream).
+    stream_id: Option<String>,
+    next_token: u64,
+}
+
+impl WriteStreamState {
+    fn handle_request(&mut self, request: WriteRequest) -> Result<WriteResponse, Status> {
+        let mut store = self.store.lock();
+
+        if store.take_write_stream_failure() {
+            return Err(Status::unavailable("injected write stream failure"));
+        }
+
+        self.next_token += 1;
+        let stream_token = Bytes::copy_from_slice(&self.next_token.to_be_bytes());
+
+        if self.stream_id.is_none() {
+            if !request.writes.is_empty() {
+                return Err(Status::invalid_argument(
+                    "the first request on a write stream can't contain writes",
+                ));
+            }
+
+            // resuming is always allowed, since the fake applies writes immediately.
+            let stream_id = match request.stream_id.is_empty() {
+                true => format!("stream-{}", store.now().as_nanos()),
+                false => request.stream_id,
+            };
+
+            self.stream_id = Some(stream_id.clone());
+
+            return Ok(WriteResponse {
+                stream_id,
+                stream_token,
+                write_results: Vec::new(),
+                commit_time: None,
+            });
+        }
+
+        let (write_results, commit_time) = store.commit(None, request.writes)?;
+
+        if store.take_write_ack_failure() {
+            return Err(Status::unavailable(
+                "injected write stream failure after applying writes",
+            ));
+        }
+
+        Ok(WriteResponse {
+            stream_id: String::new(),
+            stream_token,
+            write_results,
+            commit_time: Some(commit_time.into()),
+        })
+    }
+}
+
+fn get_document(store: &mut Store, request: GetDocumentRequest) -> Result<Document, Status> {
+    let transaction = match request.consistency_selector {
+        Some(get_document_request::ConsistencySelector::Transaction(transaction)) => {
+            Some(transaction)
+        }
+        _ => None,
+    };
+
+    match store.get(&request.name, transaction.as_ref())? {
+        Some(document) => Ok(apply_mask(document, request.mask.as_ref())),
+        None => Err(Status::not_found(format!(
+            "document '{}' not found",
+            request.name
+        ))),
+    }
+}
+
+fn update_document(store: &mut Store, request: UpdateDocumentRequest) -> Result<Document, Status> {
+    let document = request
+        .document
+        .ok_or_else(|| Status::invalid_argument("missing document"))?;
+
+    let name = document.name.clone();
+
+    let write = Write {
+        update_mask: request.update_mask,
+        update_transforms: Vec::new(),
+        current_document: request.current_document,
+        operation: Some(write::Operation::Update(document)),
+    };
+
+    store.commit(None, vec![write])?;
+
+    let document = store
+        .get(&name, None)?
+        .ok_or_else(|| Status::internal("document missing after update"))?;
+
+    Ok(apply_mask(document, request.mask.as_ref()))
+}
+
+fn delete_document(store: &mut Store, request: DeleteDocumentRequest) -> Result<Empty, Status> {
+    let write = Write {
+        update_mask: None,
+        update_transforms: Vec::new(),
+        current_document: request.current_document,
+        operation: Some(write::Operation::Delete(request.name)),
+    };
+
+    store.commit(None, vec![write])?;
+    Ok(Empty {})
+}
+
+fn batch_get_documents(
+    store: &mut Store,
+    request: BatchGetDocumentsRequest,
+) -> Result<Vec<BatchGetDocumentsResponse>, Status> {
+    use protos::firestore::batch_get_documents_request::ConsistencySelector;
+
+    let (transaction, new_transaction) = match request.consistency_selector {
+        Some(ConsistencySelector::Transaction(transaction)) => (Some(transaction), false),
+        Some(ConsistencySelector::NewTransaction(options)) => {
+            (Some(store.begin_transaction(Some(options))), true)
+        }
+        _ => (None, false),
+    };
+
+    let read_time = store.now();
+    let mut responses = Vec::with_capacity(request.documents.len());
+
+    for name in request.documents {
+        let result = match store.get(&name, transaction.as_ref())? {
+            Some(document) => batch_get_documents_response::Result::Found(apply_mask(
+                document,
+                request.mask.as_ref(),
+            )),
+            None => batch_get_documents_response::Result::Missing(name),
+        };
+
+        responses.push(BatchGetDocumentsResponse {
+            transaction: Bytes::new(),
+            read_time: Some(read_time.into()),
+            result: Some(result),
+        });
+    }
+
+    // a newly started transaction is returned in the first response
+    if new_transaction && let Some(transaction) = transaction {
+        match responses.first_mut() {
+            Some(first) => first.transaction = transaction,
+            None => responses.push(BatchGetDocumentsResponse {
+                transaction,
+                read_time: Some(read_time.into()),
+                result: None,
+            }),
+        }
+    }
+
+    Ok(responses)
+}
+
+fn begin_transaction(
+    store: &mut Store,
+    request: BeginTransactionRequest,
+) -> Result<BeginTransactionResponse, Status> {
+    Ok(BeginTransactionResponse {
+        transaction: store.begin_transaction(request.options),
+    })
+}
+
+fn commit(store: &mut Store, request: CommitRequest) -> Result<CommitResponse, Status> {
+    let transaction = (!request.transaction.is_empty()).then_some(&request.transaction);
+    let (write_results, commit_time) = store.commit(transaction, request.writes)?;
+
+    Ok(CommitResponse {
+        write_results,
+        commit_time: Some(commit_time.into()),
+    })
+}
+
+fn rollback(store: &mut Store, request: RollbackRequest) -> Result<Empty, Status> {
+    store.rollback(&request.transaction)?;
+    Ok(Empty {})
+}
+
+fn run_query(store: &mut Store, request: RunQueryRequest) -> Result<Vec<RunQueryResponse>, Status> {
+    let Some(run_query_request::QueryType::StructuredQuery(query)) = request.query_type else {
+        return Err(Status::invalid_argument("missing structured query"));
+    };
+
+    let (transaction, new_transaction) = match request.consistency_selector {
+        Some(run_query_request::ConsistencySelector::Transaction(transaction)) => {
+            (Some(transaction), false)
+        }
+        Some(run_query_request::ConsistencySelector::NewTransaction(options)) => {
+            (Some(store.begin_transaction(Some(options))), true)
+        }
+        _ => (None, false),
+    };
+
+    let documents = query::run_query(store.documents(), &request.parent, &query)?;
+
+    if let Some(ref transaction) = transaction {
+        for document in documents.iter() {
+            store.record_read(transaction, &document.name, Some(document))?;
+        }
+    }
+
+    let read_time = store.now();
+
+    let mut responses = documents
+        .into_iter()
+        .map(|document| RunQueryResponse {
+            transaction: Bytes::new(),
+            document: Some(document),
+            read_time: Some(read_time.into()),
+            skipped_results: 0,
+            explain_metrics: None,
+            continuation_selector: None,
+        })
+        .collect::<Vec<_>>();
+
+    responses.push(RunQueryResponse {
+        transaction: Bytes::new(),
+        document: None,
+        read_time: Some(read_time.into()),
+        skipped_results: 0,
+        explain_metrics: None,
+        continuation_selector: Some(run_query_response::ContinuationSelector::Done(true)),
+    });
+
+    if new_transaction && let Some(transaction) = transaction {
+        responses[0].transaction = transaction;
+    }
+
+    Ok(responses)
+}
+
+fn list_collection_ids(
+    store: &mut Store,
+    request: ListCollectionIdsRequest,
+) -> Result<ListCollectionIdsResponse, Status> {
+    let prefix = format!("{}/", request.parent);
+
+    let mut collection_ids = store
+        .documents()
+        .range(prefix.clone()..)
+        .map(|(name, _)| name)
+        .take_while(|name| name.starts_with(&prefix))
+        .filter_map(|name| name[prefix.len()..].split('/').next())
+        .filter(|id| *id > request.page_token.as_str())
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+
+    collection_ids.sort_unstable();
+    collection_ids.dedup();
+
+    let page_size = match request.page_size {
+        size if size > 0 => size as usize,
+        _ => usize::MAX,
+    };
+
+    let next_page_token = if collection_ids.len() > page_size {
+        collection_ids.truncate(page_size);
+        collection_ids.last().cloned().unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    Ok(ListCollectionIdsResponse {
+        collection_ids,
+        next_page_token,
+    })
+}
+
+fn batch_write(
+    store: &mut Store,
+    request: BatchWriteRequest,
+) -> Result<BatchWriteResponse, Status> {
+    let mut response = BatchWriteResponse {
+        write_results: Vec::with_capacity(request.writes.len()),
+        status: Vec::with_capacity(request.writes.len()),
+    };
+
+    // unlike commits, batch writes are applied individually and can partially fail.
+    for write in request.writes {
+        match store.commit(None, vec![write]) {
+            Ok((mut results, _)) => {
+                response
+                    .write_results
+                    .push(results.pop().unwrap_or_default());
+                response.status.push(protos::rpc::Status::default());
+            }
+            Err(status) => {
+                response.write_results.push(Default::default());
+                response.status.push(protos::rpc::Status {
+                    code: status.code() as i32,
+                    message: status.message().to_owned(),
+                    details: Vec::new(),
+                });
+            }
+        }
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,513 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bytes::Bytes;
+use protos::firestore::document_transform::FieldTransform;
+use protos::firestore::document_transform::field_transform::{ServerValue, TransformType};
+use protos::firestore::precondition::ConditionType;
+use protos::firestore::transaction_options::Mode;
+use protos::firestore::value::ValueType;
+use protos::firestore::{
+    ArrayValue, Document, DocumentMask, MapValue, Precondition, TransactionOptions, Value, Write,
+    WriteResult, write,
+};
+use timestamp::Timestamp;
+use tonic::Status;
+
+use crate::ser;
+use crate::value::ValueRef;
+
+/// The in-memory database backing a [`FakeFirestore`].
+///
+/// [`FakeFirestore`]: super::FakeFirestore
+#[derive(Debug, Default)]
+pub(super) struct Store {
+    /// All documents, keyed by their fully qualified name.
+    documents: BTreeMap<String, Document>,
+    transactions: HashMap<Bytes, TransactionState>,
+    next_transaction_id: u64,
+    last_time: Option<Timestamp>,
+    /// The number of upcoming `Write` stream messages that should fail, to simulate a flaky
+    /// connection.
+    write_stream_failures: usize,
+    /// The number of upcoming `Write` stream messages that should have their writes applied,
+    /// but fail before they're acknowledged.
+    write_ack_failures: usize,
+}
+
+#[derive(Debug, Default)]
+struct TransactionState {
+    read_only: bool,
+    /// Documents read in this transaction, along with their update time at the time of the
+    /// read ([`None`] if it didn't exist). If any of these change before the transaction commits,
+    /// the commit is aborted, mimicking lock contention in the real backend.
+    reads: HashMap<String, Option<Timestamp>>,
+}
+
+impl Store {
+    pub(super) fn clear(&mut self) {
+        self.documents.clear();
+        self.transactions.clear();
+    }
+
+    pub(super) fn fail_write_streams(&mut self, count: usize) {
+        self.write_stream_failures = count;
+    }
+
+    /// Returns true (and decrements the count) if the current `Write` stream message should
+    /// fail.
+    pub(super) fn take_write_stream_failure(&mut self) -> bool {
+        match self.write_stream_failures.checked_sub(1) {
+            Some(remaining) => {
+                self.write_stream_failures = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn fail_write_acks(&mut self, count: usize) {
+        self.write_ack_failures = count;
+    }
+
+    /// Returns true (and decrements the count) if the writes that were just applied shouldn't
+    /// be acknowledged.
+    pub(super) fn take_write_ack_failure(&mut self) -> bool {
+        match self.write_ack_failures.checked_sub(1) {
+            Some(remaining) => {
+                self.write_ack_failures = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn documents(&self) -> &BTreeMap<String, Document> {
+        &self.documents
+    }
+
+    /// Returns the current time, guaranteeing it's always after the previously returned time
+    /// so update times are unique.
+    pub(super) fn now(&mut self) -> Timestamp {
+        let mut now = Timestamp::now();
+
+        if let Some(last) = self.last_time
+            && now <= last
+        {
+            now = Timestamp::from_nanos_i128(last.as_nanos() + 1000);
+        }
+
+        self.last_time = Some(now);
+        now
+    }
+
+    pub(super) fn begin_transaction(&mut self, options: Option<TransactionOptions>) -> Bytes {
+        let read_only = matches!(options.and_then(|opts| opts.mode), Some(Mode::ReadOnly(_)));
+
+        self.next_transaction_id += 1;
+        let id = Bytes::copy_from_slice(&self.next_transaction_id.to_be_bytes());
+
+        self.transactions.insert(
+            id.clone(),
+            TransactionState {
+                read_only,
+                reads: HashMap::new(),
+            },
+        );
+
+        id
+    }
+
+    pub(super) fn rollback(&mut self, transaction: &Bytes) -> Result<(), Status> {
+        match self.transactions.remove(transaction) {
+            Some(_) => Ok(()),
+            None => Err(invalid_transaction()),
+        }
+    }
+
+    /// Gets a document, recording the read if done within a transaction.
+    pub(super) fn get(
+        &mut self,
+        name: &str,
+        transaction: Option<&Bytes>,
+    ) -> Result<Option<Document>, Status> {
+        let document = self.documents.get(name).cloned();
+
+        if let Some(transaction) = transaction {
+            self.record_read(transaction, name, document.as_ref())?;
+        }
+
+        Ok(document)
+    }
+
+    pub(super) fn record_read(
+        &mut self,
+        transaction: &Bytes,
+        name: &str,
+        document: Option<&Document>,
+    ) -> Result<(), Status> {
+        let state = self
+            .transactions
+            .get_mut(transaction)
+            .ok_or_else(invalid_transaction)?;
+
+        let update_time = document
+            .and_then(|doc| doc.update_time)
+            .map(Timestamp::from);
+        state.reads.entry(name.to_owned()).or_insert(update_time);
+        Ok(())
+    }
+
+    /// Atomically applies a set of writes, optionally as part of a transaction.
+    pub(super) fn commit(
+        &mut self,
+        transaction: Option<&Bytes>,
+        writes: Vec<Write>,
+    ) -> Result<(Vec<WriteResult>, Timestamp), Status> {
+        if let Some(transaction) = transaction {
+            let state = self
+                .transactions
+                .remove(transaction)
+                .ok_or_else(invalid_transaction)?;
+
+            if state.read_only && !writes.is_empty() {
+                return Err(Status::invalid_argument(
+                    "cannot write in a read-only transaction",
+                ));
+            }
+
+            for (name, read_update_time) in state.reads {
+                let current = self
+                    .documents
+                    .get(&name)
+                    .and_then(|doc| doc.update_time)
+                    .map(Timestamp::from);
+
+                if current != read_update_time {
+                    return Err(Status::aborted(format!(
+                        "transaction aborted, '{name}' was modified after being read"
+                    )));
+                }
+            }
+        }
+
+        let commit_time = self.now();
+
+        // stage all writes so nothing is applied if any of them fail
+        let mut staged: BTreeMap<String, Option<Document>> = BTreeMap::new();
+        let mut results = Vec::with_capacity(writes.len());
+
+        for write in writes {
+            let name = write_name(&write)?.to_owned();
+
+            let current = match staged.get(&name) {
+                Some(staged) => staged.as_ref(),
+                None => self.documents.get(&name),
+            };
+
+            let (document, result) = apply_write(current, write, commit_time)?;
+            staged.insert(name, document);
+            results.push(result);
+        }
+
+        for (name, document) in staged {
+            match document {
+                Some(document) => self.documents.insert(name, document),
+                None => self.documents.remove(&name),
+            };
+        }
+
+        Ok((results, commit_time))
+    }
+}
+
+fn invalid_transaction() -> Status {
+    Status::invalid_argument("transaction is invalid or has already completed")
+}
+
+fn write_name(write: &Write) -> Result<&str, Status> {
+    match write.operation {
+        Some(write::Operation::Update(ref document)) => Ok(&document.name),
+        Some(write::Operation::Delete(ref name)) => Ok(name),
+        Some(write::Operation::Transform(ref transform)) => Ok(&transform.document),
+        None => Err(Status::invalid_argument("write is missing an operation")),
+    }
+}
+
+fn check_precondition(
+    current: Option<&Document>,
+    precondition: Option<Precondition>,
+) -> Result<(), Status> {
+    match precondition.and_then(|precond| precond.condition_type) {
+        None => Ok(()),
+        Some(ConditionType::Exists(true)) if current.is_none() => {
+            Err(Status::not_found("document does not exist"))
+        }
+        Some(ConditionType::Exists(false)) if current.is_some() => {
+            Err(Status::already_exists("document already exists"))
+        }
+        Some(ConditionType::Exists(_)) => Ok(()),
+        Some(ConditionType::UpdateTime(update_time)) => {
+            match current.and_then(|doc| doc.update_time) {
+                Some(current) if current == update_time => Ok(()),
+                _ => Err(Status::failed_precondition(
+                    "document update time does not match the precondition",
+                )),
+            }
+        }
+    }
+}
+
+/// Applies a single write to the current version of a document, returning the new version
+/// ([`None`] if deleted).
+fn apply_write(
+    current: Option<&Document>,
+    write: Write,
+    time: Timestamp,
+) -> Result<(Option<Document>, WriteResult), Status> {
+    check_precondition(current, write.current_document)?;
+
+    let (name, mut fields, mut transforms) = match write.operation {
+        Some(write::Operation::Delete(_)) => {
+            let result = WriteResult {
+                update_time: Some(time.into()),
+                transform_results: Vec::new(),
+            };
+            return Ok((None, result));
+        }
+        Some(write::Operation::Update(document)) => {
+            let fields = match write.update_mask {
+                Some(mask) => {
+                    let mut fields = current.map(|doc| doc.fields.clone()).unwrap_or_default();
+
+                    for path in mask.field_paths.iter() {
+                        match crate::util::extract_value(&document.fields, path) {
+                            Some(value) => set_field(&mut fields, path, value.clone()),
+                            None => remove_field(&mut fields, path),
+                        }
+                    }
+
+                    fields
+                }
+                None => document.fields,
+            };
+
+            (document.name, fields, Vec::new())
+        }
+        Some(write::Operation::Transform(transform)) => {
+            let fields = current.map(|doc| doc.fields.clone()).unwrap_or_default();
+            (transform.document, fields, transform.field_transforms)
+        }
+        None => return Err(Status::invalid_argument("write is missing an operation")),
+    };
+
+    transforms.extend(write.update_transforms);
+
+    let transform_results = transforms
+        .into_iter()
+        .map(|transform| apply_transform(&mut fields, transform, time))
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    let document = Document {
+        name,
+        fields,
+        create_time: current
+            .and_then(|doc| doc.create_time)
+            .or(Some(time.into())),
+        update_time: Some(time.into()),
+    };
+
+    let result = WriteResult {
+        update_time: Some(time.into()),
+        transform_results,
+    };
+
+    Ok((Some(document), result))
+}
+
+fn apply_transform(
+    fields: &mut HashMap<String, Value>,
+    transform: FieldTransform,
+    time: Timestamp,
+) -> Result<Value, Status> {
+    let current = crate::util::extract_value(fields, &transform.field_path)
+        .and_then(|value| value.value_type.as_ref());
+
+    let new_value = match transform.transform_type {
+        Some(TransformType::SetToServerValue(server_value)) => {
+            match ServerValue::try_from(server_value) {
+                Ok(ServerValue::RequestTime) => ValueType::TimestampValue(time.into()),
+                _ => return Err(Status::invalid_argument("unknown server value")),
+            }
+        }
+        Some(TransformType::Increment(operand)) => {
+            let operand = operand.value_type.ok_or_else(missing_operand)?;
+            increment(current, operand)?
+        }
+        Some(TransformType::Maximum(operand)) => {
+            let operand = operand.value_type.ok_or_else(missing_operand)?;
+            extremum(current, operand, std::cmp::Ordering::Greater)?
+        }
+        Some(TransformType::Minimum(operand)) => {
+            let operand = operand.value_type.ok_or_else(missing_operand)?;
+            extremum(current, operand, std::cmp::Ordering::Less)?
+        }
+        Some(TransformType::AppendMissingElements(to_append)) => {
+            let mut values = match current {
+                Some(ValueType::ArrayValue(array)) => array.values.clone(),
+                _ => Vec::new(),
+            };
+
+            for value in to_append.values {
+                if !values.iter().any(|existing| values_equal(existing, &value)) {
+                    values.push(value);
+                }
+            }
+
+            ValueType::ArrayValue(ArrayValue { values })
+        }
+        Some(TransformType::RemoveAllFromArray(to_remove)) => {
+            let mut values = match current {
+                Some(ValueType::ArrayValue(array)) => array.values.clone(),
+                _ => Vec::new(),
+            };
+
+            values.retain(|existing| {
+                !to_remove
+                    .values
+                    .iter()
+                    .any(|value| values_equal(existing, value))
+            });
+
+            ValueType::ArrayValue(ArrayValue { values })
+        }
+        None => return Err(Status::invalid_argument("field transform has no type")),
+    };
+
+    let new_value = Value {
+        value_type: Some(new_value),
+    };
+
+    set_field(fields, &transform.field_path, new_value.clone());
+    Ok(new_value)
+}
+
+fn missing_operand() -> Status {
+    Status::invalid_argument("field transform is missing an operand")
+}
+
+fn increment(current: Option<&ValueType>, operand: ValueType) -> Result<ValueType, Status> {
+    use ValueType::{DoubleValue, IntegerValue};
+
+    Ok(match (current, operand) {
+        (Some(IntegerValue(a)), IntegerValue(b)) => IntegerValue(a.saturating_add(b)),
+        (Some(IntegerValue(a)), DoubleValue(b)) => DoubleValue(*a as f64 + b),
+        (Some(DoubleValue(a)), IntegerValue(b)) => DoubleValue(a + b as f64),
+        (Some(DoubleValue(a)), DoubleValue(b)) => DoubleValue(a + b),
+        // non-numeric (or missing) values are overwritten by the operand
+        (_, operand @ (IntegerValue(_) | DoubleValue(_))) => operand,
+        _ => {
+            return Err(Status::invalid_argument(
+                "increment operand must be a number",
+            ));
+        }
+    })
+}
+
+fn extremum(
+    current: Option<&ValueType>,
+    operand: ValueType,
+    keep_if: std::cmp::Ordering,
+) -> Result<ValueType, Status> {
+    use ValueType::{DoubleValue, IntegerValue};
+
+    if !matches!(operand, IntegerValue(_) | DoubleValue(_)) {
+        return Err(Status::invalid_argument(
+            "maximum/minimum operand must be a number",
+        ));
+    }
+
+    match current {
+        Some(current @ (IntegerValue(_) | DoubleValue(_))) => {
+            let cmp = ValueRef::from_proto_type_ref(&operand)
+                .ord_cmp(ValueRef::from_proto_type_ref(current));
+
+            if cmp == keep_if {
+                Ok(operand)
+            } else {
+                Ok(current.clone())
+            }
+        }
+        _ => Ok(operand),
+    }
+}
+
+pub(super) fn values_equal(a: &Value, b: &Value) -> bool {
+    ValueRef::from_proto_ref(a)
+        .ord_cmp(ValueRef::from_proto_ref(b))
+        .is_eq()
+}
+
+/// Sets a (potentially nested) field, creating (or replacing) any intermediate maps.
+fn set_field(fields: &mut HashMap<String, Value>, field_path: &str, value: Value) {
+    let components = ser::split_field_path(field_path);
+    let Some((last, parents)) = components.split_last() else {
+        return;
+    };
+
+    let mut current = fields;
+
+    for component in parents {
+        let entry = current.entry(component.to_string()).or_default();
+
+        if !matches!(entry.value_type, Some(ValueType::MapValue(_))) {
+            entry.value_type = Some(ValueType::MapValue(MapValue::default()));
+        }
+
+        let Some(ValueType::MapValue(ref mut map)) = entry.value_type else {
+            unreachable!("just inserted a map value");
+        };
+
+        current = &mut map.fields;
+    }
+
+    current.insert(last.to_string(), value);
+}
+
+/// Removes a (potentially nested) field, if it exists.
+fn remove_field(fields: &mut HashMap<String, Value>, field_path: &str) {
+    let components = ser::split_field_path(field_path);
+    let Some((last, parents)) = components.split_last() else {
+        return;
+    };
+
+    let mut current = fields;
+
+    for component in parents {
+        match current
+            .get_mut(&**component)
+            .and_then(|v| v.value_type.as_mut())
+        {
+            Some(ValueType::MapValue(map)) => current = &mut map.fields,
+            _ => return,
+        }
+    }
+
+    current.remove(&**last);
+}
+
+/// Projects a document down to only the fields in `mask`.
+pub(super) fn apply_mask(mut document: Document, mask: Option<&DocumentMask>) -> Document {
+    if let Some(mask) = mask {
+        let mut fields = HashMap::with_capacity(mask.field_paths.len());
+
+        for path in mask.field_paths.iter() {
+            if let Some(value) = crate::util::extract_value(&document.fields, path) {
+                set_field(&mut fields, path, value.clone());
+            }
+        }
+
+        document.fields = fields;
+    }
+
+    document
+}
@@ -0,0 +1,356 @@
+//! Filter expressions for queries that need more than a set of AND-ed field filters.
+//!
+//! ```no_run
+//! # async fn example(firestore: &mut firestore_rs::Firestore) -> firestore_rs::Result<()> {
+//! use firestore_rs::filter::Filter;
+//!
+//! // species == "orca" AND (pod == "J" OR pod == "K")
+//! let filter = Filter::and([
+//!     Filter::field("species").equals("orca")?,
+//!     Filter::or([
+//!         Filter::field("pod").equals("J")?,
+//!         Filter::field("pod").equals("K")?,
+//!     ])?,
+//! ])?;
+//!
+//! let sightings = firestore
+//!     .collection_group("sightings")
+//!     .filter(filter)
+//!     .run_to_completion::<serde_json::Value>()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+use protos::firestore::structured_query::composite_filter::Operator as CompositeOperator;
+use protos::firestore::structured_query::field_filter::Operator as FieldOperator;
+use protos::firestore::structured_query::filter::FilterType;
+use protos::firestore::structured_query::unary_filter::{OperandType, Operator as UnaryOperator};
+use protos::firestore::structured_query::{self, CompositeFilter, FieldReference, UnaryFilter};
+use protos::firestore::value::ValueType;
+use protos::firestore::{ArrayValue, Value};
+
+use crate::ser;
+
+/// The max number of values that can be used with `in` and `array-contains-any`.
+const MAX_DISJUNCTION_VALUES: usize = 30;
+
+/// `not-in` is limited to fewer values than the other array queries.
+const MAX_NOT_IN_VALUES: usize = 10;
+
+/// A (potentially nested) filter expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter(structured_query::Filter);
+
+impl Filter {
+    /// Starts a filter on a single field.
+    pub fn field<S>(field: S) -> FieldFilterExpr
+    where
+        S: AsRef<str>,
+    {
+        FieldFilterExpr {
+            field: FieldReference {
+                field_path: ser::escape_field_path(field.as_ref()),
+            },
+        }
+    }
+
+    /// Matches documents that match all of the given filters. Returns an error if `filters` is
+    /// empty.
+    pub fn and<I>(filters: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = Filter>,
+    {
+        Self::composite(CompositeOperator::And, filters)
+    }
+
+    /// Matches documents that match any of the given filters. Returns an error if `filters` is
+    /// empty.
+    pub fn or<I>(filters: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = Filter>,
+    {
+        Self::composite(CompositeOperator::Or, filters)
+    }
+
+    fn composite<I>(op: CompositeOperator, filters: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = Filter>,
+    {
+        let mut filters = filters.into_iter().map(|filter| filter.0);
+
+        let first = filters.next().ok_or(crate::Error::InvalidArgument(
+            "cannot build a composite filter with no filters",
+        ))?;
+
+        Ok(Self(composite(op, first, filters)))
+    }
+
+    pub(crate) fn into_proto(self) -> structured_query::Filter {
+        self.0
+    }
+}
+
+/// Combines filters into a composite filter. Nested composite filters using the same operator
+/// are flattened, and a single filter is returned as-is. Taking the first filter separately
+/// means there's always at least 1 filter to combine.
+pub(crate) fn composite<I>(
+    op: CompositeOperator,
+    first: structured_query::Filter,
+    rest: I,
+) -> structured_query::Filter
+where
+    I: IntoIterator<Item = structured_query::Filter>,
+{
+    let mut flattened = Vec::new();
+
+    for filter in std::iter::once(first).chain(rest) {
+        match filter.filter_type {
+            Some(FilterType::CompositeFilter(nested)) if nested.op == op as i32 => {
+                flattened.extend(nested.filters);
+            }
+            filter_type => flattened.push(structured_query::Filter { filter_type }),
+        }
+    }
+
+    if flattened.len() == 1 {
+        return flattened.pop().unwrap();
+    }
+
+    structured_query::Filter {
+        filter_type: Some(FilterType::CompositeFilter(CompositeFilter {
+            op: op as i32,
+            filters: flattened,
+        })),
+    }
+}
+
+/// A filter on a single field. Created via [`Filter::field`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldFilterExpr {
+    field: FieldReference,
+}
+
+macro_rules! impl_unary {
+    ($(($fn_name:ident, $op_variant:ident)),* $(,)?) => {
+        $(
+            #[allow(clippy::wrong_self_convention)] // convention clashes with builder pattern
+            pub fn $fn_name(self) -> Filter {
+                self.unary(UnaryOperator::$op_variant)
+            }
+        )*
+    };
+}
+
+macro_rules! impl_field {
+    ($(($fn_name:ident, $op_variant:ident)),* $(,)?) => {
+        $(
+            pub fn $fn_name<V>(self, value: V) -> crate::Result<Filter>
+            where
+                V: serde::Serialize,
+            {
+                let value = ser::serialize_value::<ser::Update>(&value)?;
+
+                Ok(self.field_filter(FieldOperator::$op_variant, Value { value_type: Some(value) }))
+            }
+        )*
+    };
+}
+
+macro_rules! impl_array_fields {
+    ($(($fn_name:ident, $op_variant:ident, $max:expr, $too_many:literal)),* $(,)?) => {
+        $(
+            pub fn $fn_name<I>(self, values: I) -> crate::Result<Filter>
+            where
+                I: IntoIterator,
+                I::Item: serde::Serialize,
+            {
+                let values = serialize_values(values, $max, $too_many)?;
+                Ok(self.field_filter(FieldOperator::$op_variant, values))
+            }
+        )*
+    };
+}
+
+impl FieldFilterExpr {
+    impl_unary! {
+        (is_null, IsNull),
+        (is_not_null, IsNotNull),
+        (is_nan, IsNan),
+        (is_not_nan, IsNotNan),
+    }
+
+    impl_field! {
+        (less_than, LessThan),
+        (less_than_or_eq, LessThanOrEqual),
+        (greater_than, GreaterThan),
+        (greater_than_or_eq, GreaterThanOrEqual),
+        (equals, Equal),
+        (not_equals, NotEqual),
+        (array_contains, ArrayContains),
+    }
+
+    impl_array_fields! {
+        (
+            one_of,
+            In,
+            MAX_DISJUNCTION_VALUES,
+            "cannot use more than 30 values in an 'in' query"
+        ),
+        (
+            not_in,
+            NotIn,
+            MAX_NOT_IN_VALUES,
+            "cannot use more than 10 values in a 'not-in' query"
+        ),
+        (
+            array_contains_any,
+            ArrayContainsAny,
+            MAX_DISJUNCTION_VALUES,
+            "cannot use more than 30 values in an 'array-contains-any' query"
+        ),
+    }
+
+    fn unary(self, op: UnaryOperator) -> Filter {
+        Filter(structured_query::Filter {
+            filter_type: Some(FilterType::UnaryFilter(UnaryFilter {
+                operand_type: Some(OperandType::Field(self.field)),
+                op: op as i32,
+            })),
+        })
+    }
+
+    fn field_filter(self, op: FieldOperator, value: Value) -> Filter {
+        Filter(structured_query::Filter {
+            filter_type: Some(FilterType::FieldFilter(structured_query::FieldFilter {
+                field: Some(self.field),
+                op: op as i32,
+                value: Some(value),
+            })),
+        })
+    }
+}
+
+fn serialize_values<I>(values: I, max: usize, too_many: &'static str) -> crate::Result<Value>
+where
+    I: IntoIterator,
+    I::Item: serde::Serialize,
+{
+    let values = values
+        .into_iter()
+        .map(|value| {
+            ser::serialize_value::<ser::Update>(&value).map(|value_type| Value {
+                value_type: Some(value_type),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if values.is_empty() {
+        return Err(crate::Error::InvalidArgument(
+            "an array query needs at least 1 value",
+        ));
+    }
+
+    if values.len() > max {
+        return Err(crate::Error::InvalidArgument(too_many));
+    }
+
+    Ok(Value {
+        value_type: Some(ValueType::ArrayValue(ArrayValue { values })),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eq(field: &str, value: i64) -> Filter {
+        Filter::field(field).equals(value).unwrap()
+    }
+
+    fn composite_parts(filter: &Filter) -> (i32, &[structured_query::Filter]) {
+        match filter.0.filter_type {
+            Some(FilterType::CompositeFilter(ref composite)) => {
+                (composite.op, composite.filters.as_slice())
+            }
+            ref other => panic!("expected a composite filter, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_flatten_nested() -> crate::Result<()> {
+        // a AND (b AND c) => a AND b AND c
+        let filter = Filter::and([eq("a", 1), Filter::and([eq("b", 2), eq("c", 3)])?])?;
+        let (op, filters) = composite_parts(&filter);
+
+        assert_eq!(op, CompositeOperator::And as i32);
+        assert_eq!(filters, [eq("a", 1).0, eq("b", 2).0, eq("c", 3).0]);
+
+        // (a OR b) OR (c OR d) => a OR b OR c OR d
+        let filter = Filter::or([
+            Filter::or([eq("a", 1), eq("b", 2)])?,
+            Filter::or([eq("c", 3), eq("d", 4)])?,
+        ])?;
+        let (op, filters) = composite_parts(&filter);
+
+        assert_eq!(op, CompositeOperator::Or as i32);
+        assert_eq!(filters.len(), 4);
+
+        // a AND (b OR c) keeps the OR nested, since the operators differ
+        let or = Filter::or([eq("b", 2), eq("c", 3)])?;
+        let filter = Filter::and([eq("a", 1), or.clone()])?;
+        let (op, filters) = composite_parts(&filter);
+
+        assert_eq!(op, CompositeOperator::And as i32);
+        assert_eq!(filters, [eq("a", 1).0, or.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_filter_collapse() -> crate::Result<()> {
+        assert_eq!(Filter::and([eq("a", 1)])?, eq("a", 1));
+        assert_eq!(Filter::or([eq("a", 1)])?, eq("a", 1));
+
+        // collapsing happens after flattening, so a single nested filter is unwrapped too
+        let or = Filter::or([eq("a", 1), eq("b", 2)])?;
+        assert_eq!(Filter::and([or.clone()])?, or);
+        assert_eq!(Filter::or([Filter::or([eq("a", 1)])?])?, eq("a", 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(matches!(
+            Filter::and([]),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Filter::or([]),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+
+        assert!(matches!(
+            Filter::field("a").one_of(Vec::<i64>::new()),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Filter::field("a").not_in(0..=MAX_NOT_IN_VALUES as i64),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+        assert!(
+            Filter::field("a")
+                .not_in(0..MAX_NOT_IN_VALUES as i64)
+                .is_ok()
+        );
+        assert!(matches!(
+            Filter::field("a").one_of(0..=MAX_DISJUNCTION_VALUES as i64),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+        assert!(
+            Filter::field("a")
+                .array_contains_any(0..MAX_DISJUNCTION_VALUES as i64)
+                .is_ok()
+        );
+    }
+}
@@ -11,6 +11,7 @@ use crate::batch::read::{BatchRead, Document};
 use crate::batch::write::BatchWrite;
 use crate::client::FirestoreClient;
 use crate::collec::CollectionRef;
+use crate::query::QueryBuilder;
 
 const DEFAULT_DATABASE: &str = "(default)";
 
@@ -104,10 +105,60 @@ impl Firestore {
         BatchWrite::new(self.client.clone())
     }
 
+    /// Opens a [`WriteStream`] for pipelined writes.
+    ///
+    /// [`WriteStream`]: crate::write_stream::WriteStream
+    pub async fn write_stream(&self) -> crate::Result<crate::write_stream::WriteStream> {
+        crate::write_stream::WriteStream::new(self.client.clone()).await
+    }
+
+    /// Returns an admin client for managing indexes, TTL policies and exports/imports.
+    #[cfg(feature = "firestore-admin")]
+    pub fn admin(&self) -> crate::admin::FirestoreAdmin {
+        crate::admin::FirestoreAdmin::new(self.client.clone())
+    }
+
     pub fn transaction(&self) -> crate::transaction::builder::TransactionBuilder {
         crate::transaction::builder::TransactionBuilder::new(self.clone())
     }
 
+    /// Runs `transaction_fn` inside a read-write transaction, committing any queued writes once
+    /// it returns [`Ok`]. If the transaction is aborted due to contention, `transaction_fn` is
+    /// retried (with backoff) in a new transaction, so it should be free of side effects outside
+    /// of the transaction itself.
+    ///
+    /// ```no_run
+    /// # async fn example(firestore: &firestore_rs::Firestore) -> firestore_rs::Result<()> {
+    /// #[derive(serde::Deserialize, serde::Serialize)]
+    /// struct Account {
+    ///     balance: i64,
+    /// }
+    ///
+    /// let new_balance = firestore
+    ///     .run_transaction(async |tx| {
+    ///         let mut account_ref = firestore.collection("accounts").doc("alice");
+    ///
+    ///         let mut account = tx
+    ///             .get::<Account, _, _>(&account_ref)
+    ///             .await?
+    ///             .map(|doc| doc.into_inner())
+    ///             .unwrap_or(Account { balance: 0 });
+    ///
+    ///         account.balance += 10;
+    ///         tx.set(&mut account_ref, &account)?;
+    ///         Ok(account.balance)
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_transaction<F, T>(&self, transaction_fn: F) -> crate::Result<T>
+    where
+        F: AsyncFnMut(&mut crate::transaction::Transaction<Firestore>) -> crate::Result<T>,
+    {
+        crate::transaction::run(self, transaction_fn).await
+    }
+
     pub fn batch_write_with_capacity(&self, capacity: usize) -> BatchWrite {
         BatchWrite::new_with_write_capacity(self.client.clone(), capacity)
     }
@@ -133,6 +184,16 @@ impl Firestore {
         CollectionRef::new_root(collec_name, self.clone())
     }
 
+    /// Queries all collections with the given ID, regardless of where they are in the
+    /// document hierarchy.
+    pub fn collection_group<C>(&mut self, collection_id: C) -> QueryBuilder<'_>
+    where
+        C: AsRef<str>,
+    {
+        let parent = format!("{}/documents", self.client.qualified_db_path);
+        QueryBuilder::collection_group(&mut self.client, parent, collection_id.as_ref().to_owned())
+    }
+
     /// Returns the ID for the GCP Project this client is running under.
     pub fn project_id(&self) -> &str {
         self.client.project_id()
@@ -46,8 +46,9 @@
 //! ```
 
 #[cfg(feature = "firestore-admin")]
-mod admin;
+pub mod admin;
 
+pub mod aggregate;
 pub mod batch;
 mod client;
 pub mod collec;
@@ -55,14 +56,17 @@ mod common;
 pub mod de;
 pub mod doc;
 pub mod error;
+#[cfg(feature = "fake")]
+pub mod fake;
+pub mod filter;
 pub mod firestore;
-// pub mod write_stream;
-// pub mod listen;
+pub mod listen;
 mod query;
 mod ser;
-mod transaction;
+pub mod transaction;
 mod util;
 mod value;
+pub mod write_stream;
 
 // re-export `protos` so downstream consumers can get
 // access to raw protobuf types when needed
@@ -1,7 +1,161 @@
+use fxhash::FxHashMap;
+use protos::firestore::Document;
+
 use crate::Reference;
 
-pub struct ChangeMap {
-    map: fxhash::FxHashMap<Box<Reference>, Change>,
+/// Changes to documents that have been received, but not yet applied to the current set of
+/// documents (since the server hasn't told us we're caught up yet).
+#[derive(Debug, Default)]
+pub(super) struct ChangeMap {
+    map: FxHashMap<Box<Reference>, Change>,
+}
+
+#[derive(Debug)]
+pub(super) enum Change {
+    Upsert(Document),
+    Delete,
+}
+
+/// The result of applying a [`ChangeMap`] to the current set of documents.
+#[derive(Debug, Default)]
+pub(super) struct ChangeSet {
+    pub(super) adds: Vec<Document>,
+    pub(super) updates: Vec<Document>,
+    pub(super) deletes: Vec<Box<Reference>>,
+}
+
+impl ChangeSet {
+    pub(super) fn is_empty(&self) -> bool {
+        self.adds.is_empty() && self.updates.is_empty() && self.deletes.is_empty()
+    }
 }
 
-enum Change {}
+impl ChangeMap {
+    pub(super) fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub(super) fn upsert(&mut self, document: Document) {
+        let reference = Reference::new_string(document.name.clone());
+        self.map.insert(reference, Change::Upsert(document));
+    }
+
+    pub(super) fn delete(&mut self, reference: Box<Reference>) {
+        self.map.insert(reference, Change::Delete);
+    }
+
+    /// Computes the number of documents we'd have after applying the pending changes to `docs`.
+    pub(super) fn size_after_apply(&self, docs: &FxHashMap<Box<Reference>, Document>) -> usize {
+        let mut size = docs.len();
+
+        for (reference, change) in self.map.iter() {
+            match (change, docs.contains_key(reference)) {
+                (Change::Upsert(_), false) => size += 1,
+                (Change::Delete, true) => size -= 1,
+                _ => (),
+            }
+        }
+
+        size
+    }
+
+    /// Applies all pending changes to `docs`, draining the change map in the process.
+    ///
+    /// Each list in the returned [`ChangeSet`] is ordered by document path, so the result
+    /// doesn't depend on the hash map iteration order. Listeners re-sort the adds/updates
+    /// according to their own ordering.
+    pub(super) fn apply(&mut self, docs: &mut FxHashMap<Box<Reference>, Document>) -> ChangeSet {
+        let mut change_set = ChangeSet::default();
+
+        for (reference, change) in self.map.drain() {
+            match change {
+                Change::Upsert(document) => match docs.get_mut(&reference) {
+                    // only count this as a modification if the document actually changed
+                    Some(existing) if existing.update_time != document.update_time => {
+                        *existing = document.clone();
+                        change_set.updates.push(document);
+                    }
+                    Some(_) => (),
+                    None => {
+                        docs.insert(reference, document.clone());
+                        change_set.adds.push(document);
+                    }
+                },
+                Change::Delete => {
+                    if docs.remove(&reference).is_some() {
+                        change_set.deletes.push(reference);
+                    }
+                }
+            }
+        }
+
+        change_set
+            .adds
+            .sort_by(|a, b| crate::util::cmp_paths(&a.name, &b.name));
+        change_set
+            .updates
+            .sort_by(|a, b| crate::util::cmp_paths(&a.name, &b.name));
+        change_set.deletes.sort();
+
+        change_set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(name: &str, update_secs: i64) -> Document {
+        Document {
+            name: name.to_owned(),
+            fields: Default::default(),
+            create_time: None,
+            update_time: Some(timestamp::Timestamp::from_seconds(update_secs).into()),
+        }
+    }
+
+    fn names(docs: &[Document]) -> Vec<&str> {
+        docs.iter().map(|doc| doc.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut docs = FxHashMap::default();
+        let mut change_map = ChangeMap::default();
+
+        for name in ["c/b", "c/a", "c/a-b/sub/x", "c/d"] {
+            change_map.upsert(doc(name, 1));
+        }
+
+        assert_eq!(change_map.size_after_apply(&docs), 4);
+
+        let initial = change_map.apply(&mut docs);
+        // path order, so 'c/a/...' sorts before 'c/a-b/...' even though '/' > '-'
+        assert_eq!(names(&initial.adds), ["c/a", "c/a-b/sub/x", "c/b", "c/d"]);
+        assert!(initial.updates.is_empty() && initial.deletes.is_empty());
+        assert_eq!(docs.len(), 4);
+
+        // an unchanged upsert isn't a modification
+        change_map.upsert(doc("c/a", 1));
+        // the last change to a document wins
+        change_map.upsert(doc("c/b", 2));
+        change_map.delete(Reference::new_string("c/b".to_owned()));
+        change_map.delete(Reference::new_string("c/d".to_owned()));
+        change_map.upsert(doc("c/d", 2));
+        change_map.upsert(doc("c/a-b/sub/x", 2));
+        change_map.upsert(doc("c/e", 1));
+        // deleting a document we never had is a no-op
+        change_map.delete(Reference::new_string("c/z".to_owned()));
+
+        assert_eq!(change_map.size_after_apply(&docs), 4);
+
+        let changes = change_map.apply(&mut docs);
+        assert_eq!(names(&changes.adds), ["c/e"]);
+        assert_eq!(names(&changes.updates), ["c/a-b/sub/x", "c/d"]);
+        assert_eq!(changes.deletes, [Reference::new_string("c/b".to_owned())]);
+        assert_eq!(docs.len(), 4);
+
+        // the map is drained after applying
+        assert!(change_map.apply(&mut docs).is_empty());
+    }
+}
@@ -1,9 +1,10 @@
 use bytes::Bytes;
+use futures::Stream;
+use fxhash::FxHashMap;
 use net_utils::backoff::Backoff;
 use net_utils::bidi2::{RequestSink, build_pair};
 use protos::firestore::listen_request::TargetChange as ListenTargetChange;
 use protos::firestore::listen_response::ResponseType;
-// use net_utils::open_close::{self, OpenClose};
 use protos::firestore::target::{ResumeType, TargetType};
 use protos::firestore::target_change::TargetChangeType;
 use protos::firestore::{
@@ -11,100 +12,133 @@ use protos::firestore::{
 };
 use timestamp::{Duration, Timestamp};
 
+use super::change_map::{ChangeMap, ChangeSet};
+use super::{ListenerType, Snapshot};
+use crate::Reference;
+use crate::client::FirestoreClient;
+
 // single target id, similar to the c# and python client
 // (though we use 'rs' instead of 'c#' or 'py')
 const TARGET_ID: i32 = 0x7273;
 
-// default backoff, copying params from the c# client. Since this is
-// continually reused, we set a huge number of backoff attempts so we should never actually
-// hit the max retry case.
-const BACKOFF: Backoff = Backoff::builder()
-    .base_delay(Duration::from_seconds(1))
-    .max_timeout(Duration::from_seconds(30))
-    .max_retries(u32::MAX)
-    .build_local();
-
-use super::TargetId;
-use crate::Firestore;
+// Max number of consecutive failed attempts to (re)connect before giving up. The backoff is
+// reset every time the server sends a resume token, so this only applies to back-to-back
+// failures.
+const MAX_RETRIES: u32 = 10;
+
+// default backoff, copying params from the c# client.
+fn default_backoff() -> Backoff {
+    Backoff::<u32>::builder()
+        .base_delay(Duration::from_seconds(1))
+        .max_timeout(Duration::from_seconds(30))
+        .max_retries(MAX_RETRIES)
+        .build_local()
+}
 
-#[pin_project::pin_project(project = ListenerProj)]
-pub struct Listener {
+/// A realtime listener on a set of documents. Created via [`DocumentRef::listen`] or
+/// [`QueryBuilder::listen`].
+///
+/// [`DocumentRef::listen`]: crate::doc::DocumentRef::listen
+/// [`QueryBuilder::listen`]: crate::query::QueryBuilder::listen
+pub struct Listener<L> {
+    client: FirestoreClient,
+    listener_type: L,
     state: ListenerState,
-    #[pin]
     stream: ListenerStreamState,
 }
 
 struct ListenerState {
-    firestore: Firestore,
     backoff: Backoff,
     current: bool,
-    change_map: fxhash::FxHashMap<Box<str>, Document>,
-    resume_bytes: Option<Bytes>,
-}
-
-struct TargetState {
-    id: TargetId,
+    has_pushed: bool,
+    /// The current set of documents, as of the last snapshot.
+    docs: FxHashMap<Box<Reference>, Document>,
+    change_map: ChangeMap,
+    resume_token: Option<Bytes>,
 }
 
-#[pin_project::pin_project(project = ListenerStreamStateProj)]
 enum ListenerStreamState {
-    Alive {
-        #[pin]
-        stream: ListenerStream,
-    },
+    Alive(Box<ListenerStream>),
     Pending,
     Closed,
 }
 
-#[pin_project::pin_project(project = ListenerStreamProj)]
 struct ListenerStream {
-    sink: RequestSink<ListenRequest>,
-    #[pin]
+    // never used directly, but we need to hold onto it, otherwise the request stream
+    // closes and the server will hang up on us.
+    _sink: RequestSink<ListenRequest>,
     stream: tonic::Streaming<ListenResponse>,
 }
 
 enum ListenResponseResult {
     Continue,
     ResetStream,
+    Snapshot(Timestamp, ChangeSet),
 }
 
 fn has_valid_target_ids(target_ids: &[i32]) -> bool {
     target_ids.is_empty() || target_ids.contains(&TARGET_ID)
 }
 
-pub struct ChangeSet {
-    deletes: Vec<Box<str>>,
-    adds: Vec<Box<str>>,
-    updates: Vec<Box<str>>,
-}
-
 impl ListenerState {
+    fn new() -> Self {
+        Self {
+            backoff: default_backoff(),
+            current: false,
+            has_pushed: false,
+            docs: FxHashMap::default(),
+            change_map: ChangeMap::default(),
+            resume_token: None,
+        }
+    }
+
+    /// Builds the request that adds our target, resuming from the last consistent snapshot if
+    /// we have a resume token for it.
+    fn listen_request(&self, database: String, target_type: TargetType) -> ListenRequest {
+        let target = Target {
+            target_id: TARGET_ID,
+            once: false,
+            expected_count: None,
+            resume_type: self.resume_token.clone().map(ResumeType::ResumeToken),
+            target_type: Some(target_type),
+        };
+
+        ListenRequest {
+            database,
+            labels: Default::default(),
+            target_change: Some(ListenTargetChange::AddTarget(target)),
+        }
+    }
+
     fn handle_response(&mut self, response: ListenResponse) -> crate::Result<ListenResponseResult> {
         // we don't want to unwrap, but we do want to error out of the response is null/invalid.
         // this matches the c# client WatchState behavior when encountering an unknown variant.
-
         let response_type = response.response_type.ok_or(crate::Error::Internal(
             "ListenResponse.response_type is invalid",
         ))?;
 
-        self.handle_response_type(response_type)
-    }
-
-    fn handle_response_type(
-        &mut self,
-        response: ResponseType,
-    ) -> crate::Result<ListenResponseResult> {
-        match response {
+        match response_type {
             ResponseType::TargetChange(change) => self.handle_target_change(change),
-            ResponseType::Filter(filter) => self.handle_filter(filter),
+            ResponseType::Filter(filter) => Ok(self.handle_filter(filter)),
             ResponseType::DocumentChange(change) => self.handle_doc_change(change),
-            ResponseType::DocumentDelete(del) => self.handle_doc_remove_delete(del.document),
-            ResponseType::DocumentRemove(rem) => self.handle_doc_remove_delete(rem.document),
+            ResponseType::DocumentDelete(del) => Ok(self.handle_doc_remove_delete(del.document)),
+            ResponseType::DocumentRemove(rem) => Ok(self.handle_doc_remove_delete(rem.document)),
         }
     }
 
-    fn handle_filter(&mut self, filter: ExistenceFilter) -> crate::Result<ListenResponseResult> {
-        todo!("{filter:#?}")
+    fn handle_filter(&mut self, filter: ExistenceFilter) -> ListenResponseResult {
+        if filter.target_id != TARGET_ID {
+            return ListenResponseResult::Continue;
+        }
+
+        // if the server thinks we should have a different number of documents than we do,
+        // we've gotten out of sync, so we need to start over from scratch.
+        if filter.count as usize != self.change_map.size_after_apply(&self.docs) {
+            self.reset_docs();
+            return ListenResponseResult::ResetStream;
+        }
+
+        ListenResponseResult::Continue
     }
 
     fn handle_target_change(
@@ -112,6 +146,9 @@ impl ListenerState {
         change: TargetChange,
     ) -> crate::Result<ListenResponseResult> {
         let has_resume_token = !change.resume_token.is_empty();
+        let valid_target_ids = has_valid_target_ids(&change.target_ids);
+
+        let mut result = ListenResponseResult::Continue;
 
         match change.target_change_type() {
             TargetChangeType::NoChange => {
@@ -121,13 +158,13 @@ impl ListenerState {
                     && change.target_ids.is_empty()
                     && self.current
                 {
-                    self.push_snapshot(
+                    result = self.push_snapshot(
                         read_time.into(),
-                        crate::util::none_if_empty(change.resume_token),
-                    )?;
+                        crate::util::none_if_empty(change.resume_token.clone()),
+                    );
                 }
             }
-            TargetChangeType::Add => debug_assert!(has_valid_target_ids(&change.target_ids)),
+            TargetChangeType::Add => debug_assert!(valid_target_ids),
             // according to the c# client, this only happens when the server aborts in an
             // unrecoverable way, so we should throw
             TargetChangeType::Remove => {
@@ -141,28 +178,46 @@ impl ListenerState {
                 };
             }
             TargetChangeType::Current => self.current = true,
-            TargetChangeType::Reset => self.reset(),
+            TargetChangeType::Reset => self.reset_docs(),
         }
 
         // if the stream is 'healthy', we should reset the backoff
-        if has_resume_token && has_valid_target_ids(&change.target_ids) {
-            self.backoff = BACKOFF;
+        if has_resume_token && valid_target_ids {
+            self.backoff.reset();
         }
 
-        Ok(ListenResponseResult::Continue)
+        Ok(result)
     }
 
     fn on_stream_init(&mut self, stream_completed_or_errored: bool) {
         self.current = false;
+
         if stream_completed_or_errored {
+            // anything we haven't pushed will be re-sent by the server, since we resume from the
+            // last consistent snapshot.
             self.change_map.clear();
+
+            // if we don't have a resume token, the server is going to send us everything from
+            // scratch.
+            if self.resume_token.is_none() {
+                self.mark_all_deleted();
+            }
         }
     }
 
-    fn reset(&mut self) {
+    /// Resets the state of the listener, such that the server will re-send all matching
+    /// documents. Any documents that aren't re-sent will be removed in the next snapshot.
+    fn reset_docs(&mut self) {
         self.change_map.clear();
-        self.resume_bytes = None;
+        self.resume_token = None;
         self.current = false;
+        self.mark_all_deleted();
+    }
+
+    fn mark_all_deleted(&mut self) {
+        for reference in self.docs.keys() {
+            self.change_map.delete(reference.clone());
+        }
     }
 
     fn handle_doc_change(&mut self, change: DocumentChange) -> crate::Result<ListenResponseResult> {
@@ -186,166 +241,450 @@ impl ListenerState {
         ))?;
 
         if changed {
-            self.change_map
-                .insert(document.name.as_str().into(), document);
+            self.change_map.upsert(document);
         } else {
-            self.change_map.remove(document.name.as_str());
+            self.change_map.delete(Reference::new_string(document.name));
         }
 
         Ok(ListenResponseResult::Continue)
     }
 
-    fn handle_doc_remove_delete(&mut self, doc: String) -> crate::Result<ListenResponseResult> {
-        self.change_map.remove(doc.as_str());
-        Ok(ListenResponseResult::Continue)
+    fn handle_doc_remove_delete(&mut self, doc: String) -> ListenResponseResult {
+        self.change_map.delete(Reference::new_string(doc));
+        ListenResponseResult::Continue
     }
 
     fn push_snapshot(
         &mut self,
         read_time: Timestamp,
         resume_token: Option<Bytes>,
-    ) -> crate::Result<()> {
-        todo!()
+    ) -> ListenResponseResult {
+        let change_set = self.change_map.apply(&mut self.docs);
+
+        // we're at a consistent point, so if the stream dies we can pick back up from here.
+        if resume_token.is_some() {
+            self.resume_token = resume_token;
+        }
+
+        // always push the first snapshot, even if it's empty, so the caller knows the
+        // initial state.
+        if !self.has_pushed || !change_set.is_empty() {
+            self.has_pushed = true;
+            ListenResponseResult::Snapshot(read_time, change_set)
+        } else {
+            ListenResponseResult::Continue
+        }
     }
 }
 
-impl Listener {
-    async fn next_message(&mut self) -> crate::Result<Option<ListenResponse>> {
-        // inner method that doesn't handle error cases or restarting a dead stream
-        async fn next_message_inner(
-            state: &mut ListenerState,
-            stream_state: &mut ListenerStreamState,
-            stream_complete_or_error: &mut bool,
-        ) -> crate::Result<Option<ListenResponse>> {
-            loop {
-                match stream_state {
-                    ListenerStreamState::Alive { stream } => {
-                        return stream.stream.message().await.map_err(crate::Error::from);
-                    }
-                    ListenerStreamState::Closed => return Ok(None),
-                    ListenerStreamState::Pending => {
-                        let backoff = state
-                            .backoff
-                            .backoff_once()
-                            .expect("overflowed u32::MAX backoffs");
-
-                        // only back off if we're not on the very first try
-                        if backoff.on_retry() > 1 {
-                            backoff.await;
-                        }
-
-                        let (sink, request_stream) = build_pair();
+impl<L: ListenerType> Listener<L> {
+    pub(crate) fn new(client: FirestoreClient, listener_type: L) -> Self {
+        Self {
+            client,
+            listener_type,
+            state: ListenerState::new(),
+            stream: ListenerStreamState::Pending,
+        }
+    }
 
-                        let stream = state
-                            .firestore
-                            .client
-                            .get()
-                            .listen(request_stream)
-                            .await?
-                            .into_inner();
+    /// Closes the listener. Any further calls to [`Listener::next_snapshot`] will return
+    /// [`None`].
+    pub fn close(&mut self) {
+        self.stream = ListenerStreamState::Closed;
+    }
 
-                        state.on_stream_init(*stream_complete_or_error);
+    pub fn is_closed(&self) -> bool {
+        matches!(self.stream, ListenerStreamState::Closed)
+    }
 
-                        *stream_state = ListenerStreamState::Alive {
-                            stream: ListenerStream { sink, stream },
-                        };
+    /// Waits for the next snapshot containing changes. The first snapshot contains all
+    /// matching documents as `added`, even if there are none.
+    ///
+    /// Dropped connections are transparently resumed. If a non-recoverable error is
+    /// encountered, the listener is closed and the error is returned.
+    pub async fn next_snapshot<T>(&mut self) -> crate::Result<Option<Snapshot<T>>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match self.next_change_set().await {
+            Ok(Some((read_time, change_set))) => {
+                match Snapshot::from_change_set(read_time, change_set) {
+                    Ok(snapshot) => Ok(Some(snapshot)),
+                    Err(error) => {
+                        self.close();
+                        Err(error)
                     }
                 }
             }
+            Ok(None) => Ok(None),
+            Err(error) => {
+                self.close();
+                Err(error)
+            }
         }
+    }
 
-        let mut stream_complete_or_error = false;
+    /// Converts this listener into a [`Stream`] of snapshots.
+    pub fn into_stream<T>(self) -> impl Stream<Item = crate::Result<Snapshot<T>>> + Send
+    where
+        L: Send,
+        T: serde::de::DeserializeOwned + Send,
+    {
+        futures::stream::unfold(self, |mut listener| async move {
+            match listener.next_snapshot().await {
+                Ok(Some(snapshot)) => Some((Ok(snapshot), listener)),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), listener)),
+            }
+        })
+    }
 
+    async fn next_change_set(&mut self) -> crate::Result<Option<(Timestamp, ChangeSet)>> {
         loop {
-            match next_message_inner(
-                &mut self.state,
-                &mut self.stream,
-                &mut stream_complete_or_error,
-            )
-            .await
-            {
-                Ok(Some(message)) => return Ok(Some(message)),
-                // we wont restart a manually closed listener
-                Ok(None) if matches!(self.stream, ListenerStreamState::Closed) => return Ok(None),
-                // if we're None and not closed, the stream itself is exhausted, so we need to
-                // restart it.
-                Ok(None) => {
+            let Some(response) = self.next_message().await? else {
+                return Ok(None);
+            };
+
+            match self.state.handle_response(response)? {
+                ListenResponseResult::Continue => (),
+                ListenResponseResult::ResetStream => {
+                    // the state is already reset at this point, so we don't want to clear
+                    // anything else out when restarting.
                     self.stream = ListenerStreamState::Pending;
-                    stream_complete_or_error = true;
                 }
-                Err(error) => match error.rpc_code() {
-                    Some(code) if crate::error::is_transient_error(code) => {
-                        self.stream = ListenerStreamState::Pending;
-                        stream_complete_or_error = true;
-
-                        // do some extra backing off if this is the error, as per the c# client
-                        if matches!(code, tonic::Code::ResourceExhausted) {
-                            self.state
-                                .backoff
-                                .backoff_once()
-                                .expect("overflowed u32::MAX retries")
-                                .await;
+                ListenResponseResult::Snapshot(read_time, mut change_set) => {
+                    change_set
+                        .adds
+                        .sort_by(|a, b| self.listener_type.cmp_documents(a, b));
+                    change_set
+                        .updates
+                        .sort_by(|a, b| self.listener_type.cmp_documents(a, b));
+
+                    return Ok(Some((read_time, change_set)));
+                }
+            }
+        }
+    }
+
+    async fn start_stream(
+        &mut self,
+        stream_complete_or_error: bool,
+    ) -> crate::Result<ListenerStream> {
+        let (sink, request_stream) = build_pair();
+
+        let request = self.state.listen_request(
+            self.client.qualified_db_path.to_string(),
+            self.listener_type.target(),
+        );
+
+        sink.send(request)
+            .map_err(|_| crate::Error::Internal("listen request stream closed unexpectedly"))?;
+
+        let stream = self.client.get().listen(request_stream).await?.into_inner();
+
+        self.state.on_stream_init(stream_complete_or_error);
+
+        Ok(ListenerStream {
+            _sink: sink,
+            stream,
+        })
+    }
+
+    async fn next_message(&mut self) -> crate::Result<Option<ListenResponse>> {
+        let mut stream_complete_or_error = false;
+
+        loop {
+            let result = match self.stream {
+                ListenerStreamState::Closed => return Ok(None),
+                ListenerStreamState::Alive(ref mut stream) => {
+                    stream.stream.message().await.map_err(crate::Error::from)
+                }
+                ListenerStreamState::Pending => {
+                    match self.start_stream(stream_complete_or_error).await {
+                        Ok(stream) => {
+                            self.stream = ListenerStreamState::Alive(Box::new(stream));
+                            continue;
                         }
+                        Err(error) => Err(error),
                     }
-                    _ => return Err(error),
-                },
+                }
+            };
+
+            let error = match result {
+                Ok(Some(message)) => return Ok(Some(message)),
+                // if we're None, the stream itself is exhausted, so we need to restart it.
+                Ok(None) => None,
+                Err(error) if error.is_transient_error() => Some(error),
+                Err(error) => return Err(error),
+            };
+
+            self.stream = ListenerStreamState::Pending;
+            stream_complete_or_error = true;
+
+            // bail with the last error if we've been failing for too long.
+            let Some(backoff) = self.state.backoff.backoff_once() else {
+                return Err(error.unwrap_or(crate::Error::Internal(
+                    "listen stream repeatedly closed by the server",
+                )));
+            };
+
+            backoff.await;
+
+            // do some extra backing off if this is the error, as per the c# client
+            let resource_exhausted = error
+                .as_ref()
+                .and_then(crate::Error::rpc_code)
+                .is_some_and(|code| code == tonic::Code::ResourceExhausted);
+
+            if resource_exhausted && let Some(backoff) = self.state.backoff.backoff_once() {
+                backoff.await;
             }
         }
     }
+}
 
-    async fn add_target(
-        &mut self,
-        target: TargetType,
-        once: bool,
-        resume_type: Option<ResumeType>,
-    ) -> crate::Result<TargetId> {
-        let target_id = TargetId::next_id();
+#[cfg(test)]
+mod tests {
+    use protos::firestore::DocumentDelete;
+    use protos::firestore::target::DocumentsTarget;
 
-        let target = Target {
-            target_id: target_id.get(),
-            once,
-            resume_type,
-            expected_count: None,
-            target_type: Some(target),
-        };
+    use super::*;
 
-        self.send_message(ListenTargetChange::AddTarget(target))
-            .await?;
+    const DB: &str = "projects/p/databases/(default)";
 
-        Ok(target_id)
+    fn doc_name(id: &str) -> String {
+        format!("{DB}/documents/c/{id}")
     }
 
-    async fn send_message(&mut self, change: ListenTargetChange) -> crate::Result<()> {
-        let message = ListenRequest {
-            database: self.state.firestore.qualified_db_path().to_owned(),
-            labels: Default::default(),
-            target_change: Some(change),
-        };
+    fn response(response_type: ResponseType) -> ListenResponse {
+        ListenResponse {
+            response_type: Some(response_type),
+        }
+    }
 
-        self.get_or_start_stream()
-            .await?
-            .ok_or(crate::Error::ListenerClosed)?
-            .1
-            .sink
-            .send(message)
-            .map_err(|_| crate::Error::ListenerClosed)
+    fn target_change(change_type: TargetChangeType, target_ids: Vec<i32>) -> ListenResponse {
+        response(ResponseType::TargetChange(TargetChange {
+            target_change_type: change_type as i32,
+            target_ids,
+            ..Default::default()
+        }))
     }
 
-    pub(crate) fn new(firestore: Firestore) -> Self {
-        Self {
-            state: ListenerState {
-                current: false,
-                firestore,
-                resume_bytes: None,
-                change_map: fxhash::FxHashMap::with_capacity_and_hasher(
-                    16,
-                    fxhash::FxBuildHasher::default(),
-                ),
-                backoff: BACKOFF,
-            },
-            stream: ListenerStreamState::Pending,
+    /// The global 'NO_CHANGE' the server sends once everything up to 'resume_token' is
+    /// consistent.
+    fn consistent(read_secs: i64, resume_token: &'static [u8]) -> ListenResponse {
+        response(ResponseType::TargetChange(TargetChange {
+            target_change_type: TargetChangeType::NoChange as i32,
+            target_ids: Vec::new(),
+            cause: None,
+            resume_token: Bytes::from_static(resume_token),
+            read_time: Some(Timestamp::from_seconds(read_secs).into()),
+        }))
+    }
+
+    fn doc_change(id: &str, update_secs: i64) -> ListenResponse {
+        response(ResponseType::DocumentChange(DocumentChange {
+            document: Some(Document {
+                name: doc_name(id),
+                fields: Default::default(),
+                create_time: None,
+                update_time: Some(Timestamp::from_seconds(update_secs).into()),
+            }),
+            target_ids: vec![TARGET_ID],
+            removed_target_ids: Vec::new(),
+        }))
+    }
+
+    fn filter(count: i32) -> ListenResponse {
+        response(ResponseType::Filter(ExistenceFilter {
+            target_id: TARGET_ID,
+            count,
+            unchanged_names: None,
+        }))
+    }
+
+    /// Feeds the scripted responses through the state, returning the result of each one.
+    fn feed(
+        state: &mut ListenerState,
+        responses: impl IntoIterator<Item = ListenResponse>,
+    ) -> Vec<ListenResponseResult> {
+        responses
+            .into_iter()
+            .map(|response| state.handle_response(response).expect("should be valid"))
+            .collect()
+    }
+
+    fn snapshot(results: Vec<ListenResponseResult>) -> (Timestamp, ChangeSet) {
+        match results.into_iter().last() {
+            Some(ListenResponseResult::Snapshot(read_time, change_set)) => (read_time, change_set),
+            _ => panic!("expected the last response to produce a snapshot"),
         }
     }
-}
 
-pub struct Change {}
+    fn names(docs: &[Document]) -> Vec<String> {
+        docs.iter().map(|doc| doc.name.clone()).collect()
+    }
+
+    fn resume_token(state: &ListenerState) -> Option<ResumeType> {
+        let request = state.listen_request(
+            DB.to_owned(),
+            TargetType::Documents(DocumentsTarget {
+                documents: vec![doc_name("a")],
+            }),
+        );
+
+        match request.target_change {
+            Some(ListenTargetChange::AddTarget(target)) => {
+                assert_eq!(target.target_id, TARGET_ID);
+                target.resume_type
+            }
+            _ => panic!("expected an AddTarget request"),
+        }
+    }
+
+    /// Runs the initial sync of docs 'a' and 'b', up to the first snapshot.
+    fn initial_sync(state: &mut ListenerState) {
+        state.on_stream_init(false);
+        assert_eq!(resume_token(state), None);
+
+        let (read_time, initial) = snapshot(feed(
+            state,
+            [
+                target_change(TargetChangeType::Add, vec![TARGET_ID]),
+                doc_change("a", 1),
+                doc_change("b", 1),
+                target_change(TargetChangeType::Current, vec![TARGET_ID]),
+                consistent(10, b"token-1"),
+            ],
+        ));
+
+        assert_eq!(read_time, Timestamp::from_seconds(10));
+        assert_eq!(names(&initial.adds), [doc_name("a"), doc_name("b")]);
+        assert!(initial.updates.is_empty() && initial.deletes.is_empty());
+    }
+
+    #[test]
+    fn test_resume_after_disconnect() {
+        let mut state = ListenerState::new();
+        initial_sync(&mut state);
+
+        // changes that come in before the connection drops aren't consistent yet, so they
+        // get dropped and re-sent by the server after resuming.
+        feed(&mut state, [doc_change("a", 2), doc_change("c", 2)]);
+
+        state.on_stream_init(true);
+
+        assert!(!state.current);
+        assert_eq!(state.docs.len(), 2);
+        assert_eq!(
+            resume_token(&state),
+            Some(ResumeType::ResumeToken(Bytes::from_static(b"token-1")))
+        );
+
+        let (_, resumed) = snapshot(feed(
+            &mut state,
+            [
+                doc_change("c", 2),
+                response(ResponseType::DocumentDelete(DocumentDelete {
+                    document: doc_name("b"),
+                    ..Default::default()
+                })),
+                target_change(TargetChangeType::Current, vec![TARGET_ID]),
+                consistent(20, b"token-2"),
+            ],
+        ));
+
+        // 'a' was only changed on the dead stream, so it isn't in the snapshot
+        assert_eq!(names(&resumed.adds), [doc_name("c")]);
+        assert!(resumed.updates.is_empty());
+        assert_eq!(resumed.deletes, [Reference::new_string(doc_name("b"))]);
+
+        assert_eq!(
+            resume_token(&state),
+            Some(ResumeType::ResumeToken(Bytes::from_static(b"token-2")))
+        );
+
+        // nothing changed since the last snapshot, so nothing is emitted
+        let results = feed(&mut state, [consistent(30, b"token-3")]);
+        assert!(matches!(results[..], [ListenResponseResult::Continue]));
+        assert_eq!(
+            resume_token(&state),
+            Some(ResumeType::ResumeToken(Bytes::from_static(b"token-3")))
+        );
+    }
+
+    #[test]
+    fn test_disconnect_without_resume_token() {
+        let mut state = ListenerState::new();
+        state.on_stream_init(false);
+
+        feed(
+            &mut state,
+            [
+                target_change(TargetChangeType::Add, vec![TARGET_ID]),
+                doc_change("a", 1),
+            ],
+        );
+
+        // no snapshot yet, so the server starts over from scratch
+        state.on_stream_init(true);
+        assert_eq!(resume_token(&state), None);
+
+        let (_, initial) = snapshot(feed(
+            &mut state,
+            [
+                doc_change("b", 1),
+                target_change(TargetChangeType::Current, vec![TARGET_ID]),
+                consistent(10, b"token-1"),
+            ],
+        ));
+
+        assert_eq!(names(&initial.adds), [doc_name("b")]);
+    }
+
+    #[test]
+    fn test_existence_filter_mismatch() {
+        let mut state = ListenerState::new();
+        initial_sync(&mut state);
+
+        // matching counts (including pending changes) don't do anything
+        let results = feed(&mut state, [doc_change("c", 2), filter(3)]);
+        assert!(matches!(
+            results[..],
+            [
+                ListenResponseResult::Continue,
+                ListenResponseResult::Continue
+            ]
+        ));
+
+        // but a mismatch means we're out of sync, so everything is thrown out
+        let results = feed(&mut state, [filter(1)]);
+        assert!(matches!(results[..], [ListenResponseResult::ResetStream]));
+
+        assert!(!state.current);
+        assert_eq!(resume_token(&state), None);
+
+        // the reset stream isn't a disconnect, the server just re-sends what's still there
+        state.on_stream_init(false);
+
+        let (read_time, reset) = snapshot(feed(
+            &mut state,
+            [
+                target_change(TargetChangeType::Add, vec![TARGET_ID]),
+                doc_change("a", 1),
+                target_change(TargetChangeType::Current, vec![TARGET_ID]),
+                consistent(20, b"token-2"),
+            ],
+        ));
+
+        // 'a' was re-sent unchanged, and 'c' never made it into a snapshot
+        assert_eq!(read_time, Timestamp::from_seconds(20));
+        assert!(reset.adds.is_empty() && reset.updates.is_empty());
+        assert_eq!(reset.deletes, [Reference::new_string(doc_name("b"))]);
+        assert_eq!(state.docs.len(), 1);
+        assert_eq!(
+            resume_token(&state),
+            Some(ResumeType::ResumeToken(Bytes::from_static(b"token-2")))
+        );
+    }
+}
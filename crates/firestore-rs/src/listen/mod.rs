@@ -1,39 +1,259 @@
+//! Realtime listeners on documents and queries.
 use std::cmp::Ordering;
 
-use protos::firestore::Document;
-use protos::firestore::structured_query::{Direction, Order};
+use protos::firestore::structured_query::Direction;
+use protos::firestore::target::query_target::QueryType;
+use protos::firestore::target::{DocumentsTarget, QueryTarget, TargetType};
+use protos::firestore::{Document, StructuredQuery};
+use timestamp::Timestamp;
+
+use crate::doc::Doc;
+use crate::value::ValueRef;
+use crate::{Reference, util};
 
 mod change_map;
 mod listener;
 pub use listener::Listener;
 
+/// Defines what a [`Listener`] is listening to, and how the resulting documents are ordered.
 pub trait ListenerType {
+    fn target(&self) -> TargetType;
+
     fn cmp_documents(&self, a: &Document, b: &Document) -> Ordering;
 }
 
-struct QueryListener {
-    orderings: Vec<Order>,
+/// Listens to a single document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentListener {
+    name: String,
+}
+
+impl DocumentListener {
+    pub(crate) fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl ListenerType for DocumentListener {
+    fn target(&self) -> TargetType {
+        TargetType::Documents(DocumentsTarget {
+            documents: vec![self.name.clone()],
+        })
+    }
+
+    fn cmp_documents(&self, a: &Document, b: &Document) -> Ordering {
+        util::cmp_paths(&a.name, &b.name)
+    }
+}
+
+/// Listens to all documents matching a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryListener {
+    parent: String,
+    query: StructuredQuery,
+}
+
+impl QueryListener {
+    pub(crate) fn new(parent: String, query: StructuredQuery) -> Self {
+        Self { parent, query }
+    }
 }
 
 impl ListenerType for QueryListener {
+    fn target(&self) -> TargetType {
+        TargetType::Query(QueryTarget {
+            parent: self.parent.clone(),
+            query_type: Some(QueryType::StructuredQuery(self.query.clone())),
+        })
+    }
+
     fn cmp_documents(&self, a: &Document, b: &Document) -> Ordering {
         let mut last_direction = Direction::Ascending;
 
-        for ordering in self.orderings.iter() {
+        for ordering in self.query.order_by.iter() {
             let Some(ref field) = ordering.field else {
                 continue;
             };
 
-            last_direction = ordering.direction();
+            last_direction = match ordering.direction() {
+                Direction::Unspecified | Direction::Ascending => Direction::Ascending,
+                Direction::Descending => Direction::Descending,
+            };
 
             let cmp = if field.field_path == "__name__" {
-                a.name.cmp(&b.name)
+                util::cmp_paths(&a.name, &b.name)
             } else {
-                let a_value = crate::util::extract_value(&a.fields, &field.field_path);
-                let b_value = crate::util::extract_value(&a.fields, &field.field_path);
+                let a_value = util::extract_value(&a.fields, &field.field_path);
+                let b_value = util::extract_value(&b.fields, &field.field_path);
+
+                match (a_value, b_value) {
+                    (Some(a), Some(b)) => {
+                        ValueRef::from_proto_ref(a).ord_cmp(ValueRef::from_proto_ref(b))
+                    }
+                    // documents missing the field shouldn't match the query in the first place,
+                    // but sort them first to be safe.
+                    (None, Some(_)) => Ordering::Less,
+                    (Some(_), None) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
             };
+
+            let cmp = match last_direction {
+                Direction::Descending => cmp.reverse(),
+                _ => cmp,
+            };
+
+            if cmp.is_ne() {
+                return cmp;
+            }
+        }
+
+        // documents are implicitly ordered by name last, in the same direction as the last
+        // explicit ordering.
+        let cmp = util::cmp_paths(&a.name, &b.name);
+
+        match last_direction {
+            Direction::Descending => cmp.reverse(),
+            _ => cmp,
         }
+    }
+}
+
+/// A set of changes to the documents being listened to, as of `read_time`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot<T> {
+    pub read_time: Timestamp,
+    /// Newly matching documents, in the order defined by the listener.
+    pub added: Vec<Doc<T>>,
+    /// Documents that still match, but have changed.
+    pub modified: Vec<Doc<T>>,
+    /// Documents that were deleted, or no longer match.
+    pub removed: Vec<Box<Reference>>,
+}
+
+impl<T> Snapshot<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+
+    fn from_change_set(
+        read_time: Timestamp,
+        change_set: change_map::ChangeSet,
+    ) -> crate::Result<Self>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let added = change_set
+            .adds
+            .into_iter()
+            .map(Doc::from_document)
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let modified = change_set
+            .updates
+            .into_iter()
+            .map(Doc::from_document)
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            read_time,
+            added,
+            modified,
+            removed: change_set.deletes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protos::firestore::structured_query::{FieldReference, Order};
+    use protos::firestore::value::ValueType;
+
+    use super::*;
+
+    fn doc(name: &str, rank: Option<i64>) -> Document {
+        let fields = rank
+            .map(|rank| {
+                let value = protos::firestore::Value {
+                    value_type: Some(ValueType::IntegerValue(rank)),
+                };
+                ("rank".to_owned(), value)
+            })
+            .into_iter()
+            .collect();
+
+        Document {
+            name: name.to_owned(),
+            fields,
+            create_time: None,
+            update_time: None,
+        }
+    }
+
+    fn listener(order_by: &[(&str, Direction)]) -> QueryListener {
+        let order_by = order_by
+            .iter()
+            .map(|(field_path, direction)| Order {
+                field: Some(FieldReference {
+                    field_path: (*field_path).to_owned(),
+                }),
+                direction: *direction as i32,
+            })
+            .collect();
+
+        QueryListener::new(
+            "projects/p/databases/(default)/documents".to_owned(),
+            StructuredQuery {
+                order_by,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn sorted_names(listener: &QueryListener, mut docs: Vec<Document>) -> Vec<String> {
+        docs.sort_by(|a, b| listener.cmp_documents(a, b));
+        docs.into_iter().map(|doc| doc.name).collect()
+    }
+
+    #[test]
+    fn test_query_cmp_documents() {
+        let docs = || {
+            vec![
+                doc("c/b", Some(1)),
+                doc("c/d", Some(2)),
+                doc("c/a", Some(1)),
+                doc("c/a-b", None),
+                doc("c/a/sub/x", Some(2)),
+            ]
+        };
+
+        // no explicit ordering, so only by name (by segment)
+        assert_eq!(
+            sorted_names(&listener(&[]), docs()),
+            ["c/a", "c/a/sub/x", "c/a-b", "c/b", "c/d"]
+        );
+
+        // missing fields sort first, ties are broken by name ascending
+        assert_eq!(
+            sorted_names(&listener(&[("rank", Direction::Ascending)]), docs()),
+            ["c/a-b", "c/a", "c/b", "c/a/sub/x", "c/d"]
+        );
+
+        // the implicit name ordering follows the last explicit direction
+        assert_eq!(
+            sorted_names(&listener(&[("rank", Direction::Descending)]), docs()),
+            ["c/d", "c/a/sub/x", "c/b", "c/a", "c/a-b"]
+        );
 
-        todo!()
+        assert_eq!(
+            sorted_names(
+                &listener(&[
+                    ("rank", Direction::Descending),
+                    ("__name__", Direction::Ascending)
+                ]),
+                docs()
+            ),
+            ["c/a/sub/x", "c/d", "c/a", "c/b", "c/a-b"]
+        );
     }
 }
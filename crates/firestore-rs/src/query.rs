@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::pin::Pin;
 use std::task::{Context, Poll, ready};
 
+use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use protos::firestore::run_query_request::{ConsistencySelector, QueryType};
 use protos::firestore::run_query_response::ContinuationSelector;
@@ -10,8 +11,8 @@ use protos::firestore::structured_query::field_filter::Operator as FieldOperator
 use protos::firestore::structured_query::filter::FilterType;
 use protos::firestore::structured_query::unary_filter::{OperandType, Operator as UnaryOperator};
 use protos::firestore::structured_query::{
-    CollectionSelector, CompositeFilter, Direction, FieldFilter, FieldReference, Filter, Order,
-    Projection, UnaryFilter,
+    CollectionSelector, Direction, FieldFilter, FieldReference, Filter, Order, Projection,
+    UnaryFilter,
 };
 use protos::firestore::value::ValueType;
 use protos::firestore::{
@@ -20,8 +21,10 @@ use protos::firestore::{
 };
 use protos::protobuf::Int32Value;
 
+use crate::aggregate::AggregationBuilder;
 use crate::client::FirestoreClient;
 use crate::de::deserialize_doc_fields;
+use crate::listen::{Listener, QueryListener, Snapshot};
 use crate::ser;
 
 pub struct FieldFilterBuilder<'a> {
@@ -237,6 +240,16 @@ impl<'a> QueryBuilder<'a> {
         }
     }
 
+    pub(crate) fn collection_group(
+        client: &'a mut FirestoreClient,
+        parent: String,
+        collection_id: String,
+    ) -> Self {
+        let mut builder = Self::collection_scoped(client, parent, collection_id);
+        builder.from.all_descendants = true;
+        builder
+    }
+
     pub fn where_field<S>(self, field: S) -> FieldFilterBuilder<'a>
     where
         S: AsRef<str>,
@@ -340,38 +353,20 @@ impl<'a> QueryBuilder<'a> {
         self.order_by_inner(field.as_ref(), Direction::Descending)
     }
 
-    fn take_filter(&mut self) -> Option<FilterType> {
-        self.filter.take().and_then(|filter| filter.filter_type)
+    /// Adds a filter expression, which can contain nested `and`/`or` filters. If the query
+    /// already has filters, documents must match both.
+    pub fn filter(mut self, filter: crate::filter::Filter) -> Self {
+        self.add_filter(filter.into_proto());
+        self
     }
 
     fn add_filter(&mut self, filter: Filter) {
-        // Passing in a composite filter not yet supported
-        assert!(!matches!(
-            filter.filter_type,
-            Some(FilterType::CompositeFilter(_))
-        ));
-
-        let existing = self.take_filter();
-
-        self.filter = match existing {
-            Some(FilterType::CompositeFilter(mut composite)) => {
-                composite.filters.push(filter);
-
-                Some(Filter {
-                    filter_type: Some(FilterType::CompositeFilter(composite)),
-                })
-            }
-            Some(filter_type) => Some(Filter {
-                filter_type: Some(FilterType::CompositeFilter(CompositeFilter {
-                    op: CompositeOperator::And as i32,
-                    filters: vec![
-                        Filter {
-                            filter_type: Some(filter_type),
-                        },
-                        filter,
-                    ],
-                })),
-            }),
+        self.filter = match self.filter.take() {
+            Some(existing) => Some(crate::filter::composite(
+                CompositeOperator::And,
+                existing,
+                [filter],
+            )),
             None => Some(filter),
         };
     }
@@ -411,6 +406,55 @@ impl<'a> QueryBuilder<'a> {
         (self.client, self.parent, query)
     }
 
+    /// Listens for realtime changes to the documents matching this query. The first snapshot
+    /// contains all matching documents, and every snapshot after that contains any changes.
+    ///
+    /// The underlying stream is resumed if the connection drops, so this only ends if the
+    /// listener encounters an error it can't recover from.
+    pub fn listen<D>(self) -> impl Stream<Item = crate::Result<Snapshot<D>>> + Send + use<D>
+    where
+        D: serde::de::DeserializeOwned + Send,
+    {
+        let (client, parent, query) = self.into_query();
+        Listener::new(client.clone(), QueryListener::new(parent, query)).into_stream()
+    }
+
+    /// Builds an aggregation query over the matching documents, for when more than a
+    /// single aggregation is needed.
+    pub fn aggregate(self) -> AggregationBuilder<'a> {
+        let (client, parent, query) = self.into_query();
+        AggregationBuilder::new(client, parent, query)
+    }
+
+    /// Counts the matching documents, without needing to retrieve them.
+    pub async fn count(self) -> crate::Result<u64> {
+        self.aggregate().count_single().run_single().await
+    }
+
+    /// Sums a numeric field across all matching documents.
+    pub async fn sum<T, S>(self, field: S) -> crate::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+        S: AsRef<str>,
+    {
+        self.aggregate()
+            .sum_single(field.as_ref())
+            .run_single()
+            .await
+    }
+
+    /// Averages a numeric field across all matching documents. Returns [`None`] if no
+    /// documents have a numeric value for the field.
+    pub async fn avg<S>(self, field: S) -> crate::Result<Option<f64>>
+    where
+        S: AsRef<str>,
+    {
+        self.aggregate()
+            .avg_single(field.as_ref())
+            .run_single()
+            .await
+    }
+
     pub async fn first<D>(mut self) -> crate::Result<Option<D>>
     where
         D: serde::de::DeserializeOwned,
@@ -448,6 +492,22 @@ impl<'a> QueryBuilder<'a> {
         })
     }
 
+    pub(crate) async fn run_in_transaction<D>(self, transaction: Bytes) -> crate::Result<Vec<D>>
+    where
+        D: serde::de::DeserializeOwned,
+    {
+        let stream = self
+            .run_raw_inner(Some(ConsistencySelector::Transaction(transaction)))
+            .await?;
+
+        QueryStream {
+            stream,
+            _marker: std::marker::PhantomData,
+        }
+        .run_to_completion()
+        .await
+    }
+
     pub async fn run_raw(self) -> crate::Result<RawQueryStream> {
         self.run_raw_inner(None).await
     }
@@ -151,6 +151,50 @@ pub(crate) fn escape_field_path(s: &str) -> String {
     dst
 }
 
+/// The inverse of [`escape_field_path`], splits an escaped field path into its unescaped
+/// components. Components that don't need unescaping are borrowed from the input.
+pub(crate) fn split_field_path(path: &str) -> Vec<Cow<'_, str>> {
+    let mut components = Vec::with_capacity(4);
+    let mut rem = path;
+
+    while !rem.is_empty() {
+        match rem.strip_prefix('`') {
+            Some(quoted) => {
+                let mut component = String::with_capacity(quoted.len());
+                let mut chars = quoted.char_indices();
+                let mut end = quoted.len();
+
+                while let Some((idx, ch)) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                component.push(escaped);
+                            }
+                        }
+                        '`' => {
+                            end = idx + 1;
+                            break;
+                        }
+                        _ => component.push(ch),
+                    }
+                }
+
+                components.push(Cow::Owned(component));
+                rem = &quoted[end..];
+            }
+            None => {
+                let (component, _) = rem.split_once('.').unwrap_or((rem, ""));
+                components.push(Cow::Borrowed(component));
+                rem = &rem[component.len()..];
+            }
+        }
+
+        rem = rem.strip_prefix('.').unwrap_or(rem);
+    }
+
+    components
+}
+
 fn component_needs_escaping(s: &str) -> bool {
     // macro to check for invalid characters.
     macro_rules! is_invalid_char {
@@ -319,6 +363,22 @@ fn test_escape_field_path() {
     }
 }
 
+#[test]
+fn test_split_field_path() {
+    const TEST_CASES: &[(&str, &[&str])] = &[
+        ("path.to.field", &["path", "to", "field"]),
+        ("path.`with space`.field", &["path", "with space", "field"]),
+        ("path.`with\\`tick`.field", &["path", "with`tick", "field"]),
+        ("`with.dot`.field", &["with.dot", "field"]),
+        ("path.`0`", &["path", "0"]),
+    ];
+
+    for (escaped, expected) in TEST_CASES {
+        let split = split_field_path(escaped);
+        assert_eq!(split, *expected, "{escaped}");
+    }
+}
+
 #[test]
 fn test_component_escape_test() {
     const TEST_CASES: &[(&str, bool)] = &[
@@ -1,8 +1,13 @@
 #![allow(dead_code)] // while in dev
 
 use bytes::Bytes;
+use net_utils::backoff::Backoff;
+use protos::firestore::get_document_request::ConsistencySelector;
+use protos::firestore::transaction_options::{Mode, ReadWrite};
 use protos::firestore::{self, BeginTransactionRequest, TransactionOptions};
 
+use crate::doc::{Doc, DocumentRef, WriteBuilder};
+use crate::query::QueryBuilder;
 use crate::{CollectionRef, Firestore, PathComponent};
 
 pub mod batch;
@@ -66,6 +71,82 @@ impl Transaction<Firestore> {
         }
     }
 
+    /// Reads a document as of the start of this transaction. In a read-write transaction, this
+    /// also locks the document until the transaction completes.
+    pub async fn get<O, C, D>(&self, doc_ref: &DocumentRef<C, D>) -> crate::Result<Option<Doc<O>>>
+    where
+        O: serde::de::DeserializeOwned,
+        C: PathComponent,
+        D: PathComponent,
+    {
+        let consistency = Some(ConsistencySelector::Transaction(self.bytes.clone()));
+
+        match doc_ref.get_doc_inner(consistency, None).await? {
+            Some(document) => Doc::from_document(document).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Runs a query within this transaction, returning all matching documents.
+    pub async fn query<D>(&self, query: QueryBuilder<'_>) -> crate::Result<Vec<D>>
+    where
+        D: serde::de::DeserializeOwned,
+    {
+        query.run_in_transaction(self.bytes.clone()).await
+    }
+
+    /// Queues a write, to be applied when the transaction commits.
+    pub fn write<C, D, W>(&mut self, wb: WriteBuilder<'_, C, D, W>) -> &mut Self
+    where
+        C: PathComponent,
+        D: PathComponent,
+        W: crate::doc::write_type::private::WriteType,
+    {
+        let (_, write) = wb.into_parts();
+        self.writes.push(write);
+        self
+    }
+
+    /// Queues a write that overwrites the document with `doc`.
+    pub fn set<T, C, D>(
+        &mut self,
+        doc_ref: &mut DocumentRef<C, D>,
+        doc: &T,
+    ) -> crate::Result<&mut Self>
+    where
+        T: serde::Serialize,
+        C: PathComponent,
+        D: PathComponent,
+    {
+        let wb = doc_ref.build_write().set(doc)?;
+        Ok(self.write(wb))
+    }
+
+    /// Queues a write that merges `doc` into the existing document.
+    pub fn update<T, C, D>(
+        &mut self,
+        doc_ref: &mut DocumentRef<C, D>,
+        doc: &T,
+    ) -> crate::Result<&mut Self>
+    where
+        T: serde::Serialize,
+        C: PathComponent,
+        D: PathComponent,
+    {
+        let wb = doc_ref.build_write().update(doc)?;
+        Ok(self.write(wb))
+    }
+
+    /// Queues a document deletion.
+    pub fn delete<C, D>(&mut self, doc_ref: &mut DocumentRef<C, D>) -> &mut Self
+    where
+        C: PathComponent,
+        D: PathComponent,
+    {
+        let wb = doc_ref.build_write().delete();
+        self.write(wb)
+    }
+
     pub async fn rollback(self) -> crate::Result<()> {
         let req = firestore::RollbackRequest {
             database: self.refer.qualified_db_path().to_owned(),
@@ -90,6 +171,63 @@ impl Transaction<Firestore> {
     }
 }
 
+/// Runs `transaction_fn` in a read-write transaction, then commits any writes it queued.
+///
+/// If the transaction is aborted due to contention (while beginning, reading or committing),
+/// `transaction_fn` is re-run in a new transaction, passing along the aborted transaction id so
+/// the retry keeps its place in line for any locks.
+pub(crate) async fn run<F, T>(firestore: &Firestore, mut transaction_fn: F) -> crate::Result<T>
+where
+    F: AsyncFnMut(&mut Transaction<Firestore>) -> crate::Result<T>,
+{
+    let mut backoff = Backoff::default();
+    let mut retry_transaction = Bytes::new();
+
+    loop {
+        let options = TransactionOptions {
+            mode: Some(Mode::ReadWrite(ReadWrite {
+                retry_transaction: retry_transaction.clone(),
+            })),
+        };
+
+        let error = match Transaction::start(firestore.clone(), options).await {
+            // beginning can be aborted too, in which case the retry keeps the previous
+            // transaction id.
+            Err(error) => error,
+            Ok(mut transaction) => {
+                retry_transaction = transaction.bytes.clone();
+
+                match transaction_fn(&mut transaction).await {
+                    Ok(output) => match transaction.commit().await {
+                        Ok(_) => return Ok(output),
+                        Err(error) => error,
+                    },
+                    Err(error) => {
+                        // the transaction is unusable either way, so a failed rollback isn't
+                        // worth surfacing over the original error.
+                        if let Err(rollback_error) = transaction.rollback().await {
+                            warn!(
+                                message = "failed to roll back transaction",
+                                error = ?rollback_error
+                            );
+                        }
+                        error
+                    }
+                }
+            }
+        };
+
+        if !error.is_aborted() {
+            return Err(error);
+        }
+
+        match backoff.backoff_once() {
+            Some(backoff) => backoff.await,
+            None => return Err(error),
+        }
+    }
+}
+
 pub struct TransactionCollectionRef<'a, C: PathComponent> {
     bytes: &'a Bytes,
     collec_ref: CollectionRef<C>,
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use protos::firestore::value::ValueType;
 use protos::firestore::{Document, Value};
 
@@ -74,3 +76,25 @@ where
 pub(crate) fn cmp_paths(a: &str, b: &str) -> std::cmp::Ordering {
     a.split('/').cmp(b.split('/'))
 }
+
+/// Extracts a (potentially nested) value from a set of document fields, given an escaped field
+/// path (i.e the same format used by [`FieldReference`]).
+///
+/// [`FieldReference`]: protos::firestore::structured_query::FieldReference
+pub(crate) fn extract_value<'a>(
+    fields: &'a HashMap<String, Value>,
+    field_path: &str,
+) -> Option<&'a Value> {
+    let mut components = crate::ser::split_field_path(field_path).into_iter();
+
+    let mut current = fields.get(&*components.next()?)?;
+
+    for component in components {
+        match current.value_type.as_ref()? {
+            ValueType::MapValue(map) => current = map.fields.get(&*component)?,
+            _ => return None,
+        }
+    }
+
+    Some(current)
+}
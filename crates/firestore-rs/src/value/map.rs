@@ -137,6 +137,18 @@ impl<'a> MapRef<'a> {
             fields: self.fields.iter(),
         }
     }
+
+    /// Returns all entries sorted by key, used when comparing map values.
+    pub(super) fn sorted_entries(&self) -> Vec<(&'a str, ValueRef<'a>)> {
+        let mut entries = self
+            .fields
+            .iter()
+            .map(|(k, v)| (k.as_str(), ValueRef::from_proto_ref(v)))
+            .collect::<Vec<_>>();
+
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        entries
+    }
 }
 
 pub struct Iter<'a> {
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use protos::r#type::LatLng;
 use timestamp::Timestamp;
 
@@ -47,8 +49,111 @@ impl<'a> ValueRef<'a> {
             .unwrap_or(Self::Null)
     }
 
-    pub(crate) fn ord_cmp(&self, _other: ValueRef<'_>) -> std::cmp::Ordering {
-        todo!()
+    /// Compares 2 values according to the Firestore value ordering, i.e the same ordering used
+    /// by the backend when applying `order_by` clauses.
+    ///
+    /// Ported from the C# client:
+    /// https://github.com/googleapis/google-cloud-dotnet/blob/f76e11a2f3a7403cb9199b72b2f5e1a303c9d50d/apis/Google.Cloud.Firestore/Google.Cloud.Firestore/ValueComparer.cs#L29
+    pub(crate) fn ord_cmp(&self, other: ValueRef<'_>) -> Ordering {
+        use ValueRef::*;
+
+        match (*self, other) {
+            // all nulls are equal according to the reference c# impl
+            (Null, Null) => Ordering::Equal,
+            (Bool(a), Bool(b)) => a.cmp(&b),
+            (Integer(a), Integer(b)) => a.cmp(&b),
+            (Integer(a), Double(b)) => cmp_doubles(a as f64, b),
+            (Double(a), Integer(b)) => cmp_doubles(a, b as f64),
+            (Double(a), Double(b)) => cmp_doubles(a, b),
+            (Timestamp(a), Timestamp(b)) => a.cmp(&b),
+            (String(a), String(b)) => a.cmp(b),
+            (Bytes(a), Bytes(b)) => a.cmp(b),
+            // references compare segment by segment, not as raw strings, so 'a/b' sorts
+            // before 'a-c/d' even though '/' > '-'.
+            (Reference(a), Reference(b)) => crate::util::cmp_paths(a.as_str(), b.as_str()),
+            (GeoPoint(a), GeoPoint(b)) => cmp_doubles(a.latitude, b.latitude)
+                .then_with(|| cmp_doubles(a.longitude, b.longitude)),
+            (Array(a), Array(b)) => {
+                let mut a_iter = a.iter();
+                let mut b_iter = b.iter();
+
+                loop {
+                    match (a_iter.next(), b_iter.next()) {
+                        (Some(a), Some(b)) => match a.ord_cmp(b) {
+                            Ordering::Equal => continue,
+                            non_eq => return non_eq,
+                        },
+                        (Some(_), None) => return Ordering::Greater,
+                        (None, Some(_)) => return Ordering::Less,
+                        (None, None) => return Ordering::Equal,
+                    }
+                }
+            }
+            (Map(a), Map(b)) => {
+                let a_entries = a.sorted_entries();
+                let b_entries = b.sorted_entries();
+
+                for ((a_key, a_value), (b_key, b_value)) in a_entries.iter().zip(b_entries.iter()) {
+                    let cmp = a_key.cmp(b_key).then_with(|| a_value.ord_cmp(*b_value));
+
+                    if cmp.is_ne() {
+                        return cmp;
+                    }
+                }
+
+                a_entries.len().cmp(&b_entries.len())
+            }
+            (a, b) => TypeOrder::from_value(&a).cmp(&TypeOrder::from_value(&b)),
+        }
+    }
+
+    /// Whether 2 values share a type in the Firestore value ordering (i.e integers and doubles
+    /// are both numbers), which is required for range comparisons to match.
+    #[cfg_attr(not(feature = "fake"), allow(dead_code))]
+    pub(crate) fn is_same_type_order(&self, other: ValueRef<'_>) -> bool {
+        TypeOrder::from_value(self) == TypeOrder::from_value(&other)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum TypeOrder {
+    Null,
+    Boolean,
+    Number,
+    Timestamp,
+    String,
+    Blob,
+    Ref,
+    GeoPoint,
+    Array,
+    Object,
+}
+
+impl TypeOrder {
+    fn from_value(value: &ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => Self::Null,
+            ValueRef::Map(_) => Self::Object,
+            ValueRef::Bool(_) => Self::Boolean,
+            ValueRef::Array(_) => Self::Array,
+            ValueRef::Timestamp(_) => Self::Timestamp,
+            ValueRef::Bytes(_) => Self::Blob,
+            ValueRef::Integer(_) | ValueRef::Double(_) => Self::Number,
+            ValueRef::String(_) => Self::String,
+            ValueRef::GeoPoint(_) => Self::GeoPoint,
+            ValueRef::Reference(_) => Self::Ref,
+        }
+    }
+}
+
+/// Firestore sorts NaN before all other numbers (including -inf), and treats all NaNs as equal.
+fn cmp_doubles(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        // -0.0 and 0.0 are equal, so we can't use total_cmp here.
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
     }
 }
 
@@ -73,76 +178,68 @@ impl serde::Serialize for ValueRef<'_> {
     }
 }
 
-/*
-impl PartialOrd for ValueRef<'_> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {}
-}
-
-impl Ord for ValueRef<'_> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Copied from:
-        // https://github.com/googleapis/google-cloud-dotnet/blob/f76e11a2f3a7403cb9199b72b2f5e1a303c9d50d/apis/Google.Cloud.Firestore/Google.Cloud.Firestore/ValueComparer.cs#L29
-        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-        enum TypeOrder {
-            Null,
-            Boolean,
-            Number,
-            Timestamp,
-            String,
-            Blob,
-            Ref,
-            GeoPoint,
-            Array,
-            Object,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        impl TypeOrder {
-            fn from_value(value: &ValueRef<'_>) -> Self {
-                match value {
-                    ValueRef::Null => Self::Null,
-                    ValueRef::Map(_) => Self::Object,
-                    ValueRef::Bool(_) => Self::Boolean,
-                    ValueRef::Array(_) => Self::Array,
-                    ValueRef::Timestamp(_) => Self::Timestamp,
-                    ValueRef::Bytes(_) => Self::Blob,
-                    ValueRef::Integer(_) | ValueRef::Double(_) => Self::Number,
-                    ValueRef::String(_) => Self::String,
-                    ValueRef::GeoPoint(_) => Self::GeoPoint,
-                    ValueRef::Reference(_) => Self::Ref,
-                }
+    fn assert_sorted(values: &[ValueRef<'_>]) {
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                assert_eq!(a.ord_cmp(*b), i.cmp(&j), "{a:?} vs {b:?}");
             }
         }
+    }
 
-        fn insane_dotnet_double_ord(a: f64, b: f64) -> std::cmp::Ordering {
-            use std::num::FpCategory::*;
-
-            match (a.classify(), b.classify()) {
-                // if both are finite numbers, use total_cmp
-                (Normal | Subnormal | Zero, Normal | Subnormal | Zero) => a.total_cmp(&b),
-                (Nan, Nan) => std::cmp::Ordering::Equal,
-                (Infinite, Infinite) => match (a.is_sign_positive(), b.is_sign_positive()) {
-                    (true, true) | (false, false) => std::cmp::Ordering::Equal,
-                    (true, false) => std::cmp::Ordering::Greater,
-                    (false, true) => std::cmp::Ordering::Less,
-                },
-                (Nan, Normal | Subnormal | Zero | Infinite) => std::cmp::Ordering::Less,
-                (Normal | Subnormal | Zero | Infinite, Nan) => std::cmp::Ordering::Greater,
-                (Infinite, Subnormal | Zero) => a.total_cmp(&b),
-            }
-        }
+    #[test]
+    fn test_ord_cmp() {
+        let nested_array = [protos::firestore::Value {
+            value_type: Some(protos::firestore::value::ValueType::IntegerValue(1)),
+        }];
 
-        use ValueRef::*;
+        // one of each type, in the firestore type order
+        assert_sorted(&[
+            ValueRef::Null,
+            ValueRef::Bool(false),
+            ValueRef::Bool(true),
+            ValueRef::Double(f64::NAN),
+            ValueRef::Double(f64::NEG_INFINITY),
+            ValueRef::Integer(-1),
+            ValueRef::Double(0.5),
+            ValueRef::Integer(1),
+            ValueRef::Timestamp(Timestamp::from_seconds(0)),
+            ValueRef::String(""),
+            ValueRef::String("a"),
+            ValueRef::Bytes(b"a"),
+            ValueRef::Reference(Reference::new("docs/a")),
+            ValueRef::GeoPoint(LatLng {
+                latitude: 0.0,
+                longitude: 0.0,
+            }),
+            ValueRef::Array(ArrayRef::from_values(&[])),
+            ValueRef::Array(ArrayRef::from_values(&nested_array)),
+            ValueRef::Map(MapRef::from_fields(&Default::default())),
+        ]);
 
-        match (self, other) {
-            // all nulls are equal according to the reference c# impl
-            (Null, Null) => std::cmp::Ordering::Equal,
-            (String(a), String(b)) => a.cmp(b),
-            (Bytes(a), Bytes(b)) => a.cmp(b),
-            (Reference(a), Reference(b)) => a.cmp(b),
-            (Bool(a), Bool(b)) => a.cmp(b),
-            (GeoPoint(a), GeoPoint(b)) => {} /* _ => TypeOrder::from_value(self).cmp(&
-                                              * TypeOrder::from_value(other)), */
-        }
+        // numbers compare by value, regardless of integer vs double
+        assert!(ValueRef::Integer(1).ord_cmp(ValueRef::Double(1.0)).is_eq());
+        assert!(
+            ValueRef::Double(-0.0)
+                .ord_cmp(ValueRef::Double(0.0))
+                .is_eq()
+        );
+        assert!(
+            ValueRef::Double(f64::NAN)
+                .ord_cmp(ValueRef::Double(f64::NAN))
+                .is_eq()
+        );
+
+        // references compare by path segment, where a raw string comparison would put
+        // 'docs/a-b' first, since '-' < '/'.
+        assert_sorted(&[
+            ValueRef::Reference(Reference::new("docs/a")),
+            ValueRef::Reference(Reference::new("docs/a/sub/b")),
+            ValueRef::Reference(Reference::new("docs/a-b")),
+            ValueRef::Reference(Reference::new("docs/b")),
+        ]);
     }
 }
-*/
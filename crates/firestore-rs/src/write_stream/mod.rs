@@ -1,64 +1,332 @@
+//! Pipelined writes via the streaming `Firestore.Write` RPC.
+//!
+//! Unlike [`BatchWrite`], writes are sent as soon as they're queued, without waiting for
+//! previously sent writes to be acknowledged. Results are returned in the same order the writes
+//! were sent.
+//!
+//! [`BatchWrite`]: crate::batch::write::BatchWrite
+use std::collections::VecDeque;
+
 use bytes::Bytes;
+use net_utils::backoff::Backoff;
 use net_utils::bidi2::{self, RequestSink};
 use protos::firestore::{Write, WriteRequest, WriteResponse, WriteResult};
+use timestamp::Duration;
 
+use crate::PathComponent;
+use crate::client::FirestoreClient;
 use crate::doc::WriteBuilder;
-use crate::{Firestore, PathComponent};
 
+/// The default number of requests that can be sent before waiting on the server to
+/// acknowledge them.
+const DEFAULT_MAX_IN_FLIGHT: usize = 64;
+
+/// The default max number of consecutive failed attempts to (re)connect before giving up. The
+/// backoff is reset every time the server acknowledges a write.
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+fn default_backoff(max_retries: u32) -> Backoff {
+    Backoff::<u32>::builder()
+        .base_delay(Duration::from_millis(100))
+        .max_timeout(Duration::from_seconds(10))
+        .max_retries(max_retries)
+        .build_local()
+}
+
+/// A pipelined writer. Created via [`Firestore::write_stream`].
+///
+/// If the underlying stream errors out with a transient error, a new stream is opened and any
+/// unacknowledged writes are re-sent on it. Reconnects are retried with an exponential backoff,
+/// up to [`WriteStream::with_max_retries`] consecutive failures, after which the last error is
+/// returned and all unacknowledged writes are dropped. Since there's no way to tell if the
+/// server applied an unacknowledged write before the stream died, non-idempotent writes (i.e
+/// field transforms) should use a precondition.
+///
+/// [`Firestore::write_stream`]: crate::Firestore::write_stream
 pub struct WriteStream {
-    firestore: Firestore,
+    client: FirestoreClient,
+    stream: Option<ActiveStream>,
+    /// The token from the last response on the current stream, sent with each request to
+    /// acknowledge the responses we've received.
+    stream_token: Bytes,
+    /// Sent requests that haven't been acknowledged yet, in the order they were sent. Kept
+    /// around so they can be re-sent if the stream needs to be re-established.
+    in_flight: VecDeque<Vec<Write>>,
+    /// Results for acknowledged writes that haven't been taken yet.
+    completed: VecDeque<WriteResult>,
+    max_in_flight: usize,
+    backoff: Backoff,
+}
+
+struct ActiveStream {
     sink: RequestSink<WriteRequest>,
     responses: tonic::Streaming<WriteResponse>,
-    stream_token: Bytes,
-    stream_id: Box<str>,
 }
 
 impl WriteStream {
-    pub(crate) async fn new(mut firestore: Firestore) -> crate::Result<Self> {
-        let (sink, responses, first_response) =
-            create_stream(&mut firestore, String::new(), Bytes::new()).await?;
+    pub(crate) async fn new(client: FirestoreClient) -> crate::Result<Self> {
+        let mut write_stream = Self {
+            client,
+            stream: None,
+            stream_token: Bytes::new(),
+            in_flight: VecDeque::with_capacity(DEFAULT_MAX_IN_FLIGHT),
+            completed: VecDeque::new(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            backoff: default_backoff(DEFAULT_MAX_RETRIES),
+        };
+
+        write_stream.reconnect().await?;
+        Ok(write_stream)
+    }
+
+    /// Sets the max number of unacknowledged writes. Once hit, [`WriteStream::send`] waits on
+    /// the server to acknowledge the oldest write before sending another.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Sets the max number of consecutive failed attempts to re-establish the stream before
+    /// giving up. Defaults to 5.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.backoff = default_backoff(max_retries);
+        self
+    }
+
+    /// The number of writes sent that haven't been acknowledged by the server yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
 
-        Ok(Self {
-            firestore,
-            sink,
-            responses,
-            stream_token: first_response.stream_token,
-            stream_id: first_response.stream_id.into_boxed_str(),
-        })
+    /// Queues a write, waiting if there are already too many unacknowledged writes. The result
+    /// can be retrieved later via [`WriteStream::next_result`] or [`WriteStream::flush`].
+    pub async fn send<C, D, W>(&mut self, wb: WriteBuilder<'_, C, D, W>) -> crate::Result<()>
+    where
+        C: PathComponent,
+        D: PathComponent,
+        W: crate::doc::write_type::private::WriteType,
+    {
+        let (_, write) = wb.into_parts();
+        self.send_writes(vec![write]).await
     }
 
-    pub async fn write_one<D, C, W>(
+    /// Sends a single write, waiting for it to be acknowledged. Results from previously sent
+    /// writes are still kept around, to be returned by [`WriteStream::next_result`].
+    pub async fn write_one<C, D, W>(
         &mut self,
         wb: WriteBuilder<'_, C, D, W>,
     ) -> crate::Result<WriteResult>
     where
         C: PathComponent,
         D: PathComponent,
-        W: crate::doc::write_type::WriteType,
+        W: crate::doc::write_type::private::WriteType,
     {
-        let (_, write) = wb.into_parts();
-        todo!()
+        self.send(wb).await?;
+        self.wait_for_in_flight().await?;
+
+        // since this was the last write sent, it's always the last result.
+        self.completed.pop_back().ok_or(crate::Error::Internal(
+            "write acknowledged without a result",
+        ))
+    }
+
+    /// Returns the result of the oldest write that hasn't had its result taken yet, waiting
+    /// for the server to acknowledge it if needed. Returns [`None`] if there are no writes
+    /// outstanding.
+    pub async fn next_result(&mut self) -> crate::Result<Option<WriteResult>> {
+        if self.completed.is_empty() && !self.in_flight.is_empty() {
+            self.receive_response().await?;
+        }
+
+        Ok(self.completed.pop_front())
+    }
+
+    /// Waits for all in-flight writes to be acknowledged, returning all outstanding results in
+    /// the order the writes were sent.
+    pub async fn flush(&mut self) -> crate::Result<Vec<WriteResult>> {
+        self.wait_for_in_flight().await?;
+        Ok(self.completed.drain(..).collect())
+    }
+
+    /// Flushes all outstanding writes, then closes the stream.
+    pub async fn close(mut self) -> crate::Result<Vec<WriteResult>> {
+        let results = self.flush().await?;
+
+        if let Some(mut stream) = self.stream.take() {
+            stream.sink.close();
+        }
+
+        Ok(results)
+    }
+
+    async fn wait_for_in_flight(&mut self) -> crate::Result<()> {
+        while !self.in_flight.is_empty() {
+            self.receive_response().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn send_writes(&mut self, writes: Vec<Write>) -> crate::Result<()> {
+        // backpressure, dont send anything else until the server catches up
+        while self.in_flight.len() >= self.max_in_flight {
+            self.receive_response().await?;
+        }
+
+        let request = self.build_request(writes.clone());
+        self.in_flight.push_back(writes);
+
+        let Some(ref stream) = self.stream else {
+            // reconnecting re-sends everything in flight, including these writes.
+            return self.reconnect().await;
+        };
+
+        // if the stream died, the write will be re-sent once the stream is re-established.
+        if stream.sink.send(request).is_err() {
+            self.stream = None;
+        }
+
+        Ok(())
+    }
+
+    async fn receive_response(&mut self) -> crate::Result<()> {
+        loop {
+            let result = match self.stream {
+                Some(ref mut stream) => stream.responses.message().await,
+                None => {
+                    self.reconnect().await?;
+                    continue;
+                }
+            };
+
+            let (error, retryable) = match result {
+                Ok(Some(response)) => return self.handle_response(response),
+                // the server hung up on us, so try and resume
+                Ok(None) => (
+                    crate::Error::Internal("firestore.Write stream closed unexpectedly"),
+                    true,
+                ),
+                Err(status) => {
+                    let error = crate::Error::from(status);
+                    let retryable = error.is_transient_error();
+                    (error, retryable)
+                }
+            };
+
+            self.stream = None;
+
+            if !retryable {
+                // we can't resume from this, so start from scratch next time.
+                self.reset();
+                return Err(error);
+            }
+
+            match self.backoff.backoff_once() {
+                Some(backoff) => backoff.await,
+                None => {
+                    self.reset();
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    fn handle_response(&mut self, response: WriteResponse) -> crate::Result<()> {
+        // validate before touching any state, so the writes stay in flight (and get re-sent
+        // on the next stream) if the response doesn't line up.
+        let writes = self.in_flight.front().ok_or(crate::Error::Internal(
+            "received a WriteResponse with no writes in flight",
+        ))?;
+
+        if writes.len() != response.write_results.len() {
+            return Err(crate::Error::Internal(
+                "WriteResponse has a different number of results than writes sent",
+            ));
+        }
+
+        self.in_flight.pop_front();
+        self.stream_token = response.stream_token;
+        self.backoff.reset();
+
+        self.completed.extend(response.write_results);
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.stream = None;
+        self.stream_token = Bytes::new();
+        self.in_flight.clear();
+        self.backoff.reset();
+    }
+
+    fn build_request(&self, writes: Vec<Write>) -> WriteRequest {
+        WriteRequest {
+            database: String::new(),
+            stream_id: String::new(),
+            writes,
+            stream_token: self.stream_token.clone(),
+            labels: Default::default(),
+        }
+    }
+
+    /// Re-establishes the stream, retrying transient failures with a backoff. On a
+    /// non-transient error, or once out of retries, all unacknowledged writes are dropped and
+    /// the error is returned.
+    async fn reconnect(&mut self) -> crate::Result<()> {
+        loop {
+            let error = match self.connect().await {
+                Ok(()) => return Ok(()),
+                Err(error) if error.is_transient_error() => error,
+                Err(error) => {
+                    self.reset();
+                    return Err(error);
+                }
+            };
+
+            match self.backoff.backoff_once() {
+                Some(backoff) => backoff.await,
+                None => {
+                    self.reset();
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// Opens a new stream, then re-sends any writes that weren't acknowledged on the old one.
+    ///
+    /// Some of those writes may have been applied before the old stream died, but resuming it
+    /// wouldn't help: the server replays the responses for those writes, and since there's no
+    /// way to tell which writes the replayed responses belong to, the re-sent writes would
+    /// either be applied twice or get paired up with the wrong results.
+    async fn connect(&mut self) -> crate::Result<()> {
+        let (sink, responses, first_response) = create_stream(&self.client).await?;
+
+        self.stream_token = first_response.stream_token;
+
+        for writes in self.in_flight.iter() {
+            sink.send(self.build_request(writes.clone()))
+                .map_err(|_| crate::Error::Internal("firestore.Write request stream closed"))?;
+        }
+
+        self.stream = Some(ActiveStream { sink, responses });
+        Ok(())
     }
 }
 
 async fn create_stream(
-    firestore: &mut Firestore,
-    stream_id: String,
-    stream_token: Bytes,
+    client: &FirestoreClient,
 ) -> crate::Result<(
     RequestSink<WriteRequest>,
     tonic::Streaming<WriteResponse>,
     WriteResponse,
 )> {
-    let database = firestore.qualified_db_path().to_owned();
-    let mut client = firestore.client.get_mut_ref();
-
     let (sink, stream) = bidi2::build_pair();
 
     let first_request = WriteRequest {
-        database,
-        stream_id,
-        stream_token,
+        database: client.qualified_db_path.to_string(),
+        stream_id: String::new(),
+        stream_token: Bytes::new(),
         writes: Vec::new(),
         labels: Default::default(),
     };
@@ -66,7 +334,7 @@ async fn create_stream(
     sink.send(first_request)
         .expect("stream hasnt even been used yet, the inner channel is alive");
 
-    let mut responses = client.write(stream).await?.into_inner();
+    let mut responses = client.get().write(stream).await?.into_inner();
 
     let Some(first_resp) = responses.message().await? else {
         return Err(crate::Error::Internal(
@@ -0,0 +1,355 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use firestore_rs::fake::FakeFirestore;
+use firestore_rs::filter::Filter;
+use futures::StreamExt;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Whale {
+    name: String,
+    pod: String,
+    age: u32,
+}
+
+impl Whale {
+    fn new(name: &str, pod: &str, age: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            pod: pod.to_owned(),
+            age,
+        }
+    }
+}
+
+async fn seed(firestore: &firestore_rs::Firestore) -> firestore_rs::Result<()> {
+    let whales = [
+        Whale::new("granny", "J", 105),
+        Whale::new("ruffles", "J", 59),
+        Whale::new("tahlequah", "J", 26),
+        Whale::new("kiki", "K", 36),
+        Whale::new("lobo", "L", 7),
+    ];
+
+    for whale in whales {
+        firestore
+            .collection("whales")
+            .doc(whale.name.clone())
+            .set(&whale)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_get_delete() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+
+    let whale = Whale::new("granny", "J", 105);
+    let mut doc_ref = firestore.collection("whales").doc("granny");
+
+    assert!(doc_ref.get::<Whale>().await?.is_none());
+
+    doc_ref.set(&whale).await?;
+    let doc = doc_ref.get::<Whale>().await?.expect("doc was just set");
+    assert_eq!(doc.into_inner(), whale);
+
+    doc_ref.delete().await?;
+    assert!(doc_ref.get::<Whale>().await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+    seed(&firestore).await?;
+
+    let mut collection = firestore.collection("whales");
+
+    let j_pod = collection
+        .query()
+        .where_field("pod")
+        .equals("J")
+        .order_by_desc("age")
+        .run_to_completion::<Whale>()
+        .await?;
+
+    let names = j_pod.iter().map(|w| w.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["granny", "ruffles", "tahlequah"]);
+
+    let filter = Filter::or([
+        Filter::field("pod").equals("K")?,
+        Filter::field("age").less_than(10)?,
+    ])?;
+
+    let mut filtered = collection
+        .query()
+        .filter(filter)
+        .run_to_completion::<Whale>()
+        .await?;
+
+    filtered.sort_by_key(|w| w.age);
+    let names = filtered.iter().map(|w| w.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["lobo", "kiki"]);
+
+    let paged = collection
+        .query()
+        .order_by("age")
+        .start_after(26)?
+        .limit(2)
+        .run_to_completion::<Whale>()
+        .await?;
+
+    let names = paged.iter().map(|w| w.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["kiki", "ruffles"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collection_group_and_ids() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let mut firestore = fake.firestore().await?;
+    seed(&firestore).await?;
+
+    firestore
+        .collection("whales")
+        .doc("granny")
+        .collection("sightings")
+        .doc("1")
+        .set(&serde_json::json!({ "location": "haro strait" }))
+        .await?;
+
+    firestore
+        .collection("sightings")
+        .doc("2")
+        .set(&serde_json::json!({ "location": "boundary pass" }))
+        .await?;
+
+    let sightings = firestore
+        .collection_group("sightings")
+        .run_to_completion::<serde_json::Value>()
+        .await?;
+
+    assert_eq!(sightings.len(), 2);
+
+    let mut ids = Vec::new();
+    let mut id_stream = std::pin::pin!(firestore.list_collection_ids());
+    while let Some(batch) = id_stream.next().await {
+        ids.extend(batch?);
+    }
+
+    assert_eq!(ids, ["sightings", "whales"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transaction_retries_on_contention() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+    seed(&firestore).await?;
+
+    let attempts = AtomicUsize::new(0);
+
+    let age = firestore
+        .run_transaction(async |tx| {
+            let mut doc_ref = firestore.collection("whales").doc("lobo");
+
+            let mut whale = tx
+                .get::<Whale, _, _>(&doc_ref)
+                .await?
+                .expect("lobo was seeded")
+                .into_inner();
+
+            // modify the document outside of the transaction on the first attempt, which
+            // should abort the commit and force a retry.
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                doc_ref.set(&Whale::new("lobo", "L", 8)).await?;
+            }
+
+            whale.age += 1;
+            tx.set(&mut doc_ref, &whale)?;
+            Ok(whale.age)
+        })
+        .await?;
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(age, 9);
+
+    let stored = firestore
+        .collection("whales")
+        .doc("lobo")
+        .get::<Whale>()
+        .await?
+        .expect("lobo exists")
+        .into_inner();
+
+    assert_eq!(stored.age, 9);
+
+    Ok(())
+}
+
+async fn get_whale(
+    firestore: &firestore_rs::Firestore,
+    name: &str,
+) -> firestore_rs::Result<Option<Whale>> {
+    let doc = firestore
+        .collection("whales")
+        .doc(name)
+        .get::<Whale>()
+        .await?;
+
+    Ok(doc.map(|doc| doc.into_inner()))
+}
+
+#[tokio::test]
+async fn test_write_stream() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+
+    let mut stream = firestore.write_stream().await?;
+
+    let whales = [
+        Whale::new("granny", "J", 105),
+        Whale::new("kiki", "K", 36),
+        Whale::new("lobo", "L", 7),
+    ];
+
+    for whale in whales.iter() {
+        let mut doc_ref = firestore.collection("whales").doc(whale.name.clone());
+        stream.send(doc_ref.build_write().set(whale)?).await?;
+    }
+
+    let results = stream.close().await?;
+    assert_eq!(results.len(), whales.len());
+
+    for whale in whales {
+        assert_eq!(get_whale(&firestore, &whale.name).await?, Some(whale));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_stream_reconnect() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+
+    let mut stream = firestore.write_stream().await?;
+
+    // the first write kills the stream, then the next 2 attempts to reconnect fail as well.
+    fake.fail_write_streams(3);
+
+    let whales = [Whale::new("granny", "J", 105), Whale::new("kiki", "K", 36)];
+
+    for whale in whales.iter() {
+        let mut doc_ref = firestore.collection("whales").doc(whale.name.clone());
+        stream.send(doc_ref.build_write().set(whale)?).await?;
+    }
+
+    // both unacknowledged writes should be re-sent once the stream is back up
+    let results = stream.flush().await?;
+    assert_eq!(results.len(), whales.len());
+    assert_eq!(stream.in_flight(), 0);
+
+    for whale in whales {
+        assert_eq!(get_whale(&firestore, &whale.name).await?, Some(whale));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_stream_applied_but_unacknowledged() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+
+    let mut stream = firestore.write_stream().await?;
+
+    // the first write gets applied, but the stream dies before it's acknowledged.
+    fake.fail_write_acks(1);
+
+    let whales = [
+        Whale::new("granny", "J", 105),
+        Whale::new("kiki", "K", 36),
+        Whale::new("lobo", "L", 7),
+    ];
+
+    for whale in whales.iter() {
+        let mut doc_ref = firestore.collection("whales").doc(whale.name.clone());
+        stream.send(doc_ref.build_write().set(whale)?).await?;
+    }
+
+    // every write is re-sent on a new stream, so there's exactly one result per write, each
+    // from the write that stuck.
+    let results = stream.flush().await?;
+    assert_eq!(results.len(), whales.len());
+    assert_eq!(stream.in_flight(), 0);
+
+    for (whale, result) in whales.iter().zip(results) {
+        let doc = firestore
+            .collection("whales")
+            .doc(whale.name.as_str())
+            .get::<Whale>()
+            .await?
+            .expect("whale should exist");
+
+        assert_eq!(
+            doc.update_time,
+            result.update_time.map(timestamp::Timestamp::from)
+        );
+        assert_eq!(doc.into_inner(), *whale);
+    }
+
+    // later writes still line up with their own results
+    let mut doc_ref = firestore.collection("whales").doc("granny");
+    let older = Whale::new("granny", "J", 106);
+    let result = stream.write_one(doc_ref.build_write().set(&older)?).await?;
+
+    let doc = doc_ref.get::<Whale>().await?.expect("whale should exist");
+    assert_eq!(
+        doc.update_time,
+        result.update_time.map(timestamp::Timestamp::from)
+    );
+    assert_eq!(doc.into_inner(), older);
+
+    assert!(stream.close().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_stream_gives_up() -> firestore_rs::Result<()> {
+    let fake = FakeFirestore::start().await?;
+    let firestore = fake.firestore().await?;
+
+    let mut stream = firestore.write_stream().await?.with_max_retries(2);
+
+    fake.fail_write_streams(usize::MAX);
+
+    let whale = Whale::new("granny", "J", 105);
+    let mut doc_ref = firestore.collection("whales").doc("granny");
+    stream.send(doc_ref.build_write().set(&whale)?).await?;
+
+    let error = stream
+        .flush()
+        .await
+        .expect_err("stream should run out of retries");
+    assert!(error.is_transient_error());
+
+    // the failed write is dropped, rather than being retried forever
+    assert_eq!(stream.in_flight(), 0);
+    assert_eq!(get_whale(&firestore, "granny").await?, None);
+
+    // once the server recovers, the stream is usable again
+    fake.fail_write_streams(0);
+    stream.send(doc_ref.build_write().set(&whale)?).await?;
+    assert_eq!(stream.close().await?.len(), 1);
+    assert_eq!(get_whale(&firestore, "granny").await?, Some(whale));
+
+    Ok(())
+}
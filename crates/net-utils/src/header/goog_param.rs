@@ -28,6 +28,10 @@ impl<Svc> GoogRequestParam<Svc> {
         let value = value.try_into()?;
         Ok(Self::new(svc, value))
     }
+
+    pub fn into_inner(self) -> Svc {
+        self.svc
+    }
 }
 
 crate::util::impl_service_for_wrapper_and_ref! {
@@ -1,15 +1,36 @@
-use super::operator::Operation;
+use super::ident::{Ident, Path, is_ident_like};
+use super::operator::{
+    AccessOperation, Between, BinaryOperation, BinaryOperator, InOperation, InRhs, Index,
+    Operation, Sides, UnaryOperation, UnaryOperator, precedence,
+};
+use super::types::{StructField, Type};
+use super::{AsAlias, Cast};
 use crate::Error;
-use crate::ast::ParseTokens;
-use crate::ast::query::QueryExpr;
-use crate::tokens::TokenizerKind;
+use crate::ast::query::{self, QueryExpr};
+use crate::ast::reserved::{DataType, Keyword};
+use crate::ast::{ParseTokens, UnexpectedToken, parse_comma_separated, parse_parens};
+use crate::tokens::{PunctOrOp, Span, StringLiteral, Token, Tokenizer, TokenizerKind};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression<'src> {
     Value(ValueExpr<'src>),
+    /// A column, query parameter or field path, i.e 'col', '@param' or 'table.struct_col.field'.
+    Path(Path<'src>),
     FunctionCall(FunctionCall<'src>),
-    SubQuery(QueryExpr<'src>),
+    SubQuery(Box<QueryExpr<'src>>),
     Operation(Operation<'src>),
+    /// 'CAST(expr AS type)', or 'SAFE_CAST(expr AS type)' if 'safe' is set.
+    Cast {
+        safe: bool,
+        cast: Box<Cast<Expression<'src>, Type<'src>>>,
+    },
+    Case(Box<CaseExpr<'src>>),
+    Extract(Box<Extract<'src>>),
+    Interval(Box<Interval<'src>>),
+    /// 'EXISTS (subquery)'
+    Exists(Box<QueryExpr<'src>>),
+    /// 'ARRAY (subquery)'
+    ArraySubQuery(Box<QueryExpr<'src>>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,17 +39,32 @@ pub enum ValueExpr<'src> {
     Boolean(bool),
     Integer(i64),
     Float(f64),
-    String(&'src str),
-    Array(Vec<Expression<'src>>),
+    String(StringLiteral<'src>),
+    /// A typed literal, i.e "DATE '2024-01-01'" or "JSON '{}'".
+    Typed {
+        data_type: DataType,
+        value: StringLiteral<'src>,
+    },
+    /// 'ARRAY<T>[...]', 'ARRAY[...]' or '[...]'.
+    Array {
+        element_type: Option<Type<'src>>,
+        elements: Vec<Expression<'src>>,
+    },
+    /// 'STRUCT<...>(...)' or 'STRUCT(...)'.
+    Struct {
+        fields: Option<Vec<StructField<'src>>>,
+        values: Vec<AsAlias<'src, Expression<'src>>>,
+    },
+    /// A tuple of expressions, i.e '(a, b)'.
     StructTuple(Vec<Expression<'src>>),
 }
 
 impl<'src> ParseTokens<'src> for Expression<'src> {
-    fn parse_tokens<T>(_tokens: &mut T) -> Result<Self, Error<'src>>
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
     where
         T: TokenizerKind<'src>,
     {
-        todo!()
+        parse_expr(tokens, 0)
     }
 }
 
@@ -36,7 +72,7 @@ impl<'src> ParseTokens<'src> for Expression<'src> {
 pub struct FunctionCall<'src> {
     pub safe: bool,
     pub name: &'src str,
-    pub arguments: Option<Arguments<'src>>,
+    pub arguments: Arguments<'src>,
     pub function_hint: Option<FunctionHint>,
 }
 
@@ -46,16 +82,20 @@ pub enum FunctionHint {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Arguments<'src> {
-    arguments: Vec<Expression<'src>>,
-    aggregate_options: Option<AggregateOptions<'src>>,
+pub enum Arguments<'src> {
+    /// 'COUNT(*)'
+    Star,
+    List {
+        arguments: Vec<Expression<'src>>,
+        aggregate_options: Option<AggregateOptions<'src>>,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct AggregateOptions<'src> {
-    distinct: bool,
-    nulls: Option<NullHandling>,
-    having: Option<Box<Having<'src>>>,
+    pub distinct: bool,
+    pub nulls: Option<NullHandling>,
+    pub having: Option<Box<Having<'src>>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -69,3 +109,980 @@ pub enum Having<'src> {
     Min(Expression<'src>),
     Max(Expression<'src>),
 }
+
+/// 'CASE [expr] WHEN ... THEN ... [ELSE ...] END'
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseExpr<'src> {
+    pub operand: Option<Expression<'src>>,
+    pub branches: Vec<WhenThen<'src>>,
+    pub else_expr: Option<Expression<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhenThen<'src> {
+    pub when: Expression<'src>,
+    pub then: Expression<'src>,
+}
+
+/// 'EXTRACT(part FROM expr [AT TIME ZONE time_zone])'
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extract<'src> {
+    pub part: Ident<'src>,
+    pub expr: Expression<'src>,
+    pub time_zone: Option<Expression<'src>>,
+}
+
+/// 'INTERVAL value part', i.e 'INTERVAL 1 DAY'
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval<'src> {
+    pub value: Expression<'src>,
+    pub part: Ident<'src>,
+}
+
+const EXPECTED_EXPR: &str = "an expression";
+
+/// Infix operators, found after an expression has been parsed.
+enum Infix {
+    Binary(BinaryOperator),
+    Is,
+    Between { negated: bool },
+    In { negated: bool },
+}
+
+impl Infix {
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Binary(op) => op.precedence(),
+            Self::Is | Self::Between { .. } | Self::In { .. } => precedence::COMPARISON,
+        }
+    }
+
+    /// How many tokens make up the operator.
+    fn token_count(&self) -> usize {
+        match self {
+            Self::Between { negated } | Self::In { negated } if *negated => 2,
+            Self::Binary(BinaryOperator::NotLike) => 2,
+            _ => 1,
+        }
+    }
+}
+
+fn peek_infix<'src, T>(tokens: &mut T) -> Result<Option<Infix>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let Some((_, token)) = tokens.peek()? else {
+        return Ok(None);
+    };
+
+    if let Some(punct) = token.as_punct_or_opt() {
+        let op = match punct {
+            PunctOrOp::Mul => BinaryOperator::Mul,
+            PunctOrOp::Div => BinaryOperator::Div,
+            PunctOrOp::Concat => BinaryOperator::Concat,
+            PunctOrOp::Plus => BinaryOperator::Add,
+            PunctOrOp::Neg => BinaryOperator::Sub,
+            PunctOrOp::LeftShift => BinaryOperator::LeftShift,
+            PunctOrOp::RightShift => BinaryOperator::RightShift,
+            PunctOrOp::And => BinaryOperator::BitwiseAnd,
+            PunctOrOp::Xor => BinaryOperator::BitwiseXor,
+            PunctOrOp::Or => BinaryOperator::BitwiseOr,
+            PunctOrOp::Eq => BinaryOperator::Eq,
+            PunctOrOp::Lt => BinaryOperator::Lt,
+            PunctOrOp::Gt => BinaryOperator::Gt,
+            PunctOrOp::Lte => BinaryOperator::Lte,
+            PunctOrOp::Gte => BinaryOperator::Gte,
+            PunctOrOp::NotEq => BinaryOperator::NotEq,
+            _ => return Ok(None),
+        };
+
+        return Ok(Some(Infix::Binary(op)));
+    }
+
+    let infix = match token.as_keyword() {
+        Some(Keyword::And) => Infix::Binary(BinaryOperator::LogicalAnd),
+        Some(Keyword::Or) => Infix::Binary(BinaryOperator::LogicalOr),
+        Some(Keyword::Like) => Infix::Binary(BinaryOperator::Like),
+        Some(Keyword::Is) => Infix::Is,
+        Some(Keyword::Between) => Infix::Between { negated: false },
+        Some(Keyword::In) => Infix::In { negated: false },
+        Some(Keyword::Not) => match tokens.peek_nth(1)?.and_then(|(_, tok)| tok.as_keyword()) {
+            Some(Keyword::Like) => Infix::Binary(BinaryOperator::NotLike),
+            Some(Keyword::Between) => Infix::Between { negated: true },
+            Some(Keyword::In) => Infix::In { negated: true },
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(infix))
+}
+
+/// Pratt style expression parser. Only operators that bind tighter than 'min_precedence' are
+/// consumed.
+pub(crate) fn parse_expr<'src, T>(
+    tokens: &mut T,
+    min_precedence: u8,
+) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let mut lhs = if tokens.next_if_kw(Keyword::Not)? {
+        let expr = parse_expr(tokens, precedence::NOT)?;
+        unary(UnaryOperator::LogicalNot, expr)
+    } else {
+        parse_unary(tokens)?
+    };
+
+    while let Some(infix) = peek_infix(tokens)? {
+        let precedence = infix.precedence();
+        if precedence <= min_precedence {
+            break;
+        }
+
+        for _ in 0..infix.token_count() {
+            tokens.next_or_eof()?;
+        }
+
+        lhs = match infix {
+            Infix::Binary(operator) => {
+                let rhs = parse_expr(tokens, precedence)?;
+                Expression::Operation(Operation::Binary(BinaryOperation {
+                    sides: Box::new(Sides { lhs, rhs }),
+                    operator,
+                }))
+            }
+            Infix::Is => parse_is(lhs, tokens)?,
+            Infix::Between { negated } => {
+                // the bounds can't contain comparisons or logical operators, otherwise the 'AND'
+                // separating them would be ambiguous.
+                let low = parse_expr(tokens, precedence::COMPARISON)?;
+                tokens.expect_kw(Keyword::And)?;
+                let high = parse_expr(tokens, precedence::COMPARISON)?;
+
+                Expression::Operation(Operation::Between(Between {
+                    expr: Box::new(lhs),
+                    negated,
+                    low: Box::new(low),
+                    high: Box::new(high),
+                }))
+            }
+            Infix::In { negated } => {
+                let rhs = parse_in_rhs(tokens)?;
+                Expression::Operation(Operation::In(InOperation {
+                    expr: Box::new(lhs),
+                    negated,
+                    rhs,
+                }))
+            }
+        };
+    }
+
+    Ok(lhs)
+}
+
+fn unary(operator: UnaryOperator, expr: Expression<'_>) -> Expression<'_> {
+    Expression::Operation(Operation::Unary(UnaryOperation {
+        operator,
+        expr: Box::new(expr),
+    }))
+}
+
+fn parse_is<'src, T>(lhs: Expression<'src>, tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    const EXPECTED: &str = "'NULL', 'TRUE' or 'FALSE'";
+
+    let negated = tokens.next_if_kw(Keyword::Not)?;
+    let (span, token) = tokens.next_or_expected(EXPECTED)?;
+
+    let operator = match (token.as_keyword(), negated) {
+        (Some(Keyword::Null), false) => UnaryOperator::IsNull,
+        (Some(Keyword::Null), true) => UnaryOperator::IsNotNull,
+        (Some(Keyword::True), false) => UnaryOperator::IsTrue,
+        (Some(Keyword::True), true) => UnaryOperator::IsNotTrue,
+        (Some(Keyword::False), false) => UnaryOperator::IsFalse,
+        (Some(Keyword::False), true) => UnaryOperator::IsNotFalse,
+        _ => return Err(UnexpectedToken::new_expected(span, token, EXPECTED).into()),
+    };
+
+    Ok(unary(operator, lhs))
+}
+
+fn parse_in_rhs<'src, T>(tokens: &mut T) -> Result<InRhs<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if tokens.next_if_kw(Keyword::Unnest)? {
+        let expr = parse_parens(tokens, "'('", Expression::parse_tokens)?;
+        return Ok(InRhs::Unnest(Box::new(expr)));
+    }
+
+    parse_parens(tokens, "a parenthesized list or subquery", |inner| {
+        if query::is_query_start(inner)? {
+            QueryExpr::parse_tokens(inner).map(|query| InRhs::SubQuery(Box::new(query)))
+        } else {
+            parse_comma_separated(inner, Expression::parse_tokens).map(InRhs::List)
+        }
+    })
+}
+
+/// Parses prefix operators, then the primary expression followed by any postfix operators.
+fn parse_unary<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let operator = match tokens
+        .peek()?
+        .and_then(|(_, token)| token.as_punct_or_opt())
+    {
+        Some(PunctOrOp::Plus) => UnaryOperator::UnaryPlus,
+        Some(PunctOrOp::Neg) => UnaryOperator::UnaryNeg,
+        Some(PunctOrOp::Tilde) => UnaryOperator::BitwiseNot,
+        _ => return parse_postfix(tokens),
+    };
+
+    tokens.next_or_eof()?;
+
+    // negative numeric literals are parsed as a single value, otherwise 'i64::MIN' would
+    // overflow before being negated.
+    if operator == UnaryOperator::UnaryNeg
+        && let Some((span, token)) = tokens.peek()?
+        && let Some(num) = token.as_numeric_literal()
+    {
+        tokens.next_or_eof()?;
+        let value = parse_numeric(span, token, num, true)?;
+        return parse_postfix_ops(Expression::Value(value), tokens);
+    }
+
+    let expr = parse_expr(tokens, precedence::UNARY)?;
+    Ok(unary(operator, expr))
+}
+
+fn parse_postfix<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let primary = parse_primary(tokens)?;
+    parse_postfix_ops(primary, tokens)
+}
+
+fn parse_postfix_ops<'src, T>(
+    mut expr: Expression<'src>,
+    tokens: &mut T,
+) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    loop {
+        if let Some(mut inner) =
+            tokens.next_if_some(|span, token| token.build_square_brackets_parser(span.start))?
+        {
+            let index = parse_index(&mut inner)?;
+            inner.expect_end("']'")?;
+
+            expr = Expression::Operation(Operation::Access(AccessOperation::Subscript {
+                lhs: Box::new(expr),
+                index: Box::new(index),
+            }));
+        } else if tokens.peek_is_punct(PunctOrOp::Dot)?
+            && tokens
+                .peek_nth(1)?
+                .is_some_and(|(_, token)| is_ident_like(&token))
+        {
+            tokens.next_or_eof()?;
+            let field = Ident::parse_tokens(tokens)?;
+
+            expr = Expression::Operation(Operation::Access(AccessOperation::Field {
+                lhs: Box::new(expr),
+                field,
+            }));
+        } else {
+            return Ok(expr);
+        }
+    }
+}
+
+fn parse_index<'src>(tokens: &mut Tokenizer<'src>) -> Result<Index<'src>, Error<'src>> {
+    let ctor: fn(Expression<'src>) -> Index<'src> = match TokenizerKind::peek(tokens)? {
+        Some((_, token)) if token.is_word("OFFSET") => Index::Offset,
+        Some((_, token)) if token.is_word("SAFE_OFFSET") => Index::SafeOffset,
+        Some((_, token)) if token.is_word("ORDINAL") => Index::Ordinal,
+        Some((_, token)) if token.is_word("SAFE_ORDINAL") => Index::SafeOrdinal,
+        _ => return Expression::parse_tokens(tokens).map(Index::Bare),
+    };
+
+    tokens.next_or_eof()?;
+    parse_parens(tokens, "'('", Expression::parse_tokens).map(ctor)
+}
+
+fn parse_numeric<'src>(
+    span: Span,
+    token: Token<'src>,
+    num: &'src str,
+    negative: bool,
+) -> Result<ValueExpr<'src>, Error<'src>> {
+    let invalid = || UnexpectedToken::new_expected(span, token, "a valid numeric literal");
+
+    let hex = num.strip_prefix("0x").or_else(|| num.strip_prefix("0X"));
+
+    if let Some(hex) = hex {
+        let value =
+            i64::from_str_radix(&format_signed(hex, negative), 16).map_err(|_| invalid())?;
+        return Ok(ValueExpr::Integer(value));
+    }
+
+    let signed = format_signed(num, negative);
+
+    if num.contains(['.', 'e', 'E']) {
+        signed
+            .parse()
+            .map(ValueExpr::Float)
+            .map_err(|_| invalid().into())
+    } else {
+        signed
+            .parse()
+            .map(ValueExpr::Integer)
+            .map_err(|_| invalid().into())
+    }
+}
+
+fn format_signed(num: &str, negative: bool) -> std::borrow::Cow<'_, str> {
+    if negative {
+        std::borrow::Cow::Owned(format!("-{num}"))
+    } else {
+        std::borrow::Cow::Borrowed(num)
+    }
+}
+
+fn parse_primary<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let (span, token) = match tokens.peek()? {
+        Some(next) => next,
+        None => return Err(UnexpectedToken::eof(tokens.eof_location(), EXPECTED_EXPR).into()),
+    };
+
+    match token {
+        Token::NumericLiteral(num) => {
+            tokens.next_or_eof()?;
+            parse_numeric(span, token, num, false).map(Expression::Value)
+        }
+        Token::StringLiteral(lit) => {
+            tokens.next_or_eof()?;
+            Ok(Expression::Value(ValueExpr::String(lit)))
+        }
+        Token::QueryParameter(_) | Token::QuotedIdentifier(_) => {
+            Path::parse_tokens(tokens).map(Expression::Path)
+        }
+        Token::Parens(_) => {
+            tokens.next_or_eof()?;
+            let mut inner = token
+                .build_parens_parser(span.start)
+                .expect("token is a parens token");
+
+            let expr = parse_parenthesized(&mut inner)?;
+            inner.expect_end("')'")?;
+            Ok(expr)
+        }
+        Token::SquareBrackets(_) => {
+            let elements = parse_array_elements(tokens)?;
+            Ok(Expression::Value(ValueExpr::Array {
+                element_type: None,
+                elements,
+            }))
+        }
+        Token::Unquoted(word) => match token.as_keyword() {
+            Some(kw) => parse_keyword_primary(span, token, kw, tokens),
+            None => parse_word_primary(word, tokens),
+        },
+        _ => Err(UnexpectedToken::new_expected(span, token, EXPECTED_EXPR).into()),
+    }
+}
+
+fn parse_parenthesized<'src>(
+    tokens: &mut Tokenizer<'src>,
+) -> Result<Expression<'src>, Error<'src>> {
+    if query::is_query_start(tokens)? {
+        let query = QueryExpr::parse_tokens(tokens)?;
+        return Ok(Expression::SubQuery(Box::new(query)));
+    }
+
+    let mut exprs = parse_comma_separated(tokens, Expression::parse_tokens)?;
+
+    if exprs.len() == 1 {
+        Ok(exprs.pop().expect("length checked"))
+    } else {
+        Ok(Expression::Value(ValueExpr::StructTuple(exprs)))
+    }
+}
+
+fn parse_array_elements<'src, T>(tokens: &mut T) -> Result<Vec<Expression<'src>>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let (span, token) = tokens.next_or_expected("'['")?;
+    let mut inner = token
+        .build_square_brackets_parser(span.start)
+        .ok_or_else(|| UnexpectedToken::new_expected(span, token, "'['"))?;
+
+    if TokenizerKind::peek(&mut inner)?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let elements = parse_comma_separated(&mut inner, Expression::parse_tokens)?;
+    inner.expect_end("']'")?;
+    Ok(elements)
+}
+
+fn parse_keyword_primary<'src, T>(
+    span: Span,
+    token: Token<'src>,
+    kw: Keyword,
+    tokens: &mut T,
+) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let value = match kw {
+        Keyword::Null => Some(ValueExpr::Null),
+        Keyword::True => Some(ValueExpr::Boolean(true)),
+        Keyword::False => Some(ValueExpr::Boolean(false)),
+        _ => None,
+    };
+
+    if let Some(value) = value {
+        tokens.next_or_eof()?;
+        return Ok(Expression::Value(value));
+    }
+
+    match kw {
+        Keyword::Case => parse_case(tokens),
+        Keyword::Cast => parse_cast(tokens, false),
+        Keyword::Extract => parse_extract(tokens),
+        Keyword::Interval => {
+            tokens.next_or_eof()?;
+            let value = parse_unary(tokens)?;
+            let part = Ident::parse_tokens(tokens)?;
+            Ok(Expression::Interval(Box::new(Interval { value, part })))
+        }
+        Keyword::Exists => {
+            tokens.next_or_eof()?;
+            let query = parse_parens(tokens, "a subquery", QueryExpr::parse_tokens)?;
+            Ok(Expression::Exists(Box::new(query)))
+        }
+        Keyword::Array => parse_array(tokens),
+        Keyword::Struct => parse_struct(tokens),
+        // reserved keywords that are also function names
+        Keyword::If | Keyword::Left | Keyword::Right
+            if tokens
+                .peek_nth(1)?
+                .is_some_and(|(_, tok)| tok.as_parens().is_some()) =>
+        {
+            tokens.next_or_eof()?;
+            parse_function_call(false, token.as_unquoted().unwrap_or_default(), tokens)
+        }
+        _ => Err(UnexpectedToken::new_expected(span, token, EXPECTED_EXPR).into()),
+    }
+}
+
+fn parse_word_primary<'src, T>(
+    word: &'src str,
+    tokens: &mut T,
+) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    // typed literals, i.e "DATE '2024-01-01'"
+    if let Some(data_type) = DataType::from_str(word)
+        && matches!(
+            data_type,
+            DataType::Date | DataType::Timestamp | DataType::Numeric | DataType::Json
+        )
+        && let Some((_, Token::StringLiteral(value))) = tokens.peek_nth(1)?
+    {
+        tokens.next_or_eof()?;
+        tokens.next_or_eof()?;
+        return Ok(Expression::Value(ValueExpr::Typed { data_type, value }));
+    }
+
+    if word.eq_ignore_ascii_case("SAFE_CAST") {
+        return parse_cast(tokens, true);
+    }
+
+    // 'SAFE.' prefixed function calls
+    let safe = word.eq_ignore_ascii_case("SAFE")
+        && tokens.peek_nth_is_punct(1, PunctOrOp::Dot)?
+        && function_name_len(tokens, 2)?.is_some();
+
+    if safe {
+        tokens.next_or_eof()?;
+        tokens.next_or_eof()?;
+    }
+
+    let Some(name_tokens) = function_name_len(tokens, 0)? else {
+        return Path::parse_tokens(tokens).map(Expression::Path);
+    };
+
+    let (first, _) = tokens.next_or_eof()?;
+    let mut last = first;
+    for _ in 1..name_tokens {
+        (last, _) = tokens.next_or_eof()?;
+    }
+
+    let name = tokens.source(first.to(last));
+    parse_function_call(safe, name, tokens)
+}
+
+/// If the tokens starting at index 'start' are a (possibly dot separated) function name
+/// followed by an argument list, returns the number of tokens making up the name.
+fn function_name_len<'src, T>(tokens: &mut T, start: usize) -> Result<Option<usize>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if tokens
+        .peek_nth(start)?
+        .is_none_or(|(_, tok)| tok.as_unquoted().is_none())
+    {
+        return Ok(None);
+    }
+
+    // function names can be dot separated (i.e 'NET.HOST(...)')
+    let mut name_tokens = 1;
+    while tokens.peek_nth_is_punct(start + name_tokens, PunctOrOp::Dot)?
+        && tokens
+            .peek_nth(start + name_tokens + 1)?
+            .is_some_and(|(_, tok)| tok.as_unquoted().is_some())
+    {
+        name_tokens += 2;
+    }
+
+    let is_function = tokens
+        .peek_nth(start + name_tokens)?
+        .is_some_and(|(_, tok)| tok.as_parens().is_some());
+
+    Ok(is_function.then_some(name_tokens))
+}
+
+/// Parses the arguments of a function call, after the name has been consumed.
+fn parse_function_call<'src, T>(
+    safe: bool,
+    name: &'src str,
+    tokens: &mut T,
+) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let arguments = parse_parens(tokens, "'('", parse_arguments)?;
+
+    let function_hint = match tokens.peek()? {
+        Some((span, token)) if token.as_hint().is_some() => {
+            tokens.next_or_eof()?;
+            Some(parse_function_hint(span, token)?)
+        }
+        _ => None,
+    };
+
+    Ok(Expression::FunctionCall(FunctionCall {
+        safe,
+        name,
+        arguments,
+        function_hint,
+    }))
+}
+
+fn parse_arguments<'src>(tokens: &mut Tokenizer<'src>) -> Result<Arguments<'src>, Error<'src>> {
+    if tokens.peek_is_punct(PunctOrOp::Mul)? && tokens.peek_nth(1)?.is_none() {
+        tokens.next_or_eof()?;
+        return Ok(Arguments::Star);
+    }
+
+    let distinct = tokens.next_if_kw(Keyword::Distinct)?;
+
+    if TokenizerKind::peek(tokens)?.is_none() {
+        return Ok(Arguments::List {
+            arguments: Vec::new(),
+            aggregate_options: None,
+        });
+    }
+
+    let arguments = parse_comma_separated(tokens, Expression::parse_tokens)?;
+
+    let nulls = if tokens.next_if_kw(Keyword::Ignore)? {
+        tokens.expect_kw(Keyword::Nulls)?;
+        Some(NullHandling::Ignore)
+    } else if tokens.next_if_kw(Keyword::Respect)? {
+        tokens.expect_kw(Keyword::Nulls)?;
+        Some(NullHandling::Respect)
+    } else {
+        None
+    };
+
+    let having = if tokens.next_if_kw(Keyword::Having)? {
+        let ctor = if tokens.next_if_word("MIN")? {
+            Having::Min
+        } else {
+            tokens.expect_word("MAX")?;
+            Having::Max
+        };
+
+        Some(Box::new(ctor(Expression::parse_tokens(tokens)?)))
+    } else {
+        None
+    };
+
+    let aggregate_options =
+        (distinct || nulls.is_some() || having.is_some()).then_some(AggregateOptions {
+            distinct,
+            nulls,
+            having,
+        });
+
+    Ok(Arguments::List {
+        arguments,
+        aggregate_options,
+    })
+}
+
+fn parse_function_hint<'src>(span: Span, token: Token<'src>) -> Result<FunctionHint, Error<'src>> {
+    let mut hint = None;
+
+    query::parse_hint(span, token, |key, span, token| {
+        if key.eq_ignore_ascii_case("DISABLE_INLINE") {
+            hint = Some(FunctionHint::DisableInline(query::hint_bool(span, token)?));
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    })?;
+
+    hint.ok_or_else(|| UnexpectedToken::new_expected(span, token, "a function hint").into())
+}
+
+fn parse_cast<'src, T>(tokens: &mut T, safe: bool) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    tokens.next_or_eof()?;
+    let cast = parse_parens(tokens, "'('", Cast::parse_tokens)?;
+
+    Ok(Expression::Cast {
+        safe,
+        cast: Box::new(cast),
+    })
+}
+
+fn parse_extract<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    tokens.next_or_eof()?;
+
+    let extract = parse_parens(tokens, "'('", |inner| {
+        let part = Ident::parse_tokens(inner)?;
+        inner.expect_kw(Keyword::From)?;
+        let expr = Expression::parse_tokens(inner)?;
+
+        let time_zone = if inner.next_if_kw(Keyword::At)? {
+            inner.expect_word("TIME")?;
+            inner.expect_word("ZONE")?;
+            Some(Expression::parse_tokens(inner)?)
+        } else {
+            None
+        };
+
+        Ok(Extract {
+            part,
+            expr,
+            time_zone,
+        })
+    })?;
+
+    Ok(Expression::Extract(Box::new(extract)))
+}
+
+fn parse_case<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    tokens.next_or_eof()?;
+
+    let operand = if tokens.peek_is_keyword(Keyword::When)? {
+        None
+    } else {
+        Some(Expression::parse_tokens(tokens)?)
+    };
+
+    let mut branches = Vec::new();
+    loop {
+        tokens.expect_kw(Keyword::When)?;
+        let when = Expression::parse_tokens(tokens)?;
+        tokens.expect_kw(Keyword::Then)?;
+        let then = Expression::parse_tokens(tokens)?;
+        branches.push(WhenThen { when, then });
+
+        if !tokens.peek_is_keyword(Keyword::When)? {
+            break;
+        }
+    }
+
+    let else_expr = if tokens.next_if_kw(Keyword::Else)? {
+        Some(Expression::parse_tokens(tokens)?)
+    } else {
+        None
+    };
+
+    tokens.expect_kw(Keyword::End)?;
+
+    Ok(Expression::Case(Box::new(CaseExpr {
+        operand,
+        branches,
+        else_expr,
+    })))
+}
+
+fn parse_array<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    // 'ARRAY (subquery)'
+    if tokens
+        .peek_nth(1)?
+        .is_some_and(|(_, tok)| tok.as_parens().is_some())
+    {
+        tokens.next_or_eof()?;
+        let query = parse_parens(tokens, "a subquery", QueryExpr::parse_tokens)?;
+        return Ok(Expression::ArraySubQuery(Box::new(query)));
+    }
+
+    let element_type = if tokens.peek_nth_is_punct(1, PunctOrOp::Lt)? {
+        match Type::parse_tokens(tokens)? {
+            Type::Array(element) => Some(*element),
+            _ => unreachable!("types starting with 'ARRAY' are always arrays"),
+        }
+    } else {
+        tokens.next_or_eof()?;
+        None
+    };
+
+    let elements = parse_array_elements(tokens)?;
+
+    Ok(Expression::Value(ValueExpr::Array {
+        element_type,
+        elements,
+    }))
+}
+
+fn parse_struct<'src, T>(tokens: &mut T) -> Result<Expression<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let fields = if tokens.peek_nth_is_punct(1, PunctOrOp::Lt)? {
+        match Type::parse_tokens(tokens)? {
+            Type::Struct(fields) => Some(fields),
+            _ => unreachable!("types starting with 'STRUCT' are always structs"),
+        }
+    } else {
+        tokens.next_or_eof()?;
+        None
+    };
+
+    let values = parse_parens(tokens, "'('", |inner| {
+        if TokenizerKind::peek(inner)?.is_none() {
+            return Ok(Vec::new());
+        }
+
+        parse_comma_separated(inner, AsAlias::parse_tokens)
+    })?;
+
+    Ok(Expression::Value(ValueExpr::Struct { fields, values }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(sql: &str) -> Expression<'_> {
+        let mut tokens = Tokenizer::new(sql);
+        let expr = Expression::parse_tokens(&mut tokens).expect("should parse");
+        tokens
+            .expect_end("the end of the expression")
+            .expect("should be fully consumed");
+        expr
+    }
+
+    fn binary<'a>(
+        lhs: Expression<'a>,
+        operator: BinaryOperator,
+        rhs: Expression<'a>,
+    ) -> Expression<'a> {
+        Expression::Operation(Operation::Binary(BinaryOperation {
+            sides: Box::new(Sides { lhs, rhs }),
+            operator,
+        }))
+    }
+
+    fn int(value: i64) -> Expression<'static> {
+        Expression::Value(ValueExpr::Integer(value))
+    }
+
+    fn col(name: &str) -> Expression<'_> {
+        Expression::Path(Path::new(Ident::Unquoted(name)))
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            parse("1 + 2 * 3"),
+            binary(
+                int(1),
+                BinaryOperator::Add,
+                binary(int(2), BinaryOperator::Mul, int(3))
+            )
+        );
+
+        assert_eq!(
+            parse("a = 1 OR NOT b AND c"),
+            binary(
+                binary(col("a"), BinaryOperator::Eq, int(1)),
+                BinaryOperator::LogicalOr,
+                binary(
+                    unary(UnaryOperator::LogicalNot, col("b")),
+                    BinaryOperator::LogicalAnd,
+                    col("c"),
+                ),
+            )
+        );
+
+        assert_eq!(parse("-9223372036854775808"), int(i64::MIN));
+        assert_eq!(
+            parse("(0x10 - 1)"),
+            binary(int(16), BinaryOperator::Sub, int(1))
+        );
+    }
+
+    #[test]
+    fn test_comparison_operators() {
+        let Expression::Operation(Operation::Between(between)) = parse("a NOT BETWEEN 1 AND 2 + 3")
+        else {
+            panic!("expected a BETWEEN expression");
+        };
+
+        assert!(between.negated);
+        assert_eq!(*between.high, binary(int(2), BinaryOperator::Add, int(3)));
+
+        let Expression::Operation(Operation::In(in_op)) = parse("a IN (SELECT b FROM t)") else {
+            panic!("expected an IN expression");
+        };
+        assert!(matches!(in_op.rhs, InRhs::SubQuery(_)));
+
+        assert_eq!(
+            parse("a IS NOT NULL"),
+            unary(UnaryOperator::IsNotNull, col("a"))
+        );
+        assert_eq!(
+            parse("a NOT LIKE 'b%'"),
+            binary(col("a"), BinaryOperator::NotLike, parse("'b%'"),)
+        );
+    }
+
+    #[test]
+    fn test_function_calls() {
+        let Expression::FunctionCall(call) = parse("SAFE.NET.HOST(url)") else {
+            panic!("expected a function call");
+        };
+        assert!(call.safe);
+        assert_eq!(call.name, "NET.HOST");
+
+        let Expression::FunctionCall(call) = parse("COUNT(*)") else {
+            panic!("expected a function call");
+        };
+        assert_eq!(call.arguments, Arguments::Star);
+
+        let Expression::FunctionCall(call) =
+            parse("ARRAY_AGG(DISTINCT x IGNORE NULLS HAVING MAX y) @{DISABLE_INLINE = TRUE}")
+        else {
+            panic!("expected a function call");
+        };
+        assert_eq!(call.function_hint, Some(FunctionHint::DisableInline(true)));
+        assert!(matches!(
+            call.arguments,
+            Arguments::List {
+                aggregate_options: Some(AggregateOptions { distinct: true, .. }),
+                ..
+            }
+        ));
+
+        assert!(matches!(
+            parse("TIMESTAMP_ADD(ts, INTERVAL 1 DAY)"),
+            Expression::FunctionCall(_)
+        ));
+        assert!(matches!(parse("IF(a, 1, 2)"), Expression::FunctionCall(_)));
+    }
+
+    #[test]
+    fn test_special_forms() {
+        let Expression::Cast { safe, cast } = parse("SAFE_CAST(a AS ARRAY<STRING(MAX)>)") else {
+            panic!("expected a cast");
+        };
+        assert!(safe);
+        assert_eq!(cast.from, col("a"));
+
+        assert!(matches!(
+            parse("CASE WHEN a > 1 THEN 'big' ELSE 'small' END"),
+            Expression::Case(_)
+        ));
+        assert!(matches!(
+            parse("EXTRACT(DATE FROM ts AT TIME ZONE 'UTC')"),
+            Expression::Extract(_)
+        ));
+        assert!(matches!(
+            parse("DATE '2024-01-01'"),
+            Expression::Value(ValueExpr::Typed {
+                data_type: DataType::Date,
+                ..
+            })
+        ));
+        assert!(matches!(parse("EXISTS (SELECT 1)"), Expression::Exists(_)));
+        assert!(matches!(
+            parse("ARRAY(SELECT 1)"),
+            Expression::ArraySubQuery(_)
+        ));
+        assert!(matches!(
+            parse("STRUCT<a INT64, b ARRAY<INT64>>(1, [2])"),
+            Expression::Value(ValueExpr::Struct {
+                fields: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_access_operators() {
+        let Expression::Operation(Operation::Access(AccessOperation::Field { lhs, field })) =
+            parse("arr[SAFE_OFFSET(0)].name")
+        else {
+            panic!("expected field access");
+        };
+
+        assert_eq!(field, Ident::Unquoted("name"));
+        assert!(matches!(
+            *lhs,
+            Expression::Operation(Operation::Access(AccessOperation::Subscript { .. }))
+        ));
+
+        let Expression::Path(path) = parse("t.col.field") else {
+            panic!("expected a path");
+        };
+        assert_eq!(path.to_string(), "t.col.field");
+    }
+
+    #[test]
+    fn test_errors_have_spans() {
+        let err = Expression::parse_tokens(&mut Tokenizer::new("1 +\n  FROM")).unwrap_err();
+        assert_eq!(err.span().start.line, 1);
+        assert_eq!(err.span().start.column, 2);
+    }
+}
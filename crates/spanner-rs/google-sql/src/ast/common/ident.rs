@@ -1,8 +1,9 @@
-use data_structures::non_empty_vec::NonEmptyVec;
+use std::fmt;
 
 use crate::Error;
 use crate::ast::reserved::Keyword;
 use crate::ast::{FromToken, ParseTokens, UnexpectedToken};
+use crate::non_empty_vec::NonEmptyVec;
 use crate::tokens::{PunctOrOp, Span, Token, TokenizerKind};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,6 +13,21 @@ pub enum Ident<'src> {
     Unquoted(&'src str),
 }
 
+impl<'src> Ident<'src> {
+    /// The identifier, without any surrounding backticks or leading '@'.
+    pub fn as_str(&self) -> &'src str {
+        match *self {
+            Self::QueryParam(param) => param.strip_prefix('@').unwrap_or(param),
+            Self::Quoted(quoted) => quoted.trim_start_matches('`').trim_end_matches('`'),
+            Self::Unquoted(unquoted) => unquoted,
+        }
+    }
+
+    pub fn is_query_param(&self) -> bool {
+        matches!(self, Self::QueryParam(_))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum InvalidIdent<'src> {
     #[error("unquoted identifier cannot be keyword: {0:?}")]
@@ -27,11 +43,7 @@ pub enum InvalidIdent<'src> {
 
 fn validate_ident(ident: &str) -> Result<(), InvalidIdent<'_>> {
     // strip a leading query parameter symbol only once
-    let ident = if ident.starts_with('@') {
-        &ident[1..]
-    } else {
-        ident
-    };
+    let ident = ident.strip_prefix('@').unwrap_or(ident);
 
     // we know it'll also end with backticks, since the tokenizer will only return valid
     // quoted sequences.
@@ -82,29 +94,77 @@ fn validate_unquoted(ident: &str) -> Result<(), InvalidIdent<'_>> {
 
 impl<'src> FromToken<'src> for Ident<'src> {
     fn from_token(span: Span, token: Token<'src>) -> Result<Self, Error<'src>> {
-        match token {
+        let result = match token {
             Token::Unquoted(unquoted) => {
-                validate_unquoted(unquoted)?;
-                Ok(Ident::Unquoted(unquoted))
-            }
-            Token::QueryParameter(qp) => {
-                validate_ident(qp)?;
-                Ok(Ident::QueryParam(qp))
+                validate_unquoted(unquoted).map(|_| Ident::Unquoted(unquoted))
             }
+            Token::QueryParameter(qp) => validate_ident(qp).map(|_| Ident::QueryParam(qp)),
             Token::QuotedIdentifier(quoted) => {
-                validate_quoted(quoted)?;
-                Ok(Ident::Quoted(quoted))
+                validate_quoted(quoted).map(|_| Ident::Quoted(quoted))
             }
-            _ => Err(UnexpectedToken::new_expected(span, token, "a valid identifier").into()),
-        }
+            _ => {
+                return Err(
+                    UnexpectedToken::new_expected(span, token, "a valid identifier").into(),
+                );
+            }
+        };
+
+        result.map_err(|error| Error::InvalidIdent { span, error })
+    }
+}
+
+/// Checks if a token could be an identifier, without validating it.
+pub(crate) fn is_ident_like(token: &Token<'_>) -> bool {
+    match token {
+        Token::Unquoted(unquoted) => Keyword::from_str(unquoted).is_none(),
+        Token::QuotedIdentifier(_) | Token::QueryParameter(_) => true,
+        _ => false,
     }
 }
 
+/// A dot separated path, i.e 'table.column' or 'struct_col.field'.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Path<'src> {
     segments: NonEmptyVec<Ident<'src>>,
 }
 
+impl<'src> Path<'src> {
+    pub fn new(ident: Ident<'src>) -> Self {
+        Self {
+            segments: NonEmptyVec::new(ident),
+        }
+    }
+
+    pub fn segments(&self) -> &NonEmptyVec<Ident<'src>> {
+        &self.segments
+    }
+
+    /// The last segment in the path.
+    pub fn name(&self) -> Ident<'src> {
+        *self.segments.last()
+    }
+
+    /// Returns the single identifier, if this path only has one segment.
+    pub fn as_ident(&self) -> Option<Ident<'src>> {
+        match self.segments.len() {
+            1 => Some(*self.segments.first()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, segment) in self.segments.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(".")?;
+            }
+            f.write_str(segment.as_str())?;
+        }
+        Ok(())
+    }
+}
+
 fn parse_remaining_path<'src, T>(
     dst: &mut Vec<Ident<'src>>,
     tokens: &mut T,
@@ -112,7 +172,14 @@ fn parse_remaining_path<'src, T>(
 where
     T: TokenizerKind<'src>,
 {
-    while tokens.next_if(|_, token| token.as_punct_or_opt() == Some(PunctOrOp::Dot))? {
+    // only continue if the token after the '.' could be an identifier, that way things like
+    // 'table.*' are left for the caller to handle.
+    while tokens.peek_is_punct(PunctOrOp::Dot)?
+        && tokens
+            .peek_nth(1)?
+            .is_some_and(|(_, token)| is_ident_like(&token))
+    {
+        tokens.next_or_eof()?;
         let next = Ident::parse_tokens(tokens)?;
         dst.push(next);
     }
@@ -1,12 +1,14 @@
 use self::ident::Ident;
-use super::{ParseTokens, UnexpectedToken};
+use super::reserved::Keyword;
+use super::{FromToken, ParseTokens, UnexpectedToken};
 use crate::Error;
-use crate::tokens::TokenizerKind;
+use crate::tokens::{Token, TokenizerKind};
 
 pub mod expression;
 pub mod ident;
 pub mod operator;
 pub mod punct;
+pub mod types;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AsAlias<'src, T> {
@@ -15,27 +17,27 @@ pub struct AsAlias<'src, T> {
 }
 
 impl<'src, P> AsAlias<'src, P> {
-    fn parse_alias<T>(item: P, tokens: &mut T) -> Result<Self, Error<'src>>
+    /// Parses an optional alias after 'item'. The 'AS' keyword is optional, so a trailing
+    /// identifier is also treated as an alias (i.e 'SELECT col alias FROM ...').
+    pub(crate) fn parse_alias<T>(item: P, tokens: &mut T) -> Result<Self, Error<'src>>
     where
         T: TokenizerKind<'src>,
     {
-        match tokens
-            .peek()?
-            .and_then(|(_span, token)| token.as_unquoted())
-        {
-            Some(unquoted) if unquoted.eq_ignore_ascii_case("as") => {
-                // move past 'AS'
-                tokens.next().transpose()?;
-
-                let alias = Ident::parse_tokens(tokens)?;
-
-                Ok(Self {
-                    item,
-                    alias: Some(alias),
-                })
+        let alias = if tokens.next_if_kw(Keyword::As)? {
+            Some(Ident::parse_tokens(tokens)?)
+        } else {
+            match tokens.peek()? {
+                Some((span, token @ (Token::Unquoted(_) | Token::QuotedIdentifier(_))))
+                    if token.as_keyword().is_none() =>
+                {
+                    tokens.next_or_eof()?;
+                    Some(Ident::from_token(span, token)?)
+                }
+                _ => None,
             }
-            _ => Ok(Self { item, alias: None }),
-        }
+        };
+
+        Ok(Self { item, alias })
     }
 }
 
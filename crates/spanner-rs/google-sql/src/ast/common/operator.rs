@@ -1,63 +1,81 @@
-use std::num::NonZeroUsize;
-
-use data_structures::non_empty_vec::NonEmptyVec;
-
 use super::expression::Expression;
 use super::ident::Ident;
+use crate::ast::query::QueryExpr;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation<'src> {
     Access(AccessOperation<'src>),
     Binary(BinaryOperation<'src>),
     Unary(UnaryOperation<'src>),
+    Between(Between<'src>),
+    In(InOperation<'src>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AccessOperation<'src> {
+    /// Field access on an arbitrary expression, i.e '(SELECT AS STRUCT ...).field'. Plain paths
+    /// (i.e 'table.column') are parsed as [`Expression::Path`].
     Field {
         lhs: Box<Expression<'src>>,
-        path: NonEmptyVec<Ident<'src>>,
-    },
-    Array {
-        lhs: Box<Expression<'src>>,
-        index: Index,
+        field: Ident<'src>,
     },
-    Json {
+    /// Array or JSON subscripts, i.e 'array[OFFSET(0)]' or 'json["field"]'.
+    Subscript {
         lhs: Box<Expression<'src>>,
-        subscript: JsonSubscript<'src>,
+        index: Box<Index<'src>>,
     },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum JsonSubscript<'src> {
-    Index(usize),
-    FieldName(&'src str),
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Index {
-    Offset(usize),
-    SafeOffset(usize),
-    Ordinal(NonZeroUsize),
-    SafeOrdinal(NonZeroUsize),
+#[derive(Debug, Clone, PartialEq)]
+pub enum Index<'src> {
+    Offset(Expression<'src>),
+    SafeOffset(Expression<'src>),
+    Ordinal(Expression<'src>),
+    SafeOrdinal(Expression<'src>),
+    /// A bare subscript, used for JSON field access/indexing, or as shorthand for 'OFFSET'.
+    Bare(Expression<'src>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnaryOperation<'src> {
-    operator: UnaryOperator,
-    expr: Box<Expression<'src>>,
+    pub operator: UnaryOperator,
+    pub expr: Box<Expression<'src>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BinaryOperation<'src> {
-    sides: Box<Sides<'src>>,
-    operator: BinaryOperator,
+    pub sides: Box<Sides<'src>>,
+    pub operator: BinaryOperator,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Sides<'src> {
-    lhs: Expression<'src>,
-    rhs: Expression<'src>,
+    pub lhs: Expression<'src>,
+    pub rhs: Expression<'src>,
+}
+
+/// 'expr [NOT] BETWEEN low AND high'
+#[derive(Debug, Clone, PartialEq)]
+pub struct Between<'src> {
+    pub expr: Box<Expression<'src>>,
+    pub negated: bool,
+    pub low: Box<Expression<'src>>,
+    pub high: Box<Expression<'src>>,
+}
+
+/// 'expr [NOT] IN (...)'
+#[derive(Debug, Clone, PartialEq)]
+pub struct InOperation<'src> {
+    pub expr: Box<Expression<'src>>,
+    pub negated: bool,
+    pub rhs: InRhs<'src>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InRhs<'src> {
+    List(Vec<Expression<'src>>),
+    SubQuery(Box<QueryExpr<'src>>),
+    Unnest(Box<Expression<'src>>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -72,8 +90,6 @@ pub enum UnaryOperator {
     IsFalse,
     IsNotFalse,
     LogicalNot,
-    LogicalAnd,
-    LogicalOr,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -96,8 +112,39 @@ pub enum BinaryOperator {
     NotEq,
     Like,
     NotLike,
-    Between,
-    NotBetween,
-    In,
-    NotIn,
+    LogicalAnd,
+    LogicalOr,
+}
+
+impl BinaryOperator {
+    /// Binding power of the operator, higher binds tighter.
+    pub(crate) const fn precedence(&self) -> u8 {
+        match self {
+            Self::Mul | Self::Div | Self::Concat => 10,
+            Self::Add | Self::Sub => 9,
+            Self::LeftShift | Self::RightShift => 8,
+            Self::BitwiseAnd => 7,
+            Self::BitwiseXor => 6,
+            Self::BitwiseOr => 5,
+            Self::Eq
+            | Self::Lt
+            | Self::Gt
+            | Self::Lte
+            | Self::Gte
+            | Self::NotEq
+            | Self::Like
+            | Self::NotLike => precedence::COMPARISON,
+            Self::LogicalAnd => precedence::AND,
+            Self::LogicalOr => precedence::OR,
+        }
+    }
+}
+
+/// Precedence levels that don't map directly to a [`BinaryOperator`].
+pub(crate) mod precedence {
+    pub const OR: u8 = 1;
+    pub const AND: u8 = 2;
+    pub const NOT: u8 = 3;
+    pub const COMPARISON: u8 = 4;
+    pub const UNARY: u8 = 11;
 }
@@ -0,0 +1,169 @@
+use super::ident::Ident;
+use crate::Error;
+use crate::ast::reserved::DataType;
+use crate::ast::{FromToken, ParseTokens, UnexpectedToken};
+use crate::tokens::{PunctOrOp, Token, Tokenizer, TokenizerKind};
+
+/// A complete type, i.e 'STRING(MAX)', 'ARRAY<INT64>' or 'STRUCT<a INT64, b BOOL>'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type<'src> {
+    Scalar {
+        data_type: DataType,
+        length: Option<Length>,
+    },
+    Array(Box<Type<'src>>),
+    Struct(Vec<StructField<'src>>),
+}
+
+/// The max length of a 'STRING' or 'BYTES' column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    Max,
+    Fixed(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructField<'src> {
+    pub name: Option<Ident<'src>>,
+    pub ty: Type<'src>,
+}
+
+impl<'src> Type<'src> {
+    /// Parses a standalone type, i.e the 'SPANNER_TYPE' column of 'INFORMATION_SCHEMA.COLUMNS'.
+    pub fn parse(ty: &'src str) -> Result<Self, Error<'src>> {
+        let mut tokens = Tokenizer::new(ty);
+        let ty = Self::parse_tokens(&mut tokens)?;
+        tokens.expect_end("the end of the type")?;
+        Ok(ty)
+    }
+}
+
+impl<'src> ParseTokens<'src> for Type<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let mut pending_gt = false;
+        let ty = parse_type(tokens, &mut pending_gt)?;
+
+        // a trailing '>' that was split from a '>>' wasn't used by a nested type
+        if pending_gt {
+            let location = tokens.eof_location();
+            return Err(UnexpectedToken::eof(location, "a matching '<'").into());
+        }
+
+        Ok(ty)
+    }
+}
+
+/// Parses a type, where 'pending_gt' tracks if a '>>' token was split, and a '>' still needs to
+/// close the parent type.
+fn parse_type<'src, T>(tokens: &mut T, pending_gt: &mut bool) -> Result<Type<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let (span, token) = tokens.next_or_expected("a data type")?;
+    let data_type = DataType::from_token(span, token)?;
+
+    match data_type {
+        DataType::Array => {
+            tokens.expect_punct(PunctOrOp::Lt, "'<'")?;
+            let element = parse_type(tokens, pending_gt)?;
+            expect_closing_angle(tokens, pending_gt)?;
+            Ok(Type::Array(Box::new(element)))
+        }
+        DataType::Struct => {
+            tokens.expect_punct(PunctOrOp::Lt, "'<'")?;
+
+            let mut fields = Vec::new();
+
+            // empty structs are allowed, i.e 'STRUCT<>'
+            if !*pending_gt && !tokens.peek_is_punct(PunctOrOp::Gt)? {
+                loop {
+                    fields.push(parse_struct_field(tokens, pending_gt)?);
+
+                    if *pending_gt || !tokens.next_if_punct_op(PunctOrOp::Comma)? {
+                        break;
+                    }
+                }
+            }
+
+            expect_closing_angle(tokens, pending_gt)?;
+            Ok(Type::Struct(fields))
+        }
+        data_type => {
+            let length = match tokens.next_if_parens()? {
+                Some(mut inner) => {
+                    let length = parse_length(&mut inner)?;
+                    inner.expect_end("')'")?;
+                    Some(length)
+                }
+                None => None,
+            };
+
+            Ok(Type::Scalar { data_type, length })
+        }
+    }
+}
+
+fn parse_struct_field<'src, T>(
+    tokens: &mut T,
+    pending_gt: &mut bool,
+) -> Result<StructField<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    // fields are optionally named, which we can tell by checking if there's a type right after
+    // the first token.
+    let is_named = tokens
+        .peek_nth(1)?
+        .is_some_and(|(_, token)| matches!(token, Token::Unquoted(_) | Token::QuotedIdentifier(_)));
+
+    let name = if is_named {
+        Some(Ident::parse_tokens(tokens)?)
+    } else {
+        None
+    };
+
+    let ty = parse_type(tokens, pending_gt)?;
+    Ok(StructField { name, ty })
+}
+
+fn expect_closing_angle<'src, T>(tokens: &mut T, pending_gt: &mut bool) -> Result<(), Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if *pending_gt {
+        *pending_gt = false;
+        return Ok(());
+    }
+
+    let (span, token) = tokens.next_or_expected("'>'")?;
+
+    match token.as_punct_or_opt() {
+        Some(PunctOrOp::Gt) => Ok(()),
+        // nested types can end with '>>', which gets tokenized as a right shift.
+        Some(PunctOrOp::RightShift) => {
+            *pending_gt = true;
+            Ok(())
+        }
+        _ => Err(UnexpectedToken::new_expected(span, token, "'>'").into()),
+    }
+}
+
+fn parse_length<'src, T>(tokens: &mut T) -> Result<Length, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let (span, token) = tokens.next_or_expected("'MAX' or a length")?;
+
+    if token.is_word("MAX") {
+        return Ok(Length::Max);
+    }
+
+    token
+        .as_numeric_literal()
+        .and_then(|num| num.parse().ok())
+        .map(Length::Fixed)
+        .ok_or_else(|| UnexpectedToken::new_expected(span, token, "'MAX' or a length").into())
+}
@@ -0,0 +1,167 @@
+use super::{Options, unexpected_next};
+use crate::Error;
+use crate::ast::common::ident::{Ident, Path};
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, parse_comma_separated};
+use crate::tokens::TokenizerKind;
+
+/// 'CREATE CHANGE STREAM name [FOR {ALL | table [(columns)], ...}] [OPTIONS (...)]'
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateChangeStream<'src> {
+    pub name: Ident<'src>,
+    pub watch: Option<WatchTarget<'src>>,
+    pub options: Options<'src>,
+}
+
+/// What a change stream watches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchTarget<'src> {
+    All,
+    Tables(Vec<WatchedTable<'src>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedTable<'src> {
+    pub table: Path<'src>,
+    /// The watched columns, or [`None`] if all columns are watched. An empty list only watches
+    /// the primary key columns.
+    pub columns: Option<Vec<Ident<'src>>>,
+}
+
+/// 'ALTER CHANGE STREAM name {SET FOR ... | DROP FOR ALL | SET OPTIONS (...)}'
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlterChangeStream<'src> {
+    pub name: Ident<'src>,
+    pub action: AlterChangeStreamAction<'src>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterChangeStreamAction<'src> {
+    SetFor(WatchTarget<'src>),
+    DropForAll,
+    SetOptions(Options<'src>),
+}
+
+/// Parses the target, after 'FOR' has been consumed.
+fn parse_watch_target<'src, T>(tokens: &mut T) -> Result<WatchTarget<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if tokens.next_if_kw(Keyword::All)? {
+        return Ok(WatchTarget::All);
+    }
+
+    let tables = parse_comma_separated(tokens, |tokens| {
+        let table = Path::parse_tokens(tokens)?;
+
+        let columns = match tokens.next_if_parens()? {
+            Some(mut inner) => {
+                if TokenizerKind::peek(&mut inner)?.is_none() {
+                    Some(Vec::new())
+                } else {
+                    let columns = parse_comma_separated(&mut inner, Ident::parse_tokens)?;
+                    inner.expect_end("')'")?;
+                    Some(columns)
+                }
+            }
+            None => None,
+        };
+
+        Ok(WatchedTable { table, columns })
+    })?;
+
+    Ok(WatchTarget::Tables(tables))
+}
+
+impl<'src> ParseTokens<'src> for CreateChangeStream<'src> {
+    /// Parses starting at 'CHANGE', after 'CREATE' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_word("CHANGE")?;
+        tokens.expect_word("STREAM")?;
+
+        let name = Ident::parse_tokens(tokens)?;
+
+        let watch = if tokens.next_if_kw(Keyword::For)? {
+            Some(parse_watch_target(tokens)?)
+        } else {
+            None
+        };
+
+        let options = Options::parse_optional_clause(tokens)?;
+
+        Ok(Self {
+            name,
+            watch,
+            options,
+        })
+    }
+}
+
+impl<'src> ParseTokens<'src> for AlterChangeStream<'src> {
+    /// Parses starting at the stream name, after 'ALTER CHANGE STREAM' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let name = Ident::parse_tokens(tokens)?;
+
+        let action = if tokens.next_if_kw(Keyword::Set)? {
+            if tokens.next_if_kw(Keyword::For)? {
+                AlterChangeStreamAction::SetFor(parse_watch_target(tokens)?)
+            } else {
+                AlterChangeStreamAction::SetOptions(Options::parse_clause(tokens)?)
+            }
+        } else if tokens.next_if_word("DROP")? {
+            tokens.expect_kw(Keyword::For)?;
+            tokens.expect_kw(Keyword::All)?;
+            AlterChangeStreamAction::DropForAll
+        } else {
+            return Err(unexpected_next(tokens, "'SET' or 'DROP'"));
+        };
+
+        Ok(Self { name, action })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Statement;
+    use crate::ast::ddl::DdlStatement;
+
+    #[test]
+    fn test_change_streams() {
+        let Statement::Ddl(DdlStatement::CreateChangeStream(stream)) = Statement::parse(
+            "CREATE CHANGE STREAM SingerStream FOR Singers, Albums (AlbumTitle), Songs () OPTIONS \
+             (retention_period = '36h', value_capture_type = 'NEW_ROW')",
+        )
+        .expect("should parse") else {
+            panic!("expected CREATE CHANGE STREAM");
+        };
+
+        let Some(WatchTarget::Tables(tables)) = stream.watch else {
+            panic!("expected watched tables");
+        };
+
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[0].columns, None);
+        assert_eq!(tables[1].columns, Some(vec![Ident::Unquoted("AlbumTitle")]));
+        assert_eq!(tables[2].columns, Some(Vec::new()));
+        assert_eq!(stream.options.entries.len(), 2);
+
+        let Statement::Ddl(DdlStatement::AlterChangeStream(alter)) =
+            Statement::parse("ALTER CHANGE STREAM SingerStream SET FOR ALL").expect("should parse")
+        else {
+            panic!("expected ALTER CHANGE STREAM");
+        };
+        assert_eq!(
+            alter.action,
+            AlterChangeStreamAction::SetFor(WatchTarget::All)
+        );
+
+        Statement::parse("ALTER CHANGE STREAM SingerStream DROP FOR ALL").expect("should parse");
+    }
+}
@@ -0,0 +1,166 @@
+use super::table::{KeyPart, parse_key_parts};
+use super::{parse_ident_list, parse_if_not_exists};
+use crate::Error;
+use crate::ast::ParseTokens;
+use crate::ast::common::expression::Expression;
+use crate::ast::common::ident::{Ident, Path};
+use crate::ast::reserved::Keyword;
+use crate::tokens::{PunctOrOp, TokenizerKind};
+
+/// 'CREATE [UNIQUE] [NULL_FILTERED] INDEX [IF NOT EXISTS] name ON table (keys)
+/// [STORING (columns)] [WHERE condition] [, INTERLEAVE IN parent]'
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateIndex<'src> {
+    pub unique: bool,
+    pub null_filtered: bool,
+    pub if_not_exists: bool,
+    pub name: Path<'src>,
+    pub table: Path<'src>,
+    pub keys: Vec<KeyPart<'src>>,
+    pub storing: Vec<Ident<'src>>,
+    /// The 'WHERE' condition of a filtered index.
+    pub filter: Option<Expression<'src>>,
+    pub interleave_in: Option<Path<'src>>,
+}
+
+/// 'ALTER INDEX name {ADD | DROP} STORED COLUMN column'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlterIndex<'src> {
+    pub name: Path<'src>,
+    pub action: AlterIndexAction<'src>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlterIndexAction<'src> {
+    AddStoredColumn(Ident<'src>),
+    DropStoredColumn(Ident<'src>),
+}
+
+impl<'src> ParseTokens<'src> for CreateIndex<'src> {
+    /// Parses starting after 'CREATE', at the optional 'UNIQUE'/'NULL_FILTERED' modifiers.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let unique = tokens.next_if_word("UNIQUE")?;
+        let null_filtered = tokens.next_if_word("NULL_FILTERED")?;
+        tokens.expect_word("INDEX")?;
+
+        let if_not_exists = parse_if_not_exists(tokens)?;
+        let name = Path::parse_tokens(tokens)?;
+
+        tokens.expect_kw(Keyword::On)?;
+        let table = Path::parse_tokens(tokens)?;
+        let keys = parse_key_parts(tokens)?;
+
+        let storing = if tokens.next_if_word("STORING")? {
+            parse_ident_list(tokens)?
+        } else {
+            Vec::new()
+        };
+
+        let filter = if tokens.next_if_kw(Keyword::Where)? {
+            Some(Expression::parse_tokens(tokens)?)
+        } else {
+            None
+        };
+
+        let interleave_in = if tokens.next_if_punct_op(PunctOrOp::Comma)? {
+            tokens.expect_word("INTERLEAVE")?;
+            tokens.expect_kw(Keyword::In)?;
+            Some(Path::parse_tokens(tokens)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            unique,
+            null_filtered,
+            if_not_exists,
+            name,
+            table,
+            keys,
+            storing,
+            filter,
+            interleave_in,
+        })
+    }
+}
+
+impl<'src> ParseTokens<'src> for AlterIndex<'src> {
+    /// Parses starting at the index name, after 'ALTER INDEX' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let name = Path::parse_tokens(tokens)?;
+
+        let add = if tokens.next_if_word("ADD")? {
+            true
+        } else {
+            tokens.expect_word("DROP")?;
+            false
+        };
+
+        tokens.expect_word("STORED")?;
+        tokens.expect_word("COLUMN")?;
+        let column = Ident::parse_tokens(tokens)?;
+
+        let action = if add {
+            AlterIndexAction::AddStoredColumn(column)
+        } else {
+            AlterIndexAction::DropStoredColumn(column)
+        };
+
+        Ok(Self { name, action })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Statement;
+    use crate::ast::ddl::DdlStatement;
+
+    #[test]
+    fn test_create_and_alter_index() {
+        let Statement::Ddl(DdlStatement::CreateIndex(index)) = Statement::parse(
+            "CREATE UNIQUE NULL_FILTERED INDEX IF NOT EXISTS AlbumsByTitle ON Albums (SingerId, \
+             AlbumTitle DESC) STORING (Rating), INTERLEAVE IN Singers",
+        )
+        .expect("should parse") else {
+            panic!("expected CREATE INDEX");
+        };
+
+        assert!(index.unique && index.null_filtered && index.if_not_exists);
+        assert_eq!(index.keys.len(), 2);
+        assert_eq!(index.storing, [Ident::Unquoted("Rating")]);
+        assert_eq!(
+            index.interleave_in,
+            Some(Path::new(Ident::Unquoted("Singers")))
+        );
+        assert!(index.filter.is_none());
+
+        let Statement::Ddl(DdlStatement::CreateIndex(filtered)) = Statement::parse(
+            "CREATE INDEX SingersByNickname ON Singers (Nickname) WHERE Nickname IS NOT NULL",
+        )
+        .expect("should parse") else {
+            panic!("expected CREATE INDEX");
+        };
+
+        assert!(filtered.filter.is_some());
+        assert!(filtered.interleave_in.is_none());
+
+        let Statement::Ddl(DdlStatement::AlterIndex(alter)) =
+            Statement::parse("ALTER INDEX AlbumsByTitle DROP STORED COLUMN Rating")
+                .expect("should parse")
+        else {
+            panic!("expected ALTER INDEX");
+        };
+
+        assert_eq!(
+            alter.action,
+            AlterIndexAction::DropStoredColumn(Ident::Unquoted("Rating"))
+        );
+    }
+}
@@ -1,42 +1,404 @@
-use super::common::ident::Ident;
-use crate::tokens::StringLiteral;
+use super::common::expression::Expression;
+use super::common::ident::{Ident, InvalidIdent, Path};
+use super::reserved::Keyword;
+use super::{ParseTokens, UnexpectedToken, parse_comma_separated, parse_parens};
+use crate::Error;
+use crate::tokens::{PunctOrOp, StringLiteral, Token, TokenizerKind};
 
+pub mod change_stream;
+pub mod index;
+pub mod sequence;
+pub mod table;
+pub mod view;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum DdlStatement<'src> {
     CreateDatabase { database_id: Ident<'src> },
     AlterDatabase(AlterDatabase<'src>),
+    CreateTable(Box<table::CreateTable<'src>>),
+    AlterTable(Box<table::AlterTable<'src>>),
+    DropTable(Drop<'src>),
+    CreateIndex(Box<index::CreateIndex<'src>>),
+    AlterIndex(index::AlterIndex<'src>),
+    DropIndex(Drop<'src>),
+    CreateChangeStream(change_stream::CreateChangeStream<'src>),
+    AlterChangeStream(change_stream::AlterChangeStream<'src>),
+    DropChangeStream(Drop<'src>),
+    CreateView(Box<view::CreateView<'src>>),
+    DropView(Drop<'src>),
+    CreateSequence(Box<sequence::CreateSequence<'src>>),
+    AlterSequence(sequence::AlterSequence<'src>),
+    DropSequence(Drop<'src>),
+}
+
+/// 'DROP {TABLE | INDEX | CHANGE STREAM | VIEW | SEQUENCE} [IF EXISTS] name'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drop<'src> {
+    pub if_exists: bool,
+    pub name: Path<'src>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct AlterDatabase<'src> {
     pub database_id: Ident<'src>,
     pub options: AlterDbOptions<'src>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct AlterDbOptions<'src> {
-    pub default_leader: Option<NullOr<DefaultLeader>>,
+    pub default_leader: Option<NullOr<StringLiteral<'src>>>,
     pub optimizer_version: Option<NullOr<OptimizerVersion<'src>>>,
     pub optimizer_statistics_package: Option<NullOr<StringLiteral<'src>>>,
     pub version_retention_period: Option<NullOr<StringLiteral<'src>>>,
+    pub enable_key_visualizer: Option<NullOr<bool>>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NullOr<T> {
     Option(T),
     Null,
 }
 
-pub enum DefaultLeader {
-    Region,
-}
-
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OptimizerVersion<'src> {
     Version(&'src str),
 }
 
+/// Generic 'OPTIONS (key = value, ...)' clause, used by columns and change streams.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options<'src> {
+    pub entries: Vec<(Ident<'src>, Expression<'src>)>,
+}
+
+impl<'src> Options<'src> {
+    /// Parses an 'OPTIONS (...)' clause, if the next token is 'OPTIONS'.
+    pub(crate) fn parse_optional_clause<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if !tokens.next_if_word("OPTIONS")? {
+            return Ok(Self::default());
+        }
+
+        let entries = parse_parens(tokens, "an options list", |inner| {
+            parse_comma_separated(inner, |inner| {
+                let key = Ident::parse_tokens(inner)?;
+                inner.expect_punct(PunctOrOp::Eq, "'='")?;
+                let value = Expression::parse_tokens(inner)?;
+                Ok((key, value))
+            })
+        })?;
+
+        Ok(Self { entries })
+    }
+
+    pub(crate) fn parse_clause<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if tokens.peek_is_word("OPTIONS")? {
+            Self::parse_optional_clause(tokens)
+        } else {
+            let (span, token) = tokens.next_or_expected("'OPTIONS'")?;
+            Err(UnexpectedToken::new_expected(span, token, "'OPTIONS'").into())
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 'ON DELETE {CASCADE | NO ACTION}'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnDelete {
+    Cascade,
+    NoAction,
+}
+
+impl OnDelete {
+    /// Parses the action, after 'ON DELETE' has been consumed.
+    pub(crate) fn parse_action<'src, T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if tokens.next_if_word("CASCADE")? {
+            Ok(Self::Cascade)
+        } else {
+            tokens.expect_kw(Keyword::No)?;
+            tokens.expect_word("ACTION")?;
+            Ok(Self::NoAction)
+        }
+    }
+
+    /// Parses an optional 'ON DELETE ...' clause.
+    pub(crate) fn parse_optional_clause<'src, T>(
+        tokens: &mut T,
+    ) -> Result<Option<Self>, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if tokens.peek_is_keyword(Keyword::On)? && tokens.peek_nth_is_word(1, "DELETE")? {
+            tokens.next_or_eof()?;
+            tokens.next_or_eof()?;
+            Self::parse_action(tokens).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Parses 'IF NOT EXISTS', returning whether it was present.
+pub(crate) fn parse_if_not_exists<'src, T>(tokens: &mut T) -> Result<bool, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if tokens.next_if_kw(Keyword::If)? {
+        tokens.expect_kw(Keyword::Not)?;
+        tokens.expect_kw(Keyword::Exists)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+fn parse_drop<'src, T>(tokens: &mut T) -> Result<Drop<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let if_exists = if tokens.next_if_kw(Keyword::If)? {
+        tokens.expect_kw(Keyword::Exists)?;
+        true
+    } else {
+        false
+    };
+
+    let name = Path::parse_tokens(tokens)?;
+    Ok(Drop { if_exists, name })
+}
+
+impl<'src> ParseTokens<'src> for DdlStatement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        const EXPECTED: &str = "'CREATE', 'ALTER' or 'DROP'";
+        const EXPECTED_OBJECT: &str =
+            "'DATABASE', 'TABLE', 'INDEX', 'CHANGE STREAM', 'VIEW' or 'SEQUENCE'";
+
+        let (span, token) = tokens.next_or_expected(EXPECTED)?;
+
+        if token.is_keyword(Keyword::Create) {
+            let (span, token) = tokens.peek()?.ok_or_else(|| {
+                Error::from(UnexpectedToken::eof(tokens.eof_location(), EXPECTED_OBJECT))
+            })?;
+
+            if token.is_word("DATABASE") {
+                tokens.next_or_eof()?;
+                let database_id = parse_database_id(tokens)?;
+                return Ok(Self::CreateDatabase { database_id });
+            }
+
+            if token.is_word("TABLE") {
+                return table::CreateTable::parse_tokens(tokens)
+                    .map(|table| Self::CreateTable(Box::new(table)));
+            }
+
+            if ["UNIQUE", "NULL_FILTERED", "INDEX"]
+                .iter()
+                .any(|word| token.is_word(word))
+            {
+                return index::CreateIndex::parse_tokens(tokens)
+                    .map(|index| Self::CreateIndex(Box::new(index)));
+            }
+
+            if token.is_word("CHANGE") {
+                return change_stream::CreateChangeStream::parse_tokens(tokens)
+                    .map(Self::CreateChangeStream);
+            }
+
+            if token.is_keyword(Keyword::Or) || token.is_word("VIEW") {
+                return view::CreateView::parse_tokens(tokens)
+                    .map(|view| Self::CreateView(Box::new(view)));
+            }
+
+            if token.is_word("SEQUENCE") {
+                return sequence::CreateSequence::parse_tokens(tokens)
+                    .map(|sequence| Self::CreateSequence(Box::new(sequence)));
+            }
+
+            return Err(UnexpectedToken::new_expected(span, token, EXPECTED_OBJECT).into());
+        }
+
+        if token.is_word("ALTER") {
+            let (span, token) = tokens.next_or_expected(EXPECTED_OBJECT)?;
+
+            if token.is_word("DATABASE") {
+                return parse_alter_database(tokens).map(Self::AlterDatabase);
+            }
+
+            if token.is_word("TABLE") {
+                return table::AlterTable::parse_tokens(tokens)
+                    .map(|table| Self::AlterTable(Box::new(table)));
+            }
+
+            if token.is_word("INDEX") {
+                return index::AlterIndex::parse_tokens(tokens).map(Self::AlterIndex);
+            }
+
+            if token.is_word("CHANGE") {
+                tokens.expect_word("STREAM")?;
+                return change_stream::AlterChangeStream::parse_tokens(tokens)
+                    .map(Self::AlterChangeStream);
+            }
+
+            if token.is_word("SEQUENCE") {
+                return sequence::AlterSequence::parse_tokens(tokens).map(Self::AlterSequence);
+            }
+
+            return Err(UnexpectedToken::new_expected(span, token, EXPECTED_OBJECT).into());
+        }
+
+        if token.is_word("DROP") {
+            let (span, token) = tokens.next_or_expected(EXPECTED_OBJECT)?;
+
+            if token.is_word("TABLE") {
+                return parse_drop(tokens).map(Self::DropTable);
+            }
+
+            if token.is_word("INDEX") {
+                return parse_drop(tokens).map(Self::DropIndex);
+            }
+
+            if token.is_word("CHANGE") {
+                tokens.expect_word("STREAM")?;
+                return parse_drop(tokens).map(Self::DropChangeStream);
+            }
+
+            if token.is_word("VIEW") {
+                return parse_drop(tokens).map(Self::DropView);
+            }
+
+            if token.is_word("SEQUENCE") {
+                return parse_drop(tokens).map(Self::DropSequence);
+            }
+
+            return Err(UnexpectedToken::new_expected(span, token, EXPECTED_OBJECT).into());
+        }
+
+        Err(UnexpectedToken::new_expected(span, token, EXPECTED).into())
+    }
+}
+
+/// Database ids can contain '-', which the tokenizer splits into separate tokens. This glues
+/// adjacent tokens back together, then validates the whole id.
+fn parse_database_id<'src, T>(tokens: &mut T) -> Result<Ident<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let (mut span, _) = tokens.next_or_expected("a database id")?;
+
+    while let Some((next, token)) = tokens.peek()?
+        && next.start.offset == span.end_offset()
+        && matches!(
+            token,
+            Token::Unquoted(_) | Token::NumericLiteral(_) | Token::PunctOrOp(PunctOrOp::Neg)
+        )
+    {
+        tokens.next_or_eof()?;
+        span = span.to(next);
+    }
+
+    let raw = tokens.source(span);
+
+    match ddl_parser::database_id(raw) {
+        Ok(_) if raw.starts_with('`') => Ok(Ident::Quoted(raw)),
+        Ok(_) => Ok(Ident::Unquoted(raw)),
+        Err(_) => Err(Error::InvalidIdent {
+            span,
+            error: InvalidIdent::InvalidIdent {
+                ident: raw,
+                reason: "database ids must be 2-30 lowercase letters, numbers, '-' or '_', \
+                         starting with a letter",
+            },
+        }),
+    }
+}
+
+fn parse_alter_database<'src, T>(tokens: &mut T) -> Result<AlterDatabase<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let database_id = parse_database_id(tokens)?;
+    tokens.expect_kw(Keyword::Set)?;
+    tokens.expect_word("OPTIONS")?;
+
+    let mut options = AlterDbOptions::default();
+
+    parse_parens(tokens, "an options list", |inner| {
+        parse_comma_separated(inner, |inner| {
+            let (key_span, key) = inner.next_or_expected("a database option")?;
+            inner.expect_punct(PunctOrOp::Eq, "'='")?;
+            let (span, value) = inner.next_or_expected("an option value")?;
+
+            let invalid = || {
+                Error::from(UnexpectedToken::new_expected(
+                    span,
+                    value,
+                    "a valid option value",
+                ))
+            };
+
+            let string_or_null = || match value {
+                Token::StringLiteral(lit) => Ok(NullOr::Option(lit)),
+                _ if value.is_keyword(Keyword::Null) => Ok(NullOr::Null),
+                _ => Err(invalid()),
+            };
+
+            if key.is_word("default_leader") {
+                options.default_leader = Some(string_or_null()?);
+            } else if key.is_word("optimizer_statistics_package") {
+                options.optimizer_statistics_package = Some(string_or_null()?);
+            } else if key.is_word("version_retention_period") {
+                options.version_retention_period = Some(string_or_null()?);
+            } else if key.is_word("optimizer_version") {
+                options.optimizer_version = Some(match value {
+                    Token::NumericLiteral(num) => NullOr::Option(OptimizerVersion::Version(num)),
+                    _ if value.is_keyword(Keyword::Null) => NullOr::Null,
+                    _ => return Err(invalid()),
+                });
+            } else if key.is_word("enable_key_visualizer") {
+                options.enable_key_visualizer = Some(match value.as_keyword() {
+                    Some(Keyword::True) => NullOr::Option(true),
+                    Some(Keyword::False) => NullOr::Option(false),
+                    Some(Keyword::Null) => NullOr::Null,
+                    _ => return Err(invalid()),
+                });
+            } else {
+                return Err(UnexpectedToken::new_expected(
+                    key_span,
+                    key,
+                    "a known database option",
+                )
+                .into());
+            }
+
+            Ok(())
+        })
+    })?;
+
+    Ok(AlterDatabase {
+        database_id,
+        options,
+    })
+}
+
 peg::parser! {
     grammar ddl_parser() for str {
         rule unescaped_database_id() -> &'input str
             = s:$(['a'..='z'] ['a'..='z' | '0'..='9' | '-' | '_']*<1, 29>)
             {?
-                if s.ends_with(|ch: char| matches!(ch, '-' | '_')) {
+                if s.ends_with(['-', '_']) {
                     Err("can't end with a '-' or '_'")
                 } else {
                     Ok(s)
@@ -44,15 +406,44 @@ peg::parser! {
             }
 
         rule escaped_database_id() -> &'input str
-            = s:$(['`'] ['_']*<2, 29> ['`']) { s }
+            = s:$("`" unescaped_database_id() "`") { s }
 
         pub rule database_id() -> &'input str = unescaped_database_id() / escaped_database_id()
     }
 }
 
+/// Helper to build an [`UnexpectedToken`] error from the next token, or the end of the input.
+pub(crate) fn unexpected_next<'src, T>(tokens: &mut T, expected: &'static str) -> Error<'src>
+where
+    T: TokenizerKind<'src>,
+{
+    match tokens.next_or_expected(expected) {
+        Ok((span, token)) => UnexpectedToken::new_expected(span, token, expected).into(),
+        Err(err) => err,
+    }
+}
+
+/// Parses a non-empty, parenthesized list of identifiers, i.e '(a, b, c)'.
+pub(crate) fn parse_ident_list<'src, T>(tokens: &mut T) -> Result<Vec<Ident<'src>>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    parse_parens(tokens, "a column list", |inner| {
+        parse_comma_separated(inner, Ident::parse_tokens)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::Statement;
+
+    fn parse(sql: &str) -> DdlStatement<'_> {
+        match Statement::parse(sql).expect("should parse") {
+            Statement::Ddl(ddl) => ddl,
+            other => panic!("expected a DDL statement, got {other:?}"),
+        }
+    }
 
     #[test]
     fn test_database_id_parser() {
@@ -60,5 +451,56 @@ mod tests {
         ddl_parser::database_id("endswith-").expect_err("should fail");
         ddl_parser::database_id("1startswithnumber").expect_err("should fail");
         ddl_parser::database_id("a-valid_id1234").expect("should be valid");
+        ddl_parser::database_id("`a-valid-id`").expect("should be valid");
+    }
+
+    #[test]
+    fn test_database_statements() {
+        assert_eq!(
+            parse("CREATE DATABASE my-database-1"),
+            DdlStatement::CreateDatabase {
+                database_id: Ident::Unquoted("my-database-1")
+            }
+        );
+
+        let DdlStatement::AlterDatabase(alter) = parse(
+            "ALTER DATABASE `my-db` SET OPTIONS (optimizer_version = 5, default_leader = NULL)",
+        ) else {
+            panic!("expected ALTER DATABASE");
+        };
+
+        assert_eq!(alter.database_id, Ident::Quoted("`my-db`"));
+        assert_eq!(
+            alter.options.optimizer_version,
+            Some(NullOr::Option(OptimizerVersion::Version("5")))
+        );
+        assert_eq!(alter.options.default_leader, Some(NullOr::Null));
+
+        let err = Statement::parse("CREATE DATABASE Invalid").unwrap_err();
+        assert!(matches!(err, Error::InvalidIdent { .. }));
+        assert_eq!(err.span().start.column, 16);
+
+        Statement::parse("ALTER DATABASE db SET OPTIONS (not_an_option = 1)")
+            .expect_err("unknown options should be rejected");
+    }
+
+    #[test]
+    fn test_drop_statements() {
+        assert_eq!(
+            parse("DROP TABLE IF EXISTS Singers"),
+            DdlStatement::DropTable(Drop {
+                if_exists: true,
+                name: Path::new(Ident::Unquoted("Singers")),
+            })
+        );
+
+        assert!(matches!(
+            parse("DROP CHANGE STREAM SingerStream"),
+            DdlStatement::DropChangeStream(_)
+        ));
+        assert!(matches!(
+            parse("DROP VIEW SingerNames"),
+            DdlStatement::DropView(_)
+        ));
     }
 }
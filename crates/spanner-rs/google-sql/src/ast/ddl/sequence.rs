@@ -0,0 +1,197 @@
+use super::{Options, parse_if_not_exists, unexpected_next};
+use crate::Error;
+use crate::ast::common::ident::Path;
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, UnexpectedToken};
+use crate::tokens::{PunctOrOp, TokenizerKind};
+
+/// 'CREATE SEQUENCE [IF NOT EXISTS] name [BIT_REVERSED_POSITIVE] [SKIP RANGE min, max]
+/// [START COUNTER WITH start] [OPTIONS (...)]'
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateSequence<'src> {
+    pub if_not_exists: bool,
+    pub name: Path<'src>,
+    pub bit_reversed_positive: bool,
+    pub skip_range: Option<SkipRange>,
+    pub start_counter_with: Option<i64>,
+    pub options: Options<'src>,
+}
+
+/// A range of values that a sequence never generates, i.e 'SKIP RANGE 1, 1000'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkipRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+/// 'ALTER SEQUENCE name {SET OPTIONS (...) | SKIP RANGE min, max | NO SKIP RANGE |
+/// RESTART COUNTER WITH counter}'
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlterSequence<'src> {
+    pub name: Path<'src>,
+    pub action: AlterSequenceAction<'src>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterSequenceAction<'src> {
+    SetOptions(Options<'src>),
+    SkipRange(SkipRange),
+    NoSkipRange,
+    RestartCounterWith(i64),
+}
+
+fn parse_integer<'src, T>(tokens: &mut T, expected: &'static str) -> Result<i64, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let (span, token) = tokens.next_or_expected(expected)?;
+
+    token
+        .as_numeric_literal()
+        .and_then(|num| num.parse().ok())
+        .ok_or_else(|| UnexpectedToken::new_expected(span, token, expected).into())
+}
+
+/// Parses the bounds of a skipped range, after 'SKIP RANGE' has been consumed.
+fn parse_skip_range<'src, T>(tokens: &mut T) -> Result<SkipRange, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let min = parse_integer(tokens, "the start of the skipped range")?;
+    tokens.expect_punct(PunctOrOp::Comma, "','")?;
+    let max = parse_integer(tokens, "the end of the skipped range")?;
+
+    Ok(SkipRange { min, max })
+}
+
+impl<'src> ParseTokens<'src> for CreateSequence<'src> {
+    /// Parses starting at 'SEQUENCE', after 'CREATE' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_word("SEQUENCE")?;
+
+        let if_not_exists = parse_if_not_exists(tokens)?;
+        let name = Path::parse_tokens(tokens)?;
+
+        let bit_reversed_positive = tokens.next_if_word("BIT_REVERSED_POSITIVE")?;
+
+        let skip_range = if tokens.next_if_word("SKIP")? {
+            tokens.expect_kw(Keyword::Range)?;
+            Some(parse_skip_range(tokens)?)
+        } else {
+            None
+        };
+
+        let start_counter_with = if tokens.next_if_word("START")? {
+            tokens.expect_word("COUNTER")?;
+            tokens.expect_kw(Keyword::With)?;
+            Some(parse_integer(tokens, "a start counter")?)
+        } else {
+            None
+        };
+
+        let options = Options::parse_optional_clause(tokens)?;
+
+        Ok(Self {
+            if_not_exists,
+            name,
+            bit_reversed_positive,
+            skip_range,
+            start_counter_with,
+            options,
+        })
+    }
+}
+
+impl<'src> ParseTokens<'src> for AlterSequence<'src> {
+    /// Parses starting at the sequence name, after 'ALTER SEQUENCE' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        const EXPECTED: &str = "'SET OPTIONS', 'SKIP RANGE', 'NO SKIP RANGE' or 'RESTART'";
+
+        let name = Path::parse_tokens(tokens)?;
+
+        let action = if tokens.next_if_kw(Keyword::Set)? {
+            AlterSequenceAction::SetOptions(Options::parse_clause(tokens)?)
+        } else if tokens.next_if_word("SKIP")? {
+            tokens.expect_kw(Keyword::Range)?;
+            AlterSequenceAction::SkipRange(parse_skip_range(tokens)?)
+        } else if tokens.next_if_kw(Keyword::No)? {
+            tokens.expect_word("SKIP")?;
+            tokens.expect_kw(Keyword::Range)?;
+            AlterSequenceAction::NoSkipRange
+        } else if tokens.next_if_word("RESTART")? {
+            tokens.expect_word("COUNTER")?;
+            tokens.expect_kw(Keyword::With)?;
+            AlterSequenceAction::RestartCounterWith(parse_integer(tokens, "a counter")?)
+        } else {
+            return Err(unexpected_next(tokens, EXPECTED));
+        };
+
+        Ok(Self { name, action })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Statement;
+    use crate::ast::common::ident::Ident;
+    use crate::ast::ddl::DdlStatement;
+
+    #[test]
+    fn test_sequences() {
+        let Statement::Ddl(DdlStatement::CreateSequence(sequence)) = Statement::parse(
+            "CREATE SEQUENCE IF NOT EXISTS SingerIds BIT_REVERSED_POSITIVE SKIP RANGE 1, 1000 \
+             START COUNTER WITH 50 OPTIONS (sequence_kind = 'bit_reversed_positive')",
+        )
+        .expect("should parse") else {
+            panic!("expected CREATE SEQUENCE");
+        };
+
+        assert!(sequence.if_not_exists && sequence.bit_reversed_positive);
+        assert_eq!(sequence.name, Path::new(Ident::Unquoted("SingerIds")));
+        assert_eq!(sequence.skip_range, Some(SkipRange { min: 1, max: 1000 }));
+        assert_eq!(sequence.start_counter_with, Some(50));
+        assert_eq!(sequence.options.entries.len(), 1);
+
+        let cases = [
+            (
+                "ALTER SEQUENCE SingerIds NO SKIP RANGE",
+                AlterSequenceAction::NoSkipRange,
+            ),
+            (
+                "ALTER SEQUENCE SingerIds RESTART COUNTER WITH 10",
+                AlterSequenceAction::RestartCounterWith(10),
+            ),
+            (
+                "ALTER SEQUENCE SingerIds SKIP RANGE 5, 10",
+                AlterSequenceAction::SkipRange(SkipRange { min: 5, max: 10 }),
+            ),
+        ];
+
+        for (sql, expected) in cases {
+            let Statement::Ddl(DdlStatement::AlterSequence(alter)) =
+                Statement::parse(sql).expect("should parse")
+            else {
+                panic!("expected ALTER SEQUENCE");
+            };
+            assert_eq!(alter.action, expected, "failed to parse '{sql}'");
+        }
+
+        Statement::parse("ALTER SEQUENCE SingerIds SET OPTIONS (start_with_counter = 1)")
+            .expect("should parse");
+        Statement::parse("ALTER SEQUENCE SingerIds RENAME TO Ids")
+            .expect_err("sequences can't be renamed");
+        Statement::parse("CREATE SEQUENCE Ids SKIP RANGE 1").expect_err("needs an upper bound");
+
+        assert!(matches!(
+            Statement::parse("DROP SEQUENCE IF EXISTS SingerIds").expect("should parse"),
+            Statement::Ddl(DdlStatement::DropSequence(_))
+        ));
+    }
+}
@@ -0,0 +1,546 @@
+use super::{OnDelete, Options, parse_ident_list, parse_if_not_exists, unexpected_next};
+use crate::Error;
+use crate::ast::common::expression::Expression;
+use crate::ast::common::ident::{Ident, Path};
+use crate::ast::common::types::Type;
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, UnexpectedToken, parse_comma_separated, parse_parens};
+use crate::tokens::{PunctOrOp, TokenizerKind};
+
+/// 'CREATE TABLE [IF NOT EXISTS] name (columns, constraints) PRIMARY KEY (keys)
+/// [, INTERLEAVE IN PARENT parent [ON DELETE ...]] [, ROW DELETION POLICY (...)]'
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateTable<'src> {
+    pub if_not_exists: bool,
+    pub name: Path<'src>,
+    pub columns: Vec<ColumnDef<'src>>,
+    pub constraints: Vec<TableConstraint<'src>>,
+    pub primary_key: Vec<KeyPart<'src>>,
+    pub interleave: Option<Interleave<'src>>,
+    pub row_deletion_policy: Option<RowDeletionPolicy<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDef<'src> {
+    pub name: Ident<'src>,
+    pub ty: Type<'src>,
+    pub not_null: bool,
+    pub value: Option<ColumnValue<'src>>,
+    pub hidden: bool,
+    pub options: Options<'src>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue<'src> {
+    /// 'DEFAULT (expr)'
+    Default(Expression<'src>),
+    /// 'AS (expr) [STORED]'
+    Generated {
+        expr: Expression<'src>,
+        stored: bool,
+    },
+}
+
+/// A primary or index key column, i.e 'SingerId DESC'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPart<'src> {
+    pub column: Ident<'src>,
+    pub ascending: bool,
+}
+
+/// 'INTERLEAVE IN PARENT parent [ON DELETE {CASCADE | NO ACTION}]'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interleave<'src> {
+    pub parent: Path<'src>,
+    pub on_delete: Option<OnDelete>,
+}
+
+/// 'ROW DELETION POLICY (OLDER_THAN(column, INTERVAL num_days DAY))'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowDeletionPolicy<'src> {
+    pub column: Ident<'src>,
+    pub num_days: u64,
+}
+
+/// '[CONSTRAINT name] {CHECK (expr) | FOREIGN KEY (...) REFERENCES ...}'
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableConstraint<'src> {
+    pub name: Option<Ident<'src>>,
+    pub kind: ConstraintKind<'src>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintKind<'src> {
+    Check(Expression<'src>),
+    ForeignKey(ForeignKey<'src>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKey<'src> {
+    pub columns: Vec<Ident<'src>>,
+    pub referenced_table: Path<'src>,
+    pub referenced_columns: Vec<Ident<'src>>,
+    pub on_delete: Option<OnDelete>,
+}
+
+/// 'ALTER TABLE name action'
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlterTable<'src> {
+    pub name: Path<'src>,
+    pub action: AlterTableAction<'src>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterTableAction<'src> {
+    AddColumn {
+        if_not_exists: bool,
+        column: ColumnDef<'src>,
+    },
+    DropColumn(Ident<'src>),
+    AlterColumn {
+        column: Ident<'src>,
+        alteration: ColumnAlteration<'src>,
+    },
+    AddConstraint(TableConstraint<'src>),
+    DropConstraint(Ident<'src>),
+    SetOnDelete(OnDelete),
+    AddRowDeletionPolicy(RowDeletionPolicy<'src>),
+    ReplaceRowDeletionPolicy(RowDeletionPolicy<'src>),
+    DropRowDeletionPolicy,
+    RenameTo(Path<'src>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnAlteration<'src> {
+    /// Changes the type, nullability or default value of the column.
+    Redefine {
+        ty: Type<'src>,
+        not_null: bool,
+        default: Option<Expression<'src>>,
+    },
+    SetOptions(Options<'src>),
+    SetDefault(Expression<'src>),
+    DropDefault,
+}
+
+impl<'src> ParseTokens<'src> for CreateTable<'src> {
+    /// Parses starting at 'TABLE', after 'CREATE' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_word("TABLE")?;
+
+        let if_not_exists = parse_if_not_exists(tokens)?;
+        let name = Path::parse_tokens(tokens)?;
+
+        let mut columns = Vec::new();
+        let mut constraints = Vec::new();
+
+        parse_parens(tokens, "a column list", |inner| {
+            // tables with only a primary key are valid, i.e 'CREATE TABLE t () PRIMARY KEY ()'
+            if TokenizerKind::peek(inner)?.is_none() {
+                return Ok(());
+            }
+
+            loop {
+                if is_constraint_start(inner)? {
+                    constraints.push(TableConstraint::parse_tokens(inner)?);
+                } else {
+                    columns.push(ColumnDef::parse_tokens(inner)?);
+                }
+
+                // trailing commas are allowed
+                if !inner.next_if_punct_op(PunctOrOp::Comma)?
+                    || TokenizerKind::peek(inner)?.is_none()
+                {
+                    return Ok(());
+                }
+            }
+        })?;
+
+        tokens.expect_word("PRIMARY")?;
+        tokens.expect_word("KEY")?;
+        let primary_key = parse_key_parts(tokens)?;
+
+        let mut interleave = None;
+        let mut row_deletion_policy = None;
+
+        while tokens.next_if_punct_op(PunctOrOp::Comma)? {
+            if tokens.next_if_word("INTERLEAVE")? {
+                tokens.expect_kw(Keyword::In)?;
+                tokens.expect_word("PARENT")?;
+                let parent = Path::parse_tokens(tokens)?;
+                let on_delete = OnDelete::parse_optional_clause(tokens)?;
+                interleave = Some(Interleave { parent, on_delete });
+            } else if tokens.peek_is_word("ROW")? {
+                row_deletion_policy = Some(parse_row_deletion_policy(tokens)?);
+            } else {
+                return Err(unexpected_next(
+                    tokens,
+                    "'INTERLEAVE IN PARENT' or 'ROW DELETION POLICY'",
+                ));
+            }
+        }
+
+        Ok(Self {
+            if_not_exists,
+            name,
+            columns,
+            constraints,
+            primary_key,
+            interleave,
+            row_deletion_policy,
+        })
+    }
+}
+
+fn is_constraint_start<'src, T>(tokens: &mut T) -> Result<bool, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    Ok(tokens.peek_is_word("CONSTRAINT")?
+        || tokens.peek_is_word("CHECK")?
+        || (tokens.peek_is_word("FOREIGN")? && tokens.peek_nth_is_word(1, "KEY")?))
+}
+
+impl<'src> ParseTokens<'src> for ColumnDef<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let name = Ident::parse_tokens(tokens)?;
+        let ty = Type::parse_tokens(tokens)?;
+        let not_null = parse_not_null(tokens)?;
+
+        let value = if tokens.next_if_kw(Keyword::Default)? {
+            let expr = parse_parens(tokens, "'('", Expression::parse_tokens)?;
+            Some(ColumnValue::Default(expr))
+        } else if tokens.next_if_kw(Keyword::As)? {
+            let expr = parse_parens(tokens, "'('", Expression::parse_tokens)?;
+            let stored = tokens.next_if_word("STORED")?;
+            Some(ColumnValue::Generated { expr, stored })
+        } else {
+            None
+        };
+
+        let hidden = tokens.next_if_word("HIDDEN")?;
+        let options = Options::parse_optional_clause(tokens)?;
+
+        Ok(Self {
+            name,
+            ty,
+            not_null,
+            value,
+            hidden,
+            options,
+        })
+    }
+}
+
+fn parse_not_null<'src, T>(tokens: &mut T) -> Result<bool, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if tokens.next_if_kw(Keyword::Not)? {
+        tokens.expect_kw(Keyword::Null)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+impl<'src> ParseTokens<'src> for TableConstraint<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let name = if tokens.next_if_word("CONSTRAINT")? {
+            Some(Ident::parse_tokens(tokens)?)
+        } else {
+            None
+        };
+
+        let kind = if tokens.next_if_word("CHECK")? {
+            ConstraintKind::Check(parse_parens(tokens, "'('", Expression::parse_tokens)?)
+        } else if tokens.next_if_word("FOREIGN")? {
+            tokens.expect_word("KEY")?;
+            let columns = parse_ident_list(tokens)?;
+            tokens.expect_word("REFERENCES")?;
+            let referenced_table = Path::parse_tokens(tokens)?;
+            let referenced_columns = parse_ident_list(tokens)?;
+            let on_delete = OnDelete::parse_optional_clause(tokens)?;
+
+            ConstraintKind::ForeignKey(ForeignKey {
+                columns,
+                referenced_table,
+                referenced_columns,
+                on_delete,
+            })
+        } else {
+            return Err(unexpected_next(tokens, "'CHECK' or 'FOREIGN KEY'"));
+        };
+
+        Ok(Self { name, kind })
+    }
+}
+
+/// Parses a parenthesized list of key columns, i.e '(SingerId, AlbumId DESC)'. Empty lists are
+/// allowed, since tables can have an empty primary key.
+pub(crate) fn parse_key_parts<'src, T>(tokens: &mut T) -> Result<Vec<KeyPart<'src>>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    parse_parens(tokens, "a key column list", |inner| {
+        if TokenizerKind::peek(inner)?.is_none() {
+            return Ok(Vec::new());
+        }
+
+        parse_comma_separated(inner, |inner| {
+            let column = Ident::parse_tokens(inner)?;
+
+            let ascending = if inner.next_if_kw(Keyword::Desc)? {
+                false
+            } else {
+                inner.next_if_kw(Keyword::Asc)?;
+                true
+            };
+
+            Ok(KeyPart { column, ascending })
+        })
+    })
+}
+
+/// Parses 'ROW DELETION POLICY (OLDER_THAN(column, INTERVAL n DAY))'.
+fn parse_row_deletion_policy<'src, T>(
+    tokens: &mut T,
+) -> Result<RowDeletionPolicy<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    tokens.expect_word("ROW")?;
+    tokens.expect_word("DELETION")?;
+    tokens.expect_word("POLICY")?;
+
+    parse_parens(tokens, "'('", |inner| {
+        inner.expect_word("OLDER_THAN")?;
+
+        parse_parens(inner, "'('", |inner| {
+            let column = Ident::parse_tokens(inner)?;
+            inner.expect_punct(PunctOrOp::Comma, "','")?;
+            inner.expect_kw(Keyword::Interval)?;
+
+            let (span, token) = inner.next_or_expected("a number of days")?;
+            let num_days = token
+                .as_numeric_literal()
+                .and_then(|num| num.parse().ok())
+                .ok_or_else(|| UnexpectedToken::new_expected(span, token, "a number of days"))?;
+
+            inner.expect_word("DAY")?;
+
+            Ok(RowDeletionPolicy { column, num_days })
+        })
+    })
+}
+
+impl<'src> ParseTokens<'src> for AlterTable<'src> {
+    /// Parses starting at the table name, after 'ALTER TABLE' has been consumed.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        const EXPECTED: &str = "'ADD', 'DROP', 'ALTER', 'SET', 'REPLACE' or 'RENAME'";
+
+        let name = Path::parse_tokens(tokens)?;
+
+        let action = if tokens.next_if_word("ADD")? {
+            if is_constraint_start(tokens)? {
+                AlterTableAction::AddConstraint(TableConstraint::parse_tokens(tokens)?)
+            } else if tokens.peek_is_word("ROW")? {
+                AlterTableAction::AddRowDeletionPolicy(parse_row_deletion_policy(tokens)?)
+            } else {
+                tokens.next_if_word("COLUMN")?;
+                let if_not_exists = parse_if_not_exists(tokens)?;
+                let column = ColumnDef::parse_tokens(tokens)?;
+
+                AlterTableAction::AddColumn {
+                    if_not_exists,
+                    column,
+                }
+            }
+        } else if tokens.next_if_word("DROP")? {
+            if tokens.next_if_word("CONSTRAINT")? {
+                AlterTableAction::DropConstraint(Ident::parse_tokens(tokens)?)
+            } else if tokens.next_if_word("ROW")? {
+                tokens.expect_word("DELETION")?;
+                tokens.expect_word("POLICY")?;
+                AlterTableAction::DropRowDeletionPolicy
+            } else {
+                tokens.next_if_word("COLUMN")?;
+                AlterTableAction::DropColumn(Ident::parse_tokens(tokens)?)
+            }
+        } else if tokens.next_if_word("ALTER")? {
+            tokens.next_if_word("COLUMN")?;
+            let column = Ident::parse_tokens(tokens)?;
+            let alteration = parse_column_alteration(tokens)?;
+
+            AlterTableAction::AlterColumn { column, alteration }
+        } else if tokens.next_if_kw(Keyword::Set)? {
+            tokens.expect_kw(Keyword::On)?;
+            tokens.expect_word("DELETE")?;
+            AlterTableAction::SetOnDelete(OnDelete::parse_action(tokens)?)
+        } else if tokens.next_if_word("REPLACE")? {
+            AlterTableAction::ReplaceRowDeletionPolicy(parse_row_deletion_policy(tokens)?)
+        } else if tokens.next_if_word("RENAME")? {
+            tokens.expect_kw(Keyword::To)?;
+            AlterTableAction::RenameTo(Path::parse_tokens(tokens)?)
+        } else {
+            return Err(unexpected_next(tokens, EXPECTED));
+        };
+
+        Ok(Self { name, action })
+    }
+}
+
+fn parse_column_alteration<'src, T>(tokens: &mut T) -> Result<ColumnAlteration<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    if tokens.next_if_kw(Keyword::Set)? {
+        if tokens.peek_is_word("OPTIONS")? {
+            return Options::parse_clause(tokens).map(ColumnAlteration::SetOptions);
+        }
+
+        tokens.expect_kw(Keyword::Default)?;
+        let expr = parse_parens(tokens, "'('", Expression::parse_tokens)?;
+        return Ok(ColumnAlteration::SetDefault(expr));
+    }
+
+    if tokens.next_if_word("DROP")? {
+        tokens.expect_kw(Keyword::Default)?;
+        return Ok(ColumnAlteration::DropDefault);
+    }
+
+    let ty = Type::parse_tokens(tokens)?;
+    let not_null = parse_not_null(tokens)?;
+
+    let default = if tokens.next_if_kw(Keyword::Default)? {
+        Some(parse_parens(tokens, "'('", Expression::parse_tokens)?)
+    } else {
+        None
+    };
+
+    Ok(ColumnAlteration::Redefine {
+        ty,
+        not_null,
+        default,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Statement;
+    use crate::ast::common::types::Length;
+    use crate::ast::ddl::DdlStatement;
+    use crate::ast::reserved::DataType;
+
+    const CREATE_ALBUMS: &str = r#"
+        CREATE TABLE IF NOT EXISTS Albums (
+            SingerId     INT64 NOT NULL,
+            AlbumId      INT64 NOT NULL,
+            AlbumTitle   STRING(MAX),
+            Tags         ARRAY<STRING(64)>,
+            Details      STRUCT<Label STRING(MAX), Year INT64>,
+            Created      TIMESTAMP NOT NULL OPTIONS (allow_commit_timestamp = true),
+            TitleUpper   STRING(MAX) AS (UPPER(AlbumTitle)) STORED,
+            Rating       FLOAT64 DEFAULT (0.0) HIDDEN,
+            CONSTRAINT FK_Label FOREIGN KEY (AlbumTitle) REFERENCES Labels (Name) ON DELETE CASCADE,
+            CHECK (Rating >= 0),
+        ) PRIMARY KEY (SingerId, AlbumId DESC),
+          INTERLEAVE IN PARENT Singers ON DELETE NO ACTION,
+          ROW DELETION POLICY (OLDER_THAN(Created, INTERVAL 30 DAY))
+    "#;
+
+    fn parse(sql: &str) -> DdlStatement<'_> {
+        match Statement::parse(sql).expect("should parse") {
+            Statement::Ddl(ddl) => ddl,
+            other => panic!("expected a DDL statement, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_create_table() {
+        let DdlStatement::CreateTable(table) = parse(CREATE_ALBUMS) else {
+            panic!("expected CREATE TABLE");
+        };
+
+        assert!(table.if_not_exists);
+        assert_eq!(table.name.to_string(), "Albums");
+        assert_eq!(table.columns.len(), 8);
+        assert_eq!(table.constraints.len(), 2);
+
+        assert_eq!(
+            table.columns[2].ty,
+            Type::Scalar {
+                data_type: DataType::String,
+                length: Some(Length::Max),
+            }
+        );
+        assert!(table.columns[0].not_null);
+        assert!(!table.columns[5].options.is_empty());
+        assert!(matches!(
+            table.columns[6].value,
+            Some(ColumnValue::Generated { stored: true, .. })
+        ));
+        assert!(table.columns[7].hidden);
+
+        assert_eq!(
+            table.primary_key[1],
+            KeyPart {
+                column: Ident::Unquoted("AlbumId"),
+                ascending: false,
+            }
+        );
+
+        let interleave = table.interleave.expect("is interleaved");
+        assert_eq!(interleave.on_delete, Some(OnDelete::NoAction));
+
+        assert_eq!(
+            table.row_deletion_policy,
+            Some(RowDeletionPolicy {
+                column: Ident::Unquoted("Created"),
+                num_days: 30,
+            })
+        );
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let cases = [
+            "ALTER TABLE Albums ADD COLUMN IF NOT EXISTS Label STRING(MAX)",
+            "ALTER TABLE Albums DROP COLUMN Label",
+            "ALTER TABLE Albums ALTER COLUMN Label STRING(1024) NOT NULL",
+            "ALTER TABLE Albums ALTER COLUMN Created SET OPTIONS (allow_commit_timestamp = null)",
+            "ALTER TABLE Albums ALTER COLUMN Rating SET DEFAULT (1.0)",
+            "ALTER TABLE Albums ALTER COLUMN Rating DROP DEFAULT",
+            "ALTER TABLE Albums ADD CONSTRAINT PositiveRating CHECK (Rating > 0)",
+            "ALTER TABLE Albums DROP CONSTRAINT PositiveRating",
+            "ALTER TABLE Albums SET ON DELETE CASCADE",
+            "ALTER TABLE Albums ADD ROW DELETION POLICY (OLDER_THAN(Created, INTERVAL 7 DAY))",
+            "ALTER TABLE Albums REPLACE ROW DELETION POLICY (OLDER_THAN(Created, INTERVAL 1 DAY))",
+            "ALTER TABLE Albums DROP ROW DELETION POLICY",
+            "ALTER TABLE Albums RENAME TO Records",
+        ];
+
+        for case in cases {
+            assert!(
+                matches!(parse(case), DdlStatement::AlterTable(_)),
+                "failed to parse '{case}'"
+            );
+        }
+
+        let err = Statement::parse("ALTER TABLE Albums ADD COLUMN Label STRIN").unwrap_err();
+        assert_eq!(err.span().start.column, 36);
+    }
+}
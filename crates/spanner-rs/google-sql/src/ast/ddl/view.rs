@@ -0,0 +1,87 @@
+use crate::Error;
+use crate::ast::common::ident::Path;
+use crate::ast::query::QueryExpr;
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, UnexpectedToken};
+use crate::tokens::TokenizerKind;
+
+/// 'CREATE [OR REPLACE] VIEW name SQL SECURITY {INVOKER | DEFINER} AS query'
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateView<'src> {
+    pub or_replace: bool,
+    pub name: Path<'src>,
+    pub security: SqlSecurity,
+    pub query: QueryExpr<'src>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlSecurity {
+    Invoker,
+    Definer,
+}
+
+impl<'src> ParseTokens<'src> for CreateView<'src> {
+    /// Parses starting after 'CREATE', at the optional 'OR REPLACE'.
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        const EXPECTED: &str = "'INVOKER' or 'DEFINER'";
+
+        let or_replace = if tokens.next_if_kw(Keyword::Or)? {
+            tokens.expect_word("REPLACE")?;
+            true
+        } else {
+            false
+        };
+
+        tokens.expect_word("VIEW")?;
+        let name = Path::parse_tokens(tokens)?;
+
+        tokens.expect_word("SQL")?;
+        tokens.expect_word("SECURITY")?;
+
+        let (span, token) = tokens.next_or_expected(EXPECTED)?;
+        let security = if token.is_word("INVOKER") {
+            SqlSecurity::Invoker
+        } else if token.is_word("DEFINER") {
+            SqlSecurity::Definer
+        } else {
+            return Err(UnexpectedToken::new_expected(span, token, EXPECTED).into());
+        };
+
+        tokens.expect_kw(Keyword::As)?;
+        let query = QueryExpr::parse_tokens(tokens)?;
+
+        Ok(Self {
+            or_replace,
+            name,
+            security,
+            query,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Statement;
+    use crate::ast::ddl::DdlStatement;
+
+    #[test]
+    fn test_create_view() {
+        let Statement::Ddl(DdlStatement::CreateView(view)) = Statement::parse(
+            "CREATE OR REPLACE VIEW SingerNames SQL SECURITY INVOKER AS SELECT Singers.SingerId, \
+             Singers.FirstName FROM Singers",
+        )
+        .expect("should parse") else {
+            panic!("expected CREATE VIEW");
+        };
+
+        assert!(view.or_replace);
+        assert_eq!(view.security, SqlSecurity::Invoker);
+
+        Statement::parse("CREATE VIEW SingerNames AS SELECT 1")
+            .expect_err("SQL SECURITY is required");
+    }
+}
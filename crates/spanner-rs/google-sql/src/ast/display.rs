@@ -0,0 +1,1484 @@
+//! [`fmt::Display`] impls that print the AST back out as SQL.
+//!
+//! The output isn't formatted like the original input (keywords are uppercased, and compound
+//! expressions are always parenthesized), but parsing it again yields an identical AST.
+
+use std::fmt;
+
+use super::Statement;
+use super::common::AsAlias;
+use super::common::expression::{
+    AggregateOptions, Arguments, CaseExpr, Expression, Extract, FunctionCall, FunctionHint, Having,
+    NullHandling, ValueExpr,
+};
+use super::common::ident::{Ident, Path};
+use super::common::operator::{
+    AccessOperation, Between, BinaryOperation, BinaryOperator, InOperation, InRhs, Index,
+    Operation, UnaryOperation, UnaryOperator,
+};
+use super::common::types::{Length, StructField, Type};
+use super::ddl::change_stream::{
+    AlterChangeStream, AlterChangeStreamAction, CreateChangeStream, WatchTarget, WatchedTable,
+};
+use super::ddl::index::{AlterIndex, AlterIndexAction, CreateIndex};
+use super::ddl::sequence::{AlterSequence, AlterSequenceAction, CreateSequence, SkipRange};
+use super::ddl::table::{
+    AlterTable, AlterTableAction, ColumnAlteration, ColumnDef, ColumnValue, ConstraintKind,
+    CreateTable, Interleave, KeyPart, RowDeletionPolicy, TableConstraint,
+};
+use super::ddl::view::{CreateView, SqlSecurity};
+use super::ddl::{AlterDatabase, DdlStatement, Drop, NullOr, OnDelete, OptimizerVersion, Options};
+use super::dml::delete::DeleteStatement;
+use super::dml::insert::{InsertConflict, InsertData, InsertStatement};
+use super::dml::update::{UpdateItem, UpdateStatement};
+use super::dml::{DmlStatement, ThenReturn, ValueExpression};
+use super::query::select::{
+    FromItem, JoinCondition, JoinKind, JoinOperation, SampleSize, Select, SelectAll,
+    SelectListItem, TableSampleMethod, TableSampleOperator, Target, UnnestOperator,
+};
+use super::query::{
+    HashJoinBuildSide, HashJoinExecution, JoinHints, JoinMethod, Limit, LockScannedRanges,
+    NullsOrder, OrderBy, QueryExpr, QueryKind, QueryStatement, Scope, SetOperator, SetOperatorKind,
+    StatementHints, TableHints,
+};
+
+/// Writes items separated by ', '.
+struct CommaSeparated<'a, T>(&'a [T]);
+
+impl<T: fmt::Display> fmt::Display for CommaSeparated<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, item) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            item.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a path with each segment as it was written in the source (including backticks), unlike
+/// the [`fmt::Display`] impl on [`Path`].
+struct SqlPath<'a, 'src>(&'a Path<'src>);
+
+impl fmt::Display for SqlPath<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, segment) in self.0.segments().iter().enumerate() {
+            if idx > 0 {
+                f.write_str(".")?;
+            }
+            segment.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes ' AS alias', if there is an alias.
+fn write_alias(f: &mut fmt::Formatter<'_>, alias: Option<&Ident<'_>>) -> fmt::Result {
+    match alias {
+        Some(alias) => write!(f, " AS {alias}"),
+        None => Ok(()),
+    }
+}
+
+impl fmt::Display for Statement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Query(query) => query.fmt(f),
+            Self::Ddl(ddl) => ddl.fmt(f),
+            Self::Dml(dml) => dml.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Ident<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the raw identifiers still have their leading '@' or backticks
+        match *self {
+            Self::QueryParam(raw) | Self::Quoted(raw) | Self::Unquoted(raw) => f.write_str(raw),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for AsAlias<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.item.fmt(f)?;
+        write_alias(f, self.alias.as_ref())
+    }
+}
+
+// ---------------- types ----------------
+
+impl fmt::Display for Type<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scalar { data_type, length } => {
+                f.write_str(data_type.as_str())?;
+                match length {
+                    Some(Length::Max) => f.write_str("(MAX)"),
+                    Some(Length::Fixed(len)) => write!(f, "({len})"),
+                    None => Ok(()),
+                }
+            }
+            Self::Array(element) => write!(f, "ARRAY<{element}>"),
+            Self::Struct(fields) => write!(f, "STRUCT<{}>", CommaSeparated(fields)),
+        }
+    }
+}
+
+impl fmt::Display for StructField<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(ref name) => write!(f, "{name} {}", self.ty),
+            None => self.ty.fmt(f),
+        }
+    }
+}
+
+// ---------------- expressions ----------------
+
+impl fmt::Display for Expression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(value) => value.fmt(f),
+            Self::Path(path) => SqlPath(path).fmt(f),
+            Self::FunctionCall(call) => call.fmt(f),
+            Self::SubQuery(query) => write!(f, "({query})"),
+            Self::Operation(operation) => operation.fmt(f),
+            Self::Cast { safe, cast } => {
+                let name = if *safe { "SAFE_CAST" } else { "CAST" };
+                write!(f, "{name}({} AS {})", cast.from, cast.to)
+            }
+            Self::Case(case) => case.fmt(f),
+            Self::Extract(extract) => extract.fmt(f),
+            Self::Interval(interval) => write!(f, "INTERVAL {} {}", interval.value, interval.part),
+            Self::Exists(query) => write!(f, "EXISTS ({query})"),
+            Self::ArraySubQuery(query) => write!(f, "ARRAY({query})"),
+        }
+    }
+}
+
+/// Writes the operand of a prefix or postfix operator, wrapping it in parentheses if it would
+/// otherwise merge with the operator (i.e a '-' followed by a numeric literal, or a path followed
+/// by a field access).
+fn write_operand(f: &mut fmt::Formatter<'_>, expr: &Expression<'_>) -> fmt::Result {
+    match expr {
+        Expression::Value(ValueExpr::Integer(_) | ValueExpr::Float(_))
+        | Expression::Path(_)
+        | Expression::Interval(_) => write!(f, "({expr})"),
+        _ => write!(f, "{expr}"),
+    }
+}
+
+impl fmt::Display for ValueExpr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("NULL"),
+            Self::Boolean(true) => f.write_str("TRUE"),
+            Self::Boolean(false) => f.write_str("FALSE"),
+            Self::Integer(int) => write!(f, "{int}"),
+            // the debug impl always includes a '.' or exponent, so it's parsed back as a float
+            Self::Float(float) => write!(f, "{float:?}"),
+            Self::String(lit) => lit.fmt(f),
+            Self::Typed { data_type, value } => write!(f, "{} {value}", data_type.as_str()),
+            Self::Array {
+                element_type,
+                elements,
+            } => {
+                if let Some(element_type) = element_type {
+                    write!(f, "ARRAY<{element_type}>")?;
+                }
+                write!(f, "[{}]", CommaSeparated(elements))
+            }
+            Self::Struct { fields, values } => {
+                f.write_str("STRUCT")?;
+                if let Some(fields) = fields {
+                    write!(f, "<{}>", CommaSeparated(fields))?;
+                }
+                write!(f, "({})", CommaSeparated(values))
+            }
+            Self::StructTuple(values) => write!(f, "({})", CommaSeparated(values)),
+        }
+    }
+}
+
+impl fmt::Display for FunctionCall<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.safe {
+            f.write_str("SAFE.")?;
+        }
+
+        write!(f, "{}({})", self.name, self.arguments)?;
+
+        match self.function_hint {
+            Some(FunctionHint::DisableInline(disable)) => {
+                write!(f, " @{{DISABLE_INLINE={}}}", bool_str(disable))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Arguments<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (arguments, options) = match self {
+            Self::Star => return f.write_str("*"),
+            Self::List {
+                arguments,
+                aggregate_options,
+            } => (arguments, aggregate_options.as_ref()),
+        };
+
+        if options.is_some_and(|options| options.distinct) {
+            f.write_str("DISTINCT ")?;
+        }
+
+        CommaSeparated(arguments).fmt(f)?;
+
+        let Some(AggregateOptions { nulls, having, .. }) = options else {
+            return Ok(());
+        };
+
+        match nulls {
+            Some(NullHandling::Ignore) => f.write_str(" IGNORE NULLS")?,
+            Some(NullHandling::Respect) => f.write_str(" RESPECT NULLS")?,
+            None => (),
+        }
+
+        match having.as_deref() {
+            Some(Having::Min(expr)) => write!(f, " HAVING MIN {expr}"),
+            Some(Having::Max(expr)) => write!(f, " HAVING MAX {expr}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for CaseExpr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CASE")?;
+
+        if let Some(ref operand) = self.operand {
+            write!(f, " {operand}")?;
+        }
+
+        for branch in self.branches.iter() {
+            write!(f, " WHEN {} THEN {}", branch.when, branch.then)?;
+        }
+
+        if let Some(ref else_expr) = self.else_expr {
+            write!(f, " ELSE {else_expr}")?;
+        }
+
+        f.write_str(" END")
+    }
+}
+
+impl fmt::Display for Extract<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EXTRACT({} FROM {}", self.part, self.expr)?;
+
+        if let Some(ref time_zone) = self.time_zone {
+            write!(f, " AT TIME ZONE {time_zone}")?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for Operation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Access(access) => access.fmt(f),
+            Self::Binary(binary) => binary.fmt(f),
+            Self::Unary(unary) => unary.fmt(f),
+            Self::Between(between) => between.fmt(f),
+            Self::In(in_op) => in_op.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for AccessOperation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field { lhs, field } => {
+                write_operand(f, lhs)?;
+                write!(f, ".{field}")
+            }
+            Self::Subscript { lhs, index } => {
+                write_operand(f, lhs)?;
+                write!(f, "[{index}]")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Index<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Offset(expr) => write!(f, "OFFSET({expr})"),
+            Self::SafeOffset(expr) => write!(f, "SAFE_OFFSET({expr})"),
+            Self::Ordinal(expr) => write!(f, "ORDINAL({expr})"),
+            Self::SafeOrdinal(expr) => write!(f, "SAFE_ORDINAL({expr})"),
+            Self::Bare(expr) => expr.fmt(f),
+        }
+    }
+}
+
+impl BinaryOperator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Concat => "||",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::LeftShift => "<<",
+            Self::RightShift => ">>",
+            Self::BitwiseAnd => "&",
+            Self::BitwiseXor => "^",
+            Self::BitwiseOr => "|",
+            Self::Eq => "=",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::Lte => "<=",
+            Self::Gte => ">=",
+            Self::NotEq => "!=",
+            Self::Like => "LIKE",
+            Self::NotLike => "NOT LIKE",
+            Self::LogicalAnd => "AND",
+            Self::LogicalOr => "OR",
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({} {} {})",
+            self.sides.lhs,
+            self.operator.as_str(),
+            self.sides.rhs
+        )
+    }
+}
+
+impl fmt::Display for UnaryOperation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.operator {
+            UnaryOperator::UnaryPlus => "+",
+            UnaryOperator::UnaryNeg => "-",
+            UnaryOperator::BitwiseNot => "~",
+            UnaryOperator::LogicalNot => "NOT ",
+            UnaryOperator::IsNull => return write!(f, "({} IS NULL)", self.expr),
+            UnaryOperator::IsNotNull => return write!(f, "({} IS NOT NULL)", self.expr),
+            UnaryOperator::IsTrue => return write!(f, "({} IS TRUE)", self.expr),
+            UnaryOperator::IsNotTrue => return write!(f, "({} IS NOT TRUE)", self.expr),
+            UnaryOperator::IsFalse => return write!(f, "({} IS FALSE)", self.expr),
+            UnaryOperator::IsNotFalse => return write!(f, "({} IS NOT FALSE)", self.expr),
+        };
+
+        write!(f, "({prefix}")?;
+        write_operand(f, &self.expr)?;
+        f.write_str(")")
+    }
+}
+
+fn not_str(negated: bool) -> &'static str {
+    if negated { "NOT " } else { "" }
+}
+
+impl fmt::Display for Between<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({} {}BETWEEN {} AND {})",
+            self.expr,
+            not_str(self.negated),
+            self.low,
+            self.high
+        )
+    }
+}
+
+impl fmt::Display for InOperation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({} {}IN ", self.expr, not_str(self.negated))?;
+
+        match self.rhs {
+            InRhs::List(ref list) => write!(f, "({}))", CommaSeparated(list)),
+            InRhs::SubQuery(ref query) => write!(f, "({query}))"),
+            InRhs::Unnest(ref expr) => write!(f, "UNNEST({expr}))"),
+        }
+    }
+}
+
+// ---------------- queries ----------------
+
+fn bool_str(value: bool) -> &'static str {
+    if value { "TRUE" } else { "FALSE" }
+}
+
+/// Hint values that aren't a plain word or number need to be quoted.
+fn hint_str(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+        value.to_owned()
+    } else if value.contains('\'') {
+        format!("\"{value}\"")
+    } else {
+        format!("'{value}'")
+    }
+}
+
+impl StatementHints<'_> {
+    fn entries(&self, dst: &mut Vec<String>) {
+        if let Some(value) = self.use_additional_parallelism {
+            dst.push(format!("USE_ADDITIONAL_PARALLELISM={}", bool_str(value)));
+        }
+        if let Some(value) = self.optimizer_version {
+            dst.push(format!("OPTIMIZER_VERSION={}", hint_str(value)));
+        }
+        if let Some(value) = self.optimizer_statistics_package {
+            dst.push(format!("OPTIMIZER_STATISTICS_PACKAGE={}", hint_str(value)));
+        }
+        if let Some(value) = self.allow_distributed_merge {
+            dst.push(format!("ALLOW_DISTRIBUTED_MERGE={}", bool_str(value)));
+        }
+        if let Some(value) = self.lock_scanned_ranges {
+            let value = match value {
+                LockScannedRanges::Exclusive => "EXCLUSIVE",
+                LockScannedRanges::Shared => "SHARED",
+            };
+            dst.push(format!("LOCK_SCANNED_RANGES={value}"));
+        }
+    }
+}
+
+impl TableHints<'_> {
+    fn entries(&self, dst: &mut Vec<String>) {
+        if let Some(value) = self.force_index {
+            dst.push(format!("FORCE_INDEX={}", hint_str(value)));
+        }
+        if let Some(value) = self.group_by_scan_optimization {
+            dst.push(format!("GROUPBY_SCAN_OPTIMIZATION={}", bool_str(value)));
+        }
+    }
+}
+
+impl JoinHints {
+    fn entries(&self, dst: &mut Vec<String>) {
+        if let Some(value) = self.force_join_order {
+            dst.push(format!("FORCE_JOIN_ORDER={}", bool_str(value)));
+        }
+        if let Some(value) = self.join_method {
+            let value = match value {
+                JoinMethod::HashJoin => "HASH_JOIN",
+                JoinMethod::ApplyJoin => "APPLY_JOIN",
+                JoinMethod::MergeJoin => "MERGE_JOIN",
+                JoinMethod::PushBroadcastHashJoin => "PUSH_BROADCAST_HASH_JOIN",
+            };
+            dst.push(format!("JOIN_METHOD={value}"));
+        }
+        if let Some(value) = self.hash_join_build_side {
+            let value = match value {
+                HashJoinBuildSide::BuildLeft => "BUILD_LEFT",
+                HashJoinBuildSide::BuildRight => "BUILD_RIGHT",
+            };
+            dst.push(format!("HASH_JOIN_BUILD_SIDE={value}"));
+        }
+        if let Some(value) = self.batch_mode {
+            dst.push(format!("BATCH_MODE={}", bool_str(value)));
+        }
+        if let Some(value) = self.hash_join_execution {
+            let value = match value {
+                HashJoinExecution::MultiPass => "MULTI_PASS",
+                HashJoinExecution::OnePass => "ONE_PASS",
+            };
+            dst.push(format!("HASH_JOIN_EXECUTION={value}"));
+        }
+    }
+}
+
+/// Writes '@{KEY=value, ...}' followed by 'suffix', or nothing if there are no hints.
+fn write_hints(f: &mut fmt::Formatter<'_>, entries: &[String], suffix: &str) -> fmt::Result {
+    if entries.is_empty() {
+        Ok(())
+    } else {
+        write!(f, "@{{{}}}{suffix}", CommaSeparated(entries))
+    }
+}
+
+impl fmt::Display for QueryStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut hints = Vec::new();
+        self.statement_hints.entries(&mut hints);
+        self.table_hints.entries(&mut hints);
+        self.join_hints.entries(&mut hints);
+
+        write_hints(f, &hints, " ")?;
+        self.query_expr.fmt(f)?;
+
+        if self.for_update {
+            f.write_str(" FOR UPDATE")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for QueryExpr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, cte) in self.cte.iter().enumerate() {
+            let prefix = if idx == 0 { "WITH " } else { ", " };
+            write!(f, "{prefix}{} AS ({})", cte.from, cte.to)?;
+        }
+
+        if !self.cte.is_empty() {
+            f.write_str(" ")?;
+        }
+
+        self.kind.fmt(f)?;
+
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY {}", CommaSeparated(&self.order_by))?;
+        }
+
+        if let Some(ref limit) = self.limit {
+            write!(f, " {limit}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for QueryKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Select(select) => select.fmt(f),
+            Self::SubQuery(query) => write!(f, "({query})"),
+            Self::SetOperation(set_op) => {
+                write!(f, "{} {} {}", set_op.lhs, set_op.operator, set_op.rhs)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("ALL"),
+            Self::Distinct => f.write_str("DISTINCT"),
+        }
+    }
+}
+
+impl fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            SetOperatorKind::Union => "UNION",
+            SetOperatorKind::Intersect => "INTERSECT",
+            SetOperatorKind::Except => "EXCEPT",
+        };
+
+        write!(f, "{kind} {}", self.scope)
+    }
+}
+
+impl fmt::Display for OrderBy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expr.fmt(f)?;
+
+        if !self.ascending {
+            f.write_str(" DESC")?;
+        }
+
+        match self.nulls {
+            Some(NullsOrder::First) => f.write_str(" NULLS FIRST"),
+            Some(NullsOrder::Last) => f.write_str(" NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Limit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LIMIT {}", self.count)?;
+
+        match self.offset {
+            Some(ref offset) => write!(f, " OFFSET {offset}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Select<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SELECT ")?;
+
+        if let Some(scope) = self.scope {
+            write!(f, "{scope} ")?;
+        }
+
+        match self.target {
+            Some(Target::Struct) => f.write_str("AS STRUCT ")?,
+            Some(Target::Value) => f.write_str("AS VALUE ")?,
+            None => (),
+        }
+
+        CommaSeparated(&self.select_list).fmt(f)?;
+
+        if let Some(ref from) = self.from {
+            write!(f, " FROM {from}")?;
+        }
+
+        if let Some(ref where_expr) = self.where_expr {
+            write!(f, " WHERE {where_expr}")?;
+        }
+
+        if !self.group_by.is_empty() {
+            write!(f, " GROUP BY {}", CommaSeparated(&self.group_by))?;
+        }
+
+        if let Some(ref having) = self.having {
+            write!(f, " HAVING {having}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SelectListItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SelectAll(select_all) => select_all.fmt(f),
+            Self::Expr(expr) => expr.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for SelectAll<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref qualifier) = self.qualifier {
+            write!(f, "{qualifier}.")?;
+        }
+
+        f.write_str("*")?;
+
+        if !self.except.is_empty() {
+            write!(f, " EXCEPT ({})", CommaSeparated(&self.except))?;
+        }
+
+        if !self.replace.is_empty() {
+            write!(f, " REPLACE ({})", CommaSeparated(&self.replace))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FromItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Table {
+                name,
+                hints,
+                alias,
+                sample,
+            } => {
+                SqlPath(name).fmt(f)?;
+
+                let mut entries = Vec::new();
+                hints.entries(&mut entries);
+                write_hints(f, &entries, "")?;
+
+                write_alias(f, alias.as_ref())?;
+
+                match sample {
+                    Some(sample) => write!(f, " {sample}"),
+                    None => Ok(()),
+                }
+            }
+            Self::Subquery(AsAlias { item, alias }) => {
+                write!(f, "({item})")?;
+                write_alias(f, alias.as_ref())
+            }
+            Self::Join(join) => join.fmt(f),
+            Self::Unnest(unnest) => unnest.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for JoinOperation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.lhs.fmt(f)?;
+
+        let kind = match self.kind {
+            JoinKind::Comma => ",",
+            JoinKind::Inner => " INNER JOIN",
+            JoinKind::Cross => " CROSS JOIN",
+            JoinKind::Full => " FULL JOIN",
+            JoinKind::Left => " LEFT JOIN",
+            JoinKind::Right => " RIGHT JOIN",
+        };
+        f.write_str(kind)?;
+
+        let mut entries = Vec::new();
+        self.hints.entries(&mut entries);
+        write_hints(f, &entries, "")?;
+
+        // joins are left associative, so a nested join on the right needs to be parenthesized
+        match self.rhs {
+            FromItem::Join(ref rhs) => write!(f, " ({rhs})")?,
+            ref rhs => write!(f, " {rhs}")?,
+        }
+
+        match self.condition {
+            Some(JoinCondition::On(ref expr)) => write!(f, " ON {expr}"),
+            Some(JoinCondition::Using(ref columns)) => {
+                write!(f, " USING ({})", CommaSeparated(columns))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for UnnestOperator<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UNNEST({})", self.expr)?;
+        write_alias(f, self.alias.as_ref())?;
+
+        match self.with_offset {
+            Some(ref with_offset) => {
+                f.write_str(" WITH OFFSET")?;
+                write_alias(f, with_offset.alias.as_ref())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for TableSampleOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let method = match self.method {
+            TableSampleMethod::Bernoulli => "BERNOULLI",
+            TableSampleMethod::Reservoir => "RESERVOIR",
+        };
+
+        match self.sample_size {
+            SampleSize::Rows(rows) => write!(f, "TABLESAMPLE {method} ({rows} ROWS)"),
+            SampleSize::Percent(percent) => write!(f, "TABLESAMPLE {method} ({percent} PERCENT)"),
+        }
+    }
+}
+
+// ---------------- DML ----------------
+
+impl fmt::Display for DmlStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Insert(insert) => insert.fmt(f),
+            Self::Delete(delete) => delete.fmt(f),
+            Self::Update(update) => update.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for ValueExpression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("DEFAULT"),
+            Self::Expression(expr) => expr.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for ThenReturn<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("THEN RETURN ")?;
+
+        if let Some(ref with_action) = self.with_action {
+            f.write_str("WITH ACTION")?;
+            write_alias(f, with_action.alias.as_ref())?;
+            f.write_str(" ")?;
+        }
+
+        CommaSeparated(&self.items).fmt(f)
+    }
+}
+
+fn write_returning(f: &mut fmt::Formatter<'_>, returning: Option<&ThenReturn<'_>>) -> fmt::Result {
+    match returning {
+        Some(returning) => write!(f, " {returning}"),
+        None => Ok(()),
+    }
+}
+
+/// A parenthesized row of 'VALUES'.
+struct Row<'a, 'src>(&'a [ValueExpression<'src>]);
+
+impl fmt::Display for Row<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({})", CommaSeparated(self.0))
+    }
+}
+
+impl fmt::Display for InsertStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("INSERT ")?;
+
+        match self.conflict {
+            Some(InsertConflict::Ignore) => f.write_str("OR IGNORE ")?,
+            Some(InsertConflict::Update) => f.write_str("OR UPDATE ")?,
+            None => (),
+        }
+
+        write!(f, "INTO {}", SqlPath(&self.table))?;
+
+        if !self.columns.is_empty() {
+            write!(f, " ({})", CommaSeparated(&self.columns))?;
+        }
+
+        match self.data {
+            InsertData::Values(ref rows) => {
+                let rows = rows.iter().map(|row| Row(row)).collect::<Vec<_>>();
+                write!(f, " VALUES {}", CommaSeparated(&rows))?;
+            }
+            InsertData::Query(ref query) => write!(f, " {query}")?,
+        }
+
+        write_returning(f, self.returning.as_ref())
+    }
+}
+
+impl fmt::Display for UpdateItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", SqlPath(&self.column), self.value)
+    }
+}
+
+impl fmt::Display for UpdateStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UPDATE {}", SqlPath(&self.table))?;
+        write_alias(f, self.alias.as_ref())?;
+
+        write!(
+            f,
+            " SET {} WHERE {}",
+            CommaSeparated(&self.assignments),
+            self.where_expr
+        )?;
+
+        write_returning(f, self.returning.as_ref())
+    }
+}
+
+impl fmt::Display for DeleteStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DELETE FROM {}", SqlPath(&self.table))?;
+        write_alias(f, self.alias.as_ref())?;
+        write!(f, " WHERE {}", self.where_expr)?;
+        write_returning(f, self.returning.as_ref())
+    }
+}
+
+// ---------------- DDL ----------------
+
+impl fmt::Display for DdlStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateDatabase { database_id } => write!(f, "CREATE DATABASE {database_id}"),
+            Self::AlterDatabase(alter) => alter.fmt(f),
+            Self::CreateTable(create) => create.fmt(f),
+            Self::AlterTable(alter) => alter.fmt(f),
+            Self::DropTable(drop) => write!(f, "DROP TABLE {drop}"),
+            Self::CreateIndex(create) => create.fmt(f),
+            Self::AlterIndex(alter) => alter.fmt(f),
+            Self::DropIndex(drop) => write!(f, "DROP INDEX {drop}"),
+            Self::CreateChangeStream(create) => create.fmt(f),
+            Self::AlterChangeStream(alter) => alter.fmt(f),
+            Self::DropChangeStream(drop) => write!(f, "DROP CHANGE STREAM {drop}"),
+            Self::CreateView(create) => create.fmt(f),
+            Self::DropView(drop) => write!(f, "DROP VIEW {drop}"),
+            Self::CreateSequence(create) => create.fmt(f),
+            Self::AlterSequence(alter) => alter.fmt(f),
+            Self::DropSequence(drop) => write!(f, "DROP SEQUENCE {drop}"),
+        }
+    }
+}
+
+impl fmt::Display for Drop<'_> {
+    /// Writes everything after the object kind, i.e '[IF EXISTS] name'.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.if_exists {
+            f.write_str("IF EXISTS ")?;
+        }
+
+        SqlPath(&self.name).fmt(f)
+    }
+}
+
+fn if_not_exists_str(if_not_exists: bool) -> &'static str {
+    if if_not_exists { " IF NOT EXISTS" } else { "" }
+}
+
+/// Writes ' OPTIONS (...)', if there are any options.
+fn write_options(f: &mut fmt::Formatter<'_>, options: &Options<'_>) -> fmt::Result {
+    if options.is_empty() {
+        Ok(())
+    } else {
+        write!(f, " {options}")
+    }
+}
+
+impl fmt::Display for Options<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OPTIONS (")?;
+
+        for (idx, (key, value)) in self.entries.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{key} = {value}")?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for OnDelete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cascade => f.write_str("ON DELETE CASCADE"),
+            Self::NoAction => f.write_str("ON DELETE NO ACTION"),
+        }
+    }
+}
+
+fn write_on_delete(f: &mut fmt::Formatter<'_>, on_delete: Option<OnDelete>) -> fmt::Result {
+    match on_delete {
+        Some(on_delete) => write!(f, " {on_delete}"),
+        None => Ok(()),
+    }
+}
+
+impl fmt::Display for AlterDatabase<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn null_or<T: fmt::Display>(value: NullOr<T>) -> String {
+            match value {
+                NullOr::Option(value) => value.to_string(),
+                NullOr::Null => "NULL".to_owned(),
+            }
+        }
+
+        let options = &self.options;
+        let mut entries = Vec::new();
+
+        if let Some(value) = options.default_leader {
+            entries.push(format!("default_leader = {}", null_or(value)));
+        }
+        if let Some(value) = options.optimizer_version {
+            let value = match value {
+                NullOr::Option(OptimizerVersion::Version(version)) => NullOr::Option(version),
+                NullOr::Null => NullOr::Null,
+            };
+            entries.push(format!("optimizer_version = {}", null_or(value)));
+        }
+        if let Some(value) = options.optimizer_statistics_package {
+            entries.push(format!("optimizer_statistics_package = {}", null_or(value)));
+        }
+        if let Some(value) = options.version_retention_period {
+            entries.push(format!("version_retention_period = {}", null_or(value)));
+        }
+        if let Some(value) = options.enable_key_visualizer {
+            let value = match value {
+                NullOr::Option(enabled) => NullOr::Option(bool_str(enabled)),
+                NullOr::Null => NullOr::Null,
+            };
+            entries.push(format!("enable_key_visualizer = {}", null_or(value)));
+        }
+
+        write!(
+            f,
+            "ALTER DATABASE {} SET OPTIONS ({})",
+            self.database_id,
+            CommaSeparated(&entries)
+        )
+    }
+}
+
+impl fmt::Display for CreateTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CREATE TABLE{} {} (",
+            if_not_exists_str(self.if_not_exists),
+            SqlPath(&self.name)
+        )?;
+
+        CommaSeparated(&self.columns).fmt(f)?;
+
+        if !self.columns.is_empty() && !self.constraints.is_empty() {
+            f.write_str(", ")?;
+        }
+
+        CommaSeparated(&self.constraints).fmt(f)?;
+
+        let primary_key = CommaSeparated(&self.primary_key);
+        write!(f, ") PRIMARY KEY ({primary_key})")?;
+
+        if let Some(ref interleave) = self.interleave {
+            write!(f, ", {interleave}")?;
+        }
+
+        if let Some(ref policy) = self.row_deletion_policy {
+            write!(f, ", {policy}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ColumnDef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.ty)?;
+
+        if self.not_null {
+            f.write_str(" NOT NULL")?;
+        }
+
+        match self.value {
+            Some(ColumnValue::Default(ref expr)) => write!(f, " DEFAULT ({expr})")?,
+            Some(ColumnValue::Generated { ref expr, stored }) => {
+                write!(f, " AS ({expr})")?;
+                if stored {
+                    f.write_str(" STORED")?;
+                }
+            }
+            None => (),
+        }
+
+        if self.hidden {
+            f.write_str(" HIDDEN")?;
+        }
+
+        write_options(f, &self.options)
+    }
+}
+
+impl fmt::Display for KeyPart<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.column.fmt(f)?;
+
+        if !self.ascending {
+            f.write_str(" DESC")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Interleave<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "INTERLEAVE IN PARENT {}", SqlPath(&self.parent))?;
+        write_on_delete(f, self.on_delete)
+    }
+}
+
+impl fmt::Display for RowDeletionPolicy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ROW DELETION POLICY (OLDER_THAN({}, INTERVAL {} DAY))",
+            self.column, self.num_days
+        )
+    }
+}
+
+impl fmt::Display for TableConstraint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref name) = self.name {
+            write!(f, "CONSTRAINT {name} ")?;
+        }
+
+        match self.kind {
+            ConstraintKind::Check(ref expr) => write!(f, "CHECK ({expr})"),
+            ConstraintKind::ForeignKey(ref foreign_key) => {
+                write!(
+                    f,
+                    "FOREIGN KEY ({}) REFERENCES {} ({})",
+                    CommaSeparated(&foreign_key.columns),
+                    SqlPath(&foreign_key.referenced_table),
+                    CommaSeparated(&foreign_key.referenced_columns)
+                )?;
+                write_on_delete(f, foreign_key.on_delete)
+            }
+        }
+    }
+}
+
+impl fmt::Display for AlterTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER TABLE {} ", SqlPath(&self.name))?;
+
+        match self.action {
+            AlterTableAction::AddColumn {
+                if_not_exists,
+                ref column,
+            } => write!(f, "ADD COLUMN{} {column}", if_not_exists_str(if_not_exists)),
+            AlterTableAction::DropColumn(ref column) => write!(f, "DROP COLUMN {column}"),
+            AlterTableAction::AlterColumn {
+                ref column,
+                ref alteration,
+            } => write!(f, "ALTER COLUMN {column} {alteration}"),
+            AlterTableAction::AddConstraint(ref constraint) => write!(f, "ADD {constraint}"),
+            AlterTableAction::DropConstraint(ref name) => write!(f, "DROP CONSTRAINT {name}"),
+            AlterTableAction::SetOnDelete(on_delete) => write!(f, "SET {on_delete}"),
+            AlterTableAction::AddRowDeletionPolicy(ref policy) => write!(f, "ADD {policy}"),
+            AlterTableAction::ReplaceRowDeletionPolicy(ref policy) => {
+                write!(f, "REPLACE {policy}")
+            }
+            AlterTableAction::DropRowDeletionPolicy => f.write_str("DROP ROW DELETION POLICY"),
+            AlterTableAction::RenameTo(ref name) => write!(f, "RENAME TO {}", SqlPath(name)),
+        }
+    }
+}
+
+impl fmt::Display for ColumnAlteration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Redefine {
+                ty,
+                not_null,
+                default,
+            } => {
+                ty.fmt(f)?;
+                if *not_null {
+                    f.write_str(" NOT NULL")?;
+                }
+                match default {
+                    Some(default) => write!(f, " DEFAULT ({default})"),
+                    None => Ok(()),
+                }
+            }
+            Self::SetOptions(options) => write!(f, "SET {options}"),
+            Self::SetDefault(expr) => write!(f, "SET DEFAULT ({expr})"),
+            Self::DropDefault => f.write_str("DROP DEFAULT"),
+        }
+    }
+}
+
+impl fmt::Display for CreateIndex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE ")?;
+
+        if self.unique {
+            f.write_str("UNIQUE ")?;
+        }
+
+        if self.null_filtered {
+            f.write_str("NULL_FILTERED ")?;
+        }
+
+        write!(
+            f,
+            "INDEX{} {} ON {} ({})",
+            if_not_exists_str(self.if_not_exists),
+            SqlPath(&self.name),
+            SqlPath(&self.table),
+            CommaSeparated(&self.keys)
+        )?;
+
+        if !self.storing.is_empty() {
+            write!(f, " STORING ({})", CommaSeparated(&self.storing))?;
+        }
+
+        if let Some(ref filter) = self.filter {
+            write!(f, " WHERE {filter}")?;
+        }
+
+        match self.interleave_in {
+            Some(ref parent) => write!(f, ", INTERLEAVE IN {}", SqlPath(parent)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for AlterIndex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (action, column) = match self.action {
+            AlterIndexAction::AddStoredColumn(column) => ("ADD", column),
+            AlterIndexAction::DropStoredColumn(column) => ("DROP", column),
+        };
+
+        write!(
+            f,
+            "ALTER INDEX {} {action} STORED COLUMN {column}",
+            SqlPath(&self.name)
+        )
+    }
+}
+
+impl fmt::Display for WatchTarget<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("ALL"),
+            Self::Tables(tables) => CommaSeparated(tables).fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for WatchedTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        SqlPath(&self.table).fmt(f)?;
+
+        match self.columns {
+            Some(ref columns) => write!(f, " ({})", CommaSeparated(columns)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for CreateChangeStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE CHANGE STREAM {}", self.name)?;
+
+        if let Some(ref watch) = self.watch {
+            write!(f, " FOR {watch}")?;
+        }
+
+        write_options(f, &self.options)
+    }
+}
+
+impl fmt::Display for AlterChangeStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER CHANGE STREAM {} ", self.name)?;
+
+        match self.action {
+            AlterChangeStreamAction::SetFor(ref watch) => write!(f, "SET FOR {watch}"),
+            AlterChangeStreamAction::DropForAll => f.write_str("DROP FOR ALL"),
+            AlterChangeStreamAction::SetOptions(ref options) => write!(f, "SET {options}"),
+        }
+    }
+}
+
+impl fmt::Display for CreateView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE ")?;
+
+        if self.or_replace {
+            f.write_str("OR REPLACE ")?;
+        }
+
+        let security = match self.security {
+            SqlSecurity::Invoker => "INVOKER",
+            SqlSecurity::Definer => "DEFINER",
+        };
+
+        write!(
+            f,
+            "VIEW {} SQL SECURITY {security} AS {}",
+            SqlPath(&self.name),
+            self.query
+        )
+    }
+}
+
+impl fmt::Display for SkipRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SKIP RANGE {}, {}", self.min, self.max)
+    }
+}
+
+impl fmt::Display for CreateSequence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CREATE SEQUENCE{} {}",
+            if_not_exists_str(self.if_not_exists),
+            SqlPath(&self.name)
+        )?;
+
+        if self.bit_reversed_positive {
+            f.write_str(" BIT_REVERSED_POSITIVE")?;
+        }
+
+        if let Some(skip_range) = self.skip_range {
+            write!(f, " {skip_range}")?;
+        }
+
+        if let Some(start) = self.start_counter_with {
+            write!(f, " START COUNTER WITH {start}")?;
+        }
+
+        write_options(f, &self.options)
+    }
+}
+
+impl fmt::Display for AlterSequence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER SEQUENCE {} ", SqlPath(&self.name))?;
+
+        match self.action {
+            AlterSequenceAction::SetOptions(ref options) => write!(f, "SET {options}"),
+            AlterSequenceAction::SkipRange(skip_range) => skip_range.fmt(f),
+            AlterSequenceAction::NoSkipRange => f.write_str("NO SKIP RANGE"),
+            AlterSequenceAction::RestartCounterWith(counter) => {
+                write!(f, "RESTART COUNTER WITH {counter}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_statements;
+
+    /// Statements that should survive a parse -> print -> parse round trip.
+    const ROUND_TRIP: &[&str] = &[
+        // queries
+        "SELECT 1",
+        "SELECT .5, 1.5e3, -2, 0x1F, -9223372036854775808, NULL, TRUE, 'a', b'bytes', r'''raw'''",
+        "SELECT a, b AS c, `select`.`from` FROM t WHERE a = @param",
+        "SELECT DISTINCT AS STRUCT a, b FROM t",
+        "SELECT * EXCEPT (a) REPLACE (b + 1 AS b) FROM t",
+        "SELECT t.* FROM t",
+        "SELECT a FROM t ORDER BY a DESC NULLS LAST, b NULLS FIRST LIMIT 10 OFFSET @skip",
+        "SELECT a FROM t WHERE id = 1 FOR UPDATE",
+        "@{USE_ADDITIONAL_PARALLELISM=TRUE, OPTIMIZER_VERSION=5, JOIN_METHOD=HASH_JOIN} SELECT 1",
+        "WITH a AS (SELECT 1 AS x), b AS (SELECT 2 AS y) SELECT x, y FROM a, b",
+        "(SELECT 1) UNION ALL (SELECT 2) EXCEPT DISTINCT SELECT 3 ORDER BY 1",
+        "SELECT a FROM t@{FORCE_INDEX=t_by_a} AS x TABLESAMPLE RESERVOIR (10 ROWS)",
+        "SELECT a FROM t TABLESAMPLE BERNOULLI (0.5 PERCENT)",
+        "SELECT s.Name FROM Singers s LEFT OUTER JOIN@{JOIN_METHOD=APPLY_JOIN} Albums a USING \
+         (SingerId) CROSS JOIN UNNEST(s.Tags) AS tag WITH OFFSET AS idx",
+        "SELECT * FROM a JOIN (b FULL JOIN c ON b.x = c.x) ON a.x = b.x RIGHT JOIN d ON TRUE",
+        "SELECT * FROM (SELECT a FROM t) AS sub, UNNEST([1, 2, 3])",
+        "SELECT COUNT(*), COUNT(DISTINCT a), ARRAY_AGG(b IGNORE NULLS HAVING MAX c) FROM t GROUP \
+         BY d HAVING COUNT(*) > 1",
+        "SELECT SAFE.NET.HOST(url), IF(a, 1, 2), f() @{DISABLE_INLINE=TRUE} FROM t",
+        "SELECT 1 + 2 * 3 - -4, (1 + 2) * 3, a || b, 1 << 2 & 3 ^ 4 | 5, ~a, -a, +a",
+        "SELECT NOT a AND b OR c, a IS NOT NULL, a IS TRUE, a IS NOT FALSE",
+        "SELECT a NOT BETWEEN 1 AND 2, a IN (1, 2), a NOT IN (SELECT b FROM t), a IN UNNEST(@arr)",
+        "SELECT a LIKE 'b%', a NOT LIKE 'c%', a != b, a <> b, a <= b, a >= b",
+        "SELECT CAST(a AS STRING(MAX)), SAFE_CAST(a AS ARRAY<STRUCT<x INT64, BYTES(10)>>)",
+        "SELECT CASE a WHEN 1 THEN 'one' ELSE 'many' END, CASE WHEN a THEN 1 END",
+        "SELECT EXTRACT(DATE FROM ts AT TIME ZONE 'UTC'), TIMESTAMP_ADD(ts, INTERVAL -1 DAY)",
+        "SELECT DATE '2024-01-01', JSON '{}', EXISTS (SELECT 1), ARRAY(SELECT 1)",
+        "SELECT ARRAY<INT64>[1, 2], [], STRUCT<a INT64>(1), STRUCT(1 AS a, 'b'), (1, 2)",
+        "SELECT arr[OFFSET(0)], arr[SAFE_ORDINAL(1)].name, json['key'], (a.b).c, (1).x",
+        "SELECT (SELECT AS STRUCT 1 AS a).a, -(1), -(-1), - -a",
+        // DML
+        "INSERT OR UPDATE INTO Singers (SingerId, Name) VALUES (1, 'a'), (2, DEFAULT) THEN RETURN \
+         WITH ACTION AS act SingerId",
+        "INSERT Singers (SELECT * FROM OtherSingers)",
+        "INSERT OR IGNORE Singers (SingerId) SELECT 1 THEN RETURN *",
+        "UPDATE Singers AS s SET s.Name = 'b', Active = DEFAULT WHERE s.SingerId = 1",
+        "DELETE Singers WHERE TRUE THEN RETURN SingerId",
+        // DDL
+        "CREATE DATABASE my-database",
+        "ALTER DATABASE `my-db` SET OPTIONS (optimizer_version = 5, default_leader = NULL, \
+         enable_key_visualizer = TRUE, version_retention_period = '7d')",
+        "CREATE TABLE IF NOT EXISTS Albums (
+            SingerId INT64 NOT NULL,
+            AlbumId INT64 NOT NULL,
+            Title STRING(MAX) OPTIONS (description = 'a; description'),
+            Tags ARRAY<STRING(64)>,
+            Upper STRING(MAX) AS (UPPER(Title)) STORED,
+            Rating FLOAT64 DEFAULT (0.0) HIDDEN,
+            CONSTRAINT FK_Title FOREIGN KEY (Title) REFERENCES Titles (Name) ON DELETE CASCADE,
+            CHECK (Rating >= 0),
+        ) PRIMARY KEY (SingerId, AlbumId DESC),
+          INTERLEAVE IN PARENT Singers ON DELETE NO ACTION,
+          ROW DELETION POLICY (OLDER_THAN(Created, INTERVAL 30 DAY))",
+        "CREATE TABLE Empty () PRIMARY KEY ()",
+        "ALTER TABLE Albums ADD COLUMN IF NOT EXISTS Label STRING(MAX) NOT NULL",
+        "ALTER TABLE Albums ALTER COLUMN Label STRING(1024) DEFAULT ('none')",
+        "ALTER TABLE Albums ALTER COLUMN Created SET OPTIONS (allow_commit_timestamp = NULL)",
+        "ALTER TABLE Albums ALTER COLUMN Rating SET DEFAULT (1.0)",
+        "ALTER TABLE Albums ALTER COLUMN Rating DROP DEFAULT",
+        "ALTER TABLE Albums ADD CONSTRAINT Positive CHECK (Rating > 0)",
+        "ALTER TABLE Albums DROP CONSTRAINT Positive",
+        "ALTER TABLE Albums SET ON DELETE CASCADE",
+        "ALTER TABLE Albums REPLACE ROW DELETION POLICY (OLDER_THAN(Created, INTERVAL 1 DAY))",
+        "ALTER TABLE Albums DROP ROW DELETION POLICY",
+        "ALTER TABLE Albums RENAME TO Records",
+        "DROP TABLE IF EXISTS Albums",
+        "CREATE UNIQUE NULL_FILTERED INDEX IF NOT EXISTS AlbumsByTitle ON Albums (SingerId, Title \
+         DESC) STORING (Rating), INTERLEAVE IN Singers",
+        "CREATE INDEX SingersByNickname ON Singers (Nickname) WHERE Nickname IS NOT NULL",
+        "CREATE NULL_FILTERED INDEX AlbumsByRating ON Albums (SingerId, Rating) STORING (Title) \
+         WHERE Rating IS NOT NULL AND Title IS NOT NULL, INTERLEAVE IN Singers",
+        "ALTER INDEX AlbumsByTitle ADD STORED COLUMN Tags",
+        "DROP INDEX AlbumsByTitle",
+        "CREATE CHANGE STREAM Everything FOR ALL",
+        "CREATE CHANGE STREAM Partial FOR Singers, Albums (Title), Songs () OPTIONS \
+         (retention_period = '36h')",
+        "ALTER CHANGE STREAM Partial SET OPTIONS (value_capture_type = 'NEW_ROW')",
+        "ALTER CHANGE STREAM Partial DROP FOR ALL",
+        "DROP CHANGE STREAM Partial",
+        "CREATE OR REPLACE VIEW Names SQL SECURITY INVOKER AS SELECT Name FROM Singers",
+        "DROP VIEW Names",
+        "CREATE SEQUENCE IF NOT EXISTS Ids BIT_REVERSED_POSITIVE SKIP RANGE 1, 1000 START COUNTER \
+         WITH 10 OPTIONS (sequence_kind = 'bit_reversed_positive')",
+        "ALTER SEQUENCE Ids SET OPTIONS (start_with_counter = 5)",
+        "ALTER SEQUENCE Ids NO SKIP RANGE",
+        "ALTER SEQUENCE Ids RESTART COUNTER WITH 100",
+        "DROP SEQUENCE IF EXISTS Ids",
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        for sql in ROUND_TRIP {
+            let parsed = Statement::parse(sql)
+                .unwrap_or_else(|error| panic!("failed to parse '{sql}': {error}"));
+
+            let printed = parsed.to_string();
+            let reparsed = Statement::parse(&printed)
+                .unwrap_or_else(|error| panic!("failed to reparse '{printed}': {error}"));
+
+            assert_eq!(parsed, reparsed, "'{sql}' printed as '{printed}'");
+            assert_eq!(
+                printed,
+                reparsed.to_string(),
+                "printing '{sql}' isn't stable"
+            );
+        }
+    }
+
+    #[test]
+    fn test_printed_sql() {
+        let cases = [
+            (
+                "select a from t order by a nulls last",
+                "SELECT a FROM t ORDER BY a NULLS LAST",
+            ),
+            ("SELECT 1 + 2 * 3", "SELECT (1 + (2 * 3))"),
+            ("SELECT .5", "SELECT 0.5"),
+            ("SELECT `t`.a FROM `t`", "SELECT `t`.a FROM `t`"),
+            (
+                "SELECT a FROM t WHERE b IS NULL FOR UPDATE",
+                "SELECT a FROM t WHERE (b IS NULL) FOR UPDATE",
+            ),
+            (
+                "CREATE SEQUENCE s OPTIONS (sequence_kind='bit_reversed_positive')",
+                "CREATE SEQUENCE s OPTIONS (sequence_kind = 'bit_reversed_positive')",
+            ),
+        ];
+
+        for (sql, expected) in cases {
+            let parsed = Statement::parse(sql).expect("should parse");
+            assert_eq!(parsed.to_string(), expected);
+        }
+
+        // statements keep their order when printed one after the other
+        let statements = parse_statements("SELECT 1; DROP TABLE t").expect("should parse");
+        let printed = statements
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        assert_eq!(printed, "SELECT 1; DROP TABLE t");
+    }
+}
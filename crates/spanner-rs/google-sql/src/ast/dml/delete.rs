@@ -1,3 +1,43 @@
+use super::ThenReturn;
+use crate::Error;
+use crate::ast::ParseTokens;
+use crate::ast::common::AsAlias;
+use crate::ast::common::expression::Expression;
+use crate::ast::common::ident::{Ident, Path};
+use crate::ast::reserved::Keyword;
+use crate::tokens::TokenizerKind;
+
+/// 'DELETE [FROM] table [[AS] alias] WHERE condition [THEN RETURN ...]'
+#[derive(Debug, Clone, PartialEq)]
 pub struct DeleteStatement<'src> {
-    _priv: &'src (),
+    pub table: Path<'src>,
+    pub alias: Option<Ident<'src>>,
+    pub where_expr: Expression<'src>,
+    pub returning: Option<ThenReturn<'src>>,
+}
+
+impl<'src> ParseTokens<'src> for DeleteStatement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_word("DELETE")?;
+        tokens.next_if_kw(Keyword::From)?;
+
+        let table = Path::parse_tokens(tokens)?;
+        let AsAlias { alias, .. } = AsAlias::parse_alias((), tokens)?;
+
+        // spanner requires a 'WHERE' clause, even if it's just 'WHERE TRUE'
+        tokens.expect_kw(Keyword::Where)?;
+        let where_expr = Expression::parse_tokens(tokens)?;
+
+        let returning = ThenReturn::parse_optional_clause(tokens)?;
+
+        Ok(Self {
+            table,
+            alias,
+            where_expr,
+            returning,
+        })
+    }
 }
@@ -1,49 +1,96 @@
-use crate::ast::ParseTokens;
-use crate::ast::common::AsAlias;
-use crate::ast::common::expression::Expression;
-use crate::ast::common::ident::Ident;
-use crate::ast::query::select::Select;
+use super::{ThenReturn, ValueExpression};
+use crate::Error;
+use crate::ast::common::ident::{Ident, Path};
+use crate::ast::query::QueryExpr;
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, UnexpectedToken, parse_comma_separated, parse_parens};
+use crate::tokens::TokenizerKind;
 
+/// 'INSERT [OR IGNORE | OR UPDATE] [INTO] table [(columns)] { VALUES (...), ... | query }
+/// [THEN RETURN ...]'
+#[derive(Debug, Clone, PartialEq)]
 pub struct InsertStatement<'src> {
-    table: AsAlias<'src, Ident<'src>>,
-    columns: Vec<Ident<'src>>,
-    data: InsertData<'src>,
-    return_clause: Vec<ReturnClause<'src>>,
+    pub conflict: Option<InsertConflict>,
+    pub table: Path<'src>,
+    pub columns: Vec<Ident<'src>>,
+    pub data: InsertData<'src>,
+    pub returning: Option<ThenReturn<'src>>,
 }
 
+/// How to handle rows that already exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertConflict {
+    /// 'INSERT OR IGNORE'
+    Ignore,
+    /// 'INSERT OR UPDATE'
+    Update,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum InsertData<'src> {
     Values(Vec<Vec<ValueExpression<'src>>>),
-    Select(Select<'src>),
+    Query(QueryExpr<'src>),
 }
 
-pub enum ValueExpression<'src> {
-    Default,
-    Expression(Expression<'src>),
-}
+impl<'src> ParseTokens<'src> for InsertStatement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_word("INSERT")?;
 
-pub enum ReturnClause<'src> {
-    SelectAll(SelectAll<'src>),
-    Expr(AsAlias<'src, Expression<'src>>),
-}
+        let conflict = if tokens.next_if_kw(Keyword::Or)? {
+            if tokens.next_if_kw(Keyword::Ignore)? {
+                Some(InsertConflict::Ignore)
+            } else {
+                tokens.expect_word("UPDATE")?;
+                Some(InsertConflict::Update)
+            }
+        } else {
+            None
+        };
 
-pub struct SelectAll<'src> {
-    table_name: Option<Ident<'src>>,
-    except: Vec<Ident<'src>>,
-    replace: Vec<(Expression<'src>, Ident<'src>)>,
-}
+        tokens.next_if_kw(Keyword::Into)?;
 
-impl<'src> ParseTokens<'src> for ValueExpression<'src> {
-    fn parse_tokens<T>(_tokens: &mut T) -> Result<Self, crate::Error<'src>>
-    where
-        T: crate::tokens::TokenizerKind<'src>,
-    {
-        todo!()
-    }
+        let table = Path::parse_tokens(tokens)?;
 
-    fn parse_optional<T>(_tokens: &mut T) -> Result<Option<Self>, crate::Error<'src>>
-    where
-        T: crate::tokens::TokenizerKind<'src>,
-    {
-        todo!()
+        // a parenthesized query would also look like a column list, so check for that first.
+        let is_column_list = tokens
+            .peek()?
+            .is_some_and(|(_, token)| token.as_parens().is_some())
+            && !crate::ast::query::is_query_start(tokens)?;
+
+        let columns = if is_column_list {
+            parse_parens(tokens, "a column list", |inner| {
+                parse_comma_separated(inner, Ident::parse_tokens)
+            })?
+        } else {
+            Vec::new()
+        };
+
+        let data = if tokens.next_if_word("VALUES")? {
+            let rows = parse_comma_separated(tokens, |tokens| {
+                parse_parens(tokens, "a parenthesized row", |inner| {
+                    parse_comma_separated(inner, ValueExpression::parse_tokens)
+                })
+            })?;
+
+            InsertData::Values(rows)
+        } else if crate::ast::query::is_query_start(tokens)? {
+            InsertData::Query(QueryExpr::parse_tokens(tokens)?)
+        } else {
+            let (span, token) = tokens.next_or_expected("'VALUES' or a query")?;
+            return Err(UnexpectedToken::new_expected(span, token, "'VALUES' or a query").into());
+        };
+
+        let returning = ThenReturn::parse_optional_clause(tokens)?;
+
+        Ok(Self {
+            conflict,
+            table,
+            columns,
+            data,
+            returning,
+        })
     }
 }
@@ -1,9 +1,175 @@
+use super::common::AsAlias;
+use super::common::expression::Expression;
+use super::common::ident::Ident;
+use super::query::select::SelectListItem;
+use super::reserved::Keyword;
+use super::{ParseTokens, UnexpectedToken, parse_comma_separated};
+use crate::Error;
+use crate::non_empty_vec::NonEmptyVec;
+use crate::tokens::TokenizerKind;
+
 pub mod delete;
 pub mod insert;
 pub mod update;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum DmlStatement<'src> {
     Insert(insert::InsertStatement<'src>),
     Delete(delete::DeleteStatement<'src>),
     Update(update::UpdateStatement<'src>),
 }
+
+impl<'src> ParseTokens<'src> for DmlStatement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        const EXPECTED: &str = "'INSERT', 'UPDATE' or 'DELETE'";
+
+        match tokens.peek()? {
+            Some((_, token)) if token.is_word("INSERT") => {
+                insert::InsertStatement::parse_tokens(tokens).map(Self::Insert)
+            }
+            Some((_, token)) if token.is_word("UPDATE") => {
+                update::UpdateStatement::parse_tokens(tokens).map(Self::Update)
+            }
+            Some((_, token)) if token.is_word("DELETE") => {
+                delete::DeleteStatement::parse_tokens(tokens).map(Self::Delete)
+            }
+            Some((span, token)) => Err(UnexpectedToken::new_expected(span, token, EXPECTED).into()),
+            None => Err(UnexpectedToken::eof(tokens.eof_location(), EXPECTED).into()),
+        }
+    }
+}
+
+/// A value in an 'INSERT' or 'UPDATE' statement, which can also be 'DEFAULT'.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueExpression<'src> {
+    Default,
+    Expression(Expression<'src>),
+}
+
+impl<'src> ParseTokens<'src> for ValueExpression<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if tokens.next_if_kw(Keyword::Default)? {
+            Ok(Self::Default)
+        } else {
+            Expression::parse_tokens(tokens).map(Self::Expression)
+        }
+    }
+}
+
+/// 'THEN RETURN [WITH ACTION [AS alias]] select_list'
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThenReturn<'src> {
+    pub with_action: Option<WithAction<'src>>,
+    pub items: NonEmptyVec<SelectListItem<'src>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithAction<'src> {
+    pub alias: Option<Ident<'src>>,
+}
+
+impl<'src> ThenReturn<'src> {
+    /// Parses a trailing 'THEN RETURN' clause, if there is one.
+    pub(crate) fn parse_optional_clause<T>(tokens: &mut T) -> Result<Option<Self>, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if !tokens.next_if_kw(Keyword::Then)? {
+            return Ok(None);
+        }
+
+        tokens.expect_word("RETURN")?;
+
+        let with_action =
+            if tokens.peek_is_keyword(Keyword::With)? && tokens.peek_nth_is_word(1, "ACTION")? {
+                tokens.next_or_eof()?;
+                tokens.next_or_eof()?;
+
+                let AsAlias { alias, .. } = AsAlias::parse_alias((), tokens)?;
+                Some(WithAction { alias })
+            } else {
+                None
+            };
+
+        let items = parse_comma_separated(tokens, SelectListItem::parse_tokens)?;
+
+        Ok(Some(Self {
+            with_action,
+            items: NonEmptyVec::from_vec(items).expect("always has 1 item"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Statement;
+
+    fn parse(sql: &str) -> DmlStatement<'_> {
+        match Statement::parse(sql).expect("should parse") {
+            Statement::Dml(dml) => dml,
+            other => panic!("expected a DML statement, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_insert() {
+        let DmlStatement::Insert(insert) = parse(
+            "INSERT OR UPDATE INTO Singers (SingerId, FirstName) VALUES (1, 'Marc'), (2, DEFAULT) \
+             THEN RETURN WITH ACTION AS act SingerId",
+        ) else {
+            panic!("expected an insert");
+        };
+
+        assert_eq!(insert.conflict, Some(insert::InsertConflict::Update));
+        assert_eq!(insert.columns.len(), 2);
+
+        let insert::InsertData::Values(rows) = &insert.data else {
+            panic!("expected values");
+        };
+        assert_eq!(rows[1][1], ValueExpression::Default);
+
+        let returning = insert.returning.expect("has a THEN RETURN clause");
+        assert_eq!(
+            returning.with_action,
+            Some(WithAction {
+                alias: Some(Ident::Unquoted("act"))
+            })
+        );
+
+        let DmlStatement::Insert(insert) =
+            parse("INSERT Singers (SingerId) SELECT SingerId FROM OtherSingers")
+        else {
+            panic!("expected an insert");
+        };
+        assert!(matches!(insert.data, insert::InsertData::Query(_)));
+    }
+
+    #[test]
+    fn test_update_and_delete() {
+        let DmlStatement::Update(update) = parse(
+            "UPDATE Singers s SET s.FirstName = 'Marc', LastName = DEFAULT WHERE s.SingerId = 1 \
+             THEN RETURN *",
+        ) else {
+            panic!("expected an update");
+        };
+
+        assert_eq!(update.alias, Some(Ident::Unquoted("s")));
+        assert_eq!(update.assignments.len(), 2);
+        assert!(update.returning.is_some());
+
+        let DmlStatement::Delete(delete) = parse("DELETE FROM Singers WHERE TRUE") else {
+            panic!("expected a delete");
+        };
+        assert_eq!(delete.table.to_string(), "Singers");
+        assert!(delete.returning.is_none());
+
+        Statement::parse("DELETE FROM Singers").expect_err("WHERE is required");
+    }
+}
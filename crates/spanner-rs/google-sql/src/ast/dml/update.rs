@@ -1,3 +1,60 @@
+use super::{ThenReturn, ValueExpression};
+use crate::Error;
+use crate::ast::common::AsAlias;
+use crate::ast::common::expression::Expression;
+use crate::ast::common::ident::{Ident, Path};
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, parse_comma_separated};
+use crate::non_empty_vec::NonEmptyVec;
+use crate::tokens::{PunctOrOp, TokenizerKind};
+
+/// 'UPDATE table [[AS] alias] SET column = value, ... WHERE condition [THEN RETURN ...]'
+#[derive(Debug, Clone, PartialEq)]
 pub struct UpdateStatement<'src> {
-    _priv: &'src (),
+    pub table: Path<'src>,
+    pub alias: Option<Ident<'src>>,
+    pub assignments: NonEmptyVec<UpdateItem<'src>>,
+    pub where_expr: Expression<'src>,
+    pub returning: Option<ThenReturn<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateItem<'src> {
+    pub column: Path<'src>,
+    pub value: ValueExpression<'src>,
+}
+
+impl<'src> ParseTokens<'src> for UpdateStatement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_word("UPDATE")?;
+
+        let table = Path::parse_tokens(tokens)?;
+        let AsAlias { alias, .. } = AsAlias::parse_alias((), tokens)?;
+
+        tokens.expect_kw(Keyword::Set)?;
+
+        let assignments = parse_comma_separated(tokens, |tokens| {
+            let column = Path::parse_tokens(tokens)?;
+            tokens.expect_punct(PunctOrOp::Eq, "'='")?;
+            let value = ValueExpression::parse_tokens(tokens)?;
+            Ok(UpdateItem { column, value })
+        })?;
+
+        // spanner requires a 'WHERE' clause, even if it's just 'WHERE TRUE'
+        tokens.expect_kw(Keyword::Where)?;
+        let where_expr = Expression::parse_tokens(tokens)?;
+
+        let returning = ThenReturn::parse_optional_clause(tokens)?;
+
+        Ok(Self {
+            table,
+            alias,
+            assignments: NonEmptyVec::from_vec(assignments).expect("always has 1 item"),
+            where_expr,
+            returning,
+        })
+    }
 }
@@ -3,17 +3,20 @@ use std::fmt;
 
 pub mod common;
 pub mod ddl;
+mod display;
 pub mod dml;
 pub mod query;
 pub mod reserved;
 
 use self::common::punct::Comma;
+use self::reserved::Keyword;
 use crate::Error;
-use crate::tokens::{Span, Token, TokenizerKind};
+use crate::tokens::{Location, PunctOrOp, Span, Token, Tokenizer, TokenizerKind};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnexpectedToken<'src> {
-    found: Token<'src>,
+    /// The token found, or [`None`] if the end of the input was reached.
+    found: Option<Token<'src>>,
     span: Span,
     expected: Option<Cow<'static, str>>,
 }
@@ -22,7 +25,7 @@ impl<'src> UnexpectedToken<'src> {
     pub(crate) fn new(span: Span, found: Token<'src>) -> Self {
         Self {
             span,
-            found,
+            found: Some(found),
             expected: None,
         }
     }
@@ -34,6 +37,21 @@ impl<'src> UnexpectedToken<'src> {
         Self::new(span, found).expected(expecting.into())
     }
 
+    /// Builds an error for when the input ended early, while expecting something else.
+    pub(crate) fn eof<M>(location: Location, expecting: M) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        Self {
+            span: Span {
+                start: location,
+                len: 0,
+            },
+            found: None,
+            expected: Some(expecting.into()),
+        }
+    }
+
     pub(crate) fn expected<M>(mut self, expecting: M) -> Self
     where
         M: Into<Cow<'static, str>>,
@@ -41,13 +59,26 @@ impl<'src> UnexpectedToken<'src> {
         self.expected = Some(expecting.into());
         self
     }
+
+    /// The span of the unexpected token.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The unexpected token, or [`None`] if the input ended early.
+    pub fn found(&self) -> Option<Token<'src>> {
+        self.found
+    }
 }
 
 impl std::error::Error for UnexpectedToken<'_> {}
 
 impl fmt::Display for UnexpectedToken<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "unexpected token {:?} found", self.found)?;
+        match self.found {
+            Some(ref found) => write!(f, "unexpected token {found:?} found at {}", self.span)?,
+            None => write!(f, "unexpected end of input at {}", self.span)?,
+        }
 
         match self.expected.as_deref() {
             Some(expected) => write!(f, ", expected {expected}"),
@@ -66,9 +97,14 @@ pub(super) trait ParseTokens<'src>: Sized {
     where
         T: TokenizerKind<'src>,
     {
-        match Self::parse_tokens(&mut tokens.lookahead_iter()) {
-            Ok(item) => Ok(Some(item)),
-            Err(Error::UnexpectedToken(_) | Error::InvalidIdent(_)) => Ok(None),
+        let mut lookahead = tokens.lookahead_iter();
+
+        match Self::parse_tokens(&mut lookahead) {
+            Ok(item) => {
+                lookahead.consume()?;
+                Ok(Some(item))
+            }
+            Err(Error::UnexpectedToken(_) | Error::InvalidIdent { .. }) => Ok(None),
             Err(err) => Err(err),
         }
     }
@@ -181,7 +217,7 @@ where
     }
 }
 
-impl<'src, P> ParseTokens<'src> for data_structures::non_empty_vec::NonEmptyVec<P>
+impl<'src, P> ParseTokens<'src> for crate::non_empty_vec::NonEmptyVec<P>
 where
     P: ParseTokens<'src>,
 {
@@ -219,8 +255,106 @@ where
     }
 }
 
+/// Parses a list of statements, separated by semicolons.
+pub fn parse_statements(sql: &str) -> Result<Vec<Statement<'_>>, Error<'_>> {
+    let mut tokens = Tokenizer::new(sql);
+    let mut statements = Vec::new();
+
+    loop {
+        while tokens.next_if_punct_op(PunctOrOp::Semi)? {}
+
+        if TokenizerKind::peek(&mut tokens)?.is_none() {
+            return Ok(statements);
+        }
+
+        statements.push(Statement::parse_tokens(&mut tokens)?);
+
+        if !tokens.next_if_punct_op(PunctOrOp::Semi)? {
+            tokens.expect_end("';' or the end of the input")?;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Statement<'src> {
     Query(query::QueryStatement<'src>),
     Ddl(ddl::DdlStatement<'src>),
     Dml(dml::DmlStatement<'src>),
 }
+
+impl<'src> Statement<'src> {
+    /// Parses a single statement, optionally followed by a semicolon.
+    pub fn parse(sql: &'src str) -> Result<Self, Error<'src>> {
+        let mut tokens = Tokenizer::new(sql);
+        let statement = Self::parse_tokens(&mut tokens)?;
+
+        tokens.next_if_punct_op(PunctOrOp::Semi)?;
+        tokens.expect_end("the end of the statement")?;
+
+        Ok(statement)
+    }
+}
+
+impl<'src> ParseTokens<'src> for Statement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        const EXPECTED: &str = "a query, DML or DDL statement";
+
+        let (span, token) = tokens
+            .peek()?
+            .ok_or_else(|| Error::from(UnexpectedToken::eof(tokens.eof_location(), EXPECTED)))?;
+
+        if token.as_hint().is_some() || query::is_query_start(tokens)? {
+            return query::QueryStatement::parse_tokens(tokens).map(Self::Query);
+        }
+
+        if ["INSERT", "UPDATE", "DELETE"]
+            .iter()
+            .any(|word| token.is_word(word))
+        {
+            return dml::DmlStatement::parse_tokens(tokens).map(Self::Dml);
+        }
+
+        if token.is_keyword(Keyword::Create) || token.is_word("ALTER") || token.is_word("DROP") {
+            return ddl::DdlStatement::parse_tokens(tokens).map(Self::Ddl);
+        }
+
+        Err(UnexpectedToken::new_expected(span, token, EXPECTED).into())
+    }
+}
+
+/// Parses a non-empty, comma separated list of items.
+pub(crate) fn parse_comma_separated<'src, T, P, F>(
+    tokens: &mut T,
+    mut parse: F,
+) -> Result<Vec<P>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+    F: FnMut(&mut T) -> Result<P, Error<'src>>,
+{
+    let mut items = vec![parse(tokens)?];
+
+    while tokens.next_if_punct_op(PunctOrOp::Comma)? {
+        items.push(parse(tokens)?);
+    }
+
+    Ok(items)
+}
+
+/// Parses the contents of a set of parentheses, ensuring everything within them is consumed.
+pub(crate) fn parse_parens<'src, T, P, F>(
+    tokens: &mut T,
+    expected: &'static str,
+    parse: F,
+) -> Result<P, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+    F: FnOnce(&mut Tokenizer<'src>) -> Result<P, Error<'src>>,
+{
+    let mut inner = tokens.expect_parens(expected)?;
+    let parsed = parse(&mut inner)?;
+    inner.expect_end("')'")?;
+    Ok(parsed)
+}
@@ -1,14 +1,21 @@
 use super::common::Cast;
 use super::common::expression::Expression;
 use super::common::ident::Ident;
+use super::reserved::Keyword;
+use super::{ParseTokens, UnexpectedToken, parse_comma_separated, parse_parens};
+use crate::Error;
+use crate::tokens::{PunctOrOp, Span, Token, TokenizerKind};
 
 pub mod select;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct QueryStatement<'src> {
     pub statement_hints: StatementHints<'src>,
     pub table_hints: TableHints<'src>,
     pub join_hints: JoinHints,
     pub query_expr: QueryExpr<'src>,
+    /// Set by a trailing 'FOR UPDATE', which locks the scanned rows in a read-write transaction.
+    pub for_update: bool,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -62,24 +69,245 @@ pub enum HashJoinExecution {
     OnePass,
 }
 
+/// Matches a hint key (case insensitively), and assigns the parsed value to a field. Evaluates
+/// to 'Ok(false)' if the key isn't recognized.
+macro_rules! match_hint {
+    ($this:ident, $key:expr, $span:expr, $value:expr; $($name:literal => $field:ident = $parse:expr),* $(,)?) => {{
+        $(
+            if $key.eq_ignore_ascii_case($name) {
+                $this.$field = Some(($parse)($span, $value)?);
+                return Ok(true);
+            }
+        )*
+
+        Ok(false)
+    }};
+}
+
+impl<'src> StatementHints<'src> {
+    fn apply(&mut self, key: &str, span: Span, value: Token<'src>) -> Result<bool, Error<'src>> {
+        match_hint! {
+            self, key, span, value;
+            "USE_ADDITIONAL_PARALLELISM" => use_additional_parallelism = hint_bool,
+            "OPTIMIZER_VERSION" => optimizer_version = hint_str,
+            "OPTIMIZER_STATISTICS_PACKAGE" => optimizer_statistics_package = hint_str,
+            "ALLOW_DISTRIBUTED_MERGE" => allow_distributed_merge = hint_bool,
+            "LOCK_SCANNED_RANGES" => lock_scanned_ranges = |span, value| hint_enum(span, value, &[
+                ("EXCLUSIVE", LockScannedRanges::Exclusive),
+                ("SHARED", LockScannedRanges::Shared),
+            ]),
+        }
+    }
+}
+
+impl<'src> TableHints<'src> {
+    fn apply(&mut self, key: &str, span: Span, value: Token<'src>) -> Result<bool, Error<'src>> {
+        match_hint! {
+            self, key, span, value;
+            "FORCE_INDEX" => force_index = hint_str,
+            "GROUPBY_SCAN_OPTIMIZATION" => group_by_scan_optimization = hint_bool,
+        }
+    }
+
+    /// Parses table hints from a hint token, i.e '@{FORCE_INDEX=index_name}'.
+    pub(crate) fn from_hint(span: Span, token: Token<'src>) -> Result<Self, Error<'src>> {
+        let mut hints = Self::default();
+        parse_hint(span, token, |key, span, value| {
+            hints.apply(key, span, value)
+        })?;
+        Ok(hints)
+    }
+}
+
+impl JoinHints {
+    fn apply<'src>(
+        &mut self,
+        key: &str,
+        span: Span,
+        value: Token<'src>,
+    ) -> Result<bool, Error<'src>> {
+        match_hint! {
+            self, key, span, value;
+            "FORCE_JOIN_ORDER" => force_join_order = hint_bool,
+            "JOIN_METHOD" => join_method = |span, value| hint_enum(span, value, &[
+                ("HASH_JOIN", JoinMethod::HashJoin),
+                ("APPLY_JOIN", JoinMethod::ApplyJoin),
+                ("MERGE_JOIN", JoinMethod::MergeJoin),
+                ("PUSH_BROADCAST_HASH_JOIN", JoinMethod::PushBroadcastHashJoin),
+            ]),
+            "HASH_JOIN_BUILD_SIDE" => hash_join_build_side = |span, value| hint_enum(span, value, &[
+                ("BUILD_LEFT", HashJoinBuildSide::BuildLeft),
+                ("BUILD_RIGHT", HashJoinBuildSide::BuildRight),
+            ]),
+            "BATCH_MODE" => batch_mode = hint_bool,
+            "HASH_JOIN_EXECUTION" => hash_join_execution = |span, value| hint_enum(span, value, &[
+                ("MULTI_PASS", HashJoinExecution::MultiPass),
+                ("ONE_PASS", HashJoinExecution::OnePass),
+            ]),
+        }
+    }
+
+    /// Parses join hints from a hint token, i.e '@{JOIN_METHOD=HASH_JOIN}'.
+    pub(crate) fn from_hint<'src>(span: Span, token: Token<'src>) -> Result<Self, Error<'src>> {
+        let mut hints = Self::default();
+        parse_hint(span, token, |key, span, value| {
+            hints.apply(key, span, value)
+        })?;
+        Ok(hints)
+    }
+}
+
+/// Parses the key-value pairs in a hint token (i.e '@{KEY=value, ...}'), passing each one to
+/// 'apply'. 'apply' should return false if the key isn't recognized.
+pub(crate) fn parse_hint<'src, F>(
+    span: Span,
+    token: Token<'src>,
+    mut apply: F,
+) -> Result<(), Error<'src>>
+where
+    F: FnMut(&str, Span, Token<'src>) -> Result<bool, Error<'src>>,
+{
+    let mut inner = token
+        .build_hint_parser(span.start)
+        .ok_or_else(|| UnexpectedToken::new_expected(span, token, "a hint"))?;
+
+    parse_comma_separated(&mut inner, |tokens| {
+        let (key_span, key_token) = tokens.next_or_expected("a hint key")?;
+
+        let key = key_token
+            .as_unquoted()
+            .ok_or_else(|| UnexpectedToken::new_expected(key_span, key_token, "a hint key"))?;
+
+        tokens.expect_punct(PunctOrOp::Eq, "'='")?;
+        let (value_span, value) = tokens.next_or_expected("a hint value")?;
+
+        if apply(key, value_span, value)? {
+            Ok(())
+        } else {
+            Err(UnexpectedToken::new_expected(key_span, key_token, "a known hint").into())
+        }
+    })?;
+
+    inner.expect_end("'}'")
+}
+
+pub(crate) fn hint_bool<'src>(span: Span, token: Token<'src>) -> Result<bool, Error<'src>> {
+    match token.as_keyword() {
+        Some(Keyword::True) => Ok(true),
+        Some(Keyword::False) => Ok(false),
+        _ => Err(UnexpectedToken::new_expected(span, token, "'TRUE' or 'FALSE'").into()),
+    }
+}
+
+fn hint_str<'src>(span: Span, token: Token<'src>) -> Result<&'src str, Error<'src>> {
+    match token {
+        Token::Unquoted(value) | Token::NumericLiteral(value) => Ok(value),
+        Token::QuotedIdentifier(quoted) => Ok(quoted.trim_matches('`')),
+        Token::StringLiteral(lit) => Ok(lit.contents()),
+        _ => Err(UnexpectedToken::new_expected(span, token, "a hint value").into()),
+    }
+}
+
+fn hint_enum<'src, E: Copy>(
+    span: Span,
+    token: Token<'src>,
+    variants: &[(&'static str, E)],
+) -> Result<E, Error<'src>> {
+    variants
+        .iter()
+        .find(|(name, _)| token.is_word(name))
+        .map(|(_, variant)| *variant)
+        .ok_or_else(|| UnexpectedToken::new_expected(span, token, "a valid hint value").into())
+}
+
+/// Checks if the next token starts a query, either with 'SELECT', 'WITH' or a parenthesized
+/// query.
+pub(crate) fn is_query_start<'src, T>(tokens: &mut T) -> Result<bool, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    match tokens.peek()? {
+        Some((_, token))
+            if token.is_keyword(Keyword::Select) || token.is_keyword(Keyword::With) =>
+        {
+            Ok(true)
+        }
+        Some((span, token)) => match token.build_parens_parser(span.start) {
+            Some(mut inner) => is_query_start(&mut inner),
+            None => Ok(false),
+        },
+        None => Ok(false),
+    }
+}
+
+impl<'src> ParseTokens<'src> for QueryStatement<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let mut statement_hints = StatementHints::default();
+        let mut table_hints = TableHints::default();
+        let mut join_hints = JoinHints::default();
+
+        if let Some((span, token)) = tokens.peek()?
+            && token.as_hint().is_some()
+        {
+            tokens.next_or_eof()?;
+
+            parse_hint(span, token, |key, span, value| {
+                Ok(statement_hints.apply(key, span, value)?
+                    || table_hints.apply(key, span, value)?
+                    || join_hints.apply(key, span, value)?)
+            })?;
+        }
+
+        let query_expr = QueryExpr::parse_tokens(tokens)?;
+
+        let for_update = if tokens.next_if_kw(Keyword::For)? {
+            tokens.expect_word("UPDATE")?;
+            true
+        } else {
+            false
+        };
+
+        Ok(Self {
+            statement_hints,
+            table_hints,
+            join_hints,
+            query_expr,
+            for_update,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct QueryExpr<'src> {
+    /// Common table expressions, from a leading 'WITH name AS (subquery), ...' clause.
     pub cte: Vec<Cast<Ident<'src>, QueryExpr<'src>>>,
     pub kind: Box<QueryKind<'src>>,
     pub order_by: Vec<OrderBy<'src>>,
-    pub limit: Option<Limit>,
+    pub limit: Option<Limit<'src>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct OrderBy<'src> {
-    expr: Expression<'src>,
-    ascending: bool,
+    pub expr: Expression<'src>,
+    pub ascending: bool,
+    /// An explicit 'NULLS FIRST' or 'NULLS LAST'. Otherwise nulls sort first when ascending, and
+    /// last when descending.
+    pub nulls: Option<NullsOrder>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Limit {
-    count: usize,
-    offset: Option<usize>,
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limit<'src> {
+    pub count: Expression<'src>,
+    pub offset: Option<Expression<'src>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -91,15 +319,15 @@ pub enum QueryKind<'src> {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SetOperation<'src> {
-    lhs: QueryExpr<'src>,
-    operator: SetOperator,
-    rhs: QueryExpr<'src>,
+    pub lhs: QueryExpr<'src>,
+    pub operator: SetOperator,
+    pub rhs: QueryExpr<'src>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SetOperator {
-    kind: SetOperatorKind,
-    scope: Scope,
+    pub kind: SetOperatorKind,
+    pub scope: Scope,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -114,3 +342,232 @@ pub enum Scope {
     All,
     Distinct,
 }
+
+impl<'src> QueryExpr<'src> {
+    fn from_kind(kind: QueryKind<'src>) -> Self {
+        Self {
+            cte: Vec::new(),
+            kind: Box::new(kind),
+            order_by: Vec::new(),
+            limit: None,
+        }
+    }
+}
+
+impl<'src> ParseTokens<'src> for QueryExpr<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let cte = if tokens.next_if_kw(Keyword::With)? {
+            parse_comma_separated(tokens, |tokens| {
+                let from = Ident::parse_tokens(tokens)?;
+                tokens.expect_kw(Keyword::As)?;
+                let to = parse_parens(tokens, "a subquery", QueryExpr::parse_tokens)?;
+                Ok(Cast { from, to })
+            })?
+        } else {
+            Vec::new()
+        };
+
+        let kind = parse_set_operations(tokens)?;
+
+        let order_by = if tokens.next_if_kw(Keyword::Order)? {
+            tokens.expect_kw(Keyword::By)?;
+            parse_comma_separated(tokens, OrderBy::parse_tokens)?
+        } else {
+            Vec::new()
+        };
+
+        let limit = if tokens.next_if_kw(Keyword::Limit)? {
+            let count = Expression::parse_tokens(tokens)?;
+
+            let offset = if tokens.next_if_word("OFFSET")? {
+                Some(Expression::parse_tokens(tokens)?)
+            } else {
+                None
+            };
+
+            Some(Limit { count, offset })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            cte,
+            kind: Box::new(kind),
+            order_by,
+            limit,
+        })
+    }
+}
+
+impl<'src> ParseTokens<'src> for OrderBy<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let expr = Expression::parse_tokens(tokens)?;
+
+        let ascending = if tokens.next_if_kw(Keyword::Desc)? {
+            false
+        } else {
+            tokens.next_if_kw(Keyword::Asc)?;
+            true
+        };
+
+        let nulls = if tokens.next_if_kw(Keyword::Nulls)? {
+            if tokens.next_if_word("FIRST")? {
+                Some(NullsOrder::First)
+            } else {
+                tokens.expect_word("LAST")?;
+                Some(NullsOrder::Last)
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            expr,
+            ascending,
+            nulls,
+        })
+    }
+}
+
+fn parse_set_operations<'src, T>(tokens: &mut T) -> Result<QueryKind<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let mut lhs = parse_query_primary(tokens)?;
+
+    loop {
+        let kind = match tokens.peek()?.and_then(|(_, token)| token.as_keyword()) {
+            Some(Keyword::Union) => SetOperatorKind::Union,
+            Some(Keyword::Intersect) => SetOperatorKind::Intersect,
+            Some(Keyword::Except) => SetOperatorKind::Except,
+            _ => return Ok(lhs),
+        };
+
+        tokens.next_or_eof()?;
+
+        let scope = if tokens.next_if_kw(Keyword::All)? {
+            Scope::All
+        } else if tokens.next_if_kw(Keyword::Distinct)? {
+            Scope::Distinct
+        } else {
+            let (span, token) = tokens.next_or_expected("'ALL' or 'DISTINCT'")?;
+            return Err(UnexpectedToken::new_expected(span, token, "'ALL' or 'DISTINCT'").into());
+        };
+
+        let rhs = parse_query_primary(tokens)?;
+
+        lhs = QueryKind::SetOperation(SetOperation {
+            lhs: QueryExpr::from_kind(lhs),
+            operator: SetOperator { kind, scope },
+            rhs: QueryExpr::from_kind(rhs),
+        });
+    }
+}
+
+fn parse_query_primary<'src, T>(tokens: &mut T) -> Result<QueryKind<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    const EXPECTED: &str = "'SELECT' or a parenthesized query";
+
+    if tokens.peek_is_keyword(Keyword::Select)? {
+        return select::Select::parse_tokens(tokens).map(QueryKind::Select);
+    }
+
+    let (span, token) = tokens.next_or_expected(EXPECTED)?;
+
+    match token.build_parens_parser(span.start) {
+        Some(mut inner) => {
+            let query = QueryExpr::parse_tokens(&mut inner)?;
+            inner.expect_end("')'")?;
+            Ok(QueryKind::SubQuery(query))
+        }
+        None => Err(UnexpectedToken::new_expected(span, token, EXPECTED).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::Tokenizer;
+
+    fn parse(sql: &str) -> Result<QueryStatement<'_>, Error<'_>> {
+        let mut tokens = Tokenizer::new(sql);
+        let statement = QueryStatement::parse_tokens(&mut tokens)?;
+        tokens.expect_end("the end of the query")?;
+        Ok(statement)
+    }
+
+    #[test]
+    fn test_statement_hints() {
+        let statement = parse(
+            "@{USE_ADDITIONAL_PARALLELISM=TRUE, OPTIMIZER_VERSION=5, JOIN_METHOD=HASH_JOIN} \
+             SELECT 1",
+        )
+        .expect("should parse");
+
+        assert_eq!(
+            statement.statement_hints.use_additional_parallelism,
+            Some(true)
+        );
+        assert_eq!(statement.statement_hints.optimizer_version, Some("5"));
+        assert_eq!(statement.join_hints.join_method, Some(JoinMethod::HashJoin));
+
+        let err = parse("@{NOT_A_HINT=TRUE} SELECT 1").unwrap_err();
+        assert_eq!(err.span().start.column, 2);
+    }
+
+    #[test]
+    fn test_set_operations_and_limits() {
+        let statement = parse(
+            "WITH a AS (SELECT 1 AS x) (SELECT x FROM a) UNION ALL (SELECT 2) ORDER BY x DESC \
+             LIMIT @max_rows OFFSET 10",
+        )
+        .expect("should parse");
+
+        let query = statement.query_expr;
+        assert_eq!(query.cte.len(), 1);
+        assert_eq!(query.cte[0].from, Ident::Unquoted("a"));
+        assert!(!query.order_by[0].ascending);
+        assert_eq!(query.order_by[0].nulls, None);
+        assert!(query.limit.is_some_and(|limit| limit.offset.is_some()));
+
+        let QueryKind::SetOperation(set_op) = *query.kind else {
+            panic!("expected a set operation");
+        };
+
+        assert_eq!(
+            set_op.operator,
+            SetOperator {
+                kind: SetOperatorKind::Union,
+                scope: Scope::All,
+            }
+        );
+
+        parse("SELECT 1 UNION SELECT 2").expect_err("set operations require ALL or DISTINCT");
+    }
+
+    #[test]
+    fn test_nulls_order_and_for_update() {
+        let statement =
+            parse("SELECT a, b FROM t ORDER BY a NULLS LAST, b DESC NULLS FIRST FOR UPDATE")
+                .expect("should parse");
+
+        assert!(statement.for_update);
+
+        let order_by = &statement.query_expr.order_by;
+        assert_eq!(order_by[0].nulls, Some(NullsOrder::Last));
+        assert!(order_by[0].ascending);
+        assert_eq!(order_by[1].nulls, Some(NullsOrder::First));
+        assert!(!order_by[1].ascending);
+
+        parse("SELECT a FROM t ORDER BY a NULLS").expect_err("NULLS needs FIRST or LAST");
+        parse("SELECT a FROM t FOR SHARE").expect_err("only FOR UPDATE is supported");
+    }
+}
@@ -1,53 +1,82 @@
-use data_structures::non_empty_vec::NonEmptyVec;
-
-use super::{QueryExpr, TableHints};
+use super::{JoinHints, QueryExpr, Scope, TableHints};
+use crate::Error;
+use crate::ast::common::AsAlias;
 use crate::ast::common::expression::Expression;
 use crate::ast::common::ident::{Ident, Path};
-use crate::ast::common::{AsAlias, Cast};
+use crate::ast::reserved::Keyword;
+use crate::ast::{ParseTokens, UnexpectedToken, parse_comma_separated, parse_parens};
+use crate::non_empty_vec::NonEmptyVec;
+use crate::tokens::{PunctOrOp, Span, Token, Tokenizer, TokenizerKind};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Select<'src> {
-    scope: Option<super::Scope>,
-    cast: Option<Cast<(), Target>>,
-    select_list: Vec<SelectListItem<'src>>,
-    from: NonEmptyVec<FromClause<'src>>,
-    where_expr: Option<Expression<'src>>,
-    group_by: Vec<Expression<'src>>,
-    having: Option<Expression<'src>>,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub struct FromClause<'src> {
-    item: FromItem<'src>,
-    table_sample_operator: Option<TableSampleOperator>,
+    pub scope: Option<Scope>,
+    /// 'SELECT AS STRUCT' or 'SELECT AS VALUE'
+    pub target: Option<Target>,
+    pub select_list: NonEmptyVec<SelectListItem<'src>>,
+    pub from: Option<FromItem<'src>>,
+    pub where_expr: Option<Expression<'src>>,
+    pub group_by: Vec<Expression<'src>>,
+    pub having: Option<Expression<'src>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FromItem<'src> {
+    /// A table, or a correlated array field path (i.e 'FROM t, t.array_col').
     Table {
-        name: Ident<'src>,
+        name: Path<'src>,
         hints: TableHints<'src>,
         alias: Option<Ident<'src>>,
+        sample: Option<TableSampleOperator>,
     },
     Subquery(AsAlias<'src, QueryExpr<'src>>),
-    Join(JoinOperation<'src>),
-    FieldPath(Path<'src>),
+    Join(Box<JoinOperation<'src>>),
     Unnest(UnnestOperator<'src>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct JoinOperation<'src> {
-    _priv: &'src (),
+    pub lhs: FromItem<'src>,
+    pub kind: JoinKind,
+    pub hints: JoinHints,
+    pub rhs: FromItem<'src>,
+    pub condition: Option<JoinCondition<'src>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    Inner,
+    Cross,
+    /// A comma cross join, i.e 'FROM a, b'
+    Comma,
+    Full,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JoinCondition<'src> {
+    On(Expression<'src>),
+    Using(NonEmptyVec<Ident<'src>>),
 }
+
+/// 'UNNEST(array) [[AS] alias] [WITH OFFSET [[AS] alias]]'
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnnestOperator<'src> {
-    _priv: &'src (),
+    pub expr: Expression<'src>,
+    pub alias: Option<Ident<'src>>,
+    pub with_offset: Option<WithOffset<'src>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithOffset<'src> {
+    pub alias: Option<Ident<'src>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct TableSampleOperator {
-    method: TableSampleMethod,
-    sample_size: SampleSize,
+    pub method: TableSampleMethod,
+    pub sample_size: SampleSize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -71,10 +100,454 @@ pub enum Target {
 #[derive(Debug, Clone, PartialEq)]
 pub enum SelectListItem<'src> {
     SelectAll(SelectAll<'src>),
-    Expr(Cast<Expression<'src>, Option<Ident<'src>>>),
+    Expr(AsAlias<'src, Expression<'src>>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// '[expr.]* [EXCEPT (columns)] [REPLACE (expr AS column, ...)]'
+#[derive(Debug, Clone, PartialEq)]
 pub struct SelectAll<'src> {
-    _lmao: &'src (),
+    /// The table/struct being expanded, if any (i.e 'table' in 'table.*').
+    pub qualifier: Option<Expression<'src>>,
+    pub except: Vec<Ident<'src>>,
+    pub replace: Vec<AsAlias<'src, Expression<'src>>>,
+}
+
+impl<'src> ParseTokens<'src> for Select<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        tokens.expect_kw(Keyword::Select)?;
+
+        let scope = if tokens.next_if_kw(Keyword::All)? {
+            Some(Scope::All)
+        } else if tokens.next_if_kw(Keyword::Distinct)? {
+            Some(Scope::Distinct)
+        } else {
+            None
+        };
+
+        let target = if tokens.next_if_kw(Keyword::As)? {
+            if tokens.next_if_kw(Keyword::Struct)? {
+                Some(Target::Struct)
+            } else {
+                tokens.expect_word("VALUE")?;
+                Some(Target::Value)
+            }
+        } else {
+            None
+        };
+
+        let select_list = parse_comma_separated(tokens, SelectListItem::parse_tokens)?;
+
+        let from = if tokens.next_if_kw(Keyword::From)? {
+            Some(FromItem::parse_tokens(tokens)?)
+        } else {
+            None
+        };
+
+        let where_expr = if tokens.next_if_kw(Keyword::Where)? {
+            Some(Expression::parse_tokens(tokens)?)
+        } else {
+            None
+        };
+
+        let group_by = if tokens.next_if_kw(Keyword::Group)? {
+            tokens.expect_kw(Keyword::By)?;
+            parse_comma_separated(tokens, Expression::parse_tokens)?
+        } else {
+            Vec::new()
+        };
+
+        let having = if tokens.next_if_kw(Keyword::Having)? {
+            Some(Expression::parse_tokens(tokens)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            scope,
+            target,
+            select_list: NonEmptyVec::from_vec(select_list).expect("always has 1 item"),
+            from,
+            where_expr,
+            group_by,
+            having,
+        })
+    }
+}
+
+impl<'src> ParseTokens<'src> for SelectListItem<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        if tokens.next_if_punct_op(PunctOrOp::Mul)? {
+            return parse_select_all_modifiers(None, tokens).map(Self::SelectAll);
+        }
+
+        let expr = Expression::parse_tokens(tokens)?;
+
+        if tokens.peek_is_punct(PunctOrOp::Dot)? && tokens.peek_nth_is_punct(1, PunctOrOp::Mul)? {
+            tokens.next_or_eof()?;
+            tokens.next_or_eof()?;
+            return parse_select_all_modifiers(Some(expr), tokens).map(Self::SelectAll);
+        }
+
+        AsAlias::parse_alias(expr, tokens).map(Self::Expr)
+    }
+}
+
+fn parse_select_all_modifiers<'src, T>(
+    qualifier: Option<Expression<'src>>,
+    tokens: &mut T,
+) -> Result<SelectAll<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    // 'EXCEPT' is also a set operator, so only treat it as a modifier if a column list follows.
+    let except = if tokens.peek_is_keyword(Keyword::Except)?
+        && tokens
+            .peek_nth(1)?
+            .is_some_and(|(_, token)| token.as_parens().is_some())
+    {
+        tokens.next_or_eof()?;
+        parse_parens(tokens, "a column list", |inner| {
+            parse_comma_separated(inner, Ident::parse_tokens)
+        })?
+    } else {
+        Vec::new()
+    };
+
+    let replace = if tokens.next_if_word("REPLACE")? {
+        parse_parens(tokens, "a replacement list", |inner| {
+            parse_comma_separated(inner, |inner| {
+                let expr = Expression::parse_tokens(inner)?;
+                inner.expect_kw(Keyword::As)?;
+                let alias = Ident::parse_tokens(inner)?;
+
+                Ok(AsAlias {
+                    item: expr,
+                    alias: Some(alias),
+                })
+            })
+        })?
+    } else {
+        Vec::new()
+    };
+
+    Ok(SelectAll {
+        qualifier,
+        except,
+        replace,
+    })
+}
+
+impl<'src> ParseTokens<'src> for FromItem<'src> {
+    fn parse_tokens<T>(tokens: &mut T) -> Result<Self, Error<'src>>
+    where
+        T: TokenizerKind<'src>,
+    {
+        let mut lhs = parse_from_primary(tokens)?;
+
+        loop {
+            if tokens.next_if_punct_op(PunctOrOp::Comma)? {
+                let rhs = parse_from_primary(tokens)?;
+
+                lhs = FromItem::Join(Box::new(JoinOperation {
+                    lhs,
+                    kind: JoinKind::Comma,
+                    hints: JoinHints::default(),
+                    rhs,
+                    condition: None,
+                }));
+                continue;
+            }
+
+            let Some(kind) = parse_join_kind(tokens)? else {
+                return Ok(lhs);
+            };
+
+            let hints = match tokens.peek()? {
+                Some((span, token)) if token.as_hint().is_some() => {
+                    tokens.next_or_eof()?;
+                    JoinHints::from_hint(span, token)?
+                }
+                _ => JoinHints::default(),
+            };
+
+            let rhs = parse_from_primary(tokens)?;
+
+            let condition = if kind == JoinKind::Cross {
+                None
+            } else if tokens.next_if_kw(Keyword::On)? {
+                Some(JoinCondition::On(Expression::parse_tokens(tokens)?))
+            } else if tokens.next_if_kw(Keyword::Using)? {
+                let columns = parse_parens(tokens, "a column list", |inner| {
+                    parse_comma_separated(inner, Ident::parse_tokens)
+                })?;
+
+                Some(JoinCondition::Using(
+                    NonEmptyVec::from_vec(columns).expect("always has 1 item"),
+                ))
+            } else {
+                None
+            };
+
+            lhs = FromItem::Join(Box::new(JoinOperation {
+                lhs,
+                kind,
+                hints,
+                rhs,
+                condition,
+            }));
+        }
+    }
+}
+
+/// Parses '[INNER | CROSS | FULL [OUTER] | LEFT [OUTER] | RIGHT [OUTER]] JOIN'.
+fn parse_join_kind<'src, T>(tokens: &mut T) -> Result<Option<JoinKind>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    let kind = match tokens.peek()?.and_then(|(_, token)| token.as_keyword()) {
+        Some(Keyword::Join) => {
+            tokens.next_or_eof()?;
+            return Ok(Some(JoinKind::Inner));
+        }
+        Some(Keyword::Inner) => JoinKind::Inner,
+        Some(Keyword::Cross) => JoinKind::Cross,
+        Some(Keyword::Full) => JoinKind::Full,
+        Some(Keyword::Left) => JoinKind::Left,
+        Some(Keyword::Right) => JoinKind::Right,
+        _ => return Ok(None),
+    };
+
+    tokens.next_or_eof()?;
+
+    if matches!(kind, JoinKind::Full | JoinKind::Left | JoinKind::Right) {
+        tokens.next_if_kw(Keyword::Outer)?;
+    }
+
+    tokens.expect_kw(Keyword::Join)?;
+    Ok(Some(kind))
+}
+
+fn parse_from_primary<'src, T>(tokens: &mut T) -> Result<FromItem<'src>, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    const EXPECTED: &str = "a table, subquery or 'UNNEST'";
+
+    let (span, token) = match tokens.peek()? {
+        Some(next) => next,
+        None => return Err(UnexpectedToken::eof(tokens.eof_location(), EXPECTED).into()),
+    };
+
+    if let Some(mut inner) = token.build_parens_parser(span.start) {
+        tokens.next_or_eof()?;
+
+        if super::is_query_start(&mut inner)? {
+            let query = parse_to_end(&mut inner, QueryExpr::parse_tokens)?;
+            return AsAlias::parse_alias(query, tokens).map(FromItem::Subquery);
+        }
+
+        // parenthesized joins, i.e 'FROM (a JOIN b ON ...) JOIN c ...'
+        return parse_to_end(&mut inner, FromItem::parse_tokens);
+    }
+
+    if tokens.next_if_kw(Keyword::Unnest)? {
+        let expr = parse_parens(tokens, "'('", Expression::parse_tokens)?;
+        let AsAlias { alias, .. } = AsAlias::parse_alias((), tokens)?;
+
+        let with_offset =
+            if tokens.peek_is_keyword(Keyword::With)? && tokens.peek_nth_is_word(1, "OFFSET")? {
+                tokens.next_or_eof()?;
+                tokens.next_or_eof()?;
+                let AsAlias { alias, .. } = AsAlias::parse_alias((), tokens)?;
+                Some(WithOffset { alias })
+            } else {
+                None
+            };
+
+        return Ok(FromItem::Unnest(UnnestOperator {
+            expr,
+            alias,
+            with_offset,
+        }));
+    }
+
+    if token.as_keyword().is_some() {
+        return Err(UnexpectedToken::new_expected(span, token, EXPECTED).into());
+    }
+
+    let name = Path::parse_tokens(tokens)?;
+
+    let hints = match tokens.peek()? {
+        Some((span, token)) if token.as_hint().is_some() => {
+            tokens.next_or_eof()?;
+            TableHints::from_hint(span, token)?
+        }
+        _ => TableHints::default(),
+    };
+
+    let AsAlias { alias, .. } = AsAlias::parse_alias((), tokens)?;
+
+    let sample = if tokens.next_if_kw(Keyword::Tablesample)? {
+        Some(parse_table_sample(tokens)?)
+    } else {
+        None
+    };
+
+    Ok(FromItem::Table {
+        name,
+        hints,
+        alias,
+        sample,
+    })
+}
+
+fn parse_to_end<'src, P, F>(tokens: &mut Tokenizer<'src>, parse: F) -> Result<P, Error<'src>>
+where
+    F: FnOnce(&mut Tokenizer<'src>) -> Result<P, Error<'src>>,
+{
+    let parsed = parse(tokens)?;
+    tokens.expect_end("')'")?;
+    Ok(parsed)
+}
+
+fn parse_table_sample<'src, T>(tokens: &mut T) -> Result<TableSampleOperator, Error<'src>>
+where
+    T: TokenizerKind<'src>,
+{
+    const EXPECTED: &str = "'BERNOULLI' or 'RESERVOIR'";
+
+    let (span, token) = tokens.next_or_expected(EXPECTED)?;
+
+    let method = if token.is_word("BERNOULLI") {
+        TableSampleMethod::Bernoulli
+    } else if token.is_word("RESERVOIR") {
+        TableSampleMethod::Reservoir
+    } else {
+        return Err(UnexpectedToken::new_expected(span, token, EXPECTED).into());
+    };
+
+    let sample_size = parse_parens(tokens, "'('", |inner| {
+        let (span, token) = inner.next_or_expected("a sample size")?;
+        let invalid = |span: Span, token: Token<'src>| {
+            Error::from(UnexpectedToken::new_expected(span, token, "a sample size"))
+        };
+
+        let size = token
+            .as_numeric_literal()
+            .ok_or_else(|| invalid(span, token))?;
+
+        if inner.next_if_word("ROWS")? {
+            size.parse()
+                .map(SampleSize::Rows)
+                .map_err(|_| invalid(span, token))
+        } else {
+            inner.expect_word("PERCENT")?;
+            size.parse()
+                .map(SampleSize::Percent)
+                .map_err(|_| invalid(span, token))
+        }
+    })?;
+
+    Ok(TableSampleOperator {
+        method,
+        sample_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::query::JoinMethod;
+
+    fn parse(sql: &str) -> Select<'_> {
+        let mut tokens = Tokenizer::new(sql);
+        let select = Select::parse_tokens(&mut tokens).expect("should parse");
+        tokens
+            .expect_end("the end of the query")
+            .expect("should be fully consumed");
+        select
+    }
+
+    #[test]
+    fn test_select_list() {
+        let select = parse("SELECT DISTINCT a, b AS c, d e, t.* EXCEPT (f) REPLACE (g + 1 AS g)");
+
+        assert_eq!(select.scope, Some(Scope::Distinct));
+        assert_eq!(select.select_list.len(), 4);
+
+        let SelectListItem::Expr(aliased) = &select.select_list[2] else {
+            panic!("expected an expression");
+        };
+        assert_eq!(aliased.alias, Some(Ident::Unquoted("e")));
+
+        let SelectListItem::SelectAll(select_all) = &select.select_list[3] else {
+            panic!("expected a select all");
+        };
+        assert!(select_all.qualifier.is_some());
+        assert_eq!(select_all.except, [Ident::Unquoted("f")]);
+        assert_eq!(select_all.replace.len(), 1);
+
+        let select = parse("SELECT AS STRUCT 1 AS a, 2 AS b");
+        assert_eq!(select.target, Some(Target::Struct));
+    }
+
+    #[test]
+    fn test_from_clause() {
+        let select = parse(
+            "SELECT s.Name, COUNT(*) AS n FROM Singers@{FORCE_INDEX=SingersByName} AS s LEFT \
+             OUTER JOIN@{JOIN_METHOD=HASH_JOIN} Albums a USING (SingerId) CROSS JOIN \
+             UNNEST(s.Tags) AS tag WITH OFFSET AS idx WHERE s.SingerId > @min_id GROUP BY s.Name \
+             HAVING n > 1",
+        );
+
+        assert_eq!(select.group_by.len(), 1);
+        assert!(select.having.is_some());
+
+        let Some(FromItem::Join(outer)) = select.from else {
+            panic!("expected a join");
+        };
+        assert_eq!(outer.kind, JoinKind::Cross);
+        assert!(matches!(
+            outer.rhs,
+            FromItem::Unnest(UnnestOperator {
+                with_offset: Some(WithOffset { alias: Some(_) }),
+                ..
+            })
+        ));
+
+        let FromItem::Join(inner) = outer.lhs else {
+            panic!("expected a join");
+        };
+        assert_eq!(inner.kind, JoinKind::Left);
+        assert_eq!(inner.hints.join_method, Some(JoinMethod::HashJoin));
+        assert!(matches!(inner.condition, Some(JoinCondition::Using(_))));
+
+        let FromItem::Table { hints, alias, .. } = inner.lhs else {
+            panic!("expected a table");
+        };
+        assert_eq!(hints.force_index, Some("SingersByName"));
+        assert_eq!(alias, Some(Ident::Unquoted("s")));
+    }
+
+    #[test]
+    fn test_subqueries_and_samples() {
+        let select =
+            parse("SELECT * FROM (SELECT a FROM t TABLESAMPLE BERNOULLI (10 PERCENT)) sub, other");
+
+        let Some(FromItem::Join(join)) = select.from else {
+            panic!("expected a join");
+        };
+        assert_eq!(join.kind, JoinKind::Comma);
+
+        let FromItem::Subquery(sub) = join.lhs else {
+            panic!("expected a subquery");
+        };
+        assert_eq!(sub.alias, Some(Ident::Unquoted("sub")));
+    }
 }
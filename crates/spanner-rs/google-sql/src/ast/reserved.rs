@@ -1,6 +1,7 @@
+use unicase::UniCase;
+
 use super::{FromToken, UnexpectedToken};
 use crate::Error;
-// use crate::map::Mapping;
 use crate::tokens::{Span, Token};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -10,6 +11,7 @@ pub enum DataType {
     Bool,
     Bytes,
     Date,
+    Float32,
     Float64,
     Int64,
     Json,
@@ -19,13 +21,6 @@ pub enum DataType {
     Timestamp,
 }
 
-peg::parser! {
-    grammar types() for str {
-        pub rule dtype() -> DataType
-            = n:$(['a'..='z' | 'A'..='Z']) {? DataType::from_str(n).ok_or("invalid data type") }
-    }
-}
-
 impl PartialOrd for DataType {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -40,36 +35,42 @@ impl Ord for DataType {
     }
 }
 
-/*
-static DATA_TYPE_MAPPING: Mapping<11, UniCase<&'static str>, DataType> = crate::map::mapping! {
-    "ARRAY" => DataType::Array,
-    "BOOL" => DataType::Bool,
-    "BYTES" => DataType::Bytes,
-    "DATE" => DataType::Date,
-    "FLOAT64" => DataType::Float64,
-    "INT64" => DataType::Int64,
-    "JSON" => DataType::Json,
-    "NUMERIC" => DataType::Numeric,
-    "STRING" => DataType::String,
-    "STRUCT" => DataType::Struct,
-    "TIMESTAMP" => DataType::Timestamp,
+static DATA_TYPE_MAPPING: phf::Map<UniCase<&'static str>, DataType> = phf::phf_map! {
+    UniCase::ascii("ARRAY") => DataType::Array,
+    UniCase::ascii("BOOL") => DataType::Bool,
+    UniCase::ascii("BYTES") => DataType::Bytes,
+    UniCase::ascii("DATE") => DataType::Date,
+    UniCase::ascii("FLOAT32") => DataType::Float32,
+    UniCase::ascii("FLOAT64") => DataType::Float64,
+    UniCase::ascii("INT64") => DataType::Int64,
+    UniCase::ascii("JSON") => DataType::Json,
+    UniCase::ascii("NUMERIC") => DataType::Numeric,
+    UniCase::ascii("STRING") => DataType::String,
+    UniCase::ascii("STRUCT") => DataType::Struct,
+    UniCase::ascii("TIMESTAMP") => DataType::Timestamp,
 };
-*/
 
 impl DataType {
-    pub fn from_str(_s: &str) -> Option<Self> {
-        // DATA_TYPE_MAPPING.get(s).copied()
-        todo!()
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        DATA_TYPE_MAPPING.get(&UniCase::ascii(s)).copied()
     }
 
-    fn as_str(&self) -> &'static str {
-        /*
-            DATA_TYPE_MAPPING
-                .get_reverse(self)
-                .expect("all variants of DataType are in the map")
-                .as_ref()
-        */
-        todo!()
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Array => "ARRAY",
+            Self::Bool => "BOOL",
+            Self::Bytes => "BYTES",
+            Self::Date => "DATE",
+            Self::Float32 => "FLOAT32",
+            Self::Float64 => "FLOAT64",
+            Self::Int64 => "INT64",
+            Self::Json => "JSON",
+            Self::Numeric => "NUMERIC",
+            Self::String => "STRING",
+            Self::Struct => "STRUCT",
+            Self::Timestamp => "TIMESTAMP",
+        }
     }
 }
 
@@ -214,115 +215,206 @@ impl Ord for Keyword {
     }
 }
 
-/*
-static KW_MAPPING: Mapping<94, UniCase<&'static str>, Keyword> = crate::map::mapping! {
-    "ALL" => Keyword::All,
-    "AND" => Keyword::And,
-    "ANY" => Keyword::Any,
-    "ARRAY" => Keyword::Array,
-    "AS" => Keyword::As,
-    "ASC" => Keyword::Asc,
-    "ASSERT_ROWS_MODIFIED" => Keyword::AssertRowsModified,
-    "AT" => Keyword::At,
-    "BETWEEN" => Keyword::Between,
-    "BY" => Keyword::By,
-    "CASE" => Keyword::Case,
-    "CAST" => Keyword::Cast,
-    "COLLATE" => Keyword::Collate,
-    "CONTAINS" => Keyword::Contains,
-    "CREATE" => Keyword::Create,
-    "CROSS" => Keyword::Cross,
-    "CUBE" => Keyword::Cube,
-    "CURRENT" => Keyword::Current,
-    "DEFAULT" => Keyword::Default,
-    "DEFINE" => Keyword::Define,
-    "DESC" => Keyword::Desc,
-    "DISTINCT" => Keyword::Distinct,
-    "ELSE" => Keyword::Else,
-    "END" => Keyword::End,
-    "ENUM" => Keyword::Enum,
-    "ESCAPE" => Keyword::Escape,
-    "EXCEPT" => Keyword::Except,
-    "EXCLUDE" => Keyword::Exclude,
-    "EXISTS" => Keyword::Exists,
-    "EXTRACT" => Keyword::Extract,
-    "FALSE" => Keyword::False,
-    "FETCH" => Keyword::Fetch,
-    "FOLLOWING" => Keyword::Following,
-    "FOR" => Keyword::For,
-    "FROM" => Keyword::From,
-    "GROUP" => Keyword::Group,
-    "GROUPING" => Keyword::Grouping,
-    "GROUPS" => Keyword::Groups,
-    "HASH" => Keyword::Hash,
-    "HAVING" => Keyword::Having,
-    "IF" => Keyword::If,
-    "IGNORE" => Keyword::Ignore,
-    "IN" => Keyword::In,
-    "INNER" => Keyword::Inner,
-    "INTERSECT" => Keyword::Intersect,
-    "INTERVAL" => Keyword::Interval,
-    "INTO" => Keyword::Into,
-    "IS" => Keyword::Is,
-    "JOIN" => Keyword::Join,
-    "LATERAL" => Keyword::Lateral,
-    "LEFT" => Keyword::Left,
-    "LIKE" => Keyword::Like,
-    "LIMIT" => Keyword::Limit,
-    "LOOKUP" => Keyword::Lookup,
-    "MERGE" => Keyword::Merge,
-    "NATURAL" => Keyword::Natural,
-    "NEW" => Keyword::New,
-    "NO" => Keyword::No,
-    "NOT" => Keyword::Not,
-    "NULL" => Keyword::Null,
-    "NULLS" => Keyword::Nulls,
-    "OF" => Keyword::Of,
-    "ON" => Keyword::On,
-    "OR" => Keyword::Or,
-    "ORDER" => Keyword::Order,
-    "OUTER" => Keyword::Outer,
-    "OVER" => Keyword::Over,
-    "PARTITION" => Keyword::Partition,
-    "PRECEDING" => Keyword::Preceding,
-    "PROTO" => Keyword::Proto,
-    "RANGE" => Keyword::Range,
-    "RECURSIVE" => Keyword::Recursive,
-    "RESPECT" => Keyword::Respect,
-    "RIGHT" => Keyword::Right,
-    "ROLLUP" => Keyword::Rollup,
-    "ROWS" => Keyword::Rows,
-    "SELECT" => Keyword::Select,
-    "SET" => Keyword::Set,
-    "SOME" => Keyword::Some,
-    "STRUCT" => Keyword::Struct,
-    "TABLESAMPLE" => Keyword::Tablesample,
-    "THEN" => Keyword::Then,
-    "TO" => Keyword::To,
-    "TREAT" => Keyword::Treat,
-    "TRUE" => Keyword::True,
-    "UNBOUNDED" => Keyword::Unbounded,
-    "UNION" => Keyword::Union,
-    "UNNEST" => Keyword::Unnest,
-    "USING" => Keyword::Using,
-    "WHEN" => Keyword::When,
-    "WHERE" => Keyword::Where,
-    "WINDOW" => Keyword::Window,
-    "WITH" => Keyword::With,
-    "WITHIN" => Keyword::Within,
+static KW_MAPPING: phf::Map<UniCase<&'static str>, Keyword> = phf::phf_map! {
+    UniCase::ascii("ALL") => Keyword::All,
+    UniCase::ascii("AND") => Keyword::And,
+    UniCase::ascii("ANY") => Keyword::Any,
+    UniCase::ascii("ARRAY") => Keyword::Array,
+    UniCase::ascii("AS") => Keyword::As,
+    UniCase::ascii("ASC") => Keyword::Asc,
+    UniCase::ascii("ASSERT_ROWS_MODIFIED") => Keyword::AssertRowsModified,
+    UniCase::ascii("AT") => Keyword::At,
+    UniCase::ascii("BETWEEN") => Keyword::Between,
+    UniCase::ascii("BY") => Keyword::By,
+    UniCase::ascii("CASE") => Keyword::Case,
+    UniCase::ascii("CAST") => Keyword::Cast,
+    UniCase::ascii("COLLATE") => Keyword::Collate,
+    UniCase::ascii("CONTAINS") => Keyword::Contains,
+    UniCase::ascii("CREATE") => Keyword::Create,
+    UniCase::ascii("CROSS") => Keyword::Cross,
+    UniCase::ascii("CUBE") => Keyword::Cube,
+    UniCase::ascii("CURRENT") => Keyword::Current,
+    UniCase::ascii("DEFAULT") => Keyword::Default,
+    UniCase::ascii("DEFINE") => Keyword::Define,
+    UniCase::ascii("DESC") => Keyword::Desc,
+    UniCase::ascii("DISTINCT") => Keyword::Distinct,
+    UniCase::ascii("ELSE") => Keyword::Else,
+    UniCase::ascii("END") => Keyword::End,
+    UniCase::ascii("ENUM") => Keyword::Enum,
+    UniCase::ascii("ESCAPE") => Keyword::Escape,
+    UniCase::ascii("EXCEPT") => Keyword::Except,
+    UniCase::ascii("EXCLUDE") => Keyword::Exclude,
+    UniCase::ascii("EXISTS") => Keyword::Exists,
+    UniCase::ascii("EXTRACT") => Keyword::Extract,
+    UniCase::ascii("FALSE") => Keyword::False,
+    UniCase::ascii("FETCH") => Keyword::Fetch,
+    UniCase::ascii("FOLLOWING") => Keyword::Following,
+    UniCase::ascii("FOR") => Keyword::For,
+    UniCase::ascii("FROM") => Keyword::From,
+    UniCase::ascii("FULL") => Keyword::Full,
+    UniCase::ascii("GROUP") => Keyword::Group,
+    UniCase::ascii("GROUPING") => Keyword::Grouping,
+    UniCase::ascii("GROUPS") => Keyword::Groups,
+    UniCase::ascii("HASH") => Keyword::Hash,
+    UniCase::ascii("HAVING") => Keyword::Having,
+    UniCase::ascii("IF") => Keyword::If,
+    UniCase::ascii("IGNORE") => Keyword::Ignore,
+    UniCase::ascii("IN") => Keyword::In,
+    UniCase::ascii("INNER") => Keyword::Inner,
+    UniCase::ascii("INTERSECT") => Keyword::Intersect,
+    UniCase::ascii("INTERVAL") => Keyword::Interval,
+    UniCase::ascii("INTO") => Keyword::Into,
+    UniCase::ascii("IS") => Keyword::Is,
+    UniCase::ascii("JOIN") => Keyword::Join,
+    UniCase::ascii("LATERAL") => Keyword::Lateral,
+    UniCase::ascii("LEFT") => Keyword::Left,
+    UniCase::ascii("LIKE") => Keyword::Like,
+    UniCase::ascii("LIMIT") => Keyword::Limit,
+    UniCase::ascii("LOOKUP") => Keyword::Lookup,
+    UniCase::ascii("MERGE") => Keyword::Merge,
+    UniCase::ascii("NATURAL") => Keyword::Natural,
+    UniCase::ascii("NEW") => Keyword::New,
+    UniCase::ascii("NO") => Keyword::No,
+    UniCase::ascii("NOT") => Keyword::Not,
+    UniCase::ascii("NULL") => Keyword::Null,
+    UniCase::ascii("NULLS") => Keyword::Nulls,
+    UniCase::ascii("OF") => Keyword::Of,
+    UniCase::ascii("ON") => Keyword::On,
+    UniCase::ascii("OR") => Keyword::Or,
+    UniCase::ascii("ORDER") => Keyword::Order,
+    UniCase::ascii("OUTER") => Keyword::Outer,
+    UniCase::ascii("OVER") => Keyword::Over,
+    UniCase::ascii("PARTITION") => Keyword::Partition,
+    UniCase::ascii("PRECEDING") => Keyword::Preceding,
+    UniCase::ascii("PROTO") => Keyword::Proto,
+    UniCase::ascii("RANGE") => Keyword::Range,
+    UniCase::ascii("RECURSIVE") => Keyword::Recursive,
+    UniCase::ascii("RESPECT") => Keyword::Respect,
+    UniCase::ascii("RIGHT") => Keyword::Right,
+    UniCase::ascii("ROLLUP") => Keyword::Rollup,
+    UniCase::ascii("ROWS") => Keyword::Rows,
+    UniCase::ascii("SELECT") => Keyword::Select,
+    UniCase::ascii("SET") => Keyword::Set,
+    UniCase::ascii("SOME") => Keyword::Some,
+    UniCase::ascii("STRUCT") => Keyword::Struct,
+    UniCase::ascii("TABLESAMPLE") => Keyword::Tablesample,
+    UniCase::ascii("THEN") => Keyword::Then,
+    UniCase::ascii("TO") => Keyword::To,
+    UniCase::ascii("TREAT") => Keyword::Treat,
+    UniCase::ascii("TRUE") => Keyword::True,
+    UniCase::ascii("UNBOUNDED") => Keyword::Unbounded,
+    UniCase::ascii("UNION") => Keyword::Union,
+    UniCase::ascii("UNNEST") => Keyword::Unnest,
+    UniCase::ascii("USING") => Keyword::Using,
+    UniCase::ascii("WHEN") => Keyword::When,
+    UniCase::ascii("WHERE") => Keyword::Where,
+    UniCase::ascii("WINDOW") => Keyword::Window,
+    UniCase::ascii("WITH") => Keyword::With,
+    UniCase::ascii("WITHIN") => Keyword::Within,
 };
-*/
 impl Keyword {
     pub fn as_str(&self) -> &'static str {
-        todo!()
-        //KW_MAPPING
-        //   .get_reverse(self)
-        //   .expect("KW_MAPPING contains all variants of Keyword")
-        //   .as_ref()
+        match self {
+            Self::All => "ALL",
+            Self::And => "AND",
+            Self::Any => "ANY",
+            Self::Array => "ARRAY",
+            Self::As => "AS",
+            Self::Asc => "ASC",
+            Self::AssertRowsModified => "ASSERT_ROWS_MODIFIED",
+            Self::At => "AT",
+            Self::Between => "BETWEEN",
+            Self::By => "BY",
+            Self::Case => "CASE",
+            Self::Cast => "CAST",
+            Self::Collate => "COLLATE",
+            Self::Contains => "CONTAINS",
+            Self::Create => "CREATE",
+            Self::Cross => "CROSS",
+            Self::Cube => "CUBE",
+            Self::Current => "CURRENT",
+            Self::Default => "DEFAULT",
+            Self::Define => "DEFINE",
+            Self::Desc => "DESC",
+            Self::Distinct => "DISTINCT",
+            Self::Else => "ELSE",
+            Self::End => "END",
+            Self::Enum => "ENUM",
+            Self::Escape => "ESCAPE",
+            Self::Except => "EXCEPT",
+            Self::Exclude => "EXCLUDE",
+            Self::Exists => "EXISTS",
+            Self::Extract => "EXTRACT",
+            Self::False => "FALSE",
+            Self::Fetch => "FETCH",
+            Self::Following => "FOLLOWING",
+            Self::For => "FOR",
+            Self::From => "FROM",
+            Self::Full => "FULL",
+            Self::Group => "GROUP",
+            Self::Grouping => "GROUPING",
+            Self::Groups => "GROUPS",
+            Self::Hash => "HASH",
+            Self::Having => "HAVING",
+            Self::If => "IF",
+            Self::Ignore => "IGNORE",
+            Self::In => "IN",
+            Self::Inner => "INNER",
+            Self::Intersect => "INTERSECT",
+            Self::Interval => "INTERVAL",
+            Self::Into => "INTO",
+            Self::Is => "IS",
+            Self::Join => "JOIN",
+            Self::Lateral => "LATERAL",
+            Self::Left => "LEFT",
+            Self::Like => "LIKE",
+            Self::Limit => "LIMIT",
+            Self::Lookup => "LOOKUP",
+            Self::Merge => "MERGE",
+            Self::Natural => "NATURAL",
+            Self::New => "NEW",
+            Self::No => "NO",
+            Self::Not => "NOT",
+            Self::Null => "NULL",
+            Self::Nulls => "NULLS",
+            Self::Of => "OF",
+            Self::On => "ON",
+            Self::Or => "OR",
+            Self::Order => "ORDER",
+            Self::Outer => "OUTER",
+            Self::Over => "OVER",
+            Self::Partition => "PARTITION",
+            Self::Preceding => "PRECEDING",
+            Self::Proto => "PROTO",
+            Self::Range => "RANGE",
+            Self::Recursive => "RECURSIVE",
+            Self::Respect => "RESPECT",
+            Self::Right => "RIGHT",
+            Self::Rollup => "ROLLUP",
+            Self::Rows => "ROWS",
+            Self::Select => "SELECT",
+            Self::Set => "SET",
+            Self::Some => "SOME",
+            Self::Struct => "STRUCT",
+            Self::Tablesample => "TABLESAMPLE",
+            Self::Then => "THEN",
+            Self::To => "TO",
+            Self::Treat => "TREAT",
+            Self::True => "TRUE",
+            Self::Unbounded => "UNBOUNDED",
+            Self::Union => "UNION",
+            Self::Unnest => "UNNEST",
+            Self::Using => "USING",
+            Self::When => "WHEN",
+            Self::Where => "WHERE",
+            Self::Window => "WINDOW",
+            Self::With => "WITH",
+            Self::Within => "WITHIN",
+        }
     }
 
-    pub(crate) fn from_str(_s: &str) -> Option<Self> {
-        // KW_MAPPING.get(s).copied()
-        todo!()
+    #[allow(clippy::should_implement_trait)]
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
+        KW_MAPPING.get(&UniCase::ascii(s)).copied()
     }
 }
@@ -1,6 +1,6 @@
 use crate::ast::UnexpectedToken;
 use crate::ast::common::ident::InvalidIdent;
-use crate::tokens::TokenizerError;
+use crate::tokens::{Span, TokenizerError};
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum Error<'src> {
@@ -8,8 +8,26 @@ pub enum Error<'src> {
     Tokenizer(TokenizerError<'src>),
     #[error(transparent)]
     UnexpectedToken(UnexpectedToken<'src>),
-    #[error(transparent)]
-    InvalidIdent(InvalidIdent<'src>),
+    #[error("{error} (at {span})")]
+    InvalidIdent {
+        span: Span,
+        error: InvalidIdent<'src>,
+    },
+}
+
+impl Error<'_> {
+    /// The span in the SQL string that caused the error. Tokenizer errors point at a single
+    /// location, so the span will be empty.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Tokenizer(error) => Span {
+                start: error.location(),
+                len: 0,
+            },
+            Self::UnexpectedToken(error) => error.span(),
+            Self::InvalidIdent { span, .. } => *span,
+        }
+    }
 }
 
 impl<'src> From<UnexpectedToken<'src>> for Error<'src> {
@@ -23,9 +41,3 @@ impl<'src> From<TokenizerError<'src>> for Error<'src> {
         Self::Tokenizer(value)
     }
 }
-
-impl<'src> From<InvalidIdent<'src>> for Error<'src> {
-    fn from(value: InvalidIdent<'src>) -> Self {
-        Self::InvalidIdent(value)
-    }
-}
@@ -6,12 +6,15 @@
 //! # 1: Tokenize text
 //! # 2: Assemble AST
 //! # 3: Evaluate (TODO)
-#![feature(pattern, array_try_from_fn, result_flattening)]
+#![feature(pattern)]
 
 pub mod ast;
 pub mod error;
 // mod map;
+pub mod non_empty_vec;
 pub mod tokens;
+// only used by the evaluation pass, which is still a TODO.
+#[allow(dead_code)]
 pub mod types;
 
 pub use error::Error;
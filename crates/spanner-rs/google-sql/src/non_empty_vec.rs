@@ -0,0 +1,70 @@
+use std::ops::Deref;
+
+/// A [`Vec`] that always contains at least 1 element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NonEmptyVec<T> {
+    inner: Vec<T>,
+}
+
+impl<T> NonEmptyVec<T> {
+    pub fn new(first: T) -> Self {
+        Self { inner: vec![first] }
+    }
+
+    pub fn from_parts(first: T, rest: Vec<T>) -> Self {
+        let mut inner = Vec::with_capacity(rest.len() + 1);
+        inner.push(first);
+        inner.extend(rest);
+        Self { inner }
+    }
+
+    pub fn from_vec(vec: Vec<T>) -> Option<Self> {
+        if vec.is_empty() {
+            None
+        } else {
+            Some(Self { inner: vec })
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.inner.push(item);
+    }
+
+    pub fn first(&self) -> &T {
+        &self.inner[0]
+    }
+
+    pub fn last(&self) -> &T {
+        &self.inner[self.inner.len() - 1]
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T> Deref for NonEmptyVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> IntoIterator for NonEmptyVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a NonEmptyVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
@@ -7,29 +7,29 @@ pub use tokenizer::{Peek, Tokenizer, TokenizerError};
 
 pub(crate) use self::tokenizer::LookaheadIter;
 use crate::Error;
+use crate::ast::UnexpectedToken;
 use crate::ast::reserved::{DataType, Keyword};
 
 pub(crate) trait TokenizerKind<'src>:
     Iterator<Item = Result<(Span, Token<'src>), TokenizerError<'src>>>
 {
     fn next_or_eof(&mut self) -> Result<(Span, Token<'src>), TokenizerError<'src>> {
-        self.next().ok_or(TokenizerError::UnexpectedEof).flatten()
+        let location = self.eof_location();
+        self.next()
+            .ok_or(TokenizerError::UnexpectedEof { location })
+            .flatten()
     }
 
     fn peek(&mut self) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>>;
 
-    fn peek_or_eof(&mut self) -> Result<(Span, Token<'src>), TokenizerError<'src>> {
-        self.peek()?.ok_or(TokenizerError::UnexpectedEof)
-    }
+    /// Peeks 'n' tokens ahead of the next token. 'peek_nth(0)' is equivalent to 'peek()'.
+    fn peek_nth(&mut self, n: usize) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>>;
 
-    fn parse_from_token<T: crate::ast::FromToken<'src>>(
-        &mut self,
-    ) -> Result<Option<T>, Error<'src>> {
-        match self.peek()? {
-            Some((span, token)) => T::from_token(span, token).map(Some),
-            None => Ok(None),
-        }
-    }
+    /// The location right after the last token.
+    fn eof_location(&self) -> Location;
+
+    /// Returns the raw source covered by a span.
+    fn source(&self, span: Span) -> &'src str;
 
     fn next_if<F>(&mut self, predicate: F) -> Result<bool, TokenizerError<'src>>
     where
@@ -61,6 +61,125 @@ pub(crate) trait TokenizerKind<'src>:
     }
 
     fn lookahead_iter(&mut self) -> LookaheadIter<'_, 'src>;
+
+    /// Like [`TokenizerKind::next_or_eof`], but returns an [`UnexpectedToken`] error describing
+    /// what was expected if there are no more tokens.
+    ///
+    /// [`UnexpectedToken`]: crate::ast::UnexpectedToken
+    fn next_or_expected(
+        &mut self,
+        expected: &'static str,
+    ) -> Result<(Span, Token<'src>), Error<'src>> {
+        match self.next() {
+            Some(result) => Ok(result?),
+            None => Err(UnexpectedToken::eof(self.eof_location(), expected).into()),
+        }
+    }
+
+    fn peek_nth_is_keyword(&mut self, n: usize, kw: Keyword) -> Result<bool, TokenizerError<'src>> {
+        Ok(self
+            .peek_nth(n)?
+            .is_some_and(|(_, token)| token.is_keyword(kw)))
+    }
+
+    fn peek_is_keyword(&mut self, kw: Keyword) -> Result<bool, TokenizerError<'src>> {
+        self.peek_nth_is_keyword(0, kw)
+    }
+
+    fn peek_nth_is_word(&mut self, n: usize, word: &str) -> Result<bool, TokenizerError<'src>> {
+        Ok(self
+            .peek_nth(n)?
+            .is_some_and(|(_, token)| token.is_word(word)))
+    }
+
+    fn peek_is_word(&mut self, word: &str) -> Result<bool, TokenizerError<'src>> {
+        self.peek_nth_is_word(0, word)
+    }
+
+    fn peek_nth_is_punct(
+        &mut self,
+        n: usize,
+        punct: PunctOrOp,
+    ) -> Result<bool, TokenizerError<'src>> {
+        Ok(self
+            .peek_nth(n)?
+            .is_some_and(|(_, token)| token.as_punct_or_opt() == Some(punct)))
+    }
+
+    fn peek_is_punct(&mut self, punct: PunctOrOp) -> Result<bool, TokenizerError<'src>> {
+        self.peek_nth_is_punct(0, punct)
+    }
+
+    fn next_if_kw(&mut self, kw: Keyword) -> Result<bool, TokenizerError<'src>> {
+        self.next_if(|_, token| token.is_keyword(kw))
+    }
+
+    fn next_if_word(&mut self, word: &str) -> Result<bool, TokenizerError<'src>> {
+        self.next_if(|_, token| token.is_word(word))
+    }
+
+    fn next_if_punct_op(&mut self, punct: PunctOrOp) -> Result<bool, TokenizerError<'src>> {
+        self.next_if(|_, token| token.as_punct_or_opt() == Some(punct))
+    }
+
+    fn expect_kw(&mut self, kw: Keyword) -> Result<Span, Error<'src>> {
+        let (span, token) = self.next_or_expected(kw.as_str())?;
+
+        if token.is_keyword(kw) {
+            Ok(span)
+        } else {
+            Err(UnexpectedToken::new_expected(span, token, kw.as_str()).into())
+        }
+    }
+
+    fn expect_word(&mut self, word: &'static str) -> Result<Span, Error<'src>> {
+        let (span, token) = self.next_or_expected(word)?;
+
+        if token.is_word(word) {
+            Ok(span)
+        } else {
+            Err(UnexpectedToken::new_expected(span, token, word).into())
+        }
+    }
+
+    fn expect_punct(
+        &mut self,
+        punct: PunctOrOp,
+        expected: &'static str,
+    ) -> Result<Span, Error<'src>> {
+        let (span, token) = self.next_or_expected(expected)?;
+
+        if token.as_punct_or_opt() == Some(punct) {
+            Ok(span)
+        } else {
+            Err(UnexpectedToken::new_expected(span, token, expected).into())
+        }
+    }
+
+    /// If the next token is wrapped in parentheses, consumes it and returns a tokenizer over the
+    /// inner tokens.
+    fn next_if_parens(&mut self) -> Result<Option<Tokenizer<'src>>, TokenizerError<'src>> {
+        self.next_if_some(|span, token| token.build_parens_parser(span.start))
+    }
+
+    fn expect_parens(&mut self, expected: &'static str) -> Result<Tokenizer<'src>, Error<'src>> {
+        let (span, token) = self.next_or_expected(expected)?;
+
+        token
+            .build_parens_parser(span.start)
+            .ok_or_else(|| UnexpectedToken::new_expected(span, token, expected).into())
+    }
+
+    /// Ensures there are no tokens left. Used to check that nested tokenizers are fully consumed.
+    fn expect_end(&mut self, expected: &'static str) -> Result<(), Error<'src>> {
+        match self.next() {
+            Some(result) => {
+                let (span, token) = result?;
+                Err(UnexpectedToken::new_expected(span, token, expected).into())
+            }
+            None => Ok(()),
+        }
+    }
 }
 
 impl<'src, T> TokenizerKind<'src> for &mut T
@@ -71,6 +190,18 @@ where
         T::peek(self)
     }
 
+    fn peek_nth(&mut self, n: usize) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
+        T::peek_nth(self, n)
+    }
+
+    fn eof_location(&self) -> Location {
+        T::eof_location(self)
+    }
+
+    fn source(&self, span: Span) -> &'src str {
+        T::source(self, span)
+    }
+
     fn lookahead_iter(&mut self) -> LookaheadIter<'_, 'src> {
         T::lookahead_iter(self)
     }
@@ -81,6 +212,18 @@ impl<'src> TokenizerKind<'src> for Tokenizer<'src> {
         self.peek_unpack()
     }
 
+    fn peek_nth(&mut self, n: usize) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
+        Tokenizer::peek_nth(self, n)
+    }
+
+    fn eof_location(&self) -> Location {
+        Tokenizer::eof_location(self)
+    }
+
+    fn source(&self, span: Span) -> &'src str {
+        Tokenizer::source(self, span)
+    }
+
     fn lookahead_iter(&mut self) -> LookaheadIter<'_, 'src> {
         self.lookahead_iter()
     }
@@ -88,7 +231,19 @@ impl<'src> TokenizerKind<'src> for Tokenizer<'src> {
 
 impl<'src> TokenizerKind<'src> for LookaheadIter<'_, 'src> {
     fn peek(&mut self) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
-        self.peek_nth(0)
+        LookaheadIter::peek_nth(self, 0)
+    }
+
+    fn peek_nth(&mut self, n: usize) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
+        LookaheadIter::peek_nth(self, n)
+    }
+
+    fn eof_location(&self) -> Location {
+        LookaheadIter::eof_location(self)
+    }
+
+    fn source(&self, span: Span) -> &'src str {
+        LookaheadIter::source(self, span)
     }
 
     fn lookahead_iter(&mut self) -> LookaheadIter<'_, 'src> {
@@ -134,6 +289,13 @@ impl Location {
     }
 }
 
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // lines and columns are 0 indexed internally, but editors start at 1
+        write!(f, "line {}, column {}", self.line + 1, self.column + 1)
+    }
+}
+
 /// A [`Location`], covering a span of bytes within a SQL string.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Span {
@@ -143,6 +305,26 @@ pub struct Span {
     pub len: usize,
 }
 
+impl Span {
+    /// Builds a span that starts at the start of 'self', and ends at the end of 'other'.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start,
+            len: (other.start.offset + other.len).saturating_sub(self.start.offset),
+        }
+    }
+
+    pub(crate) fn end_offset(&self) -> usize {
+        self.start.offset + self.len
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.start.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct StringLiteral<'a> {
     modifier: Option<QuotedModifier>,
@@ -164,6 +346,13 @@ impl<'a> StringLiteral<'a> {
     }
 }
 
+impl fmt::Display for StringLiteral<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the raw literal still has the modifier and quotes, so it can be written back as is
+        f.write_str(self.raw_literal)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum Token<'a> {
     /// An unquoted sequence of characters. Could be a keyword, identifier, numeric literal, etc.
@@ -175,7 +364,7 @@ pub enum Token<'a> {
     StringLiteral(StringLiteral<'a>),
     /// A numeric literal
     NumericLiteral(&'a str),
-    /// A Google-SQL hint, in the format '@{ ... }'
+    /// A Google-SQL hint, in the format '@{ ... }'. Includes the leading '@' and the braces.
     Hint(&'a str),
     /// A named query parameter, prefixed with '@'.
     QueryParameter(&'a str),
@@ -225,16 +414,14 @@ fn build_nested_tokenizer<'src>(
     closing: char,
     mut location: Location,
 ) -> Tokenizer<'src> {
-    let no_leading = nested_expr.trim_start_matches(opening);
-
-    let delta_len = nested_expr.len() - no_leading.len();
+    let no_leading = nested_expr
+        .strip_prefix(opening)
+        .expect("nested expression should start with the opening delimiter");
 
-    debug_assert!(delta_len > 0, "no leading characters stripped?");
+    let no_wrapping = no_leading.strip_suffix(closing).unwrap_or(no_leading);
 
-    location.offset -= delta_len;
-    location.column -= delta_len / opening.len_utf8();
-
-    let no_wrapping = no_leading.trim_end_matches(closing);
+    // the nested tokenizer starts right after the opening delimiter
+    location.advance_by(opening);
 
     Tokenizer::new_from_location(location, no_wrapping)
 }
@@ -267,16 +454,32 @@ impl<'a> Token<'a> {
         }
     }
 
+    /// Checks if this is an unquoted word, ignoring case. Used for non-reserved keywords (i.e
+    /// 'TABLE', 'INDEX', etc), which can also be used as identifiers.
+    pub fn is_word(&self, word: &str) -> bool {
+        match self.as_unquoted() {
+            Some(unquoted) => unquoted.eq_ignore_ascii_case(word),
+            None => false,
+        }
+    }
+
     pub fn build_parens_parser(&self, location: Location) -> Option<Tokenizer<'a>> {
         let sub = self.as_parens()?;
 
         Some(build_nested_tokenizer(sub, '(', ')', location))
     }
 
+    pub fn build_hint_parser(&self, mut location: Location) -> Option<Tokenizer<'a>> {
+        let sub = self.as_hint()?.strip_prefix('@')?;
+        location.advance_by('@');
+
+        Some(build_nested_tokenizer(sub, '{', '}', location))
+    }
+
     pub fn build_square_brackets_parser(&self, location: Location) -> Option<Tokenizer<'a>> {
         let sub = self.as_square_brackets()?;
 
-        Some(build_nested_tokenizer(sub, '(', ')', location))
+        Some(build_nested_tokenizer(sub, '[', ']', location))
     }
 
     fn str_eq_inner(&self, s: &str) -> bool {
@@ -341,10 +544,6 @@ pub enum Quote {
 }
 
 impl Quote {
-    fn as_char(&self) -> char {
-        *self as u8 as char
-    }
-
     // these 2 functions are to make use with QuoteFormat nicer,
     // without needing to do on-the-fly character encoding.
     // if there was more than 2 options, maybe, but this is simpler.
@@ -362,10 +561,6 @@ impl Quote {
         }
     }
 
-    fn from_leading(s: &str) -> Option<Self> {
-        s.chars().next().and_then(Self::from_char)
-    }
-
     pub(super) fn from_char(ch: char) -> Option<Self> {
         match ch {
             '\'' => Some(Self::Single),
@@ -386,27 +581,6 @@ pub enum QuoteFormat {
 }
 
 impl QuoteFormat {
-    fn len(&self) -> usize {
-        match self {
-            Self::Backticks | Self::Single(_) => 1,
-            Self::Triple(_) => 3,
-        }
-    }
-    fn from_leading(s: &str) -> Option<Self> {
-        let quote = match s.chars().next()? {
-            '\'' => Quote::Single,
-            '\"' => Quote::Double,
-            '`' => return Some(Self::Backticks),
-            _ => return None,
-        };
-
-        if s.starts_with(quote.as_triple_str()) {
-            Some(Self::Triple(quote))
-        } else {
-            Some(Self::Single(quote))
-        }
-    }
-
     fn as_str(&self) -> &'static str {
         match self {
             Self::Backticks => "`",
@@ -414,10 +588,6 @@ impl QuoteFormat {
             Self::Triple(quote) => quote.as_triple_str(),
         }
     }
-
-    fn prefixes(&self, s: &str) -> bool {
-        s.starts_with(self.as_str())
-    }
 }
 
 impl fmt::Display for Quote {
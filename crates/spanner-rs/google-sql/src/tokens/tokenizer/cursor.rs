@@ -14,17 +14,22 @@ pub(super) struct StreamCursor<'src, 'tok> {
 }
 
 impl<'src, 'tok> StreamCursor<'src, 'tok> {
-    pub(super) fn reset(&mut self) {
-        *self.current = self.start;
-        self.iter = self.remainder.char_indices().peekable();
-    }
-
     pub(super) fn is_escaped(&self) -> bool {
         let Some(prev) = self.relative_offset().checked_sub(1) else {
             return false;
         };
 
-        self.remainder.is_char_boundary(prev) && self.remainder[prev..].chars().next() == Some('\\')
+        self.remainder.is_char_boundary(prev) && self.remainder[prev..].starts_with('\\')
+    }
+
+    /// The location of the start of the token being parsed.
+    pub(super) fn start(&self) -> Location {
+        self.start
+    }
+
+    /// The current location of the cursor.
+    pub(super) fn location(&self) -> Location {
+        *self.current
     }
 
     pub(super) fn unadvanced_remainder(&self) -> &'src str {
@@ -51,16 +56,10 @@ impl<'src, 'tok> StreamCursor<'src, 'tok> {
 
         let mut count = 0;
 
-        loop {
-            match self.next() {
-                Some((_, ch)) => {
-                    count += ch.len_utf8();
-                    if bytes <= count {
-                        break;
-                    }
-                }
-                None => break,
-            }
+        while count < bytes
+            && let Some((_, ch)) = self.next()
+        {
+            count += ch.len_utf8();
         }
     }
 
@@ -142,51 +141,7 @@ impl<'src, 'tok> StreamCursor<'src, 'tok> {
     where
         I: IntoIterator<Item = (char, T)>,
     {
-        self.first_next_if_eq(options.into_iter())
-            .unwrap_or(default)
-    }
-
-    pub(super) fn advance_to_offset(&mut self, offset: usize) {
-        // if we're already here, neat
-        if self.relative_offset() == offset {
-            return;
-        }
-
-        // if the offset is past the end of the string, return (panic in debug)
-        if self.remainder.len() < offset {
-            #[cfg(debug_assertions)]
-            {
-                panic!(
-                    "offset of {offset} is past the end of the string (len = {})",
-                    self.remainder.len()
-                );
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                return;
-            }
-        }
-
-        assert!(
-            self.remainder.is_char_boundary(offset),
-            "cant offset to a non-char boundary"
-        );
-
-        // reset if we're already past the offset for some reason.
-        if self.relative_offset() > offset {
-            self.reset();
-        }
-
-        self.current
-            .advance_by_str(&self.remainder[self.relative_offset()..offset]);
-
-        self.iter = self.remainder[offset..].char_indices().peekable();
-    }
-
-    fn advance_by(&mut self, n_chars: usize) {
-        for _ in 0..n_chars {
-            self.next();
-        }
+        self.first_next_if_eq(options).unwrap_or(default)
     }
 
     pub(super) fn new(remainder: &'src str, current: &'tok mut Location) -> Self {
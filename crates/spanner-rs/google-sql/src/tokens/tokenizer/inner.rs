@@ -7,31 +7,34 @@ use crate::tokens::{
 #[derive(Debug, Clone, PartialEq)]
 pub(super) struct InnerTokenizer<'src> {
     raw: &'src str,
+    /// The offset of the first character in 'raw', relative to the entire SQL string.
+    start_offset: usize,
     current: Location,
 }
 
 impl<'src> InnerTokenizer<'src> {
-    #[inline]
+    #[cfg(test)]
     pub const fn new(sql: &'src str) -> Self {
-        Self {
-            raw: sql,
-            current: Location {
+        Self::new_from_location(
+            Location {
                 offset: 0,
                 line: 0,
                 column: 0,
             },
-        }
+            sql,
+        )
     }
 
     pub(super) const fn new_from_location(location: Location, sub_sql: &'src str) -> Self {
         Self {
             raw: sub_sql,
+            start_offset: location.offset,
             current: location,
         }
     }
 
     fn remainder(&self) -> Option<&'src str> {
-        self.raw.get(self.current.offset..)
+        self.raw.get(self.current.offset - self.start_offset..)
     }
 
     fn move_past_whitespace(&mut self) -> Option<StreamCursor<'src, '_>> {
@@ -53,14 +56,33 @@ impl<'src> InnerTokenizer<'src> {
 
 #[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
 pub enum TokenizerError<'a> {
-    #[error("invalid leading char found in sql: {0}")]
-    InvalidChar(char),
-    #[error("unbalanced delimiters, no '{expected}' found in '{rem}'")]
-    Unbalanced { rem: &'a str, expected: char },
-    #[error("unexpected EOF")]
-    UnexpectedEof,
-    #[error("no closing quote ({0})")]
-    NoClosingQuote(QuoteFormat),
+    #[error("invalid leading char '{ch}' found at {location}")]
+    InvalidChar { ch: char, location: Location },
+    #[error("unbalanced delimiters at {location}, no '{expected}' found in '{rem}'")]
+    Unbalanced {
+        rem: &'a str,
+        expected: char,
+        location: Location,
+    },
+    #[error("unexpected EOF at {location}")]
+    UnexpectedEof { location: Location },
+    #[error("no closing quote ({format}) for the literal at {location}")]
+    NoClosingQuote {
+        format: QuoteFormat,
+        location: Location,
+    },
+}
+
+impl TokenizerError<'_> {
+    /// Where in the SQL string the error occured.
+    pub fn location(&self) -> Location {
+        match *self {
+            Self::InvalidChar { location, .. }
+            | Self::Unbalanced { location, .. }
+            | Self::UnexpectedEof { location }
+            | Self::NoClosingQuote { location, .. } => location,
+        }
+    }
 }
 
 struct RawStream<'src, 'tok> {
@@ -68,12 +90,23 @@ struct RawStream<'src, 'tok> {
 }
 
 impl<'src, 'tok> RawStream<'src, 'tok> {
-    fn find_end_of_numeric_literal(&mut self) -> Result<(Span, Token<'src>), TokenizerError<'src>> {
-        fn is_valid_numeric_char(ch: char) -> bool {
-            ch.is_numeric() || matches!(ch, 'E' | 'e' | '.')
+    fn find_end_of_numeric_literal(
+        &mut self,
+        leading: char,
+    ) -> Result<(Span, Token<'src>), TokenizerError<'src>> {
+        // hex literal, i.e '0x1F'
+        if leading == '0' && self.iter.next_if(|ch| matches!(ch, 'x' | 'X')) {
+            self.iter.next_while(|ch| ch.is_ascii_hexdigit());
+            return Ok(self.iter.build_pair(Token::NumericLiteral));
         }
 
-        self.iter.next_while(is_valid_numeric_char);
+        self.iter.next_while(|ch| ch.is_ascii_digit() || ch == '.');
+
+        // exponents can have a sign, i.e '1e-5'
+        if self.iter.next_if(|ch| matches!(ch, 'e' | 'E')) {
+            self.iter.next_if(|ch| matches!(ch, '+' | '-'));
+            self.iter.next_while(|ch| ch.is_ascii_digit());
+        }
 
         Ok(self.iter.build_pair(Token::NumericLiteral))
     }
@@ -86,7 +119,7 @@ impl<'src, 'tok> RawStream<'src, 'tok> {
         F: FnOnce(&'src str) -> Token<'src>,
     {
         fn is_valid_ident_char(ch: char) -> bool {
-            ch.is_alphanumeric() || matches!(ch, '_' | '-')
+            ch.is_alphanumeric() || ch == '_'
         }
 
         self.iter.next_while(is_valid_ident_char);
@@ -107,7 +140,9 @@ impl<'src, 'tok> RawStream<'src, 'tok> {
             self.iter.advance_bytes("*/".len());
             Ok(())
         } else {
-            Err(TokenizerError::UnexpectedEof)
+            Err(TokenizerError::UnexpectedEof {
+                location: self.iter.location(),
+            })
         }
     }
 
@@ -116,7 +151,10 @@ impl<'src, 'tok> RawStream<'src, 'tok> {
             self.iter.next();
             Ok(self.iter.build_pair(Token::QuotedIdentifier))
         } else {
-            Err(TokenizerError::NoClosingQuote(QuoteFormat::Backticks))
+            Err(TokenizerError::NoClosingQuote {
+                format: QuoteFormat::Backticks,
+                location: self.iter.start(),
+            })
         }
     }
 
@@ -132,9 +170,10 @@ impl<'src, 'tok> RawStream<'src, 'tok> {
         };
 
         let format = if leading.starts_with(quote.as_triple_str()) {
-            if let Some(move_bytes) = quote
-                .as_triple_str()
-                .len()
+            // move past the opening triple quote, accounting for any modifier chars.
+            let modifier_len = self.iter.unadvanced_remainder().len() - leading.len();
+
+            if let Some(move_bytes) = (modifier_len + quote.as_triple_str().len())
                 .checked_sub(self.iter.relative_offset())
             {
                 self.iter.advance_bytes(move_bytes);
@@ -155,7 +194,10 @@ impl<'src, 'tok> RawStream<'src, 'tok> {
                 })
             }))
         } else {
-            Err(TokenizerError::NoClosingQuote(format))
+            Err(TokenizerError::NoClosingQuote {
+                format,
+                location: self.iter.start(),
+            })
         }
     }
 
@@ -168,40 +210,72 @@ impl<'src, 'tok> RawStream<'src, 'tok> {
     where
         F: FnOnce(&'src str) -> Token<'src>,
     {
-        let mut balance = 1_u8;
-
-        let mut escaped = false;
+        // the opening character has already been consumed
+        let mut balance = 1_usize;
+
+        while let Some(ch) = self.iter.peek() {
+            match ch {
+                // skip over quoted sections and comments, since they can contain unbalanced
+                // delimiters.
+                '\'' | '"' | '`' => {
+                    self.iter.next();
+                    self.skip_quoted(ch)?;
+                }
+                '-' if self.iter.remainder().starts_with("--") => {
+                    self.move_past_inline_comment();
+                }
+                '/' if self.iter.remainder().starts_with("/*") => {
+                    self.iter.advance_bytes("/*".len());
+                    self.move_past_multiline_comment()?;
+                }
+                _ => {
+                    self.iter.next();
 
-        while let Some((_, ch)) = self.iter.next() {
-            if ch == '\\' {
-                escaped = true;
-                continue;
-            }
+                    if ch == opening {
+                        balance += 1;
+                    } else if ch == closing {
+                        balance -= 1;
 
-            if ch == opening && !escaped {
-                balance = balance.checked_add(1).expect("256 levels of nesting?");
-            } else if ch == closing && !escaped {
-                match balance.checked_sub(1) {
-                    Some(sub) => balance = sub,
-                    None => {
-                        // move past the closing character.
-                        self.iter.next();
-                        return Ok(self.iter.build_pair(to_token));
+                        if balance == 0 {
+                            return Ok(self.iter.build_pair(to_token));
+                        }
                     }
                 }
-            } else {
-                escaped = false;
             }
         }
 
         Err(TokenizerError::Unbalanced {
-            rem: self.iter.remainder(),
+            rem: self.iter.unadvanced_remainder(),
             expected: closing,
+            location: self.iter.start(),
         })
     }
 
-    pub fn next_char(&mut self) -> Option<char> {
-        self.iter.next().map(|(_, ch)| ch)
+    /// Skips past a quoted section, assuming the opening quote char has been consumed.
+    fn skip_quoted(&mut self, quote_char: char) -> Result<(), TokenizerError<'src>> {
+        let format = match Quote::from_char(quote_char) {
+            Some(quote)
+                if self
+                    .iter
+                    .remainder()
+                    .starts_with(&quote.as_triple_str()[1..]) =>
+            {
+                self.iter.advance_bytes(2);
+                QuoteFormat::Triple(quote)
+            }
+            Some(quote) => QuoteFormat::Single(quote),
+            None => QuoteFormat::Backticks,
+        };
+
+        if self.iter.move_to_next_unescaped(format.as_str()) {
+            self.iter.advance_bytes(format.as_str().len());
+            Ok(())
+        } else {
+            Err(TokenizerError::NoClosingQuote {
+                format,
+                location: self.iter.location(),
+            })
+        }
     }
 }
 
@@ -228,7 +302,11 @@ impl<'a> Iterator for InnerTokenizer<'a> {
             // array indexing
             '[' => Some(stream.find_closing_pair('[', ']', Token::SquareBrackets)),
             // A numeric literal
-            '0'..='9' => Some(stream.find_end_of_numeric_literal()),
+            '0'..='9' => Some(stream.find_end_of_numeric_literal(leading)),
+            // A float literal without a leading digit, i.e '.5'
+            '.' if stream.iter.peek().is_some_and(|ch| ch.is_ascii_digit()) => {
+                Some(stream.find_end_of_numeric_literal(leading))
+            }
             // A hint, or named query parameter
             '@' => Some(hint_or_query_param(&mut stream)),
             // StringLiteral
@@ -288,7 +366,9 @@ fn inline_comment_or_neg<'src>(
             .iter
             .build_pair(|_| Token::PunctOrOp(PunctOrOp::Neg))))
     } else {
-        Some(Err(TokenizerError::UnexpectedEof))
+        Some(Err(TokenizerError::UnexpectedEof {
+            location: stream.iter.location(),
+        }))
     }
 }
 
@@ -300,7 +380,9 @@ fn multiline_comment_or_div<'src>(
         Ok(None)
     } else if stream.iter.unadvanced_remainder().len() == 1 {
         // cant have a trailing '/', so ensure there's more in the string.
-        Err(TokenizerError::UnexpectedEof)
+        Err(TokenizerError::UnexpectedEof {
+            location: stream.iter.location(),
+        })
     } else {
         Ok(Some(
             stream.iter.build_pair(|_| Token::PunctOrOp(PunctOrOp::Div)),
@@ -319,7 +401,9 @@ fn hint_or_query_param<'src>(
         stream.find_end_of_unquoted_ident(Token::QueryParameter)
     } else {
         // EOF, can't have a trailing '@'
-        Err(TokenizerError::UnexpectedEof)
+        Err(TokenizerError::UnexpectedEof {
+            location: stream.iter.location(),
+        })
     }
 }
 
@@ -343,15 +427,18 @@ fn parse_non_obvious<'src>(
     }
 
     // and if it's not one of those, it's not a char that can start off a new token
-    Err(TokenizerError::InvalidChar(leading))
+    Err(TokenizerError::InvalidChar {
+        ch: leading,
+        location: stream.iter.start(),
+    })
 }
 
 fn parse_punct_or_opt(leading: char, stream: &mut RawStream<'_, '_>) -> Option<PunctOrOp> {
     use PunctOrOp::*;
 
-    const LT_OPTS: [(char, PunctOrOp); 3] = [('<', LeftShift), ('>', Eq), ('=', Lte)];
+    const LT_OPTS: [(char, PunctOrOp); 3] = [('<', LeftShift), ('>', NotEq), ('=', Lte)];
 
-    const GT_OPTS: [(char, PunctOrOp); 2] = [('>', Eq), ('=', Gte)];
+    const GT_OPTS: [(char, PunctOrOp); 2] = [('>', RightShift), ('=', Gte)];
 
     const NOT_OPTS: [(char, PunctOrOp); 1] = [('=', NotEq)];
 
@@ -382,11 +469,12 @@ mod tests {
     use super::{InnerTokenizer, TokenizerError};
     use crate::tokens::{PunctOrOp, Quote, QuoteFormat, QuotedModifier, StringLiteral, Token};
 
-    const TEST: &str =
-        "SELECT This, That, AndTheOtherThing FROM TableA WHERE Num > 1.0 AND Str = /* comment 
+    const TEST: &str = "SELECT This, That, AndTheOtherThing FROM TableA WHERE Num > 1.0 AND Str = \
+                        /* comment 
                         */
-                        \"This\" AND That = @qp AND Bytes = rb\"abcde\" AND Neg = -1e5 AND \
-         EmptyTripleBlock = r'''''' AND TripleBlock = '''A Non Empty String With Escapes'''";
+                        \"This\" AND That = @qp AND Bytes = rb\"abcde\" AND Neg = -1e5 AND Frac = \
+                        .5e-3 AND EmptyTripleBlock = r'''''' AND TripleBlock = '''A Non Empty \
+                        String With Escapes'''";
 
     const EXPECTING: &[Token<'static>] = &[
         Token::Unquoted("SELECT"),
@@ -427,6 +515,10 @@ mod tests {
         Token::PunctOrOp(PunctOrOp::Neg),
         Token::NumericLiteral("1e5"),
         Token::Unquoted("AND"),
+        Token::Unquoted("Frac"),
+        Token::PunctOrOp(PunctOrOp::Eq),
+        Token::NumericLiteral(".5e-3"),
+        Token::Unquoted("AND"),
         Token::Unquoted("EmptyTripleBlock"),
         Token::PunctOrOp(PunctOrOp::Eq),
         Token::StringLiteral(StringLiteral {
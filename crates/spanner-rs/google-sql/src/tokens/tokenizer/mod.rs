@@ -29,12 +29,10 @@ mod cursor;
 mod inner;
 mod peek;
 
-use data_structures::maybe_owned_mut::MaybeOwnedMut;
 pub use inner::TokenizerError;
 pub use peek::Peek;
 
 use super::{Location, PunctOrOp, Span, Token};
-use crate::Error;
 use crate::ast::reserved::Keyword;
 
 /// Peekable iterator over <code>([`Span`], [`Token`])</code> generated from a raw Google-SQL
@@ -46,24 +44,35 @@ use crate::ast::reserved::Keyword;
 #[derive(Debug, Clone, PartialEq)]
 pub struct Tokenizer<'src> {
     raw: &'src str,
+    /// The location of the first character in 'raw'. Only non-zero for nested tokenizers.
+    start: Location,
     inner: peek::PeekableTokenizer<'src>,
 }
 
 /// Iterator adapter that causes the tokens to continually peek forwards when iterating, only
 /// consuming when [`LookaheadIter::consume`] is called.
+///
+/// Nested lookaheads (via [`LookaheadIter::reborrow`]) start from the parent's position, and only
+/// move the parent forwards when they're consumed.
 #[derive(Debug, PartialEq)]
 pub struct LookaheadIter<'b, 'src> {
-    next_peek_index: MaybeOwnedMut<'b, usize>,
-    parent: &'b mut Tokenizer<'src>,
+    next_peek_index: usize,
+    /// The parent lookahead index, if this is a nested lookahead. If [`None`], consuming
+    /// consumes from the underlying tokenizer directly.
+    parent_index: Option<&'b mut usize>,
+    raw: &'src str,
+    start: Location,
+    tokens: &'b mut peek::PeekableTokenizer<'src>,
 }
 
 impl<'src> Iterator for LookaheadIter<'_, 'src> {
     type Item = Result<(Span, Token<'src>), TokenizerError<'src>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next = self.next_peek_index.as_mut();
-        let res = self.parent.inner.peek_nth(*next).transpose();
-        *next += 1;
+        let res = self.tokens.peek_nth(self.next_peek_index).transpose();
+        if matches!(res, Some(Ok(_))) {
+            self.next_peek_index += 1;
+        }
         res
     }
 }
@@ -71,8 +80,11 @@ impl<'src> Iterator for LookaheadIter<'_, 'src> {
 impl<'b, 'src> LookaheadIter<'b, 'src> {
     pub(super) fn reborrow(&mut self) -> LookaheadIter<'_, 'src> {
         LookaheadIter {
-            next_peek_index: MaybeOwnedMut::MutRef(self.next_peek_index.as_mut()),
-            parent: self.parent,
+            next_peek_index: self.next_peek_index,
+            parent_index: Some(&mut self.next_peek_index),
+            raw: self.raw,
+            start: self.start,
+            tokens: self.tokens,
         }
     }
 
@@ -80,41 +92,82 @@ impl<'b, 'src> LookaheadIter<'b, 'src> {
         &mut self,
         index: usize,
     ) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
-        self.parent.inner.peek_nth(index)
+        self.tokens.peek_nth(self.next_peek_index + index)
+    }
+
+    pub(crate) fn eof_location(&self) -> Location {
+        end_location(self.start, self.raw)
+    }
+
+    pub(crate) fn source(&self, span: Span) -> &'src str {
+        source_slice(self.start, self.raw, span)
     }
 
     fn new(parent: &'b mut Tokenizer<'src>) -> Self {
         Self {
-            next_peek_index: MaybeOwnedMut::Owned(0),
-            parent,
+            next_peek_index: 0,
+            parent_index: None,
+            raw: parent.raw,
+            start: parent.start,
+            tokens: &mut parent.inner,
         }
     }
 
+    /// Consumes all tokens that were iterated over, moving the parent tokenizer (or parent
+    /// lookahead) forward. Returns the number of tokens consumed.
     pub(crate) fn consume(self) -> Result<usize, TokenizerError<'src>> {
-        let count = self.next_peek_index.saturating_sub(1);
-        if count > 0 {
-            self.parent.inner.consume_n(count)?;
+        match self.parent_index {
+            Some(parent_index) => {
+                let count = self.next_peek_index - *parent_index;
+                *parent_index = self.next_peek_index;
+                Ok(count)
+            }
+            None => {
+                self.tokens.consume_n(self.next_peek_index)?;
+                Ok(self.next_peek_index)
+            }
         }
-        Ok(count)
     }
 }
 
+fn end_location(start: Location, raw: &str) -> Location {
+    let mut location = start;
+    location.advance_by_str(raw);
+    location
+}
+
+fn source_slice(start: Location, raw: &str, span: Span) -> &str {
+    let from = span
+        .start
+        .offset
+        .saturating_sub(start.offset)
+        .min(raw.len());
+    let to = (from + span.len).min(raw.len());
+    &raw[from..to]
+}
+
 impl<'src> Tokenizer<'src> {
     #[inline]
     pub fn new(sql: &'src str) -> Self {
-        Self {
-            raw: sql,
-            inner: peek::PeekableTokenizer::new(inner::InnerTokenizer::new(sql)),
-        }
+        let start = Location {
+            offset: 0,
+            line: 0,
+            column: 0,
+        };
+
+        Self::new_from_location(start, sql)
     }
 
     pub fn lookahead_iter(&mut self) -> LookaheadIter<'_, 'src> {
         LookaheadIter::new(self)
     }
 
+    /// Builds a tokenizer over a substring of some larger SQL string, where 'location' is the
+    /// location of the first character in 'sub_sql'. Spans are relative to the larger string.
     pub fn new_from_location(location: Location, sub_sql: &'src str) -> Self {
         Self {
             raw: sub_sql,
+            start: location,
             inner: peek::PeekableTokenizer::new(inner::InnerTokenizer::new_from_location(
                 location, sub_sql,
             )),
@@ -136,21 +189,12 @@ impl<'src> Tokenizer<'src> {
         }
     }
 
-    /// convinience wrapper that calls ParseTokens::parse_tokens for 'P', but also
-    /// does checks to unwrap parenthases and build an inner tokenizer if that's the case.
-    pub(super) fn parse<P>(&mut self) -> Result<P, Error<'src>>
-    where
-        P: crate::ast::ParseTokens<'src>,
-    {
-        if let Some(mut nested) =
-            self.next_if_some(|span, token| token.build_parens_parser(span.start))?
-        {
-            let out = P::parse_tokens(&mut nested)?;
-            // TODO: check to ensure that nested is completely exhausted of non-comment tokens
-            Ok(out)
-        } else {
-            P::parse_tokens(self)
-        }
+    #[inline]
+    pub(crate) fn peek_nth(
+        &mut self,
+        index: usize,
+    ) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
+        self.inner.peek_nth(index)
     }
 
     #[inline]
@@ -158,12 +202,24 @@ impl<'src> Tokenizer<'src> {
         self.raw
     }
 
+    /// The location right after the last character in the input.
+    pub fn eof_location(&self) -> Location {
+        end_location(self.start, self.raw)
+    }
+
+    /// Returns the raw source covered by 'span'.
+    pub fn source(&self, span: Span) -> &'src str {
+        source_slice(self.start, self.raw, span)
+    }
+
     pub fn next_if_keyword(&mut self) -> Result<Option<Keyword>, TokenizerError<'src>> {
         self.next_if_some(|_, token| token.as_keyword())
     }
 
     pub fn next_or_eof(&mut self) -> Result<(Span, Token<'src>), TokenizerError<'src>> {
-        self.next().ok_or(TokenizerError::UnexpectedEof)?
+        let location = self.eof_location();
+        self.next()
+            .ok_or(TokenizerError::UnexpectedEof { location })?
     }
 
     pub fn next_if_punct(&mut self, punct: PunctOrOp) -> Result<bool, TokenizerError<'src>> {
@@ -40,16 +40,6 @@ impl<'src> PeekableTokenizer<'src> {
         }
     }
 
-    pub fn continuous_buffered(&mut self) -> Result<&[(Span, Token<'src>)], TokenizerError<'src>> {
-        self.check_error()?;
-        Ok(self.peeked.make_contiguous())
-    }
-
-    pub fn buffered(&self) -> Result<&VecDeque<(Span, Token<'src>)>, TokenizerError<'src>> {
-        self.check_error()?;
-        Ok(&self.peeked)
-    }
-
     pub fn consume_n(&mut self, n: usize) -> Result<(), TokenizerError<'src>> {
         for _ in 0..n {
             if self.next_inner()?.is_none() {
@@ -65,14 +55,14 @@ impl<'src> PeekableTokenizer<'src> {
         index: usize,
     ) -> Result<Option<(Span, Token<'src>)>, TokenizerError<'src>> {
         self.check_error()?;
-        self.fill_peek_to_n(index)?;
+        self.fill_peek_to_n(index + 1)?;
         Ok(self.peeked.get(index).copied())
     }
 
     #[inline]
     fn check_error(&self) -> Result<(), TokenizerError<'src>> {
         if let Some(ref error) = self.error {
-            Err(error.clone())
+            Err(*error)
         } else {
             Ok(())
         }
@@ -84,7 +74,7 @@ impl<'src> PeekableTokenizer<'src> {
         item: Result<T, TokenizerError<'src>>,
     ) -> Result<T, TokenizerError<'src>> {
         if let Err(ref error) = item {
-            self.error = Some(error.clone());
+            self.error = Some(*error);
         }
 
         item
@@ -103,16 +93,6 @@ impl<'src> PeekableTokenizer<'src> {
         }
     }
 
-    pub fn peek_n<const N: usize>(
-        &mut self,
-    ) -> Result<Option<[(Span, Token<'src>); N]>, TokenizerError<'src>> {
-        self.fill_peek_to_n(N)?;
-
-        Ok(std::array::try_from_fn(|index| {
-            self.peeked.get(index).copied()
-        }))
-    }
-
     fn fill_peek_to_n(&mut self, n: usize) -> Result<bool, TokenizerError<'src>> {
         if self.peeked.len() >= n {
             self.check_error()?;
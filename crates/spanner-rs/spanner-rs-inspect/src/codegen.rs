@@ -0,0 +1,361 @@
+//! Generates `#[derive(Table)]` row types from a [`Schema`].
+//!
+//! Each table gets its own module (since the derive generates a few helper types alongside the
+//! row itself), containing the row type, its `Interleaved` impl and any secondary indexes on the
+//! table. Tables with generated columns also get a read only row type that includes them.
+//!
+//! Column types map to the same types a hand written row would use, so the crate including the
+//! generated code needs `timestamp`, `bytes`, etc. for any tables with those column types.
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+
+use crate::Error;
+use crate::schema::{ColumnType, Index, Interleave, KeyPart, OnDelete, Schema, Table};
+
+/// Rust keywords, which can't be used as module or field names as-is.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "try", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Codegen {
+    /// The path to `spanner_rs` in the generated code.
+    pub crate_path: Cow<'static, str>,
+    /// Derives added to every row type.
+    pub derives: Vec<Cow<'static, str>>,
+}
+
+impl Default for Codegen {
+    fn default() -> Self {
+        Self {
+            crate_path: Cow::Borrowed("spanner_rs"),
+            derives: vec![
+                Cow::Borrowed("Debug"),
+                Cow::Borrowed("Clone"),
+                Cow::Borrowed("PartialEq"),
+            ],
+        }
+    }
+}
+
+impl Codegen {
+    /// Generates the source for every table in 'schema'.
+    pub fn generate(&self, schema: &Schema) -> Result<String, Error> {
+        let mut dst = String::from("// @generated by spanner-rs-inspect, do not edit by hand.\n");
+
+        for table in schema.tables.iter() {
+            dst.push('\n');
+            self.generate_table(schema, table, &mut dst)?;
+        }
+
+        Ok(dst)
+    }
+
+    fn generate_table(
+        &self,
+        schema: &Schema,
+        table: &Table,
+        dst: &mut String,
+    ) -> Result<(), Error> {
+        let krate = &self.crate_path;
+        let module = escape_keyword(table.name.to_case(Case::Snake));
+        let row = table.name.to_case(Case::Pascal);
+        let with_generated = format!("{row}WithGenerated");
+        let has_generated = table.columns.iter().any(|column| column.generated);
+
+        writeln!(dst, "pub mod {module} {{").unwrap();
+        writeln!(dst, "    /// The '{}' table.", table.name).unwrap();
+
+        if has_generated {
+            writeln!(dst, "    ///").unwrap();
+            writeln!(
+                dst,
+                "    /// Generated columns can't be written, so they're only in \
+                 [`{with_generated}`]."
+            )
+            .unwrap();
+        }
+
+        if !table.indexes.is_empty() {
+            writeln!(dst, "    ///").unwrap();
+            writeln!(dst, "    /// Secondary indexes are in [`indexes`].").unwrap();
+        }
+
+        writeln!(dst, "    #[derive({})]", self.derives(krate)).unwrap();
+        writeln!(dst, "    #[spanner(table = \"{}\")]", table.name).unwrap();
+        self.generate_struct(table, &row, false, dst)?;
+
+        if let Some(ref interleave) = table.interleave {
+            self.generate_interleave(schema, interleave, &row, dst);
+        }
+
+        if has_generated {
+            writeln!(dst).unwrap();
+            writeln!(
+                dst,
+                "    /// Every column in '{}', including the generated columns left out of \
+                 [`{row}`].",
+                table.name
+            )
+            .unwrap();
+            writeln!(dst, "    #[derive({})]", self.derives(krate)).unwrap();
+            self.generate_struct(table, &with_generated, true, dst)?;
+        }
+
+        if !table.indexes.is_empty() {
+            writeln!(dst).unwrap();
+            writeln!(dst, "    /// Secondary indexes on '{}'.", table.name).unwrap();
+            writeln!(dst, "    pub mod indexes {{").unwrap();
+            for index in table.indexes.iter() {
+                generate_index(krate, table, index, dst);
+            }
+            writeln!(dst, "    }}").unwrap();
+        }
+
+        writeln!(dst, "}}").unwrap();
+
+        Ok(())
+    }
+
+    /// The derives for a row type, starting with `Table`.
+    fn derives(&self, krate: &str) -> String {
+        let mut derives = format!("{krate}::Table");
+        for derive in self.derives.iter() {
+            derives.push_str(", ");
+            derives.push_str(derive);
+        }
+        derives
+    }
+
+    /// Writes a row struct. Generated columns are only included in read only rows (without a
+    /// table name), which don't get primary keys either.
+    fn generate_struct(
+        &self,
+        table: &Table,
+        row: &str,
+        read_only: bool,
+        dst: &mut String,
+    ) -> Result<(), Error> {
+        writeln!(dst, "    pub struct {row} {{").unwrap();
+
+        for column in table
+            .columns
+            .iter()
+            .filter(|column| read_only || !column.generated)
+        {
+            let field = escape_keyword(column.name.to_case(Case::Snake));
+
+            let mut ty = rust_type(&column.ty).ok_or_else(|| Error::UnsupportedType {
+                table: table.name.clone(),
+                column: column.name.clone(),
+                ty: format!("{:?}", column.ty),
+            })?;
+
+            if column.nullable {
+                ty = format!("Option<{ty}>");
+            }
+
+            let mut spanner_args = Vec::with_capacity(2);
+
+            if !read_only && let Some(pk) = table.pk_position(&column.name) {
+                spanner_args.push(format!("pk = {pk}"));
+            }
+
+            // the derive builds the column name from the field name, so only rename if that
+            // wouldn't round trip.
+            if row_column_name(&field) != column.name {
+                spanner_args.push(format!("rename = {}", column.name));
+            }
+
+            if !spanner_args.is_empty() {
+                writeln!(dst, "        #[spanner({})]", spanner_args.join(", ")).unwrap();
+            }
+
+            writeln!(dst, "        pub {field}: {ty},").unwrap();
+        }
+
+        writeln!(dst, "    }}").unwrap();
+
+        Ok(())
+    }
+
+    /// Implements `Interleaved` for an interleaved table. If the parent isn't part of the schema
+    /// there's no type to point to, so the relationship is only documented.
+    fn generate_interleave(
+        &self,
+        schema: &Schema,
+        interleave: &Interleave,
+        row: &str,
+        dst: &mut String,
+    ) {
+        let krate = &self.crate_path;
+        let on_delete = match interleave.on_delete {
+            OnDelete::Cascade => "Cascade",
+            OnDelete::NoAction => "NoAction",
+        };
+
+        writeln!(dst).unwrap();
+
+        let Some(parent) = schema.table(&interleave.parent) else {
+            writeln!(
+                dst,
+                "    // Interleaved in '{}' (ON DELETE {on_delete}), which isn't in the schema.",
+                interleave.parent
+            )
+            .unwrap();
+            return;
+        };
+
+        writeln!(dst, "    impl {krate}::table::Interleaved for {row} {{").unwrap();
+        writeln!(
+            dst,
+            "        type Parent = super::{}::{};",
+            escape_keyword(parent.name.to_case(Case::Snake)),
+            parent.name.to_case(Case::Pascal),
+        )
+        .unwrap();
+        writeln!(
+            dst,
+            "        const ON_DELETE: {krate}::table::OnDelete = \
+             {krate}::table::OnDelete::{on_delete};"
+        )
+        .unwrap();
+        writeln!(dst, "    }}").unwrap();
+    }
+}
+
+/// Writes an index as a `spanner_rs::table::Index` constant.
+fn generate_index(krate: &str, table: &Table, index: &Index, dst: &mut String) {
+    let keys = index
+        .keys
+        .iter()
+        .map(|key| {
+            format!(
+                "{krate}::table::IndexKey {{ column: \"{}\", ascending: {} }}",
+                key.column, key.ascending
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let storing = index
+        .storing
+        .iter()
+        .map(|column| format!("\"{column}\""))
+        .collect::<Vec<_>>();
+
+    let interleave_in = match index.interleave_in {
+        Some(ref parent) => format!("Some(\"{parent}\")"),
+        None => "None".to_owned(),
+    };
+
+    writeln!(dst, "        /// `{}`", index_ddl(table, index)).unwrap();
+    writeln!(
+        dst,
+        "        pub const {}: {krate}::table::Index = {krate}::table::Index {{",
+        index.name.to_case(Case::UpperSnake),
+    )
+    .unwrap();
+    writeln!(dst, "            name: \"{}\",", index.name).unwrap();
+    writeln!(dst, "            unique: {},", index.unique).unwrap();
+    writeln!(dst, "            null_filtered: {},", index.null_filtered).unwrap();
+    writeln!(dst, "            keys: &[{}],", keys.join(", ")).unwrap();
+    writeln!(dst, "            storing: &[{}],", storing.join(", ")).unwrap();
+    writeln!(dst, "            interleave_in: {interleave_in},").unwrap();
+    writeln!(dst, "        }};").unwrap();
+}
+
+/// Maps a column type to the Rust type used in the generated row, if there is one.
+fn rust_type(ty: &ColumnType) -> Option<String> {
+    let ty = match ty {
+        ColumnType::Bool => "bool",
+        ColumnType::Int64 => "i64",
+        ColumnType::Float32 => "f32",
+        ColumnType::Float64 => "f64",
+        ColumnType::String => "String",
+        ColumnType::Bytes => "bytes::Bytes",
+        ColumnType::Json => "serde_json::Value",
+        ColumnType::Date => "timestamp::Date",
+        ColumnType::Timestamp => "timestamp::Timestamp",
+        ColumnType::Uuid => "uuid::Uuid",
+        ColumnType::Array(element) => return Some(format!("Vec<{}>", rust_type(element)?)),
+        // spanner-rs decodes NUMERIC as an integer, so fractional values fail to decode
+        ColumnType::Numeric => "i128",
+        ColumnType::Unsupported(_) => return None,
+    };
+
+    Some(ty.to_owned())
+}
+
+/// Renders an index as the DDL that would create it.
+fn index_ddl(table: &Table, index: &Index) -> String {
+    let mut ddl = String::from("CREATE ");
+
+    if index.unique {
+        ddl.push_str("UNIQUE ");
+    }
+    if index.null_filtered {
+        ddl.push_str("NULL_FILTERED ");
+    }
+
+    write!(
+        ddl,
+        "INDEX {} ON {} ({})",
+        index.name,
+        table.name,
+        key_list(&index.keys)
+    )
+    .unwrap();
+
+    if !index.storing.is_empty() {
+        write!(ddl, " STORING ({})", index.storing.join(", ")).unwrap();
+    }
+
+    if let Some(ref parent) = index.interleave_in {
+        write!(ddl, ", INTERLEAVE IN {parent}").unwrap();
+    }
+
+    ddl
+}
+
+fn key_list(keys: &[KeyPart]) -> String {
+    keys.iter()
+        .map(|key| match key.ascending {
+            true => key.column.clone(),
+            false => format!("{} DESC", key.column),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn escape_keyword(mut ident: String) -> String {
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// The column name the derive uses for a field without a 'rename' (from `paste`'s ':camel').
+fn row_column_name(field: &str) -> String {
+    let mut dst = String::with_capacity(field.len());
+    let mut prev = '_';
+
+    for ch in field.chars() {
+        if ch != '_' {
+            if prev == '_' {
+                dst.extend(ch.to_uppercase());
+            } else if prev.is_uppercase() {
+                dst.extend(ch.to_lowercase());
+            } else {
+                dst.push(ch);
+            }
+        }
+        prev = ch;
+    }
+
+    dst
+}
@@ -0,0 +1,169 @@
+//! Loads a [`Schema`] from the 'INFORMATION_SCHEMA' tables of a live database.
+use spanner_rs::Client;
+use spanner_rs::queryable::Queryable;
+
+use crate::Error;
+use crate::schema::{Column, ColumnType, Index, Interleave, KeyPart, OnDelete, Schema, Table};
+
+const TABLES_QUERY: &str = "SELECT
+  TABLE_NAME,
+  PARENT_TABLE_NAME,
+  ON_DELETE_ACTION
+FROM
+  INFORMATION_SCHEMA.TABLES
+WHERE
+  TABLE_SCHEMA = ''
+  AND TABLE_TYPE = 'BASE TABLE'
+ORDER BY
+  TABLE_NAME";
+
+const COLUMNS_QUERY: &str = "SELECT
+  TABLE_NAME,
+  COLUMN_NAME,
+  IS_NULLABLE,
+  SPANNER_TYPE,
+  IS_GENERATED
+FROM
+  INFORMATION_SCHEMA.COLUMNS
+WHERE
+  TABLE_SCHEMA = ''
+ORDER BY
+  TABLE_NAME,
+  ORDINAL_POSITION";
+
+const INDEXES_QUERY: &str = "SELECT
+  TABLE_NAME,
+  INDEX_NAME,
+  IS_UNIQUE,
+  IS_NULL_FILTERED,
+  PARENT_TABLE_NAME
+FROM
+  INFORMATION_SCHEMA.INDEXES
+WHERE
+  TABLE_SCHEMA = ''
+  AND INDEX_TYPE = 'INDEX'
+ORDER BY
+  TABLE_NAME,
+  INDEX_NAME";
+
+/// Includes the primary key columns (under the 'PRIMARY_KEY' index name), storing columns have
+/// a NULL 'ORDINAL_POSITION', so they're sorted last.
+const INDEX_COLUMNS_QUERY: &str = "SELECT
+  TABLE_NAME,
+  INDEX_NAME,
+  COLUMN_NAME,
+  ORDINAL_POSITION,
+  COLUMN_ORDERING
+FROM
+  INFORMATION_SCHEMA.INDEX_COLUMNS
+WHERE
+  TABLE_SCHEMA = ''
+ORDER BY
+  TABLE_NAME,
+  INDEX_NAME,
+  ORDINAL_POSITION IS NULL,
+  ORDINAL_POSITION";
+
+const PRIMARY_KEY_INDEX: &str = "PRIMARY_KEY";
+
+type TableRow = (String, Option<String>, Option<String>);
+
+type ColumnRow = (String, String, String, String, String);
+
+type IndexRow = (String, String, bool, bool, Option<String>);
+
+type IndexColumnRow = (String, String, String, Option<i64>, Option<String>);
+
+impl Schema {
+    /// Reads the schema of the database 'client' points at. Works the same against the emulator.
+    pub async fn read(client: &mut Client) -> Result<Self, Error> {
+        let mut schema = Self::default();
+
+        for row in query::<TableRow>(client, TABLES_QUERY).await? {
+            let (name, parent, on_delete) = row;
+
+            schema.tables.push(Table {
+                name,
+                columns: Vec::new(),
+                primary_key: Vec::new(),
+                interleave: parent.map(|parent| Interleave {
+                    parent,
+                    on_delete: match on_delete.as_deref() {
+                        Some("CASCADE") => OnDelete::Cascade,
+                        _ => OnDelete::NoAction,
+                    },
+                }),
+                indexes: Vec::new(),
+            });
+        }
+
+        for row in query::<ColumnRow>(client, COLUMNS_QUERY).await? {
+            let (table, name, is_nullable, spanner_type, is_generated) = row;
+
+            // columns of views are included too, skip anything that isn't a base table.
+            if let Some(table) = schema.table_mut_opt(&table) {
+                table.columns.push(Column {
+                    name,
+                    ty: ColumnType::parse(&spanner_type),
+                    nullable: is_nullable == "YES",
+                    generated: is_generated == "ALWAYS",
+                });
+            }
+        }
+
+        for row in query::<IndexRow>(client, INDEXES_QUERY).await? {
+            let (table, name, unique, null_filtered, parent) = row;
+
+            if let Some(table) = schema.table_mut_opt(&table) {
+                table.indexes.push(Index {
+                    name,
+                    unique,
+                    null_filtered,
+                    keys: Vec::new(),
+                    storing: Vec::new(),
+                    // non-interleaved indexes can have an empty parent, rather than NULL
+                    interleave_in: parent.filter(|parent| !parent.is_empty()),
+                });
+            }
+        }
+
+        for row in query::<IndexColumnRow>(client, INDEX_COLUMNS_QUERY).await? {
+            let (table, index, column, position, ordering) = row;
+
+            let Some(table) = schema.table_mut_opt(&table) else {
+                continue;
+            };
+
+            let keys = if index == PRIMARY_KEY_INDEX {
+                &mut table.primary_key
+            } else {
+                let Some(index) = table.indexes.iter_mut().find(|idx| idx.name == index) else {
+                    continue;
+                };
+
+                if position.is_none() {
+                    index.storing.push(column);
+                    continue;
+                }
+
+                &mut index.keys
+            };
+
+            keys.push(KeyPart {
+                column,
+                ascending: ordering.as_deref() != Some("DESC"),
+            });
+        }
+
+        Ok(schema)
+    }
+
+    fn table_mut_opt(&mut self, name: &str) -> Option<&mut Table> {
+        self.tables.iter_mut().find(|table| table.name == name)
+    }
+}
+
+async fn query<T: Queryable>(client: &mut Client, sql: &str) -> Result<Vec<T>, Error> {
+    let rows = client.execute_sql::<T>(sql.to_owned(), None).await?;
+    rows.collect::<Result<Vec<T>, _>>().map_err(Error::from)
+}
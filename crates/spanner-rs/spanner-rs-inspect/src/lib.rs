@@ -1,38 +1,187 @@
+//! Generates `#[derive(spanner_rs::Table)]` row types from an existing schema, either read from the
+//! 'INFORMATION_SCHEMA' of a live database (or emulator), or parsed from DDL.
 use std::borrow::Cow;
 
-// mod schema;
+pub mod codegen;
+mod information_schema;
+pub mod schema;
+
+pub use codegen::Codegen;
+pub use schema::Schema;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Spanner(#[from] spanner_rs::Error),
+    #[error("invalid DDL: {0}")]
+    Ddl(String),
+    #[error("table '{0}' doesn't exist")]
+    UnknownTable(String),
+    #[error("table '{0}' already exists")]
+    DuplicateTable(String),
+    #[error("column '{column}' doesn't exist in table '{table}'")]
+    UnknownColumn { table: String, column: String },
+    #[error("index '{0}' doesn't exist")]
+    UnknownIndex(String),
+    #[error("column '{column}' in table '{table}' has an unsupported type: {ty}")]
+    UnsupportedType {
+        table: String,
+        column: String,
+        ty: String,
+    },
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct InspectConfig {
-    project: Cow<'static, str>,
-    instance: Cow<'static, str>,
-    database: Cow<'static, str>,
+    pub project: Cow<'static, str>,
+    pub instance: Cow<'static, str>,
+    pub database: Cow<'static, str>,
 }
 
 impl InspectConfig {
-    /*
-    async fn load(self) -> anyhow::Result<schema::InformationSchema> {
-        let db = spanner_rs::info::Database::builder(self.project)
-            .instance(self.instance)
-            .database(self.database);
+    /// Connects to the database and reads its schema.
+    pub async fn load(self) -> Result<Schema, Error> {
+        let project: &'static str = match self.project {
+            Cow::Borrowed(project) => project,
+            Cow::Owned(project) => Box::leak(project.into_boxed_str()),
+        };
 
-        schema::InformationSchema::read_from_db(db).await
+        let mut client = spanner_rs::Database::<&'static str>::new_leaked(
+            project,
+            &self.instance,
+            &self.database,
+        )
+        .build_client(gcp_auth_provider::Scope::SpannerData)
+        .await?;
+
+        Schema::read(&mut client).await
     }
-    */
 }
-/*
-#[tokio::test]
-async fn test_inspect() -> anyhow::Result<()> {
-    let config = InspectConfig {
-        project: "mysticetus-oncloud".into(),
-        instance: "mysticetus-prod".into(),
-        database: "oncloud".into(),
-    };
 
-    let schema = config.load().await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DDL: &str = "
+        CREATE TABLE Singers (
+            SingerId INT64 NOT NULL,
+            FirstName STRING(1024),
+            LastName STRING(1024) NOT NULL,
+            FullName STRING(MAX) AS (CONCAT(FirstName, ' ', LastName)) STORED,
+            Photo BYTES(MAX),
+        ) PRIMARY KEY (SingerId);
+
+        CREATE TABLE Albums (
+            SingerId INT64 NOT NULL,
+            AlbumID INT64 NOT NULL,
+            Title STRING(MAX),
+            Type STRING(16) NOT NULL,
+            ReleasedAt TIMESTAMP,
+            Tags ARRAY<STRING(MAX)>,
+        ) PRIMARY KEY (SingerId, AlbumID DESC),
+          INTERLEAVE IN PARENT Singers ON DELETE CASCADE;
+
+        CREATE UNIQUE INDEX AlbumsByTitle ON Albums (Title) STORING (ReleasedAt);
+        CREATE INDEX SingersByName ON Singers (LastName, FirstName DESC);
+        ALTER TABLE Singers ADD COLUMN Rating FLOAT64;
+        ALTER TABLE Singers DROP COLUMN Photo;
+    ";
+
+    #[test]
+    fn test_schema_from_ddl() -> Result<(), Error> {
+        let schema = Schema::from_ddl(DDL)?;
+
+        assert_eq!(schema.tables.len(), 2);
+
+        let singers = schema.table("singers").expect("case insensitive lookup");
+        let columns = singers
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            ["SingerId", "FirstName", "LastName", "FullName", "Rating"]
+        );
+        assert!(singers.column("FullName").unwrap().generated);
+        assert!(!singers.column("LastName").unwrap().nullable);
+        assert_eq!(singers.indexes[0].name, "SingersByName");
+
+        let albums = schema.table("Albums").unwrap();
+        assert_eq!(albums.pk_position("AlbumID"), Some(2));
+        assert!(!albums.primary_key[1].ascending);
 
-    println!("{schema:#?}");
+        let interleave = albums.interleave.as_ref().unwrap();
+        assert_eq!(interleave.parent, "Singers");
+        assert_eq!(interleave.on_delete, schema::OnDelete::Cascade);
 
-    Ok(())
+        assert_eq!(
+            albums.column("Tags").unwrap().ty,
+            schema::ColumnType::Array(Box::new(schema::ColumnType::String))
+        );
+        assert_eq!(albums.indexes[0].storing, ["ReleasedAt"]);
+
+        assert!(matches!(
+            Schema::from_ddl("CREATE INDEX Idx ON Missing (Id)"),
+            Err(Error::UnknownTable(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_codegen() -> Result<(), Error> {
+        let schema = Schema::from_ddl(DDL)?;
+        let code = Codegen::default().generate(&schema)?;
+
+        assert!(code.contains("pub mod albums {"));
+        assert!(code.contains(
+            "#[derive(spanner_rs::Table, Debug, Clone, PartialEq)]\n    #[spanner(table = \
+             \"Albums\")]"
+        ));
+        assert!(code.contains("#[spanner(pk = 1)]\n        pub singer_id: i64,"));
+        assert!(code.contains("#[spanner(pk = 2, rename = AlbumID)]\n        pub album_id: i64,"));
+        assert!(code.contains("pub type_: String,"));
+        assert!(code.contains("pub first_name: Option<String>,"));
+        assert!(code.contains("pub tags: Option<Vec<String>>,"));
+        assert!(code.contains("pub released_at: Option<timestamp::Timestamp>,"));
+
+        // generated columns are only in the read only row
+        assert_eq!(code.matches("pub full_name: Option<String>,").count(), 1);
+        assert!(code.contains("pub struct SingersWithGenerated {\n        pub singer_id: i64,"));
+
+        assert!(code.contains(
+            "impl spanner_rs::table::Interleaved for Albums {\n        type Parent = \
+             super::singers::Singers;\n        const ON_DELETE: spanner_rs::table::OnDelete = \
+             spanner_rs::table::OnDelete::Cascade;"
+        ));
+        assert!(code.contains(
+            "pub const ALBUMS_BY_TITLE: spanner_rs::table::Index = spanner_rs::table::Index {"
+        ));
+        assert!(code.contains(
+            "keys: &[spanner_rs::table::IndexKey { column: \"LastName\", ascending: true }, \
+             spanner_rs::table::IndexKey { column: \"FirstName\", ascending: false }],"
+        ));
+        assert!(code.contains("storing: &[\"ReleasedAt\"],"));
+        assert!(code.contains(
+            "`CREATE UNIQUE INDEX AlbumsByTitle ON Albums (Title) STORING (ReleasedAt)`"
+        ));
+
+        let numeric = Schema::from_ddl("CREATE TABLE T (Id INT64, N NUMERIC) PRIMARY KEY (Id)")?;
+        assert!(
+            Codegen::default()
+                .generate(&numeric)?
+                .contains("pub n: Option<i128>,")
+        );
+
+        let unsupported = Schema::from_ddl(
+            "CREATE TABLE T (Id INT64, S ARRAY<STRUCT<A INT64>>) PRIMARY KEY (Id)",
+        )?;
+        assert!(matches!(
+            Codegen::default().generate(&unsupported),
+            Err(Error::UnsupportedType { .. })
+        ));
+
+        Ok(())
+    }
 }
-*/
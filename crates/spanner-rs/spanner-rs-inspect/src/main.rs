@@ -0,0 +1,78 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use spanner_rs_inspect::{Codegen, InspectConfig, Schema};
+
+/// Generates `#[derive(spanner_rs::Table)]` row types from a Spanner schema.
+#[derive(Debug, Parser)]
+struct Args {
+    #[command(subcommand)]
+    source: Source,
+    /// Write the generated code to a file, rather than stdout.
+    #[arg(short, long, global = true)]
+    out: Option<PathBuf>,
+    /// The path to `spanner_rs` in the generated code.
+    #[arg(long, global = true, default_value = "spanner_rs")]
+    crate_path: String,
+    /// Extra derives to add to every row type.
+    #[arg(long, global = true, value_delimiter = ',')]
+    derive: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Source {
+    /// Read the schema from a live database.
+    Database {
+        #[arg(long)]
+        project: String,
+        #[arg(long)]
+        instance: String,
+        #[arg(long)]
+        database: String,
+    },
+    /// Parse the schema from a file of DDL statements.
+    Ddl { path: PathBuf },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let schema = match args.source {
+        Source::Database {
+            project,
+            instance,
+            database,
+        } => {
+            InspectConfig {
+                project: Cow::Owned(project),
+                instance: Cow::Owned(instance),
+                database: Cow::Owned(database),
+            }
+            .load()
+            .await?
+        }
+        Source::Ddl { path } => {
+            let ddl = tokio::fs::read_to_string(&path).await?;
+            Schema::from_ddl(&ddl)?
+        }
+    };
+
+    let mut codegen = Codegen {
+        crate_path: Cow::Owned(args.crate_path),
+        ..Default::default()
+    };
+    codegen
+        .derives
+        .extend(args.derive.into_iter().map(Cow::Owned));
+
+    let code = codegen.generate(&schema)?;
+
+    match args.out {
+        Some(path) => tokio::fs::write(path, code).await?,
+        None => print!("{code}"),
+    }
+
+    Ok(())
+}
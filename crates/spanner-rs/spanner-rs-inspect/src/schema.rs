@@ -1,397 +1,334 @@
-use std::collections::BTreeMap;
-use std::convert::Infallible;
-use std::num::NonZeroUsize;
-use std::sync::Arc;
-
-// use genco::prelude::Rust;
-use spanner_rs::convert::SpannerEncode;
-use spanner_rs::error::{ConvertError, FromError};
-use spanner_rs::info::Database;
-use spanner_rs::ty::SpannerType;
-use spanner_rs::{Client, FromSpanner, IntoSpanner, ResultIter, Scalar, Session, Value};
-
-const QUERY: &str = "SELECT
-  COLS.TABLE_NAME,
-  COLS.COLUMN_NAME, 
-  COLS.ORDINAL_POSITION, 
-  COLS.COLUMN_DEFAULT, 
-  COLS.IS_NULLABLE,
-  COLS.SPANNER_TYPE, 
-  COLS.IS_GENERATED, 
-  COLS.SPANNER_STATE,
-  IDX.INDEX_NAME,
-  IDX.INDEX_TYPE,
-  IDX.COLUMN_ORDERING 
-FROM 
-  INFORMATION_SCHEMA.COLUMNS AS COLS 
-FULL OUTER JOIN 
-  INFORMATION_SCHEMA.INDEX_COLUMNS AS IDX 
-ON 
-  COLS.COLUMN_NAME = IDX.COLUMN_NAME AND COLS.TABLE_NAME = IDX.TABLE_NAME
-WHERE 
-  COLS.TABLE_SCHEMA = \"\"
-  AND COLS.TABLE_NAME IN (
-    SELECT
-      TABLE_NAME 
-    FROM 
-      INFORMATION_SCHEMA.TABLES 
-    WHERE 
-      TABLE_TYPE = \"BASE TABLE\"
-  )
-ORDER BY 
-  COLS.TABLE_NAME,
-  COLS.ORDINAL_POSITION";
-
-#[derive(Debug)]
-pub struct InformationSchema {
-    tables: BTreeMap<Arc<str>, Table>,
+//! A minimal model of a Spanner schema, with only the parts needed to generate table types.
+use google_sql::ast::common::types::Type;
+use google_sql::ast::ddl::DdlStatement;
+use google_sql::ast::ddl::index::AlterIndexAction;
+use google_sql::ast::ddl::table::{AlterTableAction, ColumnAlteration, ColumnDef, ColumnValue};
+use google_sql::ast::reserved::DataType;
+use google_sql::ast::{Statement, parse_statements};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    pub tables: Vec<Table>,
 }
 
-impl InformationSchema {
-    pub async fn read(client: &mut Client) -> anyhow::Result<Self> {
-        let mut sess = client.create_session(None).await?;
-        let res = Self::read_from_session(&mut sess).await;
-        sess.delete().await?;
-        res
-    }
-
-    pub async fn read_from_session(sess: &mut Session) -> anyhow::Result<Self> {
-        let iter = sess
-            .execute_sql::<RawColumnView>(QUERY.to_owned(), None)
-            .await?;
-
-        let mut dst = Self {
-            tables: BTreeMap::new(),
-        };
-
-        for res in iter {
-            let col = res?;
-            dst.push_col(col)?;
-        }
-
-        Ok(dst)
-    }
-
-    fn push_col(&mut self, raw: RawColumnView) -> anyhow::Result<()> {
-        let RawColumnView {
-            table_name,
-            column_name,
-            ordinal_position,
-            column_default,
-            is_nullable,
-            spanner_type,
-            is_generated,
-            spanner_state,
-            index_type,
-            column_ordering,
-            ..
-        } = raw;
-
-        let name = Arc::from(table_name);
-        let table = self.tables.entry(name).or_insert_with_key(|k| Table {
-            name: Arc::clone(k),
-            columns: Vec::new(),
-        });
-
-        let index = match (index_type, column_ordering) {
-            (Some(index_type), Some(ordering)) => Some(Index {
-                index_type,
-                ordering,
-            }),
-            (Some(index_type), None) => Some(Index {
-                index_type,
-                ordering: Ordering::Asc,
-            }),
-            (None, _) => None,
-        };
-
-        table.columns.push(Column {
-            name: column_name,
-            ordinal_position,
-            column_default,
-            spanner_state,
-            is_nullable: matches!(is_nullable, IsNullable::Yes),
-            spanner_type,
-            is_generated: matches!(is_generated, IsGenerated::Always),
-            index,
-        });
-
-        Ok(())
-    }
-
-    pub async fn read_from_db<S: AsRef<str>>(db: Database<S>) -> anyhow::Result<Self>
-    where
-        data_structures::shared::Shared<str>: From<S>,
-    {
-        let mut client = db.build_client().await?;
-        Self::read(&mut client).await
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Table {
-    name: Arc<str>,
-    columns: Vec<Column>,
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<KeyPart>,
+    pub interleave: Option<Interleave>,
+    pub indexes: Vec<Index>,
 }
 
-impl genco::tokens::FormatInto<Rust> for &Table {
-    fn format_into(self, tokens: &mut genco::Tokens<Rust>) {
-        genco::quote_in! { *tokens =>
-            #[derive(Debug, Clone, PartialEq, spanner_rs::Table)]
-            pub struct #(self.name) {
-                #(self.col)
-            }
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Column {
-    name: String,
-    ordinal_position: NonZeroUsize,
-    column_default: Option<String>,
-    spanner_state: SpannerState,
-    is_nullable: bool,
-    spanner_type: DataType,
-    is_generated: bool,
-    index: Option<Index>,
-}
-
-impl genco::tokens::FormatInto<Rust> for &Column {
-    fn format_into(self, tokens: &mut genco::Tokens<Rust>) {
-        todo!()
-    }
-}
-
-#[derive(Debug)]
-pub struct Index {
-    index_type: IndexType,
-    ordering: Ordering,
-}
-
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum IndexType {
-    PrimaryKey,
-}
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum Ordering {
-    Asc,
-    Desc,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum Size {
-    Max,
-    Sized(usize),
+    pub name: String,
+    pub ty: ColumnType,
+    pub nullable: bool,
+    /// Generated columns can't be written to, so they're only in the generated read only types.
+    pub generated: bool,
 }
 
-impl Size {
-    fn fmt_into(&self, dst: &mut String) {
-        match self {
-            Self::Max => dst.push_str("MAX"),
-            Self::Sized(size) => dst.push_str(itoa::Buffer::new().format(*size)),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-pub enum DataType {
+/// The type of a column. Types without a Rust equivalent are kept as [`ColumnType::Unsupported`],
+/// so they only cause an error if code is actually generated for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnType {
+    Bool,
     Int64,
-    String(Size),
-    Bytes(Size),
-    Json(Size),
+    Float32,
     Float64,
-    Bool,
+    Numeric,
+    String,
+    Bytes,
+    Json,
     Date,
     Timestamp,
-    Array(Box<DataType>),
+    Uuid,
+    Array(Box<ColumnType>),
+    Unsupported(String),
 }
 
-impl SpannerType for DataType {
-    const TYPE: &'static spanner_rs::Type = &spanner_rs::Type::Scalar(Scalar::String);
+/// A primary or index key column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPart {
+    pub column: String,
+    pub ascending: bool,
 }
 
-impl IntoSpanner for DataType {
-    fn into_value(self) -> Value {
-        let mut dst = String::with_capacity(32);
-        self.fmt_into(&mut dst);
-        dst.into_value()
-    }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interleave {
+    pub parent: String,
+    pub on_delete: OnDelete,
 }
 
-impl FromSpanner for DataType {
-    fn from_value(value: Value) -> Result<Self, ConvertError> {
-        let s = value.into_string::<Self>()?;
-        Self::parse_from(&s).map_err(ConvertError::from)
-    }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnDelete {
+    Cascade,
+    #[default]
+    NoAction,
 }
 
-fn parse_size(s: &str) -> Result<Size, FromError> {
-    if s.eq_ignore_ascii_case("MAX") {
-        Ok(Size::Max)
-    } else {
-        s.parse()
-            .map(Size::Sized)
-            .map_err(|err| FromError::from_error::<DataType>(err))
-    }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    pub name: String,
+    pub unique: bool,
+    pub null_filtered: bool,
+    pub keys: Vec<KeyPart>,
+    pub storing: Vec<String>,
+    pub interleave_in: Option<String>,
 }
 
-fn parse_array(s: &str) -> Result<DataType, FromError> {
-    const LEADING: &str = "ARRAY<";
-
-    if s.len() <= LEADING.len() || !s[..LEADING.len()].eq_ignore_ascii_case(LEADING) {
-        return Err(FromError::from_anyhow::<DataType>(anyhow::anyhow!(
-            "invalid string"
-        )));
-    }
-
-    let rem = &s[LEADING.len()..];
+impl Schema {
+    /// Builds a schema from a list of DDL statements, i.e a schema dump or a set of migrations.
+    ///
+    /// 'ALTER' and 'DROP' statements are applied in order, and statements that don't affect
+    /// tables or indexes (views, change streams, etc) are ignored.
+    pub fn from_ddl(ddl: &str) -> Result<Self, Error> {
+        let statements = parse_statements(ddl).map_err(|error| Error::Ddl(error.to_string()))?;
 
-    let end = rem
-        .find('>')
-        .ok_or_else(|| FromError::from_anyhow::<DataType>(anyhow::anyhow!("invalid string")))?;
+        let mut schema = Self::default();
 
-    let elem = DataType::parse_from(&rem[..end])?;
-    Ok(DataType::Array(Box::new(elem)))
-}
+        for statement in statements {
+            if let Statement::Ddl(statement) = statement {
+                schema.apply(statement)?;
+            }
+        }
 
-fn parse_size_type(s: &str) -> Option<Result<DataType, FromError>> {
-    if s.contains("<") {
-        return None;
+        Ok(schema)
     }
 
-    let start = s.find('(')?;
-    let end = s.find(')')?;
-
-    let leading = &s[..start];
-
-    let size = match parse_size(&s[start + 1..end]) {
-        Ok(size) => size,
-        Err(err) => return Some(Err(err)),
-    };
-
-    if leading.eq_ignore_ascii_case("BYTES") {
-        Some(Ok(DataType::Bytes(size)))
-    } else if leading.eq_ignore_ascii_case("JSON") {
-        Some(Ok(DataType::Json(size)))
-    } else if leading.eq_ignore_ascii_case("STRING") {
-        Some(Ok(DataType::String(size)))
-    } else {
-        Some(Err(FromError::from_anyhow::<DataType>(anyhow::anyhow!(
-            "unknown data type '{leading}'"
-        ))))
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables
+            .iter()
+            .find(|table| table.name.eq_ignore_ascii_case(name))
     }
-}
 
-impl DataType {
-    fn parse_from(s: &str) -> Result<Self, FromError> {
-        const SIMPLE: &[(&str, DataType)] = &[
-            ("INT64", DataType::Int64),
-            ("FLOAT64", DataType::Float64),
-            ("BOOL", DataType::Bool),
-            ("DATE", DataType::Date),
-            ("TIMESTAMP", DataType::Timestamp),
-        ];
-
-        if let Some(dt) = SIMPLE.iter().find_map(|(simple, val)| {
-            if simple.eq_ignore_ascii_case(s) {
-                Some(val.clone())
-            } else {
-                None
-            }
-        }) {
-            return Ok(dt);
-        }
-
-        if let Some(res) = parse_size_type(s) {
-            return res;
-        }
+    fn table_mut(&mut self, name: &str) -> Result<&mut Table, Error> {
+        self.tables
+            .iter_mut()
+            .find(|table| table.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::UnknownTable(name.to_owned()))
+    }
 
-        parse_array(s)
+    fn index_mut(&mut self, name: &str) -> Result<&mut Index, Error> {
+        self.tables
+            .iter_mut()
+            .flat_map(|table| table.indexes.iter_mut())
+            .find(|index| index.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::UnknownIndex(name.to_owned()))
     }
 
-    fn fmt_into(&self, dst: &mut String) {
-        match self {
-            Self::Int64 => dst.push_str("INT64"),
-            Self::Float64 => dst.push_str("FLOAT64"),
-            Self::Bool => dst.push_str("BOOL"),
-            Self::Date => dst.push_str("DATE"),
-            Self::Timestamp => dst.push_str("TIMESTAMP"),
-            Self::String(size) => {
-                dst.push_str("STRING(");
-                size.fmt_into(dst);
-                dst.push_str(")");
+    fn apply(&mut self, statement: DdlStatement<'_>) -> Result<(), Error> {
+        match statement {
+            DdlStatement::CreateTable(create) => {
+                let name = create.name.to_string();
+
+                if self.table(&name).is_some() {
+                    if create.if_not_exists {
+                        return Ok(());
+                    }
+                    return Err(Error::DuplicateTable(name));
+                }
+
+                self.tables.push(Table {
+                    name,
+                    columns: create.columns.iter().map(Column::from_def).collect(),
+                    primary_key: create.primary_key.iter().map(KeyPart::from_ast).collect(),
+                    interleave: create.interleave.map(|interleave| Interleave {
+                        parent: interleave.parent.to_string(),
+                        on_delete: interleave
+                            .on_delete
+                            .map(OnDelete::from_ast)
+                            .unwrap_or_default(),
+                    }),
+                    indexes: Vec::new(),
+                });
+            }
+            DdlStatement::AlterTable(alter) => {
+                let table = self.table_mut(&alter.name.to_string())?;
+
+                match alter.action {
+                    AlterTableAction::AddColumn { column, .. } => {
+                        if table.column(column.name.as_str()).is_none() {
+                            table.columns.push(Column::from_def(&column));
+                        }
+                    }
+                    AlterTableAction::DropColumn(column) => {
+                        table.column_mut(column.as_str())?;
+                        table
+                            .columns
+                            .retain(|col| !col.name.eq_ignore_ascii_case(column.as_str()));
+                    }
+                    AlterTableAction::AlterColumn {
+                        column,
+                        alteration: ColumnAlteration::Redefine { ty, not_null, .. },
+                    } => {
+                        let column = table.column_mut(column.as_str())?;
+                        column.ty = ColumnType::from_ast(&ty);
+                        column.nullable = !not_null;
+                    }
+                    AlterTableAction::SetOnDelete(on_delete) => {
+                        if let Some(interleave) = table.interleave.as_mut() {
+                            interleave.on_delete = OnDelete::from_ast(on_delete);
+                        }
+                    }
+                    AlterTableAction::RenameTo(name) => table.name = name.to_string(),
+                    _ => (),
+                }
             }
-            Self::Bytes(size) => {
-                dst.push_str("BYTES(");
-                size.fmt_into(dst);
-                dst.push_str(")");
+            DdlStatement::DropTable(drop) => {
+                let name = drop.name.to_string();
+                if self.table(&name).is_none() && drop.if_exists {
+                    return Ok(());
+                }
+                self.table_mut(&name)?;
+                self.tables
+                    .retain(|table| !table.name.eq_ignore_ascii_case(&name));
             }
-            Self::Json(size) => {
-                dst.push_str("JSON(");
-                size.fmt_into(dst);
-                dst.push_str(")");
+            DdlStatement::CreateIndex(create) => {
+                let name = create.name.to_string();
+                let table = self.table_mut(&create.table.to_string())?;
+
+                if table.index(&name).is_some() && create.if_not_exists {
+                    return Ok(());
+                }
+
+                table.indexes.push(Index {
+                    name,
+                    unique: create.unique,
+                    null_filtered: create.null_filtered,
+                    keys: create.keys.iter().map(KeyPart::from_ast).collect(),
+                    storing: create
+                        .storing
+                        .iter()
+                        .map(|column| column.as_str().to_owned())
+                        .collect(),
+                    interleave_in: create.interleave_in.map(|parent| parent.to_string()),
+                });
             }
-            Self::Array(elem) => {
-                dst.push_str("ARRAY<");
-                elem.fmt_into(dst);
-                dst.push_str(">");
+            DdlStatement::AlterIndex(alter) => {
+                let index = self.index_mut(&alter.name.to_string())?;
+
+                match alter.action {
+                    AlterIndexAction::AddStoredColumn(column) => {
+                        index.storing.push(column.as_str().to_owned())
+                    }
+                    AlterIndexAction::DropStoredColumn(column) => index
+                        .storing
+                        .retain(|stored| !stored.eq_ignore_ascii_case(column.as_str())),
+                }
             }
+            DdlStatement::DropIndex(drop) => {
+                let name = drop.name.to_string();
+                if let Err(error) = self.index_mut(&name) {
+                    return if drop.if_exists { Ok(()) } else { Err(error) };
+                }
+
+                for table in self.tables.iter_mut() {
+                    table
+                        .indexes
+                        .retain(|index| !index.name.eq_ignore_ascii_case(&name));
+                }
+            }
+            _ => (),
         }
+
+        Ok(())
     }
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum SpannerState {
-    Committed,
-    WriteOnly,
+impl Table {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+    }
+
+    fn column_mut(&mut self, name: &str) -> Result<&mut Column, Error> {
+        let table = &self.name;
+        self.columns
+            .iter_mut()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::UnknownColumn {
+                table: table.clone(),
+                column: name.to_owned(),
+            })
+    }
+
+    pub fn index(&self, name: &str) -> Option<&Index> {
+        self.indexes
+            .iter()
+            .find(|index| index.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the 1-based position of a column in the primary key, if it's part of it.
+    pub fn pk_position(&self, column: &str) -> Option<usize> {
+        self.primary_key
+            .iter()
+            .position(|key| key.column.eq_ignore_ascii_case(column))
+            .map(|idx| idx + 1)
+    }
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum IsNullable {
-    Yes,
-    No,
+impl Column {
+    fn from_def(def: &ColumnDef<'_>) -> Self {
+        Self {
+            name: def.name.as_str().to_owned(),
+            ty: ColumnType::from_ast(&def.ty),
+            nullable: !def.not_null,
+            generated: matches!(def.value, Some(ColumnValue::Generated { .. })),
+        }
+    }
 }
 
-#[test]
-fn test_wtf() {
-    use serde::de::value::Error;
-    use serde::de::{Deserialize, IntoDeserializer};
-    let res: Result<_, Error> = IsNullable::deserialize("NO".into_deserializer());
+impl ColumnType {
+    /// Parses a type name, as found in the 'SPANNER_TYPE' column of 'INFORMATION_SCHEMA.COLUMNS'.
+    pub fn parse(spanner_type: &str) -> Self {
+        match Type::parse(spanner_type) {
+            Ok(ty) => Self::from_ast(&ty),
+            // not all types are known to the parser (i.e UUID, or PROTO/ENUM columns)
+            Err(_) if spanner_type.eq_ignore_ascii_case("UUID") => Self::Uuid,
+            Err(_) => Self::Unsupported(spanner_type.to_owned()),
+        }
+    }
 
-    println!("{res:#?}");
+    fn from_ast(ty: &Type<'_>) -> Self {
+        match ty {
+            Type::Scalar { data_type, .. } => match data_type {
+                DataType::Bool => Self::Bool,
+                DataType::Int64 => Self::Int64,
+                DataType::Float32 => Self::Float32,
+                DataType::Float64 => Self::Float64,
+                DataType::Numeric => Self::Numeric,
+                DataType::String => Self::String,
+                DataType::Bytes => Self::Bytes,
+                DataType::Json => Self::Json,
+                DataType::Date => Self::Date,
+                DataType::Timestamp => Self::Timestamp,
+                DataType::Array | DataType::Struct => Self::Unsupported(format!("{data_type:?}")),
+            },
+            Type::Array(element) => Self::Array(Box::new(Self::from_ast(element))),
+            Type::Struct(_) => Self::Unsupported("STRUCT".to_owned()),
+        }
+    }
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum IsGenerated {
-    Never,
-    Always,
+impl KeyPart {
+    fn from_ast(key: &google_sql::ast::ddl::table::KeyPart<'_>) -> Self {
+        Self {
+            column: key.column.as_str().to_owned(),
+            ascending: key.ascending,
+        }
+    }
 }
 
-#[derive(Debug, spanner_rs::Table)]
-pub struct RawColumnView {
-    #[spanner(pk = 0)]
-    table_name: String,
-    #[spanner(pk = 1)]
-    column_name: String,
-    ordinal_position: std::num::NonZeroUsize,
-    column_default: core::option::Option<String>,
-    #[spanner(with_serde_as = "String")]
-    is_nullable: IsNullable,
-    spanner_type: DataType,
-    #[spanner(with_serde_as = "String")]
-    is_generated: IsGenerated,
-    #[spanner(with_serde_as = "String")]
-    spanner_state: SpannerState,
-    #[spanner(with_serde_as = "String")]
-    index_name: Option<IndexType>,
-    #[spanner(with_serde_as = "String")]
-    index_type: Option<IndexType>,
-    #[spanner(with_serde_as = "String")]
-    column_ordering: Option<Ordering>,
+impl OnDelete {
+    fn from_ast(on_delete: google_sql::ast::ddl::OnDelete) -> Self {
+        match on_delete {
+            google_sql::ast::ddl::OnDelete::Cascade => Self::Cascade,
+            google_sql::ast::ddl::OnDelete::NoAction => Self::NoAction,
+        }
+    }
 }
@@ -0,0 +1,53 @@
+//! `#[derive(Table)]`, which hands the struct to the same parser as `spanner_rs::row!`. The
+//! struct already exists, so the parser is told (via `meta = [__derived]`) to only generate the
+//! impls.
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub fn derive(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(ref fields),
+            ..
+        }) => fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`Table` can only be derived for structs with named fields",
+            ));
+        }
+    };
+
+    if let Some(ref where_clause) = input.generics.where_clause {
+        return Err(syn::Error::new_spanned(
+            where_clause,
+            "`Table` can't be derived for structs with a where clause",
+        ));
+    }
+
+    // only the 'spanner' attributes mean anything to the parser, the rest are already on the
+    // struct.
+    let attrs = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("spanner"));
+
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let generics = &input.generics;
+
+    Ok(quote! {
+        ::spanner_rs::__parse_row! {
+            tokens = [#(#attrs)* #vis struct #ident #generics #fields],
+            imports = [],
+            meta = [__derived],
+            unprocessed_spanner_meta = [],
+            row_ident = unknown,
+            row_vis = unknown,
+            table_name = [],
+            generics = [],
+            pk_name = [PrimaryKey],
+            pks = [],
+        }
+    })
+}
@@ -8,27 +8,111 @@ use proc_macro::TokenStream;
 mod ident_str;
 
 mod database;
+mod derive_table;
 mod impl_pk_sealed;
 mod into_spanner;
+mod query;
+mod schema;
 mod table;
 
+/// Defines a row type for a query, checking the SQL against the [`Table`]s it references (or a
+/// schema declared with `database!`) at compile time.
+///
+/// The SQL is parsed when the macro is expanded, and the columns (and query parameters compared
+/// against columns) are checked to exist with the right types via `const` evaluation, so typos
+/// fail the build rather than the query.
+///
+/// ```ignore
+/// spanner_rs::query! {
+///     sql: "SELECT AlbumId, AlbumTitle AS Title FROM Albums WHERE SingerId = @singer_id",
+///     tables: [Album],
+///
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct SingerAlbums {
+///         pub album_id: i64,
+///         pub title: Option<String>,
+///     }
+/// }
+///
+/// let params = SingerAlbums::params().singer_id(1_i64).build();
+/// let rows = client.execute_sql::<SingerAlbums>(SingerAlbums::SQL.to_owned(), Some(params)).await?;
+/// ```
+///
+/// Instead of `tables`, `database: Music` checks the query against a schema declared with
+/// [`database!`].
+///
+/// Fields are matched up with the query columns by position, and need to have the same name
+/// (ignoring case and underscores). Alongside the row type, a `<Row>Params` builder is generated
+/// with a setter for each query parameter.
+///
+/// [`Table`]: https://docs.rs/spanner-rs/latest/spanner_rs/table/trait.Table.html
 #[proc_macro]
 pub fn query(tokens: TokenStream) -> TokenStream {
-    tokens
+    match query::derive(syn::parse_macro_input!(tokens as query::QueryInput)) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
 }
 
+/// Either builds a [`Database`] from its project, instance and database IDs (validated at compile
+/// time), or declares a database schema that `query!` can check queries against.
+///
+/// ```ignore
+/// let database = spanner_rs::database!("my-project", "my-instance", "my-database");
+///
+/// spanner_rs::database! {
+///     // a file of DDL statements, relative to the crate root (or inline with 'ddl: "..."')
+///     schema: "migrations/schema.sql",
+///
+///     pub struct Music;
+/// }
+///
+/// spanner_rs::query! {
+///     sql: "SELECT SingerId, FirstName FROM Singers",
+///     database: Music,
+///
+///     pub struct SingerNames {
+///         pub singer_id: i64,
+///         pub first_name: Option<String>,
+///     }
+/// }
+/// ```
+///
+/// The DDL statements are applied in order, so a migration history works as a schema.
+///
+/// [`Database`]: https://docs.rs/spanner-rs/latest/spanner_rs/info/struct.Database.html
 #[proc_macro]
 pub fn database(tokens: TokenStream) -> TokenStream {
-    match database::parse(tokens) {
+    let result = if schema::is_schema_input(&tokens.clone().into()) {
+        syn::parse::<schema::SchemaInput>(tokens).and_then(schema::derive)
+    } else {
+        database::parse(tokens)
+    };
+
+    match result {
         Ok(tokens) => tokens.into(),
         Err(error) => error.into_compile_error().into(),
     }
 }
 
+/// Implements `Table` (along with the primary key and partial row types) for a struct, exactly
+/// like wrapping it in `spanner_rs::row!` would, taking the same `#[spanner(...)]` options.
+///
+/// ```ignore
+/// #[derive(spanner_rs::Table, Debug, Clone, PartialEq)]
+/// #[spanner(table = "Singers")]
+/// pub struct Singer {
+///     #[spanner(pk = 1)]
+///     pub singer_id: i64,
+///     pub first_name: Option<String>,
+/// }
+/// ```
+///
+/// Like `row!`, the generated types clash if 2 tables are derived in the same module.
 #[proc_macro_derive(Table, attributes(spanner))]
 pub fn table_derive(tokens: TokenStream) -> TokenStream {
-    match table::derive(syn::parse_macro_input!(tokens as table::TableInput)) {
-        Ok(tokens) => tokens,
+    match derive_table::derive(syn::parse_macro_input!(tokens as syn::DeriveInput)) {
+        Ok(tokens) => tokens.into(),
         Err(err) => TokenStream::from(err.into_compile_error()),
     }
 }
@@ -0,0 +1,468 @@
+use convert_case::{Case, Casing};
+use google_sql::ast::Statement;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+use self::resolve::{Analysis, ColumnRef, ParamType};
+
+mod resolve;
+
+/// Parsed input to `query!`:
+///
+/// ```text
+/// query! {
+///     sql: "SELECT ...",
+///     tables: [Table1, Table2],
+///     // or instead of 'tables', a schema declared with 'database!'
+///     database: Schema,
+///     // optional, defaults to 'spanner_rs'
+///     crate_name: spanner_rs,
+///
+///     #[derive(...)]
+///     pub struct RowType {
+///         ...
+///     }
+/// }
+/// ```
+pub struct QueryInput {
+    crate_name: Option<syn::Ident>,
+    sql: syn::LitStr,
+    source: Source,
+    item: syn::ItemStruct,
+}
+
+/// Where the tables and columns referenced by the query are looked up.
+enum Source {
+    /// [`Table`] types, each providing a single table.
+    ///
+    /// [`Table`]: spanner_rs::Table
+    Tables(Vec<syn::Type>),
+    /// A schema type declared by `database!`.
+    Database(Box<syn::Type>),
+}
+
+impl syn::parse::Parse for QueryInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut crate_name = None;
+        let mut sql = None;
+        let mut tables = None;
+        let mut database = None;
+
+        while input.peek(syn::Ident::peek_any) && input.peek2(syn::Token![:]) {
+            let ident = input.call(syn::Ident::parse_any)?;
+            input.parse::<syn::Token![:]>()?;
+
+            if ident == "crate_name" {
+                crate_name = Some(input.call(syn::Ident::parse_any)?);
+            } else if ident == "sql" {
+                sql = Some(input.parse::<syn::LitStr>()?);
+            } else if ident == "tables" {
+                let content;
+                syn::bracketed!(content in input);
+                let parsed = content.parse_terminated::<_, syn::Token![,]>(syn::Type::parse)?;
+                tables = Some(parsed.into_iter().collect::<Vec<_>>());
+            } else if ident == "database" {
+                database = Some(input.parse::<syn::Type>()?);
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "unknown option, expected one of: 'sql', 'tables', 'database' or 'crate_name'",
+                ));
+            }
+
+            if input.peek(syn::Token![,]) {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        let item = input.parse::<syn::ItemStruct>()?;
+
+        let sql = sql.ok_or_else(|| syn::Error::new(item.ident.span(), "missing 'sql' option"))?;
+        let source = match (tables, database) {
+            (Some(tables), None) => Source::Tables(tables),
+            (None, Some(database)) => Source::Database(Box::new(database)),
+            (Some(_), Some(_)) => {
+                return Err(syn::Error::new(
+                    item.ident.span(),
+                    "only one of 'tables' or 'database' can be set",
+                ));
+            }
+            (None, None) => {
+                return Err(syn::Error::new(
+                    item.ident.span(),
+                    "missing a 'tables' or 'database' option",
+                ));
+            }
+        };
+
+        Ok(Self {
+            crate_name,
+            sql,
+            source,
+            item,
+        })
+    }
+}
+
+pub fn derive(input: QueryInput) -> syn::Result<TokenStream> {
+    let QueryInput {
+        crate_name,
+        sql,
+        source,
+        item,
+    } = input;
+
+    let krate = match crate_name {
+        Some(crate_name) => quote!(#crate_name),
+        None => quote!(::spanner_rs),
+    };
+
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "query row types can't be generic",
+        ));
+    }
+
+    let fields = match item.fields {
+        syn::Fields::Named(ref named) => named.named.iter().collect::<Vec<_>>(),
+        _ => {
+            return Err(syn::Error::new(
+                item.ident.span(),
+                "query row types must have named fields",
+            ));
+        }
+    };
+
+    let sql_string = sql.value();
+    let analysis = analyze(&sql, &sql_string)?;
+
+    if analysis.outputs.len() != fields.len() {
+        return Err(syn::Error::new(
+            item.ident.span(),
+            format!(
+                "the query returns {} columns, but '{}' has {} fields",
+                analysis.outputs.len(),
+                item.ident,
+                fields.len(),
+            ),
+        ));
+    }
+
+    // columns are decoded by index, so make sure the fields line up with the query
+    for (field, output) in fields.iter().zip(analysis.outputs.iter()) {
+        let field_ident = field.ident.as_ref().expect("named fields have idents");
+
+        if normalize(&field_ident.to_string()) != normalize(&output.name) {
+            return Err(syn::Error::new(
+                field_ident.span(),
+                format!(
+                    "field '{field_ident}' doesn't match the query column in the same position: \
+                     '{}'",
+                    output.name,
+                ),
+            ));
+        }
+    }
+
+    let ident = &item.ident;
+    let vis = &item.vis;
+    let params_ident = format_ident!("{}Params", ident);
+
+    let lookup = Lookup {
+        krate: &krate,
+        row: ident,
+    };
+
+    let table_checks = analysis.tables.iter().map(|table| match source {
+        Source::Tables(ref tables) => {
+            let message = format!("table '{table}' isn't one of the tables passed to `query!`");
+            quote! {
+                if !(false #(|| #krate::__macro_internals::ident_eq(<#tables as #krate::Table>::NAME, #table))*) {
+                    panic!(#message);
+                }
+            }
+        }
+        Source::Database(ref database) => {
+            let message = format!("table '{table}' doesn't exist in the database schema");
+            quote! {
+                if !<#database>::__has_table(#table) {
+                    panic!(#message);
+                }
+            }
+        }
+    });
+
+    let column_checks = analysis.columns.iter().map(|column| {
+        let column = lookup.expect_column(column);
+        quote!(#column;)
+    });
+
+    let field_checks = fields
+        .iter()
+        .zip(analysis.outputs.iter())
+        .filter_map(|(field, output)| Some((field, output.column.as_ref()?)))
+        .map(|(field, column)| {
+            let ty = &field.ty;
+            let field_ident = field.ident.as_ref().expect("named fields have idents");
+            let column_expr = lookup.expect_column(column);
+
+            let mismatched = format!(
+                "field '{field_ident}' has a different type than column '{}'",
+                column.column
+            );
+            let nullable = format!(
+                "column '{}' is nullable, so field '{field_ident}' needs to be an Option",
+                column.column
+            );
+
+            quote! {
+                #krate::__macro_internals::check_field::<<#ty as #krate::SpannerEncode>::SpannerType>(
+                    #column_expr,
+                    #mismatched,
+                    #nullable,
+                );
+            }
+        });
+
+    let n_columns = fields.len();
+    let column_defs = fields
+        .iter()
+        .zip(analysis.outputs.iter())
+        .enumerate()
+        .map(|(index, (field, output))| {
+            let ty = &field.ty;
+            let name = &output.name;
+            quote! {
+                #krate::column::Column::new::<<#ty as #krate::SpannerEncode>::SpannerType>(#index, #name)
+            }
+        });
+
+    let field_decoders = fields.iter().enumerate().map(|(index, field)| {
+        let field_ident = &field.ident;
+        quote! {
+            #field_ident: row.decode_at_index(#index, #krate::__macro_internals::from_spanner)?
+        }
+    });
+
+    let column_lookup = match source {
+        Source::Tables(ref tables) => {
+            let lookups = tables.iter().map(|table| {
+                quote! {
+                    if #krate::__macro_internals::ident_eq(<#table as #krate::Table>::NAME, table) {
+                        return #krate::__macro_internals::find_column(
+                            <#table as #krate::queryable::Row>::COLUMNS.as_slice(),
+                            column,
+                        );
+                    }
+                }
+            });
+
+            quote! {
+                #(#lookups)*
+                ::core::option::Option::None
+            }
+        }
+        Source::Database(ref database) => quote!(<#database>::__column(table, column)),
+    };
+
+    let n_params = analysis.params.len();
+    let param_setters = analysis
+        .params
+        .iter()
+        .map(|param| {
+            let name = &param.name;
+            let method = param_method_ident(name, &sql)?;
+
+            let checks = param.types.iter().map(|ty| {
+                let (expected, described) = match ty {
+                    ParamType::Int64 => (quote!(&#krate::Type::INT64), "INT64".to_owned()),
+                    ParamType::Column(column) => {
+                        let column_expr = lookup.expect_column(column);
+                        (quote!(#column_expr.ty), format!("column '{}'", column.column))
+                    }
+                };
+
+                let mismatched = format!(
+                    "query parameter '@{name}' needs to have the same type as {described}"
+                );
+
+                quote! {
+                    #krate::__macro_internals::check_param::<<T as #krate::IntoSpanner>::SpannerType>(
+                        #expected,
+                        #mismatched,
+                    );
+                }
+            });
+
+            let doc = format!("Sets the '@{name}' query parameter.");
+
+            Ok(quote! {
+                #[doc = #doc]
+                pub fn #method<T: #krate::IntoSpanner>(mut self, value: T) -> Self {
+                    const {
+                        #(#checks)*
+                    }
+                    self.params.insert(#name, value);
+                    self
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let params_doc = format!("Builder for the query parameters of [`{ident}`].");
+    let build_doc = format!("Builds the parameters, to be passed along with [`{ident}::SQL`].");
+
+    Ok(quote! {
+        #item
+
+        #[automatically_derived]
+        impl #ident {
+            /// The SQL for this query.
+            pub const SQL: &'static str = #sql;
+
+            /// Returns a builder for the query parameters.
+            pub fn params() -> #params_ident {
+                #params_ident {
+                    params: #krate::sql::Params::with_capacity(#n_params),
+                }
+            }
+
+            #[doc(hidden)]
+            const fn __column(
+                table: &str,
+                column: &str,
+            ) -> ::core::option::Option<#krate::column::Column<'static>> {
+                #column_lookup
+            }
+        }
+
+        const _: () = {
+            #(#table_checks)*
+            #(#column_checks)*
+            #(#field_checks)*
+        };
+
+        #[automatically_derived]
+        impl #krate::queryable::Row for #ident {
+            type NumColumns = #krate::__macro_internals::typenum::U<#n_columns>;
+            type ColumnName = &'static str;
+
+            const COLUMNS: #krate::__macro_internals::generic_array::GenericArray<
+                #krate::column::Column<'static>,
+                Self::NumColumns,
+            > = #krate::__macro_internals::generic_array::GenericArray::from_array([
+                #(#column_defs,)*
+            ]);
+        }
+
+        #[automatically_derived]
+        impl #krate::queryable::Queryable for #ident {
+            fn from_row(
+                mut row: #krate::results::RawRow<'_, Self::NumColumns>,
+            ) -> #krate::Result<Self> {
+                Ok(Self {
+                    #(#field_decoders,)*
+                })
+            }
+        }
+
+        #[doc = #params_doc]
+        #[derive(Debug, Clone, PartialEq)]
+        #vis struct #params_ident {
+            params: #krate::sql::Params,
+        }
+
+        #[automatically_derived]
+        impl #params_ident {
+            #(#param_setters)*
+
+            #[doc = #build_doc]
+            pub fn build(self) -> #krate::sql::Params {
+                self.params
+            }
+        }
+
+        #[automatically_derived]
+        impl ::core::convert::From<#params_ident> for #krate::sql::Params {
+            fn from(params: #params_ident) -> Self {
+                params.params
+            }
+        }
+    })
+}
+
+/// Parses and walks the SQL, pointing any errors at the SQL literal.
+fn analyze(sql: &syn::LitStr, sql_string: &str) -> syn::Result<Analysis> {
+    let statement = Statement::parse(sql_string)
+        .map_err(|error| syn::Error::new(sql.span(), format!("invalid SQL: {error}")))?;
+
+    let Statement::Query(query) = statement else {
+        return Err(syn::Error::new(
+            sql.span(),
+            "`query!` only supports queries (i.e 'SELECT' statements)",
+        ));
+    };
+
+    Analysis::new(&query.query_expr).map_err(|error| syn::Error::new(sql.span(), error))
+}
+
+/// Builds the `const` expressions to look up columns.
+struct Lookup<'a> {
+    krate: &'a TokenStream,
+    row: &'a syn::Ident,
+}
+
+impl Lookup<'_> {
+    fn expect_column(&self, column: &ColumnRef) -> TokenStream {
+        let Self { krate, row } = self;
+        let name = &column.column;
+
+        let candidates = column
+            .tables
+            .iter()
+            .map(|table| quote!(#row::__column(#table, #name)));
+
+        let missing = match column.tables.as_slice() {
+            [table] => format!("column '{name}' doesn't exist in table '{table}'"),
+            tables => format!(
+                "column '{name}' doesn't exist in any of the tables: {}",
+                tables.join(", ")
+            ),
+        };
+        let ambiguous =
+            format!("column '{name}' is ambiguous, qualify it with a table name or alias");
+
+        quote! {
+            #krate::__macro_internals::expect_column(
+                &[#(#candidates),*],
+                #missing,
+                #ambiguous,
+            )
+        }
+    }
+}
+
+/// Builds the setter name for a query parameter.
+fn param_method_ident(name: &str, sql: &syn::LitStr) -> syn::Result<syn::Ident> {
+    let snake = name.to_case(Case::Snake);
+
+    if snake == "build" {
+        return Err(syn::Error::new(
+            sql.span(),
+            "query parameters can't be named '@build', since it would clash with the builder",
+        ));
+    }
+
+    Ok(syn::parse_str::<syn::Ident>(&snake)
+        .unwrap_or_else(|_| syn::Ident::new_raw(&snake, Span::call_site())))
+}
+
+/// Normalizes a field or column name, so 'album_title' matches 'AlbumTitle'.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|ch| *ch != '_')
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect()
+}
@@ -0,0 +1,550 @@
+//! Walks a parsed query, collecting the tables, columns and query parameters it references, so
+//! they can be checked against the [`Table`] definitions passed to `query!`.
+//!
+//! Column references are only resolved when we know which table they come from. References
+//! into subqueries, CTEs or UNNEST-ed arrays are skipped, since we'd need to type check the
+//! inner queries to know their columns.
+//!
+//! [`Table`]: spanner_rs::Table
+use google_sql::ast::common::AsAlias;
+use google_sql::ast::common::expression::{Arguments, Expression, Having, ValueExpr};
+use google_sql::ast::common::ident::{Ident, Path};
+use google_sql::ast::common::operator::{AccessOperation, BinaryOperator, InRhs, Index, Operation};
+use google_sql::ast::query::select::{FromItem, JoinCondition, Select, SelectListItem};
+use google_sql::ast::query::{QueryExpr, QueryKind};
+
+/// A column that needs to exist in one of the tables passed to the macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnRef {
+    /// The names of the tables the column could belong to. Only has 1 element if the column
+    /// was qualified.
+    pub tables: Vec<String>,
+    pub column: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamType {
+    /// Compared against a column, so it needs to have the same type.
+    Column(ColumnRef),
+    /// Used as a 'LIMIT' or 'OFFSET'.
+    Int64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub types: Vec<ParamType>,
+}
+
+/// A column in the rows returned by the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub name: String,
+    /// The column the output is selected from, if it's a plain column reference.
+    pub column: Option<ColumnRef>,
+}
+
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// Names of all tables referenced in 'FROM' clauses.
+    pub tables: Vec<String>,
+    pub columns: Vec<ColumnRef>,
+    pub params: Vec<Param>,
+    pub outputs: Vec<Output>,
+}
+
+impl Analysis {
+    pub fn new(query: &QueryExpr<'_>) -> Result<Self, String> {
+        let mut analysis = Self::default();
+        let mut outputs = Vec::new();
+        analysis.walk_query(query, None, Some(&mut outputs))?;
+        analysis.outputs = outputs;
+        Ok(analysis)
+    }
+
+    fn add_table(&mut self, table: &str) {
+        if !self.tables.iter().any(|t| t.eq_ignore_ascii_case(table)) {
+            self.tables.push(table.to_owned());
+        }
+    }
+
+    fn add_column(&mut self, column: ColumnRef) {
+        if !self.columns.contains(&column) {
+            self.columns.push(column);
+        }
+    }
+
+    fn add_param(&mut self, name: &str, ty: Option<ParamType>) {
+        let param = match self.params.iter_mut().find(|param| param.name == name) {
+            Some(param) => param,
+            None => {
+                self.params.push(Param {
+                    name: name.to_owned(),
+                    types: Vec::new(),
+                });
+                self.params.last_mut().expect("just pushed")
+            }
+        };
+
+        if let Some(ty) = ty
+            && !param.types.contains(&ty)
+        {
+            param.types.push(ty);
+        }
+    }
+
+    fn walk_query(
+        &mut self,
+        query: &QueryExpr<'_>,
+        parent: Option<&Scope<'_>>,
+        outputs: Option<&mut Vec<Output>>,
+    ) -> Result<(), String> {
+        let mut ctes = Vec::with_capacity(query.cte.len());
+        for cte in query.cte.iter() {
+            let scope = Scope::empty(parent, &ctes);
+            self.walk_query(&cte.to, Some(&scope), None)?;
+            ctes.push(cte.from.as_str().to_owned());
+        }
+
+        match &*query.kind {
+            QueryKind::Select(select) => {
+                let mut scope = Scope::empty(parent, &ctes);
+                self.walk_select(select, &mut scope, outputs)?;
+
+                for order_by in query.order_by.iter() {
+                    self.walk_expr(&order_by.expr, &scope)?;
+                }
+            }
+            QueryKind::SubQuery(inner) => {
+                let scope = Scope::empty(parent, &ctes);
+                self.walk_query(inner, Some(&scope), outputs)?;
+            }
+            QueryKind::SetOperation(set_op) => {
+                // the column names come from the first query in a set operation
+                let scope = Scope::empty(parent, &ctes);
+                self.walk_query(&set_op.lhs, Some(&scope), outputs)?;
+                self.walk_query(&set_op.rhs, Some(&scope), None)?;
+
+                // 'ORDER BY' can only reference output columns here, which we don't check.
+                let scope = Scope::opaque(parent, &ctes);
+                for order_by in query.order_by.iter() {
+                    self.walk_expr(&order_by.expr, &scope)?;
+                }
+            }
+        }
+
+        if let Some(limit) = query.limit.as_ref() {
+            let scope = Scope::opaque(parent, &ctes);
+            for expr in std::iter::once(&limit.count).chain(limit.offset.as_ref()) {
+                match as_param(expr) {
+                    Some(param) => self.add_param(param, Some(ParamType::Int64)),
+                    None => self.walk_expr(expr, &scope)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn walk_select<'a>(
+        &mut self,
+        select: &'a Select<'_>,
+        scope: &mut Scope<'a>,
+        outputs: Option<&mut Vec<Output>>,
+    ) -> Result<(), String> {
+        if let Some(from) = select.from.as_ref() {
+            self.walk_from(from, scope)?;
+        }
+
+        for item in select.select_list.iter() {
+            if let SelectListItem::Expr(AsAlias {
+                alias: Some(alias), ..
+            }) = item
+            {
+                scope.aliases.push(alias.as_str());
+            }
+        }
+
+        if outputs.is_some() && select.target.is_some() {
+            return Err("'SELECT AS STRUCT' and 'SELECT AS VALUE' aren't supported".to_owned());
+        }
+
+        let mut outputs = outputs;
+
+        for (idx, item) in select.select_list.iter().enumerate() {
+            let AsAlias { item: expr, alias } = match item {
+                SelectListItem::Expr(expr) => expr,
+                SelectListItem::SelectAll(select_all) => {
+                    if outputs.is_some() {
+                        return Err(
+                            "'SELECT *' isn't supported, list the columns explicitly".to_owned()
+                        );
+                    }
+
+                    for replace in select_all.replace.iter() {
+                        self.walk_expr(&replace.item, scope)?;
+                    }
+                    continue;
+                }
+            };
+
+            self.walk_expr(expr, scope)?;
+
+            let Some(outputs) = outputs.as_mut() else {
+                continue;
+            };
+
+            let path = match expr {
+                Expression::Path(path) if as_param(expr).is_none() => Some(path),
+                _ => None,
+            };
+
+            let name = match (alias, path) {
+                (Some(alias), _) => alias.as_str(),
+                (None, Some(path)) => path.name().as_str(),
+                (None, None) => {
+                    return Err(format!(
+                        "select list item {} needs a name, add one with 'AS'",
+                        idx + 1
+                    ));
+                }
+            };
+
+            outputs.push(Output {
+                name: name.to_owned(),
+                // field access into a struct column has a different type than the column.
+                column: path
+                    .filter(|path| path.segments().len() <= 2)
+                    .and_then(|path| scope.resolve(path)),
+            });
+        }
+
+        for expr in select.where_expr.iter() {
+            self.walk_expr(expr, scope)?;
+        }
+
+        for expr in select.group_by.iter().chain(select.having.as_ref()) {
+            self.walk_expr(expr, scope)?;
+        }
+
+        Ok(())
+    }
+
+    fn walk_from<'a>(
+        &mut self,
+        from: &'a FromItem<'_>,
+        scope: &mut Scope<'a>,
+    ) -> Result<(), String> {
+        match from {
+            FromItem::Table { name, alias, .. } => {
+                let table = match name.as_ident() {
+                    Some(ident) if !scope.is_cte(ident.as_str()) => Some(ident.as_str()),
+                    // CTEs, correlated array paths or tables in other schemas
+                    _ => None,
+                };
+
+                if let Some(table) = table {
+                    self.add_table(table);
+                }
+
+                let alias = alias.unwrap_or_else(|| name.name());
+                scope.sources.push(Source {
+                    alias: alias.as_str(),
+                    table,
+                });
+            }
+            FromItem::Subquery(AsAlias { item, alias }) => {
+                self.walk_query(item, Some(scope), None)?;
+
+                scope.sources.push(Source {
+                    alias: alias.map(|alias| alias.as_str()).unwrap_or_default(),
+                    table: None,
+                });
+            }
+            FromItem::Unnest(unnest) => {
+                self.walk_expr(&unnest.expr, scope)?;
+
+                scope.sources.push(Source {
+                    alias: unnest.alias.map(|alias| alias.as_str()).unwrap_or_default(),
+                    table: None,
+                });
+
+                if let Some(offset_alias) = unnest.with_offset.as_ref().and_then(|w| w.alias) {
+                    scope.sources.push(Source {
+                        alias: offset_alias.as_str(),
+                        table: None,
+                    });
+                }
+            }
+            FromItem::Join(join) => {
+                self.walk_from(&join.lhs, scope)?;
+                self.walk_from(&join.rhs, scope)?;
+
+                // 'USING' columns exist in both sides of the join, so they'd always be ambiguous
+                // to 'Scope::resolve'.
+                if let Some(JoinCondition::On(expr)) = join.condition.as_ref() {
+                    self.walk_expr(expr, scope)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn walk_expr(&mut self, expr: &Expression<'_>, scope: &Scope<'_>) -> Result<(), String> {
+        match expr {
+            Expression::Path(path) => match as_param(expr) {
+                Some(param) => self.add_param(param, None),
+                None => {
+                    if let Some(column) = scope.resolve(path) {
+                        self.add_column(column);
+                    }
+                }
+            },
+            Expression::Value(value) => match value {
+                ValueExpr::Array { elements, .. } | ValueExpr::StructTuple(elements) => {
+                    for element in elements.iter() {
+                        self.walk_expr(element, scope)?;
+                    }
+                }
+                ValueExpr::Struct { values, .. } => {
+                    for value in values.iter() {
+                        self.walk_expr(&value.item, scope)?;
+                    }
+                }
+                _ => (),
+            },
+            Expression::FunctionCall(call) => {
+                if let Arguments::List {
+                    arguments,
+                    aggregate_options,
+                } = &call.arguments
+                {
+                    for argument in arguments.iter() {
+                        self.walk_expr(argument, scope)?;
+                    }
+
+                    if let Some(having) = aggregate_options.as_ref().and_then(|o| o.having.as_ref())
+                    {
+                        let (Having::Min(expr) | Having::Max(expr)) = &**having;
+                        self.walk_expr(expr, scope)?;
+                    }
+                }
+            }
+            Expression::SubQuery(query)
+            | Expression::Exists(query)
+            | Expression::ArraySubQuery(query) => self.walk_query(query, Some(scope), None)?,
+            Expression::Operation(operation) => self.walk_operation(operation, scope)?,
+            Expression::Cast { cast, .. } => self.walk_expr(&cast.from, scope)?,
+            Expression::Case(case) => {
+                for expr in case.operand.iter().chain(case.else_expr.as_ref()) {
+                    self.walk_expr(expr, scope)?;
+                }
+
+                for branch in case.branches.iter() {
+                    self.walk_expr(&branch.when, scope)?;
+                    self.walk_expr(&branch.then, scope)?;
+                }
+            }
+            Expression::Extract(extract) => {
+                for expr in std::iter::once(&extract.expr).chain(extract.time_zone.as_ref()) {
+                    self.walk_expr(expr, scope)?;
+                }
+            }
+            Expression::Interval(interval) => self.walk_expr(&interval.value, scope)?,
+        }
+
+        Ok(())
+    }
+
+    fn walk_operation(
+        &mut self,
+        operation: &Operation<'_>,
+        scope: &Scope<'_>,
+    ) -> Result<(), String> {
+        match operation {
+            Operation::Access(AccessOperation::Field { lhs, .. }) => self.walk_expr(lhs, scope),
+            Operation::Access(AccessOperation::Subscript { lhs, index }) => {
+                self.walk_expr(lhs, scope)?;
+                let (Index::Offset(index)
+                | Index::SafeOffset(index)
+                | Index::Ordinal(index)
+                | Index::SafeOrdinal(index)
+                | Index::Bare(index)) = &**index;
+                self.walk_expr(index, scope)
+            }
+            Operation::Unary(unary) => self.walk_expr(&unary.expr, scope),
+            Operation::Binary(binary) => {
+                if is_comparison(binary.operator) {
+                    self.compare(&binary.sides.lhs, &binary.sides.rhs, scope);
+                    self.compare(&binary.sides.rhs, &binary.sides.lhs, scope);
+                }
+
+                self.walk_expr(&binary.sides.lhs, scope)?;
+                self.walk_expr(&binary.sides.rhs, scope)
+            }
+            Operation::Between(between) => {
+                self.compare(&between.expr, &between.low, scope);
+                self.compare(&between.expr, &between.high, scope);
+
+                self.walk_expr(&between.expr, scope)?;
+                self.walk_expr(&between.low, scope)?;
+                self.walk_expr(&between.high, scope)
+            }
+            Operation::In(in_op) => {
+                self.walk_expr(&in_op.expr, scope)?;
+
+                match &in_op.rhs {
+                    InRhs::List(list) => {
+                        for expr in list.iter() {
+                            self.compare(&in_op.expr, expr, scope);
+                            self.walk_expr(expr, scope)?;
+                        }
+                        Ok(())
+                    }
+                    InRhs::SubQuery(query) => self.walk_query(query, Some(scope), None),
+                    InRhs::Unnest(expr) => self.walk_expr(expr, scope),
+                }
+            }
+        }
+    }
+
+    /// If 'column' is a column and 'param' is a query parameter, the parameter needs to have the
+    /// same type as the column.
+    fn compare(&mut self, column: &Expression<'_>, param: &Expression<'_>, scope: &Scope<'_>) {
+        let (Expression::Path(path), Some(param)) = (column, as_param(param)) else {
+            return;
+        };
+
+        if as_param(column).is_some() || path.segments().len() > 2 {
+            return;
+        }
+
+        if let Some(column) = scope.resolve(path) {
+            self.add_param(param, Some(ParamType::Column(column)));
+        }
+    }
+}
+
+fn is_comparison(operator: BinaryOperator) -> bool {
+    matches!(
+        operator,
+        BinaryOperator::Eq
+            | BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::Lte
+            | BinaryOperator::Gt
+            | BinaryOperator::Gte
+            | BinaryOperator::Like
+            | BinaryOperator::NotLike
+    )
+}
+
+/// Returns the parameter name (without the leading '@') if 'expr' is a bare query parameter.
+fn as_param<'src>(expr: &Expression<'src>) -> Option<&'src str> {
+    match expr {
+        Expression::Path(path) => match *path.segments().first() {
+            param @ Ident::QueryParam(_) => Some(param.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A table, subquery or array in a 'FROM' clause.
+#[derive(Debug)]
+struct Source<'a> {
+    alias: &'a str,
+    /// The table name, or [`None`] if the columns of the source aren't known.
+    table: Option<&'a str>,
+}
+
+#[derive(Debug)]
+struct Scope<'a> {
+    sources: Vec<Source<'a>>,
+    /// Aliases defined in the select list, which can be referenced by 'GROUP BY'/'ORDER BY'.
+    aliases: Vec<&'a str>,
+    ctes: &'a [String],
+    /// If set, no unqualified column references are checked.
+    opaque: bool,
+    parent: Option<&'a Scope<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn empty(parent: Option<&'a Scope<'a>>, ctes: &'a [String]) -> Self {
+        Self {
+            sources: Vec::new(),
+            aliases: Vec::new(),
+            ctes,
+            opaque: false,
+            parent,
+        }
+    }
+
+    fn opaque(parent: Option<&'a Scope<'a>>, ctes: &'a [String]) -> Self {
+        Self {
+            opaque: true,
+            ..Self::empty(parent, ctes)
+        }
+    }
+
+    fn iter_chain(&self) -> impl Iterator<Item = &Scope<'a>> {
+        std::iter::successors(Some(self), |scope| scope.parent)
+    }
+
+    fn is_cte(&self, name: &str) -> bool {
+        self.iter_chain()
+            .flat_map(|scope| scope.ctes.iter())
+            .any(|cte| cte.eq_ignore_ascii_case(name))
+    }
+
+    /// Resolves a path to the column it references, if we can tell.
+    fn resolve(&self, path: &Path<'_>) -> Option<ColumnRef> {
+        let segments = path.segments();
+        let first = segments.first().as_str();
+
+        // a qualified column, i.e 'table.column'
+        if let Some(column) = segments.get(1) {
+            let source = self
+                .iter_chain()
+                .flat_map(|scope| scope.sources.iter())
+                .find(|source| source.alias.eq_ignore_ascii_case(first));
+
+            if let Some(source) = source {
+                return source.table.map(|table| ColumnRef {
+                    tables: vec![table.to_owned()],
+                    column: column.as_str().to_owned(),
+                });
+            }
+        }
+
+        // unqualified references in a nested scope could be correlated with an outer table, so
+        // we'd need to know which tables have the column to resolve it.
+        let has_outer_sources = self
+            .parent
+            .is_some_and(|parent| parent.iter_chain().any(|scope| !scope.sources.is_empty()));
+
+        if self.opaque || has_outer_sources || self.sources.is_empty() {
+            return None;
+        }
+
+        if self
+            .aliases
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(first))
+        {
+            return None;
+        }
+
+        let tables = self
+            .sources
+            .iter()
+            .map(|source| source.table.map(str::to_owned))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(ColumnRef {
+            tables,
+            column: first.to_owned(),
+        })
+    }
+}
@@ -0,0 +1,356 @@
+//! The schema form of `database!`, which builds the tables and columns of a database from its DDL
+//! so `query!` can check queries against it:
+//!
+//! ```text
+//! database! {
+//!     // path to a file of DDL statements (relative to the crate root), or inline DDL with
+//!     // 'ddl: "CREATE TABLE ..."'
+//!     schema: "schema.sql",
+//!     // optional, defaults to 'spanner_rs'
+//!     crate_name: spanner_rs,
+//!
+//!     pub struct Music;
+//! }
+//! ```
+use std::path::PathBuf;
+
+use google_sql::ast::Statement;
+use google_sql::ast::common::types::Type;
+use google_sql::ast::ddl::DdlStatement;
+use google_sql::ast::ddl::table::{AlterTableAction, ColumnAlteration};
+use google_sql::ast::reserved::DataType;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Returns true if the input to `database!` is a schema, rather than the parts of a database
+/// name. Only the schema form declares a struct.
+pub fn is_schema_input(tokens: &TokenStream) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "struct",
+        _ => false,
+    })
+}
+
+enum DdlSource {
+    /// A path to a file of DDL, relative to the crate root.
+    File(syn::LitStr),
+    Inline(syn::LitStr),
+}
+
+pub struct SchemaInput {
+    crate_name: Option<syn::Ident>,
+    source: DdlSource,
+    item: syn::ItemStruct,
+}
+
+impl syn::parse::Parse for SchemaInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut crate_name = None;
+        let mut source = None;
+
+        while input.peek(syn::Ident::peek_any) && input.peek2(syn::Token![:]) {
+            let ident = input.call(syn::Ident::parse_any)?;
+            input.parse::<syn::Token![:]>()?;
+
+            if ident == "crate_name" {
+                crate_name = Some(input.call(syn::Ident::parse_any)?);
+            } else if ident == "schema" || ident == "ddl" {
+                let lit = input.parse::<syn::LitStr>()?;
+
+                if source.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "only one of 'schema' or 'ddl' can be set",
+                    ));
+                }
+
+                source = Some(if ident == "schema" {
+                    DdlSource::File(lit)
+                } else {
+                    DdlSource::Inline(lit)
+                });
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "unknown option, expected one of: 'schema', 'ddl' or 'crate_name'",
+                ));
+            }
+
+            if input.peek(syn::Token![,]) {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        let item = input.parse::<syn::ItemStruct>()?;
+
+        let source = source.ok_or_else(|| {
+            syn::Error::new(item.ident.span(), "missing a 'schema' or 'ddl' option")
+        })?;
+
+        Ok(Self {
+            crate_name,
+            source,
+            item,
+        })
+    }
+}
+
+/// A table, as of the last DDL statement that touched it.
+struct Table<'a> {
+    name: &'a str,
+    columns: Vec<Column<'a>>,
+}
+
+struct Column<'a> {
+    name: &'a str,
+    ty: Type<'a>,
+    nullable: bool,
+}
+
+pub fn derive(input: SchemaInput) -> syn::Result<TokenStream> {
+    let SchemaInput {
+        crate_name,
+        source,
+        item,
+    } = input;
+
+    let krate = match crate_name {
+        Some(crate_name) => quote!(#crate_name),
+        None => quote!(::spanner_rs),
+    };
+
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "database schema types can't be generic",
+        ));
+    }
+
+    let (lit, ddl, ddl_expr) = match source {
+        DdlSource::Inline(lit) => {
+            let ddl = lit.value();
+            let expr = quote!(#lit);
+            (lit, ddl, expr)
+        }
+        DdlSource::File(lit) => {
+            let path = resolve_path(&lit)?;
+            let ddl = std::fs::read_to_string(&path).map_err(|error| {
+                syn::Error::new(
+                    lit.span(),
+                    format!("couldn't read '{}': {error}", path.display()),
+                )
+            })?;
+
+            // including the file makes cargo rebuild when the schema changes
+            let path = path.to_string_lossy().into_owned();
+            let expr = quote!(::core::include_str!(#path));
+            (lit, ddl, expr)
+        }
+    };
+
+    let tables = build_tables(&ddl).map_err(|error| syn::Error::new(lit.span(), error))?;
+
+    let ident = &item.ident;
+
+    let mut table_consts = Vec::with_capacity(tables.len());
+    let mut table_lookups = Vec::with_capacity(tables.len());
+
+    for (index, table) in tables.iter().enumerate() {
+        let const_ident = format_ident!("__TABLE_{}", index);
+        let name = table.name;
+
+        let columns = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let column_name = column.name;
+                let nullable = column.nullable;
+                let ty = column_type(&krate, &column.ty).ok_or_else(|| {
+                    syn::Error::new(
+                        lit.span(),
+                        format!(
+                            "column '{name}.{column_name}' has type {}, which spanner-rs doesn't \
+                             support",
+                            column.ty,
+                        ),
+                    )
+                })?;
+
+                Ok(quote! {
+                    #krate::column::Column {
+                        index: #index,
+                        name: #column_name,
+                        ty: #ty,
+                        nullable: #nullable,
+                    }
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        table_consts.push(quote! {
+            #[doc(hidden)]
+            const #const_ident: &'static [#krate::column::Column<'static>] = &[#(#columns,)*];
+        });
+
+        table_lookups.push(quote! {
+            if #krate::__macro_internals::ident_eq(#name, table) {
+                return #krate::__macro_internals::find_column(Self::#const_ident, column);
+            }
+        });
+    }
+
+    let table_names = tables.iter().map(|table| table.name);
+
+    Ok(quote! {
+        #item
+
+        #[automatically_derived]
+        impl #ident {
+            /// The DDL statements the schema was built from.
+            pub const DDL: &'static str = #ddl_expr;
+
+            #(#table_consts)*
+
+            #[doc(hidden)]
+            pub const fn __has_table(table: &str) -> bool {
+                false #(|| #krate::__macro_internals::ident_eq(#table_names, table))*
+            }
+
+            #[doc(hidden)]
+            pub const fn __column(
+                table: &str,
+                column: &str,
+            ) -> ::core::option::Option<#krate::column::Column<'static>> {
+                #(#table_lookups)*
+                ::core::option::Option::None
+            }
+        }
+    })
+}
+
+fn resolve_path(lit: &syn::LitStr) -> syn::Result<PathBuf> {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").ok_or_else(|| {
+        syn::Error::new(
+            lit.span(),
+            "CARGO_MANIFEST_DIR isn't set, so the schema path can't be resolved",
+        )
+    })?;
+
+    Ok(PathBuf::from(root).join(lit.value()))
+}
+
+/// Applies each DDL statement in order, so the tables reflect the final schema.
+fn build_tables(ddl: &str) -> Result<Vec<Table<'_>>, String> {
+    let statements =
+        google_sql::ast::parse_statements(ddl).map_err(|error| format!("invalid DDL: {error}"))?;
+
+    let mut tables: Vec<Table<'_>> = Vec::new();
+
+    fn find<'t, 'a>(tables: &'t mut [Table<'a>], name: &str) -> Option<&'t mut Table<'a>> {
+        tables
+            .iter_mut()
+            .find(|table| table.name.eq_ignore_ascii_case(name))
+    }
+
+    for statement in statements {
+        let Statement::Ddl(ddl) = statement else {
+            return Err("the schema can only contain DDL statements".to_owned());
+        };
+
+        match ddl {
+            DdlStatement::CreateTable(create) => {
+                let name = create.name.name().as_str();
+                tables.retain(|table| !table.name.eq_ignore_ascii_case(name));
+
+                let columns = create
+                    .columns
+                    .into_iter()
+                    .map(|column| Column {
+                        name: column.name.as_str(),
+                        ty: column.ty,
+                        nullable: !column.not_null,
+                    })
+                    .collect();
+
+                tables.push(Table { name, columns });
+            }
+            DdlStatement::AlterTable(alter) => {
+                let Some(table) = find(&mut tables, alter.name.name().as_str()) else {
+                    continue;
+                };
+
+                match alter.action {
+                    AlterTableAction::AddColumn { column, .. } => table.columns.push(Column {
+                        name: column.name.as_str(),
+                        ty: column.ty,
+                        nullable: !column.not_null,
+                    }),
+                    AlterTableAction::DropColumn(dropped) => table
+                        .columns
+                        .retain(|column| !column.name.eq_ignore_ascii_case(dropped.as_str())),
+                    AlterTableAction::AlterColumn {
+                        column: altered,
+                        alteration: ColumnAlteration::Redefine { ty, not_null, .. },
+                    } => {
+                        if let Some(column) = table
+                            .columns
+                            .iter_mut()
+                            .find(|column| column.name.eq_ignore_ascii_case(altered.as_str()))
+                        {
+                            column.ty = ty;
+                            column.nullable = !not_null;
+                        }
+                    }
+                    AlterTableAction::RenameTo(name) => table.name = name.name().as_str(),
+                    _ => (),
+                }
+            }
+            DdlStatement::DropTable(drop) => {
+                let name = drop.name.name().as_str();
+                tables.retain(|table| !table.name.eq_ignore_ascii_case(name));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Builds a `const` expression for a column type, or returns [`None`] if spanner-rs doesn't
+/// support the type.
+fn column_type(krate: &TokenStream, ty: &Type<'_>) -> Option<TokenStream> {
+    match ty {
+        Type::Scalar { data_type, .. } => {
+            let scalar = scalar_const(*data_type)?;
+            Some(quote!(&#krate::Type::#scalar))
+        }
+        // columns can't be nested arrays, so only scalar elements need to be handled
+        Type::Array(element) => match **element {
+            Type::Scalar { data_type, .. } => {
+                let scalar = scalar_const(data_type)?;
+                Some(quote!(&#krate::Type::array(&#krate::Type::#scalar)))
+            }
+            _ => None,
+        },
+        Type::Struct(_) => None,
+    }
+}
+
+fn scalar_const(data_type: DataType) -> Option<syn::Ident> {
+    let name = match data_type {
+        DataType::Bool => "BOOL",
+        DataType::Bytes => "BYTES",
+        DataType::Date => "DATE",
+        DataType::Float64 => "FLOAT64",
+        DataType::Int64 => "INT64",
+        DataType::Json => "JSON",
+        DataType::Numeric => "NUMERIC",
+        DataType::String => "STRING",
+        DataType::Timestamp => "TIMESTAMP",
+        DataType::Float32 | DataType::Array | DataType::Struct => return None,
+    };
+
+    Some(format_ident!("{}", name))
+}
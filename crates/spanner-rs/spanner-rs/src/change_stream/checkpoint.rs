@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use timestamp::Timestamp;
+
+/// The progress of a single change stream partition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionCheckpoint {
+    pub token: String,
+    /// The partitions this one split or merged from. Empty for the initial partitions.
+    pub parent_tokens: Vec<String>,
+    pub start_timestamp: Timestamp,
+    /// All records with a commit timestamp before this have been handled.
+    pub watermark: Timestamp,
+    pub state: PartitionState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PartitionState {
+    /// Discovered, but waiting on parent partitions to finish.
+    Pending,
+    Running,
+    Finished,
+}
+
+/// Persists [`PartitionCheckpoint`]s, so a [`ChangeStreamReader`] can pick up where it left off.
+///
+/// Finished partitions need to be kept around as long as any of their children are still
+/// [`PartitionState::Pending`], otherwise merged children may be started before all of their
+/// parents have finished.
+///
+/// [`ChangeStreamReader`]: super::ChangeStreamReader
+pub trait CheckpointStore: Send + Sync + 'static {
+    /// Loads all known partitions.
+    fn load(&self) -> impl Future<Output = anyhow::Result<Vec<PartitionCheckpoint>>> + Send;
+
+    /// Inserts or updates a partition, keyed by [`PartitionCheckpoint::token`].
+    fn save(
+        &self,
+        checkpoint: &PartitionCheckpoint,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+/// A [`CheckpointStore`] that only keeps checkpoints in memory, so progress is lost when the
+/// process exits.
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, PartitionCheckpoint>>,
+}
+
+impl InMemoryCheckpointStore {
+    pub fn get(&self, token: &str) -> Option<PartitionCheckpoint> {
+        self.checkpoints.lock().unwrap().get(token).cloned()
+    }
+}
+
+impl CheckpointStore for InMemoryCheckpointStore {
+    async fn load(&self) -> anyhow::Result<Vec<PartitionCheckpoint>> {
+        Ok(self.checkpoints.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn save(&self, checkpoint: &PartitionCheckpoint) -> anyhow::Result<()> {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .insert(checkpoint.token.clone(), checkpoint.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+//! Reads change streams, via the `READ_<stream>` table valued functions.
+//!
+//! A change stream is split into partitions, each of which is read with a separate query. When
+//! a partition splits or merges, its query ends with a [`ChildPartitionsRecord`] describing the
+//! partitions that replace it. [`ChangeStreamReader`] follows these, reading every partition
+//! concurrently and starting merged partitions once all of their parents have finished, so
+//! changes to any given key are always handled in commit order. Each partition's query runs on
+//! its own session rather than one from the [`Client`]'s pool, since it stays open for as long as
+//! the partition does.
+//!
+//! Progress is checkpointed to a [`CheckpointStore`] as each partition advances. Records are
+//! delivered at least once: after a restart, partitions resume from their last watermark,
+//! which may replay records committed at that exact timestamp.
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+
+use timestamp::{Duration, Timestamp};
+use tokio::task::JoinSet;
+
+mod checkpoint;
+mod record;
+
+pub use checkpoint::{
+    CheckpointStore, InMemoryCheckpointStore, PartitionCheckpoint, PartitionState,
+};
+pub use record::{
+    ChangeRecord, ChangeRecords, ChildPartition, ChildPartitionsRecord, ColumnType,
+    DataChangeRecord, HeartbeatRecord, Mod, ModType, ValueCaptureType,
+};
+
+use crate::Client;
+use crate::client::DedicatedSession;
+use crate::sql::Params;
+
+const DEFAULT_HEARTBEAT: Duration = Duration::from_seconds(10);
+
+/// Reads all partitions of a change stream, passing each [`DataChangeRecord`] to a handler.
+pub struct ChangeStreamReader<S> {
+    client: Client,
+    stream: String,
+    start: Timestamp,
+    end: Option<Timestamp>,
+    heartbeat: Duration,
+    store: Arc<S>,
+}
+
+struct Shared<S> {
+    client: Client,
+    stream: String,
+    end: Option<Timestamp>,
+    heartbeat: Duration,
+    store: Arc<S>,
+}
+
+/// Returned by each partition task once its query finishes.
+struct FinishedPartition {
+    /// [`None`] for the initial query, which only returns the initial partitions.
+    token: Option<String>,
+    children: Vec<PartitionCheckpoint>,
+}
+
+impl<S: CheckpointStore> ChangeStreamReader<S> {
+    pub fn new(client: Client, stream: impl Into<String>, store: S) -> Self {
+        Self {
+            client,
+            stream: stream.into(),
+            start: Timestamp::now(),
+            end: None,
+            heartbeat: DEFAULT_HEARTBEAT,
+            store: Arc::new(store),
+        }
+    }
+
+    /// Where to start reading from. Defaults to now, and is only used if 'store' doesn't
+    /// have any existing partitions.
+    pub fn start(mut self, start: Timestamp) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Stops reading once all partitions reach 'end'. Without this, [`ChangeStreamReader::run`]
+    /// only returns on error.
+    pub fn end(mut self, end: Timestamp) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// How often each partition emits a [`HeartbeatRecord`] when there are no changes, which
+    /// is also the upper bound on how stale an idle partition's watermark can get. Defaults to
+    /// 10 seconds.
+    pub fn heartbeat(mut self, heartbeat: Duration) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Reads the change stream, calling 'handler' with every [`DataChangeRecord`]. Records
+    /// within a partition are handled sequentially, but separate partitions run concurrently.
+    ///
+    /// A partition's watermark is only advanced after 'handler' has returned for every record
+    /// before it, and any error from 'handler' stops the reader.
+    pub async fn run<F, Fut>(self, handler: F) -> crate::Result<()>
+    where
+        F: Fn(DataChangeRecord) -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        if self.stream.is_empty()
+            || !self
+                .stream
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            return Err(crate::Error::Misc(anyhow::anyhow!(
+                "invalid change stream name '{}'",
+                self.stream
+            )));
+        }
+
+        let shared = Arc::new(Shared {
+            client: self.client,
+            stream: self.stream,
+            end: self.end,
+            heartbeat: self.heartbeat,
+            store: self.store,
+        });
+
+        let mut partitions = shared
+            .store
+            .load()
+            .await?
+            .into_iter()
+            .map(|checkpoint| (checkpoint.token.clone(), checkpoint))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = JoinSet::new();
+
+        if partitions.is_empty() {
+            tasks.spawn(read_partition(
+                shared.clone(),
+                None,
+                self.start,
+                handler.clone(),
+            ));
+        } else {
+            for checkpoint in partitions.values() {
+                if checkpoint.state == PartitionState::Running {
+                    tasks.spawn(read_partition(
+                        shared.clone(),
+                        Some(checkpoint.clone()),
+                        checkpoint.watermark,
+                        handler.clone(),
+                    ));
+                }
+            }
+        }
+
+        start_ready_partitions(&shared, &mut partitions, &mut tasks, &handler).await?;
+
+        while let Some(result) = tasks.join_next().await {
+            let finished = result.map_err(|err| anyhow::anyhow!(err))??;
+
+            if let Some(token) = finished.token
+                && let Some(checkpoint) = partitions.get_mut(&token)
+            {
+                checkpoint.state = PartitionState::Finished;
+            }
+
+            for child in finished.children {
+                partitions.entry(child.token.clone()).or_insert(child);
+            }
+
+            start_ready_partitions(&shared, &mut partitions, &mut tasks, &handler).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the pending partitions that can be started, i.e all of their parents have finished.
+/// Parents we don't know about are assumed to have finished.
+fn ready_partitions(partitions: &HashMap<String, PartitionCheckpoint>) -> Vec<String> {
+    partitions
+        .values()
+        .filter(|checkpoint| checkpoint.state == PartitionState::Pending)
+        .filter(|checkpoint| {
+            checkpoint.parent_tokens.iter().all(|parent| {
+                partitions
+                    .get(parent)
+                    .is_none_or(|parent| parent.state == PartitionState::Finished)
+            })
+        })
+        .map(|checkpoint| checkpoint.token.clone())
+        .collect()
+}
+
+async fn start_ready_partitions<S, F, Fut>(
+    shared: &Arc<Shared<S>>,
+    partitions: &mut HashMap<String, PartitionCheckpoint>,
+    tasks: &mut JoinSet<crate::Result<FinishedPartition>>,
+    handler: &F,
+) -> crate::Result<()>
+where
+    S: CheckpointStore,
+    F: Fn(DataChangeRecord) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    for token in ready_partitions(partitions) {
+        let checkpoint = partitions.get_mut(&token).expect("token came from the map");
+        checkpoint.state = PartitionState::Running;
+        shared.store.save(checkpoint).await?;
+
+        tasks.spawn(read_partition(
+            shared.clone(),
+            Some(checkpoint.clone()),
+            checkpoint.watermark,
+            handler.clone(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn read_partition<S, F, Fut>(
+    shared: Arc<Shared<S>>,
+    mut checkpoint: Option<PartitionCheckpoint>,
+    start: Timestamp,
+    handler: F,
+) -> crate::Result<FinishedPartition>
+where
+    S: CheckpointStore,
+    F: Fn(DataChangeRecord) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    let mut params = Params::with_capacity(4);
+    params.insert("start_timestamp", start);
+    params.insert("heartbeat_milliseconds", shared.heartbeat.millis());
+
+    let end = match shared.end {
+        Some(end) => {
+            params.insert("end_timestamp", end);
+            "@end_timestamp"
+        }
+        None => "NULL",
+    };
+
+    let token = match checkpoint {
+        Some(ref checkpoint) => {
+            params.insert("partition_token", checkpoint.token.clone());
+            "@partition_token"
+        }
+        None => "NULL",
+    };
+
+    let sql = format!(
+        "SELECT ChangeRecord FROM READ_{}(start_timestamp => @start_timestamp, end_timestamp => \
+         {end}, partition_token => {token}, heartbeat_milliseconds => @heartbeat_milliseconds)",
+        shared.stream
+    );
+
+    // these queries can stay open for a long time, so they get their own session rather than
+    // holding onto one from the pool (which would run dry with enough partitions).
+    let session = shared.client.create_dedicated_session().await?;
+    let result = read_records(&shared, &session, sql, params, &mut checkpoint, &handler).await;
+    session.delete().await;
+    let children = result?;
+
+    // children need to be saved before this partition is marked as finished, otherwise a
+    // restart in between would lose them.
+    for child in children.iter() {
+        shared.store.save(child).await?;
+    }
+
+    if let Some(ref mut checkpoint) = checkpoint {
+        checkpoint.state = PartitionState::Finished;
+        shared.store.save(checkpoint).await?;
+    }
+
+    Ok(FinishedPartition {
+        token: checkpoint.map(|checkpoint| checkpoint.token),
+        children,
+    })
+}
+
+/// Reads a partition's query to completion, returning the child partitions it ended with.
+async fn read_records<S, F, Fut>(
+    shared: &Shared<S>,
+    session: &DedicatedSession,
+    sql: String,
+    params: Params,
+    checkpoint: &mut Option<PartitionCheckpoint>,
+    handler: &F,
+) -> crate::Result<Vec<PartitionCheckpoint>>
+where
+    S: CheckpointStore,
+    F: Fn(DataChangeRecord) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    let mut stream = pin!(
+        session
+            .execute_streaming_sql::<ChangeRecords>(sql, Some(params))
+            .await?
+    );
+
+    let mut children = Vec::new();
+
+    while let Some(chunk) = stream.next_chunk().await? {
+        let mut watermark = None;
+
+        for row in chunk {
+            for record in row?.0 {
+                match record {
+                    ChangeRecord::DataChange(record) => {
+                        let commit_timestamp = record.commit_timestamp;
+                        handler(record).await?;
+                        watermark = Some(commit_timestamp);
+                    }
+                    ChangeRecord::Heartbeat(heartbeat) => watermark = Some(heartbeat.timestamp),
+                    ChangeRecord::ChildPartitions(record) => {
+                        children.extend(record.child_partitions.into_iter().map(|child| {
+                            PartitionCheckpoint {
+                                token: child.token,
+                                parent_tokens: child.parent_partition_tokens,
+                                start_timestamp: record.start_timestamp,
+                                watermark: record.start_timestamp,
+                                state: PartitionState::Pending,
+                            }
+                        }));
+                        watermark = Some(record.start_timestamp);
+                    }
+                }
+            }
+        }
+
+        if let Some(checkpoint) = checkpoint
+            && let Some(watermark) = watermark
+            && watermark > checkpoint.watermark
+        {
+            checkpoint.watermark = watermark;
+            shared.store.save(checkpoint).await?;
+        }
+    }
+
+    Ok(children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(token: &str, parents: &[&str], state: PartitionState) -> PartitionCheckpoint {
+        PartitionCheckpoint {
+            token: token.to_owned(),
+            parent_tokens: parents.iter().map(|parent| (*parent).to_owned()).collect(),
+            start_timestamp: Timestamp::UNIX_EPOCH,
+            watermark: Timestamp::UNIX_EPOCH,
+            state,
+        }
+    }
+
+    #[test]
+    fn test_ready_partitions() {
+        let mut partitions = [
+            checkpoint("a", &[], PartitionState::Finished),
+            checkpoint("b", &[], PartitionState::Running),
+            checkpoint("split", &["a"], PartitionState::Pending),
+            checkpoint("merged", &["a", "b"], PartitionState::Pending),
+            checkpoint("orphan", &["unknown"], PartitionState::Pending),
+        ]
+        .into_iter()
+        .map(|checkpoint| (checkpoint.token.clone(), checkpoint))
+        .collect::<HashMap<_, _>>();
+
+        let mut ready = ready_partitions(&partitions);
+        ready.sort();
+        assert_eq!(ready, ["orphan", "split"]);
+
+        partitions.get_mut("b").unwrap().state = PartitionState::Finished;
+        partitions.get_mut("split").unwrap().state = PartitionState::Running;
+        partitions.get_mut("orphan").unwrap().state = PartitionState::Running;
+
+        assert_eq!(ready_partitions(&partitions), ["merged"]);
+    }
+}
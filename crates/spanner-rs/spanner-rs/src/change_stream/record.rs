@@ -0,0 +1,494 @@
+//! Typed change stream records, decoded from the `ChangeRecord` column returned by the
+//! `READ_<stream>` table valued functions.
+//!
+//! Fields are looked up by name in the result set metadata rather than by position, since
+//! Spanner only guarantees the names (and new fields have been added over time).
+use generic_array::GenericArray;
+use protos::protobuf;
+use protos::protobuf::value::Kind;
+use serde::de::{Error as _, Unexpected};
+use serde::{Deserialize, Serialize};
+use timestamp::Timestamp;
+
+use crate::column::{Column, Unnamed};
+use crate::error::ConvertError;
+use crate::queryable::{Queryable, Row};
+use crate::results::RawRow;
+use crate::ty::markers::{self, SpannerStruct};
+use crate::ty::{SpannerType, ty};
+use crate::{Field, FromSpanner, Type, Value};
+
+/// A single record from a change stream partition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChangeRecord {
+    DataChange(DataChangeRecord),
+    Heartbeat(HeartbeatRecord),
+    ChildPartitions(ChildPartitionsRecord),
+}
+
+/// A set of modifications to a single table, made in a single transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataChangeRecord {
+    pub commit_timestamp: Timestamp,
+    /// Orders records within a transaction, only unique within a partition.
+    pub record_sequence: String,
+    pub server_transaction_id: String,
+    pub is_last_record_in_transaction_in_partition: bool,
+    pub table_name: String,
+    pub column_types: Vec<ColumnType>,
+    pub mods: Vec<Mod>,
+    pub mod_type: ModType,
+    pub value_capture_type: ValueCaptureType,
+    pub number_of_records_in_transaction: i64,
+    pub number_of_partitions_in_transaction: i64,
+    pub transaction_tag: String,
+    pub is_system_transaction: bool,
+}
+
+/// Describes a column referenced by the [`Mod`]s in a [`DataChangeRecord`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnType {
+    pub name: String,
+    /// The column type, as JSON (i.e `{"code":"INT64"}`).
+    pub ty: String,
+    pub is_primary_key: bool,
+    pub ordinal_position: i64,
+}
+
+/// A single modified row. Each field is a JSON object, keyed by column name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mod {
+    pub keys: String,
+    pub new_values: String,
+    pub old_values: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModType {
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValueCaptureType {
+    OldAndNewValues,
+    NewValues,
+    NewRow,
+    NewRowAndOldValues,
+}
+
+/// Emitted when there haven't been any changes in a partition for the requested heartbeat
+/// interval. No [`DataChangeRecord`]s will follow with an earlier commit timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeartbeatRecord {
+    pub timestamp: Timestamp,
+}
+
+/// Emitted when a partition splits or merges. The partition ends after these records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildPartitionsRecord {
+    /// Child partitions should be queried starting from this timestamp.
+    pub start_timestamp: Timestamp,
+    pub record_sequence: String,
+    pub child_partitions: Vec<ChildPartition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChildPartition {
+    pub token: String,
+    /// More than one parent means this partition is the result of a merge.
+    pub parent_partition_tokens: Vec<String>,
+}
+
+#[cfg(feature = "serde_json")]
+impl Mod {
+    pub fn parse_keys<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.keys)
+    }
+
+    pub fn parse_new_values<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.new_values)
+    }
+
+    pub fn parse_old_values<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.old_values)
+    }
+}
+
+impl ModType {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "INSERT" => Some(Self::Insert),
+            "UPDATE" => Some(Self::Update),
+            "DELETE" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+impl ValueCaptureType {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "OLD_AND_NEW_VALUES" => Some(Self::OldAndNewValues),
+            "NEW_VALUES" => Some(Self::NewValues),
+            "NEW_ROW" => Some(Self::NewRow),
+            "NEW_ROW_AND_OLD_VALUES" => Some(Self::NewRowAndOldValues),
+            _ => None,
+        }
+    }
+}
+
+/// All records in a single row from a change stream query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeRecords(pub Vec<ChangeRecord>);
+
+impl Row for ChangeRecords {
+    type NumColumns = typenum::U1;
+    type ColumnName = Unnamed;
+
+    const COLUMNS: GenericArray<Column<'static, Unnamed>, typenum::U1> =
+        GenericArray::from_array([Column::unnamed::<ChangeRecordColumn>(0)]);
+}
+
+impl Queryable for ChangeRecords {
+    fn from_row(mut row: RawRow<'_, Self::NumColumns>) -> crate::Result<Self> {
+        row.decode_at_index(0, |field, value| {
+            decode_struct_array(&field.ty, value, |record| {
+                let mut records = Vec::with_capacity(1);
+
+                records.extend(
+                    record
+                        .decode_structs("data_change_record", DataChangeRecord::decode)?
+                        .into_iter()
+                        .map(ChangeRecord::DataChange),
+                );
+                records.extend(
+                    record
+                        .decode_structs("heartbeat_record", HeartbeatRecord::decode)?
+                        .into_iter()
+                        .map(ChangeRecord::Heartbeat),
+                );
+                records.extend(
+                    record
+                        .decode_structs("child_partitions_record", ChildPartitionsRecord::decode)?
+                        .into_iter()
+                        .map(ChangeRecord::ChildPartitions),
+                );
+
+                Ok(records)
+            })
+            .map(|nested| ChangeRecords(nested.into_iter().flatten().collect()))
+            .map_err(|err| err.column(field.name.clone()))
+        })
+    }
+}
+
+impl DataChangeRecord {
+    fn decode(fields: &mut StructDecoder<'_>) -> Result<Self, ConvertError> {
+        let mod_type: String = fields.decode("mod_type")?;
+        let value_capture_type: String = fields.decode("value_capture_type")?;
+
+        Ok(Self {
+            commit_timestamp: fields.decode("commit_timestamp")?,
+            record_sequence: fields.decode("record_sequence")?,
+            server_transaction_id: fields.decode("server_transaction_id")?,
+            is_last_record_in_transaction_in_partition: fields
+                .decode("is_last_record_in_transaction_in_partition")?,
+            table_name: fields.decode("table_name")?,
+            column_types: fields.decode_structs("column_types", ColumnType::decode)?,
+            mods: fields.decode_structs("mods", Mod::decode)?,
+            mod_type: ModType::from_str(&mod_type).ok_or_else(|| {
+                ConvertError::invalid_value(Unexpected::Str(&mod_type), &"a valid mod_type")
+            })?,
+            value_capture_type: ValueCaptureType::from_str(&value_capture_type).ok_or_else(
+                || {
+                    ConvertError::invalid_value(
+                        Unexpected::Str(&value_capture_type),
+                        &"a valid value_capture_type",
+                    )
+                },
+            )?,
+            number_of_records_in_transaction: fields.decode("number_of_records_in_transaction")?,
+            number_of_partitions_in_transaction: fields
+                .decode("number_of_partitions_in_transaction")?,
+            transaction_tag: fields.decode("transaction_tag")?,
+            is_system_transaction: fields.decode("is_system_transaction")?,
+        })
+    }
+}
+
+impl ColumnType {
+    fn decode(fields: &mut StructDecoder<'_>) -> Result<Self, ConvertError> {
+        Ok(Self {
+            name: fields.decode("name")?,
+            ty: fields.decode("type")?,
+            is_primary_key: fields.decode("is_primary_key")?,
+            ordinal_position: fields.decode("ordinal_position")?,
+        })
+    }
+}
+
+impl Mod {
+    fn decode(fields: &mut StructDecoder<'_>) -> Result<Self, ConvertError> {
+        Ok(Self {
+            keys: fields.decode("keys")?,
+            new_values: fields.decode("new_values")?,
+            old_values: fields.decode("old_values")?,
+        })
+    }
+}
+
+impl HeartbeatRecord {
+    fn decode(fields: &mut StructDecoder<'_>) -> Result<Self, ConvertError> {
+        Ok(Self {
+            timestamp: fields.decode("timestamp")?,
+        })
+    }
+}
+
+impl ChildPartitionsRecord {
+    fn decode(fields: &mut StructDecoder<'_>) -> Result<Self, ConvertError> {
+        Ok(Self {
+            start_timestamp: fields.decode("start_timestamp")?,
+            record_sequence: fields.decode("record_sequence")?,
+            child_partitions: fields.decode_structs("child_partitions", ChildPartition::decode)?,
+        })
+    }
+}
+
+impl ChildPartition {
+    fn decode(fields: &mut StructDecoder<'_>) -> Result<Self, ConvertError> {
+        Ok(Self {
+            token: fields.decode("token")?,
+            parent_partition_tokens: fields.decode("parent_partition_tokens")?,
+        })
+    }
+}
+
+/// The values of an encoded STRUCT (which Spanner sends as a list), paired with the field
+/// definitions from the result set metadata.
+pub(super) struct StructDecoder<'a> {
+    fields: &'a [Field],
+    values: Vec<protobuf::Value>,
+}
+
+impl StructDecoder<'_> {
+    fn take(&mut self, name: &'static str) -> Result<(&Field, Value), ConvertError> {
+        let index = self
+            .fields
+            .iter()
+            .position(|field| field.name == name)
+            .filter(|index| *index < self.values.len())
+            .ok_or_else(|| ConvertError::missing_field(name))?;
+
+        let value = Value::from_proto(std::mem::take(&mut self.values[index]));
+        Ok((&self.fields[index], value))
+    }
+
+    fn decode<T: FromSpanner>(&mut self, name: &'static str) -> Result<T, ConvertError> {
+        let (field, value) = self.take(name)?;
+        T::from_field_and_value(field, value)
+    }
+
+    fn decode_structs<T>(
+        &mut self,
+        name: &'static str,
+        decode: impl FnMut(&mut StructDecoder<'_>) -> Result<T, ConvertError>,
+    ) -> Result<Vec<T>, ConvertError> {
+        let (field, value) = self.take(name)?;
+        decode_struct_array(&field.ty, value, decode).map_err(|err| err.column(name))
+    }
+}
+
+/// Decodes an `ARRAY<STRUCT<..>>`, treating NULL as empty.
+fn decode_struct_array<T>(
+    ty: &Type,
+    value: Value,
+    mut decode: impl FnMut(&mut StructDecoder<'_>) -> Result<T, ConvertError>,
+) -> Result<Vec<T>, ConvertError> {
+    let fields = ty
+        .get_array_elem()
+        .and_then(|elem| elem.get_struct_fields().map(|fields| fields.into_owned()))
+        .ok_or_else(|| {
+            ConvertError::invalid_type(Unexpected::Other(&format!("{ty:?}")), &"ARRAY<STRUCT>")
+        })?;
+
+    let elements = match value.0 {
+        Kind::NullValue(_) => return Ok(Vec::new()),
+        Kind::ListValue(list) => list.values,
+        _ => {
+            return Err(ConvertError::invalid_type(
+                Unexpected::Other("non-array"),
+                &"an array",
+            ));
+        }
+    };
+
+    let mut dst = Vec::with_capacity(elements.len());
+
+    for element in elements {
+        let values = match element.kind {
+            Some(Kind::ListValue(list)) => list.values,
+            _ => {
+                return Err(ConvertError::invalid_type(
+                    Unexpected::Other("non-struct"),
+                    &"a struct",
+                ));
+            }
+        };
+
+        dst.push(decode(&mut StructDecoder {
+            fields: &fields,
+            values,
+        })?);
+    }
+
+    Ok(dst)
+}
+
+// ------------- Spanner type definitions ------------- //
+
+/// Marker for the type of the `ChangeRecord` column.
+pub(super) enum ChangeRecordColumn {}
+
+impl SpannerType for ChangeRecordColumn {
+    type Type = markers::Array<markers::Struct<ChangeRecordFields>>;
+    type Nullable = typenum::False;
+}
+
+macro_rules! struct_fields {
+    ($($marker:ident { $($name:literal: $ty:expr),* $(,)? })*) => {
+        $(
+            pub(super) enum $marker {}
+
+            impl SpannerStruct for $marker {
+                const FIELDS: &'static [Field] = &[$(Field::new($ty, $name)),*];
+            }
+
+            impl SpannerType for $marker {
+                type Type = markers::Struct<$marker>;
+                type Nullable = typenum::False;
+            }
+        )*
+    };
+}
+
+const fn struct_array<T: SpannerType>() -> Type {
+    Type::array(ty::<T>())
+}
+
+struct_fields! {
+    ChangeRecordFields {
+        "data_change_record": struct_array::<DataChangeRecordFields>(),
+        "heartbeat_record": struct_array::<HeartbeatRecordFields>(),
+        "child_partitions_record": struct_array::<ChildPartitionsRecordFields>(),
+    }
+    DataChangeRecordFields {
+        "commit_timestamp": Type::TIMESTAMP,
+        "record_sequence": Type::STRING,
+        "server_transaction_id": Type::STRING,
+        "is_last_record_in_transaction_in_partition": Type::BOOL,
+        "table_name": Type::STRING,
+        "column_types": struct_array::<ColumnTypeFields>(),
+        "mods": struct_array::<ModFields>(),
+        "mod_type": Type::STRING,
+        "value_capture_type": Type::STRING,
+        "number_of_records_in_transaction": Type::INT64,
+        "number_of_partitions_in_transaction": Type::INT64,
+        "transaction_tag": Type::STRING,
+        "is_system_transaction": Type::BOOL,
+    }
+    ColumnTypeFields {
+        "name": Type::STRING,
+        "type": Type::JSON,
+        "is_primary_key": Type::BOOL,
+        "ordinal_position": Type::INT64,
+    }
+    ModFields {
+        "keys": Type::JSON,
+        "new_values": Type::JSON,
+        "old_values": Type::JSON,
+    }
+    HeartbeatRecordFields {
+        "timestamp": Type::TIMESTAMP,
+    }
+    ChildPartitionsRecordFields {
+        "start_timestamp": Type::TIMESTAMP,
+        "record_sequence": Type::STRING,
+        "child_partitions": struct_array::<ChildPartitionFields>(),
+    }
+    ChildPartitionFields {
+        "token": Type::STRING,
+        "parent_partition_tokens": Type::array(&Type::STRING),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protos::spanner::StructType;
+    use protos::spanner::struct_type::Field as ProtoField;
+
+    use super::*;
+    use crate::results::FieldIndex;
+
+    fn string(s: &str) -> protobuf::Value {
+        protobuf::Value {
+            kind: Some(Kind::StringValue(s.to_owned())),
+        }
+    }
+
+    fn list(values: Vec<protobuf::Value>) -> protobuf::Value {
+        protobuf::Value {
+            kind: Some(Kind::ListValue(protobuf::ListValue { values })),
+        }
+    }
+
+    #[test]
+    fn test_decode_change_records() -> crate::Result<()> {
+        let row_type = StructType {
+            fields: vec![ProtoField {
+                name: "ChangeRecord".to_owned(),
+                r#type: Some(ty::<ChangeRecordColumn>().into_proto()),
+            }],
+        };
+        let index = FieldIndex::from_struct_type::<ChangeRecords>(row_type)?;
+
+        let heartbeat = list(vec![
+            list(vec![]),
+            list(vec![list(vec![string("2024-01-01T00:00:00Z")])]),
+            list(vec![]),
+        ]);
+
+        let child_partitions = list(vec![
+            list(vec![]),
+            list(vec![]),
+            list(vec![list(vec![
+                string("2024-01-01T00:00:01Z"),
+                string("00000001"),
+                list(vec![list(vec![
+                    string("child"),
+                    list(vec![string("parent-a"), string("parent-b")]),
+                ])]),
+            ])]),
+        ]);
+
+        let row = vec![list(vec![heartbeat, child_partitions])];
+        let records = ChangeRecords::from_row(RawRow::new(&index, row))?.0;
+
+        assert_eq!(records.len(), 2);
+        assert!(matches!(records[0], ChangeRecord::Heartbeat(_)));
+
+        let ChangeRecord::ChildPartitions(ref child) = records[1] else {
+            panic!("expected a child partitions record, found {:?}", records[1]);
+        };
+        assert_eq!(child.record_sequence, "00000001");
+        assert_eq!(child.child_partitions[0].token, "child");
+        assert_eq!(
+            child.child_partitions[0].parent_partition_tokens,
+            ["parent-a", "parent-b"]
+        );
+
+        Ok(())
+    }
+}
@@ -12,20 +12,25 @@ use tonic::transport::{Channel, ClientTlsConfig};
 use crate::info::Database;
 use crate::key_set::WriteBuilder;
 use crate::queryable::Queryable;
+use crate::request_options::RequestOptions;
+use crate::results::plan::{QueryPlan, QueryProfile};
 use crate::tx::{ShouldCommit, Transaction};
 use crate::{ResultIter, StreamingRead};
 
+mod dedicated;
 mod pool;
 
 #[cfg(feature = "admin")]
 pub mod admin;
 #[cfg(feature = "emulator")]
 pub mod emulator;
+pub mod partition;
 
 mod session;
 
 pub(crate) mod connection;
 
+pub(crate) use dedicated::DedicatedSession;
 pub use pool::{PoolError, Session};
 use pool::{SESSION_POOL, SessionPool};
 pub use session::SessionClient;
@@ -36,6 +41,7 @@ const ENDPOINT: &str = "https://spanner.googleapis.com";
 #[derive(Clone)]
 pub struct Client {
     parts: Arc<ClientParts>,
+    options: Arc<RequestOptions>,
 }
 
 impl fmt::Debug for Client {
@@ -44,6 +50,7 @@ impl fmt::Debug for Client {
             .field("info", &self.parts.info)
             .field("channel", &self.parts.channel)
             .field("role", &self.parts.role)
+            .field("options", &self.options)
             .finish()
     }
 }
@@ -92,6 +99,7 @@ impl Client {
                 channel,
                 role,
             }),
+            options: Arc::default(),
         }
     }
 
@@ -257,6 +265,7 @@ impl Client {
         Ok(SessionClient {
             parts: self.parts.clone(),
             session,
+            options: RequestOptions::clone(&self.options),
         })
     }
 
@@ -264,6 +273,20 @@ impl Client {
         self.parts.role.as_deref()
     }
 
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.options
+    }
+
+    /// Returns a client that sends 'options' with every request, on top of any options already
+    /// set on this client. Both clients share the same connection and session pool, so this is
+    /// cheap enough to call per request.
+    pub fn with_request_options(&self, options: &RequestOptions) -> Self {
+        Self {
+            parts: self.parts.clone(),
+            options: Arc::new(self.options.merged(options)),
+        }
+    }
+
     // crate::connection::impl_deferred_read_functions!();
 
     pub async fn insert_or_update<T: crate::Table>(
@@ -287,16 +310,47 @@ impl Client {
             .await
     }
 
-    pub(crate) async fn execute_dml(
+    /// Shortcut for [`SessionClient::execute_batch_dml`].
+    pub async fn execute_batch_dml(&self, batch: crate::dml::BatchDml) -> crate::Result<Vec<i64>> {
+        self.borrow_session(None)
+            .await?
+            .execute_batch_dml(batch)
+            .await
+    }
+
+    /// Shortcut for [`SessionClient::execute_partitioned_dml`].
+    pub async fn execute_partitioned_dml(
         &self,
-        statements: Vec<protos::spanner::execute_batch_dml_request::Statement>,
-    ) -> crate::Result<protos::spanner::ExecuteBatchDmlResponse> {
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<i64> {
         self.borrow_session(None)
             .await?
-            .execute_dml(statements)
+            .execute_partitioned_dml(sql, params)
             .await
     }
 
+    /// Shortcut for [`SessionClient::execute_sql_with_plan`].
+    pub async fn execute_sql_with_plan<T: Queryable>(
+        &self,
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<QueryProfile<T>> {
+        self.borrow_session(None)
+            .await?
+            .execute_sql_with_plan(sql, params)
+            .await
+    }
+
+    /// Shortcut for [`SessionClient::explain`].
+    pub async fn explain(
+        &self,
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<QueryPlan> {
+        self.borrow_session(None).await?.explain(sql, params).await
+    }
+
     pub async fn execute_streaming_sql<T: Queryable>(
         &mut self,
         sql: String,
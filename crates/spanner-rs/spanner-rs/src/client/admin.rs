@@ -1,18 +1,27 @@
 //! [`SpannerAdmin`], a wrapper around both 'google.spanner.admin.instance' and
 //! 'google.spanner.admin.database' APIs.
+//!
+//! Long running operations (creating instances, databases and backups, copying backups and
+//! restoring databases) return an [`OperationHandle`], with the metadata decoded up front.
+//! [`poll_progress`] can be used to report how far along backups/restores are.
 
 use std::collections::HashMap;
+use std::future::Future;
 
 use bytes::Bytes;
 use gcp_auth_channel::{AuthChannel, Scope};
 use longrunning::OperationHandle;
+use protos::iam;
 use protos::spanner::admin::database::database_admin_client::DatabaseAdminClient;
 use protos::spanner::admin::database::{
-    self, CreateDatabaseMetadata, CreateDatabaseRequest, DatabaseDialect, GetDatabaseDdlRequest,
+    self, Backup, CopyBackupMetadata, CopyBackupRequest, CreateBackupMetadata, CreateBackupRequest,
+    CreateDatabaseMetadata, CreateDatabaseRequest, DatabaseDialect, DropDatabaseRequest,
+    GetDatabaseDdlRequest, ListBackupsRequest, ListDatabasesRequest, OperationProgress,
+    RestoreDatabaseMetadata, RestoreDatabaseRequest, restore_database_request,
 };
 use protos::spanner::admin::instance::instance_admin_client::InstanceAdminClient;
 use protos::spanner::admin::instance::{self, CreateInstanceMetadata, CreateInstanceRequest};
-use timestamp::Duration;
+use timestamp::{Duration, Timestamp};
 
 use crate::client::Client;
 use crate::info::{Database, Instance};
@@ -43,6 +52,54 @@ impl InstanceCompute {
     }
 }
 
+/// Metadata of long running operations that report their progress.
+pub trait OperationMetadata: prost::Message + Default {
+    fn progress(&self) -> Option<&OperationProgress>;
+}
+
+macro_rules! impl_operation_metadata {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl OperationMetadata for $t {
+                fn progress(&self) -> Option<&OperationProgress> {
+                    self.progress.as_ref()
+                }
+            }
+        )*
+    };
+}
+
+impl_operation_metadata!(
+    CreateBackupMetadata,
+    CopyBackupMetadata,
+    RestoreDatabaseMetadata
+);
+
+/// Polls 'handle' once, returning the progress of the operation as a percentage (0-100).
+pub async fn poll_progress<M, R>(handle: &mut OperationHandle<M, R>) -> crate::Result<Option<i32>>
+where
+    M: OperationMetadata,
+{
+    handle.poll_operation().await?;
+    Ok(progress_percent(handle.parse_metadata()?))
+}
+
+fn progress_percent<M: OperationMetadata>(metadata: Option<&M>) -> Option<i32> {
+    metadata
+        .and_then(OperationMetadata::progress)
+        .map(|progress| progress.progress_percent)
+}
+
+/// Decodes the initial metadata of a newly started operation, so it's available via
+/// [`OperationHandle::metadata`] without needing to poll first.
+fn with_metadata<M, R>(mut handle: OperationHandle<M, R>) -> crate::Result<OperationHandle<M, R>>
+where
+    M: prost::Message + Default,
+{
+    handle.parse_metadata()?;
+    Ok(handle)
+}
+
 impl SpannerAdmin {
     pub(crate) fn from_channel(channel: AuthChannel) -> Self {
         Self {
@@ -206,4 +263,391 @@ impl SpannerAdmin {
             update_operation,
         ))
     }
+
+    pub async fn list_databases<I: AsRef<str>>(
+        &self,
+        instance: &Instance<I>,
+    ) -> crate::Result<Vec<database::Database>> {
+        let client = self.db_client();
+        let parent = instance.build_qualified();
+
+        collect_pages(|page_token| {
+            let mut client = client.clone();
+            let req = list_databases_request(parent.clone(), page_token);
+
+            async move {
+                let resp = client.list_databases(req).await?.into_inner();
+                Ok((resp.databases, resp.next_page_token))
+            }
+        })
+        .await
+    }
+
+    /// Drops a database, along with all of its data. Backups of the database are kept.
+    pub async fn drop_database<S: AsRef<str>>(&self, db: &Database<S>) -> crate::Result<()> {
+        let req = DropDatabaseRequest {
+            database: db.qualified_database().to_owned(),
+        };
+
+        self.db_client().drop_database(req).await?;
+
+        Ok(())
+    }
+
+    /// Starts backing up a database. The backup is named
+    /// 'projects/<project>/instances/<instance>/backups/<backup_id>', in the same instance as
+    /// the database.
+    ///
+    /// If 'version_time' is [`None`], the backup is of the database as of when the backup
+    /// starts. Otherwise, it needs to be within the database version retention period.
+    pub async fn create_backup<S: AsRef<str>>(
+        &self,
+        db: &Database<S>,
+        backup_id: String,
+        expire_time: Timestamp,
+        version_time: Option<Timestamp>,
+    ) -> crate::Result<OperationHandle<CreateBackupMetadata, Backup>> {
+        let req = create_backup_request(db, backup_id, expire_time, version_time);
+
+        let mut channel = self.channel.clone();
+        let operation = DatabaseAdminClient::new(&mut channel)
+            .create_backup(req)
+            .await?
+            .into_inner();
+
+        with_metadata(OperationHandle::from_channel(channel, operation))
+    }
+
+    /// Copies an existing backup (by its qualified name) to 'instance', which can be in a
+    /// different region or project.
+    pub async fn copy_backup<I: AsRef<str>>(
+        &self,
+        source_backup: String,
+        instance: &Instance<I>,
+        backup_id: String,
+        expire_time: Timestamp,
+    ) -> crate::Result<OperationHandle<CopyBackupMetadata, Backup>> {
+        let req = copy_backup_request(source_backup, instance, backup_id, expire_time);
+
+        let mut channel = self.channel.clone();
+        let operation = DatabaseAdminClient::new(&mut channel)
+            .copy_backup(req)
+            .await?
+            .into_inner();
+
+        with_metadata(OperationHandle::from_channel(channel, operation))
+    }
+
+    /// Restores a backup (by its qualified name) into a new database. 'db' must not exist yet,
+    /// and needs to be in the same project as the backup.
+    pub async fn restore_database<S: AsRef<str>>(
+        &self,
+        backup: String,
+        db: &Database<S>,
+    ) -> crate::Result<OperationHandle<RestoreDatabaseMetadata, database::Database>> {
+        let req = restore_request(backup, db);
+
+        let mut channel = self.channel.clone();
+        let operation = DatabaseAdminClient::new(&mut channel)
+            .restore_database(req)
+            .await?
+            .into_inner();
+
+        with_metadata(OperationHandle::from_channel(channel, operation))
+    }
+
+    /// Lists the backups in an instance. See the [filter docs] for the syntax of 'filter'.
+    ///
+    /// [filter docs]: https://cloud.google.com/spanner/docs/reference/rpc/google.spanner.admin.database.v1#listbackupsrequest
+    pub async fn list_backups<I: AsRef<str>>(
+        &self,
+        instance: &Instance<I>,
+        filter: Option<String>,
+    ) -> crate::Result<Vec<Backup>> {
+        let client = self.db_client();
+        let parent = instance.build_qualified();
+        let filter = filter.unwrap_or_default();
+
+        collect_pages(|page_token| {
+            let mut client = client.clone();
+            let req = list_backups_request(parent.clone(), filter.clone(), page_token);
+
+            async move {
+                let resp = client.list_backups(req).await?.into_inner();
+                Ok((resp.backups, resp.next_page_token))
+            }
+        })
+        .await
+    }
+
+    pub async fn get_database_iam_policy<S: AsRef<str>>(
+        &self,
+        db: &Database<S>,
+    ) -> crate::Result<iam::Policy> {
+        let req = get_iam_policy_request(db);
+
+        let policy = self.db_client().get_iam_policy(req).await?.into_inner();
+
+        Ok(policy)
+    }
+
+    /// Replaces the IAM policy of a database. To avoid clobbering concurrent changes, 'policy'
+    /// should be a modified copy of the result of [`SpannerAdmin::get_database_iam_policy`],
+    /// which keeps the etag.
+    pub async fn set_database_iam_policy<S: AsRef<str>>(
+        &self,
+        db: &Database<S>,
+        policy: iam::Policy,
+    ) -> crate::Result<iam::Policy> {
+        let req = set_iam_policy_request(db, policy);
+
+        let policy = self.db_client().set_iam_policy(req).await?.into_inner();
+
+        Ok(policy)
+    }
+}
+
+/// Calls 'get_page' with each 'next_page_token' until one comes back empty, collecting the
+/// items from every page. The first page is requested with an empty token.
+async fn collect_pages<T, F, Fut>(mut get_page: F) -> crate::Result<Vec<T>>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = crate::Result<(Vec<T>, String)>>,
+{
+    let mut items = Vec::new();
+    let mut page_token = String::new();
+
+    loop {
+        let (page, next_page_token) = get_page(page_token).await?;
+        items.extend(page);
+
+        if next_page_token.is_empty() {
+            return Ok(items);
+        }
+
+        page_token = next_page_token;
+    }
+}
+
+fn list_databases_request(parent: String, page_token: String) -> ListDatabasesRequest {
+    ListDatabasesRequest {
+        parent,
+        page_size: 0,
+        page_token,
+    }
+}
+
+fn list_backups_request(parent: String, filter: String, page_token: String) -> ListBackupsRequest {
+    ListBackupsRequest {
+        parent,
+        filter,
+        page_size: 0,
+        page_token,
+    }
+}
+
+fn create_backup_request<S: AsRef<str>>(
+    db: &Database<S>,
+    backup_id: String,
+    expire_time: Timestamp,
+    version_time: Option<Timestamp>,
+) -> CreateBackupRequest {
+    CreateBackupRequest {
+        parent: db.qualified_instance().to_owned(),
+        backup_id,
+        backup: Some(Backup {
+            database: db.qualified_database().to_owned(),
+            version_time: version_time.map(Into::into),
+            expire_time: Some(expire_time.into()),
+            ..Default::default()
+        }),
+        encryption_config: None,
+    }
+}
+
+fn copy_backup_request<I: AsRef<str>>(
+    source_backup: String,
+    instance: &Instance<I>,
+    backup_id: String,
+    expire_time: Timestamp,
+) -> CopyBackupRequest {
+    CopyBackupRequest {
+        parent: instance.build_qualified(),
+        backup_id,
+        source_backup,
+        expire_time: Some(expire_time.into()),
+        encryption_config: None,
+    }
+}
+
+fn restore_request<S: AsRef<str>>(backup: String, db: &Database<S>) -> RestoreDatabaseRequest {
+    RestoreDatabaseRequest {
+        parent: db.qualified_instance().to_owned(),
+        database_id: db.database().to_owned(),
+        encryption_config: None,
+        source: Some(restore_database_request::Source::Backup(backup)),
+    }
+}
+
+fn get_iam_policy_request<S: AsRef<str>>(db: &Database<S>) -> iam::GetIamPolicyRequest {
+    iam::GetIamPolicyRequest {
+        resource: db.qualified_database().to_owned(),
+        options: None,
+    }
+}
+
+fn set_iam_policy_request<S: AsRef<str>>(
+    db: &Database<S>,
+    policy: iam::Policy,
+) -> iam::SetIamPolicyRequest {
+    iam::SetIamPolicyRequest {
+        resource: db.qualified_database().to_owned(),
+        policy: Some(policy),
+        update_mask: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    const DB: Database<&'static str> = crate::database!("test-project", "test-instance", "test-db");
+
+    const INSTANCE_PATH: &str = "projects/test-project/instances/test-instance";
+    const DB_PATH: &str = "projects/test-project/instances/test-instance/databases/test-db";
+    const BACKUP_PATH: &str = "projects/test-project/instances/test-instance/backups/nightly";
+
+    #[test]
+    fn test_backup_requests() {
+        let expire_time = Timestamp::from_seconds(1_700_000_000);
+        let version_time = Timestamp::from_seconds(1_600_000_000);
+
+        let create =
+            create_backup_request(&DB, "nightly".to_owned(), expire_time, Some(version_time));
+        assert_eq!(create.parent, INSTANCE_PATH);
+        assert_eq!(create.backup_id, "nightly");
+
+        let backup = create.backup.expect("backup should be set");
+        assert_eq!(backup.database, DB_PATH);
+        assert_eq!(backup.expire_time, Some(expire_time.into()));
+        assert_eq!(backup.version_time, Some(version_time.into()));
+
+        let other_instance = crate::info::Project::new("other-project").instance("other-instance");
+        let copy = copy_backup_request(
+            BACKUP_PATH.to_owned(),
+            &other_instance,
+            "nightly-copy".to_owned(),
+            expire_time,
+        );
+        assert_eq!(
+            copy.parent,
+            "projects/other-project/instances/other-instance"
+        );
+        assert_eq!(copy.source_backup, BACKUP_PATH);
+        assert_eq!(copy.backup_id, "nightly-copy");
+        assert_eq!(copy.expire_time, Some(expire_time.into()));
+
+        let restore = restore_request(BACKUP_PATH.to_owned(), &DB);
+        assert_eq!(restore.parent, INSTANCE_PATH);
+        assert_eq!(restore.database_id, "test-db");
+        assert_eq!(
+            restore.source,
+            Some(restore_database_request::Source::Backup(
+                BACKUP_PATH.to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_iam_requests() {
+        assert_eq!(get_iam_policy_request(&DB).resource, DB_PATH);
+
+        let policy = iam::Policy {
+            etag: Bytes::from_static(b"etag"),
+            ..Default::default()
+        };
+
+        let set = set_iam_policy_request(&DB, policy.clone());
+        assert_eq!(set.resource, DB_PATH);
+        assert_eq!(set.policy, Some(policy));
+    }
+
+    #[test]
+    fn test_collect_pages() {
+        let mut pages = vec![
+            (vec![1, 2], "second".to_owned()),
+            (vec![3], "third".to_owned()),
+            (vec![], String::new()),
+        ]
+        .into_iter();
+
+        let mut requested_tokens = Vec::new();
+
+        let items = futures::executor::block_on(collect_pages(|page_token| {
+            requested_tokens.push(page_token);
+            let page = pages.next().expect("requested too many pages");
+            async move { Ok(page) }
+        }))
+        .unwrap();
+
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(requested_tokens, ["", "second", "third"]);
+
+        let list_databases = list_databases_request(INSTANCE_PATH.to_owned(), "second".to_owned());
+        assert_eq!(list_databases.parent, INSTANCE_PATH);
+        assert_eq!(list_databases.page_token, "second");
+
+        let list_backups = list_backups_request(
+            INSTANCE_PATH.to_owned(),
+            "database:test-db".to_owned(),
+            String::new(),
+        );
+        assert_eq!(list_backups.parent, INSTANCE_PATH);
+        assert_eq!(list_backups.filter, "database:test-db");
+    }
+
+    #[test]
+    fn test_metadata_progress() {
+        fn progress(percent: i32) -> Option<OperationProgress> {
+            Some(OperationProgress {
+                progress_percent: percent,
+                ..Default::default()
+            })
+        }
+
+        // same decoding as 'OperationHandle::parse_metadata', from the raw operation metadata
+        fn decode<M: OperationMetadata>(metadata: impl Message) -> M {
+            M::decode(metadata.encode_to_vec().as_slice()).expect("should decode")
+        }
+
+        let create: CreateBackupMetadata = decode(CreateBackupMetadata {
+            name: BACKUP_PATH.to_owned(),
+            progress: progress(25),
+            ..Default::default()
+        });
+        assert_eq!(create.name, BACKUP_PATH);
+        assert_eq!(progress_percent(Some(&create)), Some(25));
+
+        let copy: CopyBackupMetadata = decode(CopyBackupMetadata {
+            progress: progress(50),
+            ..Default::default()
+        });
+        assert_eq!(progress_percent(Some(&copy)), Some(50));
+
+        let restore: RestoreDatabaseMetadata = decode(RestoreDatabaseMetadata {
+            name: DB_PATH.to_owned(),
+            progress: progress(100),
+            ..Default::default()
+        });
+        assert_eq!(progress_percent(Some(&restore)), Some(100));
+
+        // no metadata yet, or no progress reported in it
+        assert_eq!(progress_percent::<CreateBackupMetadata>(None), None);
+        assert_eq!(
+            progress_percent(Some(&RestoreDatabaseMetadata::default())),
+            None
+        );
+    }
 }
@@ -16,17 +16,20 @@ use gcp_auth_provider::service::AuthSvc;
 use protos::spanner::transaction_options::read_only::TimestampBound;
 use protos::spanner::transaction_options::read_write::ReadLockMode;
 use protos::spanner::transaction_options::{IsolationLevel, Mode, ReadOnly, ReadWrite};
+use protos::spanner::transaction_selector::Selector;
 use protos::spanner::{
-    self, BeginTransactionRequest, CommitRequest, CommitResponse, ExecuteSqlRequest,
-    PartialResultSet, ReadRequest, ResultSet, TransactionOptions, TransactionSelector,
-    commit_request, execute_sql_request, read_request,
+    self, BeginTransactionRequest, CommitRequest, CommitResponse, DirectedReadOptions,
+    ExecuteBatchDmlRequest, ExecuteBatchDmlResponse, ExecuteSqlRequest, PartialResultSet,
+    ReadRequest, ResultSet, TransactionOptions, TransactionSelector, commit_request,
+    execute_batch_dml_request, execute_sql_request, read_request,
 };
 
 use crate::key_set::KeySet;
 use crate::queryable::Queryable;
+use crate::request_options::RequestOptions;
 use crate::results::{ResultIter, StreamingRead};
 use crate::sql::Params;
-use crate::tx::ReadWriteTx;
+use crate::tx::{ReadOnlyTx, ReadWriteTx};
 use crate::{Table, tx};
 
 /// A const proto key set to read an entire table
@@ -61,6 +64,7 @@ const DEFAULT_READ_ONLY: TransactionOptions = TransactionOptions {
 pub struct ConnectionParts<'a, T> {
     pub(crate) client_parts: &'a crate::client::ClientParts,
     pub(crate) session: &'a Arc<Session>,
+    pub(crate) options: &'a RequestOptions,
     tx: T,
 }
 
@@ -69,11 +73,13 @@ impl<'a, T> ConnectionParts<'a, T> {
     pub(crate) fn from_parts(
         client_parts: &'a crate::client::ClientParts,
         session: &'a Arc<Session>,
+        options: &'a RequestOptions,
         tx: T,
     ) -> Self {
         Self {
             client_parts,
             session,
+            options,
             tx,
         }
     }
@@ -91,6 +97,8 @@ fn build_read_request(
     lim: Option<u32>,
     order_by: Option<read_request::OrderBy>,
     lock_hint: Option<read_request::LockHint>,
+    request_options: Option<spanner::RequestOptions>,
+    directed_read_options: Option<DirectedReadOptions>,
 ) -> ReadRequest {
     ReadRequest {
         session,
@@ -99,14 +107,14 @@ fn build_read_request(
         index: String::new(),
         order_by: order_by.unwrap_or(read_request::OrderBy::Unspecified) as i32,
         lock_hint: lock_hint.unwrap_or(read_request::LockHint::Unspecified) as i32,
-        directed_read_options: None,
+        directed_read_options,
         key_set: Some(key_set),
         table,
         columns: cols,
         limit: lim.unwrap_or(0) as i64,
         resume_token: Bytes::new(),
         partition_token: Bytes::new(),
-        request_options: None,
+        request_options,
         routing_hint: None,
     }
 }
@@ -114,11 +122,13 @@ fn build_read_request(
 /// Helper to construct the [`ExecuteSqlRequest`] used by all Spanner sql request (both streaming
 /// and unary). Specifically Non-generic.
 #[inline]
-fn build_sql_request(
+pub(super) fn build_sql_request(
     session: String,
     tx: TransactionSelector,
     sql: String,
     params: Option<Params>,
+    request_options: Option<spanner::RequestOptions>,
+    directed_read_options: Option<DirectedReadOptions>,
 ) -> ExecuteSqlRequest {
     let (params, param_types) = params.map(Params::into_parts).unwrap_or_default();
 
@@ -126,10 +136,10 @@ fn build_sql_request(
         session,
         data_boost_enabled: false,
         transaction: Some(tx),
-        directed_read_options: None,
+        directed_read_options,
         resume_token: Bytes::new(),
         partition_token: Bytes::new(),
-        request_options: None,
+        request_options,
         sql,
         params,
         param_types,
@@ -149,6 +159,17 @@ impl<Tx> ConnectionParts<'_, Tx> {
     }
 }
 
+impl<Tx: crate::private::SealedTx> ConnectionParts<'_, Tx> {
+    /// The directed read options to send, if 'Tx' allows them.
+    fn directed_read_options(&self) -> Option<DirectedReadOptions> {
+        if Tx::DIRECTED_READS {
+            self.options.directed_read_proto()
+        } else {
+            None
+        }
+    }
+}
+
 // ------------ Read only functions -------------- //
 impl<Tx> ConnectionParts<'_, Tx>
 where
@@ -179,6 +200,8 @@ where
             lim,
             order_by,
             lock_hint,
+            self.options.to_proto(),
+            self.directed_read_options(),
         );
 
         self.client()
@@ -192,18 +215,31 @@ where
         &mut self,
         sql: String,
         params: Option<Params>,
+    ) -> crate::Result<ResultSet> {
+        self.execute_sql_with_mode_inner(sql, params, execute_sql_request::QueryMode::Profile)
+            .await
+    }
+
+    pub(crate) async fn execute_sql_with_mode_inner(
+        &mut self,
+        sql: String,
+        params: Option<Params>,
+        query_mode: execute_sql_request::QueryMode,
     ) -> crate::Result<ResultSet> {
         let raw_session = self
             .session
             .raw_session()
             .ok_or(crate::Error::SessionDeleted)?;
 
-        let request = build_sql_request(
+        let mut request = build_sql_request(
             raw_session.name.clone(),
             self.tx.build_read_only_selector(),
             sql,
             params,
+            self.options.to_proto(),
+            self.directed_read_options(),
         );
+        request.query_mode = query_mode as i32;
 
         self.client()
             .execute_sql(request)
@@ -235,6 +271,8 @@ where
             lim,
             order_by,
             lock_hint,
+            self.options.to_proto(),
+            self.directed_read_options(),
         );
 
         self.client()
@@ -259,6 +297,8 @@ where
             self.tx.build_read_only_selector(),
             sql,
             params,
+            self.options.to_proto(),
+            self.directed_read_options(),
         );
 
         self.client()
@@ -388,13 +428,14 @@ fn build_commit_request(
     session: String,
     tx: commit_request::Transaction,
     mutations: Vec<spanner::Mutation>,
+    request_options: Option<spanner::RequestOptions>,
 ) -> CommitRequest {
     CommitRequest {
         max_commit_delay: None,
         session,
         mutations,
         return_commit_stats: true,
-        request_options: None,
+        request_options,
         transaction: Some(tx),
         precommit_token: None,
     }
@@ -417,6 +458,7 @@ impl<Tx: ReadWriteTx> ConnectionParts<'_, Tx> {
             raw_session.name.clone(),
             self.tx.build_read_write(),
             mutations,
+            self.options.to_proto(),
         );
 
         self.client()
@@ -443,6 +485,79 @@ impl ConnectionParts<'_, tx::Existing<'_, tx::ReadWrite>> {
 
         Ok(())
     }
+
+    pub(crate) async fn execute_batch_dml_inner(
+        &mut self,
+        statements: Vec<execute_batch_dml_request::Statement>,
+        seqno: i64,
+    ) -> crate::Result<ExecuteBatchDmlResponse> {
+        let raw_session = self
+            .session
+            .raw_session()
+            .ok_or(crate::Error::SessionDeleted)?;
+
+        let request = ExecuteBatchDmlRequest {
+            session: raw_session.name.clone(),
+            transaction: Some(self.tx.build_read_only_selector()),
+            statements,
+            seqno,
+            request_options: self.options.to_proto(),
+            last_statements: false,
+        };
+
+        self.client()
+            .execute_batch_dml(request)
+            .await
+            .map(tonic::Response::into_inner)
+            .map_err(crate::Error::from)
+    }
+}
+
+// ----------- Partitioned DML specific ----------- //
+impl ConnectionParts<'_, tx::Existing<'_, tx::PartitionedDml>> {
+    /// Executes the statement, returning the lower bound on the number of modified rows.
+    pub(crate) async fn execute_partitioned_dml_inner(
+        &mut self,
+        sql: String,
+        params: Option<Params>,
+    ) -> crate::Result<i64> {
+        let raw_session = self
+            .session
+            .raw_session()
+            .ok_or(crate::Error::SessionDeleted)?;
+
+        let selector = TransactionSelector {
+            selector: Some(Selector::Id(self.tx.clone_id())),
+        };
+
+        let mut request = build_sql_request(
+            raw_session.name.clone(),
+            selector,
+            sql,
+            params,
+            self.options.to_proto(),
+            None,
+        );
+        // profiling a statement that touches the entire table isn't worth the overhead.
+        request.query_mode = execute_sql_request::QueryMode::Normal as i32;
+
+        // partitioned DML can run for a long time, so use the streaming variant to avoid
+        // hitting the deadline for unary requests. The stats are only in the final message.
+        let mut streaming = self
+            .client()
+            .execute_streaming_sql(request)
+            .await?
+            .into_inner();
+
+        let mut stats = None;
+        while let Some(partial) = streaming.message().await? {
+            if partial.stats.is_some() {
+                stats = partial.stats;
+            }
+        }
+
+        crate::dml::row_count(stats)
+    }
 }
 
 // ----------- Special begin tx methods --------------- //
@@ -457,7 +572,7 @@ impl<'a, Tx: tx::TxOptions + Copy> ConnectionParts<'a, tx::Begin<Tx>> {
         let request = BeginTransactionRequest {
             session: raw_session.name.clone(),
             options: Some(Tx::OPTIONS),
-            request_options: None,
+            request_options: self.options.to_proto(),
             mutation_key: None,
         };
 
@@ -492,6 +607,8 @@ impl<'a, Tx: tx::TxOptions + Copy> ConnectionParts<'a, tx::Begin<Tx>> {
             lim,
             order_by,
             lock_hint,
+            self.options.to_proto(),
+            self.directed_read_options(),
         );
 
         let mut result_set = self.client().read(req).await?.into_inner();
@@ -534,6 +651,8 @@ impl<'a, Tx: tx::TxOptions + Copy> ConnectionParts<'a, tx::Begin<Tx>> {
             lim,
             order_by,
             lock_hint,
+            self.options.to_proto(),
+            self.directed_read_options(),
         );
 
         let mut streaming = self.client().streaming_read(req).await?.into_inner();
@@ -0,0 +1,75 @@
+//! Sessions created outside of the pool, for queries that can stay open indefinitely (i.e change
+//! streams). Holding a pooled session for those would starve everything else once enough of
+//! them are running, since the pool has a fixed size.
+use std::sync::Arc;
+
+use protos::spanner::spanner_client::SpannerClient;
+use protos::spanner::transaction_selector::Selector;
+use protos::spanner::{self, TransactionSelector};
+
+use super::connection::build_sql_request;
+use super::{Client, ClientParts, pool};
+use crate::StreamingRead;
+use crate::queryable::Queryable;
+use crate::request_options::RequestOptions;
+use crate::sql::Params;
+
+/// A session that isn't part of the pool. Needs to be deleted with [`DedicatedSession::delete`]
+/// once it's done with, otherwise it lingers until Spanner cleans it up after an hour of
+/// inactivity.
+pub(crate) struct DedicatedSession {
+    parts: Arc<ClientParts>,
+    options: Arc<RequestOptions>,
+    name: String,
+}
+
+impl Client {
+    pub(crate) async fn create_dedicated_session(&self) -> crate::Result<DedicatedSession> {
+        let session = pool::create_session(&self.parts).await?;
+
+        Ok(DedicatedSession {
+            parts: self.parts.clone(),
+            options: self.options.clone(),
+            name: session.name,
+        })
+    }
+}
+
+impl DedicatedSession {
+    /// Runs a streaming query in a single use, read only transaction.
+    pub(crate) async fn execute_streaming_sql<T: Queryable>(
+        &self,
+        sql: String,
+        params: Option<Params>,
+    ) -> crate::Result<StreamingRead<T>> {
+        let request = build_sql_request(
+            self.name.clone(),
+            TransactionSelector {
+                selector: Some(Selector::SingleUse(crate::tx::READ_ONLY)),
+            },
+            sql,
+            params,
+            self.options.to_proto(),
+            self.options.directed_read_proto(),
+        );
+
+        let response = SpannerClient::new(self.parts.channel.clone())
+            .execute_streaming_sql(request)
+            .await?;
+
+        Ok(StreamingRead::from_streaming(response.into_inner()))
+    }
+
+    /// Deletes the session. Errors are only logged, since Spanner eventually deletes idle
+    /// sessions anyways.
+    pub(crate) async fn delete(self) {
+        let request = spanner::DeleteSessionRequest { name: self.name };
+
+        if let Err(error) = SpannerClient::new(self.parts.channel.clone())
+            .delete_session(request)
+            .await
+        {
+            warn!(message = "failed to delete dedicated session", ?error);
+        }
+    }
+}
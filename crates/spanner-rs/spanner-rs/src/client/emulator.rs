@@ -255,6 +255,32 @@ impl Emulator {
         ))
     }
 
+    /// [`Emulator::create_database`], then applies 'migrations' to the new database, so it
+    /// matches what [`Migrator::run`] would produce in production.
+    ///
+    /// [`Migrator::run`]: crate::migration::Migrator::run
+    #[cfg(feature = "admin")]
+    pub async fn create_migrated_database(
+        &self,
+        database: crate::Database,
+        instance_compute: crate::client::admin::InstanceCompute,
+        migrations: crate::migration::Migrations,
+        timeout: Option<Duration>,
+    ) -> crate::Result<crate::Client> {
+        let client = self
+            .create_database(database, instance_compute, vec![], timeout)
+            .await?;
+
+        let mut migrator = crate::migration::Migrator::new(client.clone(), migrations);
+        if let Some(timeout) = timeout {
+            migrator = migrator.timeout(timeout);
+        }
+
+        migrator.run().await?;
+
+        Ok(client)
+    }
+
     pub fn options(&self) -> &EmulatorOptions {
         &self.options
     }
@@ -0,0 +1,371 @@
+//! Partitioned queries and reads, for reading large result sets in parallel.
+//!
+//! Partitioning a query (via [`Client::partition_query`]) or read (via
+//! [`KeySet::partition_read`]) starts a read-only transaction on a dedicated session, and
+//! splits the results into [`Partition`]s. Each [`Partition`] is serializable, and can be
+//! executed independently by any worker or process that has a [`Client`] for the same
+//! database. All partitions read at the same timestamp.
+//!
+//! The dedicated session is kept around until [`Partitions::release`] is called (or Spanner
+//! cleans it up after an hour of inactivity), so partitions can keep being executed until then.
+//!
+//! [`KeySet::partition_read`]: crate::key_set::KeySet::partition_read
+use std::marker::PhantomData;
+
+use protos::spanner::spanner_client::SpannerClient;
+use protos::spanner::transaction_selector::Selector;
+use protos::spanner::{
+    self, ExecuteSqlRequest, PartitionQueryRequest, PartitionReadRequest, ReadRequest,
+    TransactionSelector, execute_sql_request, read_request,
+};
+use serde::{Deserialize, Serialize};
+use timestamp::Timestamp;
+
+use super::{Client, ClientParts, pool};
+use crate::StreamingRead;
+use crate::queryable::Queryable;
+use crate::sql::Params;
+
+/// The set of partitions returned by [`Client::partition_query`] or
+/// [`KeySet::partition_read`].
+///
+/// [`KeySet::partition_read`]: crate::key_set::KeySet::partition_read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Partitions<T> {
+    session: String,
+    read_timestamp: Option<Timestamp>,
+    partitions: Vec<Partition<T>>,
+}
+
+/// A single partition of a query or read. Serializable, so it can be sent to other workers
+/// and executed with [`Partition::execute`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Partition<T> {
+    request: PartitionRequest,
+    read_timestamp: Option<Timestamp>,
+    #[serde(skip)]
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum PartitionRequest {
+    Query(ExecuteSqlRequest),
+    Read(ReadRequest),
+}
+
+/// Options that control how results are partitioned. Both are hints, Spanner may return
+/// a different number/size of partitions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartitionOptions {
+    /// The desired data size for each partition.
+    pub partition_size_bytes: Option<u64>,
+    /// The desired maximum number of partitions.
+    pub max_partitions: Option<u64>,
+}
+
+impl PartitionOptions {
+    fn into_proto(self) -> spanner::PartitionOptions {
+        spanner::PartitionOptions {
+            partition_size_bytes: self.partition_size_bytes.unwrap_or(0) as i64,
+            max_partitions: self.max_partitions.unwrap_or(0) as i64,
+        }
+    }
+}
+
+impl<T> Clone for Partition<T> {
+    fn clone(&self) -> Self {
+        Self {
+            request: self.request.clone(),
+            read_timestamp: self.read_timestamp,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Partition<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.request == other.request && self.read_timestamp == other.read_timestamp
+    }
+}
+
+impl<T> Partitions<T> {
+    /// The timestamp all partitions read at.
+    pub fn read_timestamp(&self) -> Option<Timestamp> {
+        self.read_timestamp
+    }
+
+    pub fn len(&self) -> usize {
+        self.partitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.partitions.is_empty()
+    }
+
+    pub fn partitions(&self) -> &[Partition<T>] {
+        &self.partitions
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Partition<T>> {
+        self.partitions.iter()
+    }
+
+    /// Deletes the session the partitions were created with. Any partitions that haven't been
+    /// executed yet will fail after this.
+    pub async fn release(&self, client: &Client) -> crate::Result<()> {
+        SpannerClient::new(client.parts.channel.clone())
+            .delete_session(spanner::DeleteSessionRequest {
+                name: self.session.clone(),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Partitions<T> {
+    type Item = &'a Partition<T>;
+    type IntoIter = std::slice::Iter<'a, Partition<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.partitions.iter()
+    }
+}
+
+impl<T> IntoIterator for Partitions<T> {
+    type Item = Partition<T>;
+    type IntoIter = std::vec::IntoIter<Partition<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.partitions.into_iter()
+    }
+}
+
+impl<T> Partition<T> {
+    /// The timestamp this partition reads at, shared with all other partitions from the same
+    /// [`Partitions`].
+    pub fn read_timestamp(&self) -> Option<Timestamp> {
+        self.read_timestamp
+    }
+}
+
+impl<T: Queryable> Partition<T> {
+    /// Executes this partition. 'client' only needs to point at the same database, it doesn't
+    /// need to be the client that created the partitions.
+    pub async fn execute(&self, client: &Client) -> crate::Result<StreamingRead<T>> {
+        let mut spanner_client = SpannerClient::new(client.parts.channel.clone());
+
+        let response = match self.request.clone() {
+            PartitionRequest::Query(request) => {
+                spanner_client.execute_streaming_sql(request).await?
+            }
+            PartitionRequest::Read(request) => spanner_client.streaming_read(request).await?,
+        };
+
+        Ok(StreamingRead::from_streaming(response.into_inner()))
+    }
+}
+
+impl Client {
+    /// Partitions a query, so the results can be read in parallel. See the
+    /// [module docs](crate::partition) for details.
+    ///
+    /// Only queries where the first operator in the execution plan is a distributed union
+    /// can be partitioned, i.e most simple scans/filters over a table.
+    pub async fn partition_query<T: Queryable>(
+        &self,
+        sql: String,
+        params: Option<Params>,
+    ) -> crate::Result<Partitions<T>> {
+        self.partition_query_with_options(sql, params, PartitionOptions::default())
+            .await
+    }
+
+    /// [`Client::partition_query`], with explicit [`PartitionOptions`].
+    pub async fn partition_query_with_options<T: Queryable>(
+        &self,
+        sql: String,
+        params: Option<Params>,
+        options: PartitionOptions,
+    ) -> crate::Result<Partitions<T>> {
+        // partitions need a session that outlives this call, so this can't come from the pool
+        let session = pool::create_session(&self.parts).await?.name;
+        let (params, param_types) = params.map(Params::into_parts).unwrap_or_default();
+
+        let request = PartitionQueryRequest {
+            session: session.clone(),
+            transaction: Some(begin_read_only()),
+            sql: sql.clone(),
+            params: params.clone(),
+            param_types: param_types.clone(),
+            partition_options: Some(options.into_proto()),
+        };
+
+        let result = SpannerClient::new(self.parts.channel.clone())
+            .partition_query(request)
+            .await;
+
+        let template = ExecuteSqlRequest {
+            session: session.clone(),
+            data_boost_enabled: false,
+            transaction: None,
+            directed_read_options: self.options.directed_read_proto(),
+            resume_token: Default::default(),
+            partition_token: Default::default(),
+            request_options: self.options.to_proto(),
+            sql,
+            params,
+            param_types,
+            // partitioned queries can only use the normal query mode
+            query_mode: execute_sql_request::QueryMode::Normal as i32,
+            seqno: 0,
+            query_options: None,
+            last_statement: false,
+            routing_hint: None,
+        };
+
+        build_partitions(&self.parts, session, result, |transaction, token| {
+            PartitionRequest::Query(ExecuteSqlRequest {
+                transaction: Some(transaction),
+                partition_token: token,
+                ..template.clone()
+            })
+        })
+        .await
+    }
+
+    pub(crate) async fn partition_read<T: crate::Table + Queryable>(
+        &self,
+        key_set: spanner::KeySet,
+        options: PartitionOptions,
+    ) -> crate::Result<Partitions<T>> {
+        let session = pool::create_session(&self.parts).await?.name;
+        let columns = crate::util::table_col_names::<T>();
+
+        let request = PartitionReadRequest {
+            session: session.clone(),
+            transaction: Some(begin_read_only()),
+            table: T::NAME.to_owned(),
+            index: String::new(),
+            columns: columns.clone(),
+            key_set: Some(key_set.clone()),
+            partition_options: Some(options.into_proto()),
+        };
+
+        let result = SpannerClient::new(self.parts.channel.clone())
+            .partition_read(request)
+            .await;
+
+        let template = ReadRequest {
+            session: session.clone(),
+            data_boost_enabled: false,
+            transaction: None,
+            index: String::new(),
+            order_by: read_request::OrderBy::Unspecified as i32,
+            lock_hint: read_request::LockHint::Unspecified as i32,
+            directed_read_options: self.options.directed_read_proto(),
+            key_set: Some(key_set),
+            table: T::NAME.to_owned(),
+            columns,
+            limit: 0,
+            resume_token: Default::default(),
+            partition_token: Default::default(),
+            request_options: self.options.to_proto(),
+            routing_hint: None,
+        };
+
+        build_partitions(&self.parts, session, result, |transaction, token| {
+            PartitionRequest::Read(ReadRequest {
+                transaction: Some(transaction),
+                partition_token: token,
+                ..template.clone()
+            })
+        })
+        .await
+    }
+}
+
+fn begin_read_only() -> TransactionSelector {
+    TransactionSelector {
+        selector: Some(Selector::Begin(crate::tx::READ_ONLY)),
+    }
+}
+
+/// Builds the [`Partitions`] from the partition response, deleting the dedicated session if
+/// partitioning failed.
+async fn build_partitions<T>(
+    parts: &ClientParts,
+    session: String,
+    result: Result<tonic::Response<spanner::PartitionResponse>, tonic::Status>,
+    mut build_request: impl FnMut(TransactionSelector, bytes::Bytes) -> PartitionRequest,
+) -> crate::Result<Partitions<T>> {
+    let response = match result {
+        Ok(response) => response.into_inner(),
+        Err(status) => {
+            let delete = spanner::DeleteSessionRequest { name: session };
+            if let Err(error) = SpannerClient::new(parts.channel.clone())
+                .delete_session(delete)
+                .await
+            {
+                warn!(message = "failed to delete partition session", ?error);
+            }
+            return Err(status.into());
+        }
+    };
+
+    let transaction = response
+        .transaction
+        .ok_or_else(|| anyhow::anyhow!("partition response is missing the transaction"))?;
+
+    let read_timestamp = transaction.read_timestamp.map(Timestamp::from);
+
+    let partitions = response
+        .partitions
+        .into_iter()
+        .map(|partition| Partition {
+            request: build_request(
+                TransactionSelector {
+                    selector: Some(Selector::Id(transaction.id.clone())),
+                },
+                partition.partition_token,
+            ),
+            read_timestamp,
+            _marker: PhantomData,
+        })
+        .collect();
+
+    Ok(Partitions {
+        session,
+        read_timestamp,
+        partitions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_round_trip() {
+        let partition = Partition::<(i64,)> {
+            request: PartitionRequest::Query(ExecuteSqlRequest {
+                session: "projects/p/instances/i/databases/d/sessions/s".to_owned(),
+                transaction: Some(TransactionSelector {
+                    selector: Some(Selector::Id(bytes::Bytes::from_static(b"tx-id"))),
+                }),
+                sql: "SELECT 1".to_owned(),
+                partition_token: bytes::Bytes::from_static(b"\x00\x01token"),
+                ..Default::default()
+            }),
+            read_timestamp: Some(Timestamp::UNIX_EPOCH),
+            _marker: PhantomData,
+        };
+
+        let json = serde_json::to_string(&partition).unwrap();
+        let decoded: Partition<(i64,)> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(partition, decoded);
+    }
+}
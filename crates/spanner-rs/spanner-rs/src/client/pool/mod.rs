@@ -321,7 +321,7 @@ impl SessionPool {
     }
 }
 
-async fn create_session(parts: &ClientParts) -> crate::Result<spanner::Session> {
+pub(super) async fn create_session(parts: &ClientParts) -> crate::Result<spanner::Session> {
     SpannerClient::new(parts.channel.clone())
         .create_session(spanner::CreateSessionRequest {
             database: parts.info.qualified_database().to_owned(),
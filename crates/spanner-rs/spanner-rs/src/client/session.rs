@@ -1,22 +1,28 @@
 use std::sync::Arc;
 
+use net_utils::backoff::Backoff;
 use protos::spanner::commit_response::CommitStats;
+use protos::spanner::execute_sql_request::QueryMode;
 use timestamp::Timestamp;
 use tokio::task::JoinHandle;
 
 use super::ClientParts;
 use super::connection::ConnectionParts;
 use super::pool::Session;
+use crate::dml::BatchDml;
 use crate::error::ConvertError;
 use crate::key_set::{IntoKeyRange, IntoKeySet, KeySet, WriteBuilder};
 use crate::private::SealedConnection;
 use crate::queryable::Queryable;
-use crate::tx::{ShouldCommit, SingleUse, Transaction};
+use crate::request_options::RequestOptions;
+use crate::results::plan::{QueryPlan, QueryProfile};
+use crate::tx::{Existing, PartitionedDml, ShouldCommit, SingleUse, Transaction};
 use crate::{ResultIter, StreamingRead, Table};
 
 pub struct SessionClient {
     pub(super) parts: Arc<ClientParts>,
     pub(super) session: Arc<Session>,
+    pub(super) options: RequestOptions,
 }
 
 impl Drop for SessionClient {
@@ -51,7 +57,12 @@ impl SealedConnection for SessionClient {
 
     #[inline]
     fn connection_parts(&self) -> ConnectionParts<'_, Self::Tx<'_>> {
-        ConnectionParts::from_parts(&self.parts, &self.session, crate::tx::SingleUse)
+        ConnectionParts::from_parts(
+            &self.parts,
+            &self.session,
+            &self.options,
+            crate::tx::SingleUse,
+        )
     }
 }
 impl SessionClient {
@@ -65,6 +76,19 @@ impl SessionClient {
         self.parts.role.as_deref()
     }
 
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.options
+    }
+
+    /// Overrides the request options inherited from the [`Client`], for all requests made with
+    /// this session (including transactions started afterwards).
+    ///
+    /// [`Client`]: crate::Client
+    pub fn set_request_options(&mut self, options: &RequestOptions) -> &mut Self {
+        self.options = self.options.merged(options);
+        self
+    }
+
     async fn write_inner(
         &self,
         mutations: Vec<protos::spanner::Mutation>,
@@ -119,38 +143,91 @@ impl SessionClient {
     }
 
     pub async fn begin_transaction(&self) -> crate::Result<Transaction<'_>> {
-        let mut parts =
-            ConnectionParts::from_parts(&self.parts, &self.session, crate::tx::Begin::default());
+        let mut parts = ConnectionParts::from_parts(
+            &self.parts,
+            &self.session,
+            &self.options,
+            crate::tx::Begin::default(),
+        );
 
         let tx = parts.begin_tx().await?;
 
         Ok(Transaction::new(parts, tx))
     }
 
-    pub(crate) async fn execute_dml(
-        &self,
-        statements: Vec<protos::spanner::execute_batch_dml_request::Statement>,
-    ) -> crate::Result<protos::spanner::ExecuteBatchDmlResponse> {
-        let raw_session = self
-            .session
-            .raw_session()
-            .ok_or(crate::Error::SessionDeleted)?;
-
-        let req = protos::spanner::ExecuteBatchDmlRequest {
-            session: raw_session.name.clone(),
-            transaction: None,
-            statements,
-            seqno: 0,
-            request_options: None,
-            last_statements: false,
-        };
+    /// Executes a batch of DML statements in a new read-write transaction, returning the
+    /// number of rows modified by each statement. The transaction is only committed if every
+    /// statement succeeds.
+    pub async fn execute_batch_dml(&self, batch: BatchDml) -> crate::Result<Vec<i64>> {
+        let mut tx = self.begin_transaction().await?;
 
-        let resp = protos::spanner::spanner_client::SpannerClient::new(self.parts.channel.clone())
-            .execute_batch_dml(req)
-            .await?
-            .into_inner();
+        match tx.execute_batch_dml(batch).await {
+            Ok(row_counts) => {
+                tx.commit().await?;
+                Ok(row_counts)
+            }
+            Err(error) => {
+                if let Err(tx_error) = tx.rollback().await {
+                    error!(message = "error rolling back transaction", ?tx_error, orig_error = ?error);
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Executes a statement as partitioned DML, returning a lower bound on the number of
+    /// modified rows.
+    ///
+    /// Partitioned DML isn't atomic, and the statement may be applied more than once to some
+    /// rows, so it needs to be idempotent. In exchange, it isn't subject to the mutation or
+    /// timeout limits of a regular transaction, which makes it suitable for large backfills
+    /// and deletes.
+    pub async fn execute_partitioned_dml(
+        &self,
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<i64> {
+        let mut backoff = Backoff::default();
+
+        loop {
+            match self
+                .try_execute_partitioned_dml(sql.clone(), params.clone())
+                .await
+            {
+                Err(crate::Error::Status(status)) if status.code() == tonic::Code::Aborted => {
+                    match backoff.backoff_once() {
+                        Some(backoff) => backoff.await,
+                        None => return Err(crate::Error::TransactionContention),
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
 
-        Ok(resp)
+    async fn try_execute_partitioned_dml(
+        &self,
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<i64> {
+        let tx = ConnectionParts::from_parts(
+            &self.parts,
+            &self.session,
+            &self.options,
+            crate::tx::Begin::<PartitionedDml>::default(),
+        )
+        .begin_tx()
+        .await?;
+
+        ConnectionParts::from_parts(
+            &self.parts,
+            &self.session,
+            &self.options,
+            Existing::new(&tx),
+        )
+        .execute_partitioned_dml_inner(sql, params)
+        .await
     }
 
     pub async fn execute_streaming_sql<T: Queryable>(
@@ -179,6 +256,42 @@ impl SessionClient {
         ResultIter::from_result_set(result_set)
     }
 
+    /// Executes a query, returning the rows along with the query stats and plan.
+    pub async fn execute_sql_with_plan<T: Queryable>(
+        &self,
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<QueryProfile<T>> {
+        let result_set = self
+            .connection_parts()
+            .execute_sql_with_mode_inner(sql, params, QueryMode::Profile)
+            .await?;
+
+        let mut rows = ResultIter::from_result_set(result_set)?;
+        let plan = rows.take_query_plan();
+        let stats = rows.take_query_stats();
+
+        Ok(QueryProfile { rows, stats, plan })
+    }
+
+    /// Returns the plan for a query, without executing it.
+    pub async fn explain(
+        &self,
+        sql: String,
+        params: Option<crate::sql::Params>,
+    ) -> crate::Result<QueryPlan> {
+        let result_set = self
+            .connection_parts()
+            .execute_sql_with_mode_inner(sql, params, QueryMode::Plan)
+            .await?;
+
+        result_set
+            .stats
+            .and_then(|stats| stats.query_plan)
+            .map(QueryPlan::from_proto)
+            .ok_or(crate::Error::MissingResultMetadata)
+    }
+
     pub fn mutate(&self, capacity: usize) -> MutationBuilder<'_> {
         MutationBuilder {
             mutations: Vec::with_capacity(capacity),
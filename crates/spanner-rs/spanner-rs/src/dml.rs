@@ -0,0 +1,143 @@
+//! Typed batch DML, and the helpers shared with partitioned DML.
+use protos::spanner::execute_batch_dml_request::Statement;
+use protos::spanner::result_set_stats::RowCount;
+use protos::spanner::{ExecuteBatchDmlResponse, ResultSetStats};
+
+use crate::sql::Params;
+
+/// A batch of DML statements, executed in order within a single transaction via
+/// [`Client::execute_batch_dml`] or [`Transaction::execute_batch_dml`].
+///
+/// [`Client::execute_batch_dml`]: crate::Client::execute_batch_dml
+/// [`Transaction::execute_batch_dml`]: crate::tx::Transaction::execute_batch_dml
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchDml {
+    statements: Vec<Statement>,
+}
+
+/// Returned when a statement in a [`BatchDml`] fails. Statements are executed in order, and
+/// execution stops at the first failure.
+#[derive(Debug, thiserror::Error)]
+#[error("statement {index} in the DML batch failed: {status}")]
+pub struct BatchDmlError {
+    /// The index of the failed statement.
+    pub index: usize,
+    /// The row counts of the statements before 'index', which succeeded.
+    pub row_counts: Vec<i64>,
+    pub status: tonic::Status,
+}
+
+impl BatchDml {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            statements: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    pub fn add<S>(&mut self, sql: S, params: Option<Params>) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let (params, param_types) = params.map(Params::into_parts).unwrap_or_default();
+
+        self.statements.push(Statement {
+            sql: sql.into(),
+            params,
+            param_types,
+        });
+        self
+    }
+
+    pub(crate) fn into_statements(self) -> Vec<Statement> {
+        self.statements
+    }
+}
+
+/// Pulls the row count out of the stats of a DML statement.
+pub(crate) fn row_count(stats: Option<ResultSetStats>) -> crate::Result<i64> {
+    match stats.and_then(|stats| stats.row_count) {
+        Some(RowCount::RowCountExact(count) | RowCount::RowCountLowerBound(count)) => Ok(count),
+        None => Err(crate::Error::MissingResultMetadata),
+    }
+}
+
+/// Gets the row count of each statement from a batch response, or the [`BatchDmlError`] for
+/// the statement that failed.
+pub(crate) fn batch_row_counts(response: ExecuteBatchDmlResponse) -> crate::Result<Vec<i64>> {
+    let row_counts = response
+        .result_sets
+        .into_iter()
+        .map(|result_set| row_count(result_set.stats))
+        .collect::<crate::Result<Vec<i64>>>()?;
+
+    match response.status {
+        Some(status) if status.code != tonic::Code::Ok as i32 => {
+            Err(crate::Error::BatchDml(BatchDmlError {
+                index: row_counts.len(),
+                row_counts,
+                status: status.into(),
+            }))
+        }
+        _ => Ok(row_counts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protos::spanner::ResultSet;
+
+    use super::*;
+
+    fn result_set(count: i64) -> ResultSet {
+        ResultSet {
+            stats: Some(ResultSetStats {
+                row_count: Some(RowCount::RowCountExact(count)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_batch_row_counts() {
+        let response = ExecuteBatchDmlResponse {
+            result_sets: vec![result_set(3), result_set(0)],
+            status: Some(Default::default()),
+            precommit_token: None,
+        };
+        assert_eq!(batch_row_counts(response).unwrap(), [3, 0]);
+
+        let response = ExecuteBatchDmlResponse {
+            result_sets: vec![result_set(5)],
+            status: Some(protos::rpc::Status {
+                code: tonic::Code::InvalidArgument as i32,
+                message: "bad statement".to_owned(),
+                details: vec![],
+            }),
+            precommit_token: None,
+        };
+
+        match batch_row_counts(response) {
+            Err(crate::Error::BatchDml(error)) => {
+                assert_eq!(error.index, 1);
+                assert_eq!(error.row_counts, [5]);
+                assert_eq!(error.status.code(), tonic::Code::InvalidArgument);
+            }
+            other => panic!("expected a batch DML error, got {other:?}"),
+        }
+    }
+}
@@ -36,6 +36,8 @@ pub enum Error {
     #[error(transparent)]
     Http(#[from] http::Error),
     #[error(transparent)]
+    BatchDml(#[from] crate::dml::BatchDmlError),
+    #[error(transparent)]
     Misc(#[from] anyhow::Error),
 }
 
@@ -156,7 +156,10 @@ impl<I: AsRef<str>> fmt::Display for Instance<I> {
 }
 
 // re-enable when const slice indexing comes back
-/// Statically builds a [`Database`]
+/// Statically builds a [`Database`], or declares a database schema for [`query!`] to check
+/// queries against (see `spanner_rs_macros::database` for the schema syntax).
+///
+/// [`query!`]: crate::query
 #[macro_export]
 macro_rules! database {
     ($project_id:literal, $instance:literal, $database:literal) => {{
@@ -172,6 +175,9 @@ macro_rules! database {
             ),
         )
     }};
+    ($($schema:tt)+) => {
+        $crate::__macro_internals::database_schema! { $($schema)+ }
+    };
 }
 
 #[derive(Clone, Copy)]
@@ -7,11 +7,12 @@ use protos::spanner::key_range::{EndKeyType, StartKeyType};
 use protos::spanner::{self, KeyRange};
 
 use crate::error::ConvertError;
+use crate::partition::{PartitionOptions, Partitions};
 use crate::pk::IntoPartialPkParts;
 use crate::private::SealedToKey;
 use crate::queryable::Queryable;
 use crate::results::{ResultIter, StreamingRead};
-use crate::{IntoSpanner, PrimaryKey, Table};
+use crate::{Client, IntoSpanner, PrimaryKey, Table};
 
 #[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
 pub struct WriteBuilder<T: Table> {
@@ -358,6 +359,36 @@ impl<T: Table> KeySet<T> {
             .await
     }
 
+    /// Partitions a read of this key set, so the rows can be read in parallel. See
+    /// [`crate::partition`] for details.
+    ///
+    /// Partitioned reads can't be limited, so this errors if a limit is set.
+    pub async fn partition_read(&mut self, client: &Client) -> crate::Result<Partitions<T>>
+    where
+        T: Queryable,
+    {
+        self.partition_read_with_options(client, PartitionOptions::default())
+            .await
+    }
+
+    /// [`KeySet::partition_read`], with explicit [`PartitionOptions`].
+    pub async fn partition_read_with_options(
+        &mut self,
+        client: &Client,
+        options: PartitionOptions,
+    ) -> crate::Result<Partitions<T>>
+    where
+        T: Queryable,
+    {
+        if self.limit.is_some() {
+            return Err(crate::Error::Misc(anyhow::anyhow!(
+                "partitioned reads don't support a limit"
+            )));
+        }
+
+        client.partition_read(self.to_proto(), options).await
+    }
+
     pub fn add_keys<I>(&mut self, keys: I) -> &mut Self
     where
         I: IntoIterator<Item = T::Pk>,
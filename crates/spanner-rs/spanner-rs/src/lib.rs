@@ -10,17 +10,23 @@
 extern crate tracing;
 
 mod batch_write;
+pub mod change_stream;
 mod client;
 pub mod column;
 pub mod convert;
+pub mod dml;
 pub mod error;
 pub mod info;
 // pub mod insertable;
 pub mod key_set;
 #[doc(hidden)]
 pub mod macros;
+#[cfg(feature = "admin")]
+pub mod migration;
 pub mod pk;
+mod query_check;
 pub mod queryable;
+pub mod request_options;
 pub mod results;
 pub mod serde;
 // mod session;
@@ -36,12 +42,13 @@ pub mod with;
 pub use client::admin;
 #[cfg(feature = "emulator")]
 pub use client::emulator;
-pub use client::{Client, SessionClient};
+pub use client::{Client, SessionClient, partition};
 pub use convert::{FromSpanner, IntoSpanner, SpannerEncode};
 pub use error::Error;
 pub use info::Database;
 pub use pk::{PartialPkParts, PkParts, PrimaryKey};
 pub use results::{ResultIter, StreamingRead};
+pub use spanner_rs_macros::{Table, query};
 // pub use session::Session;
 pub use table::Table;
 pub use ty::{Field, Scalar, Type};
@@ -66,11 +73,13 @@ pub mod __macro_internals {
     // re-export for macro usage
     pub use generic_array;
     pub use paste::paste;
+    pub use spanner_rs_macros::database as database_schema;
     pub use typenum;
 
     use crate::Field;
     use crate::convert::{FromSpanner, SpannerEncode};
     use crate::error::ConvertError;
+    pub use crate::query_check::{check_field, check_param, expect_column, find_column, ident_eq};
 
     #[inline]
     #[doc(hidden)]
@@ -126,7 +135,11 @@ mod private {
     ///
     /// [`ReadOnlyTx`]: crate::tx::ReadOnlyTx
     /// [`ReadWriteTx`]: crate::tx::ReadWriteTx
-    pub trait SealedTx: Copy {}
+    pub trait SealedTx: Copy {
+        /// Whether requests in this kind of transaction can set directed read options, which
+        /// Spanner only allows for read-only requests.
+        const DIRECTED_READS: bool = false;
+    }
 }
 
 /// Type alias for [`core::result::Result`] with the error variant pre-set to [`Error`].
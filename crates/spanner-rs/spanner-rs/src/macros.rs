@@ -865,7 +865,27 @@ macro_rules! __impl_columns {
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __row_impls {
+    // `#[derive(Table)]` already defined the struct, so it only needs the impls
     (
+        row = {
+            imports = $imports:tt,
+            meta = [__derived],
+            $($row:tt)*
+        },
+        $($args:tt)*
+    ) => {
+        $crate::__row_impls! {
+            derived = [__derived],
+            row = {
+                imports = $imports,
+                meta = [],
+                $($row)*
+            },
+            $($args)*
+        }
+    };
+    (
+        derived = [$($derived:tt)?],
         row = {
             imports = [$($imports:tt)*],
             meta = [$($meta:tt)*],
@@ -893,12 +913,20 @@ macro_rules! __row_impls {
         pks = [$(($pk_field:ident, ($($pk_type:tt)*), $pk_index:literal, $pk_generic:tt)),* $(,)?],
         pk_name = [$pk_name:ident],
     ) => {
-        $($meta)*
-        $row_vis struct $row <$($generics)*> {
-            $(
-                $($column_metas)*
-                $field_vis $field: $($column_ty)*,
-            )+
+        $crate::__row_struct! {
+            derived = [$($derived)?],
+            meta = [$($meta)*],
+            row = $row,
+            row_vis = $row_vis,
+            generics = [$($generics)*],
+            fields = [
+                $({
+                    meta = [$($column_metas)*],
+                    field_vis = $field_vis,
+                    field = $field,
+                    ty = ($($column_ty)*),
+                }),+
+            ],
         }
 
         impl<$($generics)*> $crate::queryable::Row for $row <$($generics)*>
@@ -1002,11 +1030,49 @@ macro_rules! __row_impls {
             generics = [$($generics)*],
         }
     };
+    (row = $($args:tt)*) => {
+        $crate::__row_impls! {
+            derived = [],
+            row = $($args)*
+        }
+    };
     ($($t:tt)*) => {
         $crate::__invalid_row_syntax!("__row_impls" $($t)*);
     }
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __row_struct {
+    (derived = [__derived], $($ignored:tt)*) => {};
+    (
+        derived = [],
+        meta = [$($meta:tt)*],
+        row = $row:ident,
+        row_vis = $row_vis:vis,
+        generics = [$($generics:tt)*],
+        fields = [
+            $({
+                meta = [$($column_metas:tt)*],
+                field_vis = $field_vis:vis,
+                field = $field:ident,
+                ty = ($($column_ty:tt)*),
+            }),+
+        ],
+    ) => {
+        $($meta)*
+        $row_vis struct $row <$($generics)*> {
+            $(
+                $($column_metas)*
+                $field_vis $field: $($column_ty)*,
+            )+
+        }
+    };
+    ($($t:tt)*) => {
+        $crate::__invalid_row_syntax!("__row_struct" $($t)*);
+    }
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __impl_table {
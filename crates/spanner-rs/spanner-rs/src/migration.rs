@@ -0,0 +1,620 @@
+//! Versioned schema migrations.
+//!
+//! Migrations are `.sql` files named `<version>_<name>.sql` (i.e `0001_create_users.sql`), each
+//! containing one or more DDL statements separated by `;`. [`Migrator::run`] applies any that
+//! haven't been applied yet in version order, one DDL batch (and long running operation) per
+//! migration, and records each in the [`MIGRATIONS_TABLE`] table. Statements are split with the
+//! google-sql tokenizer, so `;`s in strings and comments are fine.
+//!
+//! This works the same against the emulator, either by passing the [`Client`] returned by
+//! [`Emulator::create_database`] to [`Migrator::new`], or via
+//! [`Emulator::create_migrated_database`].
+//!
+//! [`Emulator::create_database`]: crate::emulator::Emulator::create_database
+//! [`Emulator::create_migrated_database`]: crate::emulator::Emulator::create_migrated_database
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use anyhow::anyhow;
+use google_sql::tokens::{PunctOrOp, Tokenizer, TokenizerError};
+use timestamp::Duration;
+
+use crate::Client;
+use crate::admin::SpannerAdmin;
+use crate::dml::BatchDml;
+use crate::sql::Params;
+
+/// The table applied migrations are recorded in.
+pub const MIGRATIONS_TABLE: &str = "SchemaMigrations";
+
+const CREATE_MIGRATIONS_TABLE: &str = "CREATE TABLE SchemaMigrations (
+  Version INT64 NOT NULL,
+  Name STRING(MAX) NOT NULL,
+  AppliedAt TIMESTAMP NOT NULL OPTIONS (allow_commit_timestamp = true),
+) PRIMARY KEY (Version)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u64,
+    pub name: String,
+    pub statements: Vec<String>,
+}
+
+impl Migration {
+    /// Parses a migration from its file name and contents.
+    pub fn parse(file_name: &str, contents: &str) -> crate::Result<Self> {
+        let stem = file_name
+            .strip_suffix(".sql")
+            .ok_or_else(|| anyhow!("migration '{file_name}' isn't a .sql file"))?;
+
+        let (version, name) = stem.split_once('_').unwrap_or((stem, ""));
+
+        let version = version.parse::<u64>().map_err(|_| {
+            anyhow!("migration '{file_name}' needs to be named '<version>_<name>.sql'")
+        })?;
+
+        let statements = split_statements(contents)
+            .map_err(|error| anyhow!("failed to parse migration '{file_name}': {error}"))?;
+
+        if statements.is_empty() {
+            return Err(anyhow!("migration '{file_name}' doesn't contain any statements").into());
+        }
+
+        Ok(Self {
+            version,
+            name: name.to_owned(),
+            statements,
+        })
+    }
+}
+
+/// Splits DDL into statements on the top level `;`s, dropping the `;`s themselves (which Spanner
+/// doesn't accept). Uses the google-sql tokenizer, so `;`s in string literals, quoted identifiers
+/// and comments don't split anything, and comments between statements are dropped.
+fn split_statements(contents: &str) -> Result<Vec<String>, TokenizerError<'_>> {
+    let mut statements = Vec::new();
+    // the byte range of the current statement, from the start of its first token to the end
+    // of its last.
+    let mut current: Option<(usize, usize)> = None;
+
+    for result in Tokenizer::new(contents) {
+        let (span, token) = result?;
+
+        if token == PunctOrOp::Semi {
+            if let Some((start, end)) = current.take() {
+                statements.push(contents[start..end].to_owned());
+            }
+            continue;
+        }
+
+        let end = span.start.offset + span.len;
+        match current {
+            Some((_, ref mut current_end)) => *current_end = end,
+            None => current = Some((span.start.offset, end)),
+        }
+    }
+
+    if let Some((start, end)) = current {
+        statements.push(contents[start..end].to_owned());
+    }
+
+    Ok(statements)
+}
+
+/// An ordered set of [`Migration`]s, with unique versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Migrations {
+    migrations: Vec<Migration>,
+}
+
+impl Migrations {
+    pub fn new(mut migrations: Vec<Migration>) -> crate::Result<Self> {
+        migrations.sort_by_key(|migration| migration.version);
+
+        if let Some(pair) = migrations
+            .windows(2)
+            .find(|pair| pair[0].version == pair[1].version)
+        {
+            return Err(anyhow!(
+                "migrations '{}' and '{}' have the same version ({})",
+                pair[0].name,
+                pair[1].name,
+                pair[0].version
+            )
+            .into());
+        }
+
+        Ok(Self { migrations })
+    }
+
+    /// Loads every `.sql` file in 'dir'. Other files are ignored.
+    pub fn from_dir(dir: impl AsRef<Path>) -> crate::Result<Self> {
+        let mut migrations = Vec::new();
+
+        for entry in std::fs::read_dir(dir.as_ref()).map_err(anyhow::Error::from)? {
+            let path = entry.map_err(anyhow::Error::from)?.path();
+
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+
+            if !path.is_file() || !file_name.ends_with(".sql") {
+                continue;
+            }
+
+            let contents = std::fs::read_to_string(&path).map_err(anyhow::Error::from)?;
+            migrations.push(Migration::parse(file_name, &contents)?);
+        }
+
+        Self::new(migrations)
+    }
+
+    /// Builds migrations from (file name, contents) pairs, i.e from [`include_str`].
+    pub fn from_files<'a, I>(files: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        files
+            .into_iter()
+            .map(|(file_name, contents)| Migration::parse(file_name, contents))
+            .collect::<crate::Result<Vec<_>>>()
+            .and_then(Self::new)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Migration> {
+        self.migrations.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.migrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.migrations.is_empty()
+    }
+
+    /// Returns the migrations that haven't been applied, erroring if any of those are older
+    /// than the latest applied migration, since applying them out of order isn't safe.
+    fn pending(&self, applied: &BTreeSet<u64>) -> crate::Result<Vec<&Migration>> {
+        let latest = applied.last().copied();
+
+        let pending = self
+            .migrations
+            .iter()
+            .filter(|migration| !applied.contains(&migration.version))
+            .collect::<Vec<_>>();
+
+        if let (Some(latest), Some(first)) = (latest, pending.first())
+            && first.version < latest
+        {
+            return Err(anyhow!(
+                "migration {} ('{}') is pending, but the newer migration {latest} has already \
+                 been applied",
+                first.version,
+                first.name,
+            )
+            .into());
+        }
+
+        Ok(pending)
+    }
+}
+
+/// A named schema object, i.e a table or index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SchemaObject {
+    /// i.e 'TABLE', 'INDEX' or 'CHANGE STREAM'.
+    pub kind: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Verb {
+    Create,
+    Alter,
+    Drop,
+}
+
+/// Words that make up an object kind, i.e 'INDEX' or 'CHANGE STREAM'.
+const KIND_WORDS: &[&str] = &[
+    "TABLE", "INDEX", "SEARCH", "VECTOR", "VIEW", "CHANGE", "STREAM", "ROLE", "SEQUENCE", "MODEL",
+    "SCHEMA", "PROPERTY", "GRAPH",
+];
+
+/// Words that don't change what kind of object a statement refers to.
+const MODIFIERS: &[&str] = &[
+    "UNIQUE",
+    "NULL_FILTERED",
+    "OR",
+    "REPLACE",
+    "IF",
+    "NOT",
+    "EXISTS",
+];
+
+/// Parses the verb and object a DDL statement refers to. Statements that don't name a single
+/// object (i.e 'ALTER DATABASE' or 'GRANT') return [`None`].
+fn parse_statement(statement: &str) -> Option<(Verb, SchemaObject)> {
+    let mut words = statement
+        .split(|ch: char| ch.is_whitespace() || ch == '(')
+        .filter(|word| !word.is_empty());
+
+    let verb = match words.next()?.to_ascii_uppercase().as_str() {
+        "CREATE" => Verb::Create,
+        "ALTER" => Verb::Alter,
+        "DROP" => Verb::Drop,
+        _ => return None,
+    };
+
+    let mut kind = Vec::new();
+
+    for word in words {
+        let upper = word.to_ascii_uppercase();
+
+        if MODIFIERS.contains(&upper.as_str()) {
+            continue;
+        }
+
+        if KIND_WORDS.contains(&upper.as_str()) {
+            kind.push(upper);
+            continue;
+        }
+
+        if kind.is_empty() {
+            return None;
+        }
+
+        return Some((
+            verb,
+            SchemaObject {
+                kind: kind.join(" "),
+                name: word.trim_matches('`').to_owned(),
+            },
+        ));
+    }
+
+    None
+}
+
+/// A problem [`Migrator::dry_run`] found with a pending statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The statement creates an object that already exists.
+    AlreadyExists(SchemaObject),
+    /// The statement alters or drops an object that doesn't exist.
+    Missing(SchemaObject),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedStatement {
+    pub version: u64,
+    pub statement: String,
+    pub conflict: Option<Conflict>,
+}
+
+/// The result of [`Migrator::dry_run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRun {
+    /// The versions that have already been applied.
+    pub applied: BTreeSet<u64>,
+    /// Every statement that would be applied, in order.
+    pub statements: Vec<PlannedStatement>,
+}
+
+impl DryRun {
+    pub fn has_conflicts(&self) -> bool {
+        self.statements
+            .iter()
+            .any(|statement| statement.conflict.is_some())
+    }
+}
+
+/// Diffs the pending migrations against the current DDL, tracking the objects created and
+/// dropped by earlier statements in the plan.
+fn plan(current_ddl: &[String], pending: &[&Migration]) -> Vec<PlannedStatement> {
+    let mut objects = current_ddl
+        .iter()
+        .filter_map(|statement| match parse_statement(statement)? {
+            (Verb::Create, object) => Some(object),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut planned = Vec::new();
+
+    for migration in pending {
+        for statement in migration.statements.iter() {
+            let conflict = match parse_statement(statement) {
+                Some((Verb::Create, object)) => {
+                    if objects.contains(&object) {
+                        Some(Conflict::AlreadyExists(object))
+                    } else {
+                        objects.insert(object);
+                        None
+                    }
+                }
+                Some((Verb::Alter, object)) if !objects.contains(&object) => {
+                    Some(Conflict::Missing(object))
+                }
+                Some((Verb::Drop, object)) => {
+                    if objects.remove(&object) {
+                        None
+                    } else {
+                        Some(Conflict::Missing(object))
+                    }
+                }
+                _ => None,
+            };
+
+            planned.push(PlannedStatement {
+                version: migration.version,
+                statement: statement.clone(),
+                conflict,
+            });
+        }
+    }
+
+    planned
+}
+
+/// Applies [`Migrations`] to a database.
+pub struct Migrator {
+    client: Client,
+    admin: SpannerAdmin,
+    migrations: Migrations,
+    timeout: Option<Duration>,
+}
+
+impl Migrator {
+    pub fn new(client: Client, migrations: Migrations) -> Self {
+        Self {
+            admin: SpannerAdmin::from_client(&client),
+            client,
+            migrations,
+            timeout: None,
+        }
+    }
+
+    /// The timeout for each poll of a DDL operation. Defaults to the server default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    async fn current_ddl(&self) -> crate::Result<Vec<String>> {
+        self.admin
+            .clone()
+            .get_database_ddl(self.client.database_info())
+            .await
+    }
+
+    async fn applied_versions(&self, current_ddl: &[String]) -> crate::Result<BTreeSet<u64>> {
+        if !has_migrations_table(current_ddl) {
+            return Ok(BTreeSet::new());
+        }
+
+        let rows = self
+            .client
+            .borrow_session(None)
+            .await?
+            .execute_sql::<(i64,)>(format!("SELECT Version FROM {MIGRATIONS_TABLE}"), None)
+            .await?;
+
+        rows.map(|row| row.map(|(version,)| version as u64))
+            .collect()
+    }
+
+    /// Diffs the pending migrations against the current DDL without applying anything.
+    pub async fn dry_run(&self) -> crate::Result<DryRun> {
+        let current_ddl = self.current_ddl().await?;
+        let applied = self.applied_versions(&current_ddl).await?;
+        let pending = self.migrations.pending(&applied)?;
+
+        Ok(DryRun {
+            statements: plan(&current_ddl, &pending),
+            applied,
+        })
+    }
+
+    /// Applies all pending migrations in order, returning the versions that were applied.
+    ///
+    /// Each migration is claimed by inserting its row into [`MIGRATIONS_TABLE`] before its DDL
+    /// runs, so concurrent runners can't apply the same migration twice. The runner that loses
+    /// the race errors instead. If the DDL fails, the claim is released so the migration can be
+    /// retried.
+    pub async fn run(&self) -> crate::Result<Vec<u64>> {
+        let current_ddl = self.current_ddl().await?;
+
+        if !has_migrations_table(&current_ddl)
+            && let Err(error) = self
+                .apply_ddl(vec![CREATE_MIGRATIONS_TABLE.to_owned()])
+                .await
+        {
+            // a concurrent runner may have created it first.
+            if !has_migrations_table(&self.current_ddl().await?) {
+                return Err(error);
+            }
+        }
+
+        let applied = self.applied_versions(&current_ddl).await?;
+        let pending = self.migrations.pending(&applied)?;
+
+        let mut newly_applied = Vec::with_capacity(pending.len());
+
+        for migration in pending {
+            self.claim(migration).await?;
+
+            info!(
+                message = "applying migration",
+                version = migration.version,
+                name = migration.name
+            );
+
+            if let Err(error) = self.apply_ddl(migration.statements.clone()).await {
+                self.release(migration).await;
+                return Err(error);
+            }
+
+            newly_applied.push(migration.version);
+        }
+
+        Ok(newly_applied)
+    }
+
+    async fn apply_ddl(&self, statements: Vec<String>) -> crate::Result<()> {
+        self.admin
+            .clone()
+            .update_database_ddl(self.client.database_info(), statements, None, None)
+            .await?
+            .wait(self.timeout)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Inserts the row for a migration, failing if the row already exists (i.e another runner
+    /// claimed it since the applied versions were read).
+    async fn claim(&self, migration: &Migration) -> crate::Result<()> {
+        let mut params = Params::with_capacity(2);
+        params.insert("version", migration.version as i64);
+        params.insert("name", migration.name.clone());
+
+        let mut batch = BatchDml::with_capacity(1);
+        batch.add(
+            format!(
+                "INSERT INTO {MIGRATIONS_TABLE} (Version, Name, AppliedAt) VALUES (@version, \
+                 @name, PENDING_COMMIT_TIMESTAMP())"
+            ),
+            Some(params),
+        );
+
+        match self.client.execute_batch_dml(batch).await {
+            Ok(_) => Ok(()),
+            Err(crate::Error::BatchDml(error))
+                if error.status.code() == tonic::Code::AlreadyExists =>
+            {
+                Err(anyhow!(
+                    "migration {} ('{}') was claimed by another runner",
+                    migration.version,
+                    migration.name,
+                )
+                .into())
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Deletes the row inserted by [`Migrator::claim`]. Errors are only logged, since the
+    /// error from the DDL is the one worth returning.
+    async fn release(&self, migration: &Migration) {
+        let mut params = Params::with_capacity(1);
+        params.insert("version", migration.version as i64);
+
+        let mut batch = BatchDml::with_capacity(1);
+        batch.add(
+            format!("DELETE FROM {MIGRATIONS_TABLE} WHERE Version = @version"),
+            Some(params),
+        );
+
+        if let Err(error) = self.client.execute_batch_dml(batch).await {
+            warn!(
+                message = "failed to release migration",
+                version = migration.version,
+                ?error
+            );
+        }
+    }
+}
+
+fn has_migrations_table(current_ddl: &[String]) -> bool {
+    let table = SchemaObject {
+        kind: "TABLE".to_owned(),
+        name: MIGRATIONS_TABLE.to_owned(),
+    };
+
+    current_ddl
+        .iter()
+        .any(|statement| parse_statement(statement) == Some((Verb::Create, table.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_plan() {
+        let migrations = Migrations::from_files([
+            (
+                "0002_add_email.sql",
+                "-- add an email column\nALTER TABLE Users ADD COLUMN Email STRING(MAX);\nCREATE \
+                 UNIQUE INDEX UsersByEmail ON Users(Email);",
+            ),
+            (
+                "0001_create_users.sql",
+                "CREATE TABLE Users (\n  Id INT64 NOT NULL,\n) PRIMARY KEY (Id);\n",
+            ),
+            ("0003_drop_legacy.sql", "DROP TABLE Legacy"),
+        ])
+        .unwrap();
+
+        let versions = migrations.iter().map(|m| m.version).collect::<Vec<_>>();
+        assert_eq!(versions, [1, 2, 3]);
+        assert_eq!(migrations.iter().nth(1).unwrap().statements.len(), 2);
+
+        assert!(Migration::parse("create_users.sql", "SELECT 1").is_err());
+        assert!(
+            Migrations::from_files([("1_a.sql", "SELECT 1"), ("1_b.sql", "SELECT 1")]).is_err()
+        );
+
+        // version 2 pending after 3 was applied isn't safe to apply.
+        assert!(migrations.pending(&BTreeSet::from([1, 3])).is_err());
+
+        let current_ddl = [CREATE_MIGRATIONS_TABLE.to_owned()];
+        let pending = migrations.pending(&BTreeSet::new()).unwrap();
+        let planned = plan(&current_ddl, &pending);
+
+        let conflicts = planned
+            .iter()
+            .map(|statement| statement.conflict.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            conflicts,
+            [
+                None,
+                None,
+                None,
+                Some(Conflict::Missing(SchemaObject {
+                    kind: "TABLE".to_owned(),
+                    name: "Legacy".to_owned(),
+                })),
+            ]
+        );
+
+        assert!(has_migrations_table(&current_ddl));
+    }
+
+    #[test]
+    fn test_split_statements() {
+        let contents = "-- a leading comment; with a semicolon
+CREATE TABLE Users (
+  Id INT64 NOT NULL, -- trailing comment;
+  Name STRING(MAX) OPTIONS (description = 'first; last'),
+) PRIMARY KEY (Id);
+/* block; comment */
+ALTER TABLE Users SET OPTIONS (description = \"users;\");;
+";
+
+        assert_eq!(
+            split_statements(contents).unwrap(),
+            [
+                "CREATE TABLE Users (\n  Id INT64 NOT NULL, -- trailing comment;\n  Name \
+                 STRING(MAX) OPTIONS (description = 'first; last'),\n) PRIMARY KEY (Id)",
+                "ALTER TABLE Users SET OPTIONS (description = \"users;\")",
+            ]
+        );
+
+        assert!(split_statements("-- only a comment\n").unwrap().is_empty());
+        assert!(split_statements("CREATE TABLE Users (Id INT64").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! `const` helpers for the code generated by [`query!`], used to check the columns and query
+//! parameters a query references against [`Table`] definitions at compile time.
+//!
+//! [`query!`]: crate::query
+//! [`Table`]: crate::Table
+use std::borrow::Cow;
+
+use shared::static_or_boxed::StaticOrBoxed;
+
+use crate::column::Column;
+use crate::ty::{Field, ProtoName, SpannerType, Type};
+
+/// Compares 2 identifiers, ignoring ASCII case (since Spanner identifiers are case insensitive).
+pub const fn ident_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if !a[i].eq_ignore_ascii_case(&b[i]) {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Finds a column by name.
+pub const fn find_column(columns: &[Column<'static>], name: &str) -> Option<Column<'static>> {
+    let mut i = 0;
+    while i < columns.len() {
+        if ident_eq(columns[i].name, name) {
+            return Some(columns[i]);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Resolves a column reference, given the lookup result from each table the column could
+/// belong to. Panics with 'missing' if no tables contain the column, and 'ambiguous' if more than
+/// 1 does.
+pub const fn expect_column(
+    candidates: &[Option<Column<'static>>],
+    missing: &'static str,
+    ambiguous: &'static str,
+) -> Column<'static> {
+    let mut found = None;
+
+    let mut i = 0;
+    while i < candidates.len() {
+        if let Some(column) = candidates[i] {
+            if found.is_some() {
+                panic!("{}", ambiguous);
+            }
+            found = Some(column);
+        }
+        i += 1;
+    }
+
+    match found {
+        Some(column) => column,
+        None => panic!("{}", missing),
+    }
+}
+
+/// Checks that a row field with the spanner type 'T' can be decoded from 'column'.
+pub const fn check_field<T: SpannerType + ?Sized>(
+    column: Column<'static>,
+    mismatched: &'static str,
+    nullable: &'static str,
+) {
+    if !types_match(crate::ty::ty::<T>(), column.ty) {
+        panic!("{}", mismatched);
+    }
+
+    if column.nullable && !crate::ty::nullable::<T>() {
+        panic!("{}", nullable);
+    }
+}
+
+/// Checks that a query parameter with the spanner type 'T' matches the expected type.
+pub const fn check_param<T: SpannerType + ?Sized>(expected: &Type, mismatched: &'static str) {
+    if !types_match(crate::ty::ty::<T>(), expected) {
+        panic!("{}", mismatched);
+    }
+}
+
+/// `const` equivalent of '=='.
+///
+/// Types built at runtime (i.e from a result set) are never part of a [`Table`] definition,
+/// so the boxed variants are never considered equal.
+///
+/// [`Table`]: crate::Table
+pub const fn types_match(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Scalar(a), Type::Scalar(b)) => *a as u8 == *b as u8,
+        (Type::Proto(a), Type::Proto(b)) | (Type::Enum(a), Type::Enum(b)) => {
+            proto_names_match(a, b)
+        }
+        (
+            Type::Array {
+                element: StaticOrBoxed::Static(a),
+            },
+            Type::Array {
+                element: StaticOrBoxed::Static(b),
+            },
+        ) => types_match(a, b),
+        (
+            Type::Struct {
+                fields: StaticOrBoxed::Static(a),
+            },
+            Type::Struct {
+                fields: StaticOrBoxed::Static(b),
+            },
+        ) => fields_match(a, b),
+        _ => false,
+    }
+}
+
+const fn fields_match(a: &[Field], b: &[Field]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        let names_match = match (&a[i].name, &b[i].name) {
+            (Cow::Borrowed(a), Cow::Borrowed(b)) => ident_eq(a, b),
+            _ => false,
+        };
+
+        if !names_match || !types_match(&a[i].ty, &b[i].ty) {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+const fn proto_names_match(a: &ProtoName, b: &ProtoName) -> bool {
+    match (a, b) {
+        (
+            ProtoName::Split {
+                package: a_package,
+                name: a_name,
+            },
+            ProtoName::Split {
+                package: b_package,
+                name: b_name,
+            },
+        ) => str_eq(a_package, b_package) && str_eq(a_name, b_name),
+        (
+            ProtoName::FullyQualified(Cow::Borrowed(a)),
+            ProtoName::FullyQualified(Cow::Borrowed(b)),
+        ) => str_eq(a, b),
+        _ => false,
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
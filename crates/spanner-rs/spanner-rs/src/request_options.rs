@@ -0,0 +1,236 @@
+//! Per-client and per-call [`RequestOptions`], i.e RPC priority, request/transaction tags and
+//! directed reads.
+//!
+//! Options set on a [`Client`] are inherited by every [`SessionClient`] it hands out, and every
+//! [`Transaction`] started from those. Each level can override individual fields.
+//!
+//! [`Client`]: crate::Client
+//! [`SessionClient`]: crate::SessionClient
+//! [`Transaction`]: crate::tx::Transaction
+use std::sync::Arc;
+
+use protos::spanner::directed_read_options::{
+    ExcludeReplicas, IncludeReplicas, Replicas, replica_selection,
+};
+use protos::spanner::{self, DirectedReadOptions, request_options};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    const fn into_proto(self) -> request_options::Priority {
+        match self {
+            Self::Low => request_options::Priority::Low,
+            Self::Medium => request_options::Priority::Medium,
+            Self::High => request_options::Priority::High,
+        }
+    }
+}
+
+/// Options sent along with each request. Fields left as [`None`] fall back to the options of
+/// the parent client/session, or the Spanner defaults if none are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    pub priority: Option<Priority>,
+    /// Shows up in the query/read statistics tables, to help with debugging and monitoring.
+    pub request_tag: Option<Arc<str>>,
+    /// Shows up in the transaction/lock statistics tables. Ignored for requests that aren't
+    /// part of a read-write transaction.
+    pub transaction_tag: Option<Arc<str>>,
+    /// Which replicas reads and queries are routed to. Spanner only accepts these for
+    /// read-only requests, so they're left out of anything in a read-write transaction.
+    pub directed_read: Option<DirectedRead>,
+}
+
+/// Routes read-only requests to (or away from) specific replicas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectedRead {
+    /// Only route requests to these replicas, in order of preference.
+    Include {
+        replicas: Vec<ReplicaSelection>,
+        /// If true, requests fail instead of falling back to other replicas when none of
+        /// 'replicas' are available.
+        auto_failover_disabled: bool,
+    },
+    /// Never route requests to these replicas.
+    Exclude { replicas: Vec<ReplicaSelection> },
+}
+
+/// Selects replicas by location, type or both. An empty selection matches every replica.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplicaSelection {
+    /// The replica region, i.e 'us-east1'.
+    pub location: Option<String>,
+    pub replica_type: Option<ReplicaType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReplicaType {
+    ReadWrite,
+    ReadOnly,
+}
+
+impl RequestOptions {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn request_tag(mut self, tag: impl Into<Arc<str>>) -> Self {
+        self.request_tag = Some(tag.into());
+        self
+    }
+
+    pub fn transaction_tag(mut self, tag: impl Into<Arc<str>>) -> Self {
+        self.transaction_tag = Some(tag.into());
+        self
+    }
+
+    pub fn directed_read(mut self, directed_read: DirectedRead) -> Self {
+        self.directed_read = Some(directed_read);
+        self
+    }
+
+    /// Returns 'self', with any fields set in 'overrides' replaced.
+    pub fn merged(&self, overrides: &RequestOptions) -> Self {
+        Self {
+            priority: overrides.priority.or(self.priority),
+            request_tag: overrides
+                .request_tag
+                .clone()
+                .or_else(|| self.request_tag.clone()),
+            transaction_tag: overrides
+                .transaction_tag
+                .clone()
+                .or_else(|| self.transaction_tag.clone()),
+            directed_read: overrides
+                .directed_read
+                .clone()
+                .or_else(|| self.directed_read.clone()),
+        }
+    }
+
+    pub(crate) fn to_proto(&self) -> Option<spanner::RequestOptions> {
+        if self.priority.is_none() && self.request_tag.is_none() && self.transaction_tag.is_none() {
+            return None;
+        }
+
+        Some(spanner::RequestOptions {
+            priority: self
+                .priority
+                .map_or(request_options::Priority::Unspecified, Priority::into_proto)
+                as i32,
+            request_tag: self.request_tag.as_deref().unwrap_or_default().to_owned(),
+            transaction_tag: self
+                .transaction_tag
+                .as_deref()
+                .unwrap_or_default()
+                .to_owned(),
+        })
+    }
+
+    pub(crate) fn directed_read_proto(&self) -> Option<DirectedReadOptions> {
+        self.directed_read.as_ref().map(DirectedRead::to_proto)
+    }
+}
+
+impl DirectedRead {
+    /// Only route requests to read-only replicas in 'location'.
+    pub fn read_only_replicas_in(location: impl Into<String>) -> Self {
+        Self::Include {
+            replicas: vec![ReplicaSelection {
+                location: Some(location.into()),
+                replica_type: Some(ReplicaType::ReadOnly),
+            }],
+            auto_failover_disabled: false,
+        }
+    }
+
+    fn to_proto(&self) -> DirectedReadOptions {
+        fn selections(
+            replicas: &[ReplicaSelection],
+        ) -> Vec<spanner::directed_read_options::ReplicaSelection> {
+            replicas.iter().map(ReplicaSelection::to_proto).collect()
+        }
+
+        let replicas = match self {
+            Self::Include {
+                replicas,
+                auto_failover_disabled,
+            } => Replicas::IncludeReplicas(IncludeReplicas {
+                replica_selections: selections(replicas),
+                auto_failover_disabled: *auto_failover_disabled,
+            }),
+            Self::Exclude { replicas } => Replicas::ExcludeReplicas(ExcludeReplicas {
+                replica_selections: selections(replicas),
+            }),
+        };
+
+        DirectedReadOptions {
+            replicas: Some(replicas),
+        }
+    }
+}
+
+impl ReplicaSelection {
+    fn to_proto(&self) -> spanner::directed_read_options::ReplicaSelection {
+        let replica_type = match self.replica_type {
+            None => replica_selection::Type::Unspecified,
+            Some(ReplicaType::ReadWrite) => replica_selection::Type::ReadWrite,
+            Some(ReplicaType::ReadOnly) => replica_selection::Type::ReadOnly,
+        };
+
+        spanner::directed_read_options::ReplicaSelection {
+            location: self.location.clone().unwrap_or_default(),
+            r#type: replica_type as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_to_proto() {
+        assert_eq!(RequestOptions::new().to_proto(), None);
+
+        let client = RequestOptions::new()
+            .priority(Priority::Low)
+            .transaction_tag("backfill")
+            .directed_read(DirectedRead::read_only_replicas_in("us-east1"));
+
+        let call = client.merged(&RequestOptions::new().request_tag("load-users"));
+
+        assert_eq!(
+            call.to_proto(),
+            Some(spanner::RequestOptions {
+                priority: request_options::Priority::Low as i32,
+                request_tag: "load-users".to_owned(),
+                transaction_tag: "backfill".to_owned(),
+            })
+        );
+
+        let directed = call.directed_read_proto().unwrap();
+        match directed.replicas {
+            Some(Replicas::IncludeReplicas(include)) => {
+                assert_eq!(include.replica_selections.len(), 1);
+                assert_eq!(include.replica_selections[0].location, "us-east1");
+                assert_eq!(
+                    include.replica_selections[0].r#type,
+                    replica_selection::Type::ReadOnly as i32
+                );
+            }
+            other => panic!("expected included replicas, got {other:?}"),
+        }
+    }
+}
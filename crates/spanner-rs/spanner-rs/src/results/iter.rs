@@ -1,6 +1,7 @@
 use protos::protobuf::ListValue;
 use protos::spanner;
 
+use super::plan::QueryPlan;
 use super::stats::QueryStats;
 use super::{FieldIndex, RawRow};
 use crate::queryable::Queryable;
@@ -203,6 +204,14 @@ impl<T: Queryable> ResultIter<T> {
             .map(QueryStats::from_struct)
     }
 
+    /// Takes the query plan, leaving the rest of the stats in place.
+    pub fn take_query_plan(&mut self) -> Option<QueryPlan> {
+        self.stats
+            .as_mut()
+            .and_then(|raw| raw.query_plan.take())
+            .map(QueryPlan::from_proto)
+    }
+
     pub fn take_raw_stats(&mut self) -> Option<spanner::ResultSetStats> {
         self.stats.take()
     }
@@ -7,6 +7,7 @@ use crate::error::MissingTypeInfo;
 use crate::queryable::Queryable;
 
 pub mod iter;
+pub mod plan;
 pub mod stats;
 pub mod streaming;
 
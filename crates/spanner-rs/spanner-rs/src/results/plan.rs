@@ -0,0 +1,332 @@
+//! Query plans, as returned by [`SessionClient::explain`] and
+//! [`SessionClient::execute_sql_with_plan`].
+//!
+//! [`SessionClient::explain`]: crate::SessionClient::explain
+//! [`SessionClient::execute_sql_with_plan`]: crate::SessionClient::execute_sql_with_plan
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use protos::protobuf::value::Kind;
+use protos::protobuf::{self, Struct};
+use protos::spanner::{self, plan_node};
+use timestamp::Duration;
+
+use super::ResultIter;
+use super::stats::{QueryStats, parse_count, parse_duration};
+use crate::queryable::Queryable;
+
+/// The rows of a profiled query, along with its stats and plan.
+#[derive(Debug)]
+pub struct QueryProfile<T: Queryable> {
+    pub rows: ResultIter<T>,
+    pub stats: Option<QueryStats>,
+    pub plan: Option<QueryPlan>,
+}
+
+/// A query plan tree. The root is always the first node.
+///
+/// The [`fmt::Display`] impl renders the relational operators as indented text, similar to
+/// the plan view in the cloud console. [`QueryPlan::to_dot`] renders the same tree as a
+/// graphviz DOT graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    nodes: Vec<PlanNode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlanNodeKind {
+    /// An operator that produces rows, i.e a scan or join.
+    Relational,
+    /// An expression, i.e a filter condition or computed column.
+    Scalar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanNode {
+    pub index: usize,
+    pub kind: PlanNodeKind,
+    pub display_name: String,
+    /// A condensed form of scalar nodes, i.e '($Id > 5)'.
+    pub description: Option<String>,
+    pub children: Vec<ChildLink>,
+    /// Operator specific details, i.e the table being scanned.
+    pub metadata: HashMap<String, protobuf::Value>,
+    /// Only set when the query was profiled.
+    pub execution_stats: HashMap<String, protobuf::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildLink {
+    pub child_index: usize,
+    /// The role of the child, i.e 'Input', 'Condition' or 'Split Range'.
+    pub link_type: Option<String>,
+    /// The name of the variable the child's output is bound to.
+    pub variable: Option<String>,
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() { None } else { Some(s) }
+}
+
+fn struct_fields(s: Option<Struct>) -> HashMap<String, protobuf::Value> {
+    s.map(|s| s.fields).unwrap_or_default()
+}
+
+impl QueryPlan {
+    pub(crate) fn from_proto(plan: spanner::QueryPlan) -> Self {
+        let nodes = plan
+            .plan_nodes
+            .into_iter()
+            .map(|node| PlanNode {
+                index: node.index as usize,
+                kind: match node.kind() {
+                    plan_node::Kind::Scalar => PlanNodeKind::Scalar,
+                    _ => PlanNodeKind::Relational,
+                },
+                description: node
+                    .short_representation
+                    .and_then(|repr| non_empty(repr.description)),
+                display_name: node.display_name,
+                children: node
+                    .child_links
+                    .into_iter()
+                    .map(|link| ChildLink {
+                        child_index: link.child_index as usize,
+                        link_type: non_empty(link.r#type),
+                        variable: non_empty(link.variable),
+                    })
+                    .collect(),
+                metadata: struct_fields(node.metadata),
+                execution_stats: struct_fields(node.execution_stats),
+            })
+            .collect();
+
+        Self { nodes }
+    }
+
+    pub fn nodes(&self) -> &[PlanNode] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> Option<&PlanNode> {
+        self.nodes.first()
+    }
+
+    pub fn node(&self, index: usize) -> Option<&PlanNode> {
+        self.nodes.get(index)
+    }
+
+    /// Iterates over the children of 'node', along with the link to each.
+    pub fn children<'a>(
+        &'a self,
+        node: &'a PlanNode,
+    ) -> impl Iterator<Item = (&'a ChildLink, &'a PlanNode)> + 'a {
+        node.children
+            .iter()
+            .filter_map(|link| Some((link, self.node(link.child_index)?)))
+    }
+
+    /// Renders the relational operators as a graphviz DOT graph.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph plan {\n  node [shape=box];\n");
+
+        for node in self.relational_nodes() {
+            let mut label = node.display_name.clone();
+            if let Some(summary) = node.stats_summary() {
+                label.push_str("\\n");
+                label.push_str(&summary);
+            }
+
+            let _ = writeln!(dot, "  n{} [label=\"{}\"];", node.index, escape_dot(&label));
+
+            for (link, child) in self.children(node) {
+                if child.kind != PlanNodeKind::Relational {
+                    continue;
+                }
+
+                let _ = match link.link_type {
+                    Some(ref link_type) => writeln!(
+                        dot,
+                        "  n{} -> n{} [label=\"{}\"];",
+                        node.index,
+                        child.index,
+                        escape_dot(link_type)
+                    ),
+                    None => writeln!(dot, "  n{} -> n{};", node.index, child.index),
+                };
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn relational_nodes(&self) -> impl Iterator<Item = &PlanNode> {
+        self.nodes
+            .iter()
+            .filter(|node| node.kind == PlanNodeKind::Relational)
+    }
+
+    fn fmt_node(&self, f: &mut fmt::Formatter<'_>, node: &PlanNode, depth: usize) -> fmt::Result {
+        // plans are trees, but guard against malformed ones anyways.
+        if depth > self.nodes.len() {
+            return Ok(());
+        }
+
+        write!(f, "{:indent$}{}", "", node.display_name, indent = depth * 2)?;
+        if let Some(summary) = node.stats_summary() {
+            write!(f, " ({summary})")?;
+        }
+        writeln!(f)?;
+
+        for (link, child) in self.children(node) {
+            match child.kind {
+                PlanNodeKind::Relational => self.fmt_node(f, child, depth + 1)?,
+                // only show the scalar children that describe this operator, like conditions.
+                PlanNodeKind::Scalar => {
+                    if let (Some(link_type), Some(description)) =
+                        (&link.link_type, &child.description)
+                    {
+                        writeln!(
+                            f,
+                            "{:indent$}{link_type}: {description}",
+                            "",
+                            indent = (depth + 1) * 2
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.root() {
+            Some(root) => self.fmt_node(f, root, 0),
+            None => Ok(()),
+        }
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('"', "\\\"")
+}
+
+impl PlanNode {
+    /// The number of rows this operator returned, if profiled.
+    pub fn rows(&self) -> Option<u64> {
+        parse_count(self.execution_stat_field("rows", "total")?)
+    }
+
+    /// The total time spent in this operator, if profiled.
+    pub fn latency(&self) -> Option<Duration> {
+        let total = self.execution_stat_field("latency", "total")?;
+        let unit = self.execution_stat_field("latency", "unit")?;
+
+        match (total.kind.as_ref()?, unit.kind.as_ref()?) {
+            (Kind::StringValue(total), Kind::StringValue(unit)) => parse_duration(total, unit),
+            _ => None,
+        }
+    }
+
+    fn execution_stat_field(&self, stat: &str, field: &str) -> Option<&protobuf::Value> {
+        match self.execution_stats.get(stat)?.kind.as_ref()? {
+            Kind::StructValue(stat) => stat.fields.get(field),
+            _ => None,
+        }
+    }
+
+    fn stats_summary(&self) -> Option<String> {
+        match (self.rows(), self.latency()) {
+            (Some(rows), Some(latency)) => Some(format!("rows: {rows}, latency: {latency:?}")),
+            (Some(rows), None) => Some(format!("rows: {rows}")),
+            (None, Some(latency)) => Some(format!("latency: {latency:?}")),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> protobuf::Value {
+        protobuf::Value {
+            kind: Some(Kind::StringValue(s.to_owned())),
+        }
+    }
+
+    fn node(
+        index: i32,
+        kind: plan_node::Kind,
+        name: &str,
+        children: &[(i32, &str)],
+    ) -> spanner::PlanNode {
+        spanner::PlanNode {
+            index,
+            kind: kind as i32,
+            display_name: name.to_owned(),
+            child_links: children
+                .iter()
+                .map(|(child_index, link_type)| plan_node::ChildLink {
+                    child_index: *child_index,
+                    r#type: (*link_type).to_owned(),
+                    variable: String::new(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_plan() {
+        let mut scan = node(1, plan_node::Kind::Relational, "Table Scan", &[]);
+        scan.execution_stats = Some(Struct {
+            fields: HashMap::from([(
+                "rows".to_owned(),
+                protobuf::Value {
+                    kind: Some(Kind::StructValue(Struct {
+                        fields: HashMap::from([
+                            ("total".to_owned(), string("3")),
+                            ("unit".to_owned(), string("rows")),
+                        ]),
+                    })),
+                },
+            )]),
+        });
+
+        let mut condition = node(2, plan_node::Kind::Scalar, "Function", &[]);
+        condition.short_representation = Some(plan_node::ShortRepresentation {
+            description: "($Id > 5)".to_owned(),
+            subqueries: HashMap::new(),
+        });
+
+        let plan = QueryPlan::from_proto(spanner::QueryPlan {
+            plan_nodes: vec![
+                node(
+                    0,
+                    plan_node::Kind::Relational,
+                    "Filter",
+                    &[(1, "Input"), (2, "Condition")],
+                ),
+                scan,
+                condition,
+            ],
+            query_advice: None,
+        });
+
+        assert_eq!(plan.node(1).unwrap().rows(), Some(3));
+        assert_eq!(
+            plan.to_string(),
+            "Filter\n  Table Scan (rows: 3)\n  Condition: ($Id > 5)\n"
+        );
+        assert_eq!(
+            plan.to_dot(),
+            "digraph plan {\n  node [shape=box];\n  n0 [label=\"Filter\"];\n  n0 -> n1 \
+             [label=\"Input\"];\n  n1 [label=\"Table Scan\\nrows: 3\"];\n}\n"
+        );
+    }
+}
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 use std::fmt;
 
+use protos::protobuf::value::Kind;
 use protos::protobuf::{self, Struct};
+use timestamp::Duration;
 
 use crate::value::fmt_helpers::DebugValue;
 
+/// The stats Spanner returns for a profiled query. The common fields have typed accessors,
+/// anything else can be looked up via [`QueryStats::get`].
 #[derive(Clone, PartialEq)]
 pub struct QueryStats {
     fields: HashMap<String, protobuf::Value>,
@@ -17,6 +21,72 @@ impl QueryStats {
             fields: struc.fields,
         }
     }
+
+    /// Gets a raw stat by name, i.e 'optimizer_version'.
+    pub fn get(&self, name: &str) -> Option<&protobuf::Value> {
+        self.fields.get(name)
+    }
+
+    pub fn query_text(&self) -> Option<&str> {
+        match self.get("query_text")?.kind.as_ref()? {
+            Kind::StringValue(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn elapsed_time(&self) -> Option<Duration> {
+        self.get_duration("elapsed_time")
+    }
+
+    pub fn cpu_time(&self) -> Option<Duration> {
+        self.get_duration("cpu_time")
+    }
+
+    pub fn rows_returned(&self) -> Option<u64> {
+        self.get_count("rows_returned")
+    }
+
+    pub fn rows_scanned(&self) -> Option<u64> {
+        self.get_count("rows_scanned")
+    }
+
+    fn get_duration(&self, name: &str) -> Option<Duration> {
+        match self.get(name)?.kind.as_ref()? {
+            Kind::StringValue(value) => {
+                let (total, unit) = value.split_once(' ')?;
+                parse_duration(total, unit)
+            }
+            _ => None,
+        }
+    }
+
+    fn get_count(&self, name: &str) -> Option<u64> {
+        parse_count(self.get(name)?)
+    }
+}
+
+/// Parses the counts in stats, which are usually strings but occasionally numbers.
+pub(crate) fn parse_count(value: &protobuf::Value) -> Option<u64> {
+    match value.kind.as_ref()? {
+        Kind::StringValue(count) => count.parse().ok(),
+        Kind::NumberValue(count) if *count >= 0.0 => Some(*count as u64),
+        _ => None,
+    }
+}
+
+/// Parses durations in stats, which come as a decimal total + unit, i.e ('1.25', 'msecs').
+pub(crate) fn parse_duration(total: &str, unit: &str) -> Option<Duration> {
+    let total = total.parse::<f64>().ok()?;
+
+    let scale = match unit {
+        "secs" | "sec" | "s" => 1.0,
+        "msecs" | "msec" | "ms" => 1e-3,
+        "usecs" | "usec" | "us" => 1e-6,
+        "nsecs" | "nsec" | "ns" => 1e-9,
+        _ => return None,
+    };
+
+    Duration::from_seconds_f64_checked(total * scale)
 }
 
 impl fmt::Debug for QueryStats {
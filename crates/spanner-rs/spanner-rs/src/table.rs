@@ -40,3 +40,42 @@ pub trait PartialTable: Sized {
         })
     }
 }
+
+/// Implemented by tables that are interleaved in a parent table.
+pub trait Interleaved: Table {
+    /// The parent table.
+    type Parent: Table;
+
+    /// What happens to rows in this table when their parent row is deleted.
+    const ON_DELETE: OnDelete;
+}
+
+/// The 'ON DELETE' action of an interleaved table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OnDelete {
+    /// 'ON DELETE CASCADE', child rows are deleted along with the parent row.
+    Cascade,
+    /// 'ON DELETE NO ACTION', deleting a parent row with child rows fails.
+    #[default]
+    NoAction,
+}
+
+/// A secondary index on a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Index {
+    pub name: &'static str,
+    pub unique: bool,
+    pub null_filtered: bool,
+    pub keys: &'static [IndexKey],
+    /// Non-key columns stored in the index.
+    pub storing: &'static [&'static str],
+    /// The table the index is interleaved in, if any.
+    pub interleave_in: Option<&'static str>,
+}
+
+/// A key column in an [`Index`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexKey {
+    pub column: &'static str,
+    pub ascending: bool,
+}
@@ -47,7 +47,9 @@ pub trait TxOptions: crate::private::SealedTx {
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ReadOnly;
 
-impl crate::private::SealedTx for ReadOnly {}
+impl crate::private::SealedTx for ReadOnly {
+    const DIRECTED_READS: bool = true;
+}
 
 impl TxOptions for ReadOnly {
     const OPTIONS: TransactionOptions = READ_ONLY;
@@ -62,11 +64,27 @@ impl TxOptions for ReadWrite {
     const OPTIONS: TransactionOptions = READ_WRITE;
 }
 
+/// A partitioned DML transaction, which can only be used to execute a single DML statement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartitionedDml;
+
+impl crate::private::SealedTx for PartitionedDml {}
+
+impl TxOptions for PartitionedDml {
+    const OPTIONS: TransactionOptions = TransactionOptions {
+        isolation_level: IsolationLevel::Unspecified as i32,
+        exclude_txn_from_change_streams: false,
+        mode: Some(Mode::PartitionedDml(transaction_options::PartitionedDml {})),
+    };
+}
+
 /// Describes a single use transaction, that's used/created
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SingleUse;
 
-impl crate::private::SealedTx for SingleUse {}
+impl crate::private::SealedTx for SingleUse {
+    const DIRECTED_READS: bool = true;
+}
 
 impl ReadOnlyTx for SingleUse {
     #[inline]
@@ -106,7 +124,9 @@ impl<T: Copy> Existing<'_, T> {
     }
 }
 
-impl<T: Copy> crate::private::SealedTx for Existing<'_, T> {}
+impl<T: crate::private::SealedTx> crate::private::SealedTx for Existing<'_, T> {
+    const DIRECTED_READS: bool = T::DIRECTED_READS;
+}
 
 impl ReadOnlyTx for Existing<'_, ReadOnly> {
     #[inline]
@@ -145,7 +165,9 @@ impl<T: TxOptions> Begin<T> {
     }
 }
 
-impl<T: TxOptions> crate::private::SealedTx for Begin<T> {}
+impl<T: TxOptions> crate::private::SealedTx for Begin<T> {
+    const DIRECTED_READS: bool = T::DIRECTED_READS;
+}
 
 impl<T: TxOptions> TxOptions for Begin<T> {
     const OPTIONS: TransactionOptions = T::OPTIONS;
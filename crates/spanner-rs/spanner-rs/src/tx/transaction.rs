@@ -9,16 +9,22 @@ use super::{Existing, ReadWrite};
 use crate::Table;
 use crate::client::connection::ConnectionParts;
 use crate::client::{ClientParts, Session};
+use crate::dml::BatchDml;
 use crate::error::ConvertError;
 use crate::key_set::{KeySet, WriteBuilder};
 use crate::private::SealedConnection;
+use crate::request_options::RequestOptions;
 
 #[must_use = "a transaction must be committed or rolled back"]
 pub struct Transaction<'a> {
     session: &'a Arc<Session>,
     client_parts: &'a ClientParts,
+    options: RequestOptions,
     mutations: Vec<Mutation>,
     tx: spanner::Transaction,
+    /// Sequence number for DML requests, which need to be strictly increasing within a
+    /// transaction.
+    seqno: i64,
     /// [`None`] is an uncommitted, not rolled-back transaction. When one of those 2 occurs,
     /// this is set (before any errors are thrown).
     state: Option<TxState>,
@@ -48,7 +54,9 @@ impl<'a> Transaction<'a> {
             mutations: vec![],
             client_parts: parts.client_parts,
             session: parts.session,
+            options: parts.options.clone(),
             tx,
+            seqno: 0,
             state: None,
             completed_successfully: false,
         }
@@ -66,6 +74,7 @@ impl<'tx> SealedConnection for Transaction<'tx> {
         crate::client::connection::ConnectionParts::from_parts(
             self.client_parts,
             self.session,
+            &self.options,
             super::Existing::new(&self.tx),
         )
     }
@@ -105,6 +114,18 @@ impl Transaction<'_> {
         Ok(self.add_mutation(Operation::Replace(build_single_row_write(row)?)))
     }
 
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.options
+    }
+
+    /// Overrides the request options for the remaining requests in this transaction, i.e to
+    /// tag individual statements. The transaction tag should stay the same for the entire
+    /// transaction, since Spanner only uses the tag from the first request.
+    pub fn set_request_options(&mut self, options: &RequestOptions) -> &mut Self {
+        self.options = self.options.merged(options);
+        self
+    }
+
     pub const fn state(&self) -> Option<TxState> {
         if self.completed_successfully {
             self.state
@@ -166,6 +187,20 @@ impl Transaction<'_> {
         Ok(self.add_mutation(Operation::Insert(build_many_row_write(rows)?)))
     }
 
+    /// Executes a batch of DML statements in this transaction, returning the number of rows
+    /// modified by each statement.
+    pub async fn execute_batch_dml(&mut self, batch: BatchDml) -> crate::Result<Vec<i64>> {
+        self.seqno += 1;
+        let seqno = self.seqno;
+
+        let response = self
+            .connection_parts()
+            .execute_batch_dml_inner(batch.into_statements(), seqno)
+            .await?;
+
+        crate::dml::batch_row_counts(response)
+    }
+
     fn add_mutation(&mut self, operation: Operation) -> &mut Self {
         self.mutations.push(Mutation {
             operation: Some(operation),
@@ -16,3 +16,116 @@ spanner_rs::row! {
         pub list: Vec<String>,
     }
 }
+
+mod derived {
+    #[derive(spanner_rs::Table, Debug, Clone, PartialEq)]
+    #[spanner(table = "Singers")]
+    pub struct Singer {
+        #[spanner(pk = 1)]
+        pub singer_id: i64,
+        #[spanner(rename = Nickname)]
+        pub stage_name: Option<String>,
+    }
+}
+
+spanner_rs::query! {
+    sql: "SELECT s.GeoHash, s.Name AS Label, Longitude \
+          FROM SharedSgts AS s \
+          WHERE s.SightingTime > @after AND s.Latitude BETWEEN @min_lat AND @max_lat \
+          ORDER BY s.SightingTime DESC \
+          LIMIT @max_rows",
+    tables: [SharedSightings],
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct NamedSightings {
+        pub geo_hash: String,
+        pub label: Option<String>,
+        pub longitude: f64,
+    }
+}
+
+spanner_rs::database! {
+    schema: "tests/schema.sql",
+
+    pub struct Music;
+}
+
+spanner_rs::query! {
+    sql: "SELECT s.Nickname, a.AlbumTitle \
+          FROM Singers AS s JOIN Albums AS a ON s.SingerId = a.SingerId \
+          WHERE a.AlbumId = @album_id",
+    database: Music,
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct AlbumTitles {
+        pub nickname: String,
+        pub album_title: Option<String>,
+    }
+}
+
+#[test]
+fn test_query_macro() {
+    use spanner_rs::queryable::Row;
+
+    let names = NamedSightings::COLUMNS
+        .iter()
+        .map(|column| (column.name, column.nullable))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        [("GeoHash", false), ("Label", true), ("Longitude", false)]
+    );
+
+    let after = timestamp::Timestamp::UNIX_EPOCH;
+
+    let params = NamedSightings::params()
+        .after(after)
+        .min_lat(-10.0)
+        .max_lat(10.0)
+        .max_rows(100_i64)
+        .build();
+
+    let mut expected = spanner_rs::sql::Params::new();
+    expected
+        .insert("after", after)
+        .insert("min_lat", -10.0)
+        .insert("max_lat", 10.0)
+        .insert("max_rows", 100_i64);
+
+    assert_eq!(params, expected);
+}
+
+#[test]
+fn test_query_macro_with_database() {
+    use spanner_rs::queryable::Row;
+
+    assert!(Music::DDL.contains("CREATE TABLE Albums"));
+
+    let names = AlbumTitles::COLUMNS
+        .iter()
+        .map(|column| (column.name, column.nullable))
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, [("Nickname", false), ("AlbumTitle", true)]);
+
+    let mut expected = spanner_rs::sql::Params::new();
+    expected.insert("album_id", 1_i64);
+
+    assert_eq!(AlbumTitles::params().album_id(1_i64).build(), expected);
+}
+
+#[test]
+fn test_table_derive() {
+    use spanner_rs::Table;
+    use spanner_rs::queryable::Row;
+
+    assert_eq!(<derived::Singer as Table>::NAME, "Singers");
+
+    let names = derived::Singer::COLUMNS
+        .iter()
+        .map(|column| (column.name, column.nullable))
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, [("SingerId", false), ("Nickname", true)]);
+}
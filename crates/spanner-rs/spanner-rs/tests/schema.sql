@@ -0,0 +1,14 @@
+CREATE TABLE Singers (
+    SingerId INT64 NOT NULL,
+    FirstName STRING(1024),
+    LastName STRING(1024),
+) PRIMARY KEY (SingerId);
+
+CREATE TABLE Albums (
+    SingerId INT64 NOT NULL,
+    AlbumId INT64 NOT NULL,
+    AlbumTitle STRING(MAX),
+) PRIMARY KEY (SingerId, AlbumId),
+  INTERLEAVE IN PARENT Singers ON DELETE CASCADE;
+
+ALTER TABLE Singers ADD COLUMN Nickname STRING(MAX) NOT NULL;
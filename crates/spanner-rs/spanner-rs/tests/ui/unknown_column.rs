@@ -0,0 +1,20 @@
+spanner_rs::database! {
+    ddl: "CREATE TABLE Singers (
+              SingerId INT64 NOT NULL,
+              FirstName STRING(1024),
+          ) PRIMARY KEY (SingerId)",
+
+    pub struct Music;
+}
+
+spanner_rs::query! {
+    sql: "SELECT SingerId, FristName FROM Singers",
+    database: Music,
+
+    pub struct SingerNames {
+        pub singer_id: i64,
+        pub frist_name: Option<String>,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+spanner_rs::database! {
+    ddl: "CREATE TABLE Singers (
+              SingerId INT64 NOT NULL,
+              FirstName STRING(1024),
+          ) PRIMARY KEY (SingerId)",
+
+    pub struct Music;
+}
+
+spanner_rs::query! {
+    sql: "SELECT SingerId FROM Singer",
+    database: Music,
+
+    pub struct SingerIds {
+        pub singer_id: i64,
+    }
+}
+
+fn main() {}